@@ -0,0 +1,482 @@
+/*
+ * ==========================================================================
+ * PAWX - Code with Claws! 🐾
+ * ==========================================================================
+ *
+ * File:     arena.rs
+ * Purpose:  The arena-allocated AST the interpreter executes
+ *
+ * The parser builds a tree (`Expr` / `Stmt`, with `Box` and `Vec`
+ * children). That tree is what the AST cache stores, `when` blocks are
+ * resolved on, and embedders construct by hand. Before running, it is
+ * lowered into an [`Ast`]: every node lives in one of three flat vectors
+ * and refers to its children by typed index.
+ *
+ *  - `ExprId` / `StmtId`   a single child
+ *  - `ExprList`            a run of consecutive expressions (call
+ *                          arguments, array elements)
+ *  - `StmtList`            a run of consecutive statements (a block)
+ *  - `ParamList`           a run of consecutive parameters
+ *
+ * Lists are always contiguous, so a function body is just a range. A
+ * `FunctionDef` holds an `Rc<Ast>` plus ranges, which makes copying a
+ * function (or running a loop body again) free of any AST cloning.
+ *
+ * Nodes are laid out children first: a statement's nested blocks and
+ * expressions sit before the statement itself.
+ *
+ * --------------------------------------------------------------------------
+ * Author:   Sam Wilcox
+ * Email:    sam@pawx-lang.com
+ * Website:  https://www.pawx-lang.com
+ * GitHub:   https://github.com/samwilcox/pawx
+ *
+ * License:
+ * This file is part of the PAWX programming language project.
+ *
+ * PAWX is dual-licensed under the terms of:
+ *   - The MIT License
+ *   - The Apache License, Version 2.0
+ *
+ * You may choose either license to govern your use of this software.
+ * Full license text available at:
+ *     https://license.pawx-lang.com
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under these licenses is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *
+ * ==========================================================================
+ */
+
+use std::ops::Index;
+use std::rc::Rc;
+
+use crate::ast::{AccessLevel, ClassMember, Expr, InstinctMember, MatchArm, Param, Pattern, Stmt, TestMode};
+use crate::lexer::token::Token;
+use crate::span::Span;
+use crate::value::Value;
+
+/// Index of an expression in an [`Ast`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExprId(u32);
+
+/// Index of a statement in an [`Ast`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StmtId(u32);
+
+macro_rules! node_list {
+    ($(#[$doc:meta])* $name:ident) => {
+        $(#[$doc])*
+        #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+        pub struct $name {
+            start: u32,
+            len: u32,
+        }
+
+        impl $name {
+            fn range(self) -> std::ops::Range<usize> {
+                self.start as usize..(self.start + self.len) as usize
+            }
+        }
+    };
+}
+
+node_list!(
+    /// Consecutive expressions in an [`Ast`].
+    ExprList
+);
+node_list!(
+    /// Consecutive statements in an [`Ast`]; a block.
+    StmtList
+);
+node_list!(
+    /// Consecutive parameters in an [`Ast`].
+    ParamList
+);
+
+impl ExprList {
+    pub fn iter(self) -> impl DoubleEndedIterator<Item = ExprId> {
+        self.range().map(|i| ExprId(i as u32))
+    }
+}
+
+impl StmtList {
+    pub fn iter(self) -> impl DoubleEndedIterator<Item = StmtId> {
+        self.range().map(|i| StmtId(i as u32))
+    }
+}
+
+/// An expression; mirrors [`Expr`].
+#[derive(Debug, Clone)]
+pub enum ExprNode {
+    Literal { value: Value },
+    Identifier { name: String, span: Span },
+    Assign { name: String, value: ExprId, span: Span },
+    Binary { left: ExprId, operator: Token, right: ExprId, span: Span },
+    Unary { operator: Token, right: ExprId, span: Span },
+    Call { callee: ExprId, arguments: ExprList, span: Span },
+    NamedArgument { name: String, value: ExprId, span: Span },
+    Get { object: ExprId, name: String, span: Span },
+    Set { object: ExprId, name: String, value: ExprId, span: Span },
+    Index { object: ExprId, index: ExprId, span: Span },
+    IndexAssign { object: ExprId, index: ExprId, value: ExprId, span: Span },
+    ArrayLiteral { values: ExprList, span: Span },
+    ObjectLiteral { fields: Box<[(String, ExprId)]>, span: Span },
+    Lambda { params: Box<[String]>, body: StmtList, span: Span },
+    Tap { path: ExprId, span: Span },
+    New { class_name: String, arguments: ExprList, span: Span },
+    PostIncrement { name: String, span: Span },
+    PostDecrement { name: String, span: Span },
+    Tuple { values: ExprList },
+    Grouping { expr: ExprId },
+    Logical { left: ExprId, operator: Token, right: ExprId, span: Span },
+    Nap { expr: ExprId, span: Span },
+}
+
+/// A statement; mirrors [`Stmt`]. Its span is kept alongside, see
+/// [`Ast::span`].
+#[derive(Debug, Clone)]
+pub enum StmtNode {
+    Expression(ExprId),
+    PublicVar { name: String, value: ExprId },
+    PrivateVar { name: String, value: ExprId },
+    ProtectedVar { name: String, value: ExprId },
+    DestructureVar { names: Box<[String]>, value: ExprId },
+    Function {
+        name: String,
+        params: ParamList,
+        body: StmtList,
+        return_type: Option<String>,
+        is_async: bool,
+    },
+
+    /// `function` is always a [`StmtNode::Function`].
+    Decorated { decorators: ExprList, function: StmtId },
+    Return(Option<ExprId>),
+    If { condition: ExprId, then_branch: StmtList, else_branch: Option<StmtList> },
+    While { condition: ExprId, body: StmtList },
+    Try {
+        try_block: StmtList,
+        catch_param: Option<String>,
+        catch_block: Option<StmtList>,
+        finally_block: Option<StmtList>,
+    },
+    Throw(ExprId),
+
+    /// Normally resolved before lowering; kept as a tree so it can still be
+    /// resolved when it reaches the interpreter.
+    When(Box<Stmt>),
+    Match { subject: ExprId, arms: Box<[ArmNode]> },
+    Test { name: String, body: StmtList, mode: TestMode },
+    Clowder {
+        name: String,
+        base: Option<String>,
+        interfaces: Box<[String]>,
+        members: Box<[MemberNode]>,
+        is_exported: bool,
+        is_default: bool,
+    },
+    Instinct { name: String, members: Box<[InstinctMember]> },
+    Export { name: Option<String>, value: ExprId },
+    Pride { name: String, body: StmtList },
+}
+
+/// A parameter; mirrors [`Param`].
+#[derive(Debug, Clone)]
+pub struct ParamNode {
+    pub name: String,
+    pub default: Option<ExprId>,
+}
+
+/// One arm of a `match`; mirrors [`MatchArm`].
+#[derive(Debug, Clone)]
+pub struct ArmNode {
+    pub patterns: Vec<Pattern>,
+    pub guard: Option<ExprId>,
+    pub body: StmtList,
+}
+
+/// A `clowder` member; mirrors [`ClassMember`].
+///
+/// A setter's single parameter is stored as a one-element `ParamList`, so
+/// it can be called like any other function.
+#[derive(Debug, Clone)]
+pub enum MemberNode {
+    Field { name: String, access: AccessLevel, value: Option<ExprId>, is_static: bool },
+    Method { name: String, access: AccessLevel, params: ParamList, body: StmtList, is_static: bool },
+    Getter { name: String, body: StmtList },
+    Setter { name: String, param: ParamList, body: StmtList },
+}
+
+/// A lowered program (or fragment of one).
+#[derive(Default)]
+pub struct Ast {
+    exprs: Vec<ExprNode>,
+    stmts: Vec<StmtNode>,
+    spans: Vec<Span>,
+    params: Vec<ParamNode>,
+}
+
+/// Ast contents are not shown; a function or class printed with `{:?}`
+/// would otherwise dump the whole program.
+impl std::fmt::Debug for Ast {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Ast({} exprs, {} stmts)", self.exprs.len(), self.stmts.len())
+    }
+}
+
+impl Index<ExprId> for Ast {
+    type Output = ExprNode;
+
+    fn index(&self, id: ExprId) -> &ExprNode {
+        &self.exprs[id.0 as usize]
+    }
+}
+
+impl Index<StmtId> for Ast {
+    type Output = StmtNode;
+
+    fn index(&self, id: StmtId) -> &StmtNode {
+        &self.stmts[id.0 as usize]
+    }
+}
+
+impl Index<ParamList> for Ast {
+    type Output = [ParamNode];
+
+    fn index(&self, list: ParamList) -> &[ParamNode] {
+        &self.params[list.range()]
+    }
+}
+
+impl Ast {
+    /// Lowers a parsed program, returning the arena and its top-level block.
+    pub fn lower(stmts: Vec<Stmt>) -> (Rc<Ast>, StmtList) {
+        let mut ast = Ast::default();
+        let body = ast.stmt_list(stmts);
+        (Rc::new(ast), body)
+    }
+
+    /// Lowers free-standing expressions, such as arguments built by native
+    /// code for a call into user code.
+    pub fn lower_exprs(exprs: Vec<Expr>) -> (Rc<Ast>, ExprList) {
+        let mut ast = Ast::default();
+        let list = ast.expr_list(exprs);
+        (Rc::new(ast), list)
+    }
+
+    /// Where a statement starts in the source.
+    pub fn span(&self, id: StmtId) -> Span {
+        self.spans[id.0 as usize]
+    }
+
+    /* ----------------------------- */
+    /* LOWERING                      */
+    /* ----------------------------- */
+
+    // Each list is lowered children first and then pushed in one go, which
+    // keeps its own nodes adjacent.
+
+    fn expr(&mut self, expr: Expr) -> ExprId {
+        let node = self.expr_node(expr);
+        self.exprs.push(node);
+        ExprId(self.exprs.len() as u32 - 1)
+    }
+
+    fn expr_list(&mut self, exprs: Vec<Expr>) -> ExprList {
+        let nodes: Vec<ExprNode> = exprs.into_iter().map(|e| self.expr_node(e)).collect();
+        let start = self.exprs.len() as u32;
+        self.exprs.extend(nodes);
+        ExprList { start, len: self.exprs.len() as u32 - start }
+    }
+
+    fn stmt_list(&mut self, stmts: Vec<Stmt>) -> StmtList {
+        let nodes: Vec<(Span, StmtNode)> = stmts.into_iter().map(|s| (s.span(), self.stmt_node(s))).collect();
+        let start = self.stmts.len() as u32;
+        for (span, node) in nodes {
+            self.spans.push(span);
+            self.stmts.push(node);
+        }
+        StmtList { start, len: self.stmts.len() as u32 - start }
+    }
+
+    fn param_list(&mut self, params: Vec<Param>) -> ParamList {
+        let nodes: Vec<ParamNode> = params
+            .into_iter()
+            .map(|p| ParamNode {
+                name: p.name,
+                default: p.default.map(|d| self.expr(d)),
+            })
+            .collect();
+        let start = self.params.len() as u32;
+        self.params.extend(nodes);
+        ParamList { start, len: self.params.len() as u32 - start }
+    }
+
+    fn expr_node(&mut self, expr: Expr) -> ExprNode {
+        match expr {
+            Expr::Literal { value, .. } => ExprNode::Literal { value },
+            Expr::Identifier { name, span } => ExprNode::Identifier { name, span },
+            Expr::Assign { name, value, span } => ExprNode::Assign { name, value: self.expr(*value), span },
+            Expr::Binary { left, operator, right, span } => ExprNode::Binary {
+                left: self.expr(*left),
+                operator,
+                right: self.expr(*right),
+                span,
+            },
+            Expr::Unary { operator, right, span } => ExprNode::Unary { operator, right: self.expr(*right), span },
+            Expr::Call { callee, arguments, span } => ExprNode::Call {
+                callee: self.expr(*callee),
+                arguments: self.expr_list(arguments),
+                span,
+            },
+            Expr::NamedArgument { name, value, span } => {
+                ExprNode::NamedArgument { name, value: self.expr(*value), span }
+            }
+            Expr::Get { object, name, span } => ExprNode::Get { object: self.expr(*object), name, span },
+            Expr::Set { object, name, value, span } => ExprNode::Set {
+                object: self.expr(*object),
+                name,
+                value: self.expr(*value),
+                span,
+            },
+            Expr::Index { object, index, span } => ExprNode::Index {
+                object: self.expr(*object),
+                index: self.expr(*index),
+                span,
+            },
+            Expr::IndexAssign { object, index, value, span } => ExprNode::IndexAssign {
+                object: self.expr(*object),
+                index: self.expr(*index),
+                value: self.expr(*value),
+                span,
+            },
+            Expr::ArrayLiteral { values, span } => ExprNode::ArrayLiteral { values: self.expr_list(values), span },
+            Expr::ObjectLiteral { fields, span } => ExprNode::ObjectLiteral {
+                fields: fields.into_iter().map(|(name, value)| (name, self.expr(value))).collect(),
+                span,
+            },
+            Expr::Lambda { params, body, span } => ExprNode::Lambda {
+                params: params.into(),
+                body: self.stmt_list(body),
+                span,
+            },
+            Expr::Tap { path, span } => ExprNode::Tap { path: self.expr(*path), span },
+            Expr::New { class_name, arguments, span } => ExprNode::New {
+                class_name,
+                arguments: self.expr_list(arguments),
+                span,
+            },
+            Expr::PostIncrement { name, span } => ExprNode::PostIncrement { name, span },
+            Expr::PostDecrement { name, span } => ExprNode::PostDecrement { name, span },
+            Expr::Tuple { values, .. } => ExprNode::Tuple { values: self.expr_list(values) },
+            Expr::Grouping { expr, .. } => ExprNode::Grouping { expr: self.expr(*expr) },
+            Expr::Logical { left, operator, right, span } => ExprNode::Logical {
+                left: self.expr(*left),
+                operator,
+                right: self.expr(*right),
+                span,
+            },
+            Expr::Nap { expr, span } => ExprNode::Nap { expr: self.expr(*expr), span },
+        }
+    }
+
+    fn stmt_node(&mut self, stmt: Stmt) -> StmtNode {
+        match stmt {
+            Stmt::Expression(expr) => StmtNode::Expression(self.expr(expr)),
+            Stmt::PublicVar { name, value, .. } => StmtNode::PublicVar { name, value: self.expr(value) },
+            Stmt::PrivateVar { name, value, .. } => StmtNode::PrivateVar { name, value: self.expr(value) },
+            Stmt::ProtectedVar { name, value, .. } => StmtNode::ProtectedVar { name, value: self.expr(value) },
+            Stmt::DestructureVar { names, value, .. } => StmtNode::DestructureVar {
+                names: names.into(),
+                value: self.expr(value),
+            },
+            Stmt::Function { name, params, body, return_type, is_async, .. } => StmtNode::Function {
+                name,
+                params: self.param_list(params),
+                body: self.stmt_list(body),
+                return_type,
+                is_async,
+            },
+            Stmt::Decorated { decorators, function, .. } => {
+                let decorators = self.expr_list(decorators);
+                let function = self.stmt_list(vec![*function]);
+                StmtNode::Decorated { decorators, function: StmtId(function.start) }
+            }
+            Stmt::Return { value, .. } => StmtNode::Return(value.map(|e| self.expr(e))),
+            Stmt::If { condition, then_branch, else_branch, .. } => StmtNode::If {
+                condition: self.expr(condition),
+                then_branch: self.stmt_list(then_branch),
+                else_branch: else_branch.map(|b| self.stmt_list(b)),
+            },
+            Stmt::While { condition, body, .. } => StmtNode::While {
+                condition: self.expr(condition),
+                body: self.stmt_list(body),
+            },
+            Stmt::Try { try_block, catch_param, catch_block, finally_block, .. } => StmtNode::Try {
+                try_block: self.stmt_list(try_block),
+                catch_param,
+                catch_block: catch_block.map(|b| self.stmt_list(b)),
+                finally_block: finally_block.map(|b| self.stmt_list(b)),
+            },
+            Stmt::Throw(expr) => StmtNode::Throw(self.expr(expr)),
+            when @ Stmt::When { .. } => StmtNode::When(Box::new(when)),
+            Stmt::Match { subject, arms, .. } => StmtNode::Match {
+                subject: self.expr(subject),
+                arms: arms.into_iter().map(|arm| self.arm(arm)).collect(),
+            },
+            Stmt::Test { name, body, mode, .. } => StmtNode::Test { name, body: self.stmt_list(body), mode },
+            Stmt::Clowder { name, base, interfaces, members, is_exported, is_default, .. } => StmtNode::Clowder {
+                name,
+                base,
+                interfaces: interfaces.into(),
+                members: members.into_iter().map(|m| self.member(m)).collect(),
+                is_exported,
+                is_default,
+            },
+            Stmt::Instinct { name, members, .. } => StmtNode::Instinct { name, members: members.into() },
+            Stmt::Export { name, value, .. } => StmtNode::Export { name, value: self.expr(value) },
+            Stmt::Pride { name, body, .. } => StmtNode::Pride { name, body: self.stmt_list(body) },
+        }
+    }
+
+    fn arm(&mut self, arm: MatchArm) -> ArmNode {
+        ArmNode {
+            patterns: arm.patterns,
+            guard: arm.guard.map(|g| self.expr(g)),
+            body: self.stmt_list(arm.body),
+        }
+    }
+
+    fn member(&mut self, member: ClassMember) -> MemberNode {
+        match member {
+            ClassMember::Field { name, access, value, is_static, .. } => MemberNode::Field {
+                name,
+                access,
+                value: value.map(|v| self.expr(v)),
+                is_static,
+            },
+            ClassMember::Method { name, access, params, body, is_static, .. } => MemberNode::Method {
+                name,
+                access,
+                params: self.param_list(params),
+                body: self.stmt_list(body),
+                is_static,
+            },
+            ClassMember::Getter { name, body, .. } => MemberNode::Getter {
+                name,
+                body: self.stmt_list(body),
+            },
+            ClassMember::Setter { name, param_name, param_type, body } => MemberNode::Setter {
+                name,
+                param: self.param_list(vec![Param {
+                    name: param_name,
+                    default: None,
+                    type_annotation: param_type,
+                }]),
+                body: self.stmt_list(body),
+            },
+        }
+    }
+}
@@ -71,8 +71,10 @@
 
 use crate::ast::{Expr, Param, Stmt};
 
+use serde::{Deserialize, Serialize};
+
 /// Controls visibility of class members.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum AccessLevel {
     Public,
     Private,
@@ -80,7 +82,7 @@ pub enum AccessLevel {
 }
 
 /// Represents **one declared member inside a PAWX `clowder`**.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ClassMember {
     /// Class field
     Field {
@@ -29,9 +29,12 @@
 use crate::{ast::Stmt, lexer::token::Token, value::Value};
 use crate::span::Span;
 
-#[derive(Debug, Clone)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Expr {
     Literal {
+        #[serde(with = "crate::ast::literal")]
         value: Value,
         span: Span,
     },
@@ -66,6 +69,14 @@ pub enum Expr {
         span: Span,
     },
 
+    /// `name: value` inside a call's argument list. Only valid as a direct
+    /// argument of `Call`; resolved against parameter names in `calls.rs`.
+    NamedArgument {
+        name: String,
+        value: Box<Expr>,
+        span: Span,
+    },
+
     Get {
         object: Box<Expr>,
         name: String,
@@ -145,4 +156,42 @@ pub enum Expr {
         right: Box<Expr>,
         span: Span,
     },
-}
\ No newline at end of file
+
+    /// `nap future`: waits for a Furure and evaluates to its value; a
+    /// rejected Furure throws its error instead.
+    Nap {
+        expr: Box<Expr>,
+        span: Span,
+    },
+}
+
+impl Expr {
+    /// Where the expression is reported in errors: its first token for
+    /// literals and names, its operator or opening bracket otherwise.
+    pub fn span(&self) -> Span {
+        match self {
+            Expr::Literal { span, .. }
+            | Expr::Identifier { span, .. }
+            | Expr::Assign { span, .. }
+            | Expr::Binary { span, .. }
+            | Expr::Unary { span, .. }
+            | Expr::Call { span, .. }
+            | Expr::NamedArgument { span, .. }
+            | Expr::Get { span, .. }
+            | Expr::Set { span, .. }
+            | Expr::Index { span, .. }
+            | Expr::IndexAssign { span, .. }
+            | Expr::ArrayLiteral { span, .. }
+            | Expr::ObjectLiteral { span, .. }
+            | Expr::Lambda { span, .. }
+            | Expr::Tap { span, .. }
+            | Expr::New { span, .. }
+            | Expr::PostIncrement { span, .. }
+            | Expr::PostDecrement { span, .. }
+            | Expr::Tuple { span, .. }
+            | Expr::Grouping { span, .. }
+            | Expr::Logical { span, .. }
+            | Expr::Nap { span, .. } => *span,
+        }
+    }
+}
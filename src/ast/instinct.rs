@@ -28,12 +28,16 @@
 
 use crate::ast::Param;
 
- #[derive(Debug, Clone)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum InstinctMemberKind {
-    Method,      // ← THIS is what your parser expects
+    Method,
+    Getter,
+    Setter,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InstinctMember {
     pub name: String,
     pub params: Vec<Param>,
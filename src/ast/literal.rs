@@ -0,0 +1,69 @@
+/*
+ * ==========================================================================
+ * PAWX - Code with Claws! 🐾
+ * ==========================================================================
+ *
+ * File:     literal.rs
+ * Purpose:  Serializes the literal values stored in the AST
+ *
+ * The parser only ever puts `null`, booleans, numbers and strings into
+ * `Expr::Literal` and `Pattern::Literal`, so only those are written when
+ * an AST is cached. Any other value fails to serialize.
+ *
+ * Used as `#[serde(with = "crate::ast::literal")]`.
+ *
+ * --------------------------------------------------------------------------
+ * Author:   Sam Wilcox
+ * Email:    sam@pawx-lang.com
+ * Website:  https://www.pawx-lang.com
+ * GitHub:   https://github.com/samwilcox/pawx
+ *
+ * License:
+ * This file is part of the PAWX programming language project.
+ *
+ * PAWX is dual-licensed under the terms of:
+ *   - The MIT License
+ *   - The Apache License, Version 2.0
+ *
+ * You may choose either license to govern your use of this software.
+ * Full license text available at:
+ *     https://license.pawx-lang.com
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under these licenses is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *
+ * ==========================================================================
+ */
+
+use serde::{ser::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::value::Value;
+
+#[derive(Serialize, Deserialize)]
+enum Literal {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+}
+
+pub fn serialize<S: Serializer>(value: &Value, serializer: S) -> Result<S::Ok, S::Error> {
+    let literal = match value {
+        Value::Null => Literal::Null,
+        Value::Bool(b) => Literal::Bool(*b),
+        Value::Number(n) => Literal::Number(*n),
+        Value::String(s) => Literal::String(s.to_string()),
+        other => return Err(S::Error::custom(format!("cannot serialize literal {:?}", other))),
+    };
+    literal.serialize(serializer)
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Value, D::Error> {
+    Ok(match Literal::deserialize(deserializer)? {
+        Literal::Null => Value::Null,
+        Literal::Bool(b) => Value::Bool(b),
+        Literal::Number(n) => Value::Number(n),
+        Literal::String(s) => Value::String(s.into()),
+    })
+}
@@ -31,11 +31,15 @@ pub mod stmt;
 pub mod param;
 pub mod class;
 pub mod instinct;
+pub mod pattern;
 pub mod types;
+pub mod literal;
+pub mod arena;
 
 pub use expr::*;
 pub use stmt::*;
 pub use param::*;
 pub use class::*;
 pub use instinct::*;
+pub use pattern::*;
 pub use types::*;
\ No newline at end of file
@@ -42,8 +42,10 @@
 
 use crate::ast::Expr;
 
+use serde::{Deserialize, Serialize};
+
 /// Represents **one declared parameter** in a function, lambda, or method.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Param {
     /// Parameter name (identifier)
     pub name: String,
@@ -0,0 +1,103 @@
+/*
+ * ==========================================================================
+ * PAWX - Code with Claws! 🐾
+ * ==========================================================================
+ *
+ * File:     pattern.rs
+ * Purpose:  Defines the AST structures for `match` statements
+ *
+ * This file defines the `Pattern` and `MatchArm` types used by:
+ *  - `match (value) { case <pattern> { ... } default { ... } }`
+ *  - `case 1, 2, 3 { ... }` (several patterns per arm)
+ *  - `case n if n > 10 { ... }` (guard clauses)
+ *
+ * It supports:
+ *  - Literal patterns (`case 404`, `case "ok"`, `case null`)
+ *  - Bindings and the `_` wildcard
+ *  - Type patterns (`case Number n`, `case String`)
+ *  - Object patterns (`case {status: 404, body}`)
+ *  - Array patterns (`case [first, ...rest]`)
+ *
+ * --------------------------------------------------------------------------
+ * Author:   Sam Wilcox
+ * Email:    sam@pawx-lang.com
+ * Website:  https://www.pawx-lang.com
+ * GitHub:   https://github.com/samwilcox/pawx
+ *
+ * License:
+ * This file is part of the PAWX programming language project.
+ *
+ * PAWX is dual-licensed under the terms of:
+ *   - The MIT License
+ *   - The Apache License, Version 2.0
+ *
+ * You may choose either license to govern your use of this software.
+ * Full license text available at:
+ *     https://license.pawx-lang.com
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under these licenses is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *
+ * ==========================================================================
+ */
+
+use crate::ast::{Expr, Stmt};
+use crate::value::Value;
+
+use serde::{Deserialize, Serialize};
+
+/// A pattern tested against a value in a `match` arm.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Pattern {
+    /// `_` — matches anything, binds nothing.
+    Wildcard,
+
+    /// `404`, `"ok"`, `true`, `null` — matches by strict equality.
+    Literal(#[serde(with = "crate::ast::literal")] Value),
+
+    /// `n` — matches anything and binds it.
+    Binding(String),
+
+    /// `Number`, `Number n`, `Cat c` — matches by type name (or class name
+    /// for instances), optionally binding the value.
+    Type {
+        type_name: String,
+        binding: Option<String>,
+    },
+
+    /// `{status: 404, body}` — matches objects (and instances) that have
+    /// every listed key, with each value matching its sub-pattern.
+    /// Extra keys are allowed.
+    Object {
+        fields: Vec<(String, Pattern)>,
+    },
+
+    /// `[first, second]` / `[first, ...rest]` — matches arrays by length
+    /// and element patterns.
+    Array {
+        elements: Vec<Pattern>,
+
+        /// - `None`: the length must match exactly
+        /// - `Some(None)`: `...` — any remaining elements are ignored
+        /// - `Some(Some(name))`: `...name` — remaining elements are bound
+        ///   as an array
+        rest: Option<Option<String>>,
+    },
+}
+
+/// One `case` (or `default`) arm of a `match` statement.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MatchArm {
+    /// Alternatives tried left to right (`case 1, 2, 3`); the arm matches
+    /// if any of them does. `default` arms hold a single
+    /// `Pattern::Wildcard`.
+    pub patterns: Vec<Pattern>,
+
+    /// Optional `if <expr>` guard, evaluated with the pattern's bindings in
+    /// scope. The arm only runs when the guard is truthy.
+    pub guard: Option<Expr>,
+
+    /// Statements executed when the pattern matches.
+    pub body: Vec<Stmt>,
+}
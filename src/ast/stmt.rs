@@ -28,9 +28,29 @@
 use crate::ast::{Expr, Param};
 use crate::ast::class::{ClassMember, AccessLevel};
 use crate::ast::instinct::{InstinctMember};
+use crate::ast::pattern::MatchArm;
+use crate::span::Span;
+
+use serde::{Deserialize, Serialize};
+
+/// How a test block takes part in a run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum TestMode {
+    /// `test "..." { }`
+    #[default]
+    Normal,
+
+    /// `test.only "..." { }`: when any test is focused, only focused tests run.
+    Only,
+
+    /// `test.skip "..." { }`: reported but never run.
+    Skip,
+}
 
 /// All executable PAWX statements.
-#[derive(Debug, Clone)]
+///
+/// Every statement knows where it starts in the source (see [`Stmt::span`]).
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Stmt {
     /* ----------------------------- */
     /* EXPRESSIONS                   */
@@ -45,16 +65,29 @@ pub enum Stmt {
     PublicVar {
         name: String,
         value: Expr,
+        span: Span,
     },
 
     PrivateVar {
         name: String,
         value: Expr,
+        span: Span,
     },
 
     ProtectedVar {
         name: String,
         value: Expr,
+        span: Span,
+    },
+
+    /// `snuggle { foo, bar } = tap("utils");`
+    ///
+    /// Binds the named exports of a module (or fields of an object) as
+    /// public variables.
+    DestructureVar {
+        names: Vec<String>,
+        value: Expr,
+        span: Span,
     },
 
     /* ----------------------------- */
@@ -67,9 +100,23 @@ pub enum Stmt {
         body: Vec<Stmt>,
         return_type: Option<String>,
         is_async: bool,
+        span: Span,
     },
 
-    Return(Option<Expr>),
+    /// `@memoize purr fib -> (n) -> { ... }`
+    ///
+    /// Each decorator is called with the function value (innermost first)
+    /// and the final result is bound under the function's name.
+    Decorated {
+        decorators: Vec<Expr>,
+        function: Box<Stmt>,
+        span: Span,
+    },
+
+    Return {
+        value: Option<Expr>,
+        span: Span,
+    },
 
     /* ----------------------------- */
     /* CONTROL FLOW                  */
@@ -79,11 +126,13 @@ pub enum Stmt {
         condition: Expr,
         then_branch: Vec<Stmt>,
         else_branch: Option<Vec<Stmt>>,
+        span: Span,
     },
 
     While {
         condition: Expr,
         body: Vec<Stmt>,
+        span: Span,
     },
 
     Try {
@@ -91,11 +140,41 @@ pub enum Stmt {
         catch_param: Option<String>,
         catch_block: Option<Vec<Stmt>>,
         finally_block: Option<Vec<Stmt>>,
+        span: Span,
     },
 
     Throw(Expr),
 
-    Nap(Expr),
+    /// `when ENV == "production" { ... } else { ... }`
+    ///
+    /// Compile-time block: the condition is checked against `--define`
+    /// values and environment variables before the program runs, and the
+    /// block is replaced by the chosen branch (see `interpreter::conditional`).
+    When {
+        condition: Expr,
+        then_branch: Vec<Stmt>,
+        else_branch: Option<Vec<Stmt>>,
+        span: Span,
+    },
+
+    /// `match (value) { case <pattern> { ... } default { ... } }`
+    ///
+    /// Arms are tried top to bottom; only the first match runs.
+    Match {
+        subject: Expr,
+        arms: Vec<MatchArm>,
+        span: Span,
+    },
+
+    /// `test "adds numbers" { ... }` (also `it`, `test.only`, `test.skip`)
+    ///
+    /// Skipped during normal runs; registered and run by `pawx test`.
+    Test {
+        name: String,
+        body: Vec<Stmt>,
+        mode: TestMode,
+        span: Span,
+    },
 
     /* ----------------------------- */
     /* CLASSES (CLOWDER)             */
@@ -108,6 +187,7 @@ pub enum Stmt {
         members: Vec<ClassMember>,
         is_exported: bool,
         is_default: bool,
+        span: Span,
     },
 
     /* ----------------------------- */
@@ -119,6 +199,7 @@ pub enum Stmt {
         members: Vec<InstinctMember>,
         is_exported: bool,
         is_default: bool,
+        span: Span,
     },
 
     /* ----------------------------- */
@@ -128,6 +209,7 @@ pub enum Stmt {
     Export {
         name: Option<String>, // None = default export
         value: Expr,
+        span: Span,
     },
 
     /* ----------------------------- */
@@ -137,5 +219,33 @@ pub enum Stmt {
     Pride {
         name: String,
         body: Vec<Stmt>,
+        span: Span,
     },
-}
\ No newline at end of file
+}
+
+impl Stmt {
+    /// Where the statement starts in the source.
+    pub fn span(&self) -> Span {
+        match self {
+            Stmt::Expression(expr) | Stmt::Throw(expr) => expr.span(),
+
+            Stmt::PublicVar { span, .. }
+            | Stmt::PrivateVar { span, .. }
+            | Stmt::ProtectedVar { span, .. }
+            | Stmt::DestructureVar { span, .. }
+            | Stmt::Function { span, .. }
+            | Stmt::Decorated { span, .. }
+            | Stmt::Return { span, .. }
+            | Stmt::If { span, .. }
+            | Stmt::While { span, .. }
+            | Stmt::Try { span, .. }
+            | Stmt::When { span, .. }
+            | Stmt::Match { span, .. }
+            | Stmt::Test { span, .. }
+            | Stmt::Clowder { span, .. }
+            | Stmt::Instinct { span, .. }
+            | Stmt::Export { span, .. }
+            | Stmt::Pride { span, .. } => *span,
+        }
+    }
+}
@@ -0,0 +1,115 @@
+/*
+ * ==========================================================================
+ * PAWX - Code with Claws!
+ * ==========================================================================
+ *
+ * Parsed AST Cache (`.pawx-cache/`)
+ * ---------------------------------
+ * Keeps the parsed AST of every script on disk so later runs can skip
+ * lexing and parsing:
+ *
+ * ```text
+ * .pawx-cache/
+ *   3f1c…e9.ast      AST of one source text (bincode)
+ * ```
+ *
+ * Entries are keyed by a SHA-256 of the source together with the PAWX
+ * version and the AST layout, so editing a file (or upgrading PAWX) simply
+ * misses the cache. Sources with syntax errors are never cached.
+ * Nothing is ever invalidated in place; delete the directory to reclaim
+ * space. A corrupt or unreadable entry is ignored and re-parsed.
+ *
+ * The cache lives in the directory `pawx` was started from. `--no-cache`
+ * turns it off for a run.
+ *
+ * --------------------------------------------------------------------------
+ * Author:   Sam Wilcox
+ * Email:    sam@pawx-lang.com
+ * Website:  https://www.pawx-lang.com
+ * Github:   https://github.com/samwilcox/pawx
+ *
+ * License:
+ * This file is part of the PAWX programming language project.
+ *
+ * PAWX is dual-licensed under the terms of:
+ *   - The MIT License
+ *   - The Apache License, Version 2.0
+ *
+ * You may choose either license to govern your use of this software.
+ * Full license text available at:
+ *    https://license.pawx-lang.com
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under these licenses is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *
+ * ==========================================================================
+ */
+
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use sha2::{Digest, Sha256};
+
+use crate::ast::Stmt;
+use crate::error::PawxError;
+use crate::{lexer, parser};
+
+/// Directory holding cached ASTs.
+pub const CACHE_DIR: &str = ".pawx-cache";
+
+/// Bumped whenever the serialized shape of `Stmt`/`Expr` changes.
+const AST_FORMAT: u32 = 5;
+
+/// Whether the cache is used (`--no-cache` turns it off).
+static ENABLED: AtomicBool = AtomicBool::new(true);
+
+/// Turns the cache off for the rest of the process.
+pub fn disable() {
+    ENABLED.store(false, Ordering::Relaxed);
+}
+
+/// Returns the cache file for `source`.
+fn entry_path(source: &str) -> Option<PathBuf> {
+    let mut hasher = Sha256::new();
+    hasher.update(env!("CARGO_PKG_VERSION").as_bytes());
+    hasher.update([0]);
+    hasher.update(AST_FORMAT.to_le_bytes());
+    hasher.update(source.as_bytes());
+
+    let key: String = hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect();
+    let dir = std::env::current_dir().ok()?.join(CACHE_DIR);
+    Some(dir.join(format!("{}.ast", key)))
+}
+
+/// Parses `source`, reusing a cached AST when the source is unchanged.
+///
+/// Failing to read or write the cache never fails the parse.
+pub fn parse(source: &str) -> Result<Vec<Stmt>, PawxError> {
+    let path = ENABLED.load(Ordering::Relaxed).then(|| entry_path(source)).flatten();
+
+    if let Some(path) = &path {
+        let cached = fs::read(path).ok().and_then(|bytes| bincode::deserialize(&bytes).ok());
+        if let Some(ast) = cached {
+            return Ok(ast);
+        }
+    }
+
+    let ast = parser::parse(lexer::tokenize(source)?)?;
+
+    if let Some(path) = path {
+        if let Ok(bytes) = bincode::serialize(&ast) {
+            // Write then rename, so a concurrent run never reads half a file.
+            let partial = path.with_extension(format!("{}.tmp", std::process::id()));
+            let stored = path.parent().is_some_and(|dir| fs::create_dir_all(dir).is_ok())
+                && fs::write(&partial, bytes).is_ok()
+                && fs::rename(&partial, &path).is_ok();
+            if !stored {
+                let _ = fs::remove_file(&partial);
+            }
+        }
+    }
+
+    Ok(ast)
+}
@@ -0,0 +1,359 @@
+/*
+ * ==========================================================================
+ * PAWX - Code with Claws!
+ * ==========================================================================
+ *
+ * Benchmark Baselines (`pawx bench`)
+ * ----------------------------------
+ * Statistics, saved baselines and comparisons for `pawx bench`, which times
+ * whole script runs.
+ *
+ * `--save <name>` stores every sample under `.pawx/bench/<name>.json`:
+ *
+ * ```text
+ * {
+ *   "version": 1,
+ *   "benchmarks": {
+ *     "bench/parse.px": { "samples_ms": [41.2, 40.8, 42.0] }
+ *   }
+ * }
+ * ```
+ *
+ * `--compare <name>` checks new samples against those with Welch's t-test.
+ * A benchmark regresses when its mean is slower by more than the threshold
+ * percentage *and* the difference is significant (p < 0.05). With fewer
+ * than two samples on either side only the threshold is used.
+ *
+ * --------------------------------------------------------------------------
+ * Author:   Sam Wilcox
+ * Email:    sam@pawx-lang.com
+ * Website:  https://www.pawx-lang.com
+ * Github:   https://github.com/samwilcox/pawx
+ *
+ * License:
+ * This file is part of the PAWX programming language project.
+ *
+ * PAWX is dual-licensed under the terms of:
+ *   - The MIT License
+ *   - The Apache License, Version 2.0
+ *
+ * You may choose either license to govern your use of this software.
+ * Full license text available at:
+ *    https://license.pawx-lang.com
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under these licenses is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *
+ * ==========================================================================
+ */
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+/// Directory (relative to the project) holding saved baselines.
+pub const BASELINE_DIR: &str = ".pawx/bench";
+
+/// Significance level for the t-test.
+pub const ALPHA: f64 = 0.05;
+
+/// Summary statistics of one benchmark's samples, in milliseconds.
+#[derive(Debug, Clone)]
+pub struct Stats {
+    pub runs: usize,
+    pub mean: f64,
+    pub stddev: f64,
+    pub median: f64,
+    pub min: f64,
+    pub max: f64,
+}
+
+impl Stats {
+    /// Summarizes `samples`, which must not be empty.
+    pub fn of(samples: &[f64]) -> Self {
+        let runs = samples.len();
+        let mean = samples.iter().sum::<f64>() / runs as f64;
+        let variance = match runs {
+            0 | 1 => 0.0,
+            _ => samples.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / (runs - 1) as f64,
+        };
+
+        let mut sorted = samples.to_vec();
+        sorted.sort_by(f64::total_cmp);
+        let median = match runs % 2 {
+            1 => sorted[runs / 2],
+            _ => (sorted[runs / 2 - 1] + sorted[runs / 2]) / 2.0,
+        };
+
+        Self {
+            runs,
+            mean,
+            stddev: variance.sqrt(),
+            median,
+            min: sorted[0],
+            max: sorted[runs - 1],
+        }
+    }
+
+    fn to_json(&self) -> serde_json::Value {
+        json!({
+            "runs": self.runs,
+            "mean_ms": self.mean,
+            "stddev_ms": self.stddev,
+            "median_ms": self.median,
+            "min_ms": self.min,
+            "max_ms": self.max,
+        })
+    }
+}
+
+/* ============================================================================
+ * Welch's t-test
+ * ============================================================================
+ */
+
+/// Natural log of the gamma function (Lanczos approximation).
+fn ln_gamma(x: f64) -> f64 {
+    const G: [f64; 6] = [
+        76.18009172947146,
+        -86.50532032941677,
+        24.01409824083091,
+        -1.231739572450155,
+        0.1208650973866179e-2,
+        -0.5395239384953e-5,
+    ];
+
+    let tmp = x + 5.5 - (x + 0.5) * (x + 5.5).ln();
+    let series = G
+        .iter()
+        .enumerate()
+        .fold(1.000000000190015, |sum, (i, g)| sum + g / (x + 1.0 + i as f64));
+    -tmp + (2.5066282746310005 * series / x).ln()
+}
+
+/// Continued fraction for the incomplete beta function.
+fn beta_fraction(a: f64, b: f64, x: f64) -> f64 {
+    const TINY: f64 = 1e-30;
+    let (qab, qap, qam) = (a + b, a + 1.0, a - 1.0);
+    let mut c = 1.0;
+    let mut d = 1.0 - qab * x / qap;
+    if d.abs() < TINY {
+        d = TINY;
+    }
+    d = 1.0 / d;
+    let mut h = d;
+
+    for m in 1..200 {
+        let m = m as f64;
+        let m2 = 2.0 * m;
+
+        let aa = m * (b - m) * x / ((qam + m2) * (a + m2));
+        d = 1.0 + aa * d;
+        d = if d.abs() < TINY { 1.0 / TINY } else { 1.0 / d };
+        c = 1.0 + aa / c;
+        if c.abs() < TINY {
+            c = TINY;
+        }
+        h *= d * c;
+
+        let aa = -(a + m) * (qab + m) * x / ((a + m2) * (qap + m2));
+        d = 1.0 + aa * d;
+        d = if d.abs() < TINY { 1.0 / TINY } else { 1.0 / d };
+        c = 1.0 + aa / c;
+        if c.abs() < TINY {
+            c = TINY;
+        }
+        let delta = d * c;
+        h *= delta;
+
+        if (delta - 1.0).abs() < 1e-12 {
+            break;
+        }
+    }
+    h
+}
+
+/// Regularized incomplete beta function `I_x(a, b)`.
+fn incomplete_beta(a: f64, b: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    if x >= 1.0 {
+        return 1.0;
+    }
+
+    let front = (ln_gamma(a + b) - ln_gamma(a) - ln_gamma(b) + a * x.ln() + b * (1.0 - x).ln()).exp();
+    if x < (a + 1.0) / (a + b + 2.0) {
+        front * beta_fraction(a, b, x) / a
+    } else {
+        1.0 - front * beta_fraction(b, a, 1.0 - x) / b
+    }
+}
+
+/// Two-sided p-value of Welch's t-test for a difference in means, or
+/// `None` with fewer than two samples on either side.
+pub fn welch_p_value(a: &Stats, b: &Stats) -> Option<f64> {
+    if a.runs < 2 || b.runs < 2 {
+        return None;
+    }
+
+    let (va, vb) = (a.stddev.powi(2) / a.runs as f64, b.stddev.powi(2) / b.runs as f64);
+    if va + vb == 0.0 {
+        // Identical, noise-free samples: any difference is certain.
+        return Some(if a.mean == b.mean { 1.0 } else { 0.0 });
+    }
+
+    let t = (a.mean - b.mean) / (va + vb).sqrt();
+    let df = (va + vb).powi(2) / (va.powi(2) / (a.runs - 1) as f64 + vb.powi(2) / (b.runs - 1) as f64);
+    Some(incomplete_beta(df / 2.0, 0.5, df / (df + t * t)))
+}
+
+/* ============================================================================
+ * Baselines
+ * ============================================================================
+ */
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct BaselineEntry {
+    samples_ms: Vec<f64>,
+}
+
+/// Samples saved by `pawx bench --save`, keyed by benchmark name.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Baseline {
+    version: u32,
+    benchmarks: BTreeMap<String, BaselineEntry>,
+}
+
+/// Returns the file a baseline called `name` is stored in.
+pub fn baseline_path(root: &Path, name: &str) -> PathBuf {
+    root.join(BASELINE_DIR).join(format!("{}.json", name))
+}
+
+impl Baseline {
+    /// Builds a baseline from `(name, samples)` pairs.
+    pub fn from_samples(results: &[(String, Vec<f64>)]) -> Self {
+        Self {
+            version: 1,
+            benchmarks: results
+                .iter()
+                .map(|(name, samples)| (name.clone(), BaselineEntry { samples_ms: samples.clone() }))
+                .collect(),
+        }
+    }
+
+    /// Loads the baseline stored at `path`.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        serde_json::from_str(&text).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    /// Writes the baseline to `path`, creating its directory.
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+        }
+        let text = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        fs::write(path, text + "\n").map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    /// Returns the saved samples for `name`.
+    pub fn samples(&self, name: &str) -> Option<&[f64]> {
+        self.benchmarks.get(name).map(|e| e.samples_ms.as_slice())
+    }
+}
+
+/* ============================================================================
+ * Comparison
+ * ============================================================================
+ */
+
+/// How a benchmark changed against its baseline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    /// Not in the baseline.
+    New,
+    Unchanged,
+    Improvement,
+    Regression,
+}
+
+impl Verdict {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Verdict::New => "new",
+            Verdict::Unchanged => "unchanged",
+            Verdict::Improvement => "improvement",
+            Verdict::Regression => "regression",
+        }
+    }
+}
+
+/// One benchmark's result, compared against a baseline if there was one.
+#[derive(Debug, Clone)]
+pub struct Comparison {
+    pub name: String,
+    pub stats: Stats,
+    pub baseline: Option<Stats>,
+
+    /// Percentage change of the mean (positive is slower).
+    pub change_pct: Option<f64>,
+    pub p_value: Option<f64>,
+    pub verdict: Verdict,
+}
+
+/// Compares `samples` with `baseline`; changes within `threshold_pct`
+/// percent, or that are not significant, count as unchanged.
+pub fn compare(name: &str, samples: &[f64], baseline: Option<&[f64]>, threshold_pct: f64) -> Comparison {
+    let stats = Stats::of(samples);
+    let baseline = baseline.filter(|s| !s.is_empty()).map(Stats::of);
+
+    let (change_pct, p_value, verdict) = match &baseline {
+        None => (None, None, Verdict::New),
+        Some(base) => {
+            let change = (stats.mean - base.mean) / base.mean * 100.0;
+            let p = welch_p_value(&stats, base);
+            let significant = p.is_none_or(|p| p < ALPHA);
+
+            let verdict = match (significant, change) {
+                (true, c) if c > threshold_pct => Verdict::Regression,
+                (true, c) if c < -threshold_pct => Verdict::Improvement,
+                _ => Verdict::Unchanged,
+            };
+            (Some(change), p, verdict)
+        }
+    };
+
+    Comparison {
+        name: name.to_string(),
+        stats,
+        baseline,
+        change_pct,
+        p_value,
+        verdict,
+    }
+}
+
+/// Renders comparisons as the `--json` report.
+pub fn to_json(comparisons: &[Comparison]) -> String {
+    let benchmarks: Vec<_> = comparisons
+        .iter()
+        .map(|c| {
+            let mut value = c.stats.to_json();
+            value["name"] = json!(c.name);
+            value["baseline"] = c.baseline.as_ref().map(Stats::to_json).unwrap_or(serde_json::Value::Null);
+            value["change_pct"] = json!(c.change_pct);
+            value["p_value"] = json!(c.p_value);
+            value["verdict"] = json!(c.verdict.as_str());
+            value
+        })
+        .collect();
+
+    let regressions = comparisons.iter().filter(|c| c.verdict == Verdict::Regression).count();
+    serde_json::to_string_pretty(&json!({ "benchmarks": benchmarks, "regressions": regressions }))
+        .unwrap_or_default()
+}
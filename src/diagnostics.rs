@@ -26,9 +26,37 @@
  * ==========================================================================
  */
 
+use serde_json::json;
+
 use crate::error::PawxError;
 use crate::span::Span;
-use std::fs;
+
+/// How diagnostics are written (`--error-format`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ErrorFormat {
+    /// The source line with a caret under the error.
+    #[default]
+    Human,
+
+    /// One JSON object per line, for editors and other tools:
+    ///
+    /// ```text
+    /// {"code":"E1001","name":"UndefinedVariable","message":"undefined variable 'x'",
+    ///  "span":{"file":"app.px","line":4,"column":16},"hints":[]}
+    /// ```
+    Json,
+}
+
+impl ErrorFormat {
+    /// Parses an `--error-format` value.
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "human" => Some(ErrorFormat::Human),
+            "json" => Some(ErrorFormat::Json),
+            _ => None,
+        }
+    }
+}
 
 /// Responsible for rendering human-friendly, compiler-style diagnostics
 /// for PAWX errors.
@@ -52,6 +80,9 @@ pub struct DiagnosticPrinter {
     ///
     /// Used only for display purposes in diagnostics.
     file_name: String,
+
+    /// Output format, human-readable unless set with `with_format`.
+    format: ErrorFormat,
 }
 
 impl DiagnosticPrinter {
@@ -67,9 +98,16 @@ impl DiagnosticPrinter {
         Self {
             file_name: file_name.into(),
             source: source.into(),
+            format: ErrorFormat::Human,
         }
     }
 
+    /// Sets the output format (builder-style).
+    pub fn with_format(mut self, format: ErrorFormat) -> Self {
+        self.format = format;
+        self
+    }
+
     /// Prints a formatted error diagnostic to stderr.
     ///
     /// This function:
@@ -81,14 +119,21 @@ impl DiagnosticPrinter {
     ///
     /// # Output Example
     /// ```text
-    /// error[P0004]: invalid binary operation
+    /// error[E2002]: invalid binary operation
     ///   --> example.px:12:10
     ///    |
     /// 12 | let x = 5 + true
     ///    |          ^
     /// help: Check operand types or use a conversion.
     /// ```
+    ///
+    /// With [`ErrorFormat::Json`] a single JSON line is printed instead.
     pub fn print(&self, error: &PawxError) {
+        if self.format == ErrorFormat::Json {
+            eprintln!("{}", self.to_json(error));
+            return;
+        }
+
         // Destructure the span to get precise location data
         let Span { line, column } = error.span;
 
@@ -136,4 +181,20 @@ impl DiagnosticPrinter {
             eprintln!("\nhelp: {}", help);
         }
     }
-}
\ No newline at end of file
+
+    /// The JSON form of `error`. Columns are 1-based, as in the
+    /// human-readable output.
+    pub fn to_json(&self, error: &PawxError) -> serde_json::Value {
+        json!({
+            "code": error.code.code(),
+            "name": error.code.name(),
+            "message": error.message,
+            "span": {
+                "file": self.file_name,
+                "line": error.span.line,
+                "column": error.span.column + 1,
+            },
+            "hints": error.help.iter().collect::<Vec<_>>(),
+        })
+    }
+}
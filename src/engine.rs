@@ -0,0 +1,158 @@
+/*
+ * ==========================================================================
+ * PAWX - Code with Claws!
+ * ==========================================================================
+ *
+ * Compile Once, Run Many (`Engine`, `Program`)
+ * --------------------------------------------
+ * Embedders that run the same script over and over (once per HTTP
+ * request, per job, per message) shouldn't lex and parse it every time:
+ *
+ * ```text
+ * let program = Engine::compile(&source)?;        // once
+ *
+ * let engine = Engine::new(Prelude::new());
+ * for request in requests {
+ *     let globals = HashMap::from([("request".to_string(), to_value(&request))]);
+ *     engine.run_program(&program, globals)?;      // per request
+ * }
+ * ```
+ *
+ * A `Program` is the parsed tree. It is immutable, `Send` and `Sync`, and
+ * cheap to clone (an `Arc`), so one compiled program can be shared by
+ * every worker thread; each thread makes its own `Engine`, since runtime
+ * values never leave the thread that made them. An engine decodes a
+ * program the first time it runs it and keeps the tree for later runs.
+ * Every run gets a fresh global scope: nothing one run defines is seen by
+ * the next.
+ *
+ * Each run still lowers the tree into the evaluator's arena (see
+ * ast::arena), because `when` blocks are resolved against the engine's
+ * prelude; that is one linear pass, far cheaper than lexing and parsing.
+ *
+ * --------------------------------------------------------------------------
+ * Author:   Sam Wilcox
+ * Email:    sam@pawx-lang.com
+ * Website:  https://www.pawx-lang.com
+ * Github:   https://github.com/samwilcox/pawx
+ *
+ * License:
+ * This file is part of the PAWX programming language project.
+ *
+ * PAWX is dual-licensed under the terms of:
+ *   - The MIT License
+ *   - The Apache License, Version 2.0
+ *
+ * You may choose either license to govern your use of this software.
+ * Full license text available at:
+ *    https://license.pawx-lang.com
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under these licenses is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *
+ * ==========================================================================
+ */
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::sync::{Arc, Weak};
+
+use crate::ast::Stmt;
+use crate::error::{ErrorCode, PawxError};
+use crate::interpreter::prelude::Prelude;
+use crate::span::Span;
+use crate::value::Value;
+use crate::{interpreter, lexer, parser};
+
+/// A parsed PAWX program, ready to run any number of times.
+///
+/// Parsed trees hold runtime values (literals), which are tied to one
+/// thread, so a program carries its tree encoded the way the AST cache
+/// stores it (see ast_cache.rs).
+#[derive(Clone)]
+pub struct Program {
+    encoded: Arc<[u8]>,
+}
+
+impl std::fmt::Debug for Program {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Program({} bytes)", self.encoded.len())
+    }
+}
+
+// Programs are shared across threads
+const _: fn() = || {
+    fn shareable<T: Send + Sync>() {}
+    shareable::<Program>();
+};
+
+/// A decoded tree and the program it came from.
+type Decoded = (Weak<[u8]>, Rc<[Stmt]>);
+
+/// Runs compiled programs with a fixed prelude.
+#[derive(Debug, Clone, Default)]
+pub struct Engine {
+    prelude: Prelude,
+    /// Trees decoded on this engine, by program; entries of dropped
+    /// programs are pruned as new ones arrive.
+    decoded: RefCell<HashMap<usize, Decoded>>,
+}
+
+impl Engine {
+    /// Creates an engine whose runs install the globals of `prelude`.
+    pub fn new(prelude: Prelude) -> Self {
+        Engine {
+            prelude,
+            decoded: RefCell::default(),
+        }
+    }
+
+    /// Lexes and parses `source` once.
+    ///
+    /// # Errors
+    /// The first syntax error, with its span.
+    pub fn compile(source: &str) -> Result<Program, PawxError> {
+        let tokens = lexer::tokenize(source)?;
+        let statements = parser::parse(tokens)?;
+        let encoded = bincode::serialize(&statements).map_err(|e| {
+            PawxError::new(ErrorCode::RuntimeError, format!("cannot compile program: {}", e), Span::default())
+        })?;
+
+        Ok(Program {
+            encoded: encoded.into(),
+        })
+    }
+
+    /// Runs `program` in a fresh global scope holding the prelude's globals
+    /// and `globals` (which win over prelude globals of the same name),
+    /// until its timers have fired.
+    ///
+    /// # Errors
+    /// A runtime error or uncaught throw at the top level, with the
+    /// location of the statement that failed.
+    pub fn run_program(&self, program: &Program, globals: HashMap<String, Value>) -> Result<(), PawxError> {
+        let statements = self.statements(program)?;
+        interpreter::run_with_globals(statements.to_vec(), &self.prelude, globals)
+    }
+
+    /// The tree of `program`, decoded on first use.
+    fn statements(&self, program: &Program) -> Result<Rc<[Stmt]>, PawxError> {
+        let key = Arc::as_ptr(&program.encoded) as *const u8 as usize;
+        let mut decoded = self.decoded.borrow_mut();
+
+        if let Some((_, statements)) = decoded.get(&key) {
+            return Ok(statements.clone());
+        }
+
+        let statements: Vec<Stmt> = bincode::deserialize(&program.encoded).map_err(|e| {
+            PawxError::new(ErrorCode::RuntimeError, format!("cannot load program: {}", e), Span::default())
+        })?;
+        let statements: Rc<[Stmt]> = statements.into();
+
+        decoded.retain(|_, (alive, _)| alive.strong_count() > 0);
+        decoded.insert(key, (Arc::downgrade(&program.encoded), statements.clone()));
+        Ok(statements)
+    }
+}
@@ -26,12 +26,116 @@
  * ==========================================================================
  */
 
+use std::fmt;
+
 use crate::span::Span;
+use crate::value::Value;
+
+/// Stable identifier for each kind of error PAWX reports.
+///
+/// Codes are grouped by hundreds of the first digit and never reused, so
+/// tools can match on them across releases:
+///
+/// | Range  | Kind                                  |
+/// |--------|---------------------------------------|
+/// | E0xxx  | Lexing and parsing                    |
+/// | E1xxx  | Names and properties that don't exist |
+/// | E2xxx  | Operations on the wrong type          |
+/// | E3xxx  | Other runtime failures                |
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorCode {
+    SyntaxError,
+    UndefinedVariable,
+    UnknownProperty,
+    UndefinedReference,
+    InvalidUnaryOperand,
+    InvalidBinaryOperand,
+    TypeMismatch,
+    InvalidIndexType,
+    NotIndexable,
+    NotAnObject,
+    InvalidDecimalOperation,
+    InstinctNotSatisfied,
+    InaccessibleMember,
+    RuntimeError,
+    IndexOutOfBounds,
+    InvalidArguments,
+    UncaughtException,
+    BuiltinError,
+    ModuleNotFound,
+    CircularImport,
+    RecursionLimit,
+    LockfileMismatch,
+}
+
+impl ErrorCode {
+    /// The code shown in diagnostics, e.g. `E1001`.
+    pub fn code(self) -> &'static str {
+        match self {
+            ErrorCode::SyntaxError => "E0001",
+            ErrorCode::UndefinedVariable => "E1001",
+            ErrorCode::UnknownProperty => "E1002",
+            ErrorCode::UndefinedReference => "E1003",
+            ErrorCode::InvalidUnaryOperand => "E2001",
+            ErrorCode::InvalidBinaryOperand => "E2002",
+            ErrorCode::TypeMismatch => "E2003",
+            ErrorCode::InvalidIndexType => "E2004",
+            ErrorCode::NotIndexable => "E2005",
+            ErrorCode::NotAnObject => "E2006",
+            ErrorCode::InvalidDecimalOperation => "E2007",
+            ErrorCode::InstinctNotSatisfied => "E2008",
+            ErrorCode::InaccessibleMember => "E2009",
+            ErrorCode::RuntimeError => "E3001",
+            ErrorCode::IndexOutOfBounds => "E3002",
+            ErrorCode::InvalidArguments => "E3003",
+            ErrorCode::UncaughtException => "E3004",
+            ErrorCode::BuiltinError => "E3005",
+            ErrorCode::ModuleNotFound => "E3006",
+            ErrorCode::CircularImport => "E3007",
+            ErrorCode::RecursionLimit => "E3008",
+            ErrorCode::LockfileMismatch => "E3009",
+        }
+    }
+
+    /// The variant name, e.g. `UndefinedVariable`.
+    pub fn name(self) -> &'static str {
+        match self {
+            ErrorCode::SyntaxError => "SyntaxError",
+            ErrorCode::UndefinedVariable => "UndefinedVariable",
+            ErrorCode::UnknownProperty => "UnknownProperty",
+            ErrorCode::UndefinedReference => "UndefinedReference",
+            ErrorCode::InvalidUnaryOperand => "InvalidUnaryOperand",
+            ErrorCode::InvalidBinaryOperand => "InvalidBinaryOperand",
+            ErrorCode::TypeMismatch => "TypeMismatch",
+            ErrorCode::InvalidIndexType => "InvalidIndexType",
+            ErrorCode::NotIndexable => "NotIndexable",
+            ErrorCode::NotAnObject => "NotAnObject",
+            ErrorCode::InvalidDecimalOperation => "InvalidDecimalOperation",
+            ErrorCode::InstinctNotSatisfied => "InstinctNotSatisfied",
+            ErrorCode::InaccessibleMember => "InaccessibleMember",
+            ErrorCode::RuntimeError => "RuntimeError",
+            ErrorCode::IndexOutOfBounds => "IndexOutOfBounds",
+            ErrorCode::InvalidArguments => "InvalidArguments",
+            ErrorCode::UncaughtException => "UncaughtException",
+            ErrorCode::BuiltinError => "BuiltinError",
+            ErrorCode::ModuleNotFound => "ModuleNotFound",
+            ErrorCode::CircularImport => "CircularImport",
+            ErrorCode::RecursionLimit => "RecursionLimit",
+            ErrorCode::LockfileMismatch => "LockfileMismatch",
+        }
+    }
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct PawxError {
-    /// Stable error code (P0001, P0002, …)
-    pub code: &'static str,
+    /// Stable error code (see [`ErrorCode`])
+    pub code: ErrorCode,
 
     /// Human-readable error message
     pub message: String,
@@ -41,12 +145,16 @@ pub struct PawxError {
 
     /// Optional note / help text
     pub help: Option<String>,
+
+    /// The message of the `Error` a script threw, when this is an uncaught
+    /// `throw` of one. Kept as text so errors stay `Send`.
+    pub thrown: Option<String>,
 }
 
 impl PawxError {
     /// Generic constructor
     pub fn new(
-        code: &'static str,
+        code: ErrorCode,
         message: impl Into<String>,
         span: Span,
     ) -> Self {
@@ -55,15 +163,46 @@ impl PawxError {
             message: message.into(),
             span,
             help: None,
+            thrown: None,
         }
     }
 
+    /// Uncaught `throw` of `value`. A thrown `Error` reaches a surrounding
+    /// `catch` unchanged; other values arrive as an `Error` carrying this
+    /// message.
+    pub fn thrown(value: Value, span: Span) -> Self {
+        let mut error = Self::new(
+            ErrorCode::UncaughtException,
+            format!("Uncaught exception: {}", value.stringify()),
+            span,
+        );
+        if let Value::Error { message } = value {
+            error.thrown = Some(message);
+        }
+        error
+    }
+
+    /// The `Error` value a `catch` receives for this error.
+    pub fn into_value(self) -> Value {
+        Value::Error {
+            message: self.thrown.unwrap_or(self.message),
+        }
+    }
+
+    /// Syntax error (lexing / parsing)
+    pub fn syntax_error(
+        message: impl Into<String>,
+        span: Span,
+    ) -> Self {
+        Self::new(ErrorCode::SyntaxError, message, span)
+    }
+
     /// Runtime error (during evaluation)
     pub fn runtime_error(
         message: impl Into<String>,
         span: Span,
     ) -> Self {
-        Self::new("E_RUNTIME", message, span)
+        Self::new(ErrorCode::RuntimeError, message, span)
     }
 
     /// Type error (invalid operation / operand types)
@@ -71,7 +210,7 @@ impl PawxError {
         message: impl Into<String>,
         span: Span,
     ) -> Self {
-        Self::new("E_TYPE", message, span)
+        Self::new(ErrorCode::TypeMismatch, message, span)
     }
 
     /// Reference error (undefined variable, property, etc.)
@@ -79,7 +218,7 @@ impl PawxError {
         message: impl Into<String>,
         span: Span,
     ) -> Self {
-        Self::new("E_REFERENCE", message, span)
+        Self::new(ErrorCode::UndefinedReference, message, span)
     }
 
     /// Attach a help message to the error (builder-style).
@@ -87,4 +226,15 @@ impl PawxError {
         self.help = Some(help.into());
         self
     }
-}
\ No newline at end of file
+}
+
+/// One-line form: `message (at line:column)`.
+///
+/// Use `DiagnosticPrinter` to show the offending source line as well.
+impl fmt::Display for PawxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (at {})", self.message, self.span)
+    }
+}
+
+impl std::error::Error for PawxError {}
\ No newline at end of file
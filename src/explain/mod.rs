@@ -0,0 +1,106 @@
+/*
+ * ==========================================================================
+ * PAWX - Code with Claws!
+ * ==========================================================================
+ * 
+ * Author:   Sam Wilcox
+ * Email:    sam@pawx-lang.com
+ * Website:  https://www.pawx-lang.com
+ * Github:   https://github.com/samwilcox/pawx
+ * 
+ * License:
+ * This file is part of the PAWX programming language project.
+ * 
+ * PAWX is dual-licensed under the terms of:
+ *   - The MIT license
+ *   - The Apache License, Version 2.0
+ * 
+ * You may choose either license to govern your use of this software.
+ * Full license text available at:
+ *    https://license.pawx-lang.com
+ * 
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under these licenses is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * 
+ * ==========================================================================
+ */
+
+
+/*!
+ * PAWX Error Explanations (`pawx explain`)
+ * ----------------------------------------
+ *
+ * Long-form help for each [`ErrorCode`]: what it means, common causes, and
+ * an erroneous and a corrected example. The text lives in `errors/`, one
+ * Markdown file per code, and is compiled into the binary, so
+ * `pawx explain E1001` works offline.
+ *
+ * The match in [`explanation`] is exhaustive, so adding an error code
+ * without writing its explanation does not compile.
+ */
+
+use crate::error::ErrorCode;
+
+/// Every error code, in numeric order.
+pub const ALL: [ErrorCode; 22] = [
+    ErrorCode::SyntaxError,
+    ErrorCode::UndefinedVariable,
+    ErrorCode::UnknownProperty,
+    ErrorCode::UndefinedReference,
+    ErrorCode::InvalidUnaryOperand,
+    ErrorCode::InvalidBinaryOperand,
+    ErrorCode::TypeMismatch,
+    ErrorCode::InvalidIndexType,
+    ErrorCode::NotIndexable,
+    ErrorCode::NotAnObject,
+    ErrorCode::InvalidDecimalOperation,
+    ErrorCode::InstinctNotSatisfied,
+    ErrorCode::InaccessibleMember,
+    ErrorCode::RuntimeError,
+    ErrorCode::IndexOutOfBounds,
+    ErrorCode::InvalidArguments,
+    ErrorCode::UncaughtException,
+    ErrorCode::BuiltinError,
+    ErrorCode::ModuleNotFound,
+    ErrorCode::CircularImport,
+    ErrorCode::RecursionLimit,
+    ErrorCode::LockfileMismatch,
+];
+
+/// The extended description of `code`.
+pub fn explanation(code: ErrorCode) -> &'static str {
+    match code {
+        ErrorCode::SyntaxError => include_str!("errors/E0001.md"),
+        ErrorCode::UndefinedVariable => include_str!("errors/E1001.md"),
+        ErrorCode::UnknownProperty => include_str!("errors/E1002.md"),
+        ErrorCode::UndefinedReference => include_str!("errors/E1003.md"),
+        ErrorCode::InvalidUnaryOperand => include_str!("errors/E2001.md"),
+        ErrorCode::InvalidBinaryOperand => include_str!("errors/E2002.md"),
+        ErrorCode::TypeMismatch => include_str!("errors/E2003.md"),
+        ErrorCode::InvalidIndexType => include_str!("errors/E2004.md"),
+        ErrorCode::NotIndexable => include_str!("errors/E2005.md"),
+        ErrorCode::NotAnObject => include_str!("errors/E2006.md"),
+        ErrorCode::InvalidDecimalOperation => include_str!("errors/E2007.md"),
+        ErrorCode::InstinctNotSatisfied => include_str!("errors/E2008.md"),
+        ErrorCode::InaccessibleMember => include_str!("errors/E2009.md"),
+        ErrorCode::RuntimeError => include_str!("errors/E3001.md"),
+        ErrorCode::IndexOutOfBounds => include_str!("errors/E3002.md"),
+        ErrorCode::InvalidArguments => include_str!("errors/E3003.md"),
+        ErrorCode::UncaughtException => include_str!("errors/E3004.md"),
+        ErrorCode::BuiltinError => include_str!("errors/E3005.md"),
+        ErrorCode::ModuleNotFound => include_str!("errors/E3006.md"),
+        ErrorCode::CircularImport => include_str!("errors/E3007.md"),
+        ErrorCode::RecursionLimit => include_str!("errors/E3008.md"),
+        ErrorCode::LockfileMismatch => include_str!("errors/E3009.md"),
+    }
+}
+
+/// Looks up a code by number (`E1001`, case-insensitive, the `E` optional)
+/// or by name (`UndefinedVariable`).
+pub fn lookup(query: &str) -> Option<ErrorCode> {
+    let query = query.trim();
+    let number = query.strip_prefix(['E', 'e']).unwrap_or(query);
+
+    ALL.into_iter().find(|code| code.code()[1..] == *number || code.name().eq_ignore_ascii_case(query))
+}
@@ -0,0 +1,428 @@
+/*
+ * ==========================================================================
+ * PAWX - Code with Claws!
+ * ==========================================================================
+ *
+ * Automated Migrations (`pawx fix`)
+ * ---------------------------------
+ * Rewrites code that matches a structural pattern (see `grep.rs`) into a
+ * replacement, filling in the replacement's metavariables from the match:
+ *
+ * ```text
+ * from: Http.get($url, $options)
+ * to:   Http.request($url, $options)
+ * ```
+ *
+ * Rules come from three places, applied in this order:
+ *
+ * - the fixes built into PAWX ([`BUILTIN`]), one per breaking change;
+ * - installed packages whose `paw.json` names a rules file under
+ *   `"fixes"`, so a library can ship the upgrade for its breaking changes;
+ * - rules files passed with `pawx fix --rules <file>`.
+ *
+ * A rules file is JSON:
+ *
+ * ```text
+ * {
+ *   "rules": [
+ *     {
+ *       "name": "get-to-request",
+ *       "description": "Http.get was replaced by Http.request",
+ *       "from": "Http.get($url, $options)",
+ *       "to": "Http.request($url, $options)"
+ *     }
+ *   ]
+ * }
+ * ```
+ *
+ * Only the matched code is replaced: everything around it, comments and
+ * formatting included, is kept as written, and a metavariable is filled in
+ * with the original text of what it matched. A rule whose output no longer
+ * parses is reported and leaves the file untouched.
+ *
+ * --------------------------------------------------------------------------
+ * Author:   Sam Wilcox
+ * Email:    sam@pawx-lang.com
+ * Website:  https://www.pawx-lang.com
+ * Github:   https://github.com/samwilcox/pawx
+ *
+ * License:
+ * This file is part of the PAWX programming language project.
+ *
+ * PAWX is dual-licensed under the terms of:
+ *   - The MIT License
+ *   - The Apache License, Version 2.0
+ *
+ * You may choose either license to govern your use of this software.
+ * Full license text available at:
+ *    https://license.pawx-lang.com
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under these licenses is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *
+ * ==========================================================================
+ */
+
+use std::fs;
+use std::ops::Range;
+use std::path::Path;
+
+use serde::Deserialize;
+use serde_json::Value as Json;
+
+use crate::error::PawxError;
+use crate::grep::{self, Bindings, Pattern};
+use crate::lexer;
+use crate::lexer::token::{Token, TokenKind};
+use crate::lockfile;
+use crate::manifest::{Manifest, MANIFEST_FILE};
+use crate::parser;
+use crate::parser::parser::Parser;
+use crate::span::Span;
+
+/// Fixes built into `pawx fix`, as `(name, description, from, to)`.
+///
+/// A breaking change to the language or a built-in adds its migration
+/// here, so upgrading a project is one `pawx fix` away.
+pub const BUILTIN: &[(&str, &str, &str, &str)] = &[
+    (
+        "group-by-get",
+        "groupBy returns an Immutable.map; read groups with get",
+        "$list.groupBy($$args)[$key]",
+        "$list.groupBy($$args).get($key)",
+    ),
+    (
+        "count-by-get",
+        "countBy returns an Immutable.map; read counts with get",
+        "$list.countBy($$args)[$key]",
+        "$list.countBy($$args).get($key)",
+    ),
+];
+
+/// A rewrite from one structural pattern to a replacement.
+#[derive(Debug, Clone)]
+pub struct Rule {
+    pub name: String,
+    pub description: String,
+    pattern: Pattern,
+    replacement: String,
+}
+
+/// One rewrite made by [`apply`].
+#[derive(Debug, Clone)]
+pub struct Edit {
+    /// Name of the rule that made it.
+    pub rule: String,
+
+    /// Where the replaced code started, before the rule ran.
+    pub span: Span,
+}
+
+#[derive(Deserialize)]
+struct RuleFile {
+    rules: Vec<RuleSpec>,
+}
+
+#[derive(Deserialize)]
+struct RuleSpec {
+    name: String,
+    #[serde(default)]
+    description: String,
+    from: String,
+    to: String,
+}
+
+impl Rule {
+    /// Creates a rule replacing code that matches `from` with `to`.
+    ///
+    /// # Errors
+    /// If `from` is not a valid pattern.
+    pub fn new(name: &str, description: &str, from: &str, to: &str) -> Result<Rule, PawxError> {
+        Ok(Rule {
+            name: name.to_string(),
+            description: description.to_string(),
+            pattern: Pattern::parse(from)?,
+            replacement: to.to_string(),
+        })
+    }
+
+    /// Applies the rule to `source`, returning the new source and where
+    /// each replacement started.
+    fn apply(&self, source: &str) -> Result<(String, Vec<Span>), PawxError> {
+        let tokens = lexer::tokenize(source)?;
+        let stmts = parser::parse(tokens.clone())?;
+        let tree = grep::to_json(&stmts);
+
+        let mut found = Vec::new();
+        self.pattern.search(&tree, &mut found);
+
+        let text = Text::new(source, &tokens);
+        let mut located: Vec<(Range<usize>, &Bindings)> =
+            found.iter().filter_map(|f| Some((text.extent(f.node)?, &f.bindings))).collect();
+
+        // Outermost first; a match inside code already being replaced is
+        // left for the next run.
+        located.sort_by(|a, b| a.0.start.cmp(&b.0.start).then(b.0.end.cmp(&a.0.end)));
+
+        let mut output = String::new();
+        let mut spans = Vec::new();
+        let mut pos = 0;
+
+        for (range, bindings) in located {
+            if range.start < pos {
+                continue;
+            }
+
+            let replacement = self.fill(&text, bindings)?;
+            if replacement.chars().eq(text.chars[range.clone()].iter().copied()) {
+                continue;
+            }
+
+            output.extend(&text.chars[pos..range.start]);
+            output.push_str(&replacement);
+            spans.push(text.span_at(range.start));
+            pos = range.end;
+        }
+
+        output.extend(&text.chars[pos..]);
+
+        if let Err(e) = parser::parse(lexer::tokenize(&output)?) {
+            return Err(PawxError::syntax_error(
+                format!("fix '{}' would leave invalid code: {}", self.name, e.message),
+                e.span,
+            ));
+        }
+
+        Ok((output, spans))
+    }
+
+    /// The replacement with each `$name` swapped for the text it matched.
+    fn fill(&self, text: &Text, bindings: &Bindings) -> Result<String, PawxError> {
+        let mut out = String::new();
+        let mut chars = self.replacement.chars().peekable();
+        let mut quote: Option<char> = None;
+
+        while let Some(c) = chars.next() {
+            match (quote, c) {
+                (Some(q), _) if c == q => quote = None,
+                (None, '"' | '\'') => quote = Some(c),
+                (None, '$') => {
+                    let many = chars.next_if_eq(&'$').is_some();
+                    let mut name = String::new();
+                    while let Some(next) = chars.next_if(|n| n.is_alphanumeric() || *n == '_') {
+                        name.push(next);
+                    }
+
+                    let bound = bindings.get(&name).ok_or_else(|| {
+                        let sigil = if many { "$$" } else { "$" };
+                        PawxError::syntax_error(
+                            format!("fix '{}' uses {}{}, which its pattern does not bind", self.name, sigil, name),
+                            Span::new(1, 0),
+                        )
+                    })?;
+
+                    out.push_str(&text.source_of(&bound.nodes).ok_or_else(|| {
+                        PawxError::syntax_error(
+                            format!("fix '{}' could not find the code ${} matched", self.name, name),
+                            Span::new(1, 0),
+                        )
+                    })?);
+                    continue;
+                }
+                _ => {}
+            }
+            out.push(c);
+        }
+
+        Ok(out)
+    }
+}
+
+/// The fixes built into PAWX.
+pub fn builtin_rules() -> Vec<Rule> {
+    BUILTIN
+        .iter()
+        .map(|(name, description, from, to)| {
+            Rule::new(name, description, from, to).expect("built-in fix patterns are valid")
+        })
+        .collect()
+}
+
+/// Reads a rules file.
+///
+/// # Errors
+/// If the file cannot be read, is not a rules file, or holds an invalid
+/// pattern.
+pub fn load_rules(path: &Path) -> Result<Vec<Rule>, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let file: RuleFile = serde_json::from_str(&text).map_err(|e| format!("{}: {}", path.display(), e))?;
+
+    file.rules
+        .iter()
+        .map(|spec| {
+            Rule::new(&spec.name, &spec.description, &spec.from, &spec.to)
+                .map_err(|e| format!("{}: rule '{}': {}", path.display(), spec.name, e.message))
+        })
+        .collect()
+}
+
+/// The rules shipped by packages installed under `root`, named
+/// `<package>/<rule>`.
+///
+/// # Errors
+/// If a package's manifest or rules file cannot be read.
+pub fn package_rules(root: &Path) -> Result<Vec<Rule>, String> {
+    let mut rules = Vec::new();
+
+    for (package, dir) in lockfile::installed_packages(root)? {
+        let manifest = dir.join(MANIFEST_FILE);
+        if !manifest.is_file() {
+            continue;
+        }
+
+        if let Some(fixes) = Manifest::load(&manifest)?.fixes {
+            for mut rule in load_rules(&dir.join(fixes))? {
+                rule.name = format!("{}/{}", package, rule.name);
+                rules.push(rule);
+            }
+        }
+    }
+
+    Ok(rules)
+}
+
+/// Applies `rules` to `source` one after another, returning the new source
+/// and the edits made.
+///
+/// # Errors
+/// If `source` does not parse, or a rule would leave code that does not.
+pub fn apply(rules: &[Rule], source: &str) -> Result<(String, Vec<Edit>), PawxError> {
+    let mut source = source.to_string();
+    let mut edits = Vec::new();
+
+    for rule in rules {
+        let (rewritten, spans) = rule.apply(&source)?;
+        edits.extend(spans.into_iter().map(|span| Edit { rule: rule.name.clone(), span }));
+        source = rewritten;
+    }
+
+    Ok((source, edits))
+}
+
+/* ============================================================================
+ * Locating Matches in the Source
+ * ============================================================================
+ */
+
+/// Source text with the tokens lexed from it.
+struct Text<'t> {
+    chars: Vec<char>,
+    line_starts: Vec<usize>,
+    tokens: &'t [Token],
+}
+
+impl<'t> Text<'t> {
+    fn new(source: &str, tokens: &'t [Token]) -> Self {
+        let chars: Vec<char> = source.chars().collect();
+        let mut line_starts = vec![0];
+        line_starts.extend(chars.iter().enumerate().filter(|(_, c)| **c == '\n').map(|(i, _)| i + 1));
+
+        Self { chars, line_starts, tokens }
+    }
+
+    fn offset(&self, span: Span) -> usize {
+        self.line_starts[span.line - 1] + span.column
+    }
+
+    fn span_at(&self, offset: usize) -> Span {
+        let line = self.line_starts.partition_point(|start| *start <= offset);
+        Span::new(line, offset - self.line_starts[line - 1])
+    }
+
+    fn token_end(&self, token: &Token) -> usize {
+        let len = token.lexeme.chars().count();
+        // The lexeme leaves out the quotes.
+        self.offset(token.span) + if token.kind == TokenKind::String { len + 2 } else { len }
+    }
+
+    fn token_at(&self, (line, column): (usize, usize)) -> Option<usize> {
+        self.tokens.iter().position(|t| t.span.line == line && t.span.column == column)
+    }
+
+    /// The source range of a statement or expression node.
+    ///
+    /// Nodes only record where some of their tokens start, so this finds
+    /// the shortest run of tokens from the node's first one that parses
+    /// back into the same node (plus a statement's closing `;`).
+    fn extent(&self, node: &Json) -> Option<Range<usize>> {
+        let (first, last) = grep::bounds(node)?;
+        let first = self.token_at(first)?;
+        let last = self.token_at(last)?;
+        let want = grep::strip_spans(node);
+
+        // The final token is `Eof`.
+        let end = (last + 1..self.tokens.len()).find(|end| parses_to(&self.tokens[first..*end], &want))?;
+        let end = match self.tokens.get(end) {
+            Some(next) if next.lexeme == ";" && parses_to(&self.tokens[first..=end], &want) => end + 1,
+            _ => end,
+        };
+
+        Some(self.offset(self.tokens[first].span)..self.token_end(&self.tokens[end - 1]))
+    }
+
+    /// The original text of what a metavariable matched.
+    fn source_of(&self, nodes: &[&Json]) -> Option<String> {
+        match nodes {
+            [] => Some(String::new()),
+            [Json::String(name)] => Some(name.clone()),
+            _ => {
+                let ranges: Option<Vec<Range<usize>>> = nodes.iter().map(|node| self.extent(node)).collect();
+                if let Some(ranges) = ranges {
+                    let range = ranges[0].start..ranges[ranges.len() - 1].end;
+                    return Some(self.chars[range].iter().collect());
+                }
+
+                // Parameters are not expressions; rebuild them instead.
+                let params: Option<Vec<String>> = nodes.iter().map(|node| self.param_source(node)).collect();
+                params.map(|params| params.join(", "))
+            }
+        }
+    }
+
+    fn param_source(&self, node: &Json) -> Option<String> {
+        let mut text = node.get("name")?.as_str()?.to_string();
+
+        if let Some(annotation) = node.get("type_annotation")?.as_str() {
+            text.push_str(": ");
+            text.push_str(annotation);
+        }
+
+        let default = node.get("default")?;
+        if !default.is_null() {
+            text.push_str(" = ");
+            text.extend(&self.chars[self.extent(default)?]);
+        }
+
+        Some(text)
+    }
+}
+
+/// Whether `tokens`, and nothing more, parse as an expression or statement
+/// equal to `want`.
+fn parses_to(tokens: &[Token], want: &Json) -> bool {
+    let Some(last) = tokens.last() else {
+        return false;
+    };
+
+    let eof = Token { kind: TokenKind::Eof, lexeme: String::new(), span: last.span };
+    let parser = || Parser { tokens: tokens.iter().cloned().chain([eof.clone()]).collect(), current: 0, in_static: false };
+
+    let mut p = parser();
+    if p.expression().is_ok_and(|expr| p.current == tokens.len() && grep::strip_spans(&grep::to_json(&expr)) == *want) {
+        return true;
+    }
+
+    let mut p = parser();
+    p.statement().is_ok_and(|stmt| p.current == tokens.len() && grep::strip_spans(&grep::to_json(&stmt)) == *want)
+}
@@ -43,6 +43,7 @@ use std::collections::HashMap;
 use std::rc::Rc;
 
 use crate::array_proto::create_array_proto;
+use crate::interpreter::calls::raise_error;
 use crate::runtime::{NativeFn, PawxValue};
 
 /// Creates and returns the global `String` object for the PAWX runtime.
@@ -94,17 +95,17 @@ pub fn create_string_global() -> PawxValue {
 /// ```
 fn string_split(args: Vec<PawxValue>) -> PawxValue {
     if args.len() != 2 {
-        panic!("String.split(string, delimiter) requires 2 arguments");
+        return raise_error("String.split(string, delimiter) requires 2 arguments");
     }
 
     let string = match &args[0] {
         PawxValue::String(s) => s.clone(),
-        _ => panic!("String.split() expects a string as the first argument"),
+        _ => return raise_error("String.split() expects a string as the first argument"),
     };
 
     let delimiter = match &args[1] {
         PawxValue::String(s) => s.clone(),
-        _ => panic!("String.split() expects a string as the delimiter"),
+        _ => return raise_error("String.split() expects a string as the delimiter"),
     };
 
     let parts: Vec<PawxValue> = string
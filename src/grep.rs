@@ -0,0 +1,416 @@
+/*
+ * ==========================================================================
+ * PAWX - Code with Claws!
+ * ==========================================================================
+ *
+ * Structural Search (`pawx grep`)
+ * -------------------------------
+ * Finds code by its shape rather than its text. A pattern is ordinary PAWX
+ * with metavariables in place of the parts that may vary:
+ *
+ * ```text
+ * pawx grep 'purr $name -> ($params) -> { $$ }'
+ * pawx grep 'Json.parse($x)' src tests
+ * pawx grep '$a == $a'
+ * ```
+ *
+ * - `$name` matches any single expression, name or parameter. Using the
+ *   same metavariable twice requires both places to match the same code.
+ * - `$$` (or `$$name`) matches any number of arguments, parameters or
+ *   statements, including none.
+ * - `$_` matches anything without binding it.
+ * - A metavariable alone in a list (`($params)`, `f($args)`, `{ $body }`)
+ *   matches the whole list.
+ *
+ * Patterns and files go through the same lexer and parser as the
+ * interpreter, then are compared as syntax trees, so whitespace, comments
+ * and line breaks never affect a match.
+ *
+ * --------------------------------------------------------------------------
+ * Author:   Sam Wilcox
+ * Email:    sam@pawx-lang.com
+ * Website:  https://www.pawx-lang.com
+ * Github:   https://github.com/samwilcox/pawx
+ *
+ * License:
+ * This file is part of the PAWX programming language project.
+ *
+ * PAWX is dual-licensed under the terms of:
+ *   - The MIT License
+ *   - The Apache License, Version 2.0
+ *
+ * You may choose either license to govern your use of this software.
+ * Full license text available at:
+ *    https://license.pawx-lang.com
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under these licenses is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *
+ * ==========================================================================
+ */
+
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde_json::Value as Json;
+
+use crate::ast::Stmt;
+use crate::error::PawxError;
+use crate::lexer;
+use crate::lockfile::MODULES_DIR;
+use crate::parser;
+use crate::span::Span;
+
+/// Identifier prefix a `$name` metavariable is rewritten to before parsing.
+const META: &str = "__pawx_meta_";
+
+/// Identifier prefix a `$$` / `$$name` metavariable is rewritten to.
+const META_MANY: &str = "__pawx_metas_";
+
+/// Optional annotations a pattern may leave out and still match code that
+/// has them: `purr $f -> ($$) -> { $$ }` also finds `-> Number` functions.
+const OPTIONAL_KEYS: &[&str] = &["return_type", "type_annotation"];
+
+/// Directories never searched when walking a project.
+const SKIPPED_DIRS: &[&str] = &[MODULES_DIR, "target"];
+
+/// A compiled structural search pattern.
+#[derive(Debug, Clone)]
+pub struct Pattern {
+    /// The pattern's syntax tree, serialized without spans.
+    tree: Json,
+}
+
+/// What a metavariable stands for.
+enum Meta<'a> {
+    /// `$name`: exactly one node.
+    One(&'a str),
+    /// `$$name`: any run of list elements.
+    Many(&'a str),
+}
+
+/// What a metavariable matched: the code, without spans, for comparing
+/// against other uses of the same metavariable, and the matched nodes.
+#[derive(Debug, Clone)]
+pub(crate) struct Bound<'a> {
+    value: Json,
+    pub nodes: Vec<&'a Json>,
+}
+
+pub(crate) type Bindings<'a> = HashMap<String, Bound<'a>>;
+
+/// One match in a syntax tree serialized with [`to_json`].
+pub(crate) struct Found<'a> {
+    pub node: &'a Json,
+    pub bindings: Bindings<'a>,
+}
+
+impl Pattern {
+    /// Parses a pattern: one PAWX statement or expression, with
+    /// metavariables.
+    ///
+    /// # Errors
+    /// If the pattern is not valid PAWX, holds more than one statement, or
+    /// is nothing but a metavariable.
+    pub fn parse(pattern: &str) -> Result<Self, PawxError> {
+        let tokens = lexer::tokenize(&desugar(pattern))?;
+        let mut stmts = parser::parse(tokens)?;
+
+        if stmts.len() != 1 {
+            return Err(PawxError::syntax_error(
+                format!("a pattern must be a single statement or expression, found {}", stmts.len()),
+                Span::new(1, 0),
+            ));
+        }
+
+        let tree = match stmts.remove(0) {
+            Stmt::Expression(expr) => to_json(&expr),
+            stmt => to_json(&stmt),
+        };
+
+        if meta(&tree).is_some() {
+            return Err(PawxError::syntax_error(
+                "a pattern must contain some code, not only a metavariable",
+                Span::new(1, 0),
+            ));
+        }
+
+        Ok(Self { tree: strip_spans(&tree) })
+    }
+
+    /// Returns where each match in `source` starts, in source order.
+    ///
+    /// # Errors
+    /// If `source` does not lex or parse.
+    pub fn find(&self, source: &str) -> Result<Vec<Span>, PawxError> {
+        let stmts = parser::parse(lexer::tokenize(source)?)?;
+        let tree = to_json(&stmts);
+
+        let mut found = Vec::new();
+        self.search(&tree, &mut found);
+
+        let starts: BTreeSet<(usize, usize)> = found.iter().filter_map(|f| start_of(f.node)).collect();
+        Ok(starts.into_iter().map(|(line, column)| Span::new(line, column)).collect())
+    }
+
+    /// Collects every match inside `node`, outer matches before the ones
+    /// nested in them.
+    pub(crate) fn search<'a>(&self, node: &'a Json, found: &mut Vec<Found<'a>>) {
+        match node {
+            Json::Object(map) => {
+                let mut bindings = Bindings::new();
+                if same_variant(&self.tree, node) && matches(&self.tree, node, &mut bindings) {
+                    found.push(Found { node, bindings });
+                }
+                for child in map.values() {
+                    self.search(child, found);
+                }
+            }
+            Json::Array(items) => {
+                for item in items {
+                    self.search(item, found);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Rewrites `$name`, `$$name` and `$$` into identifiers the parser accepts.
+/// String literals are left alone.
+fn desugar(pattern: &str) -> String {
+    let mut out = String::with_capacity(pattern.len());
+    let mut chars = pattern.chars().peekable();
+    let mut quote: Option<char> = None;
+
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(q), _) if c == q => quote = None,
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '$') => {
+                let many = chars.next_if_eq(&'$').is_some();
+                out.push_str(if many { META_MANY } else { META });
+                while let Some(next) = chars.next_if(|n| n.is_alphanumeric() || *n == '_') {
+                    out.push(next);
+                }
+                continue;
+            }
+            _ => {}
+        }
+        out.push(c);
+    }
+
+    out
+}
+
+pub(crate) fn to_json<T: serde::Serialize>(node: &T) -> Json {
+    serde_json::to_value(node).unwrap_or(Json::Null)
+}
+
+fn is_span_key(key: &str) -> bool {
+    key == "span"
+}
+
+pub(crate) fn strip_spans(node: &Json) -> Json {
+    match node {
+        Json::Object(map) => Json::Object(
+            map.iter()
+                .filter(|(key, _)| !is_span_key(key))
+                .map(|(key, value)| (key.clone(), strip_spans(value)))
+                .collect(),
+        ),
+        Json::Array(items) => Json::Array(items.iter().map(strip_spans).collect()),
+        other => other.clone(),
+    }
+}
+
+/// The metavariable `node` stands for, if it is one: a bare name, an
+/// identifier expression, its expression statement, or a parameter with no
+/// default or type.
+fn meta(node: &Json) -> Option<Meta<'_>> {
+    match node {
+        Json::String(name) => name
+            .strip_prefix(META_MANY)
+            .map(Meta::Many)
+            .or_else(|| name.strip_prefix(META).map(Meta::One)),
+        Json::Object(map) if map.len() == 1 => match map.iter().next() {
+            Some((variant, inner)) if variant == "Identifier" => meta(inner.get("name")?),
+            Some((variant, inner)) if variant == "Expression" => meta(inner),
+            _ => None,
+        },
+        Json::Object(map) if map.contains_key("default") => {
+            let bare = map.get("default").is_some_and(Json::is_null)
+                && map.get("type_annotation").is_none_or(Json::is_null);
+            if bare { meta(map.get("name")?) } else { None }
+        }
+        _ => None,
+    }
+}
+
+/// Whether two serialized enum values are the same variant.
+fn same_variant(pattern: &Json, node: &Json) -> bool {
+    match (pattern, node) {
+        (Json::Object(p), Json::Object(n)) => p.len() == 1 && n.len() == 1 && p.keys().eq(n.keys()),
+        _ => false,
+    }
+}
+
+/// Records what `name` matched, or checks it against an earlier binding.
+fn bind<'a>(name: &str, value: Json, nodes: Vec<&'a Json>, bindings: &mut Bindings<'a>) -> bool {
+    if name.is_empty() || name == "_" {
+        return true;
+    }
+
+    // `purr $f`, `($x)` and `$f(1)` bind a bare name, a parameter and an
+    // identifier; reduce the last two to the name so all three can agree.
+    let plain_param = value.get("default").is_some_and(Json::is_null)
+        && value.get("type_annotation").is_some_and(Json::is_null);
+    let plain_name = if plain_param {
+        value.get("name")
+    } else {
+        value.get("Identifier").and_then(|inner| inner.get("name"))
+    };
+    let value = match plain_name {
+        Some(name) => name.clone(),
+        None => value,
+    };
+
+    match bindings.get(name) {
+        Some(bound) => bound.value == value,
+        None => {
+            bindings.insert(name.to_string(), Bound { value, nodes });
+            true
+        }
+    }
+}
+
+fn matches<'a>(pattern: &Json, node: &'a Json, bindings: &mut Bindings<'a>) -> bool {
+    if let Some(Meta::One(name) | Meta::Many(name)) = meta(pattern) {
+        return bind(name, strip_spans(node), vec![node], bindings);
+    }
+
+    match (pattern, node) {
+        (Json::Object(p), Json::Object(n)) => {
+            n.keys().filter(|key| !is_span_key(key)).count() == p.len()
+                && p.iter().all(|(key, value)| {
+                    (value.is_null() && OPTIONAL_KEYS.contains(&key.as_str()))
+                        || n.get(key).is_some_and(|child| matches(value, child, bindings))
+                })
+        }
+        (Json::Array(p), Json::Array(n)) => matches_list(p, n, bindings),
+        _ => pattern == node,
+    }
+}
+
+fn matches_list<'a>(pattern: &[Json], nodes: &'a [Json], bindings: &mut Bindings<'a>) -> bool {
+    if let [only] = pattern {
+        if let Some(Meta::One(name)) = meta(only) {
+            let value = match nodes {
+                [node] => strip_spans(node),
+                _ => Json::Array(nodes.iter().map(strip_spans).collect()),
+            };
+            return bind(name, value, nodes.iter().collect(), bindings);
+        }
+    }
+
+    let Some((first, rest)) = pattern.split_first() else {
+        return nodes.is_empty();
+    };
+
+    // Each attempt works on a copy so a failed one leaves no bindings behind.
+    let attempt = |bindings: &mut Bindings<'a>, f: &dyn Fn(&mut Bindings<'a>) -> bool| {
+        let mut trial = bindings.clone();
+        let ok = f(&mut trial);
+        if ok {
+            *bindings = trial;
+        }
+        ok
+    };
+
+    match meta(first) {
+        Some(Meta::Many(name)) => (0..=nodes.len()).any(|taken| {
+            attempt(bindings, &|trial| {
+                let run = Json::Array(nodes[..taken].iter().map(strip_spans).collect());
+                bind(name, run, nodes[..taken].iter().collect(), trial) && matches_list(rest, &nodes[taken..], trial)
+            })
+        }),
+        _ => nodes.split_first().is_some_and(|(node, others)| {
+            attempt(bindings, &|trial| matches(first, node, trial) && matches_list(rest, others, trial))
+        }),
+    }
+}
+
+/// The earliest `(line, column)` of any span inside `node`.
+pub(crate) fn start_of(node: &Json) -> Option<(usize, usize)> {
+    bounds(node).map(|(first, _)| first)
+}
+
+/// A `(line, column)` position.
+type Position = (usize, usize);
+
+/// The earliest and latest `(line, column)` of any span inside `node`.
+pub(crate) fn bounds(node: &Json) -> Option<(Position, Position)> {
+    let mut bounds = None;
+    collect_bounds(node, &mut bounds);
+    bounds
+}
+
+fn collect_bounds(node: &Json, bounds: &mut Option<(Position, Position)>) {
+    match node {
+        Json::Object(map) => {
+            for (key, value) in map {
+                if is_span_key(key) {
+                    let line = value.get("line").and_then(Json::as_u64);
+                    let column = value.get("column").and_then(Json::as_u64);
+                    if let (Some(line), Some(column)) = (line, column) {
+                        let here = (line as usize, column as usize);
+                        *bounds = match *bounds {
+                            Some((first, last)) => Some((first.min(here), last.max(here))),
+                            None => Some((here, here)),
+                        };
+                    }
+                } else {
+                    collect_bounds(value, bounds);
+                }
+            }
+        }
+        Json::Array(items) => items.iter().for_each(|item| collect_bounds(item, bounds)),
+        _ => {}
+    }
+}
+
+/// Every `.px` file under `paths`, sorted. Directories are searched
+/// recursively, skipping hidden ones (such as the AST cache), `paw_modules`
+/// and `target`; files named directly are always included.
+pub fn source_files(paths: &[PathBuf]) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    for path in paths {
+        if path.is_dir() {
+            walk(path, &mut files);
+        } else {
+            files.push(path.clone());
+        }
+    }
+    files.sort();
+    files.dedup();
+    files
+}
+
+fn walk(dir: &Path, out: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+
+    for path in entries.flatten().map(|entry| entry.path()) {
+        let name = path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+        if path.is_dir() {
+            if !name.starts_with('.') && !SKIPPED_DIRS.contains(&name.as_str()) {
+                walk(&path, out);
+            }
+        } else if path.extension().is_some_and(|ext| ext == "px") {
+            out.push(path);
+        }
+    }
+}
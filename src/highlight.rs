@@ -0,0 +1,385 @@
+/*
+ * ==========================================================================
+ * PAWX - Code with Claws!
+ * ==========================================================================
+ *
+ * Syntax Highlighting (`pawx highlight`)
+ * --------------------------------------
+ * Highlights PAWX source with the real lexer, so the colors always agree
+ * with how the interpreter reads the code:
+ *
+ * ```text
+ * pawx highlight app.px --ansi > /dev/tty
+ * pawx highlight app.px --html > app.html
+ * ```
+ *
+ * HTML output is a `<pre class="pawx">` block whose spans carry
+ * `pawx-<class>` CSS classes (`pawx-keyword`, `pawx-string`, ...).
+ *
+ * The same tables (`lexer::keywords`, `lexer::operators` and the built-in
+ * module list) generate grammars for editors, so they stay in sync with
+ * the lexer instead of being maintained by hand:
+ *
+ * ```text
+ * pawx highlight --grammar textmate               > pawx.tmLanguage.json
+ * pawx highlight --grammar tree-sitter            > grammar.js
+ * pawx highlight --grammar tree-sitter-highlights > queries/highlights.scm
+ * ```
+ *
+ * The Tree-sitter grammar is lexical: it recognizes tokens, not the full
+ * syntax tree, which is all highlighting needs.
+ *
+ * --------------------------------------------------------------------------
+ * Author:   Sam Wilcox
+ * Email:    sam@pawx-lang.com
+ * Website:  https://www.pawx-lang.com
+ * Github:   https://github.com/samwilcox/pawx
+ *
+ * License:
+ * This file is part of the PAWX programming language project.
+ *
+ * PAWX is dual-licensed under the terms of:
+ *   - The MIT License
+ *   - The Apache License, Version 2.0
+ *
+ * You may choose either license to govern your use of this software.
+ * Full license text available at:
+ *    https://license.pawx-lang.com
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under these licenses is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *
+ * ==========================================================================
+ */
+
+use serde_json::json;
+
+use crate::error::PawxError;
+use crate::interpreter::prelude::BUILTIN_MODULES;
+use crate::lexer::keywords::{CONSTANTS, KEYWORDS};
+use crate::lexer::operators::{OPERATORS, PUNCTUATION};
+use crate::lexer::token::{Token, TokenKind};
+
+/// Globals highlighted as built-ins besides the built-in modules.
+const BUILTIN_FUNCTIONS: &[&str] = &[
+    "meow",
+    "setTimeout",
+    "setInterval",
+    "clearTimeout",
+    "clearInterval",
+    "__file",
+    "__dir",
+    "module",
+];
+
+/// Highlighting category of a piece of source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Class {
+    Keyword,
+    Constant,
+    String,
+    Number,
+    Comment,
+    Operator,
+    Punctuation,
+    Function,
+    Builtin,
+    Identifier,
+
+    /// Whitespace between tokens.
+    Text,
+}
+
+impl Class {
+    /// CSS class name suffix and Tree-sitter node name.
+    pub fn name(self) -> &'static str {
+        match self {
+            Class::Keyword => "keyword",
+            Class::Constant => "constant",
+            Class::String => "string",
+            Class::Number => "number",
+            Class::Comment => "comment",
+            Class::Operator => "operator",
+            Class::Punctuation => "punctuation",
+            Class::Function => "function",
+            Class::Builtin => "builtin",
+            Class::Identifier => "identifier",
+            Class::Text => "text",
+        }
+    }
+
+    /// ANSI SGR color code, `None` for the terminal's default color.
+    fn ansi(self) -> Option<&'static str> {
+        match self {
+            Class::Keyword => Some("35"),
+            Class::Constant | Class::Number => Some("33"),
+            Class::String => Some("32"),
+            Class::Comment => Some("90"),
+            Class::Function => Some("34"),
+            Class::Builtin => Some("36"),
+            Class::Operator | Class::Punctuation | Class::Identifier | Class::Text => None,
+        }
+    }
+}
+
+/* ============================================================================
+ * Classifying Source
+ * ============================================================================
+ */
+
+/// Splits `source` into classified pieces that, joined, reproduce it
+/// exactly, comments and whitespace included.
+///
+/// # Errors
+/// A syntax error if the source cannot be tokenized (an unterminated
+/// string or block comment).
+pub fn classify(source: &str) -> Result<Vec<(Class, String)>, PawxError> {
+    let tokens = crate::lexer::tokenize(source)?;
+    let chars: Vec<char> = source.chars().collect();
+
+    // Tokens carry a line and column; find where each line starts.
+    let mut line_starts = vec![0];
+    line_starts.extend(chars.iter().enumerate().filter(|(_, c)| **c == '\n').map(|(i, _)| i + 1));
+
+    let mut pieces = Vec::new();
+    let mut pos = 0;
+
+    for (i, token) in tokens.iter().enumerate() {
+        if token.kind == TokenKind::Eof {
+            break;
+        }
+
+        let start = line_starts[token.span.line - 1] + token.span.column;
+        let len = match token.kind {
+            // The lexeme leaves out the quotes.
+            TokenKind::String => token.lexeme.chars().count() + 2,
+            _ => token.lexeme.chars().count(),
+        };
+
+        push_gap(&mut pieces, &chars[pos..start]);
+        pieces.push((class_of(&tokens, i), chars[start..start + len].iter().collect()));
+        pos = start + len;
+    }
+
+    push_gap(&mut pieces, &chars[pos..]);
+    Ok(pieces)
+}
+
+/// Classifies the token at `index`, looking at its neighbours to pick out
+/// function names.
+fn class_of(tokens: &[Token], index: usize) -> Class {
+    let token = &tokens[index];
+    let lexeme = token.lexeme.as_str();
+
+    match token.kind {
+        TokenKind::Keyword if CONSTANTS.contains(&lexeme) => Class::Constant,
+        TokenKind::Keyword => Class::Keyword,
+        TokenKind::String => Class::String,
+        TokenKind::Number => Class::Number,
+        TokenKind::Symbol if PUNCTUATION.contains(&lexeme) => Class::Punctuation,
+        TokenKind::Symbol => Class::Operator,
+        TokenKind::Eof => Class::Text,
+
+        TokenKind::Identifier => {
+            let declared = index > 0 && tokens[index - 1].lexeme == "purr";
+            let called = tokens.get(index + 1).is_some_and(|next| next.lexeme == "(");
+
+            if BUILTIN_MODULES.contains(&lexeme) || BUILTIN_FUNCTIONS.contains(&lexeme) {
+                Class::Builtin
+            } else if declared || called {
+                Class::Function
+            } else {
+                Class::Identifier
+            }
+        }
+    }
+}
+
+/// Pushes the text between two tokens, separating comments from
+/// whitespace.
+fn push_gap(pieces: &mut Vec<(Class, String)>, gap: &[char]) {
+    let mut i = 0;
+    let mut text = String::new();
+
+    while i < gap.len() {
+        let end = match (gap[i], gap.get(i + 1)) {
+            ('/', Some('/')) => gap[i..].iter().position(|c| *c == '\n').map_or(gap.len(), |n| i + n),
+            ('/', Some('*')) => gap[i + 2..]
+                .windows(2)
+                .position(|w| w == ['*', '/'])
+                .map_or(gap.len(), |n| i + 2 + n + 2),
+            (c, _) => {
+                text.push(c);
+                i += 1;
+                continue;
+            }
+        };
+
+        if !text.is_empty() {
+            pieces.push((Class::Text, std::mem::take(&mut text)));
+        }
+        pieces.push((Class::Comment, gap[i..end].iter().collect()));
+        i = end;
+    }
+
+    if !text.is_empty() {
+        pieces.push((Class::Text, text));
+    }
+}
+
+/* ============================================================================
+ * Output Formats
+ * ============================================================================
+ */
+
+/// Renders `source` with ANSI color escapes for a terminal.
+pub fn to_ansi(source: &str) -> Result<String, PawxError> {
+    let mut out = String::new();
+
+    for (class, text) in classify(source)? {
+        match class.ansi() {
+            Some(code) => out.push_str(&format!("\x1b[{}m{}\x1b[0m", code, text)),
+            None => out.push_str(&text),
+        }
+    }
+
+    Ok(out)
+}
+
+/// Renders `source` as an HTML `<pre>` block with `pawx-<class>` spans.
+pub fn to_html(source: &str) -> Result<String, PawxError> {
+    let mut out = String::from("<pre class=\"pawx\"><code>");
+
+    for (class, text) in classify(source)? {
+        let text = escape_html(&text);
+        match class {
+            Class::Text | Class::Identifier => out.push_str(&text),
+            _ => out.push_str(&format!("<span class=\"pawx-{}\">{}</span>", class.name(), text)),
+        }
+    }
+
+    out.push_str("</code></pre>\n");
+    Ok(out)
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/* ============================================================================
+ * Editor Grammars
+ * ============================================================================
+ */
+
+/// Keywords that introduce syntax (everything but the constants).
+fn syntax_keywords() -> Vec<&'static str> {
+    KEYWORDS.iter().copied().filter(|k| !CONSTANTS.contains(k)).collect()
+}
+
+fn builtins() -> Vec<&'static str> {
+    BUILTIN_MODULES.iter().chain(BUILTIN_FUNCTIONS).copied().collect()
+}
+
+/// `\b(a|b|c)\b` for a list of words.
+fn word_pattern(words: &[&str]) -> String {
+    format!("\\b({})\\b", words.join("|"))
+}
+
+/// Escapes a symbol for use in a regular expression.
+fn regex_escape(symbol: &str) -> String {
+    symbol
+        .chars()
+        .map(|c| if c.is_ascii_punctuation() { format!("\\{}", c) } else { c.to_string() })
+        .collect()
+}
+
+/// A TextMate grammar (`.tmLanguage.json`), as used by VS Code, Sublime
+/// Text and GitHub.
+pub fn textmate_grammar() -> serde_json::Value {
+    let operators: Vec<String> = OPERATORS.iter().map(|op| regex_escape(op)).collect();
+
+    json!({
+        "$schema": "https://raw.githubusercontent.com/martinring/tmlanguage/master/tmlanguage.json",
+        "name": "PAWX",
+        "scopeName": "source.pawx",
+        "fileTypes": ["px"],
+        "patterns": [
+            { "name": "comment.line.double-slash.pawx", "match": "//.*$" },
+            { "name": "comment.block.pawx", "begin": "/\\*", "end": "\\*/" },
+            { "name": "string.quoted.double.pawx", "begin": "\"", "end": "\"" },
+            { "name": "string.quoted.single.pawx", "begin": "'", "end": "'" },
+            { "name": "constant.numeric.pawx", "match": "\\b\\d+(\\.\\d+)?\\b" },
+            { "name": "constant.language.pawx", "match": word_pattern(CONSTANTS) },
+            {
+                "match": "\\b(purr)\\s+([A-Za-z_][A-Za-z0-9_]*)",
+                "captures": {
+                    "1": { "name": "keyword.control.pawx" },
+                    "2": { "name": "entity.name.function.pawx" }
+                }
+            },
+            { "name": "keyword.control.pawx", "match": word_pattern(&syntax_keywords()) },
+            { "name": "support.class.pawx", "match": word_pattern(&builtins()) },
+            { "name": "entity.name.function.pawx", "match": "\\b[A-Za-z_][A-Za-z0-9_]*(?=\\s*\\()" },
+            { "name": "keyword.operator.pawx", "match": operators.join("|") },
+            { "name": "punctuation.pawx", "match": format!("[{}]", PUNCTUATION.iter().map(|p| regex_escape(p)).collect::<String>()) }
+        ]
+    })
+}
+
+/// Quotes `text` as a JavaScript string literal.
+fn js_string(text: &str) -> String {
+    serde_json::Value::from(text).to_string()
+}
+
+fn js_choice(items: &[&str]) -> String {
+    let items: Vec<String> = items.iter().map(|item| js_string(item)).collect();
+    format!("choice({})", items.join(", "))
+}
+
+/// A lexical Tree-sitter grammar (`grammar.js`).
+pub fn tree_sitter_grammar() -> String {
+    let mut out = String::new();
+
+    out.push_str("// Generated by `pawx highlight --grammar tree-sitter`; do not edit.\n");
+    out.push_str("module.exports = grammar({\n");
+    out.push_str("  name: 'pawx',\n");
+    out.push_str("  extras: $ => [/\\s/, $.comment],\n");
+    out.push_str("  word: $ => $.identifier,\n");
+    out.push_str("  rules: {\n");
+    out.push_str("    source_file: $ => repeat($._token),\n");
+    out.push_str("    _token: $ => choice($.keyword, $.constant, $.number, $.string, $.identifier, $.operator, $.punctuation),\n");
+    out.push_str(&format!("    keyword: $ => {},\n", js_choice(&syntax_keywords())));
+    out.push_str(&format!("    constant: $ => {},\n", js_choice(CONSTANTS)));
+    out.push_str("    number: $ => /\\d+(\\.\\d+)?/,\n");
+    out.push_str("    string: $ => choice(seq('\"', /[^\"]*/, '\"'), seq(\"'\", /[^']*/, \"'\")),\n");
+    out.push_str("    identifier: $ => /[A-Za-z_][A-Za-z0-9_]*/,\n");
+    out.push_str(&format!("    operator: $ => {},\n", js_choice(OPERATORS)));
+    out.push_str(&format!("    punctuation: $ => {},\n", js_choice(PUNCTUATION)));
+    out.push_str("    comment: $ => token(choice(seq('//', /.*/), seq('/*', /[^*]*\\*+([^/*][^*]*\\*+)*/, '/'))),\n");
+    out.push_str("  }\n");
+    out.push_str("});\n");
+    out
+}
+
+/// Highlight queries (`queries/highlights.scm`) for [`tree_sitter_grammar`].
+pub fn tree_sitter_highlights() -> String {
+    let builtins: Vec<String> = builtins().iter().map(|b| js_string(b)).collect();
+
+    format!(
+        "; Generated by `pawx highlight --grammar tree-sitter-highlights`; do not edit.\n\
+         (keyword) @keyword\n\
+         (constant) @constant.builtin\n\
+         (number) @number\n\
+         (string) @string\n\
+         (comment) @comment\n\
+         (operator) @operator\n\
+         (punctuation) @punctuation.delimiter\n\
+         ((identifier) @type.builtin\n (#any-of? @type.builtin {}))\n\
+         (source_file\n (identifier) @function\n . (punctuation) @_open\n (#eq? @_open \"(\"))\n",
+        builtins.join(" ")
+    )
+}
@@ -13,8 +13,18 @@
  *  - Executing native runtime functions
  *  - Executing user-defined PAWX functions
  *  - Handling default parameters
+ *  - Matching named (`name: value`) arguments to parameters
  *  - Converting `throw` into returnable error values
  *
+ * Natives have the signature `NativeFn`, `Fn(Vec<Value>) -> Result<Value,
+ * PawxError>`, and script functions handed to them (lambdas, bound
+ * methods, `purr` values) are wrapped in that shape too. An error inside
+ * one comes back as its `Err`, with its original code and span, and the
+ * native passes it on with `?`, so a script's `try / catch` sees it and a
+ * native looping over callbacks (`forEach`, `map`, ...) runs no more
+ * script code after the first failure. Natives reject bad arguments the
+ * same way, with `raise_error`.
+ *
  * This module is one of the most **security-sensitive runtime layers**
  * because it controls execution boundaries, scope chaining, and error flow.
  *
@@ -45,7 +55,9 @@
 use std::cell::RefCell;
 use std::env::args;
 use std::rc::Rc;
+use std::sync::Arc;
 
+use crate::ast::arena::{Ast, ExprList, ExprNode, ParamNode};
 use crate::ast::Expr;
 use crate::interpreter::environment::{Environment, FunctionDef};
 use crate::span::Span;
@@ -53,8 +65,68 @@ use crate::value::Value;
 
 use crate::interpreter::statements::exec_stmt;
 use crate::interpreter::expressions::eval_expr;
-use crate::interpreter::ExecSignal;
-use crate::error::PawxError;
+use crate::interpreter::{current_span, ExecSignal};
+use crate::interpreter::limits;
+use crate::error::{ErrorCode, PawxError};
+
+/// Most function scopes kept for reuse.
+const ENV_POOL_LIMIT: usize = 64;
+
+thread_local! {
+    /// Scopes of finished calls, reused by later calls so that a call does
+    /// not allocate a new scope and value map every time.
+    static ENV_POOL: RefCell<Vec<Rc<RefCell<Environment>>>> = const { RefCell::new(Vec::new()) };
+}
+
+/// A `E3005` error at the current statement, for a native to return.
+pub fn builtin_error(message: impl Into<String>) -> PawxError {
+    PawxError::new(ErrorCode::BuiltinError, message, current_span())
+}
+
+/// Fails the running native call with a `E3005` error at the current
+/// statement (see [`builtin_error`]).
+///
+/// # Errors
+/// Always.
+pub fn raise_error<T>(message: impl Into<String>) -> Result<T, PawxError> {
+    Err(builtin_error(message))
+}
+
+/// Wraps a script-level call as a function value natives can call.
+pub(crate) fn script_function(call: impl Fn(Vec<Value>) -> Result<Value, PawxError> + 'static) -> Value {
+    Value::NativeFunction(Arc::new(call))
+}
+
+/// Returns an empty call scope chained to `parent`, with room for
+/// `capacity` values, reusing a pooled scope when there is one.
+pub(crate) fn acquire_env(parent: Rc<RefCell<Environment>>, capacity: usize) -> Rc<RefCell<Environment>> {
+    match ENV_POOL.with(|pool| pool.borrow_mut().pop()) {
+        Some(env) => {
+            env.borrow_mut().reset(Some(parent), capacity);
+            env
+        }
+        None => Rc::new(RefCell::new(Environment::with_capacity(Some(parent), capacity))),
+    }
+}
+
+/// Hands a finished call's scope back to the pool.
+///
+/// A scope that outlives the call (captured by a lambda, say) is left
+/// alone. Otherwise its values are dropped now, as they would have been
+/// with the scope itself.
+pub(crate) fn release_env(env: Rc<RefCell<Environment>>) {
+    if Rc::strong_count(&env) != 1 || Rc::weak_count(&env) != 0 {
+        return;
+    }
+
+    env.borrow_mut().reset(None, 0);
+    ENV_POOL.with(|pool| {
+        let mut pool = pool.borrow_mut();
+        if pool.len() < ENV_POOL_LIMIT {
+            pool.push(env);
+        }
+    });
+}
 
 /// Executes a **constructor or class method body** using already-evaluated
 /// argument values.
@@ -72,7 +144,7 @@ use crate::error::PawxError;
 /// Any `return` value inside a constructor is **ignored** (JS-style).
 /// Any `throw` immediately aborts execution.
 ///
-/// # Panics
+/// # Errors
 /// - If the constructor throws an error
 fn call_method_value(
     func: FunctionDef,
@@ -89,16 +161,20 @@ fn call_method_value(
         .define_public("this".to_string(), instance);
 
     // Bind parameters
-    for (i, param) in func.params.iter().enumerate() {
-        let val = args.get(i).cloned().unwrap_or(Value::Null);
+    for (i, param) in func.params().iter().enumerate() {
+        let val = match args.get(i) {
+            Some(val) => val.clone(),
+            None => bind_default(&func.ast, param, &func_env)?,
+        };
+
         func_env
             .borrow_mut()
             .define_public(param.name.clone(), val);
     }
 
     // Execute body
-    for stmt in func.body {
-        match exec_stmt(stmt, func_env.clone())? {
+    for stmt in func.body.iter() {
+        match exec_stmt(&func.ast, stmt, func_env.clone())? {
             ExecSignal::None => {}
 
             // Constructors discard return values
@@ -108,7 +184,7 @@ fn call_method_value(
             ExecSignal::Throw(value) => {
                 return Err(PawxError::runtime_error(
                     format!("Constructor threw error: {}", value.stringify()),
-                    Span::new(0, 0),
+                    current_span(),
                 ));
             }
         }
@@ -117,13 +193,12 @@ fn call_method_value(
     Ok(())
 }
 
-/// Invokes a **standard instance method** using unevaluated expression
-/// arguments.
+/// Invokes a **standard instance method** with evaluated arguments.
 ///
 /// This function:
 /// 1. Creates a local method scope
 /// 2. Binds `this` to the instance
-/// 3. Evaluates and binds arguments
+/// 3. Binds arguments
 /// 4. Executes the method body
 /// 5. Returns the first encountered `return` value
 ///
@@ -135,7 +210,7 @@ fn call_method_value(
 fn call_method(
     func: FunctionDef,
     instance: Value,
-    args: Vec<Expr>,
+    args: Vec<Value>,
     env: Rc<RefCell<Environment>>,
 ) -> Result<Value, PawxError> {
     // Create method execution scope
@@ -144,12 +219,11 @@ fn call_method(
     // Bind `this`
     func_env.borrow_mut().define_public("this".to_string(), instance);
 
-    // Bind parameters (evaluated lazily from expressions)
-    for (i, param) in func.params.iter().enumerate() {
-        let val = if i < args.len() {
-            eval_expr(args[i].clone(), func_env.clone())?
-        } else {
-            Value::Null
+    // Bind parameters
+    for (i, param) in func.params().iter().enumerate() {
+        let val = match args.get(i) {
+            Some(val) => val.clone(),
+            None => bind_default(&func.ast, param, &func_env)?,
         };
 
         func_env
@@ -158,43 +232,156 @@ fn call_method(
     }
 
     // Execute body and return on first `return`
-    for stmt in func.body {
-        if let Ok(ExecSignal::Return(v)) = exec_stmt(stmt, func_env.clone()) {
-            return Ok(v);
+    for stmt in func.body.iter() {
+        match exec_stmt(&func.ast, stmt, func_env.clone())? {
+            ExecSignal::None => {}
+            ExecSignal::Return(v) => return Ok(v),
+            ExecSignal::Throw(value) => {
+                return Err(PawxError::thrown(value, current_span()));
+            }
         }
     }
 
     Ok(Value::Null)
 }
 
+/// Executes a **callable runtime value** with tree-AST argument
+/// expressions, for native code that builds its own `Expr`s.
+///
+/// The arguments are lowered into a small arena of their own and passed to
+/// [`call_with_args`].
+pub fn call_value(
+    callee_val: Value,
+    arguments: Vec<Expr>,
+    env: Rc<RefCell<Environment>>,
+) -> Result<Value, PawxError> {
+    let (ast, arguments) = Ast::lower_exprs(arguments);
+    call_with_args(&ast, callee_val, arguments, env)
+}
+
 /// Executes a **callable runtime value**, such as native functions.
 ///
 /// This function:
 /// - Evaluates all argument expressions
 /// - Dispatches directly into a native Rust function
 ///
-/// # Panics
+/// # Errors
 /// - If the callee is not callable
-pub fn call_value(
+pub fn call_with_args(
+    ast: &Rc<Ast>,
     callee_val: Value,
-    arguments: Vec<Expr>,
+    arguments: ExprList,
     env: Rc<RefCell<Environment>>,
 ) -> Result<Value, PawxError> {
     // Evaluate all argument expressions eagerly
     let mut args = Vec::new();
-    for arg in arguments {
-        args.push(eval_expr(arg, env.clone())?);
+    for arg in arguments.iter() {
+        if let ExprNode::NamedArgument { name, span, .. } = &ast[arg] {
+            return Err(PawxError::new(
+                ErrorCode::InvalidArguments,
+                format!("named argument '{}' needs a 'purr' function or clowder method called by name", name),
+                *span,
+            )
+            .with_help("lambdas, built-ins and functions passed around as values take positional arguments only"));
+        }
+
+        args.push(eval_expr(ast, arg, env.clone())?);
     }
 
     match callee_val {
         // Native functions work as usual
-        Value::NativeFunction(f) => Ok(f(args)),
+        Value::NativeFunction(f) => f(args),
 
         // Allow non-function values to pass through safely (for chaining)
         other => Ok(other),
     }
 }
 
+/// Matches call-site arguments against a user function's parameters.
+///
+/// Positional arguments fill parameters left to right; `name: value`
+/// arguments are then matched by parameter name. A positional argument may
+/// not follow a named one.
+///
+/// Arguments are evaluated in call-site order. The returned slots line up
+/// with `func.params`; `None` means the parameter was not supplied and its
+/// default (or `null`) applies.
+///
+/// # Errors
+/// - `E3003` for an unknown parameter name
+/// - `E3003` when a parameter is supplied more than once
+/// - `E3003` for a positional argument after a named one
+pub fn bind_arguments(
+    ast: &Rc<Ast>,
+    name: &str,
+    func: &FunctionDef,
+    arguments: ExprList,
+    env: Rc<RefCell<Environment>>,
+    span: Span,
+) -> Result<Vec<Option<Value>>, PawxError> {
+    let params = func.params();
+    let mut slots: Vec<Option<Value>> = vec![None; params.len()];
+    let mut position = 0;
+    let mut seen_named = false;
+
+    for arg in arguments.iter() {
+        match &ast[arg] {
+            ExprNode::NamedArgument { name: param_name, value, span: arg_span } => {
+                seen_named = true;
+
+                let index = params
+                    .iter()
+                    .position(|p| &p.name == param_name)
+                    .ok_or_else(|| {
+                        let expected: Vec<&str> =
+                            params.iter().map(|p| p.name.as_str()).collect();
+
+                        PawxError::new(
+                            ErrorCode::InvalidArguments,
+                            format!("unknown parameter '{}' in call to '{}'", param_name, name),
+                            *arg_span,
+                        )
+                        .with_help(format!("'{}' accepts: {}", name, expected.join(", ")))
+                    })?;
+
+                if slots[index].is_some() {
+                    return Err(PawxError::new(
+                        ErrorCode::InvalidArguments,
+                        format!(
+                            "parameter '{}' passed more than once in call to '{}'",
+                            param_name, name
+                        ),
+                        *arg_span,
+                    ));
+                }
+
+                slots[index] = Some(eval_expr(ast, *value, env.clone())?);
+            }
+
+            _ => {
+                if seen_named {
+                    return Err(PawxError::new(
+                        ErrorCode::InvalidArguments,
+                        format!("positional argument follows a named argument in call to '{}'", name),
+                        span,
+                    )
+                    .with_help("pass positional arguments first, then `name: value` pairs"));
+                }
+
+                let val = eval_expr(ast, arg, env.clone())?;
+
+                // Extra positional arguments are evaluated but ignored
+                if position < slots.len() {
+                    slots[position] = Some(val);
+                }
+                position += 1;
+            }
+        }
+    }
+
+    Ok(slots)
+}
+
 /// Executes a **user-defined PAWX function**.
 ///
 /// This is the primary execution path for:
@@ -215,18 +402,52 @@ pub fn call_user_function(
     arg_vals: Vec<Value>,
     env: Rc<RefCell<Environment>>,
 ) -> Result<Value, PawxError> {
+    let slots = arg_vals.into_iter().map(Some).collect();
+    call_user_function_slots(func, slots, env)
+}
+
+/// Executes a user-defined function with arguments already matched to
+/// parameters by [`bind_arguments`].
+///
+/// Missing slots (`None`) fall back to the parameter's default expression,
+/// evaluated in the function scope, or `null`.
+///
+/// A `zoom purr` function returns a Furure: its return value, or the error
+/// it failed with, for the caller to `nap` on.
+pub fn call_user_function_slots(
+    func: FunctionDef,
+    slots: Vec<Option<Value>>,
+    env: Rc<RefCell<Environment>>,
+) -> Result<Value, PawxError> {
+    let _call = limits::enter_call()?;
+
     // Create function-local scope chained to the outer environment
-    let func_env = Rc::new(RefCell::new(Environment::new(Some(env))));
+    let func_env = acquire_env(env, func.params().len());
+    let result = run_function(&func, slots, &func_env);
+    release_env(func_env);
 
+    if !func.is_async {
+        return result;
+    }
+
+    Ok(match result {
+        Ok(value @ Value::Furure(_)) => value,
+        Ok(value) => Value::Furure(Box::new(value)),
+        Err(err) => Value::Furure(Box::new(err.into_value())),
+    })
+}
+
+/// Binds `slots` in `func_env` and runs the function body there.
+fn run_function(
+    func: &FunctionDef,
+    mut slots: Vec<Option<Value>>,
+    func_env: &Rc<RefCell<Environment>>,
+) -> Result<Value, PawxError> {
     // Bind parameters (arguments already evaluated!)
-    for (i, param) in func.params.iter().enumerate() {
-        let val = if i < arg_vals.len() {
-            arg_vals[i].clone()
-        } else if let Some(default_expr) = &param.default {
-            // Defaults are expressions → must be evaluated
-            eval_expr(default_expr.clone(), func_env.clone())?
-        } else {
-            Value::Null
+    for (i, param) in func.params().iter().enumerate() {
+        let val = match slots.get_mut(i).and_then(Option::take) {
+            Some(val) => val,
+            None => bind_default(&func.ast, param, func_env)?,
         };
 
         func_env
@@ -235,8 +456,8 @@ pub fn call_user_function(
     }
 
     // Execute function body
-    for stmt in func.body {
-        match exec_stmt(stmt, func_env.clone())? {
+    for stmt in func.body.iter() {
+        match exec_stmt(&func.ast, stmt, func_env.clone())? {
             ExecSignal::None => {}
 
             ExecSignal::Return(value) => {
@@ -244,14 +465,37 @@ pub fn call_user_function(
             }
 
             ExecSignal::Throw(value) => {
-                return Err(PawxError::runtime_error(
-                    format!("Uncaught exception: {}", value.stringify()),
-                    Span::new(0, 0),
-                ));
+                return Err(PawxError::thrown(value, current_span()));
             }
         }
     }
 
     // No explicit return → null
     Ok(Value::Null)
-}
\ No newline at end of file
+}
+
+/// Wraps a user-defined function as a first-class callable value.
+///
+/// Used when a `purr` declaration must be passed around as a value (for
+/// example to a decorator). The function runs in `env`, its defining scope.
+/// Errors propagate out of the native that called it (see
+/// [`script_function`]).
+pub fn function_value(func: FunctionDef, env: Rc<RefCell<Environment>>) -> Value {
+    script_function(move |args| call_user_function(func.clone(), args, env.clone()))
+}
+
+/// Evaluates a parameter's default for a missing argument.
+///
+/// Defaults are expressions evaluated in the callee's scope, so they can
+/// refer to `this` and to earlier parameters. Parameters without a default
+/// receive `null`.
+pub(crate) fn bind_default(
+    ast: &Rc<Ast>,
+    param: &ParamNode,
+    func_env: &Rc<RefCell<Environment>>,
+) -> Result<Value, PawxError> {
+    match param.default {
+        Some(default_expr) => eval_expr(ast, default_expr, func_env.clone()),
+        None => Ok(Value::Null),
+    }
+}
@@ -37,11 +37,14 @@
  * =============================================================================
  */
 
-use crate::ast::{ClassMember, Expr, Param};
-use crate::error::PawxError;
+use crate::ast::arena::{Ast, ExprList, ExprNode, MemberNode, ParamList, StmtList};
+use crate::error::{ErrorCode, PawxError};
 use crate::interpreter::environment::{Environment, FunctionDef};
+use crate::interpreter::calls::{acquire_env, bind_arguments, bind_default, release_env, script_function};
+use crate::interpreter::{current_span, limits};
 use crate::span::Span;
-use crate::value::Value;
+use crate::ast::{AccessLevel, InstinctMemberKind};
+use crate::value::{ClassDef, InstinctDef, Value};
 use crate::interpreter::expressions::{eval_expr};
 use crate::interpreter::statements::{exec_stmt, ExecSignal};
 
@@ -49,6 +52,11 @@ use std::cell::RefCell;
 use std::collections::HashMap;
 use std::rc::Rc;
 
+/// Binding in a method's scope naming the clowder that declares the method,
+/// which decides what `den` and `lair` members it may touch. It is not a
+/// valid identifier, so programs cannot read or shadow it.
+const CLOWDER_SCOPE: &str = "<clowder>";
+
 /// ==========================================================================
 /// CLASS CONSTRUCTION
 /// ==========================================================================
@@ -59,10 +67,14 @@ use std::rc::Rc;
 /// - Extracts all fields
 /// - Registers all methods
 /// - Registers getters and setters
+/// - Checks it against every instinct it practices
 /// - Produces the final executable `Value::Class` object
 ///
 /// # Parameters
 /// - `name` - Class name
+/// - `base` - The class it `inherits`, whose fields it starts with
+/// - `instincts` - The instincts it `practices`
+/// - `ast` - The arena holding the class members
 /// - `members` - All class members parsed from the AST
 /// - `env` - Current runtime environment
 ///
@@ -70,74 +82,138 @@ use std::rc::Rc;
 /// A fully constructed `Value::Class`
 pub fn build_class_value(
     name: String,
-    members: Vec<ClassMember>,
+    base: Option<Rc<ClassDef>>,
+    instincts: Vec<Rc<InstinctDef>>,
+    ast: &Rc<Ast>,
+    members: &[MemberNode],
     env: Rc<RefCell<Environment>>,
 ) -> Result<Value, PawxError> {
     let mut methods  = HashMap::new();
     let mut getters  = HashMap::new();
     let mut setters  = HashMap::new();
-    let mut fields   = HashMap::new();
+    let mut fields   = base.as_ref().map(|base| base.fields.clone()).unwrap_or_default();
+    let mut static_methods = HashMap::new();
+    let mut static_fields  = HashMap::new();
+    let mut access   = HashMap::new();
+
+    let function = |params: ParamList, body: StmtList| FunctionDef {
+        ast: ast.clone(),
+        params,
+        body,
+        return_type: None,
+        is_async: false,
+        name_span: current_span(),
+    };
 
     for member in members {
         match member {
-            ClassMember::Field { name, value, .. } => {
+            MemberNode::Field { name, access: level, value, is_static } => {
+                access.insert(name.clone(), level.clone());
+
                 let val = if let Some(expr) = value {
-                    eval_expr(expr, env.clone())
+                    eval_expr(ast, *expr, env.clone())
                 } else {
                     Ok(Value::Null)
                 };
 
-                fields.insert(name, val?);
+                if *is_static {
+                    static_fields.insert(name.clone(), val?);
+                } else {
+                    fields.insert(name.clone(), val?);
+                }
             }
 
-            ClassMember::Method { name, params, body, .. } => {
-                let func = FunctionDef {
-                    params,
-                    body,
-                    return_type: None,
-                    is_async: false,
-                    name_span: Span::new(0, 0),
-                };
-                methods.insert(name, func);
-            }
+            MemberNode::Method { name, access: level, params, body, is_static } => {
+                access.insert(name.clone(), level.clone());
 
-            ClassMember::Getter { name, body, .. } => {
-                let func = FunctionDef {
-                    params: vec![],
-                    body,
-                    return_type: None,
-                    is_async: false,
-                    name_span: Span::new(0, 0),
-                };
-                getters.insert(name, func);
+                if *is_static {
+                    static_methods.insert(name.clone(), function(*params, *body));
+                } else {
+                    methods.insert(name.clone(), function(*params, *body));
+                }
             }
 
-            ClassMember::Setter { name, param_name, body, .. } => {
-                let func = FunctionDef {
-                    params: vec![Param {
-                        name: param_name,
-                        default: None,
-                        type_annotation: None,
-                    }],
-                    body,
-                    return_type: None,
-                    is_async: false,
-                    name_span: Span::new(0, 0),
-                };
-                setters.insert(name, func);
+            MemberNode::Getter { name, body, .. } => {
+                access.insert(name.clone(), AccessLevel::Public);
+                getters.insert(name.clone(), function(Default::default(), *body));
             }
 
-            _ => {}
+            MemberNode::Setter { name, param, body } => {
+                access.insert(name.clone(), AccessLevel::Public);
+                setters.insert(name.clone(), function(*param, *body));
+            }
         }
     }
 
-    Ok(Value::Class {
+    let class = Rc::new(ClassDef {
         name,
+        base,
         methods,
         getters,
         setters,
         fields,
-    })
+        static_methods,
+        static_fields: RefCell::new(static_fields),
+        access,
+        instincts,
+    });
+
+    check_instincts(&class)?;
+
+    Ok(Value::Class(class))
+}
+
+/// Verifies that `class` provides every member of the instincts it
+/// practices, inherited members included.
+///
+/// A method satisfies a signature if it can be called with the signature's
+/// number of arguments: extra parameters must have defaults.
+fn check_instincts(class: &Rc<ClassDef>) -> Result<(), PawxError> {
+    let mut problems = Vec::new();
+
+    for instinct in &class.instincts {
+        for signature in &instinct.members {
+            let found = match signature.kind {
+                InstinctMemberKind::Method => class.find_method(&signature.name),
+                InstinctMemberKind::Getter => class.find_getter(&signature.name),
+                InstinctMemberKind::Setter => class.find_setter(&signature.name),
+            };
+
+            let Some((func, _)) = found else {
+                problems.push(format!("missing {} (from {})", signature, instinct.name));
+                continue;
+            };
+
+            let params = func.params();
+            let required = params.iter().filter(|p| p.default.is_none()).count();
+            let arity = signature.params.len();
+
+            if signature.kind == InstinctMemberKind::Method && (arity < required || arity > params.len()) {
+                problems.push(format!(
+                    "{} takes {} parameter{}, but {} declares {}",
+                    signature.name,
+                    params.len(),
+                    if params.len() == 1 { "" } else { "s" },
+                    instinct.name,
+                    signature,
+                ));
+            }
+        }
+    }
+
+    if problems.is_empty() {
+        return Ok(());
+    }
+
+    Err(PawxError::new(
+        ErrorCode::InstinctNotSatisfied,
+        format!(
+            "clowder '{}' does not satisfy its instincts:\n  - {}",
+            class.name,
+            problems.join("\n  - ")
+        ),
+        current_span(),
+    ))
 }
 
 /// ==========================================================================
@@ -149,18 +225,23 @@ pub fn build_class_value(
 /// This function:
 /// - Evaluates constructor arguments
 /// - Creates an instance with shared mutable fields
-/// - Binds and executes the constructor (`new`) if present
+/// - Binds and executes the constructor (`new`), the class's own or the
+///   nearest inherited one, if present
 ///
 /// # Parameters
 /// - `class_name` - Name of the class
+/// - `ast` - The arena holding the arguments
 /// - `arguments` - Constructor arguments
+/// - `span` - Location of the `new` expression
 /// - `env` - Current runtime environment
 ///
 /// # Returns
 /// A fully initialized `Value::Instance`
 pub fn construct_instance(
     class_name: String,
-    arguments: Vec<Expr>,
+    ast: &Rc<Ast>,
+    arguments: ExprList,
+    span: Span,
     env: Rc<RefCell<Environment>>,
 ) -> Result<Value, PawxError> {
     let class_val = env
@@ -168,45 +249,158 @@ pub fn construct_instance(
         .get(&class_name, false)
         .ok_or_else(|| PawxError::runtime_error(
             format!("Undefined class '{}'", class_name),
-            Span::new(0, 0),
+            span,
         ))?;
 
-    let (methods, getters, setters, fields) = match &class_val {
-        Value::Class { methods, getters, setters, fields, .. } => (
-            methods.clone(),
-            getters.clone(),
-            setters.clone(),
-            fields.clone(),
-        ),
+    let class = match class_val {
+        Value::Class(class) => class,
         _ => {
             return Err(PawxError::runtime_error(
                 format!("'{}' is not a class", class_name),
-                Span::new(0, 0),
+                span,
             ));
         }
     };
 
     let instance = Value::Instance {
-        class_name: class_name.clone(),
-        fields: Rc::new(RefCell::new(fields)),
-        methods: methods.clone(),
-        getters: getters.clone(),
-        setters: setters.clone(),
+        fields: Rc::new(RefCell::new(class.fields.clone())),
+        class: class.clone(),
     };
 
-    // build Vec<Value> (NOT Vec<Result<...>>)
-    let mut arg_values = Vec::new();
-    for arg in arguments {
-        arg_values.push(eval_expr(arg, env.clone())?);
-    }
+    match class.find_method("new") {
+        Some((constructor, owner)) => {
+            let slots = bind_arguments(ast, &class_name, &constructor, arguments, env.clone(), span)?;
+            call_member_slots(constructor, &owner, instance.clone(), slots, env)?;
+        }
 
-    if let Some(constructor) = methods.get("new") {
-        call_method_value(constructor.clone(), instance.clone(), arg_values, env.clone())?;
+        // Without a constructor the arguments are evaluated and ignored
+        None => {
+            for arg in arguments.iter() {
+                if let ExprNode::NamedArgument { name, span, .. } = &ast[arg] {
+                    return Err(PawxError::new(
+                        ErrorCode::InvalidArguments,
+                        format!("unknown parameter '{}' in call to '{}'", name, class_name),
+                        *span,
+                    )
+                    .with_help(format!("clowder '{}' has no constructor", class_name)));
+                }
+                eval_expr(ast, arg, env.clone())?;
+            }
+        }
     }
 
     Ok(instance)
 }
 
+/* ==========================================================================
+ * SUPER
+ * ========================================================================== */
+
+/// The base class and instance a `super` expression refers to.
+///
+/// Methods of a class that `inherits` run with `super` bound to the base
+/// of the class that declares them, not of the instance's class, so a
+/// chain of `super` calls climbs one level at a time.
+fn super_target(env: &Rc<RefCell<Environment>>) -> Result<(Rc<ClassDef>, Value), PawxError> {
+    let env = env.borrow();
+
+    match (env.get("super", false), env.get("this", false)) {
+        (Some(Value::Class(base)), Some(this)) => Ok((base, this)),
+        _ => Err(PawxError::reference_error(
+            "'super' can only be used in a method of a clowder that inherits another",
+            current_span(),
+        )),
+    }
+}
+
+/// Runs the base class constructor for `super(...)`.
+///
+/// Does nothing but evaluate the arguments if no base class declares a
+/// constructor.
+pub fn call_super_constructor(
+    ast: &Rc<Ast>,
+    arguments: ExprList,
+    span: Span,
+    env: Rc<RefCell<Environment>>,
+) -> Result<Value, PawxError> {
+    let (base, this) = super_target(&env)?;
+
+    match base.find_method("new") {
+        Some((constructor, owner)) => {
+            let slots = bind_arguments(ast, "super", &constructor, arguments, env.clone(), span)?;
+            call_member_slots(constructor, &owner, this, slots, env)?;
+        }
+        None => {
+            for arg in arguments.iter() {
+                eval_expr(ast, arg, env.clone())?;
+            }
+        }
+    }
+
+    Ok(Value::Null)
+}
+
+/// Resolves `super.name`: a base class getter, method (bound to `this`) or
+/// field.
+pub fn get_super_property(name: String, span: Span, env: Rc<RefCell<Environment>>) -> Result<Value, PawxError> {
+    let (base, this) = super_target(&env)?;
+    resolve_property(&base, this, name, span, env)
+}
+
+/// Calls `super.name(...)` when `name` is a base class method, matching the
+/// call-site `arguments` to its parameters.
+///
+/// # Returns
+/// - `None` if `name` is not a method there; see [`call_method`]
+pub fn call_super_method(
+    name: &str,
+    ast: &Rc<Ast>,
+    arguments: ExprList,
+    span: Span,
+    env: Rc<RefCell<Environment>>,
+) -> Result<Option<Value>, PawxError> {
+    let (base, this) = super_target(&env)?;
+    call_instance_method(&base, this, name, ast, arguments, span, env)
+}
+
+/* ==========================================================================
+ * ACCESS CONTROL
+ * ========================================================================== */
+
+/// Checks that code running in `env` may use member `name` of `class`.
+///
+/// `den` members are only usable from methods of the clowder that declares
+/// them, `lair` members from those of its subclasses too. Lambdas created
+/// in a method keep its access; code outside any method has none.
+fn check_access(class: &Rc<ClassDef>, name: &str, span: Span, env: &Rc<RefCell<Environment>>) -> Result<(), PawxError> {
+    let Some((level, declarer)) = class.find_access(name) else {
+        return Ok(());
+    };
+
+    let caller = match env.borrow().get(CLOWDER_SCOPE, false) {
+        Some(Value::Class(caller)) => Some(caller),
+        _ => None,
+    };
+
+    let message = match level {
+        AccessLevel::Public => return Ok(()),
+
+        AccessLevel::Private if caller.as_ref().is_some_and(|c| Rc::ptr_eq(c, &declarer)) => return Ok(()),
+        AccessLevel::Private => format!(
+            "'{}' is den (private) to clowder '{}' and can only be used inside its methods",
+            name, declarer.name
+        ),
+
+        AccessLevel::Protected if caller.as_ref().is_some_and(|c| c.inherits_from(&declarer)) => return Ok(()),
+        AccessLevel::Protected => format!(
+            "'{}' is lair (protected) in clowder '{}' and can only be used inside the methods of '{}' and clowders that inherit it",
+            name, declarer.name, declarer.name
+        ),
+    };
+
+    Err(PawxError::new(ErrorCode::InaccessibleMember, message, span))
+}
+
 /// ==========================================================================
 /// INSTANCE PROPERTY ACCESS
 /// ==========================================================================
@@ -228,74 +422,122 @@ pub fn construct_instance(
 pub fn get_instance_property(
     instance: Value,
     name: String,
+    span: Span,
     env: Rc<RefCell<Environment>>,
 ) -> Result<Value, PawxError> {
-    match instance {
-        Value::Instance {
-            fields,
-            methods,
-            getters,
-            setters,
-            ..
-        } => {
-            // Getter
-            if let Some(getter) = getters.get(&name) {
-                return call_method(
-                    getter.clone(),
-                    Value::Instance {
-                        class_name: "".into(),
-                        fields,
-                        methods,
-                        getters,
-                        setters,
-                    },
-                    vec![],
-                    env,
-                );
-            }
+    match &instance {
+        Value::Instance { class, .. } => {
+            let class = class.clone();
+            resolve_property(&class, instance, name, span, env)
+        }
 
-            // Direct field
-            if let Some(val) = fields.borrow().get(&name) {
-                return Ok(val.clone());
-            }
+        _ => Err(PawxError::runtime_error(
+            "Property access only valid on class instances".to_string(),
+            current_span(),
+        )),
+    }
+}
 
-            // Method → return a bound native function
-            if let Some(method) = methods.get(&name) {
-                let method = method.clone();
-                let instance = Value::Instance {
-                    class_name: "".into(),
-                    fields,
-                    methods,
-                    getters,
-                    setters,
-                };
+/// Looks `name` up for `instance`, starting at `class`: getters first, then
+/// the instance's fields, then methods.
+fn resolve_property(
+    class: &Rc<ClassDef>,
+    instance: Value,
+    name: String,
+    span: Span,
+    env: Rc<RefCell<Environment>>,
+) -> Result<Value, PawxError> {
+    let Value::Instance { fields, .. } = &instance else {
+        return Err(PawxError::runtime_error(
+            "Property access only valid on class instances".to_string(),
+            current_span(),
+        ));
+    };
 
-                return Ok(Value::NativeFunction(std::sync::Arc::new(
-                    move |_args| {
-                        match call_method(
-                            method.clone(),
-                            instance.clone(),
-                            vec![],
-                            env.clone(),
-                        ) {
-                            Ok(v) => v,
-                            Err(e) => Value::Error { message: e.message },
-                        }
-                    },
-                )));
+    check_access(class, &name, span, &env)?;
+
+    // Getter
+    if let Some((getter, owner)) = class.find_getter(&name) {
+        return call_member(getter, &owner, instance, vec![], env);
+    }
+
+    // Direct field
+    if let Some(val) = fields.borrow().get(&name) {
+        return Ok(val.clone());
+    }
+
+    // Method → return a bound native function
+    if let Some((method, owner)) = class.find_method(&name) {
+        return Ok(script_function(move |args| {
+            call_member(method.clone(), &owner, instance.clone(), args, env.clone())
+        }));
+    }
+
+    Err(PawxError::runtime_error(
+        format!("Undefined property '{}' on instance", name),
+        current_span(),
+    ))
+}
+
+/// Calls `target.name(...)` when `name` is a method of the instance, or a
+/// static method of the clowder, `target`. The call-site `arguments` are
+/// matched to its parameters, so they may be passed by name.
+///
+/// # Returns
+/// - `None` if `name` is not a method there (a getter, a field, or not a
+///   clowder value at all); the caller reads the property and calls it
+pub fn call_method(
+    target: &Value,
+    name: &str,
+    ast: &Rc<Ast>,
+    arguments: ExprList,
+    span: Span,
+    env: Rc<RefCell<Environment>>,
+) -> Result<Option<Value>, PawxError> {
+    match target {
+        Value::Instance { class, .. } => call_instance_method(class, target.clone(), name, ast, arguments, span, env),
+
+        Value::Class(class) => {
+            if class.find_static_field(name).is_some() {
+                return Ok(None);
             }
+            let Some((method, owner)) = class.find_static_method(name) else {
+                return Ok(None);
+            };
 
-            Err(PawxError::runtime_error(
-                format!("Undefined property '{}' on instance", name),
-                Span::new(0, 0),
-            ))
+            check_access(class, name, span, &env)?;
+            let slots = bind_arguments(ast, name, &method, arguments, env.clone(), span)?;
+            call_static_slots(method, &owner, slots, env).map(Some)
         }
 
-        _ => Err(PawxError::runtime_error(
-            "Property access only valid on class instances".to_string(),
-            Span::new(0, 0),
-        )),
+        _ => Ok(None),
+    }
+}
+
+/// Calls method `name` of `instance`, looked up from `class`, unless a
+/// getter or field of that name comes first.
+fn call_instance_method(
+    class: &Rc<ClassDef>,
+    instance: Value,
+    name: &str,
+    ast: &Rc<Ast>,
+    arguments: ExprList,
+    span: Span,
+    env: Rc<RefCell<Environment>>,
+) -> Result<Option<Value>, PawxError> {
+    let Value::Instance { fields, .. } = &instance else {
+        return Ok(None);
+    };
+    if class.find_getter(name).is_some() || fields.borrow().contains_key(name) {
+        return Ok(None);
     }
+    let Some((method, owner)) = class.find_method(name) else {
+        return Ok(None);
+    };
+
+    check_access(class, name, span, &env)?;
+    let slots = bind_arguments(ast, name, &method, arguments, env.clone(), span)?;
+    call_member_slots(method, &owner, instance, slots, env).map(Some)
 }
 
 /// ==========================================================================
@@ -304,109 +546,189 @@ pub fn get_instance_property(
 
 /// Assigns a value to a property on a class instance (`obj.property = value`).
 ///
-/// If a setter exists, it is executed instead of direct assignment.
+/// If a setter exists (declared or inherited), it is executed instead of
+/// direct assignment.
 ///
 /// # Returns
 /// The assigned value
+///
+/// # Errors
+/// - If the setter fails
+/// - If `instance` is not a class instance
 pub fn set_instance_property(
     instance: Value,
     name: String,
     value: Value,
+    span: Span,
     env: Rc<RefCell<Environment>>,
-) -> Value {
+) -> Result<Value, PawxError> {
     match instance {
-        Value::Instance {
-            class_name,
-            fields,
-            methods,
-            getters,
-            setters,
-        } => {
-            if let Some(setter_def) = setters.get(&name) {
-                call_method_value(
-                    setter_def.clone(),
-                    Value::Instance {
-                        class_name,
-                        fields,
-                        methods,
-                        getters,
-                        setters,
-                    },
+        Value::Instance { class, fields } => {
+            check_access(&class, &name, span, &env)?;
+
+            if let Some((setter, owner)) = class.find_setter(&name) {
+                call_member(
+                    setter,
+                    &owner,
+                    Value::Instance { class: class.clone(), fields },
                     vec![value.clone()],
                     env,
-                );
-                value
+                )?;
             } else {
                 fields.borrow_mut().insert(name, value.clone());
-                value
             }
+            Ok(value)
         }
 
-        _ => panic!("Only instances support field assignment"),
+        _ => Err(PawxError::runtime_error(
+            "Only instances support field assignment",
+            current_span(),
+        )),
     }
 }
 
+/* ==========================================================================
+ * STATIC MEMBERS
+ * ========================================================================== */
+
+/// Resolves `Class.name`: a static field, or a static method as a function.
+///
+/// Both are looked up along the `inherits` chain.
+pub fn get_static_property(
+    class: &Rc<ClassDef>,
+    name: &str,
+    span: Span,
+    env: Rc<RefCell<Environment>>,
+) -> Result<Value, PawxError> {
+    check_access(class, name, span, &env)?;
+
+    if let Some(value) = class.find_static_field(name) {
+        return Ok(value);
+    }
+
+    if let Some((method, owner)) = class.find_static_method(name) {
+        return Ok(script_function(move |args| {
+            call_static(method.clone(), &owner, args, env.clone())
+        }));
+    }
+
+    Err(PawxError::new(
+        ErrorCode::UnknownProperty,
+        format!("clowder '{}' has no static member '{}'", class.name, name),
+        span,
+    ))
+}
+
+/// Assigns `Class.name = value`, on the class itself even if the field was
+/// inherited.
+pub fn set_static_property(
+    class: &Rc<ClassDef>,
+    name: &str,
+    value: Value,
+    span: Span,
+    env: &Rc<RefCell<Environment>>,
+) -> Result<Value, PawxError> {
+    check_access(class, name, span, env)?;
+
+    class.static_fields.borrow_mut().insert(name.to_string(), value.clone());
+    Ok(value)
+}
+
 /// ==========================================================================
 /// METHOD & CONSTRUCTOR EXECUTION
 /// ==========================================================================
 
-/// Executes a class constructor or setter method with pre-evaluated arguments.
-pub fn call_method_value(
+/// Executes a method, getter or setter declared by `owner`, with `super`
+/// bound to `owner`'s base class.
+pub fn call_member(
     func: FunctionDef,
+    owner: &Rc<ClassDef>,
     instance: Value,
     args: Vec<Value>,
     env: Rc<RefCell<Environment>>,
 ) -> Result<Value, PawxError> {
-    let func_env = Rc::new(RefCell::new(Environment::new(Some(env))));
-
-    func_env
-        .borrow_mut()
-        .define_public("this".to_string(), instance);
+    let slots = args.into_iter().map(Some).collect();
+    call_member_slots(func, owner, instance, slots, env)
+}
 
-    for (i, param) in func.params.iter().enumerate() {
-        let val = args.get(i).cloned().unwrap_or(Value::Null);
-        func_env
-            .borrow_mut()
-            .define_public(param.name.clone(), val);
-    }
+/// Executes a method with arguments already matched to parameters by
+/// [`bind_arguments`]; missing slots take their default.
+pub fn call_member_slots(
+    func: FunctionDef,
+    owner: &Rc<ClassDef>,
+    instance: Value,
+    slots: Vec<Option<Value>>,
+    env: Rc<RefCell<Environment>>,
+) -> Result<Value, PawxError> {
+    let _call = limits::enter_call()?;
 
-    for stmt in func.body {
-        match exec_stmt(stmt, func_env.clone())? {
-            ExecSignal::None => {}
-            ExecSignal::Return(value) => return Ok(value),
-            ExecSignal::Throw(value) => {
-                return Err(PawxError::runtime_error(
-                    format!("Uncaught exception: {}", value.stringify()),
-                    Span::new(0, 0), // until you plumb real stmt spans
-                ));
-            }
-        }
-    }
+    // Extra slots for `this`, `super` and the declaring clowder
+    let func_env = acquire_env(env, func.params().len() + 3);
+    let result = run_method(&func, owner, Some(instance), slots, &func_env);
+    release_env(func_env);
+    result
+}
 
-    Ok(Value::Null)
+/// Executes a static method declared by `owner`; there is no `this` or
+/// `super`.
+pub fn call_static(
+    func: FunctionDef,
+    owner: &Rc<ClassDef>,
+    args: Vec<Value>,
+    env: Rc<RefCell<Environment>>,
+) -> Result<Value, PawxError> {
+    let slots = args.into_iter().map(Some).collect();
+    call_static_slots(func, owner, slots, env)
 }
 
-/// Executes a class method and returns the return value.
-pub fn call_method(
+/// Executes a static method with arguments already matched to parameters
+/// by [`bind_arguments`].
+pub fn call_static_slots(
     func: FunctionDef,
-    instance: Value,
-    args: Vec<Expr>,
+    owner: &Rc<ClassDef>,
+    slots: Vec<Option<Value>>,
     env: Rc<RefCell<Environment>>,
 ) -> Result<Value, PawxError> {
-    let func_env = Rc::new(RefCell::new(Environment::new(Some(env))));
+    let _call = limits::enter_call()?;
 
-    // Bind `this`
+    let func_env = acquire_env(env, func.params().len() + 1);
+    let result = run_method(&func, owner, None, slots, &func_env);
+    release_env(func_env);
+    result
+}
+
+/// Binds the declaring clowder, `this` and `super` (for instance methods)
+/// and the arguments in `func_env` and runs the method body.
+fn run_method(
+    func: &FunctionDef,
+    owner: &Rc<ClassDef>,
+    instance: Option<Value>,
+    mut slots: Vec<Option<Value>>,
+    func_env: &Rc<RefCell<Environment>>,
+) -> Result<Value, PawxError> {
     func_env
         .borrow_mut()
-        .define_public("this".to_string(), instance);
+        .define_public(CLOWDER_SCOPE.to_string(), Value::Class(owner.clone()));
+
+    // Bind `this`
+    if let Some(instance) = instance {
+        func_env
+            .borrow_mut()
+            .define_public("this".to_string(), instance);
+
+        if let Some(base) = owner.base.clone() {
+            func_env
+                .borrow_mut()
+                .define_public("super".to_string(), Value::Class(base));
+        }
+    }
 
     // Bind parameters
-    for (i, param) in func.params.iter().enumerate() {
-        let val = if i < args.len() {
-            eval_expr(args[i].clone(), func_env.clone())
-        } else {
-            Ok(Value::Null)
-        }?;
+    for (i, param) in func.params().iter().enumerate() {
+        let val = match slots.get_mut(i).and_then(Option::take) {
+            Some(val) => val,
+            None => bind_default(&func.ast, param, func_env)?,
+        };
 
         func_env
             .borrow_mut()
@@ -414,8 +736,8 @@ pub fn call_method(
     }
 
     // Execute body
-    for stmt in func.body {
-        match exec_stmt(stmt, func_env.clone())? {
+    for stmt in func.body.iter() {
+        match exec_stmt(&func.ast, stmt, func_env.clone())? {
             ExecSignal::None => {}
 
             ExecSignal::Return(v) => {
@@ -423,13 +745,10 @@ pub fn call_method(
             }
 
             ExecSignal::Throw(v) => {
-                return Err(PawxError::runtime_error(
-                    format!("Uncaught exception: {}", v.stringify()),
-                    Span::new(0, 0),
-                ));
+                return Err(PawxError::thrown(v, current_span()));
             }
         }
     }
 
     Ok(Value::Null)
-}
\ No newline at end of file
+}
@@ -0,0 +1,256 @@
+/*
+ * ============================================================================
+ * PAWX - Code with Claws!
+ * ============================================================================
+ *
+ * Author:   Sam Wilcox
+ * Email:    sam@pawx-lang.com
+ * Website:  https://www.pawx-lang.com
+ * Github:   https://github.com/samwilcox/pawx
+ *
+ * License:
+ * This file is part of the PAWX programming language project.
+ *
+ * PAWX is dual-licensed under the terms of:
+ *   - The MIT license
+ *   - The Apache License, Version 2.0
+ *
+ * You may choose either license to govern your use of this software.
+ * Full license text available at:
+ *    https://license.pawx-lang.com
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under these licenses is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *
+ * ============================================================================
+ */
+
+/*!
+ * PAWX Virtual Clock
+ * ------------------
+ *
+ * When a program runs with `--virtual-time`, time stops moving on its own.
+ * Instead, a **virtual clock** is advanced explicitly from PAWX code:
+ *
+ * ```pawx
+ * setTimeout(() -> { meow("tick") }, 1000);
+ * Clock.advance(1000);   // prints "tick"
+ * ```
+ *
+ * While virtual time is enabled:
+ *
+ *  • setTimeout / setInterval schedule against the virtual clock
+ *  • Time.now() returns the virtual time in milliseconds (starting at 0)
+ *  • Time.sleep(ms) advances the virtual clock instead of blocking
+ *
+ * Timers fire **in order of due time** (ties broken by creation order), and
+ * always on the interpreter thread, so time-dependent logic can be tested
+ * deterministically.
+ */
+
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use crate::error::PawxError;
+use crate::interpreter::calls::raise_error;
+use crate::value::Value;
+
+/* ============================================================================
+ * Virtual Timer Records
+ * ============================================================================
+ */
+
+/// A timer scheduled against the virtual clock.
+#[derive(Debug, Clone)]
+pub struct ScheduledTimer {
+    /// Timer ID returned to PAWX code.
+    pub id: u64,
+
+    /// Virtual time (ms) at which the timer fires next.
+    pub due: u64,
+
+    /// Repeat period for intervals, `None` for one-shot timeouts.
+    pub interval: Option<u64>,
+
+    /// Callback invoked when the timer fires.
+    pub callback: Value,
+}
+
+/* ============================================================================
+ * Virtual Clock
+ * ============================================================================
+ */
+
+/// A controllable clock shared by the timer runtime and the `Time` / `Clock`
+/// globals.
+///
+/// Cloning a `VirtualClock` produces another handle to the **same** clock.
+#[derive(Debug, Clone, Default)]
+pub struct VirtualClock {
+    now: Rc<Cell<u64>>,
+    scheduled: Rc<RefCell<Vec<ScheduledTimer>>>,
+}
+
+impl VirtualClock {
+    /// Creates a new virtual clock starting at time `0`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the current virtual time in milliseconds.
+    pub fn now(&self) -> u64 {
+        self.now.get()
+    }
+
+    /// Schedules a timer to fire `delay` milliseconds from now.
+    ///
+    /// # Parameters
+    /// - `id`: Timer ID allocated by the timer runtime
+    /// - `delay`: Delay in milliseconds
+    /// - `interval`: Repeat period for intervals
+    /// - `callback`: Function invoked when the timer fires
+    pub fn schedule(&self, id: u64, delay: u64, interval: Option<u64>, callback: Value) {
+        self.scheduled.borrow_mut().push(ScheduledTimer {
+            id,
+            due: self.now() + delay,
+            interval,
+            callback,
+        });
+    }
+
+    /// Cancels a scheduled timer.
+    ///
+    /// # Returns
+    /// - `true` if a timer with the given ID was pending
+    pub fn cancel(&self, id: u64) -> bool {
+        let mut scheduled = self.scheduled.borrow_mut();
+        let before = scheduled.len();
+        scheduled.retain(|t| t.id != id);
+        scheduled.len() != before
+    }
+
+    /// Returns the virtual time (ms) the earliest pending timer is due at.
+    pub fn next_due(&self) -> Option<u64> {
+        self.scheduled.borrow().iter().map(|t| t.due).min()
+    }
+
+    /// Returns the number of timers still waiting to fire.
+    pub fn pending(&self) -> usize {
+        self.scheduled.borrow().len()
+    }
+
+    /// Advances the clock by `ms` milliseconds, firing every timer that
+    /// becomes due along the way.
+    ///
+    /// # Behavior
+    /// - Timers fire in order of due time, then by ID
+    /// - The clock is set to each timer's due time before its callback runs
+    /// - Intervals are rescheduled before their callback runs, so a callback
+    ///   may safely clear its own interval
+    /// - Timers scheduled by callbacks fire in the same call if they fall
+    ///   inside the advanced window
+    ///
+    /// # Errors
+    /// The first error raised by a timer callback. The clock stays at that
+    /// timer's due time; later timers are left waiting.
+    pub fn advance(&self, ms: u64) -> Result<(), PawxError> {
+        let target = self.now() + ms;
+
+        while let Some(callback) = self.fire_next(target) {
+            if let Value::NativeFunction(f) = callback {
+                f(vec![])?;
+            }
+        }
+
+        self.now.set(target);
+        Ok(())
+    }
+
+    /// Pops the next timer due at or before `target`, moving the clock to
+    /// its due time.
+    fn fire_next(&self, target: u64) -> Option<Value> {
+        let mut scheduled = self.scheduled.borrow_mut();
+
+        let index = scheduled
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| t.due <= target)
+            .min_by_key(|(_, t)| (t.due, t.id))
+            .map(|(i, _)| i)?;
+
+        let due = scheduled[index].due;
+        self.now.set(due);
+
+        let callback = scheduled[index].callback.clone();
+
+        match scheduled[index].interval {
+            // Zero-length intervals still move forward to avoid spinning forever.
+            Some(period) => scheduled[index].due = due + period.max(1),
+            None => {
+                scheduled.remove(index);
+            }
+        }
+
+        Some(callback)
+    }
+}
+
+/* ============================================================================
+ * Global `Clock` Object
+ * ============================================================================
+ */
+
+/// Creates the global `Clock` object bound to a virtual clock.
+///
+/// # Installed Functions
+/// - `Clock.now()`        → current virtual time (ms)
+/// - `Clock.advance(ms)`  → move time forward, firing due timers
+/// - `Clock.pending()`    → number of timers waiting to fire
+///
+/// # PAWX Example
+/// ```pawx
+/// snuggle fired = false;
+/// setTimeout(() -> { fired = true }, 500);
+/// Clock.advance(499);   // fired == false
+/// Clock.advance(1);     // fired == true
+/// ```
+pub fn create_global_clock_value(clock: &VirtualClock) -> Value {
+    let mut fields = HashMap::new();
+
+    let c = clock.clone();
+    fields.insert(
+        "now".to_string(),
+        Value::NativeFunction(Arc::new(move |_args: Vec<Value>| -> Result<Value, PawxError> {
+            Ok(Value::Number(c.now() as f64))
+        })),
+    );
+
+    let c = clock.clone();
+    fields.insert(
+        "advance".to_string(),
+        Value::NativeFunction(Arc::new(move |args: Vec<Value>| -> Result<Value, PawxError> {
+            let ms = match args.first().and_then(crate::prototypes::duration::millis) {
+                Some(n) if n >= 0.0 => n as u64,
+                _ => return raise_error("Clock.advance(ms) requires a non-negative number or Duration"),
+            };
+
+            c.advance(ms)?;
+            Ok(Value::Number(c.now() as f64))
+        })),
+    );
+
+    let c = clock.clone();
+    fields.insert(
+        "pending".to_string(),
+        Value::NativeFunction(Arc::new(move |_args: Vec<Value>| -> Result<Value, PawxError> {
+            Ok(Value::Number(c.pending() as f64))
+        })),
+    );
+
+    Value::Object {
+        fields: Rc::new(RefCell::new(fields)),
+    }
+}
@@ -0,0 +1,451 @@
+/*
+ * ============================================================================
+ * PAWX - Code with Claws!
+ * ============================================================================
+ *
+ * Author:   Sam Wilcox
+ * Email:    sam@pawx-lang.com
+ * Website:  https://www.pawx-lang.com
+ * Github:   https://github.com/samwilcox/pawx
+ *
+ * License:
+ * This file is part of the PAWX programming language project.
+ *
+ * PAWX is dual-licensed under the terms of:
+ *   - The MIT license
+ *   - The Apache License, Version 2.0
+ *
+ * You may choose either license to govern your use of this software.
+ * Full license text available at:
+ *    https://license.pawx-lang.com
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under these licenses is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *
+ * ============================================================================
+ */
+
+/*!
+ * PAWX Conditional Compilation
+ * ----------------------------
+ *
+ * `when` blocks are decided **before** the program runs:
+ *
+ * ```pawx
+ * when ENV == "production" {
+ *     snuggle apiUrl = "https://api.example.com";
+ * } else {
+ *     snuggle apiUrl = "http://localhost:3000";
+ *     meow("debug build");
+ * }
+ * ```
+ *
+ * Names in a condition are looked up in the `--define NAME=VALUE` flags
+ * first, then in the environment variables; an unknown name is `null`.
+ * Conditions may only use names, literals, `==`, `!=`, `!`, `&&`, `||`
+ * and parentheses, so the result never depends on program state.
+ *
+ * [`resolve`] replaces every `when` in the tree, including those in
+ * function, method and lambda bodies, with the statements of its chosen
+ * branch. Declarations inside a `when` therefore belong to the enclosing
+ * scope, and the untaken branch is gone from the AST entirely, ready to be
+ * dropped from bundles.
+ */
+
+use std::collections::HashMap;
+use std::env;
+
+use crate::ast::class::ClassMember;
+use crate::ast::{Expr, Stmt};
+use crate::error::PawxError;
+use crate::value::Value;
+
+/// Values for names used in `when` conditions.
+#[derive(Debug, Clone, Default)]
+pub struct Defines {
+    values: HashMap<String, String>,
+}
+
+impl Defines {
+    /// Creates a set of defines from `(name, value)` pairs.
+    pub fn new(values: impl IntoIterator<Item = (String, String)>) -> Self {
+        Self {
+            values: values.into_iter().collect(),
+        }
+    }
+
+    /// Looks up `name` in the defines, then the environment.
+    pub fn get(&self, name: &str) -> Option<String> {
+        self.values.get(name).cloned().or_else(|| env::var(name).ok())
+    }
+}
+
+/* ============================================================================
+ * Condition Evaluation
+ * ============================================================================
+ */
+
+fn is_truthy(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::Bool(b) => *b,
+        Value::Number(n) => *n != 0.0,
+        Value::String(s) => !s.is_empty() && &**s != "false" && &**s != "0",
+        _ => true,
+    }
+}
+
+fn same(left: &Value, right: &Value) -> bool {
+    match (left, right) {
+        (Value::Null, Value::Null) => true,
+        (Value::Bool(a), Value::Bool(b)) => a == b,
+        (Value::Number(a), Value::Number(b)) => a == b,
+        (Value::String(a), Value::String(b)) => a == b,
+
+        // Defines are strings, so `PORT == 80` compares against "80".
+        (Value::String(s), Value::Number(n)) | (Value::Number(n), Value::String(s)) => {
+            s.parse::<f64>().is_ok_and(|v| v == *n)
+        }
+        (Value::String(s), Value::Bool(b)) | (Value::Bool(b), Value::String(s)) => *s == b.to_string().into(),
+
+        _ => false,
+    }
+}
+
+fn eval(expr: &Expr, defines: &Defines) -> Result<Value, String> {
+    match expr {
+        Expr::Literal { value, .. } => match value {
+            Value::Null | Value::Bool(_) | Value::Number(_) | Value::String(_) => Ok(value.clone()),
+            _ => Err("only string, number, boolean and null literals are allowed".to_string()),
+        },
+
+        Expr::Identifier { name, .. } => Ok(defines.get(name).map(|v| Value::String(v.into())).unwrap_or(Value::Null)),
+
+        Expr::Grouping { expr, .. } => eval(expr, defines),
+
+        Expr::Unary { operator, right, .. } if operator.lexeme == "!" => {
+            Ok(Value::Bool(!is_truthy(&eval(right, defines)?)))
+        }
+
+        Expr::Binary { left, operator, right, .. } if operator.lexeme == "==" || operator.lexeme == "!=" => {
+            let equal = same(&eval(left, defines)?, &eval(right, defines)?);
+            Ok(Value::Bool(equal == (operator.lexeme == "==")))
+        }
+
+        Expr::Logical { left, operator, right, .. } => {
+            let left = is_truthy(&eval(left, defines)?);
+            let value = match operator.lexeme.as_str() {
+                "&&" => left && is_truthy(&eval(right, defines)?),
+                "||" => left || is_truthy(&eval(right, defines)?),
+                other => return Err(format!("unsupported operator '{}'", other)),
+            };
+            Ok(Value::Bool(value))
+        }
+
+        _ => Err("conditions may only use names, literals, ==, !=, !, && and ||".to_string()),
+    }
+}
+
+/// Decides a `when` condition.
+///
+/// # Errors
+/// If the condition uses anything other than names, literals, `==`, `!=`,
+/// `!`, `&&`, `||` and parentheses.
+pub fn evaluate(condition: &Expr, defines: &Defines) -> Result<bool, String> {
+    eval(condition, defines).map(|v| is_truthy(&v))
+}
+
+/* ============================================================================
+ * AST Resolution
+ * ============================================================================
+ */
+
+/// Replaces every `when` block in `statements` with its chosen branch.
+///
+/// # Errors
+/// A syntax error at the condition if a `when` condition is not a
+/// compile-time expression
+pub fn resolve(statements: Vec<Stmt>, defines: &Defines) -> Result<Vec<Stmt>, PawxError> {
+    let mut out = Vec::with_capacity(statements.len());
+
+    for stmt in statements {
+        match stmt {
+            Stmt::When {
+                condition,
+                then_branch,
+                else_branch,
+                ..
+            } => {
+                let taken = evaluate(&condition, defines).map_err(|e| {
+                    PawxError::syntax_error(format!("invalid 'when' condition: {}", e), condition.span())
+                })?;
+
+                let branch = if taken { then_branch } else { else_branch.unwrap_or_default() };
+                out.extend(resolve(branch, defines)?);
+            }
+
+            other => out.push(resolve_stmt(other, defines)?),
+        }
+    }
+
+    Ok(out)
+}
+
+fn resolve_stmt(stmt: Stmt, defines: &Defines) -> Result<Stmt, PawxError> {
+    let block = |body: Vec<Stmt>| resolve(body, defines);
+
+    Ok(match stmt {
+        Stmt::Expression(expr) => Stmt::Expression(resolve_expr(expr, defines)?),
+
+        Stmt::PublicVar { name, value, span } => Stmt::PublicVar {
+            name,
+            value: resolve_expr(value, defines)?,
+            span,
+        },
+        Stmt::PrivateVar { name, value, span } => Stmt::PrivateVar {
+            name,
+            value: resolve_expr(value, defines)?,
+            span,
+        },
+        Stmt::ProtectedVar { name, value, span } => Stmt::ProtectedVar {
+            name,
+            value: resolve_expr(value, defines)?,
+            span,
+        },
+        Stmt::DestructureVar { names, value, span } => Stmt::DestructureVar {
+            names,
+            value: resolve_expr(value, defines)?,
+            span,
+        },
+
+        Stmt::Function {
+            name,
+            params,
+            body,
+            return_type,
+            is_async,
+            span,
+        } => Stmt::Function {
+            name,
+            params,
+            body: block(body)?,
+            return_type,
+            is_async,
+            span,
+        },
+
+        Stmt::Decorated { decorators, function, span } => Stmt::Decorated {
+            decorators,
+            function: Box::new(resolve_stmt(*function, defines)?),
+            span,
+        },
+
+        Stmt::Return { value, span } => Stmt::Return {
+            value: value.map(|v| resolve_expr(v, defines)).transpose()?,
+            span,
+        },
+
+        Stmt::If {
+            condition,
+            then_branch,
+            else_branch,
+            span,
+        } => Stmt::If {
+            condition: resolve_expr(condition, defines)?,
+            then_branch: block(then_branch)?,
+            else_branch: else_branch.map(block).transpose()?,
+            span,
+        },
+
+        Stmt::While { condition, body, span } => Stmt::While {
+            condition: resolve_expr(condition, defines)?,
+            body: block(body)?,
+            span,
+        },
+
+        Stmt::Try {
+            try_block,
+            catch_param,
+            catch_block,
+            finally_block,
+            span,
+        } => Stmt::Try {
+            try_block: block(try_block)?,
+            catch_param,
+            catch_block: catch_block.map(block).transpose()?,
+            finally_block: finally_block.map(block).transpose()?,
+            span,
+        },
+
+        Stmt::Match { subject, mut arms, span } => {
+            for arm in &mut arms {
+                arm.body = resolve(std::mem::take(&mut arm.body), defines)?;
+            }
+            Stmt::Match {
+                subject: resolve_expr(subject, defines)?,
+                arms,
+                span,
+            }
+        }
+
+        Stmt::Clowder {
+            name,
+            base,
+            interfaces,
+            members,
+            is_exported,
+            is_default,
+            span,
+        } => Stmt::Clowder {
+            name,
+            base,
+            interfaces,
+            members: members
+                .into_iter()
+                .map(|m| resolve_member(m, defines))
+                .collect::<Result<_, _>>()?,
+            is_exported,
+            is_default,
+            span,
+        },
+
+        Stmt::Export { name, value, span } => Stmt::Export {
+            name,
+            value: resolve_expr(value, defines)?,
+            span,
+        },
+
+        Stmt::Pride { name, body, span } => Stmt::Pride { name, body: block(body)?, span },
+
+        Stmt::Test { name, body, mode, span } => Stmt::Test { name, body: block(body)?, mode, span },
+
+        other => other,
+    })
+}
+
+fn resolve_member(member: ClassMember, defines: &Defines) -> Result<ClassMember, PawxError> {
+    Ok(match member {
+        ClassMember::Method {
+            name,
+            access,
+            is_static,
+            params,
+            return_type,
+            body,
+        } => ClassMember::Method {
+            name,
+            access,
+            is_static,
+            params,
+            return_type,
+            body: resolve(body, defines)?,
+        },
+
+        ClassMember::Getter { name, return_type, body } => ClassMember::Getter {
+            name,
+            return_type,
+            body: resolve(body, defines)?,
+        },
+
+        ClassMember::Setter {
+            name,
+            param_name,
+            param_type,
+            body,
+        } => ClassMember::Setter {
+            name,
+            param_name,
+            param_type,
+            body: resolve(body, defines)?,
+        },
+
+        field => field,
+    })
+}
+
+/// Resolves `when` blocks inside lambda bodies nested in `expr`.
+fn resolve_expr(expr: Expr, defines: &Defines) -> Result<Expr, PawxError> {
+    let sub = |e: Box<Expr>| resolve_expr(*e, defines).map(Box::new);
+    let list = |es: Vec<Expr>| es.into_iter().map(|e| resolve_expr(e, defines)).collect::<Result<_, _>>();
+
+    Ok(match expr {
+        Expr::Lambda { params, body, span } => Expr::Lambda {
+            params,
+            body: resolve(body, defines)?,
+            span,
+        },
+
+        Expr::Assign { name, value, span } => Expr::Assign { name, value: sub(value)?, span },
+
+        Expr::Binary { left, operator, right, span } => Expr::Binary {
+            left: sub(left)?,
+            operator,
+            right: sub(right)?,
+            span,
+        },
+
+        Expr::Logical { left, operator, right, span } => Expr::Logical {
+            left: sub(left)?,
+            operator,
+            right: sub(right)?,
+            span,
+        },
+
+        Expr::Unary { operator, right, span } => Expr::Unary { operator, right: sub(right)?, span },
+
+        Expr::Call { callee, arguments, span } => Expr::Call {
+            callee: sub(callee)?,
+            arguments: list(arguments)?,
+            span,
+        },
+
+        Expr::NamedArgument { name, value, span } => Expr::NamedArgument { name, value: sub(value)?, span },
+
+        Expr::Get { object, name, span } => Expr::Get { object: sub(object)?, name, span },
+
+        Expr::Set { object, name, value, span } => Expr::Set {
+            object: sub(object)?,
+            name,
+            value: sub(value)?,
+            span,
+        },
+
+        Expr::Index { object, index, span } => Expr::Index {
+            object: sub(object)?,
+            index: sub(index)?,
+            span,
+        },
+
+        Expr::IndexAssign { object, index, value, span } => Expr::IndexAssign {
+            object: sub(object)?,
+            index: sub(index)?,
+            value: sub(value)?,
+            span,
+        },
+
+        Expr::ArrayLiteral { values, span } => Expr::ArrayLiteral { values: list(values)?, span },
+
+        Expr::ObjectLiteral { fields, span } => Expr::ObjectLiteral {
+            fields: fields
+                .into_iter()
+                .map(|(k, v)| Ok((k, resolve_expr(v, defines)?)))
+                .collect::<Result<_, PawxError>>()?,
+            span,
+        },
+
+        Expr::New { class_name, arguments, span } => Expr::New {
+            class_name,
+            arguments: list(arguments)?,
+            span,
+        },
+
+        Expr::Tuple { values, span } => Expr::Tuple { values: list(values)?, span },
+
+        Expr::Grouping { expr, span } => Expr::Grouping { expr: sub(expr)?, span },
+
+        Expr::Nap { expr, span } => Expr::Nap { expr: sub(expr)?, span },
+
+        other => other,
+    })
+}
@@ -0,0 +1,76 @@
+/*
+ * ============================================================================
+ * PAWX - Code with Claws!
+ * ============================================================================
+ *
+ * Author:   Sam Wilcox
+ * Email:    sam@pawx-lang.com
+ * Website:  https://www.pawx-lang.com
+ * Github:   https://github.com/samwilcox/pawx
+ *
+ * License:
+ * This file is part of the PAWX programming language project.
+ *
+ * PAWX is dual-licensed under the terms of:
+ *   - The MIT license
+ *   - The Apache License, Version 2.0
+ *
+ * You may choose either license to govern your use of this software.
+ * Full license text available at:
+ *    https://license.pawx-lang.com
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under these licenses is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *
+ * ============================================================================
+ */
+
+/*!
+ * PAWX Deterministic Mode
+ * -----------------------
+ *
+ * With `--deterministic`, running the same program twice produces the same
+ * output byte for byte, which is what reproducible pipelines and golden
+ * tests need:
+ *
+ *  • Math.random() draws from a generator seeded with `--seed` (default 0)
+ *  • object keys are visited in sorted order wherever a program can observe
+ *    them (printing, Json.stringify, Object.keys / values / entries)
+ *  • Time, Date and the timer functions run on the virtual clock, as with
+ *    `--virtual-time`
+ *
+ * Object fields live in hash maps, whose order changes from run to run;
+ * sorting only where keys are observed keeps the fast path untouched when
+ * the mode is off.
+ *
+ * Cryptographic randomness (`Auth`) is never seeded.
+ */
+
+use std::cell::Cell;
+use std::collections::{BTreeMap, HashMap};
+
+thread_local! {
+    /// Seed of the running program, when deterministic mode is on.
+    static SEED: Cell<Option<u64>> = const { Cell::new(None) };
+}
+
+/// Turns deterministic mode on for the program running on this thread.
+pub fn enable(seed: u64) {
+    SEED.with(|s| s.set(Some(seed)));
+    crate::prototypes::math::seed_random(seed);
+}
+
+/// Returns the seed if deterministic mode is on.
+pub fn seed() -> Option<u64> {
+    SEED.with(Cell::get)
+}
+
+/// Returns the entries of `map` in the order a program should see them:
+/// sorted by key in deterministic mode, hash order otherwise.
+pub fn entries<V>(map: &HashMap<String, V>) -> Vec<(&String, &V)> {
+    match seed() {
+        Some(_) => map.iter().collect::<BTreeMap<_, _>>().into_iter().collect(),
+        None => map.iter().collect(),
+    }
+}
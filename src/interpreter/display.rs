@@ -39,6 +39,7 @@
  */
 
 use crate::value::Value;
+use crate::interpreter::{determinism, limits};
 use std::cell::RefCell;
 use std::rc::Rc;
 use std::collections::HashMap;
@@ -63,40 +64,101 @@ use std::collections::HashMap;
 ///   - Function         → "[function]"
 ///   - Class            → "[class Cat]"
 ///   - Instance         → "[instance Cat]"
+///
+/// Containers nested deeper than `--max-depth` are shown as `[...]`, so
+/// building a message from a value never fails.
 /// ============================================================================
 pub fn value_to_string(val: &Value) -> String {
-    match val {
-        // ------------------------
-        // Primitive Types
-        // ------------------------
+    write_value(val, false).unwrap_or_default()
+}
 
-        Value::String(s) => s.clone(),
-        Value::Number(n) => n.to_string(),
-        Value::Bool(b) => b.to_string(),
-        Value::Null => "null".to_string(),
+/// Like [`value_to_string`], for output the program asked for (`meow`):
+/// a value nested deeper than `--max-depth` is an error instead of being
+/// cut short.
+pub fn try_value_to_string(val: &Value) -> Result<String, String> {
+    write_value(val, true)
+}
 
-        // ------------------------
-        // Regex
-        // ------------------------
+fn write_value(val: &Value, strict: bool) -> Result<String, String> {
+    let mut out = String::new();
+
+    // Pieces still to write, next one last. Containers push their parts
+    // here instead of recursing, so deep values cannot overflow the stack.
+    let mut pending = vec![Piece::Value(val.clone(), 0)];
+
+    while let Some(piece) = pending.pop() {
+        match piece {
+            Piece::Text(text) => out.push_str(text),
+            Piece::Key(key) => {
+                out.push_str(&key);
+                out.push_str(": ");
+            }
+            Piece::Value(value, depth) => {
+                if strict && is_container(&value) {
+                    limits::check_nesting(depth + 1, "printing")?;
+                }
+                write_display(&value, depth, &mut out, &mut pending);
+            }
+        }
+    }
+
+    Ok(out)
+}
+
+fn is_container(val: &Value) -> bool {
+    matches!(
+        val,
+        Value::Array { .. } | Value::Object { .. } | Value::Tuple(_) | Value::ImmutableList(_) | Value::ImmutableMap(_)
+    )
+}
+
+/// Part of a value still to be written by [`value_to_string`].
+enum Piece {
+    Text(&'static str),
+    Key(String),
+    /// A value and how many containers it is nested in.
+    Value(Value, usize),
+}
+
+/// Queues `open`, the elements separated by `", "`, then `close`.
+///
+/// Containers nested deeper than `--max-depth` are shown as `[...]`.
+fn queue_items(
+    open: &'static str,
+    items: Vec<(Option<String>, Value)>,
+    close: &'static str,
+    depth: usize,
+    out: &mut String,
+    pending: &mut Vec<Piece>,
+) {
+    if depth + 1 > limits::max_depth() {
+        out.push_str("[...]");
+        return;
+    }
 
-        Value::Regex(r) => format!("/{}/", r.as_str()),
+    out.push_str(open);
+    pending.push(Piece::Text(close));
 
+    for (i, (key, value)) in items.into_iter().enumerate().rev() {
+        pending.push(Piece::Value(value, depth + 1));
+        if let Some(key) = key {
+            pending.push(Piece::Key(key));
+        }
+        if i > 0 {
+            pending.push(Piece::Text(", "));
+        }
+    }
+}
+
+fn write_display(val: &Value, depth: usize, out: &mut String, pending: &mut Vec<Piece>) {
+    let items = |values: &[Value]| values.iter().map(|v| (None, v.clone())).collect::<Vec<_>>();
+
+    match val {
         // ------------------------
         // Arrays
         // ------------------------
 
-        Value::Array { values, .. } => {
-            let borrowed = values.borrow();
-            let mut out = String::from("[");
-            for (i, v) in borrowed.iter().enumerate() {
-                if i > 0 {
-                    out.push_str(", ");
-                }
-                out.push_str(&value_to_string(v));
-            }
-            out.push(']');
-            out
-        }
+        Value::Array { values, .. } => queue_items("[", items(&values.borrow()), "]", depth, out, pending),
 
         // ------------------------
         // Objects
@@ -104,38 +166,61 @@ pub fn value_to_string(val: &Value) -> String {
 
         Value::Object { fields } => {
             let map = fields.borrow();
-            let mut out = String::from("{ ");
-            let mut first = true;
+            let entries = determinism::entries(&map)
+                .into_iter()
+                .map(|(k, v)| (Some(k.clone()), v.clone()))
+                .collect();
+            queue_items("{ ", entries, " }", depth, out, pending);
+        }
 
-            for (k, v) in map.iter() {
-                if !first {
-                    out.push_str(", ");
-                }
-                first = false;
-                out.push_str(k);
-                out.push_str(": ");
-                out.push_str(&value_to_string(v));
-            }
+        // ------------------------
+        // Tuples
+        // ------------------------
+
+        Value::Tuple(values) => queue_items("(", items(values), ")", depth, out, pending),
+
+        // ------------------------
+        // Immutable Collections
+        // ------------------------
+
+        Value::ImmutableList(list) => {
+            let values: Vec<Value> = list.iter().cloned().collect();
+            queue_items("List[", items(&values), "]", depth, out, pending);
+        }
 
-            out.push_str(" }");
-            out
+        Value::ImmutableMap(map) => {
+            let entries = map.iter().map(|(k, v)| (Some(k.clone()), v.clone())).collect();
+            queue_items("Map{ ", entries, " }", depth, out, pending);
         }
 
+        other => out.push_str(&scalar_to_string(other)),
+    }
+}
+
+/// Formats a value that holds no other values.
+fn scalar_to_string(val: &Value) -> String {
+    match val {
         // ------------------------
-        // Tuples
+        // Primitive Types
         // ------------------------
 
-        Value::Tuple(values) => {
-            let mut out = String::from("(");
-            for (i, v) in values.iter().enumerate() {
-                if i > 0 {
-                    out.push_str(", ");
-                }
-                out.push_str(&value_to_string(v));
-            }
-            out.push(')');
-            out
+        Value::String(s) => s.to_string(),
+        Value::Number(n) => n.to_string(),
+        Value::Decimal(d) => d.to_string(),
+        Value::Duration(ms) => crate::prototypes::duration::format_duration(*ms),
+
+        Value::Float64Array(data) => {
+            let inner = data.borrow().iter().map(|n| n.to_string()).collect::<Vec<_>>().join(", ");
+            format!("Float64Array[{}]", inner)
         }
+        Value::Bool(b) => b.to_string(),
+        Value::Null => "null".to_string(),
+
+        // ------------------------
+        // Regex
+        // ------------------------
+
+        Value::Regex(r) => format!("/{}/{}", r.as_str(), r.flags()),
 
         // ------------------------
         // Runtime Types
@@ -143,19 +228,30 @@ pub fn value_to_string(val: &Value) -> String {
 
         Value::NativeFunction(_) => "[function]".to_string(),
 
-        Value::Class { name, .. } => format!("[class {}]", name),
+        Value::Class(class) => format!("[class {}]", class.name),
+
+        Value::Instinct(instinct) => format!("[instinct {}]", instinct.name),
 
-        Value::Instance { class_name, .. } => {
-            format!("[instance {}]", class_name)
+        Value::Instance { class, .. } => {
+            format!("[instance {}]", class.name)
         }
 
-        Value::Furure(_) => "[future]".to_string(),
+        Value::Furure(_) | Value::PendingFurure(_) => "[future]".to_string(),
 
         Value::Error { message } => format!("Error({})", message),
 
         Value::Module { exports, .. } => {
             format!("[module {} exports]", exports.len())
         }
+
+        Value::CancelToken(_) => "[CancelToken]".to_string(),
+
+        // Containers are written by `write_display`
+        Value::Array { .. }
+        | Value::Object { .. }
+        | Value::Tuple(_)
+        | Value::ImmutableList(_)
+        | Value::ImmutableMap(_) => unreachable!("containers are queued, not formatted"),
     }
 }
 
@@ -165,8 +261,10 @@ pub fn value_to_string(val: &Value) -> String {
 ///   - API responses
 ///   - Debug serialization
 ///
-/// Unsupported runtime values (functions, classes, instances) are serialized
-/// as placeholder strings.
+/// Objects with a `toJson` function and class instances pass through their
+/// `toJson()` hook first (see `prototypes::json`). Other unsupported runtime
+/// values (functions, classes) are serialized as placeholder strings. The
+/// encoding itself lives in `prototypes::json_writer`.
 ///
 /// Examples:
 ///   - Number(3)        → "3"
@@ -175,85 +273,125 @@ pub fn value_to_string(val: &Value) -> String {
 ///   - Null            → "null"
 ///   - Array           → "[1,2,3]"
 ///   - Object          → "{\"x\":1,\"y\":2}"
+///
+/// Fails with a message if the value is nested deeper than `--max-depth`.
 /// ============================================================================
-pub fn value_to_json(val: &Value) -> String {
-    match val {
-        // ------------------------
-        // JSON Primitives
-        // ------------------------
+pub fn value_to_json(val: &Value) -> Result<String, String> {
+    crate::prototypes::json_writer::to_json(val)
+}
+/// ============================================================================
+/// value_to_pretty
+/// ============================================================================
+/// Converts a PAWX runtime `Value` into a **stable, multi-line string**.
+/// This is used by:
+///   - Snapshot tests (`expect(value).toMatchSnapshot()`)
+///
+/// The output must not change between runs, so object, map and instance
+/// keys are sorted. Strings are quoted and escaped, and each element of a
+/// non-empty container goes on its own line, indented by two spaces.
+///
+/// Examples:
+///   - String("cat")   → "\"cat\""
+///   - Array([])       → "[]"
+///   - Array([1, 2])   → "[\n  1,\n  2,\n]"
+///   - Instance        → "Cat {\n  name: \"Tom\",\n}"
+/// ============================================================================
+pub fn value_to_pretty(val: &Value) -> String {
+    let mut out = String::new();
+    write_pretty(val, 0, &mut out);
+    out
+}
 
-        Value::Number(n) => n.to_string(),
+/// Quotes an object key unless it is a plain identifier.
+fn pretty_key(key: &str) -> String {
+    let plain = key.chars().next().is_some_and(|c| c.is_alphabetic() || c == '_')
+        && key.chars().all(|c| c.is_alphanumeric() || c == '_');
 
-        Value::String(s) => {
-            // Proper JSON escaping
-            format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
-        }
+    if plain {
+        key.to_string()
+    } else {
+        serde_json::to_string(key).unwrap_or_default()
+    }
+}
 
-        Value::Bool(b) => b.to_string(),
-        Value::Null => "null".to_string(),
+/// Writes `open`, one `item` per line, then `close`.
+fn write_pretty_items<T>(
+    open: &str,
+    close: &str,
+    items: &[T],
+    indent: usize,
+    out: &mut String,
+    mut item: impl FnMut(&T, &mut String),
+) {
+    out.push_str(open);
+    if items.is_empty() {
+        out.push_str(close);
+        return;
+    }
 
-        // ------------------------
-        // JSON Regex (serialized as string)
-        // ------------------------
+    out.push('\n');
+    for it in items {
+        out.push_str(&"  ".repeat(indent + 1));
+        item(it, out);
+        out.push_str(",\n");
+    }
+    out.push_str(&"  ".repeat(indent));
+    out.push_str(close);
+}
 
-        Value::Regex(r) => {
-            format!("\"/{}/\"", r.as_str())
-        }
+fn write_pretty_fields(open: &str, fields: &HashMap<String, Value>, indent: usize, out: &mut String) {
+    let mut entries: Vec<(&String, &Value)> = fields.iter().collect();
+    entries.sort_by(|a, b| a.0.cmp(b.0));
 
-        // ------------------------
-        // JSON Arrays
-        // ------------------------
+    write_pretty_items(open, "}", &entries, indent, out, |(k, v), out| {
+        out.push_str(&pretty_key(k));
+        out.push_str(": ");
+        write_pretty(v, indent + 1, out);
+    });
+}
 
-        Value::Array { values, .. } => {
-            let arr = values.borrow();
-            let inner: Vec<String> = arr.iter().map(value_to_json).collect();
-            format!("[{}]", inner.join(","))
-        }
+fn write_pretty(val: &Value, indent: usize, out: &mut String) {
+    let values = |values: &[Value], open: &str, close: &str, out: &mut String| {
+        write_pretty_items(open, close, values, indent, out, |v, out| write_pretty(v, indent + 1, out));
+    };
 
-        // ------------------------
-        // JSON Objects
-        // ------------------------
+    match val {
+        Value::String(s) => out.push_str(&serde_json::to_string(&**s).unwrap_or_default()),
 
-        Value::Object { fields } => {
-            let map = fields.borrow();
-            let mut parts = Vec::new();
-
-            for (k, v) in map.iter() {
-                parts.push(format!(
-                    "\"{}\":{}",
-                    k.replace('\\', "\\\\").replace('"', "\\\""),
-                    value_to_json(v)
-                ));
-            }
+        Value::Array { values: items, .. } => values(&items.borrow(), "[", "]", out),
+
+        Value::Tuple(items) => values(items, "(", ")", out),
 
-            format!("{{{}}}", parts.join(","))
+        Value::ImmutableList(list) => {
+            let items: Vec<Value> = list.iter().cloned().collect();
+            values(&items, "List [", "]", out);
         }
 
-        // ------------------------
-        // Unsupported JSON Values
-        // ------------------------
+        Value::Float64Array(data) => {
+            let items: Vec<Value> = data.borrow().iter().map(|n| Value::Number(*n)).collect();
+            values(&items, "Float64Array [", "]", out);
+        }
 
-        Value::NativeFunction(_) => "\"[function]\"".to_string(),
+        Value::Object { fields } => write_pretty_fields("{", &fields.borrow(), indent, out),
 
-        Value::Class { name, .. } => {
-            format!("\"[class {}]\"", name)
+        Value::Instance { class, fields } => {
+            write_pretty_fields(&format!("{} {{", class.name), &fields.borrow(), indent, out)
         }
 
-        Value::Instance { class_name, .. } => {
-            format!("\"[instance {}]\"", class_name)
+        // OrdMap iterates in key order already.
+        Value::ImmutableMap(map) => {
+            let entries: Vec<(&String, &Value)> = map.iter().collect();
+            write_pretty_items("Map {", "}", &entries, indent, out, |(k, v), out| {
+                out.push_str(&pretty_key(k));
+                out.push_str(": ");
+                write_pretty(v, indent + 1, out);
+            });
         }
 
-        Value::Furure(_) => "\"[future]\"".to_string(),
-
-        Value::Module { .. } => "\"[module]\"".to_string(),
-
         Value::Error { message } => {
-            format!("\"Error({})\"", message.replace('"', "\\\""))
+            out.push_str(&format!("Error({})", serde_json::to_string(message).unwrap_or_default()))
         }
 
-        Value::Tuple(values) => {
-            let inner: Vec<String> = values.iter().map(value_to_json).collect();
-            format!("[{}]", inner.join(","))
-        }
+        other => out.push_str(&value_to_string(other)),
     }
-}
\ No newline at end of file
+}
@@ -49,7 +49,7 @@ use std::cell::RefCell;
 use std::collections::HashMap;
 use std::rc::Rc;
 
-use crate::ast::{Param, Stmt};
+use crate::ast::arena::{Ast, ParamList, ParamNode, StmtList};
 use crate::value::Value;
 
 #[derive(Debug, Clone, PartialEq)]
@@ -65,21 +65,51 @@ pub struct EnvEntry {
     pub access: Access,
 }
 
+/// A user-defined function: its parameters and body inside `ast`.
+///
+/// Cloning shares the arena, so it copies no AST.
 #[derive(Debug, Clone)]
 pub struct FunctionDef {
-    pub params: Vec<Param>,
-    pub body: Vec<Stmt>,
+    pub ast: Rc<Ast>,
+    pub params: ParamList,
+    pub body: StmtList,
     pub return_type: Option<String>,
     pub is_async: bool,
     pub(crate) name_span: crate::span::Span,
 }
 
+impl FunctionDef {
+    /// The function's parameters, in order.
+    pub fn params(&self) -> &[ParamNode] {
+        &self.ast[self.params]
+    }
+}
+
+/// A global whose value is only built the first time it is read.
+#[derive(Clone)]
+pub enum LazyValue {
+    Pending(Rc<dyn Fn() -> Value>),
+    Ready(Value),
+}
+
+impl std::fmt::Debug for LazyValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LazyValue::Pending(_) => f.write_str("Pending"),
+            LazyValue::Ready(value) => f.debug_tuple("Ready").field(value).finish(),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Environment {
     pub values: HashMap<String, EnvEntry>,
     pub functions: HashMap<String, FunctionDef>,
     parent: Option<Rc<RefCell<Environment>>>,
     pub timers: HashMap<u64, Value>,
+
+    /// Public values built on first read (see [`Environment::define_lazy`]).
+    lazy: RefCell<HashMap<String, LazyValue>>,
 }
 
 impl Environment {
@@ -89,11 +119,55 @@ impl Environment {
             functions: HashMap::new(),
             timers: HashMap::new(),   // ✅ REQUIRED FIX
             parent,
+            lazy: RefCell::new(HashMap::new()),
+        }
+    }
+
+    /// Creates a scope with room for `capacity` values, e.g. a function's
+    /// parameters.
+    pub fn with_capacity(parent: Option<Rc<RefCell<Environment>>>, capacity: usize) -> Self {
+        Self {
+            values: HashMap::with_capacity(capacity),
+            ..Self::new(parent)
         }
     }
 
+    /// Empties the scope for reuse under a new parent, keeping the
+    /// capacity of its value map.
+    pub(crate) fn reset(&mut self, parent: Option<Rc<RefCell<Environment>>>, capacity: usize) {
+        self.values.clear();
+        self.values.reserve(capacity);
+        self.functions.clear();
+        self.timers.clear();
+        self.lazy.get_mut().clear();
+        self.parent = parent;
+    }
+
+    /// Defines a public value that `init` builds the first time the name
+    /// is read. A later definition or assignment replaces it unbuilt.
+    pub fn define_lazy(&mut self, name: String, init: impl Fn() -> Value + 'static) {
+        self.values.remove(&name);
+        self.lazy.get_mut().insert(name, LazyValue::Pending(Rc::new(init)));
+    }
+
+    /// Builds (once) and returns the lazy value named `name`.
+    fn get_lazy(&self, name: &str) -> Option<Value> {
+        let pending = match self.lazy.borrow().get(name)? {
+            LazyValue::Ready(value) => return Some(value.clone()),
+            LazyValue::Pending(init) => init.clone(),
+        };
+
+        // Build without holding the borrow: `init` may read other globals.
+        let value = pending();
+        self.lazy
+            .borrow_mut()
+            .insert(name.to_string(), LazyValue::Ready(value.clone()));
+        Some(value)
+    }
+
     // pride = PUBLIC
     pub fn define_public(&mut self, name: String, value: Value) {
+        self.lazy.get_mut().remove(&name);
         self.values.insert(
             name,
             EnvEntry {
@@ -105,6 +179,7 @@ impl Environment {
 
     // den = PRIVATE
     pub fn define_private(&mut self, name: String, value: Value) {
+        self.lazy.get_mut().remove(&name);
         self.values.insert(
             name,
             EnvEntry {
@@ -116,6 +191,7 @@ impl Environment {
 
     // lair = PROTECTED
     pub fn define_protected(&mut self, name: String, value: Value) {
+        self.lazy.get_mut().remove(&name);
         self.values.insert(
             name,
             EnvEntry {
@@ -131,6 +207,11 @@ impl Environment {
             return true;
         }
 
+        if self.lazy.get_mut().remove(name).is_some() {
+            self.define_public(name.to_string(), value);
+            return true;
+        }
+
         if let Some(parent) = &self.parent {
             return parent.borrow_mut().assign(name, value);
         }
@@ -141,30 +222,39 @@ impl Environment {
     // Public    -> anywhere
     // Private   -> same scope only
     // Protected -> same scope + child scopes
+    //
+    // A private variable of an enclosing scope is treated as missing.
     pub fn get(&self, name: &str, is_child_scope: bool) -> Option<Value> {
+        self.lookup_from(name, is_child_scope).ok().flatten()
+    }
+
+    /// Looks `name` up from this scope outwards, failing with a message when
+    /// it is `den` (private) to an enclosing scope.
+    pub fn lookup(&self, name: &str) -> Result<Option<Value>, String> {
+        self.lookup_from(name, false)
+    }
+
+    fn lookup_from(&self, name: &str, is_child_scope: bool) -> Result<Option<Value>, String> {
         if let Some(entry) = self.values.get(name) {
-            match entry.access {
-                Access::Public => return Some(entry.value.clone()),
-
-                Access::Private => {
-                    if !is_child_scope {
-                        return Some(entry.value.clone());
-                    } else {
-                        panic!("Private access violation: '{}'", name);
-                    }
-                }
-
-                Access::Protected => {
-                    return Some(entry.value.clone());
-                }
-            }
+            return match entry.access {
+                Access::Private if is_child_scope => Err(format!(
+                    "'{}' is den (private) and can only be used in the scope that declares it",
+                    name
+                )),
+
+                Access::Public | Access::Private | Access::Protected => Ok(Some(entry.value.clone())),
+            };
+        }
+
+        if let Some(value) = self.get_lazy(name) {
+            return Ok(Some(value));
         }
 
         if let Some(parent) = &self.parent {
-            return parent.borrow().get(name, true);
+            return parent.borrow().lookup_from(name, true);
         }
 
-        None
+        Ok(None)
     }
 
     pub fn define_function(&mut self, name: String, func: FunctionDef) {
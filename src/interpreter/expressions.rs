@@ -52,14 +52,19 @@ use std::collections::HashMap;
 use std::rc::Rc;
 use std::sync::Arc;
 
-use crate::ast::Expr;
-use crate::error::PawxError;
+use crate::ast::arena::{Ast, ExprId, ExprList, ExprNode};
+use crate::span::Span;
+use crate::error::{ErrorCode, PawxError};
+use crate::interpreter::{current_span, hooks, limits, numeric};
 use crate::interpreter::environment::Environment;
 use crate::interpreter::environment::FunctionDef;
 use crate::value::Value;
 
 // Call dispatch (from calls.rs)
-use crate::interpreter::calls::{call_user_function, call_value};
+use crate::interpreter::calls::{
+    acquire_env, bind_arguments, call_user_function_slots, call_with_args, raise_error, release_env,
+    script_function,
+};
 
 // Array prototype
 use crate::prototypes::array::create_array_proto;
@@ -68,7 +73,7 @@ use crate::prototypes::array::create_array_proto;
 use crate::interpreter::{exec_stmt, ExecSignal};
 use crate::interpreter::helpers::is_truthy;
 
-fn resolve_furure(value: &Value) -> Value {
+fn resolve_furure(value: &Value) -> Result<Value, PawxError> {
     let mut current = value.clone();
 
     loop {
@@ -81,57 +86,70 @@ fn resolve_furure(value: &Value) -> Value {
                 return f(vec![]);
             }
 
-            other => return other,
+            other => return Ok(other),
         }
     }
 }
 
+/// The outcome of napping on a Furure settled with `value`.
+fn rethrow(value: Value, span: Span) -> Result<Value, PawxError> {
+    match value {
+        error @ Value::Error { .. } => Err(PawxError::thrown(error, span)),
+        value => Ok(value),
+    }
+}
+
 /// Evaluates a single PAWX expression and returns its runtime value.
 ///
 /// This is the **core expression dispatcher** used throughout the interpreter.
 /// Every expression in the language eventually passes through this function.
 ///
 /// # Arguments
-/// - `expr` → The AST expression to evaluate
+/// - `ast`  → The arena holding the expression
+/// - `id`   → The expression to evaluate
 /// - `env`  → The current runtime environment
 ///
 /// # Returns
 /// - The evaluated runtime `Value`
-pub fn eval_expr(expr: Expr, env: Rc<RefCell<Environment>>) -> Result<Value, PawxError> {
-    match expr {
+pub fn eval_expr(ast: &Rc<Ast>, id: ExprId, env: Rc<RefCell<Environment>>) -> Result<Value, PawxError> {
+    match &ast[id] {
         // ---------------------------------------------------------------------
         // Literal Values
         // ---------------------------------------------------------------------
-        Expr::Literal { value, .. } => Ok(value),
+        ExprNode::Literal { value, .. } => Ok(value.clone()),
 
         // ---------------------------------------------------------------------
         // Identifier Lookup
         // ---------------------------------------------------------------------
-        Expr::Identifier { name, .. } => {
+        ExprNode::Identifier { name, span } => {
             match name.as_str() {
                 "true"  => Ok(Value::Bool(true)),
                 "false" => Ok(Value::Bool(false)),
                 "null"  => Ok(Value::Null),
 
                 // `this`
-                "this" => Ok(env.borrow()
+                "this" => env.borrow()
                     .get("this", false)
-                    .unwrap_or_else(|| panic!("'this' used outside of class"))),
+                    .ok_or_else(|| PawxError::runtime_error("'this' used outside of a clowder", *span)),
+
+                // `super` is only meaningful as `super(...)` or `super.name`
+                "super" => Err(PawxError::runtime_error(
+                    "'super' must be called or followed by a property, as in super(...) or super.name",
+                    *span,
+                )),
 
                 // Normal variable lookup
-                _ => Ok(env.borrow()
-                    .get(&name, false)
-                    .unwrap_or_else(|| panic!("Undefined variable '{}'", name))),
+                _ => read_variable(&env, name, *span),
             }
         }
 
         // ---------------------------------------------------------------------
         // Tuple Literal
         // ---------------------------------------------------------------------
-        Expr::Tuple { values, .. } => {
+        ExprNode::Tuple { values, .. } => {
            let evaluated: Vec<Value> = values
-            .into_iter()
-            .map(|v| eval_expr(v, env.clone()))
+            .iter()
+            .map(|v| eval_expr(ast, v, env.clone()))
             .collect::<Result<Vec<_>, _>>()?;
 
             Ok(Value::Tuple(evaluated))
@@ -140,24 +158,18 @@ pub fn eval_expr(expr: Expr, env: Rc<RefCell<Environment>>) -> Result<Value, Paw
         // ---------------------------------------------------------------------
         // Assignment (simple identifier = expr)
         // ---------------------------------------------------------------------
-        Expr::Assign { name, value, span } => {
-            let assigned = eval_expr(*value, env.clone())?;
+        ExprNode::Assign { name, value, span } => {
+            let assigned = eval_expr(ast, *value, env.clone())?;
 
             // If we assign a module, automatically unwrap its default export
             if let Value::Module { default: Some(default_val), .. } = &assigned {
                 let real = *default_val.clone();
-                env.borrow_mut().define_public(name, real.clone());
+                env.borrow_mut().define_public(name.clone(), real.clone());
                 return Ok(real);
             }
 
-            if !env.borrow_mut().assign(&name, assigned.clone()) {
-                return Err(
-                    PawxError::new(
-                        "P0002",
-                        format!("undefined variable '{}'", name),
-                        span,
-                    )
-                );
+            if !env.borrow_mut().assign(name, assigned.clone()) {
+                return Err(undefined_variable(name, *span));
             }
 
             Ok(assigned)
@@ -166,21 +178,26 @@ pub fn eval_expr(expr: Expr, env: Rc<RefCell<Environment>>) -> Result<Value, Paw
         // ---------------------------------------------------------------------
         // Unary Operators
         // ---------------------------------------------------------------------
-        Expr::Unary { operator, right, span } => {
-            let r = eval_expr(*right, env)?;
+        ExprNode::Unary { operator, right, span } => {
+            let r = eval_expr(ast, *right, env)?;
 
             match (operator.lexeme.as_str(), &r) {
                 ("-", Value::Number(n)) => Ok(Value::Number(-n)),
+                ("-", Value::Decimal(d)) => Ok(Value::Decimal(-*d)),
+                ("-", Value::Duration(ms)) => Ok(Value::Duration(-*ms)),
                 ("!", Value::Bool(b)) => Ok(Value::Bool(!b)),
+                ("~", Value::Number(n)) => numeric::bitwise_not(*n).map(Value::Number).map_err(|message| {
+                    PawxError::new(ErrorCode::InvalidUnaryOperand, format!("invalid operation '~': {}", message), *span)
+                }),
 
                 _ => Err(PawxError::new(
-                    "P0003",
+                    ErrorCode::InvalidUnaryOperand,
                     format!(
                         "invalid unary operation '{}' on {}",
                         operator.lexeme,
                         r.type_name(),
                     ),
-                    span,
+                    *span,
                 )),
             }
         }
@@ -188,9 +205,35 @@ pub fn eval_expr(expr: Expr, env: Rc<RefCell<Environment>>) -> Result<Value, Paw
         // ---------------------------------------------------------------------
         // Binary Operators
         // ---------------------------------------------------------------------
-        Expr::Binary { left, operator, right, span } => {
-            let l = eval_expr(*left, env.clone())?;
-            let r = eval_expr(*right, env)?;
+        ExprNode::Binary { left, operator, right, span } => {
+            let l = eval_expr(ast, *left, env.clone())?;
+            let r = eval_expr(ast, *right, env)?;
+
+            // Decimal arithmetic and comparisons (mixing with Numbers allowed)
+            if matches!(l, Value::Decimal(_)) || matches!(r, Value::Decimal(_)) {
+                if let Some(result) = crate::prototypes::decimal::binary_op(&l, &r, &operator.lexeme) {
+                    return result.map_err(|message| {
+                        PawxError::new(
+                            ErrorCode::InvalidDecimalOperation,
+                            format!("invalid decimal operation '{}': {}", operator.lexeme, message),
+                            *span,
+                        )
+                    });
+                }
+            }
+
+            // Duration arithmetic, comparisons, and date (epoch ms) offsets
+            if matches!(l, Value::Duration(_)) || matches!(r, Value::Duration(_)) {
+                if let Some(result) = crate::prototypes::duration::binary_op(&l, &r, &operator.lexeme) {
+                    return result.map_err(|message| {
+                        PawxError::new(
+                            ErrorCode::InvalidBinaryOperand,
+                            format!("invalid duration operation '{}': {}", operator.lexeme, message),
+                            *span,
+                        )
+                    });
+                }
+            }
 
             match (l, r, operator.lexeme.as_str()) {
                 // -------------------------------
@@ -202,9 +245,33 @@ pub fn eval_expr(expr: Expr, env: Rc<RefCell<Environment>>) -> Result<Value, Paw
                 (Value::Number(a), Value::Number(b), "/") => Ok(Value::Number(a / b)),
                 (Value::Number(a), Value::Number(b), "%") => Ok(Value::Number(a % b)),
 
-                (Value::String(a), Value::String(b), "+") => Ok(Value::String(format!("{}{}", a, b))),
-                (Value::String(a), Value::Number(b), "+") => Ok(Value::String(format!("{}{}", a, b))),
-                (Value::Number(a), Value::String(b), "+") => Ok(Value::String(format!("{}{}", a, b))),
+                // Power, integer division and bitwise operators (see numeric.rs)
+                (Value::Number(a), Value::Number(b), op @ ("**" | "~/" | "&" | "|" | "^" | "<<" | ">>" | ">>>")) => {
+                    numeric::binary_op(a, b, op).map(Value::Number).map_err(|message| {
+                        PawxError::new(
+                            ErrorCode::InvalidBinaryOperand,
+                            format!("invalid operation '{}': {}", op, message),
+                            *span,
+                        )
+                    })
+                }
+
+                (Value::String(a), Value::String(b), "+") => Ok(Value::String(format!("{}{}", a, b).into())),
+                (Value::String(a), Value::Number(b), "+") => Ok(Value::String(format!("{}{}", a, b).into())),
+                (Value::Number(a), Value::String(b), "+") => Ok(Value::String(format!("{}{}", a, b).into())),
+
+                // -------------------------------
+                // Tuples and immutable collections compare structurally
+                // -------------------------------
+                (
+                    a @ (Value::Tuple(_) | Value::ImmutableList(_) | Value::ImmutableMap(_)),
+                    b,
+                    op @ ("==" | "!=" | "===" | "!=="),
+                ) => {
+                    let equal = Value::try_equals(&a, &b, op.len() == 3)
+                        .map_err(|message| PawxError::runtime_error(message, *span))?;
+                    Ok(Value::Bool(equal == op.starts_with('=')))
+                }
 
                 // -------------------------------
                 // Loose Equality (==)
@@ -259,17 +326,29 @@ pub fn eval_expr(expr: Expr, env: Rc<RefCell<Environment>>) -> Result<Value, Paw
                 (Value::Number(a), Value::Number(b), ">=") => Ok(Value::Bool(a >= b)),
                 (Value::Number(a), Value::Number(b), "<=") => Ok(Value::Bool(a <= b)),
 
+                // -------------------------------
+                // Class membership (instanceof)
+                // -------------------------------
+                (value, Value::Class(class), "instanceof") => Ok(Value::Bool(matches!(
+                    &value,
+                    Value::Instance { class: own, .. } if own.inherits_from(&class)
+                ))),
+                (value, Value::Instinct(instinct), "instanceof") => Ok(Value::Bool(matches!(
+                    &value,
+                    Value::Instance { class, .. } if class.practices(&instinct)
+                ))),
+
                 // -------------------------------
                 // Error fallback
                 // -------------------------------
                 (l, r, _) => Err(
                     PawxError::new(
-                        "P0004",
+                        ErrorCode::InvalidBinaryOperand,
                         format!(
                             "invalid binrary operation '{}'",
                             operator.lexeme
                         ),
-                        span,
+                        *span,
                     )
                     .with_help(format!(
                         "'{}' cannot be applied to {} and {}",
@@ -284,33 +363,54 @@ pub fn eval_expr(expr: Expr, env: Rc<RefCell<Environment>>) -> Result<Value, Paw
         // ---------------------------------------------------------------------
         // Function Calls
         // ---------------------------------------------------------------------
-        Expr::Call { callee, arguments, span } => {
-            match *callee {
+        ExprNode::Call { callee, arguments, span } => {
+            if hooks::active() {
+                let name = match &ast[*callee] {
+                    ExprNode::Identifier { name, .. } | ExprNode::Get { name, .. } => name.as_str(),
+                    _ => "<anonymous>",
+                };
+                hooks::call(name, *span);
+            }
+
+            match &ast[*callee] {
+                // Base class constructor: super(...)
+                ExprNode::Identifier { name, .. } if name == "super" => {
+                    crate::interpreter::classes::call_super_constructor(ast, *arguments, *span, env)
+                }
+
                 // Direct named call: foo(...)
-                Expr::Identifier { name, .. } => {
-                    // User-defined function
-                    if let Some(func) = env.borrow().get_function(&name) {
-                        let arg_vals: Vec<Value> = arguments
-                            .into_iter()
-                            .map(|a| eval_expr(a, env.clone()))
-                            .collect::<Result<Vec<_>, _>>()?;
-
-                        return Ok(call_user_function(func, arg_vals, env.clone())?);
+                ExprNode::Identifier { name, .. } => call_named(ast, name, *arguments, *span, env),
+
+                // Base class method: super.name(...)
+                ExprNode::Get { object, name, span: get_span }
+                    if matches!(&ast[*object], ExprNode::Identifier { name, .. } if name == "super") =>
+                {
+                    match crate::interpreter::classes::call_super_method(name, ast, *arguments, *span, env.clone())? {
+                        Some(value) => Ok(value),
+                        None => {
+                            let callee_val = crate::interpreter::classes::get_super_property(name.clone(), *get_span, env.clone())?;
+                            call_with_args(ast, callee_val, *arguments, env)
+                        }
                     }
+                }
 
-                    // Anything else callable by name (class, built-in, etc.)
-                    let callee_val = env
-                        .borrow()
-                        .get(&name, false)
-                        .unwrap_or_else(|| panic!("Undefined function or callable '{}'", name));
-
-                    Ok(call_value(callee_val, arguments, env.clone())?)
+                // Method call: obj.name(...), where clowder methods take
+                // named arguments too
+                ExprNode::Get { object, name, span: get_span } => {
+                    let target = eval_expr(ast, *object, env.clone())?;
+                    match crate::interpreter::classes::call_method(&target, name, ast, *arguments, *span, env.clone())? {
+                        Some(value) => Ok(value),
+                        None => {
+                            let callee_val = get_property(target, name.clone(), *get_span, env.clone())?;
+                            call_with_args(ast, callee_val, *arguments, env)
+                        }
+                    }
                 }
 
                 // Method calls & higher-order funcs
-                other => {
-                    let callee_val = eval_expr(other, env.clone())?;
-                    Ok(call_value(callee_val, arguments, env)?)
+                _ => {
+                    let callee_val = eval_expr(ast, *callee, env.clone())?;
+                    Ok(call_with_args(ast, callee_val, *arguments, env)?)
                 }
             }
         }
@@ -318,15 +418,31 @@ pub fn eval_expr(expr: Expr, env: Rc<RefCell<Environment>>) -> Result<Value, Paw
         // ---------------------------------------------------------------------
         // Grouping
         // ---------------------------------------------------------------------
-        Expr::Grouping { expr, .. } => eval_expr(*expr, env),
+        ExprNode::Grouping { expr, .. } => eval_expr(ast, *expr, env),
+
+        // ---------------------------------------------------------------------
+        // Nap (await)
+        //
+        // Unwraps a Furure, first running the event loop until a pending one
+        // settles: its value, or a throw of the error it holds (a failed or
+        // cancelled operation) that `catch` receives unchanged.
+        // ---------------------------------------------------------------------
+        ExprNode::Nap { expr, span } => match eval_expr(ast, *expr, env)? {
+            Value::Furure(inner) => rethrow(*inner, *span),
+            Value::PendingFurure(furure) => rethrow(crate::interpreter::wait_for(&furure)?, *span),
+            other => Err(PawxError::type_error(
+                format!("nap can only be used on a Future, got {}", other.type_name()),
+                *span,
+            )),
+        },
 
         // ---------------------------------------------------------------------
         // Array Literal
         // ---------------------------------------------------------------------
-        Expr::ArrayLiteral { values, span } => {
+        ExprNode::ArrayLiteral { values, span } => {
             let evaluated: Vec<Value> = values
-                .into_iter()
-                .map(|v| eval_expr(v, env.clone()))
+                .iter()
+                .map(|v| eval_expr(ast, v, env.clone()))
                 .collect::<Result<Vec<_>, _>>()?;
 
 
@@ -339,37 +455,57 @@ pub fn eval_expr(expr: Expr, env: Rc<RefCell<Environment>>) -> Result<Value, Paw
         // ---------------------------------------------------------------------
         // Index Read: arr[i]
         // ---------------------------------------------------------------------
-        Expr::Index { object, index, span } => {
-            let obj = eval_expr(*object, env.clone());
-            let idx = eval_expr(*index, env);
+        ExprNode::Index { object, index, span } => {
+            let obj = eval_expr(ast, *object, env.clone())?;
+            let idx = eval_expr(ast, *index, env)?;
 
             let i = match idx {
-                Ok(Value::Number(n)) => n as usize,
-                _ => panic!("Array index must be a number"),
+                Value::Number(n) => n as usize,
+                other => {
+                    return Err(PawxError::new(
+                        ErrorCode::InvalidIndexType,
+                        format!("array index must be a number, got {}", other.type_name()),
+                        *span,
+                    ))
+                }
             };
 
             match obj {
-                Ok(Value::Array { values, .. }) => {
+                Value::Array { values, .. } => {
                     Ok(values.borrow().get(i).cloned().unwrap_or(Value::Null))
                 }
-                _ => panic!("Indexing only supported on arrays"),
+                Value::Float64Array(data) => {
+                    Ok(crate::prototypes::typed_array::get_index(&data, i))
+                }
+                Value::ImmutableList(list) => {
+                    Ok(crate::prototypes::immutable::list_index(&list, i))
+                }
+                // Strings index by character, not byte
+                Value::String(s) => Ok(crate::prototypes::string::char_at(&s, i)),
+                other => Err(PawxError::new(
+                    ErrorCode::NotIndexable,
+                    format!("indexing is only supported on arrays and strings, got {}", other.type_name()),
+                    *span,
+                )),
             }
         }
 
         // ---------------------------------------------------------------------
         // Index Assignment: arr[i] = value
         // ---------------------------------------------------------------------
-        Expr::IndexAssign { object, index, value, span } => {
+        ExprNode::IndexAssign { object, index, value, span } => {
+            let span = *span;
+
             // Evaluate each expression ONCE
-            let obj = eval_expr(*object, env.clone())?;
-            let idx = eval_expr(*index, env.clone())?;
-            let val = eval_expr(*value, env)?; // ✅ moved & unwrapped once
+            let obj = eval_expr(ast, *object, env.clone())?;
+            let idx = eval_expr(ast, *index, env.clone())?;
+            let val = eval_expr(ast, *value, env)?; // ✅ moved & unwrapped once
 
             let i = match idx {
                 Value::Number(n) => n as usize,
                 _ => {
                     return Err(PawxError::new(
-                        "P0012",
+                        ErrorCode::InvalidIndexType,
                         "array index must be a number",
                         span,
                     ))
@@ -382,7 +518,7 @@ pub fn eval_expr(expr: Expr, env: Rc<RefCell<Environment>>) -> Result<Value, Paw
 
                     if i >= arr.len() {
                         return Err(PawxError::new(
-                            "P0013",
+                            ErrorCode::IndexOutOfBounds,
                             "array index out of bounds",
                             span,
                         ));
@@ -392,8 +528,14 @@ pub fn eval_expr(expr: Expr, env: Rc<RefCell<Environment>>) -> Result<Value, Paw
                     Ok(val)
                 }
 
+                Value::Float64Array(data) => {
+                    crate::prototypes::typed_array::set_index(&data, i, &val)
+                        .map_err(|message| PawxError::new(ErrorCode::IndexOutOfBounds, message, span))?;
+                    Ok(val)
+                }
+
                 _ => Err(PawxError::new(
-                    "P0014",
+                    ErrorCode::NotIndexable,
                     "index assignment only supported on arrays",
                     span,
                 )),
@@ -403,12 +545,12 @@ pub fn eval_expr(expr: Expr, env: Rc<RefCell<Environment>>) -> Result<Value, Paw
         // ---------------------------------------------------------------------
         // Object Literal: { a: 1, b: 2 }
         // ---------------------------------------------------------------------
-        Expr::ObjectLiteral { fields, span } => {
+        ExprNode::ObjectLiteral { fields, span } => {
             let mut map = HashMap::new();
 
-            for (name, expr) in fields {
-                let value = eval_expr(expr, env.clone())?;
-                map.insert(name, value);
+            for (name, expr) in fields.iter() {
+                let value = eval_expr(ast, *expr, env.clone())?;
+                map.insert(name.clone(), value);
             }
 
             Ok(Value::Object {
@@ -420,155 +562,65 @@ pub fn eval_expr(expr: Expr, env: Rc<RefCell<Environment>>) -> Result<Value, Paw
         // ---------------------------------------------------------------------
         // Property Get: obj.prop
         // ---------------------------------------------------------------------
-        Expr::Get { object, name, span } => {
-            let target = eval_expr(*object, env.clone());
-            let prop_name = name; // move into local for convenience
-
-            match target {
-                // ---------------------------------
-                // Plain object: obj.prop
-                // ---------------------------------
-                Ok(Value::Object { fields }) => {
-                    Ok(fields
-                        .borrow()
-                        .get(&prop_name)
-                        .cloned()
-                        .unwrap_or(Value::Null))
-                }
-
-                // ---------------------------------
-                // Array: arr.length or arr.method
-                // ---------------------------------
-                Ok(Value::Array { values, proto }) => {
-                    if prop_name == "length" {
-                        Ok(Value::Number(values.borrow().len() as f64))
-                    } else if let Some(Value::NativeFunction(f)) = proto.get(&prop_name).cloned() {
-                        let receiver = Value::Array {
-                            values: values.clone(),
-                            proto: proto.clone(),
-                        };
-
-                        Ok(Value::NativeFunction(Arc::new(move |args| {
-                            let mut full_args = Vec::with_capacity(args.len() + 1);
-                            full_args.push(receiver.clone());
-                            full_args.extend(args);
-                            f(full_args)
-                        })))
-                    } else {
-                        Ok(Value::Null)
-                    }
-                }
-
-                // ---------------------------------
-                // Furure: .then / .catch / .finally
-                // ---------------------------------
-                Ok(Value::Furure(inner)) => {
-                    let resolved = (*inner).clone(); // the stored result
-
-                    match prop_name.as_str() {
-                        // then(callback) – always runs, passes the resolved value
-                        "then" => {
-                            Ok(Value::NativeFunction(Arc::new(move |args: Vec<Value>| -> Value {
-                                if args.is_empty() {
-                                    panic!("then(callback): missing callback");
-                                }
-
-                                let callback = args[0].clone();
-                                let value_for_chain = resolved.clone();
-
-                                if let Value::NativeFunction(cb) = callback {
-                                    cb(vec![resolved.clone()]);
-                                } else {
-                                    panic!("then(...) expects a function");
-                                }
-
-                                // return a new Furure carrying the same resolved value for chaining
-                                Value::Furure(Box::new(value_for_chain))
-                            })))
-                        }
-
-                        // catch(callback) – only runs if resolved is an Error
-                        "catch" => {
-                            Ok(Value::NativeFunction(Arc::new(move |args: Vec<Value>| -> Value {
-                                if args.is_empty() {
-                                    panic!("catch(callback): missing callback");
-                                }
-
-                                let callback = args[0].clone();
-                                let value_for_chain = resolved.clone();
-
-                                if let Value::Error { .. } = resolved {
-                                    if let Value::NativeFunction(cb) = callback {
-                                        cb(vec![resolved.clone()]);
-                                    } else {
-                                        panic!("catch(...) expects a function");
-                                    }
-                                }
-
-                                // chain always continues with the same value
-                                Value::Furure(Box::new(value_for_chain))
-                            })))
-                        }
-
-                        // finally(callback) – always runs, ignores result, preserves chain
-                        "finally" => {
-                            Ok(Value::NativeFunction(Arc::new(move |args: Vec<Value>| -> Value {
-                                if args.is_empty() {
-                                    panic!("finally(callback): missing callback");
-                                }
-
-                                let callback = args[0].clone();
-                                let value_for_chain = resolved.clone();
-
-                                if let Value::NativeFunction(cb) = callback {
-                                    cb(vec![]);
-                                } else {
-                                    panic!("finally(...) expects a function");
-                                }
-
-                                Value::Furure(Box::new(value_for_chain))
-                            })))
-                        }
-
-                        other => panic!("Property '{}' not supported on Furure", other),
-                    }
-                }
-
-                // ---------------------------------
-                // Fallback
-                // ---------------------------------
-                other => panic!("Property '{}' not supported on {:?}", prop_name, other),
+        ExprNode::Get { object, name, span } => {
+            // Base class member: super.name
+            if matches!(&ast[*object], ExprNode::Identifier { name, .. } if name == "super") {
+                return crate::interpreter::classes::get_super_property(name.clone(), *span, env);
             }
+
+            let target = eval_expr(ast, *object, env.clone())?;
+            get_property(target, name.clone(), *span, env)
         }
 
         // ---------------------------------------------------------------------
         // Property Set: obj.prop = value
         // ---------------------------------------------------------------------
-        Expr::Set { object, name, value, span } => {
-            let target = eval_expr(*object, env.clone());
-            let val = eval_expr(*value, env);
+        ExprNode::Set { object, name, value, span } => {
+            let target = eval_expr(ast, *object, env.clone());
+            let val = eval_expr(ast, *value, env.clone());
 
-            match target {
-                Ok(Value::Object { fields }) => {
+            match target? {
+                Value::Object { fields } => {
                     let value = val?;
-                    fields.borrow_mut().insert(name, value.clone());
+                    fields.borrow_mut().insert(name.clone(), value.clone());
+
+                    // Process.env passes assignments on to the environment
+                    crate::prototypes::process::on_object_set(&fields, name, &value)
+                        .map_err(|message| PawxError::new(ErrorCode::InvalidArguments, message, *span))?;
                     Ok(value)
                 }
 
-                other => {
-                    panic!("Cannot assign property on non-object value: {:?}", other);
+                // Class instance: setters, then fields
+                instance @ Value::Instance { .. } => {
+                    let value = val?;
+                    crate::interpreter::classes::set_instance_property(instance, name.clone(), value.clone(), *span, env)?;
+                    Ok(value)
                 }
+
+                // Clowder: static fields
+                Value::Class(class) => crate::interpreter::classes::set_static_property(&class, name, val?, *span, &env),
+
+                other => Err(PawxError::new(
+                    ErrorCode::NotAnObject,
+                    format!("cannot assign property '{}' on {}", name, other.type_name()),
+                    *span,
+                )),
             }
         }
 
         // ---------------------------------------------------------------------
         // Lambda
         // ---------------------------------------------------------------------
-        Expr::Lambda { params, body, span } => {
+        ExprNode::Lambda { params, body, span } => {
             let captured_env = env.clone();
+            let ast = ast.clone();
+            let params = params.clone();
+            let body = *body;
 
-            Ok(Value::NativeFunction(Arc::new(move |args: Vec<Value>| -> Value {
-                let local_env = Rc::new(RefCell::new(Environment::new(Some(captured_env.clone()))));
+            // Errors and throws propagate like a `purr` call's
+            Ok(script_function(move |args| {
+                let _call = limits::enter_call()?;
+                let local_env = acquire_env(captured_env.clone(), params.len());
 
                 // Bind parameters
                 for (i, name) in params.iter().enumerate() {
@@ -577,105 +629,108 @@ pub fn eval_expr(expr: Expr, env: Rc<RefCell<Environment>>) -> Result<Value, Paw
                 }
 
                 // Execute body
-                for stmt in &body {
-                    match exec_stmt(stmt.clone(), local_env.clone()) {
-                        Ok(ExecSignal::None) => {}
-
-                        Ok(ExecSignal::Return(v)) => {
-                            return v;
-                        }
-
-                        Ok(ExecSignal::Throw(e)) => {
-                            return e;
-                        }
-
-                        Err(err) => {
-                            return Value::Error {
-                                message: err.message,
-                            };
+                let result = 'body: {
+                    for stmt in body.iter() {
+                        match exec_stmt(&ast, stmt, local_env.clone()) {
+                            Ok(ExecSignal::None) => {}
+
+                            Ok(ExecSignal::Return(v)) => {
+                                break 'body Ok(v);
+                            }
+
+                            Ok(ExecSignal::Throw(e)) => {
+                                break 'body Err(PawxError::thrown(e, current_span()));
+                            }
+
+                            Err(err) => {
+                                break 'body Err(err);
+                            }
                         }
                     }
-                }
 
-                Value::Null
-            })))
+                    Ok(Value::Null)
+                };
+
+                release_env(local_env);
+                result
+            }))
         }
 
         // ---------------------------------------------------------------------
         // Postfix Operators: i++, i--
         // ---------------------------------------------------------------------
-        Expr::PostIncrement { name, span } => {
-            let current = env.borrow().get(&name, false)
-                .unwrap_or_else(|| panic!("Undefined variable '{}'", name));
+        ExprNode::PostIncrement { name, span } => {
+            let current = read_variable(&env, name, *span)?;
 
             if let Value::Number(n) = current {
                 let new_val = Value::Number(n + 1.0);
-                env.borrow_mut().assign(&name, new_val);
+                env.borrow_mut().assign(name, new_val);
                 Ok(Value::Number(n))
             } else {
-                panic!("++ only allowed on numbers");
+                Err(PawxError::new(
+                    ErrorCode::InvalidUnaryOperand,
+                    format!("'++' is only allowed on numbers, got {}", current.type_name()),
+                    *span,
+                ))
             }
         }
 
-        Expr::PostDecrement { name, span } => {
-            let current = env.borrow().get(&name, false)
-                .unwrap_or_else(|| panic!("Undefined variable '{}'", name));
+        ExprNode::PostDecrement { name, span } => {
+            let current = read_variable(&env, name, *span)?;
 
             if let Value::Number(n) = current {
                 let new_val = Value::Number(n - 1.0);
-                env.borrow_mut().assign(&name, new_val);
+                env.borrow_mut().assign(name, new_val);
                 Ok(Value::Number(n))
             } else {
-                panic!("-- only allowed on numbers");
+                Err(PawxError::new(
+                    ErrorCode::InvalidUnaryOperand,
+                    format!("'--' is only allowed on numbers, got {}", current.type_name()),
+                    *span,
+                ))
             }
         }
 
         // ---------------------------------------------------------------------
         // `new` Class Construction
         // ---------------------------------------------------------------------
-        Expr::New { class_name, arguments, span } => {
-            // For now, treat `new Foo(a, b)` as sugar for `Foo(a, b)` and let
-            // `call_value` decide how to construct instances from class values.
-            let call_expr = Expr::Call {
-                callee: Box::new(Expr::Identifier { name: class_name, span }),
-                arguments,
-                span,
-            };
-
-            eval_expr(call_expr, env)
+        ExprNode::New { class_name, arguments, span } => {
+            crate::interpreter::classes::construct_instance(class_name.clone(), ast, *arguments, *span, env)
         }
 
         // ---------------------------------------------------------------------
         // tap() Module Import
         // ---------------------------------------------------------------------
-        Expr::Tap { path, span } => {
-            let pval = eval_expr(*path, env);
+        ExprNode::Tap { path, span } => {
+            let pval = eval_expr(ast, *path, env);
 
-            let path_str = match pval {
-                Ok(Value::String(s)) => s,
-                other => panic!("tap() path must be a string, got {:?}", other),
+            let path_str = match pval? {
+                Value::String(s) => s,
+                other => {
+                    return Err(PawxError::type_error(
+                        format!("tap() path must be a string, got {}", other.type_name()),
+                        *span,
+                    ))
+                }
             };
 
-            // For now, we don't have a full module loader wired in the Rust
-            // version. If you want, we can add a loader that:
-            //  • resolves the path
-            //  • reads + lexes + parses + executes the module
-            //  • returns Value::Module { exports, default }
-            panic!(
-                "tap() is not yet implemented in the Rust interpreter for path '{}'",
-                path_str
-            );
+            // Modules replaced with Test.mockModule()
+            if let Some(module) = crate::interpreter::modules::mocked(&path_str) {
+                return Ok(module);
+            }
+
+            crate::interpreter::modules::load(&path_str, ast, *span)
         }
 
-        Expr::Logical { left, operator, right, span } => {
-            let left_val = eval_expr(*left, env.clone())?;
+        ExprNode::Logical { left, operator, right, span } => {
+            let left_val = eval_expr(ast, *left, env.clone())?;
 
             match operator.lexeme.as_str() {
                 "||" => {
                     if is_truthy(&left_val) {
                         Ok(left_val)
                     } else {
-                        eval_expr(*right, env)
+                        eval_expr(ast, *right, env)
                     }
                 }
 
@@ -683,13 +738,13 @@ pub fn eval_expr(expr: Expr, env: Rc<RefCell<Environment>>) -> Result<Value, Paw
                     if !is_truthy(&left_val) {
                         Ok(left_val)
                     } else {
-                        eval_expr(*right, env)
+                        eval_expr(ast, *right, env)
                     }
                 }
 
                 _ => Err(PawxError::runtime_error(
                     format!("Invalid logical operator '{}'", operator.lexeme),
-                    span,
+                    *span,
                 )),
             }
         }
@@ -697,13 +752,280 @@ pub fn eval_expr(expr: Expr, env: Rc<RefCell<Environment>>) -> Result<Value, Paw
         // ---------------------------------------------------------------------
         // Fallback – should be unreachable once all variants handled
         // ---------------------------------------------------------------------
-        other => panic!("Unhandled expression variant: {:?}", other),
+        other => Err(PawxError::runtime_error(
+            format!("Unhandled expression variant: {:?}", other),
+            current_span(),
+        )),
+    }
+}
+
+/// Calls `name(arguments)`: a `purr` function if one is in scope, otherwise
+/// whatever callable value the name holds (class, built-in, etc.).
+fn call_named(
+    ast: &Rc<Ast>,
+    name: &str,
+    arguments: ExprList,
+    span: Span,
+    env: Rc<RefCell<Environment>>,
+) -> Result<Value, PawxError> {
+    // User-defined function
+    let func = env.borrow().get_function(name);
+    if let Some(func) = func {
+        let slots = bind_arguments(ast, name, &func, arguments, env.clone(), span)?;
+        return call_user_function_slots(func, slots, env);
+    }
+
+    let callee_val = match env.borrow().lookup(name) {
+        Ok(Some(value)) => value,
+        Ok(None) => return Err(PawxError::new(ErrorCode::UndefinedVariable, format!("undefined function '{}'", name), span)),
+        Err(message) => return Err(PawxError::new(ErrorCode::InaccessibleMember, message, span)),
+    };
+
+    call_with_args(ast, callee_val, arguments, env)
+}
+
+/// Reads property `prop_name` of an evaluated `target` (`obj.prop`).
+fn get_property(target: Value, prop_name: String, span: Span, env: Rc<RefCell<Environment>>) -> Result<Value, PawxError> {
+    match target {
+        // ---------------------------------
+        // Plain object: obj.prop
+        // ---------------------------------
+        Value::Object { fields } => {
+            Ok(fields
+                .borrow()
+                .get(&prop_name)
+                .cloned()
+                .unwrap_or(Value::Null))
+        }
+
+        // ---------------------------------
+        // Class instance: getters, fields, methods
+        // ---------------------------------
+        instance @ Value::Instance { .. } => {
+            crate::interpreter::classes::get_instance_property(instance, prop_name, span, env)
+        }
+
+        // ---------------------------------
+        // Module: its exports
+        // ---------------------------------
+        Value::Module { exports, default } => match (exports.get(&prop_name), default) {
+            (Some(value), _) => Ok(value.clone()),
+            (None, Some(default)) if prop_name == "default" => Ok(*default),
+            _ => Err(PawxError::new(
+                ErrorCode::UnknownProperty,
+                format!("module has no export '{}'", prop_name),
+                span,
+            )),
+        },
+
+        // ---------------------------------
+        // Clowder: static fields and methods
+        // ---------------------------------
+        Value::Class(class) => {
+            crate::interpreter::classes::get_static_property(&class, &prop_name, span, env)
+        }
+
+        // ---------------------------------
+        // Array: arr.length or arr.method
+        // ---------------------------------
+        Value::Array { values, proto } => {
+            if prop_name == "length" {
+                Ok(Value::Number(values.borrow().len() as f64))
+            } else if let Some(Value::NativeFunction(f)) = proto.get(&prop_name).cloned() {
+                let receiver = Value::Array {
+                    values: values.clone(),
+                    proto: proto.clone(),
+                };
+
+                Ok(Value::NativeFunction(Arc::new(move |args| {
+                    let mut full_args = Vec::with_capacity(args.len() + 1);
+                    full_args.push(receiver.clone());
+                    full_args.extend(args);
+                    f(full_args)
+                })))
+            } else {
+                Ok(Value::Null)
+            }
+        }
+
+        // ---------------------------------
+        // String: str.length or str.method
+        // ---------------------------------
+        Value::String(s) => crate::prototypes::string::string_property(&s, &prop_name).ok_or_else(|| {
+            PawxError::new(
+                ErrorCode::UnknownProperty,
+                format!("property '{}' is not supported on String", prop_name),
+                span,
+            )
+        }),
+
+        // ---------------------------------
+        // Furure: .then / .catch / .finally
+        // ---------------------------------
+        Value::Furure(inner) => {
+            let resolved = (*inner).clone(); // the stored result
+
+            match prop_name.as_str() {
+                // then(callback) – always runs, passes the resolved value
+                "then" => {
+                    Ok(Value::NativeFunction(Arc::new(move |args: Vec<Value>| -> Result<Value, PawxError> {
+                        if args.is_empty() {
+                            return raise_error("then(callback): missing callback");
+                        }
+
+                        let callback = args[0].clone();
+                        let value_for_chain = resolved.clone();
+
+                        if let Value::NativeFunction(cb) = callback {
+                            cb(vec![resolved.clone()])?;
+                        } else {
+                            return raise_error("then(...) expects a function");
+                        }
+
+                        // return a new Furure carrying the same resolved value for chaining
+                        Ok(Value::Furure(Box::new(value_for_chain)))
+                    })))
+                }
+
+                // catch(callback) – only runs if resolved is an Error
+                "catch" => {
+                    Ok(Value::NativeFunction(Arc::new(move |args: Vec<Value>| -> Result<Value, PawxError> {
+                        if args.is_empty() {
+                            return raise_error("catch(callback): missing callback");
+                        }
+
+                        let callback = args[0].clone();
+                        let value_for_chain = resolved.clone();
+
+                        if let Value::Error { .. } = resolved {
+                            if let Value::NativeFunction(cb) = callback {
+                                cb(vec![resolved.clone()])?;
+                            } else {
+                                return raise_error("catch(...) expects a function");
+                            }
+                        }
+
+                        // chain always continues with the same value
+                        Ok(Value::Furure(Box::new(value_for_chain)))
+                    })))
+                }
+
+                // finally(callback) – always runs, ignores result, preserves chain
+                "finally" => {
+                    Ok(Value::NativeFunction(Arc::new(move |args: Vec<Value>| -> Result<Value, PawxError> {
+                        if args.is_empty() {
+                            return raise_error("finally(callback): missing callback");
+                        }
+
+                        let callback = args[0].clone();
+                        let value_for_chain = resolved.clone();
+
+                        if let Value::NativeFunction(cb) = callback {
+                            cb(vec![])?;
+                        } else {
+                            return raise_error("finally(...) expects a function");
+                        }
+
+                        Ok(Value::Furure(Box::new(value_for_chain)))
+                    })))
+                }
+
+                other => Err(PawxError::new(
+                    ErrorCode::UnknownProperty,
+                    format!("property '{}' is not supported on Furure", other),
+                    span,
+                )),
+            }
+        }
+
+        // ---------------------------------
+        // CancelToken: .cancel / .isCancelled / .onCancel / .reason
+        // ---------------------------------
+        Value::CancelToken(token) => {
+            Ok(crate::prototypes::cancel::cancel_token_property(&token, &prop_name)?)
+        }
+
+        // ---------------------------------
+        // Pending Furure: .then / .catch / .finally
+        // ---------------------------------
+        Value::PendingFurure(furure) => {
+            Ok(crate::prototypes::furure::pending_furure_property(&furure, &prop_name)?)
+        }
+
+        // ---------------------------------
+        // Regex: .source / .flags / .exec / .replaceAll / ...
+        // ---------------------------------
+        Value::Regex(regex) => {
+            Ok(crate::prototypes::regex::regex_property(&regex, &prop_name)?)
+        }
+
+        // ---------------------------------
+        // Decimal: .add / .round / .toFixed / ...
+        // ---------------------------------
+        Value::Decimal(d) => {
+            Ok(crate::prototypes::decimal::decimal_property(&d, &prop_name)?)
+        }
+
+        // ---------------------------------
+        // Duration: .seconds / .humanize / ...
+        // ---------------------------------
+        Value::Duration(ms) => {
+            Ok(crate::prototypes::duration::duration_property(ms, &prop_name)?)
+        }
+
+        // ---------------------------------
+        // Float64Array: .length / .sum / .dot / ...
+        // ---------------------------------
+        Value::Float64Array(data) => {
+            Ok(crate::prototypes::typed_array::float64_array_property(&data, &prop_name)?)
+        }
+
+        // ---------------------------------
+        // Immutable.list / Immutable.map: .set / .push / ...
+        // ---------------------------------
+        Value::ImmutableList(list) => {
+            Ok(crate::prototypes::immutable::list_property(&list, &prop_name)?)
+        }
+
+        Value::ImmutableMap(map) => {
+            Ok(crate::prototypes::immutable::map_property(&map, &prop_name)?)
+        }
+
+        // ---------------------------------
+        // Error: .message
+        // ---------------------------------
+        Value::Error { message } if prop_name == "message" => Ok(Value::String(message.into())),
+
+        // ---------------------------------
+        // Fallback
+        // ---------------------------------
+        other => Err(PawxError::new(
+            ErrorCode::UnknownProperty,
+            format!("property '{}' is not supported on {}", prop_name, other.type_name()),
+            span,
+        )),
+
+    }
+}
+
+fn undefined_variable(name: &str, span: Span) -> PawxError {
+    PawxError::new(ErrorCode::UndefinedVariable, format!("undefined variable '{}'", name), span)
+}
+
+/// Reads variable `name` as seen from `env`.
+fn read_variable(env: &Rc<RefCell<Environment>>, name: &str, span: Span) -> Result<Value, PawxError> {
+    match env.borrow().lookup(name) {
+        Ok(Some(value)) => Ok(value),
+        Ok(None) => Err(undefined_variable(name, span)),
+        Err(message) => Err(PawxError::new(ErrorCode::InaccessibleMember, message, span)),
     }
 }
 
 fn values_equal_strict(a: &Value, b: &Value) -> bool {
     match (a, b) {
         (Value::Number(x), Value::Number(y)) => x == y,
+        (Value::Decimal(x), Value::Decimal(y)) => x == y,
+        (Value::Duration(x), Value::Duration(y)) => x == y,
         (Value::String(x), Value::String(y)) => x == y,
         (Value::Bool(x), Value::Bool(y)) => x == y,
         (Value::Null, Value::Null) => true,
@@ -0,0 +1,156 @@
+/*
+ * ============================================================================
+ * PAWX - Code with Claws!
+ * ============================================================================
+ *
+ * Author:   Sam Wilcox
+ * Email:    sam@pawx-lang.com
+ * Website:  https://www.pawx-lang.com
+ * Github:   https://github.com/samwilcox/pawx
+ *
+ * License:
+ * This file is part of the PAWX programming language project.
+ *
+ * PAWX is dual-licensed under the terms of:
+ *   - The MIT license
+ *   - The Apache License, Version 2.0
+ *
+ * You may choose either license to govern your use of this software.
+ * Full license text available at:
+ *    https://license.pawx-lang.com
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under these licenses is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *
+ * ============================================================================
+ */
+
+/*!
+ * PAWX Interpreter Hooks
+ * ----------------------
+ *
+ * Lets a host application watch a program run without patching the
+ * interpreter:
+ *
+ * ```text
+ * struct Counter(Cell<u64>);
+ *
+ * impl InterpreterHooks for Counter {
+ *     fn on_statement(&self, _span: Span) {
+ *         self.0.set(self.0.get() + 1);
+ *     }
+ * }
+ *
+ * let counter = Rc::new(Counter(Cell::new(0)));
+ * pawx::run_with_prelude(source, &Prelude::new().with_hooks(counter.clone()))?;
+ * ```
+ *
+ * Hooks are installed per interpreter thread, so an isolate runs without
+ * the hooks of the program that spawned it. With no hooks installed each
+ * event costs a single flag check.
+ *
+ * `pawx --profile` is built on these hooks (see `profiler.rs`).
+ */
+
+use std::cell::{Cell, RefCell};
+use std::fmt;
+use std::rc::Rc;
+
+use crate::span::Span;
+use crate::value::Value;
+
+/// Callbacks for events in a running program.
+///
+/// Every method does nothing by default; implement the ones you need.
+/// Hooks run on the interpreter thread, in the middle of execution, and must
+/// not run PAWX code themselves.
+pub trait InterpreterHooks {
+    /// A statement is about to run.
+    fn on_statement(&self, span: Span) {
+        let _ = span;
+    }
+
+    /// A call expression is about to invoke `name`: the function name for
+    /// `f(x)`, the method name for `obj.m(x)`, `<anonymous>` otherwise.
+    fn on_call(&self, name: &str, span: Span) {
+        let _ = (name, span);
+    }
+
+    /// A value was thrown, either by `throw` or as a runtime error, at the
+    /// statement `span`. Reported once, where it was raised, whether or not
+    /// it is caught.
+    fn on_throw(&self, error: &Value, span: Span) {
+        let _ = (error, span);
+    }
+
+    /// Values were freed and `finalized` `WeakRef` finalizers ran.
+    ///
+    /// PAWX values are reference counted, so this finalization pass is the
+    /// closest thing it has to a garbage collection.
+    fn on_gc(&self, finalized: usize) {
+        let _ = finalized;
+    }
+}
+
+/// Hooks held by a `Prelude` until the program starts.
+#[derive(Clone)]
+pub struct HookSet(pub(crate) Rc<dyn InterpreterHooks>);
+
+impl fmt::Debug for HookSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("HookSet(..)")
+    }
+}
+
+thread_local! {
+    static HOOKS: RefCell<Option<Rc<dyn InterpreterHooks>>> = const { RefCell::new(None) };
+
+    /// Mirrors `HOOKS.is_some()`, so the fast path never touches the RefCell.
+    static ACTIVE: Cell<bool> = const { Cell::new(false) };
+
+    /// Set once the error currently propagating has been reported.
+    static RAISED: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Installs (or with `None`, removes) the hooks for this thread.
+pub fn install(hooks: Option<Rc<dyn InterpreterHooks>>) {
+    ACTIVE.with(|a| a.set(hooks.is_some()));
+    HOOKS.with(|h| *h.borrow_mut() = hooks);
+}
+
+/// Whether any hooks are installed on this thread.
+#[inline]
+pub fn active() -> bool {
+    ACTIVE.with(Cell::get)
+}
+
+fn with_hooks(f: impl FnOnce(&dyn InterpreterHooks)) {
+    if active() {
+        HOOKS.with(|h| {
+            if let Some(hooks) = h.borrow().as_ref() {
+                f(hooks.as_ref());
+            }
+        });
+    }
+}
+
+pub(crate) fn statement(span: Span) {
+    RAISED.with(|r| r.set(false));
+    with_hooks(|h| h.on_statement(span));
+}
+
+pub(crate) fn call(name: &str, span: Span) {
+    with_hooks(|h| h.on_call(name, span));
+}
+
+/// Reports `error` unless a statement nested inside this one already did.
+pub(crate) fn throw(error: &Value, span: Span) {
+    if !RAISED.with(|r| r.replace(true)) {
+        with_hooks(|h| h.on_throw(error, span));
+    }
+}
+
+pub(crate) fn gc(finalized: usize) {
+    with_hooks(|h| h.on_gc(finalized));
+}
@@ -0,0 +1,122 @@
+/*
+ * ============================================================================
+ * PAWX - Code with Claws!
+ * ============================================================================
+ *
+ * Author:   Sam Wilcox
+ * Email:    sam@pawx-lang.com
+ * Website:  https://www.pawx-lang.com
+ * Github:   https://github.com/samwilcox/pawx
+ *
+ * License:
+ * This file is part of the PAWX programming language project.
+ *
+ * PAWX is dual-licensed under the terms of:
+ *   - The MIT license
+ *   - The Apache License, Version 2.0
+ *
+ * You may choose either license to govern your use of this software.
+ * Full license text available at:
+ *    https://license.pawx-lang.com
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under these licenses is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *
+ * ============================================================================
+ */
+
+/*!
+ * PAWX Depth Limits
+ * -----------------
+ *
+ * One setting, `--max-depth <n>` (default 1000), bounds how deep a program
+ * may go:
+ *
+ *  • nested function, method and lambda calls; one call too many is an
+ *    E3008 error instead of a crash of the whole process
+ *  • nested arrays, objects and tuples in the code that walks a whole
+ *    value (meow, Json.stringify, Fs.writeJson, res.json, Auth.sign,
+ *    equality of tuples and immutable collections, assertEq and sending
+ *    to an isolate); a value nested deeper fails with a catchable error
+ *
+ * Those walkers keep their own stack instead of recursing, so how deep a
+ * value can be is decided by this setting rather than by the Rust stack.
+ * Only values formatted into the interpreter's own messages are cut short
+ * instead, with containers past the limit shown as `[...]`.
+ *
+ * Calls do recurse on the Rust stack, so interpreter threads are started
+ * with [`STACK_SIZE`]: room for a few thousand calls in a debug build and
+ * tens of thousands in a release build.
+ */
+
+use std::cell::Cell;
+
+use crate::error::{ErrorCode, PawxError};
+use crate::interpreter::current_span;
+
+/// Depth limit used unless the program sets another.
+pub const DEFAULT_MAX_DEPTH: usize = 1000;
+
+/// Stack size of threads that run PAWX code.
+pub const STACK_SIZE: usize = 256 * 1024 * 1024;
+
+thread_local! {
+    /// Depth limit of the program running on this thread.
+    static MAX_DEPTH: Cell<usize> = const { Cell::new(DEFAULT_MAX_DEPTH) };
+
+    /// Calls currently running on this thread.
+    static CALL_DEPTH: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Sets the depth limit for the program running on this thread.
+pub fn set_max_depth(depth: usize) {
+    MAX_DEPTH.with(|d| d.set(depth));
+}
+
+/// Returns the depth limit of the program running on this thread.
+pub fn max_depth() -> usize {
+    MAX_DEPTH.with(Cell::get)
+}
+
+/// A running call; the call depth goes back down when it is dropped.
+pub struct CallGuard(());
+
+impl Drop for CallGuard {
+    fn drop(&mut self) {
+        CALL_DEPTH.with(|d| d.set(d.get() - 1));
+    }
+}
+
+/// Counts a call that is about to run.
+///
+/// # Errors
+/// - `E3008` if the call would go deeper than the limit
+pub fn enter_call() -> Result<CallGuard, PawxError> {
+    let limit = max_depth();
+    let depth = CALL_DEPTH.with(Cell::get);
+
+    if depth >= limit {
+        return Err(PawxError::new(
+            ErrorCode::RecursionLimit,
+            format!("maximum recursion depth of {} calls exceeded", limit),
+            current_span(),
+        ));
+    }
+
+    CALL_DEPTH.with(|d| d.set(depth + 1));
+    Ok(CallGuard(()))
+}
+
+/// Checks that a container at nesting `depth` (the root is 0) is allowed.
+///
+/// # Errors
+/// A message naming `what` failed if `depth` is beyond the limit; built-ins
+/// walking a value raise it as a catchable error.
+pub fn check_nesting(depth: usize, what: &str) -> Result<(), String> {
+    let limit = max_depth();
+    if depth > limit {
+        return Err(format!("{}: value is nested more than {} levels deep", what, limit));
+    }
+    Ok(())
+}
@@ -18,10 +18,14 @@
  * 
  *  - timers.rs      → Timer scheduling and dispatch
  *  - statements.rs → Statement execution (exec_stmt)
+ *  - patterns.rs    → `match` pattern testing and bindings
  *  - expressions.rs→ Expression evaluation (eval_expr)
  *  - calls.rs       → Function and method invocation
  *  - display.rs     → Value formatting utilities
  *  - classes.rs     → Class & instance behavior
+ *  - prelude.rs     → Standard global installation
+ *  - clock.rs       → Virtual clock for `--virtual-time`
+ *  - limits.rs      → Call and nesting depth limits (`--max-depth`)
  * 
  * --------------------------------------------------------------------------
  * Author:   Sam Wilcox
@@ -49,44 +53,312 @@
 
 pub mod timers;
 pub mod statements;
+pub mod patterns;
 pub mod expressions;
 pub mod calls;
 pub mod display;
 pub mod classes;
 pub mod environment;
 pub mod helpers;
+pub mod numeric;
+pub mod prelude;
+pub mod clock;
+pub mod determinism;
+pub mod limits;
+pub mod hooks;
+pub mod profiler;
+pub mod conditional;
+pub mod testing;
+pub mod snapshots;
+pub mod reporters;
+pub mod modules;
 
-use std::cell::RefCell;
-use std::rc::Rc;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::rc::{Rc, Weak};
 use std::sync::Arc;
 
+use crate::ast::arena::{Ast, StmtNode};
 use crate::ast::Stmt;
+use crate::error::{ErrorCode, PawxError};
 use crate::interpreter::environment::Environment;
+use crate::span::Span;
 use crate::value::Value;
 use crate::interpreter::environment::FunctionDef;
+use crate::prototypes::furure::PendingFurure;
 
-use timers::{install_timers, TimerRuntime};
+use clock::VirtualClock;
+use timers::{install_timers, install_virtual_timers, TimerRuntime};
+use prelude::Prelude;
 use statements::{exec_stmt, ExecSignal};
-use display::value_to_string;
+use display::{try_value_to_string, value_to_string};
 
-/// Executes a full PAWX program from a list of parsed statements.
-pub fn run(statements: Vec<Stmt>) {
+thread_local! {
+    /// Global scope of the running program, for native functions that call
+    /// back into user code (e.g. class methods) and have no scope of their own.
+    static GLOBAL_ENV: RefCell<Weak<RefCell<Environment>>> = const { RefCell::new(Weak::new()) };
+
+    /// Start of the statement being executed on this thread.
+    static CURRENT_SPAN: Cell<Span> = const { Cell::new(Span { line: 0, column: 0 }) };
+
+    /// Timers of the running program, so `nap` can run the event loop.
+    static EVENT_LOOP: RefCell<Option<(TimerRuntime, Option<VirtualClock>)>> = const { RefCell::new(None) };
+}
+
+/// Returns where in the source the running program currently is.
+///
+/// After an uncaught error or a panic this is the statement that failed,
+/// which is how errors raised by natives get a source location.
+pub fn current_span() -> Span {
+    CURRENT_SPAN.with(Cell::get)
+}
+
+/// Replaces the current source location, returning the previous one.
+pub(crate) fn set_current_span(span: Span) -> Span {
+    CURRENT_SPAN.with(|s| s.replace(span))
+}
+
+/// Returns the running program's global scope.
+///
+/// Outside a running program this is a fresh, empty environment.
+pub fn global_env() -> Rc<RefCell<Environment>> {
+    GLOBAL_ENV
+        .with(|g| g.borrow().upgrade())
+        .unwrap_or_else(|| Rc::new(RefCell::new(Environment::new(None))))
+}
+
+/// Executes a full PAWX program using a custom prelude.
+///
+/// Only the globals described by `prelude` are installed, in addition to the
+/// core intrinsics (`meow` and the timer functions).
+///
+/// # Errors
+/// A runtime error or uncaught throw at the top level stops the program and
+/// is returned with the location of the statement that failed.
+pub fn run_with_prelude(statements: Vec<Stmt>, prelude: &Prelude) -> Result<(), PawxError> {
+    run_with_globals(statements, prelude, HashMap::new())
+}
+
+/// Executes a full PAWX program using a custom prelude, with `globals`
+/// defined on top of it (replacing prelude globals of the same name).
+///
+/// # Errors
+/// As [`run_with_prelude`].
+pub fn run_with_globals(
+    statements: Vec<Stmt>,
+    prelude: &Prelude,
+    globals: HashMap<String, Value>,
+) -> Result<(), PawxError> {
+    let (env, timer_runtime) = bootstrap(prelude);
+    for (name, value) in globals {
+        env.borrow_mut().define_public(name, value);
+    }
+
+    // -------------------------------------------------------------------------
+    // Compile-time `when` blocks
+    // -------------------------------------------------------------------------
+    let statements = conditional::resolve(statements, prelude.defines())?;
+
+    if prelude.tests_enabled() {
+        testing::enable();
+
+        if let Some(script) = prelude.script() {
+            snapshots::enable(std::path::Path::new(script), prelude.snapshot_updates_enabled());
+        }
+    }
+
+    // -------------------------------------------------------------------------
+    // Lower the tree into the arena the evaluator runs on (see ast::arena)
+    // -------------------------------------------------------------------------
+    let (ast, body) = Ast::lower(statements);
+    if let Some(script) = prelude.script() {
+        modules::register_source(&ast, std::path::Path::new(script));
+    }
+
+    // -------------------------------------------------------------------------
+    // Main Execution Loop (WITH TIMER PUMP)
+    // -------------------------------------------------------------------------
+
+    for stmt in body.iter() {
+        match exec_stmt(&ast, stmt, env.clone()) {
+            Ok(ExecSignal::None) => {}
+
+            Ok(ExecSignal::Return(_)) => {
+                // top-level return is allowed but ignored
+                break;
+            }
+
+            Ok(ExecSignal::Throw(err)) => {
+                return Err(PawxError::new(
+                    ErrorCode::UncaughtException,
+                    format!("uncaught {}", value_to_string(&err)),
+                    current_span(),
+                ));
+            }
+
+            Err(e) => return Err(e),
+        }
+
+        // Timer pump delegated to timers.rs
+        timers::pump_timers(&timer_runtime)?;
+        dispatch_events()?;
+    }
+
+    // -------------------------------------------------------------------------
+    // Event Loop: stay alive until every pending timer has fired
+    // -------------------------------------------------------------------------
+    while timers::wait_for_next(&timer_runtime) {
+        timers::pump_timers(&timer_runtime)?;
+        dispatch_events()?;
+    }
+
+    // Test blocks registered while the program ran
+    let test_summary = prelude.tests_enabled().then(|| {
+        let suite = prelude.script().unwrap_or("<main>");
+        let (summary, records) =
+            testing::run_registered(suite, prelude.test_filter(), || {
+                if let Err(error) = timers::pump_timers(&timer_runtime) {
+                    eprintln!("pawx test: timer callback failed: {}", error);
+                }
+            });
+
+        for reporter in prelude.reporters() {
+            if let Err(e) = reporter.write(&records) {
+                eprintln!("pawx test: {}", e);
+                std::process::exit(1);
+            }
+        }
+        summary
+    });
+
+    // Final drain
+    timers::pump_timers(&timer_runtime)?;
+    dispatch_events()?;
+    testing::run_cleanups();
+
+    if test_summary.is_some_and(|s| s.failed > 0) {
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
+
+/* ============================================================================
+ * SESSIONS
+ * ============================================================================
+ */
+
+/// A program run a piece at a time in one global scope, as `pawx repl`
+/// does: each piece sees the variables, functions and timers the earlier
+/// ones left behind.
+pub struct Session {
+    env: Rc<RefCell<Environment>>,
+    timer_runtime: TimerRuntime,
+    defines: conditional::Defines,
+}
+
+impl Session {
+    /// Creates the global scope and installs `prelude` into it.
+    pub fn new(prelude: &Prelude) -> Self {
+        let (env, timer_runtime) = bootstrap(prelude);
+        Session {
+            env,
+            timer_runtime,
+            defines: prelude.defines().clone(),
+        }
+    }
+
+    /// Runs `statements` in the session's global scope.
+    ///
+    /// # Returns
+    /// - The value of the last statement, if it is an expression
+    ///
+    /// # Errors
+    /// A runtime error or uncaught throw stops the statements; whatever
+    /// ran before it stays defined.
+    pub fn run(&self, statements: Vec<Stmt>) -> Result<Option<Value>, PawxError> {
+        let statements = conditional::resolve(statements, &self.defines)?;
+        let (ast, body) = Ast::lower(statements);
+        let last = body.iter().next_back();
+
+        for stmt in body.iter() {
+            if Some(stmt) == last {
+                if let StmtNode::Expression(expr) = &ast[stmt] {
+                    set_current_span(ast.span(stmt));
+                    let value = expressions::eval_expr(&ast, *expr, self.env.clone())?;
+                    self.pump()?;
+                    return Ok(Some(value));
+                }
+            }
+
+            match exec_stmt(&ast, stmt, self.env.clone())? {
+                ExecSignal::None => {}
+                ExecSignal::Return(_) => break,
+                ExecSignal::Throw(err) => {
+                    return Err(PawxError::new(
+                        ErrorCode::UncaughtException,
+                        format!("uncaught {}", value_to_string(&err)),
+                        current_span(),
+                    ));
+                }
+            }
+
+            self.pump()?;
+        }
+
+        Ok(None)
+    }
+
+    /// Runs the timer callbacks that are due and any pending events.
+    ///
+    /// # Errors
+    /// - If a timer or event callback fails
+    pub fn pump(&self) -> Result<(), PawxError> {
+        timers::pump_timers(&self.timer_runtime)?;
+        dispatch_events()?;
+        Ok(())
+    }
+
+    /// When the next pending timer is due, if any.
+    pub fn next_due(&self) -> Option<std::time::Instant> {
+        self.timer_runtime.next_due()
+    }
+}
+
+/// Creates the global scope of a program: the timer functions, `meow`,
+/// and the globals `prelude` describes.
+fn bootstrap(prelude: &Prelude) -> (Rc<RefCell<Environment>>, TimerRuntime) {
     let env = Rc::new(RefCell::new(Environment::new(None)));
+    GLOBAL_ENV.with(|g| *g.borrow_mut() = Rc::downgrade(&env));
 
     // -------------------------------------------------------------------------
     // Install Timers (MOVED TO timers.rs)
     // -------------------------------------------------------------------------
-    let timer_runtime: TimerRuntime = install_timers(env.clone());
+    let timer_runtime: TimerRuntime = match prelude.virtual_clock() {
+        Some(clock) => install_virtual_timers(env.clone(), clock),
+        None => install_timers(env.clone()),
+    };
+    EVENT_LOOP.with(|l| *l.borrow_mut() = Some((timer_runtime.clone(), prelude.virtual_clock().cloned())));
 
     // -------------------------------------------------------------------------
     // Built-in: meow(...)
     // -------------------------------------------------------------------------
+    let output = prelude.output();
+    let print_line = move |line: &str| match &output {
+        Some(buffer) => {
+            let mut buffer = buffer.borrow_mut();
+            buffer.push_str(line);
+            buffer.push('\n');
+        }
+        None => println!("{}", line),
+    };
+
     env.borrow_mut().define_public(
         "meow".to_string(),
-        Value::NativeFunction(Arc::new(|args: Vec<Value>| -> Value {
+        Value::NativeFunction(Arc::new(move |args: Vec<Value>| -> Result<Value, PawxError> {
             if args.is_empty() {
-                println!();
-                return Value::Null;
+                print_line("");
+                return Ok(Value::Null);
             }
 
             if let Value::String(format) = &args[0] {
@@ -97,97 +369,131 @@ pub fn run(statements: Vec<Stmt>) {
 
                     while let Some(c) = chars.next() {
                         if c == '$' && arg_index < args.len() {
-                            output.push_str(&value_to_string(&args[arg_index]));
+                            match try_value_to_string(&args[arg_index]) {
+                                Ok(text) => output.push_str(&text),
+                                Err(message) => return calls::raise_error(message),
+                            }
                             arg_index += 1;
                         } else {
                             output.push(c);
                         }
                     }
 
-                    println!("{}", output);
-                    return Value::Null;
+                    print_line(&output);
+                    return Ok(Value::Null);
                 }
             }
 
             let mut parts = Vec::new();
             for val in args {
-                parts.push(value_to_string(&val));
+                match try_value_to_string(&val) {
+                    Ok(text) => parts.push(text),
+                    Err(message) => return calls::raise_error(message),
+                }
             }
 
-            println!("{}", parts.join(" "));
-            Value::Null
+            print_line(&parts.join(" "));
+            Ok(Value::Null)
         })),
     );
 
     // -------------------------------------------------------------------------
-    // Standard Global Objects
+    // Standard Global Objects (configured by the prelude)
     // -------------------------------------------------------------------------
-    env.borrow_mut().define_public("Error".to_string(), Value::NativeFunction(Arc::new(|args| {
-        let message = match args.get(0) {
-            Some(Value::String(s)) => s.clone(),
-            _ => "Unknown error".to_string(),
-        };
-        Value::Error { message }
-    })));
+    if let Some(seed) = prelude.deterministic_seed() {
+        determinism::enable(seed);
+    }
+    crate::prototypes::eval::set_enabled(prelude.eval_enabled());
+    limits::set_max_depth(prelude.max_depth());
+    hooks::install(prelude.hooks());
+    prelude.install(&env);
+    modules::start(prelude.script().map(std::path::Path::new), prelude.frozen_enabled());
 
-    env.borrow_mut().define_public("Array".to_string(), crate::prototypes::array::create_global_array_object());
-    env.borrow_mut().define_public(
-    "String".to_string(), Value::Object { fields: Rc::new(RefCell::new(crate::prototypes::string::create_global_string_object())) });
-    env.borrow_mut().define_public("Math".to_string(), crate::prototypes::math::create_global_math_value());
-    env.borrow_mut().define_public("Time".to_string(), crate::prototypes::time::create_global_time_value());
-    env.borrow_mut().define_public("Date".to_string(), crate::prototypes::time::create_global_time_value());
-    env.borrow_mut().define_public("Http".to_string(), crate::prototypes::http::create_global_http_object());
-    env.borrow_mut().define_public("String".to_string(), Value::Object { fields: Rc::new(RefCell::new(crate::prototypes::string::create_global_string_object())) });
-    env.borrow_mut().define_public("Regex".to_string(), Value::Object { fields: Rc::new(RefCell::new(crate::prototypes::regex::create_global_regex_object())) });
-    env.borrow_mut().define_public("Fs".to_string(), crate::prototypes::fs::create_fs_global());
+    // `Process.argv[0]` is not always a file (the REPL's is `<repl>`)
+    let script = prelude.script().map(std::path::Path::new).filter(|p| p.is_file());
+    modules::define_meta(&mut env.borrow_mut(), script, true);
 
-    // -------------------------------------------------------------------------
-    // Main Execution Loop (WITH TIMER PUMP)
-    // -------------------------------------------------------------------------
-    for stmt in statements {
-        match exec_stmt(stmt, env.clone()) {
-            Ok(ExecSignal::None) => {}
+    (env, timer_runtime)
+}
 
-            Ok(ExecSignal::Return(_)) => {
-                // top-level return is allowed but ignored
-                break;
-            }
+/// Runs the work that piles up between statements: finalizers for freed
+/// values, and messages received by Mqtt clients and serial ports.
+///
+/// # Errors
+/// The first error raised by one of their callbacks.
+fn dispatch_events() -> Result<(), PawxError> {
+    crate::prototypes::weak::run_finalizers()?;
+    crate::prototypes::mqtt::dispatch_messages()?;
+    #[cfg(feature = "serial")]
+    crate::prototypes::serial::dispatch_data()?;
+    Ok(())
+}
 
-            Ok(ExecSignal::Throw(err)) => {
-                panic!("Uncaught Pawx error: {:?}", err);
-            }
+/// Runs the event loop until `furure` settles: due timers and pending
+/// events first, then waiting for the next timer (or, under virtual time,
+/// advancing the clock to it).
+///
+/// # Returns
+/// - The settled value; an `Error` if the Furure was rejected
+///
+/// # Errors
+/// - The first error a timer or event callback fails with
+/// - If no timer is left that could settle `furure`
+pub fn wait_for(furure: &PendingFurure) -> Result<Value, PawxError> {
+    let (runtime, clock) = EVENT_LOOP.with(|l| l.borrow().clone()).unwrap_or_default();
 
-            Err(e) => {
-                panic!("Uncaught Pawx runtime error: {:?}", e);
-            }
+    loop {
+        if let Some(value) = furure.value() {
+            return Ok(value);
         }
 
-        // Timer pump delegated to timers.rs
-        timers::pump_timers(&timer_runtime);
-    }
+        timers::pump_timers(&runtime)?;
+        dispatch_events()?;
+        if let Some(value) = furure.value() {
+            return Ok(value);
+        }
 
-    // Final drain
-    timers::pump_timers(&timer_runtime);
+        let waited = match &clock {
+            Some(clock) => match clock.next_due() {
+                Some(due) => {
+                    clock.advance(due.saturating_sub(clock.now()))?;
+                    true
+                }
+                None => false,
+            },
+            None => timers::wait_for_next(&runtime),
+        };
+
+        if !waited {
+            return calls::raise_error("nap: the Furure can never settle, no timers are pending");
+        }
+    }
 }
 
 /// Executes a module inside an existing environment.
-pub fn run_in_env(statements: Vec<Stmt>, env: Rc<RefCell<Environment>>) {
-    for stmt in statements {
-        match exec_stmt(stmt, env.clone()) {
-            Ok(ExecSignal::None) => {}
+///
+/// # Errors
+/// A runtime error or uncaught throw at the module's top level, with the
+/// location of the statement that failed.
+pub fn run_in_env(statements: Vec<Stmt>, env: Rc<RefCell<Environment>>) -> Result<(), PawxError> {
+    let (ast, body) = Ast::lower(statements);
 
-            Ok(ExecSignal::Return(_)) => {
-                // ignore return at module top-level
-                break;
-            }
+    for stmt in body.iter() {
+        match exec_stmt(&ast, stmt, env.clone())? {
+            ExecSignal::None => {}
 
-            Ok(ExecSignal::Throw(err)) => {
-                panic!("Uncaught Pawx error in module: {:?}", err);
-            }
+            // ignore return at module top-level
+            ExecSignal::Return(_) => break,
 
-            Err(e) => {
-                panic!("Uncaught Pawx runtime error in module: {:?}", e);
+            ExecSignal::Throw(err) => {
+                return Err(PawxError::new(
+                    ErrorCode::UncaughtException,
+                    format!("uncaught {}", value_to_string(&err)),
+                    current_span(),
+                ));
             }
         }
     }
-}
\ No newline at end of file
+
+    Ok(())
+}
@@ -0,0 +1,481 @@
+/*
+ * ============================================================================
+ * PAWX - Code with Claws!
+ * ============================================================================
+ *
+ * Author:   Sam Wilcox
+ * Email:    sam@pawx-lang.com
+ * Website:  https://www.pawx-lang.com
+ * Github:   https://github.com/samwilcox/pawx
+ *
+ * License:
+ * This file is part of the PAWX programming language project.
+ *
+ * PAWX is dual-licensed under the terms of:
+ *   - The MIT license
+ *   - The Apache License, Version 2.0
+ *
+ * You may choose either license to govern your use of this software.
+ * Full license text available at:
+ *    https://license.pawx-lang.com
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under these licenses is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *
+ * ============================================================================
+ */
+
+/*!
+ * PAWX Module Loader
+ * ------------------
+ *
+ * Finds, runs and caches the files `tap` imports.
+ *
+ * A path is resolved in this order:
+ *
+ *  - `./x` and `../x` relative to the file that contains the `tap`
+ *  - an absolute path as it is
+ *  - a bare `x` next to the importing file, then at the project root (the
+ *    directory of the nearest `paw.json`), then as a member package of the
+ *    workspace the project is in, then as an installed package in
+ *    `paw_modules/x`
+ *
+ * A workspace member is found by the `name` in its `paw.json`: with
+ * `"name": "@cats/util"`, `tap("@cats/util")` loads the member's `entry`
+ * and `tap("@cats/util/strings")` its `strings.px`.
+ *
+ * Each candidate is tried as written, with `.px` added, as a directory's
+ * `index.px`, and as a package directory's manifest `entry`.
+ *
+ * A module runs once, in its own scope under the globals. Its top-level
+ * `snuggle` variables, functions and clowders become its exports and
+ * `exports default` its default; `den` and `lair` variables stay private.
+ * Later taps of the same file, however they spell it, get the cached
+ * module. A module that taps itself again while it is still loading is
+ * reported with the whole chain of imports that led back to it.
+ *
+ * Every file that runs, the program's own script included, sees where it
+ * came from:
+ *
+ *  - `__file` its absolute path (`null` when there is no file, as in the
+ *    REPL)
+ *  - `__dir` the directory it is in (the working directory without a file)
+ *  - `module.meta` `{ path, dir, isMain }`, and `module.isMain`, which is
+ *    true only for the script the program was started with, so a file can
+ *    be both a library and a command:
+ *
+ * ```text
+ * snuggle parse = (text) -> { ... };
+ *
+ * if (module.isMain) {
+ *     meow(parse(Fs.readFile(Process.argv[0])));
+ * }
+ * ```
+ *
+ * They are `lair` names, so modules never export them.
+ *
+ * With `--frozen`, the first `tap` of a file inside an installed package
+ * checks that package's version and content hash against `paw.lock`, and
+ * stops with `LockfileMismatch` if they differ.
+ *
+ * Tests replace modules here with `Test.mockModule("./db", fake)`; every
+ * later `tap("./db")` then returns `fake` instead of loading the file.
+ * `./db`, `db` and `./db.px` all name the same module.
+ */
+
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use crate::ast::arena::Ast;
+use crate::error::{ErrorCode, PawxError};
+use crate::interpreter::calls::function_value;
+use crate::interpreter::environment::{Access, Environment};
+use crate::interpreter::statements::{exec_stmt, ExecSignal};
+use crate::interpreter::{current_span, global_env};
+use crate::interpreter::display::value_to_string;
+use crate::lockfile::{Lockfile, LOCK_FILE, MODULES_DIR};
+use crate::manifest::{Manifest, MANIFEST_FILE};
+use crate::span::Span;
+use crate::value::Value;
+
+thread_local! {
+    /// Modules replaced by tests, keyed by normalized path.
+    static MOCKS: RefCell<HashMap<String, Value>> = RefCell::new(HashMap::new());
+
+    /// The program's entry script, which bare paths and the project root
+    /// are found from when no module is loading.
+    static ENTRY: RefCell<Option<PathBuf>> = const { RefCell::new(None) };
+
+    /// Loaded modules, keyed by canonical path.
+    static LOADED: RefCell<HashMap<PathBuf, Value>> = RefCell::new(HashMap::new());
+
+    /// Modules being loaded, outermost first.
+    static LOADING: RefCell<Vec<PathBuf>> = const { RefCell::new(Vec::new()) };
+
+    /// The file each lowered tree came from, keyed by its address; the tree
+    /// is kept alive so the address cannot be reused.
+    static SOURCES: RefCell<HashMap<usize, (Rc<Ast>, PathBuf)>> = RefCell::new(HashMap::new());
+
+    /// Whether tapped packages are checked against `paw.lock`.
+    static FROZEN: Cell<bool> = const { Cell::new(false) };
+
+    /// Installed package directories already checked against `paw.lock`.
+    static VERIFIED: RefCell<HashSet<PathBuf>> = RefCell::new(HashSet::new());
+}
+
+/// Normalizes a `tap` path so different spellings of a module match.
+pub fn cache_key(path: &str) -> String {
+    let path = path.trim().replace('\\', "/");
+    let path = path.strip_prefix("./").unwrap_or(&path);
+    path.strip_suffix(".px").unwrap_or(path).to_string()
+}
+
+/// Makes `tap(path)` return `value`.
+pub fn mock(path: &str, value: Value) {
+    MOCKS.with(|m| m.borrow_mut().insert(cache_key(path), value));
+}
+
+/// Removes the mock for `path`, returning whether there was one.
+pub fn unmock(path: &str) -> bool {
+    MOCKS.with(|m| m.borrow_mut().remove(&cache_key(path)).is_some())
+}
+
+/// Removes every module mock.
+pub fn clear_mocks() {
+    MOCKS.with(|m| m.borrow_mut().clear());
+}
+
+/// Returns the mock registered for `path`, if any.
+pub fn mocked(path: &str) -> Option<Value> {
+    MOCKS.with(|m| m.borrow().get(&cache_key(path)).cloned())
+}
+
+/* ============================================================================
+ * LOADING
+ * ============================================================================
+ */
+
+/// Forgets every loaded module and makes `entry` the program's entry
+/// script. `frozen` checks tapped packages against `paw.lock`. Called when
+/// a program starts.
+pub fn start(entry: Option<&Path>, frozen: bool) {
+    ENTRY.with(|e| *e.borrow_mut() = entry.map(Path::to_path_buf));
+    LOADED.with(|l| l.borrow_mut().clear());
+    LOADING.with(|l| l.borrow_mut().clear());
+    SOURCES.with(|s| s.borrow_mut().clear());
+    FROZEN.with(|f| f.set(frozen));
+    VERIFIED.with(|v| v.borrow_mut().clear());
+}
+
+/// Defines `__file`, `__dir` and `module` (see module docs) in `env`, the
+/// top-level scope of the file at `path`.
+pub fn define_meta(env: &mut Environment, path: Option<&Path>, is_main: bool) {
+    let path = path.map(|p| fs::canonicalize(p).unwrap_or_else(|_| p.to_path_buf()));
+    let dir = match &path {
+        Some(path) => dir_of(path),
+        None => std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")),
+    };
+
+    let file = match &path {
+        Some(path) => Value::String(path.display().to_string().into()),
+        None => Value::Null,
+    };
+    let dir = Value::String(dir.display().to_string().into());
+
+    let meta = HashMap::from([
+        ("path".to_string(), file.clone()),
+        ("dir".to_string(), dir.clone()),
+        ("isMain".to_string(), Value::Bool(is_main)),
+    ]);
+    let meta = Value::Object {
+        fields: Rc::new(RefCell::new(meta)),
+    };
+    let module = HashMap::from([
+        ("meta".to_string(), meta),
+        ("isMain".to_string(), Value::Bool(is_main)),
+    ]);
+
+    env.define_protected("__file".to_string(), file);
+    env.define_protected("__dir".to_string(), dir);
+    env.define_protected(
+        "module".to_string(),
+        Value::Object {
+            fields: Rc::new(RefCell::new(module)),
+        },
+    );
+}
+
+/// Records that `ast` was lowered from the file at `path`, so its taps
+/// resolve relative to it.
+pub fn register_source(ast: &Rc<Ast>, path: &Path) {
+    SOURCES.with(|s| {
+        s.borrow_mut()
+            .insert(Rc::as_ptr(ast) as usize, (ast.clone(), path.to_path_buf()))
+    });
+}
+
+fn source_of(ast: &Rc<Ast>) -> Option<PathBuf> {
+    SOURCES.with(|s| s.borrow().get(&(Rc::as_ptr(ast) as usize)).map(|(_, path)| path.clone()))
+}
+
+fn entry() -> Option<PathBuf> {
+    ENTRY.with(|e| e.borrow().clone())
+}
+
+/// The directory of `file`; `.` for a bare file name.
+fn dir_of(file: &Path) -> PathBuf {
+    match file.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+        _ => PathBuf::from("."),
+    }
+}
+
+/// The directory of the nearest `paw.json` above `dir`, or `dir` itself.
+fn project_root(dir: &Path) -> PathBuf {
+    let absolute = fs::canonicalize(dir).unwrap_or_else(|_| dir.to_path_buf());
+    match Manifest::find(&absolute) {
+        Ok(Some((root, _))) => root,
+        _ => absolute,
+    }
+}
+
+/// The files `base` may name, in the order they are tried.
+fn candidates(base: &Path) -> Vec<PathBuf> {
+    let mut files = vec![base.to_path_buf()];
+
+    if base.extension().is_none_or(|ext| ext != "px") {
+        let mut with_ext = base.as_os_str().to_owned();
+        with_ext.push(".px");
+        files.push(PathBuf::from(with_ext));
+    }
+
+    files.push(base.join("index.px"));
+
+    if let Ok(manifest) = Manifest::load(&base.join(MANIFEST_FILE)) {
+        files.push(base.join(manifest.entry));
+    }
+
+    files
+}
+
+/// The path `spec` names inside a member of the workspace `root` is in,
+/// when `spec` starts with that member's package name.
+fn workspace_member(spec: &str, root: &Path) -> Option<PathBuf> {
+    let (workspace, manifest) = Manifest::find_workspace(root).ok()??;
+    let members = manifest.workspace_members(&workspace).ok()?;
+
+    members
+        .into_iter()
+        .filter_map(|(dir, member)| match spec.strip_prefix(member.name.as_str())? {
+            "" => Some((member.name.len(), dir)),
+            rest => rest.strip_prefix('/').map(|rest| (member.name.len(), dir.join(rest))),
+        })
+        .max_by_key(|(len, _)| *len)
+        .map(|(_, path)| path)
+}
+
+/// Finds the file `spec` names when tapped from a file in `importer_dir`.
+///
+/// # Errors
+/// With every file that was tried, if none exists.
+fn resolve(spec: &str, importer_dir: &Path) -> Result<PathBuf, Vec<PathBuf>> {
+    let path = Path::new(spec);
+
+    let bases = if path.is_absolute() {
+        vec![path.to_path_buf()]
+    } else if spec.starts_with("./") || spec.starts_with("../") {
+        vec![importer_dir.join(path)]
+    } else {
+        let root = project_root(&entry().map(|e| dir_of(&e)).unwrap_or_else(|| importer_dir.to_path_buf()));
+        let mut bases = vec![importer_dir.join(path), root.join(path)];
+        bases.extend(workspace_member(spec, &root));
+        bases.push(root.join(MODULES_DIR).join(path));
+        bases
+    };
+
+    let mut tried = Vec::new();
+    for base in bases {
+        for file in candidates(&base) {
+            if file.is_file() {
+                return Ok(fs::canonicalize(&file).unwrap_or(file));
+            }
+            tried.push(file);
+        }
+    }
+
+    Err(tried)
+}
+
+/// The project root, package name and package directory of the installed
+/// package `path` is part of, if it is under a `paw_modules` directory.
+fn installed_package(path: &Path) -> Option<(PathBuf, String, PathBuf)> {
+    let modules = path.ancestors().find(|dir| dir.file_name().is_some_and(|n| n == MODULES_DIR))?;
+    let mut parts = path
+        .strip_prefix(modules)
+        .ok()?
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().into_owned());
+
+    let first = parts.next()?;
+    let name = if first.starts_with('@') {
+        format!("{}/{}", first, parts.next()?)
+    } else {
+        first
+    };
+
+    Some((modules.parent()?.to_path_buf(), name.clone(), modules.join(name)))
+}
+
+/// In `--frozen` runs, checks the installed package `path` belongs to
+/// against `paw.lock` the first time one of its files is tapped.
+///
+/// # Errors
+/// `LockfileMismatch` if there is no lockfile or the package differs from it.
+fn check_locked(path: &Path, span: Span) -> Result<(), PawxError> {
+    if !FROZEN.with(Cell::get) {
+        return Ok(());
+    }
+
+    let Some((root, name, dir)) = installed_package(path) else {
+        return Ok(());
+    };
+
+    if VERIFIED.with(|v| v.borrow().contains(&dir)) {
+        return Ok(());
+    }
+
+    let checked = match Lockfile::load(&root) {
+        Ok(Some(lock)) => lock.verify_package(&name, &dir),
+        Ok(None) => Err(vec![format!("--frozen requires {} in {}", LOCK_FILE, display(&root))]),
+        Err(e) => Err(vec![e]),
+    };
+
+    if let Err(problems) = checked {
+        return Err(PawxError::new(
+            ErrorCode::LockfileMismatch,
+            format!("package '{}' does not match {}", name, LOCK_FILE),
+            span,
+        )
+        .with_help(problems.join("; ")));
+    }
+
+    VERIFIED.with(|v| v.borrow_mut().insert(dir));
+    Ok(())
+}
+
+/// How `path` is shown in errors: relative to the working directory when
+/// it is under it.
+fn display(path: &Path) -> String {
+    let cwd = std::env::current_dir().ok().and_then(|d| fs::canonicalize(d).ok());
+    let shown = cwd.as_deref().and_then(|cwd| path.strip_prefix(cwd).ok()).unwrap_or(path);
+    shown.display().to_string()
+}
+
+/// Loads the module `spec` names for a `tap` in `ast` (see module docs).
+///
+/// # Errors
+/// - `ModuleNotFound` if no file matches
+/// - `CircularImport` if the module is still loading
+/// - `LockfileMismatch` if `--frozen` and its package differs from `paw.lock`
+/// - Any error the module's own code stops with
+pub fn load(spec: &str, ast: &Rc<Ast>, span: Span) -> Result<Value, PawxError> {
+    let importer = source_of(ast)
+        .or_else(|| LOADING.with(|l| l.borrow().last().cloned()))
+        .or_else(entry);
+    let importer_dir = importer.as_deref().map(dir_of).unwrap_or_else(|| PathBuf::from("."));
+
+    let path = resolve(spec, &importer_dir).map_err(|tried| {
+        let tried: Vec<String> = tried.iter().map(|p| display(p)).collect();
+        PawxError::new(ErrorCode::ModuleNotFound, format!("cannot find module '{}'", spec), span)
+            .with_help(format!("looked for {}", tried.join(", ")))
+    })?;
+
+    if let Some(module) = LOADED.with(|l| l.borrow().get(&path).cloned()) {
+        return Ok(module);
+    }
+
+    check_locked(&path, span)?;
+
+    let loading = LOADING.with(|l| l.borrow().clone());
+    if loading.contains(&path) {
+        let mut chain: Vec<String> = entry().iter().chain(&loading).map(|p| display(p)).collect();
+        chain.push(display(&path));
+        return Err(PawxError::new(
+            ErrorCode::CircularImport,
+            format!("circular tap of '{}': {}", spec, chain.join(" -> ")),
+            span,
+        ));
+    }
+
+    LOADING.with(|l| l.borrow_mut().push(path.clone()));
+    let result = run_module(&path, span);
+    LOADING.with(|l| l.borrow_mut().pop());
+
+    // Errors are reported against the importing file, so the span moves
+    // to the `tap` and the help keeps where in the module it happened
+    let module = result.map_err(|mut error| {
+        if error.help.is_none() {
+            let at = format!("{}:{}:{}", display(&path), error.span.line, error.span.column + 1);
+            error.help = Some(match &importer {
+                Some(importer) => format!("raised at {}, loaded by a tap in {}", at, display(importer)),
+                None => format!("raised at {}", at),
+            });
+        }
+        error.span = span;
+        error
+    })?;
+
+    LOADED.with(|l| l.borrow_mut().insert(path, module.clone()));
+    Ok(module)
+}
+
+/// Runs the file at `path` in a fresh scope and collects its exports.
+fn run_module(path: &Path, span: Span) -> Result<Value, PawxError> {
+    let source = fs::read_to_string(path).map_err(|e| {
+        PawxError::new(ErrorCode::ModuleNotFound, format!("cannot read module '{}': {}", display(path), e), span)
+    })?;
+
+    let statements = crate::ast_cache::parse(&source)?;
+    let (ast, body) = Ast::lower(statements);
+    register_source(&ast, path);
+
+    let env = Rc::new(RefCell::new(Environment::new(Some(global_env()))));
+    define_meta(&mut env.borrow_mut(), Some(path), false);
+
+    for stmt in body.iter() {
+        match exec_stmt(&ast, stmt, env.clone())? {
+            ExecSignal::None => {}
+            ExecSignal::Return(_) => break,
+            ExecSignal::Throw(err) => {
+                return Err(PawxError::new(
+                    ErrorCode::UncaughtException,
+                    format!("uncaught {}", value_to_string(&err)),
+                    current_span(),
+                ));
+            }
+        }
+    }
+
+    let scope = env.borrow();
+    let mut exports: HashMap<String, Value> = scope
+        .values
+        .iter()
+        .filter(|(_, entry)| entry.access == Access::Public)
+        .map(|(name, entry)| (name.clone(), entry.value.clone()))
+        .collect();
+
+    for (name, func) in &scope.functions {
+        exports.insert(name.clone(), function_value(func.clone(), env.clone()));
+    }
+
+    let default = exports.remove("default").map(Box::new);
+
+    Ok(Value::Module {
+        exports: Rc::new(exports),
+        default,
+    })
+}
@@ -0,0 +1,103 @@
+/*
+ * ============================================================================
+ * PAWX - Code with Claws!
+ * ============================================================================
+ *
+ * Author:   Sam Wilcox
+ * Email:    sam@pawx-lang.com
+ * Website:  https://www.pawx-lang.com
+ * Github:   https://github.com/samwilcox/pawx
+ *
+ * License:
+ * This file is part of the PAWX programming language project.
+ *
+ * PAWX is dual-licensed under the terms of:
+ *   - The MIT license
+ *   - The Apache License, Version 2.0
+ *
+ * You may choose either license to govern your use of this software.
+ * Full license text available at:
+ *    https://license.pawx-lang.com
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under these licenses is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *
+ * ============================================================================
+ */
+
+/*!
+ * Numeric Operators Beyond `+ - * / %`
+ * ------------------------------------
+ *
+ * ```text
+ * a ** b     power (right-associative; -2 ** 2 is -(2 ** 2))
+ * a ~/ b     integer division: a / b truncated toward zero, so
+ *            a == (a ~/ b) * b + a % b
+ * a & b, a | b, a ^ b, ~a      bitwise and, or, xor, not
+ * a << n, a >> n               shifts; >> keeps the sign
+ * a >>> n                      shift right, filling with zeros
+ * ```
+ *
+ * Numbers are f64, so the bitwise operators first convert each operand to
+ * a 64-bit signed integer:
+ *
+ *  - the fraction is dropped (toward zero): `5.9 & 7` is `5`, `-5.9 | 0`
+ *    is `-5`
+ *  - `NaN`, infinities and values outside the i64 range are errors rather
+ *    than silently wrapping
+ *  - the shift count is taken modulo 64, as the hardware does
+ *
+ * Results are converted back to f64, which is exact up to 2^53; beyond
+ * that the result is rounded to the nearest Number. `>>>` treats its left
+ * operand as an unsigned 64-bit pattern, so `-1 >>> 0` is 2^64 - 1.
+ */
+
+/// Converts a Number operand to the integer a bitwise operator works on.
+///
+/// # Errors
+/// If `n` is `NaN`, infinite, or outside the i64 range.
+pub fn to_integer(n: f64) -> Result<i64, String> {
+    // i64::MAX is not representable; 2^63 is the first value past it
+    const LIMIT: f64 = 9_223_372_036_854_775_808.0;
+
+    if n.is_finite() && (-LIMIT..LIMIT).contains(&n.trunc()) {
+        Ok(n.trunc() as i64)
+    } else {
+        Err(format!("{} is not a 64-bit integer", n))
+    }
+}
+
+/// Evaluates `a op b` for the operators above.
+///
+/// # Errors
+/// For an operand outside the integer rules, an integer division by
+/// zero, or an operator not listed above.
+pub fn binary_op(a: f64, b: f64, op: &str) -> Result<f64, String> {
+    let integers = || Ok::<_, String>((to_integer(a)?, to_integer(b)?));
+    let shift = |n: i64| (n & 63) as u32;
+
+    match op {
+        "**" => Ok(a.powf(b)),
+
+        "~/" if b == 0.0 => Err("integer division by zero".to_string()),
+        "~/" => Ok((a / b).trunc()),
+
+        "&" => integers().map(|(x, y)| (x & y) as f64),
+        "|" => integers().map(|(x, y)| (x | y) as f64),
+        "^" => integers().map(|(x, y)| (x ^ y) as f64),
+        "<<" => integers().map(|(x, y)| x.wrapping_shl(shift(y)) as f64),
+        ">>" => integers().map(|(x, y)| x.wrapping_shr(shift(y)) as f64),
+        ">>>" => integers().map(|(x, y)| (x as u64).wrapping_shr(shift(y)) as f64),
+
+        _ => Err(format!("'{}' is not a numeric operator", op)),
+    }
+}
+
+/// Evaluates `~n`.
+///
+/// # Errors
+/// As [`to_integer`].
+pub fn bitwise_not(n: f64) -> Result<f64, String> {
+    to_integer(n).map(|x| !x as f64)
+}
@@ -0,0 +1,144 @@
+/*
+ * ==========================================================================
+ * PAWX - Code with Claws!
+ * ==========================================================================
+ *
+ * Pattern Matching
+ *
+ * Tests `match` arm patterns against runtime values and collects the
+ * bindings a successful match introduces into the arm's scope.
+ *
+ * --------------------------------------------------------------------------
+ * Author:   Sam Wilcox
+ * Email:    sam@pawx-lang.com
+ * Website:  https://www.pawx-lang.com
+ * Github:   https://github.com/samwilcox/pawx
+ *
+ * License:
+ * This file is part of the PAWX programming language project.
+ *
+ * PAWX is dual-licensed under the terms of:
+ *   - The MIT License
+ *   - The Apache License, Version 2.0
+ *
+ * You may choose either license to govern your use of this software.
+ * Full license text available at:
+ *    https://license.pawx-lang.com
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under these licenses is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *
+ * ==========================================================================
+ */
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::ast::Pattern;
+use crate::error::PawxError;
+use crate::value::Value;
+
+/// Tests `value` against `pattern`.
+///
+/// On success, every binding the pattern introduces is appended to
+/// `bindings` as `(name, value)`. On failure `bindings` may hold partial
+/// results and should be discarded.
+///
+/// # Errors
+/// If a literal and the value are nested deeper than `--max-depth`.
+///
+/// # Rules
+/// - Literals compare with strict equality
+/// - Type patterns compare against `Value::type_name()`; instances also
+///   match their class name
+/// - Object patterns require every listed key (extra keys are allowed)
+/// - Array patterns require an exact length unless they end with `...`
+pub fn match_pattern(pattern: &Pattern, value: &Value, bindings: &mut Vec<(String, Value)>) -> Result<bool, PawxError> {
+    match pattern {
+        Pattern::Wildcard => Ok(true),
+
+        Pattern::Literal(expected) => Value::equals_strict(expected, value),
+
+        Pattern::Binding(name) => {
+            bindings.push((name.clone(), value.clone()));
+            Ok(true)
+        }
+
+        Pattern::Type { type_name, binding } => {
+            if !matches_type(type_name, value) {
+                return Ok(false);
+            }
+
+            if let Some(name) = binding {
+                bindings.push((name.clone(), value.clone()));
+            }
+            Ok(true)
+        }
+
+        Pattern::Object { fields } => {
+            let map = match value {
+                Value::Object { fields } | Value::Instance { fields, .. } => fields.borrow().clone(),
+                _ => return Ok(false),
+            };
+
+            for (key, sub) in fields {
+                match map.get(key) {
+                    Some(field) if match_pattern(sub, field, bindings)? => {}
+                    _ => return Ok(false),
+                }
+            }
+            Ok(true)
+        }
+
+        Pattern::Array { elements, rest } => {
+            let items = match value {
+                Value::Array { values, .. } => values.borrow().clone(),
+                _ => return Ok(false),
+            };
+
+            let length_ok = match rest {
+                None => items.len() == elements.len(),
+                Some(_) => items.len() >= elements.len(),
+            };
+
+            if !length_ok {
+                return Ok(false);
+            }
+
+            for (sub, item) in elements.iter().zip(items.iter()) {
+                if !match_pattern(sub, item, bindings)? {
+                    return Ok(false);
+                }
+            }
+
+            if let Some(Some(name)) = rest {
+                let tail = items[elements.len()..].to_vec();
+                bindings.push((
+                    name.clone(),
+                    Value::Array {
+                        values: Rc::new(RefCell::new(tail)),
+                        proto: crate::prototypes::array::create_array_proto(),
+                    },
+                ));
+            }
+
+            Ok(true)
+        }
+    }
+}
+
+/// Checks a value against a type pattern name.
+fn matches_type(type_name: &str, value: &Value) -> bool {
+    if let Value::Instance { class, .. } = value {
+        if class.name == type_name {
+            return true;
+        }
+    }
+
+    match type_name {
+        "Boolean" => matches!(value, Value::Bool(_)),
+        "Any" => true,
+        other => value.type_name() == other,
+    }
+}
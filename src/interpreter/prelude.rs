@@ -0,0 +1,750 @@
+/*
+ * ============================================================================
+ * PAWX - Code with Claws!
+ * ============================================================================
+ *
+ * Author:   Sam Wilcox
+ * Email:    sam@pawx-lang.com
+ * Website:  https://www.pawx-lang.com
+ * Github:   https://github.com/samwilcox/pawx
+ *
+ * License:
+ * This file is part of the PAWX programming language project.
+ *
+ * PAWX is dual-licensed under the terms of:
+ *   - The MIT license
+ *   - The Apache License, Version 2.0
+ *
+ * You may choose either license to govern your use of this software.
+ * Full license text available at:
+ *    https://license.pawx-lang.com
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under these licenses is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *
+ * ============================================================================
+ */
+
+/*!
+ * PAWX Prelude
+ * ------------
+ *
+ * The prelude decides which **standard global objects** are installed into
+ * the root environment before a program starts executing.
+ *
+ * By default every program receives:
+ *
+ *  • Error
+ *  • Array / Float64Array
+ *  • Immutable
+ *  • String / StringBuilder
+ *  • Math
+ *  • Decimal
+ *  • Duration
+ *  • Time / Date
+ *  • Perf
+ *  • Intl
+ *  • Json
+ *  • Codec
+ *  • Msgpack / Protobuf
+ *  • Markdown / Template / Site
+ *  • Http
+ *  • Rpc
+ *  • Mqtt
+ *  • Serial (with the `serial` feature)
+ *  • Gui (with the `gui` feature)
+ *  • Isolate (with the `isolates` feature)
+ *  • Regex
+ *  • Fs
+ *  • CancelToken
+ *  • Context
+ *  • Stream
+ *  • Auth
+ *  • RateLimit / Semaphore
+ *  • Schema
+ *  • memoize / Memo
+ *  • WeakRef
+ *  • eval / compile
+ *  • Process
+ *  • Io / listen
+ *  • assert / assertEq / assertNe / expect
+ *  • Mock / Test
+ *
+ * Embedders (and the `pawx` binary through CLI flags) can build a custom
+ * prelude to compose minimal, deterministic, or sandboxed environments:
+ *
+ * ```text
+ * Prelude::empty()
+ *     .with("Math", "Math")
+ *     .with("Files", "Fs")
+ * ```
+ *
+ * Core intrinsics such as `meow` and the timer functions are part of the
+ * runtime itself and are always installed.
+ *
+ * A prelude may also enable **virtual time** (`--virtual-time`), which binds
+ * timers and `Time` to a controllable clock and installs the `Clock` global.
+ *
+ * A **deterministic** prelude (`--deterministic`) also seeds `Math.random`
+ * and sorts object keys wherever they are observed (see `determinism`).
+ *
+ * Likewise, an **in-memory filesystem** (`pawx test --sandbox-fs`) gives
+ * `Fs` a private tree instead of the disk.
+ *
+ * Hosts running untrusted code can forbid `eval` and `compile` outright
+ * (`--no-eval`), including in isolates the program spawns.
+ *
+ * Calls and nested values are limited to `--max-depth` levels (see
+ * limits.rs), which isolates inherit too.
+ *
+ * There is deliberately no startup snapshot of the initialized globals.
+ * Nearly every global is a table of native functions, which are Rust
+ * closures and cannot be written to disk, and `install` only registers
+ * builders: a module is constructed the first time a program reads it.
+ * Starting the interpreter with every global in use takes a few
+ * milliseconds, and parsing is already skipped for unchanged files by the
+ * AST cache (`ast_cache.rs`), which is keyed by the binary version.
+ */
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use crate::interpreter::clock::{create_global_clock_value, VirtualClock};
+use crate::interpreter::conditional::Defines;
+use crate::interpreter::environment::Environment;
+use crate::interpreter::hooks::{HookSet, InterpreterHooks};
+use crate::interpreter::reporters::Reporter;
+use crate::prototypes::memfs::{FsBackend, MemoryFs};
+use crate::value::Value;
+
+/* ============================================================================
+ * Built-in Module Registry
+ * ============================================================================
+ */
+
+/// Names of every built-in module that can be installed as a global.
+///
+/// Lookups against this table are case-insensitive, so `--global Files=fs`
+/// and `--global Files=Fs` resolve to the same module.
+pub const BUILTIN_MODULES: &[&str] = &[
+    "Error",
+    "Array",
+    "Float64Array",
+    "Immutable",
+    "String",
+    "StringBuilder",
+    "Math",
+    "Decimal",
+    "Duration",
+    "Time",
+    "Date",
+    "Perf",
+    "Intl",
+    "Json",
+    "Codec",
+    "Msgpack",
+    "Protobuf",
+    "Markdown",
+    "Template",
+    "Site",
+    "Http",
+    "Rpc",
+    "Mqtt",
+    #[cfg(feature = "serial")]
+    "Serial",
+    #[cfg(feature = "gui")]
+    "Gui",
+    #[cfg(feature = "isolates")]
+    "Isolate",
+    "Regex",
+    "Fs",
+    "CancelToken",
+    "Context",
+    "Stream",
+    "Auth",
+    "RateLimit",
+    "Semaphore",
+    "Schema",
+    "memoize",
+    "Memo",
+    "WeakRef",
+    "eval",
+    "compile",
+    "Process",
+    "Io",
+    "listen",
+    "assert",
+    "assertEq",
+    "assertNe",
+    "expect",
+    "Mock",
+    "Test",
+];
+
+/// Resolves a user-supplied module name to its canonical spelling.
+///
+/// # Returns
+/// - `Some(&str)` with the canonical name if the module exists
+/// - `None` if no built-in module matches
+pub fn canonical_module_name(module: &str) -> Option<&'static str> {
+    BUILTIN_MODULES
+        .iter()
+        .copied()
+        .find(|m| m.eq_ignore_ascii_case(module))
+}
+
+/// Constructs a fresh runtime value for a built-in module.
+///
+/// Every call builds a **new** object, so two globals bound to the same
+/// module never share mutable state.
+///
+/// # Parameters
+/// - `module`: Built-in module name (case-insensitive)
+/// - `clock`: Virtual clock to bind time-based modules to, if any
+/// - `argv`: Script path and arguments exposed as `Process.argv`
+/// - `fs`: Where `Fs` (and `Test.tempDir()`) keep their files
+///
+/// # Returns
+/// - `Some(Value)` for a known module
+/// - `None` if the module name is unknown
+pub fn create_builtin_module(
+    module: &str,
+    clock: Option<&VirtualClock>,
+    argv: &[String],
+    fs: &FsBackend,
+) -> Option<Value> {
+    let value = match canonical_module_name(module)? {
+        "Error" => Value::NativeFunction(Arc::new(|args| {
+            let message = match args.first() {
+                Some(Value::String(s)) => s.to_string(),
+                _ => "Unknown error".to_string(),
+            };
+            Ok(Value::Error { message })
+        })),
+
+        "Array" => crate::prototypes::array::create_global_array_object(),
+
+        "Float64Array" => Value::Object {
+            fields: Rc::new(RefCell::new(
+                crate::prototypes::typed_array::create_global_float64_array_object(),
+            )),
+        },
+
+        "Immutable" => Value::Object {
+            fields: Rc::new(RefCell::new(
+                crate::prototypes::immutable::create_global_immutable_object(),
+            )),
+        },
+
+        "String" => Value::Object {
+            fields: Rc::new(RefCell::new(
+                crate::prototypes::string::create_global_string_object(),
+            )),
+        },
+
+        "StringBuilder" => crate::prototypes::string_builder::create_global_string_builder_value(),
+
+        "Math" => crate::prototypes::math::create_global_math_value(),
+
+        "Decimal" => Value::Object {
+            fields: Rc::new(RefCell::new(
+                crate::prototypes::decimal::create_global_decimal_object(),
+            )),
+        },
+
+        "Duration" => Value::Object {
+            fields: Rc::new(RefCell::new(
+                crate::prototypes::duration::create_global_duration_object(clock),
+            )),
+        },
+
+        "Time" | "Date" => match clock {
+            Some(clock) => crate::prototypes::time::create_virtual_time_value(clock),
+            None => crate::prototypes::time::create_global_time_value(),
+        },
+
+        "Perf" => Value::Object {
+            fields: Rc::new(RefCell::new(crate::prototypes::perf::create_global_perf_object())),
+        },
+
+        "Intl" => Value::Object {
+            fields: Rc::new(RefCell::new(
+                crate::prototypes::intl::create_global_intl_object(),
+            )),
+        },
+
+        "Json" => Value::Object {
+            fields: Rc::new(RefCell::new(
+                crate::prototypes::json::create_global_json_object(),
+            )),
+        },
+
+        "Codec" => Value::Object {
+            fields: Rc::new(RefCell::new(
+                crate::prototypes::codec::create_global_codec_object(),
+            )),
+        },
+
+        "Msgpack" => Value::Object {
+            fields: Rc::new(RefCell::new(
+                crate::prototypes::msgpack::create_global_msgpack_object(),
+            )),
+        },
+
+        "Protobuf" => Value::Object {
+            fields: Rc::new(RefCell::new(
+                crate::prototypes::protobuf::create_global_protobuf_object(),
+            )),
+        },
+
+        "Markdown" => Value::Object {
+            fields: Rc::new(RefCell::new(
+                crate::prototypes::markdown::create_global_markdown_object(),
+            )),
+        },
+
+        "Template" => Value::Object {
+            fields: Rc::new(RefCell::new(
+                crate::prototypes::template::create_global_template_object(),
+            )),
+        },
+
+        "Site" => Value::Object {
+            fields: Rc::new(RefCell::new(
+                crate::prototypes::site::create_global_site_object(),
+            )),
+        },
+
+        "Http" => crate::prototypes::http::create_global_http_object(),
+
+        "Rpc" => Value::Object {
+            fields: Rc::new(RefCell::new(
+                crate::prototypes::rpc::create_global_rpc_object(),
+            )),
+        },
+
+        "Mqtt" => Value::Object {
+            fields: Rc::new(RefCell::new(
+                crate::prototypes::mqtt::create_global_mqtt_object(),
+            )),
+        },
+
+        #[cfg(feature = "serial")]
+        "Serial" => Value::Object {
+            fields: Rc::new(RefCell::new(
+                crate::prototypes::serial::create_global_serial_object(),
+            )),
+        },
+
+        #[cfg(feature = "gui")]
+        "Gui" => Value::Object {
+            fields: Rc::new(RefCell::new(
+                crate::prototypes::gui::create_global_gui_object(),
+            )),
+        },
+
+        #[cfg(feature = "isolates")]
+        "Isolate" => Value::Object {
+            fields: Rc::new(RefCell::new(
+                crate::prototypes::isolate::create_global_isolate_object(),
+            )),
+        },
+
+        "Regex" => Value::Object {
+            fields: Rc::new(RefCell::new(
+                crate::prototypes::regex::create_global_regex_object(),
+            )),
+        },
+
+        "Fs" => crate::prototypes::fs::create_fs_global(fs.clone()),
+
+        "CancelToken" => crate::prototypes::cancel::create_global_cancel_token_value(),
+
+        "Context" => Value::Object {
+            fields: Rc::new(RefCell::new(
+                crate::prototypes::context::create_global_context_object(),
+            )),
+        },
+
+        "Stream" => Value::Object {
+            fields: Rc::new(RefCell::new(
+                crate::prototypes::stream::create_global_stream_object(),
+            )),
+        },
+
+        "Auth" => Value::Object {
+            fields: Rc::new(RefCell::new(
+                crate::prototypes::auth::create_global_auth_object(),
+            )),
+        },
+
+        "RateLimit" => Value::Object {
+            fields: Rc::new(RefCell::new(
+                crate::prototypes::limit::create_global_rate_limit_object(clock),
+            )),
+        },
+
+        "Semaphore" => crate::prototypes::limit::create_global_semaphore_value(),
+
+        "Schema" => Value::Object {
+            fields: Rc::new(RefCell::new(
+                crate::prototypes::schema::create_global_schema_object(),
+            )),
+        },
+
+        "memoize" => crate::prototypes::memo::create_global_memoize_value(),
+
+        "Memo" => Value::Object {
+            fields: Rc::new(RefCell::new(
+                crate::prototypes::memo::create_global_memo_object(),
+            )),
+        },
+
+        "WeakRef" => crate::prototypes::weak::create_global_weak_ref_value(),
+
+        "eval" => crate::prototypes::eval::create_global_eval_value(),
+
+        "compile" => crate::prototypes::eval::create_global_compile_value(),
+
+        "Process" => Value::Object {
+            fields: Rc::new(RefCell::new(
+                crate::prototypes::process::create_global_process_object(argv),
+            )),
+        },
+
+        "Io" => Value::Object {
+            fields: Rc::new(RefCell::new(crate::prototypes::io::create_global_io_object())),
+        },
+
+        "listen" => crate::prototypes::io::create_global_listen_value(),
+
+        "assert" => crate::prototypes::assert::create_global_assert_value(),
+
+        "assertEq" => crate::prototypes::assert::create_global_assert_eq_value(),
+
+        "assertNe" => crate::prototypes::assert::create_global_assert_ne_value(),
+
+        "expect" => crate::prototypes::assert::create_global_expect_value(),
+
+        "Mock" => Value::Object {
+            fields: Rc::new(RefCell::new(crate::prototypes::mock::create_global_mock_object())),
+        },
+
+        "Test" => Value::Object {
+            fields: Rc::new(RefCell::new(crate::prototypes::mock::create_global_test_object(fs))),
+        },
+
+        _ => return None,
+    };
+
+    Some(value)
+}
+
+/* ============================================================================
+ * Prelude Builder
+ * ============================================================================
+ */
+
+/// Describes the set of global objects installed before a program runs.
+///
+/// Each entry binds a **global name** to a **built-in module**. Entries are
+/// installed in insertion order, and a later binding for the same name
+/// replaces an earlier one.
+#[derive(Debug, Clone)]
+pub struct Prelude {
+    globals: Vec<(String, String)>,
+    virtual_clock: Option<VirtualClock>,
+    deterministic: Option<u64>,
+    argv: Vec<String>,
+    defines: Defines,
+    tests: bool,
+    update_snapshots: bool,
+    test_filter: Option<String>,
+    reporters: Vec<Reporter>,
+    fs: FsBackend,
+    hooks: Option<HookSet>,
+    output: Option<OutputBuffer>,
+    eval: bool,
+    max_depth: usize,
+    frozen: bool,
+}
+
+/// Collects what `meow` prints when output is captured.
+pub type OutputBuffer = Rc<RefCell<String>>;
+
+impl Default for Prelude {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Prelude {
+    /// Creates the standard prelude containing every default global.
+    pub fn new() -> Self {
+        let globals = BUILTIN_MODULES
+            .iter()
+            .map(|m| (m.to_string(), m.to_string()))
+            .collect();
+
+        Self {
+            globals,
+            virtual_clock: None,
+            deterministic: None,
+            argv: Vec::new(),
+            defines: Defines::default(),
+            tests: false,
+            update_snapshots: false,
+            test_filter: None,
+            reporters: Vec::new(),
+            fs: FsBackend::Disk,
+            hooks: None,
+            output: None,
+            eval: true,
+            max_depth: crate::interpreter::limits::DEFAULT_MAX_DEPTH,
+            frozen: false,
+        }
+    }
+
+    /// Creates an empty prelude with no standard globals installed.
+    pub fn empty() -> Self {
+        Self {
+            globals: Vec::new(),
+            virtual_clock: None,
+            deterministic: None,
+            argv: Vec::new(),
+            defines: Defines::default(),
+            tests: false,
+            update_snapshots: false,
+            test_filter: None,
+            reporters: Vec::new(),
+            fs: FsBackend::Disk,
+            hooks: None,
+            output: None,
+            eval: true,
+            max_depth: crate::interpreter::limits::DEFAULT_MAX_DEPTH,
+            frozen: false,
+        }
+    }
+
+    /// Binds the built-in `module` under the global `name` (builder-style).
+    ///
+    /// # Example
+    /// ```text
+    /// Prelude::empty().with("Files", "Fs")
+    /// ```
+    pub fn with(mut self, name: impl Into<String>, module: impl Into<String>) -> Self {
+        let name = name.into();
+        self.globals.retain(|(existing, _)| *existing != name);
+        self.globals.push((name, module.into()));
+        self
+    }
+
+    /// Removes the global `name` from the prelude (builder-style).
+    pub fn without(mut self, name: &str) -> Self {
+        self.globals.retain(|(existing, _)| existing != name);
+        self
+    }
+
+    /// Enables virtual time (builder-style).
+    ///
+    /// Timers and `Time.now()` run against a virtual clock that only moves
+    /// when advanced, and the `Clock` global is installed.
+    pub fn with_virtual_time(mut self) -> Self {
+        self.virtual_clock = Some(VirtualClock::new());
+        self
+    }
+
+    /// Enables deterministic mode with the given `Math.random` seed
+    /// (builder-style).
+    ///
+    /// Implies virtual time, and object keys are observed in sorted order.
+    pub fn with_deterministic(mut self, seed: u64) -> Self {
+        self.deterministic = Some(seed);
+        if self.virtual_clock.is_none() {
+            self = self.with_virtual_time();
+        }
+        self
+    }
+
+    /// Returns the `Math.random` seed if deterministic mode is enabled.
+    pub fn deterministic_seed(&self) -> Option<u64> {
+        self.deterministic
+    }
+
+    /// Reports the program's statements, calls, throws and finalizer passes
+    /// to `hooks` (builder-style).
+    pub fn with_hooks(mut self, hooks: Rc<dyn InterpreterHooks>) -> Self {
+        self.hooks = Some(HookSet(hooks));
+        self
+    }
+
+    /// Returns the hooks set with [`with_hooks`](Self::with_hooks).
+    pub fn hooks(&self) -> Option<Rc<dyn InterpreterHooks>> {
+        self.hooks.as_ref().map(|h| h.0.clone())
+    }
+
+    /// Sets the script path and arguments seen as `Process.argv` (builder-style).
+    pub fn with_argv(mut self, argv: Vec<String>) -> Self {
+        self.argv = argv;
+        self
+    }
+
+    /// Sets the values `when` conditions see (builder-style).
+    ///
+    /// Names not listed here fall back to environment variables.
+    pub fn with_defines(mut self, defines: Defines) -> Self {
+        self.defines = defines;
+        self
+    }
+
+    /// Returns the values used to decide `when` blocks.
+    pub fn defines(&self) -> &Defines {
+        &self.defines
+    }
+
+    /// Enables test mode (builder-style): `test` blocks are collected and
+    /// run after the program's top-level code.
+    pub fn with_tests(mut self) -> Self {
+        self.tests = true;
+        self
+    }
+
+    /// Returns whether test mode is enabled.
+    pub fn tests_enabled(&self) -> bool {
+        self.tests
+    }
+
+    /// Rewrites snapshots that no longer match instead of failing
+    /// (builder-style). Only has an effect in test mode.
+    pub fn with_snapshot_updates(mut self) -> Self {
+        self.update_snapshots = true;
+        self
+    }
+
+    /// Returns whether mismatching snapshots are rewritten.
+    pub fn snapshot_updates_enabled(&self) -> bool {
+        self.update_snapshots
+    }
+
+    /// Only runs tests whose name contains `pattern` (builder-style).
+    pub fn with_test_filter(mut self, pattern: impl Into<String>) -> Self {
+        self.test_filter = Some(pattern.into());
+        self
+    }
+
+    /// Returns the test name filter, if any.
+    pub fn test_filter(&self) -> Option<&str> {
+        self.test_filter.as_deref()
+    }
+
+    /// Writes a test report after the run (builder-style).
+    pub fn with_reporter(mut self, reporter: Reporter) -> Self {
+        self.reporters.push(reporter);
+        self
+    }
+
+    /// Returns the test reports to write.
+    pub fn reporters(&self) -> &[Reporter] {
+        &self.reporters
+    }
+
+    /// Gives `Fs` an in-memory filesystem instead of the disk
+    /// (builder-style). The tree starts empty and lives as long as the
+    /// program.
+    pub fn with_memory_fs(mut self) -> Self {
+        self.fs = FsBackend::Memory(MemoryFs::new());
+        self
+    }
+
+    /// Makes `eval` and `compile` throw instead of running code
+    /// (builder-style). Isolates spawned by the program inherit this.
+    pub fn without_eval(mut self) -> Self {
+        self.eval = false;
+        self
+    }
+
+    /// Returns whether `eval` and `compile` may run code.
+    pub fn eval_enabled(&self) -> bool {
+        self.eval
+    }
+
+    /// Sets how deep calls and nested values may go (builder-style).
+    /// Isolates spawned by the program inherit this.
+    pub fn with_max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = depth;
+        self
+    }
+
+    /// Returns the call and nesting depth limit.
+    pub fn max_depth(&self) -> usize {
+        self.max_depth
+    }
+
+    /// Checks every installed package a `tap` loads against `paw.lock`
+    /// before it runs (builder-style).
+    pub fn with_frozen(mut self) -> Self {
+        self.frozen = true;
+        self
+    }
+
+    /// Returns whether tapped packages are checked against `paw.lock`.
+    pub fn frozen_enabled(&self) -> bool {
+        self.frozen
+    }
+
+    /// Appends everything `meow` prints to `buffer` instead of writing it
+    /// to stdout (builder-style).
+    pub fn with_captured_output(mut self, buffer: OutputBuffer) -> Self {
+        self.output = Some(buffer);
+        self
+    }
+
+    /// Returns the buffer `meow` writes to, if output is captured.
+    pub fn output(&self) -> Option<OutputBuffer> {
+        self.output.clone()
+    }
+
+    /// Returns the script path (`Process.argv[0]`), if one was set.
+    pub fn script(&self) -> Option<&str> {
+        self.argv.first().map(String::as_str)
+    }
+
+    /// Returns the virtual clock if virtual time is enabled.
+    pub fn virtual_clock(&self) -> Option<&VirtualClock> {
+        self.virtual_clock.as_ref()
+    }
+
+    /// Installs every configured global into the given environment.
+    ///
+    /// Each global is built the first time the program reads it.
+    ///
+    /// # Panics
+    /// - If a binding refers to an unknown built-in module
+    pub fn install(&self, env: &Rc<RefCell<Environment>>) {
+        for (name, module) in &self.globals {
+            let module = canonical_module_name(module).unwrap_or_else(|| {
+                panic!(
+                    "Unknown built-in module '{}' for global '{}' (available: {})",
+                    module,
+                    name,
+                    BUILTIN_MODULES.join(", ")
+                )
+            });
+
+            // Most programs touch a handful of globals; build each one on
+            // first use instead of paying for all of them up front.
+            let (clock, argv, fs) = (self.virtual_clock.clone(), self.argv.clone(), self.fs.clone());
+            env.borrow_mut().define_lazy(name.clone(), move || {
+                create_builtin_module(module, clock.as_ref(), &argv, &fs)
+                    .expect("canonical module names always build")
+            });
+        }
+
+        if let Some(clock) = &self.virtual_clock {
+            env.borrow_mut()
+                .define_public("Clock".to_string(), create_global_clock_value(clock));
+        }
+    }
+}
@@ -0,0 +1,133 @@
+/*
+ * ============================================================================
+ * PAWX - Code with Claws!
+ * ============================================================================
+ *
+ * Author:   Sam Wilcox
+ * Email:    sam@pawx-lang.com
+ * Website:  https://www.pawx-lang.com
+ * Github:   https://github.com/samwilcox/pawx
+ *
+ * License:
+ * This file is part of the PAWX programming language project.
+ *
+ * PAWX is dual-licensed under the terms of:
+ *   - The MIT license
+ *   - The Apache License, Version 2.0
+ *
+ * You may choose either license to govern your use of this software.
+ * Full license text available at:
+ *    https://license.pawx-lang.com
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under these licenses is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *
+ * ============================================================================
+ */
+
+/*!
+ * PAWX Profiler (`pawx --profile`)
+ * --------------------------------
+ *
+ * Counts what a program does through [`InterpreterHooks`] and prints the
+ * busiest lines and most called functions when it exits:
+ *
+ * ```text
+ * profile: 3955 statements, 1976 calls, 1 throws, 0 finalizer passes
+ *
+ *     line      runs
+ *        2      2960
+ *        3       986
+ *
+ *   function     calls
+ *   fib           1973
+ * ```
+ *
+ * It counts rather than times, so results are exact and the same on every
+ * run of the same program.
+ */
+
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+
+use crate::interpreter::hooks::InterpreterHooks;
+use crate::span::Span;
+use crate::value::Value;
+
+/// Rows shown per table.
+const TOP: usize = 10;
+
+/// Statement and call counters for one program run.
+#[derive(Default)]
+pub struct Profiler {
+    lines: RefCell<HashMap<usize, u64>>,
+    calls: RefCell<HashMap<String, u64>>,
+    statements: Cell<u64>,
+    throws: Cell<u64>,
+    gc_passes: Cell<u64>,
+}
+
+impl InterpreterHooks for Profiler {
+    fn on_statement(&self, span: Span) {
+        self.statements.set(self.statements.get() + 1);
+        *self.lines.borrow_mut().entry(span.line).or_default() += 1;
+    }
+
+    fn on_call(&self, name: &str, _span: Span) {
+        let mut calls = self.calls.borrow_mut();
+        match calls.get_mut(name) {
+            Some(count) => *count += 1,
+            None => {
+                calls.insert(name.to_string(), 1);
+            }
+        }
+    }
+
+    fn on_throw(&self, _error: &Value, _span: Span) {
+        self.throws.set(self.throws.get() + 1);
+    }
+
+    fn on_gc(&self, _finalized: usize) {
+        self.gc_passes.set(self.gc_passes.get() + 1);
+    }
+}
+
+impl Profiler {
+    /// Prints the summary to stderr.
+    pub fn report(&self) {
+        let total_calls: u64 = self.calls.borrow().values().sum();
+
+        eprintln!();
+        eprintln!(
+            "profile: {} statements, {} calls, {} throws, {} finalizer passes",
+            self.statements.get(),
+            total_calls,
+            self.throws.get(),
+            self.gc_passes.get()
+        );
+
+        eprintln!();
+        eprintln!("  {:>6}  {:>8}", "line", "runs");
+        for (line, runs) in top(self.lines.borrow().iter().map(|(l, n)| (*l, *n))) {
+            eprintln!("  {:>6}  {:>8}", line, runs);
+        }
+
+        let calls = self.calls.borrow();
+        let width = calls.keys().map(String::len).max().unwrap_or(0).max("function".len());
+
+        eprintln!();
+        eprintln!("  {:<width$}  {:>8}", "function", "calls");
+        for (name, count) in top(calls.iter().map(|(name, n)| (name.as_str(), *n))) {
+            eprintln!("  {:<width$}  {:>8}", name, count);
+        }
+    }
+}
+
+/// The `TOP` largest counts, ties broken by key.
+fn top<K: Ord>(counts: impl Iterator<Item = (K, u64)>) -> Vec<(K, u64)> {
+    let mut rows: Vec<(K, u64)> = counts.collect();
+    rows.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    rows.truncate(TOP);
+    rows
+}
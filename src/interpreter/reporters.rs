@@ -0,0 +1,241 @@
+/*
+ * ============================================================================
+ * PAWX - Code with Claws!
+ * ============================================================================
+ *
+ * Author:   Sam Wilcox
+ * Email:    sam@pawx-lang.com
+ * Website:  https://www.pawx-lang.com
+ * Github:   https://github.com/samwilcox/pawx
+ *
+ * License:
+ * This file is part of the PAWX programming language project.
+ *
+ * PAWX is dual-licensed under the terms of:
+ *   - The MIT license
+ *   - The Apache License, Version 2.0
+ *
+ * You may choose either license to govern your use of this software.
+ * Full license text available at:
+ *    https://license.pawx-lang.com
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under these licenses is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *
+ * ============================================================================
+ */
+
+/*!
+ * PAWX Test Reporters
+ * -------------------
+ *
+ * Machine-readable test results for CI dashboards, requested with
+ * `pawx test --reporter <format>:<file>`:
+ *
+ *   - `junit`  JUnit XML, one `<testsuite>` per test file
+ *   - `tap`    TAP version 13
+ *   - `json`   an array of [`TestRecord`]s
+ *
+ * Reports are always written to the real disk, even under `--sandbox-fs`.
+ * When several files run in their own processes, each process writes a
+ * `json` report and `pawx test` merges them into the requested formats.
+ */
+
+use serde::{Deserialize, Serialize};
+
+use crate::prototypes::memfs::FsBackend;
+
+/// How a test ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TestStatus {
+    Passed,
+    Failed,
+    Skipped,
+}
+
+/// The result of one test.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TestRecord {
+    /// The test file.
+    pub suite: String,
+    pub name: String,
+    pub status: TestStatus,
+
+    /// Why the test failed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+
+    pub duration_ms: f64,
+}
+
+/// A report format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    Junit,
+    Tap,
+    Json,
+}
+
+/// A report to write after the run.
+#[derive(Debug, Clone)]
+pub struct Reporter {
+    pub format: ReportFormat,
+    pub path: String,
+}
+
+impl Reporter {
+    /// Parses `<format>:<file>`, e.g. `junit:reports/tests.xml`.
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let (format, path) = spec
+            .split_once(':')
+            .filter(|(_, path)| !path.is_empty())
+            .ok_or_else(|| format!("--reporter expects <format>:<file>, got '{}'", spec))?;
+
+        let format = match format {
+            "junit" => ReportFormat::Junit,
+            "tap" => ReportFormat::Tap,
+            "json" => ReportFormat::Json,
+            other => return Err(format!("Unknown reporter '{}' (available: junit, tap, json)", other)),
+        };
+
+        Ok(Self {
+            format,
+            path: path.to_string(),
+        })
+    }
+
+    /// Renders `records` and writes them to the report file.
+    pub fn write(&self, records: &[TestRecord]) -> Result<(), String> {
+        let text = match self.format {
+            ReportFormat::Junit => junit(records),
+            ReportFormat::Tap => tap(records),
+            ReportFormat::Json => serde_json::to_string_pretty(records).map_err(|e| e.to_string())?,
+        };
+
+        let disk = FsBackend::Disk;
+        if let Some(dir) = std::path::Path::new(&self.path).parent().filter(|d| !d.as_os_str().is_empty()) {
+            disk.create_dir(&dir.to_string_lossy(), true)
+                .map_err(|e| format!("{}: {}", dir.display(), e))?;
+        }
+        disk.write(&self.path, text.as_bytes())
+            .map_err(|e| format!("{}: {}", self.path, e))
+    }
+}
+
+/// Reads a `json` report written by another `pawx test` process.
+pub fn read_json(path: &str) -> Result<Vec<TestRecord>, String> {
+    let bytes = FsBackend::Disk.read(path).map_err(|e| format!("{}: {}", path, e))?;
+    serde_json::from_slice(&bytes).map_err(|e| format!("{}: {}", path, e))
+}
+
+fn escape_xml(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+fn seconds(ms: f64) -> String {
+    format!("{:.3}", ms / 1000.0)
+}
+
+/// Counts `(tests, failures, skipped, total ms)`.
+fn totals<'a>(records: impl Iterator<Item = &'a TestRecord>) -> (usize, usize, usize, f64) {
+    records.fold((0, 0, 0, 0.0), |(n, f, s, ms), r| {
+        (
+            n + 1,
+            f + (r.status == TestStatus::Failed) as usize,
+            s + (r.status == TestStatus::Skipped) as usize,
+            ms + r.duration_ms,
+        )
+    })
+}
+
+/// Renders JUnit XML.
+pub fn junit(records: &[TestRecord]) -> String {
+    let mut suites: Vec<&str> = Vec::new();
+    for r in records {
+        if !suites.contains(&r.suite.as_str()) {
+            suites.push(&r.suite);
+        }
+    }
+
+    let (n, f, s, ms) = totals(records.iter());
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out.push_str(&format!(
+        "<testsuites tests=\"{}\" failures=\"{}\" skipped=\"{}\" time=\"{}\">\n",
+        n, f, s, seconds(ms)
+    ));
+
+    for suite in suites {
+        let cases = || records.iter().filter(|r| r.suite == suite);
+        let (n, f, s, ms) = totals(cases());
+        out.push_str(&format!(
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" skipped=\"{}\" time=\"{}\">\n",
+            escape_xml(suite), n, f, s, seconds(ms)
+        ));
+
+        for case in cases() {
+            let open = format!(
+                "    <testcase name=\"{}\" classname=\"{}\" time=\"{}\"",
+                escape_xml(&case.name),
+                escape_xml(suite),
+                seconds(case.duration_ms)
+            );
+
+            match case.status {
+                TestStatus::Passed => out.push_str(&format!("{}/>\n", open)),
+                TestStatus::Skipped => out.push_str(&format!("{}>\n      <skipped/>\n    </testcase>\n", open)),
+                TestStatus::Failed => {
+                    let message = case.message.as_deref().unwrap_or("failed");
+                    out.push_str(&format!(
+                        "{}>\n      <failure message=\"{}\">{}</failure>\n    </testcase>\n",
+                        open,
+                        escape_xml(message.lines().next().unwrap_or("")),
+                        escape_xml(message)
+                    ));
+                }
+            }
+        }
+
+        out.push_str("  </testsuite>\n");
+    }
+
+    out.push_str("</testsuites>\n");
+    out
+}
+
+/// Renders TAP version 13.
+pub fn tap(records: &[TestRecord]) -> String {
+    let mut out = format!("TAP version 13\n1..{}\n", records.len());
+
+    for (i, r) in records.iter().enumerate() {
+        // `#` starts a directive in TAP, so it cannot appear in a description.
+        let description = format!("{} › {}", r.suite, r.name).replace('#', "\\#");
+
+        match r.status {
+            TestStatus::Passed => out.push_str(&format!("ok {} - {}\n", i + 1, description)),
+            TestStatus::Skipped => out.push_str(&format!("ok {} - {} # SKIP\n", i + 1, description)),
+            TestStatus::Failed => {
+                out.push_str(&format!("not ok {} - {}\n", i + 1, description));
+                out.push_str("  ---\n  message: |\n");
+                for line in r.message.as_deref().unwrap_or("failed").lines() {
+                    out.push_str(&format!("    {}\n", line));
+                }
+                out.push_str(&format!("  duration_ms: {:.3}\n  ...\n", r.duration_ms));
+            }
+        }
+    }
+
+    out
+}
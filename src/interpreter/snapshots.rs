@@ -0,0 +1,296 @@
+/*
+ * ============================================================================
+ * PAWX - Code with Claws!
+ * ============================================================================
+ *
+ * Author:   Sam Wilcox
+ * Email:    sam@pawx-lang.com
+ * Website:  https://www.pawx-lang.com
+ * Github:   https://github.com/samwilcox/pawx
+ *
+ * License:
+ * This file is part of the PAWX programming language project.
+ *
+ * PAWX is dual-licensed under the terms of:
+ *   - The MIT license
+ *   - The Apache License, Version 2.0
+ *
+ * You may choose either license to govern your use of this software.
+ * Full license text available at:
+ *    https://license.pawx-lang.com
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under these licenses is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *
+ * ============================================================================
+ */
+
+/*!
+ * PAWX Snapshot Testing
+ * ---------------------
+ *
+ * `expect(value).toMatchSnapshot()` compares a value against the copy saved
+ * by an earlier run:
+ *
+ * ```pawx
+ * test "renders a user" {
+ *     expect(render(user)).toMatchSnapshot();
+ *     expect(user).toMatchSnapshot("raw user");
+ * }
+ * ```
+ *
+ * Values are stored pretty-printed (see [`value_to_pretty`]) in
+ * `__snapshots__/<script>.snap` next to the test script, one section per
+ * snapshot:
+ *
+ * ```text
+ * >>> renders a user 1
+ * "<p>Tom</p>"
+ *
+ * >>> renders a user: raw user
+ * {
+ *   name: "Tom",
+ * }
+ * ```
+ *
+ * Unnamed snapshots are numbered in the order a test takes them. A missing
+ * snapshot is written and passes; a different value fails with a line diff.
+ * With `pawx test --update-snapshots` mismatches are overwritten instead,
+ * and snapshots no test asked for are removed from the file.
+ */
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::interpreter::display::value_to_pretty;
+use crate::interpreter::testing;
+use crate::value::Value;
+
+/// Directory, next to the test script, that holds snapshot files.
+pub const SNAPSHOT_DIR: &str = "__snapshots__";
+
+const HEADER: &str = ">>> ";
+
+/// The snapshot file of the script under test.
+struct SnapshotFile {
+    path: PathBuf,
+    entries: BTreeMap<String, String>,
+    update: bool,
+    seen: HashSet<String>,
+    counters: HashMap<String, usize>,
+    written: usize,
+    updated: usize,
+}
+
+thread_local! {
+    /// Snapshot state; `None` outside test mode or without a script file.
+    static SNAPSHOTS: RefCell<Option<SnapshotFile>> = const { RefCell::new(None) };
+}
+
+/// Returns the snapshot file for `script`.
+pub fn snapshot_path(script: &Path) -> PathBuf {
+    let file = script.file_name().unwrap_or_default().to_string_lossy();
+    script
+        .parent()
+        .unwrap_or(Path::new(""))
+        .join(SNAPSHOT_DIR)
+        .join(format!("{}.snap", file))
+}
+
+fn parse(contents: &str) -> BTreeMap<String, String> {
+    let mut entries = BTreeMap::new();
+    let mut current: Option<(String, Vec<&str>)> = None;
+
+    let mut finish = |entry: Option<(String, Vec<&str>)>| {
+        if let Some((key, mut lines)) = entry {
+            while lines.last().is_some_and(|l| l.is_empty()) {
+                lines.pop();
+            }
+            entries.insert(key, lines.join("\n"));
+        }
+    };
+
+    for line in contents.lines() {
+        if let Some(key) = line.strip_prefix(HEADER) {
+            finish(current.take());
+            current = Some((key.to_string(), Vec::new()));
+        } else if let Some((_, lines)) = current.as_mut() {
+            lines.push(line);
+        }
+    }
+    finish(current);
+
+    entries
+}
+
+/// Turns snapshots on for the test script at `script`.
+///
+/// With `update` set, mismatching snapshots are rewritten instead of
+/// failing.
+pub fn enable(script: &Path, update: bool) {
+    let path = snapshot_path(script);
+    let entries = fs::read_to_string(&path).map(|c| parse(&c)).unwrap_or_default();
+
+    SNAPSHOTS.with(|s| {
+        *s.borrow_mut() = Some(SnapshotFile {
+            path,
+            entries,
+            update,
+            seen: HashSet::new(),
+            counters: HashMap::new(),
+            written: 0,
+            updated: 0,
+        })
+    });
+}
+
+/// Line diff of two snapshots: `-` lines are expected, `+` lines actual.
+fn diff(expected: &str, actual: &str) -> String {
+    let old: Vec<&str> = expected.lines().collect();
+    let new: Vec<&str> = actual.lines().collect();
+
+    // Longest common subsequence table, filled from the end.
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut out = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            out.push(format!("  {}", old[i]));
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            out.push(format!("- {}", old[i]));
+            i += 1;
+        } else {
+            out.push(format!("+ {}", new[j]));
+            j += 1;
+        }
+    }
+
+    out.join("\n")
+}
+
+/// Compares `value` with its stored snapshot for the running test.
+///
+/// # Errors
+/// If snapshots are unavailable, no test is running, or the value does
+/// not match (outside update mode).
+pub fn check(value: &Value, label: Option<&str>) -> Result<(), String> {
+    let test = testing::current_test().ok_or("toMatchSnapshot() can only be used inside a test block")?;
+    let actual = value_to_pretty(value);
+
+    SNAPSHOTS.with(|s| {
+        let mut s = s.borrow_mut();
+        let file = s
+            .as_mut()
+            .ok_or("toMatchSnapshot() needs a test script file to store snapshots next to")?;
+
+        let key = match label {
+            Some(label) => format!("{}: {}", test, label),
+            None => {
+                let n = file.counters.entry(test.clone()).or_insert(0);
+                *n += 1;
+                format!("{} {}", test, n)
+            }
+        };
+
+        if !file.seen.insert(key.clone()) {
+            return Err(format!("snapshot '{}' is used twice", key));
+        }
+
+        match file.entries.get(&key) {
+            None => {
+                file.entries.insert(key, actual);
+                file.written += 1;
+                Ok(())
+            }
+            Some(expected) if *expected == actual => Ok(()),
+            Some(_) if file.update => {
+                file.entries.insert(key, actual);
+                file.updated += 1;
+                Ok(())
+            }
+            Some(expected) => Err(format!(
+                "snapshot '{}' does not match (- snapshot, + received)\n{}",
+                key,
+                diff(expected, &actual)
+            )),
+        }
+    })
+}
+
+/// Saves the snapshot file if anything changed and reports what happened.
+///
+/// Snapshots no test asked for only count as obsolete when `all_passed`:
+/// a failing test may have stopped before reaching them, and a skipped
+/// test never ran.
+///
+/// # Returns
+/// - A one-line summary such as `2 written, 1 updated`, or `None` if
+///   nothing changed
+pub fn finish(all_passed: bool) -> Option<String> {
+    let mut file = SNAPSHOTS.with(|s| s.borrow_mut().take())?;
+
+    let mut removed = 0;
+    let mut obsolete = 0;
+    for key in file.entries.keys().cloned().collect::<Vec<_>>() {
+        if all_passed && !file.seen.contains(&key) {
+            if file.update {
+                file.entries.remove(&key);
+                removed += 1;
+            } else {
+                obsolete += 1;
+            }
+        }
+    }
+
+    if file.written + file.updated + removed > 0 {
+        let mut out = String::new();
+        for (key, body) in &file.entries {
+            out.push_str(HEADER);
+            out.push_str(key);
+            out.push('\n');
+            out.push_str(body);
+            out.push_str("\n\n");
+        }
+
+        let saved = if file.entries.is_empty() {
+            fs::remove_file(&file.path)
+        } else {
+            file.path
+                .parent()
+                .map_or(Ok(()), fs::create_dir_all)
+                .and_then(|_| fs::write(&file.path, out))
+        };
+
+        if let Err(e) = saved {
+            return Some(format!("could not save {}: {}", file.path.display(), e));
+        }
+    }
+
+    let parts: Vec<String> = [
+        (file.written, "written"),
+        (file.updated, "updated"),
+        (removed, "removed"),
+        (obsolete, "obsolete (run with --update-snapshots to remove)"),
+    ]
+    .iter()
+    .filter(|(n, _)| *n > 0)
+    .map(|(n, what)| format!("{} {}", n, what))
+    .collect();
+
+    (!parts.is_empty()).then(|| parts.join(", "))
+}
@@ -39,7 +39,7 @@
  * This file strictly handles:
  * 
  *  • Variable declarations
- *  • Control flow (if, while)
+ *  • Control flow (if, while, match)
  *  • Function declarations
  *  • Class declarations (clowder)
  *  • Interfaces (instinct)
@@ -48,12 +48,17 @@
  *  • Expression statements
  */
 
-use crate::ast::{ClassMember, Stmt};
-use crate::error::PawxError;
+use crate::ast::arena::{Ast, StmtId, StmtNode};
+use crate::ast::Stmt;
+use crate::error::{ErrorCode, PawxError};
 use crate::interpreter::environment::{Environment, FunctionDef};
-use crate::span::Span;
-use crate::value::Value;
+use crate::value::{InstinctDef, InstinctSignature, Value};
+use crate::interpreter::calls::function_value;
+use crate::interpreter::classes::build_class_value;
 use crate::interpreter::expressions::eval_expr;
+use crate::interpreter::patterns::match_pattern;
+use crate::interpreter::hooks;
+use crate::interpreter::set_current_span;
 
 use std::cell::RefCell;
 use std::collections::HashMap;
@@ -84,73 +89,183 @@ pub enum ExecSignal {
 
 /// Executes a single PAWX statement inside the given environment.
 ///
+/// The statement becomes the current source location (see
+/// [`current_span`](crate::interpreter::current_span)) while it runs. When it
+/// throws or fails, the location is left pointing at the innermost failing
+/// statement so the error can be reported there.
+///
+/// Installed [`InterpreterHooks`](hooks::InterpreterHooks) see the statement
+/// and anything it throws.
+pub fn exec_stmt(ast: &Rc<Ast>, id: StmtId, env: Rc<RefCell<Environment>>) -> Result<ExecSignal, PawxError> {
+    let span = ast.span(id);
+    let outer = set_current_span(span);
+
+    if hooks::active() {
+        hooks::statement(span);
+    }
+
+    let result = exec_node(ast, id, env);
+
+    match &result {
+        Ok(ExecSignal::None | ExecSignal::Return(_)) => {
+            set_current_span(outer);
+        }
+        Ok(ExecSignal::Throw(value)) if hooks::active() => hooks::throw(value, span),
+        Err(e) if hooks::active() => {
+            hooks::throw(&e.clone().into_value(), span);
+        }
+        _ => {}
+    }
+    result
+}
+
 /// This is the **core dispatch function for all statement execution**.
-pub fn exec_stmt(stmt: Stmt, env: Rc<RefCell<Environment>>) -> Result<ExecSignal, PawxError> {
-    match stmt {
+fn exec_node(ast: &Rc<Ast>, id: StmtId, env: Rc<RefCell<Environment>>) -> Result<ExecSignal, PawxError> {
+    match &ast[id] {
         /* ------------------------------------------------------------------
          * Expression Statement
          * ---------------------------------------------------------------- */
-        Stmt::Expression(expr) => {
-            eval_expr(expr, env)?;
+        StmtNode::Expression(expr) => {
+            eval_expr(ast, *expr, env)?;
             Ok(ExecSignal::None)
         }
 
         /* ------------------------------------------------------------------
          * Variable Declarations
          * ---------------------------------------------------------------- */
-        Stmt::PublicVar { name, value } => {
-            let val = eval_expr(value, env.clone())?;
+        StmtNode::PublicVar { name, value } => {
+            let val = eval_expr(ast, *value, env.clone())?;
 
             let final_value = match val {
                 Value::Module { default: Some(default), .. } => (*default).clone(),
                 other => other,
             };
 
-            env.borrow_mut().define_public(name, final_value);
+            env.borrow_mut().define_public(name.clone(), final_value);
             Ok(ExecSignal::None)
         }
 
-        Stmt::PrivateVar { name, value } => {
-            let val = eval_expr(value, env.clone())?;
-            env.borrow_mut().define_private(name, val);
+        StmtNode::PrivateVar { name, value } => {
+            let val = eval_expr(ast, *value, env.clone())?;
+            env.borrow_mut().define_private(name.clone(), val);
             Ok(ExecSignal::None)
         }
 
-        Stmt::ProtectedVar { name, value } => {
-            let val = eval_expr(value, env.clone())?;
-            env.borrow_mut().define_protected(name, val);
+        StmtNode::ProtectedVar { name, value } => {
+            let val = eval_expr(ast, *value, env.clone())?;
+            env.borrow_mut().define_protected(name.clone(), val);
+            Ok(ExecSignal::None)
+        }
+
+        StmtNode::DestructureVar { names, value } => {
+            let val = eval_expr(ast, *value, env.clone())?;
+
+            // Module exports, or an object's fields
+            let (owner, kind, available): (&str, &str, HashMap<String, Value>) = match &val {
+                Value::Module { exports, .. } => ("module", "export", (**exports).clone()),
+                Value::Object { fields } => ("object", "field", fields.borrow().clone()),
+                other => {
+                    return Err(PawxError::type_error(
+                        format!("cannot destructure {}, expected a module or an object", other.type_name()),
+                        ast.span(id),
+                    ))
+                }
+            };
+
+            // Check every name first, so a miss binds nothing
+            if let Some(missing) = names.iter().find(|name| !available.contains_key(*name)) {
+                let mut known: Vec<&str> = available.keys().map(String::as_str).collect();
+                known.sort_unstable();
+
+                let help = if known.is_empty() {
+                    format!("the {} has no {}s", owner, kind)
+                } else {
+                    format!("available {}s: {}", kind, known.join(", "))
+                };
+
+                return Err(PawxError::new(
+                    ErrorCode::UnknownProperty,
+                    format!("{} has no {} '{}'", owner, kind, missing),
+                    ast.span(id),
+                )
+                .with_help(help));
+            }
+
+            let mut env = env.borrow_mut();
+            for name in names.iter() {
+                env.define_public(name.clone(), available[name].clone());
+            }
             Ok(ExecSignal::None)
         }
 
         /* ------------------------------------------------------------------
          * Function Declaration
          * ---------------------------------------------------------------- */
-        Stmt::Function {
+        StmtNode::Function {
             name,
             params,
             body,
             return_type,
             is_async,
-            ..
         } => {
             let func_def = FunctionDef {
-                params,
-                body,
-                return_type,
-                is_async,
-                name_span: Span::new(0, 0),
+                ast: ast.clone(),
+                params: *params,
+                body: *body,
+                return_type: return_type.clone(),
+                is_async: *is_async,
+                name_span: ast.span(id),
             };
 
-            env.borrow_mut().define_function(name, func_def);
+            env.borrow_mut().define_function(name.clone(), func_def);
+            Ok(ExecSignal::None)
+        }
+
+        /* ------------------------------------------------------------------
+         * Decorated Function Declaration
+         * ---------------------------------------------------------------- */
+        StmtNode::Decorated { decorators, function } => {
+            let StmtNode::Function { name, params, body, return_type, is_async } = &ast[*function] else {
+                return Err(PawxError::runtime_error(
+                    "Decorators can only be applied to 'purr' function declarations",
+                    ast.span(id),
+                ));
+            };
+
+            let func_def = FunctionDef {
+                ast: ast.clone(),
+                params: *params,
+                body: *body,
+                return_type: return_type.clone(),
+                is_async: *is_async,
+                name_span: ast.span(*function),
+            };
+
+            let mut value = function_value(func_def, env.clone());
+
+            // Innermost (closest to `purr`) decorator applies first
+            for decorator in decorators.iter().rev() {
+                value = match eval_expr(ast, decorator, env.clone())? {
+                    Value::NativeFunction(f) => f(vec![value])?,
+                    other => {
+                        return Err(PawxError::type_error(
+                            format!("Decorator must be a function, got {}", other.type_name()),
+                            ast.span(id),
+                        ))
+                    }
+                };
+            }
+
+            env.borrow_mut().define_public(name.clone(), value);
             Ok(ExecSignal::None)
         }
 
         /* ------------------------------------------------------------------
          * Return Statement
          * ---------------------------------------------------------------- */
-        Stmt::Return(expr_opt) => {
+        StmtNode::Return(expr_opt) => {
             let val = match expr_opt {
-                Some(expr) => eval_expr(expr, env),
+                Some(expr) => eval_expr(ast, *expr, env),
                 None => Ok(Value::Null),
             };
 
@@ -160,12 +275,12 @@ pub fn exec_stmt(stmt: Stmt, env: Rc<RefCell<Environment>>) -> Result<ExecSignal
         /* ------------------------------------------------------------------
          * If / Else Control Flow
          * ---------------------------------------------------------------- */
-        Stmt::If {
+        StmtNode::If {
             condition,
             then_branch,
             else_branch,
         } => {
-            let cond_val = eval_expr(condition, env.clone());
+            let cond_val = eval_expr(ast, *condition, env.clone());
 
             let truthy = match cond_val {
                 Ok(Value::Bool(b)) => b,
@@ -175,15 +290,15 @@ pub fn exec_stmt(stmt: Stmt, env: Rc<RefCell<Environment>>) -> Result<ExecSignal
             };
 
             if truthy {
-                for s in then_branch {
-                    match exec_stmt(s, env.clone()) {
+                for s in then_branch.iter() {
+                    match exec_stmt(ast, s, env.clone()) {
                         Ok(ExecSignal::None) => {}
                         other => return other,
                     }
                 }
             } else if let Some(else_body) = else_branch {
-                for s in else_body {
-                    match exec_stmt(s, env.clone()) {
+                for s in else_body.iter() {
+                    match exec_stmt(ast, s, env.clone()) {
                         Ok(ExecSignal::None) => {}
                         other => return other,
                     }
@@ -196,9 +311,9 @@ pub fn exec_stmt(stmt: Stmt, env: Rc<RefCell<Environment>>) -> Result<ExecSignal
         /* ------------------------------------------------------------------
          * While Loop
          * ---------------------------------------------------------------- */
-        Stmt::While { condition, body } => {
+        StmtNode::While { condition, body } => {
             loop {
-                let cond_val = eval_expr(condition.clone(), env.clone());
+                let cond_val = eval_expr(ast, *condition, env.clone());
 
                 let truthy = match cond_val {
                     Ok(Value::Bool(b)) => b,
@@ -211,12 +326,63 @@ pub fn exec_stmt(stmt: Stmt, env: Rc<RefCell<Environment>>) -> Result<ExecSignal
                     break;
                 }
 
-                for s in &body {
-                    match exec_stmt(s.clone(), env.clone()) {
+                for s in body.iter() {
+                    match exec_stmt(ast, s, env.clone()) {
+                        Ok(ExecSignal::None) => {}
+                        other => return other,
+                    }
+                }
+            }
+
+            Ok(ExecSignal::None)
+        }
+
+        /* ------------------------------------------------------------------
+         * Match (first matching arm, guard included, runs in its own scope)
+         * ---------------------------------------------------------------- */
+        StmtNode::Match { subject, arms } => {
+            let value = eval_expr(ast, *subject, env.clone())?;
+
+            for arm in arms.iter() {
+                // Alternatives are tried left to right; the guard is checked
+                // against each matching alternative's bindings.
+                let mut selected = None;
+
+                for pattern in &arm.patterns {
+                    let mut bindings = Vec::new();
+
+                    if !match_pattern(pattern, &value, &mut bindings)? {
+                        continue;
+                    }
+
+                    let arm_env = Rc::new(RefCell::new(Environment::new(Some(env.clone()))));
+                    for (name, bound) in bindings {
+                        arm_env.borrow_mut().define_public(name, bound);
+                    }
+
+                    let passes = match arm.guard {
+                        Some(guard) => eval_expr(ast, guard, arm_env.clone())?.is_truthy(),
+                        None => true,
+                    };
+
+                    if passes {
+                        selected = Some(arm_env);
+                        break;
+                    }
+                }
+
+                let Some(arm_env) = selected else {
+                    continue;
+                };
+
+                for s in arm.body.iter() {
+                    match exec_stmt(ast, s, arm_env.clone()) {
                         Ok(ExecSignal::None) => {}
                         other => return other,
                     }
                 }
+
+                break;
             }
 
             Ok(ExecSignal::None)
@@ -225,7 +391,7 @@ pub fn exec_stmt(stmt: Stmt, env: Rc<RefCell<Environment>>) -> Result<ExecSignal
         /* ------------------------------------------------------------------
          * Try / Catch / Finally
          * ---------------------------------------------------------------- */
-        Stmt::Try {
+        StmtNode::Try {
             try_block,
             catch_param,
             catch_block,
@@ -234,8 +400,8 @@ pub fn exec_stmt(stmt: Stmt, env: Rc<RefCell<Environment>>) -> Result<ExecSignal
             let mut result = ExecSignal::None;
 
             // TRY
-            for stmt in try_block {
-                match exec_stmt(stmt, env.clone()) {
+            for stmt in try_block.iter() {
+                match exec_stmt(ast, stmt, env.clone()) {
                     Ok(ExecSignal::None) => {}
 
                     Ok(ExecSignal::Return(v)) => {
@@ -245,25 +411,21 @@ pub fn exec_stmt(stmt: Stmt, env: Rc<RefCell<Environment>>) -> Result<ExecSignal
 
                     Ok(ExecSignal::Throw(err)) => {
                         // Handle explicit throw
-                        if let (Some(name), Some(catch_body)) =
-                            (catch_param.clone(), catch_block.clone())
-                        {
+                        if let (Some(name), Some(catch_body)) = (catch_param, catch_block) {
                             let catch_env =
                                 Rc::new(RefCell::new(Environment::new(Some(env.clone()))));
 
-                            catch_env.borrow_mut().define_public(name, err);
+                            catch_env.borrow_mut().define_public(name.clone(), err);
 
-                            for cstmt in catch_body {
-                                match exec_stmt(cstmt, catch_env.clone()) {
+                            for cstmt in catch_body.iter() {
+                                match exec_stmt(ast, cstmt, catch_env.clone()) {
                                     Ok(ExecSignal::None) => {}
                                     Ok(other) => {
                                         result = other;
                                         break;
                                     }
                                     Err(e) => {
-                                        result = ExecSignal::Throw(Value::Error {
-                                            message: e.message,
-                                        });
+                                        result = ExecSignal::Throw(e.into_value());
                                         break;
                                     }
                                 }
@@ -277,29 +439,23 @@ pub fn exec_stmt(stmt: Stmt, env: Rc<RefCell<Environment>>) -> Result<ExecSignal
 
                     Err(e) => {
                         // Normalize runtime error → throw
-                        let err_val = Value::Error {
-                            message: e.message,
-                        };
+                        let err_val = e.into_value();
 
-                        if let (Some(name), Some(catch_body)) =
-                            (catch_param.clone(), catch_block.clone())
-                        {
+                        if let (Some(name), Some(catch_body)) = (catch_param, catch_block) {
                             let catch_env =
                                 Rc::new(RefCell::new(Environment::new(Some(env.clone()))));
 
-                            catch_env.borrow_mut().define_public(name, err_val);
+                            catch_env.borrow_mut().define_public(name.clone(), err_val);
 
-                            for cstmt in catch_body {
-                                match exec_stmt(cstmt, catch_env.clone()) {
+                            for cstmt in catch_body.iter() {
+                                match exec_stmt(ast, cstmt, catch_env.clone()) {
                                     Ok(ExecSignal::None) => {}
                                     Ok(other) => {
                                         result = other;
                                         break;
                                     }
                                     Err(e) => {
-                                        result = ExecSignal::Throw(Value::Error {
-                                            message: e.message,
-                                        });
+                                        result = ExecSignal::Throw(e.into_value());
                                         break;
                                     }
                                 }
@@ -315,14 +471,12 @@ pub fn exec_stmt(stmt: Stmt, env: Rc<RefCell<Environment>>) -> Result<ExecSignal
 
             // FINALLY (always runs)
             if let Some(finally_body) = finally_block {
-                for fstmt in finally_body {
-                    match exec_stmt(fstmt, env.clone()) {
+                for fstmt in finally_body.iter() {
+                    match exec_stmt(ast, fstmt, env.clone()) {
                         Ok(ExecSignal::None) => {}
                         Ok(other) => return Ok(other),
                         Err(e) => {
-                            return Ok(ExecSignal::Throw(Value::Error {
-                                message: e.message,
-                            }))
+                            return Ok(ExecSignal::Throw(e.into_value()))
                         }
                     }
                 }
@@ -334,89 +488,47 @@ pub fn exec_stmt(stmt: Stmt, env: Rc<RefCell<Environment>>) -> Result<ExecSignal
         /* ------------------------------------------------------------------
          * Class (Clowder)
          * ---------------------------------------------------------------- */
-        Stmt::Clowder {
+        StmtNode::Clowder {
             name,
-            base: _,
-            interfaces: _,
+            base,
+            interfaces,
             members,
             is_exported,
             is_default,
         } => {
-            let mut methods: HashMap<String, FunctionDef> = HashMap::new();
-            let mut getters: HashMap<String, FunctionDef> = HashMap::new();
-            let mut setters: HashMap<String, FunctionDef> = HashMap::new();
-            let mut fields: HashMap<String, Value> = HashMap::new();
-
-            for member in members {
-                match member {
-                    ClassMember::Field { name, value, .. } => {
-                        let val = if let Some(expr) = value {
-                            eval_expr(expr, env.clone())?
-                        } else {
-                            Value::Null
-                        };
-
-                        fields.insert(name, val);
-                    }
-
-                    ClassMember::Method { name, params, body, .. } => {
-                        methods.insert(
-                            name,
-                            FunctionDef {
-                                params,
-                                body,
-                                return_type: None,
-                                is_async: false,
-                                name_span: Span::new(0, 0),
-                            },
-                        );
-                    }
-
-                    ClassMember::Getter { name, body, .. } => {
-                        getters.insert(
-                            name,
-                            FunctionDef {
-                                params: vec![],
-                                body,
-                                return_type: None,
-                                is_async: false,
-                                name_span: Span::new(0, 0),
-                            },
-                        );
+            let base = match base {
+                Some(base) => match env.borrow().get(base, false) {
+                    Some(Value::Class(class)) => Some(class),
+                    _ => {
+                        return Err(PawxError::type_error(
+                            format!("Clowder '{}' cannot inherit '{}': it is not a clowder", name, base),
+                            ast.span(id),
+                        ))
                     }
+                },
+                None => None,
+            };
 
-                    ClassMember::Setter { name, param_name, body, .. } => {
-                        setters.insert(
-                            name,
-                            FunctionDef {
-                                params: vec![crate::ast::Param {
-                                    name: param_name,
-                                    default: None,
-                                    type_annotation: None,
-                                }],
-                                body,
-                                return_type: None,
-                                is_async: false,
-                                name_span: Span::new(0, 0),
-                            },
-                        );
+            let mut instincts = Vec::new();
+            for interface in interfaces.iter() {
+                match env.borrow().get(interface, false) {
+                    Some(Value::Instinct(instinct)) => instincts.push(instinct),
+                    _ => {
+                        return Err(PawxError::type_error(
+                            format!("Clowder '{}' cannot practice '{}': it is not an instinct", name, interface),
+                            ast.span(id),
+                        ))
                     }
                 }
             }
 
-            let class_val = Value::Class {
-                name: name.clone(),
-                methods,
-                getters,
-                setters,
-                fields,
-            };
+            let class_val = build_class_value(name.clone(), base, instincts, ast, members, env.clone())?;
 
-            if is_exported && is_default {
+            if *is_exported && *is_default {
                 env.borrow_mut()
                     .define_public("default".to_string(), class_val);
             } else {
-                env.borrow_mut().define_public(name, class_val);
+                env.borrow_mut().define_public(name.clone(), class_val);
             }
 
             Ok(ExecSignal::None)
@@ -425,23 +537,33 @@ pub fn exec_stmt(stmt: Stmt, env: Rc<RefCell<Environment>>) -> Result<ExecSignal
         /* ------------------------------------------------------------------
          * Interface (Instinct)
          * ---------------------------------------------------------------- */
-        Stmt::Instinct { name, .. } => {
-            // For now, instinct types are only compile-time;
-            // at runtime we just expose a sentinel value.
-            env.borrow_mut().define_public(name, Value::Null);
+        StmtNode::Instinct { name, members } => {
+            let instinct = InstinctDef {
+                name: name.clone(),
+                members: members
+                    .iter()
+                    .map(|member| InstinctSignature {
+                        name: member.name.clone(),
+                        kind: member.kind,
+                        params: member.params.iter().map(|p| p.name.clone()).collect(),
+                    })
+                    .collect(),
+            };
+
+            env.borrow_mut().define_public(name.clone(), Value::Instinct(Rc::new(instinct)));
             Ok(ExecSignal::None)
         }
 
         /* ------------------------------------------------------------------
          * Export Statement
          * ---------------------------------------------------------------- */
-        Stmt::Export { name, value } => {
-            let val = eval_expr(value, env.clone());
+        StmtNode::Export { name, value } => {
+            let val = eval_expr(ast, *value, env.clone());
 
             // 🔧 This avoids the fragile `match name { ... }` and
             // works cleanly with `Option<String>`.
             if let Some(export_name) = name {
-                env.borrow_mut().define_public(export_name, val?);
+                env.borrow_mut().define_public(export_name.clone(), val?);
             } else {
                 // default export: `exports default = expr;`
                 env.borrow_mut()
@@ -454,33 +576,49 @@ pub fn exec_stmt(stmt: Stmt, env: Rc<RefCell<Environment>>) -> Result<ExecSignal
         /* ------------------------------------------------------------------
          * Throw Statement
          * ---------------------------------------------------------------- */
-        Stmt::Throw(expr) => {
-            let val = eval_expr(expr, env);
+        StmtNode::Throw(expr) => {
+            let val = eval_expr(ast, *expr, env);
             Ok(ExecSignal::Throw(val?))
         }
 
         /* ------------------------------------------------------------------
-         * Nap (Await-like)
+         * When Block
+         *
+         * Normally resolved before execution; one that reaches the
+         * interpreter (e.g. from an embedder's AST) is decided against the
+         * environment variables alone.
          * ---------------------------------------------------------------- */
-        Stmt::Nap(expr) => {
-            let val = eval_expr(expr, env);
-
-            match val {
-                Ok(Value::Furure(inner)) => Ok(ExecSignal::Return(*inner)),
-                _ => panic!("nap can only be used on a Future"),
+        StmtNode::When(when) => {
+            let defines = crate::interpreter::conditional::Defines::default();
+            let chosen = crate::interpreter::conditional::resolve(vec![(**when).clone()], &defines)?;
+            let (ast, body) = Ast::lower(chosen);
+            for s in body.iter() {
+                match exec_stmt(&ast, s, env.clone()) {
+                    Ok(ExecSignal::None) => {}
+                    other => return other,
+                }
             }
+            Ok(ExecSignal::None)
+        }
+
+        /* ------------------------------------------------------------------
+         * Test Block (registered in test mode, skipped otherwise)
+         * ---------------------------------------------------------------- */
+        StmtNode::Test { name, body, mode } => {
+            crate::interpreter::testing::register(name.clone(), ast.clone(), *body, *mode, env);
+            Ok(ExecSignal::None)
         }
 
         /* ------------------------------------------------------------------
          * Pride Block
          * ---------------------------------------------------------------- */
-        Stmt::Pride { name, body } => {
+        StmtNode::Pride { name, body } => {
             // Create a new lexical scope for the pride block
             let pride_env = Rc::new(RefCell::new(Environment::new(Some(env.clone()))));
 
             // Execute all statements inside the pride block
-            for stmt in body {
-                match exec_stmt(stmt, pride_env.clone()) {
+            for stmt in body.iter() {
+                match exec_stmt(ast, stmt, pride_env.clone()) {
                     Ok(ExecSignal::None) => {}
 
                     Ok(ExecSignal::Return(v)) => {
@@ -492,9 +630,7 @@ pub fn exec_stmt(stmt: Stmt, env: Rc<RefCell<Environment>>) -> Result<ExecSignal
                     }
 
                     Err(e) => {
-                        return Ok(ExecSignal::Throw(Value::Error {
-                            message: e.message,
-                        }));
+                        return Ok(ExecSignal::Throw(e.into_value()));
                     }
                 }
             }
@@ -506,7 +642,7 @@ pub fn exec_stmt(stmt: Stmt, env: Rc<RefCell<Environment>>) -> Result<ExecSignal
             }
 
             env.borrow_mut().define_public(
-                name,
+                name.clone(),
                 Value::Object {
                     fields: Rc::new(RefCell::new(fields)),
                 },
@@ -532,8 +668,10 @@ pub fn run_in_env(
     statements: Vec<Stmt>,
     env: Rc<RefCell<Environment>>,
 ) -> Result<ExecSignal, PawxError> {
-    for stmt in statements {
-        match exec_stmt(stmt, env.clone()) {
+    let (ast, body) = Ast::lower(statements);
+
+    for stmt in body.iter() {
+        match exec_stmt(&ast, stmt, env.clone()) {
             Ok(ExecSignal::None) => {}
 
             Ok(ExecSignal::Return(v)) => {
@@ -545,9 +683,7 @@ pub fn run_in_env(
             }
 
             Err(e) => {
-                return Ok(ExecSignal::Throw(Value::Error {
-                    message: e.message,
-                }));
+                return Ok(ExecSignal::Throw(e.into_value()));
             }
         }
     }
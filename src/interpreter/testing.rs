@@ -0,0 +1,274 @@
+/*
+ * ============================================================================
+ * PAWX - Code with Claws!
+ * ============================================================================
+ *
+ * Author:   Sam Wilcox
+ * Email:    sam@pawx-lang.com
+ * Website:  https://www.pawx-lang.com
+ * Github:   https://github.com/samwilcox/pawx
+ *
+ * License:
+ * This file is part of the PAWX programming language project.
+ *
+ * PAWX is dual-licensed under the terms of:
+ *   - The MIT license
+ *   - The Apache License, Version 2.0
+ *
+ * You may choose either license to govern your use of this software.
+ * Full license text available at:
+ *    https://license.pawx-lang.com
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under these licenses is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *
+ * ============================================================================
+ */
+
+/*!
+ * PAWX Test Blocks
+ * ----------------
+ *
+ * Tests live next to the code they test:
+ *
+ * ```pawx
+ * purr add -> (a, b) -> { return a + b; }
+ *
+ * test "adds numbers" {
+ *     assertEq(add(1, 2), 3);
+ * }
+ * ```
+ *
+ * In a normal run a `test` block is skipped. In test mode (`pawx test`)
+ * each block is **registered** when execution reaches it, together with
+ * the scope it appeared in, and the registered tests run one by one after
+ * the program's top-level code has finished.
+ *
+ * A test fails if it throws, hits a runtime error, or panics (which is how
+ * native functions such as `assertEq` report failures). Each test runs in
+ * its own child scope, so variables it declares do not leak into the next.
+ *
+ * `it` is another name for `test`. `test.skip "..." { }` is reported but
+ * never run; once any `test.only "..." { }` is registered, every test that
+ * is not focused is skipped. `pawx test --filter <pattern>` also skips
+ * tests whose name does not contain the pattern.
+ *
+ * Tests can also compare values against saved snapshots with
+ * `expect(value).toMatchSnapshot()` (see the `snapshots` module), and the
+ * results can be written as JUnit or TAP (see the `reporters` module).
+ *
+ * Failure messages are printed indented under the test. On a terminal
+ * (unless `NO_COLOR` is set), the `-` and `+` lines of a structural diff
+ * from `assertEq` or `toEqual` are shown in red and green; reports always
+ * get plain text.
+ */
+
+use std::cell::RefCell;
+use std::io::IsTerminal;
+use std::panic::{self, AssertUnwindSafe};
+use std::rc::Rc;
+use std::time::Instant;
+
+use crate::ast::arena::{Ast, StmtList};
+use crate::ast::TestMode;
+use crate::interpreter::environment::Environment;
+use crate::interpreter::statements::{exec_stmt, ExecSignal};
+use crate::interpreter::display::value_to_string;
+use crate::interpreter::reporters::{TestRecord, TestStatus};
+use crate::interpreter::snapshots;
+
+/// A test block registered in test mode.
+struct TestCase {
+    name: String,
+    ast: Rc<Ast>,
+    body: StmtList,
+    mode: TestMode,
+    env: Rc<RefCell<Environment>>,
+}
+
+thread_local! {
+    /// Registered tests; `None` outside test mode.
+    static TESTS: RefCell<Option<Vec<TestCase>>> = const { RefCell::new(None) };
+
+    /// Name of the test that is running.
+    static CURRENT: RefCell<Option<String>> = const { RefCell::new(None) };
+
+    /// Cleanups to run when the current test (or the program) finishes.
+    static CLEANUPS: RefCell<Vec<Box<dyn FnOnce()>>> = RefCell::new(Vec::new());
+}
+
+/// Turns test mode on for the current thread.
+pub fn enable() {
+    TESTS.with(|t| *t.borrow_mut() = Some(Vec::new()));
+}
+
+/// Registers a test block if test mode is on; otherwise does nothing.
+pub fn register(name: String, ast: Rc<Ast>, body: StmtList, mode: TestMode, env: Rc<RefCell<Environment>>) {
+    TESTS.with(|t| {
+        if let Some(tests) = t.borrow_mut().as_mut() {
+            tests.push(TestCase { name, ast, body, mode, env });
+        }
+    });
+}
+
+/// Returns the name of the running test, if any.
+pub fn current_test() -> Option<String> {
+    CURRENT.with(|c| c.borrow().clone())
+}
+
+/// Schedules `f` to run when the current test finishes, or when the
+/// program finishes if no test is running.
+pub fn on_cleanup(f: impl FnOnce() + 'static) {
+    CLEANUPS.with(|c| c.borrow_mut().push(Box::new(f)));
+}
+
+/// Runs every scheduled cleanup, newest first.
+pub fn run_cleanups() {
+    let cleanups = CLEANUPS.with(|c| std::mem::take(&mut *c.borrow_mut()));
+    for cleanup in cleanups.into_iter().rev() {
+        // A failing cleanup must not stop the others.
+        let _ = panic::catch_unwind(AssertUnwindSafe(cleanup));
+    }
+}
+
+/// Outcome of a test run.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TestSummary {
+    pub passed: usize,
+    pub failed: usize,
+    pub skipped: usize,
+}
+
+/// Extracts the message from a panic payload.
+pub(crate) fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        s.to_string()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "panicked".to_string()
+    }
+}
+
+/// Runs one test body, returning the failure message if it fails.
+fn run_case(case: &TestCase) -> Result<(), String> {
+    let env = Rc::new(RefCell::new(Environment::new(Some(case.env.clone()))));
+    CURRENT.with(|c| *c.borrow_mut() = Some(case.name.clone()));
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        for stmt in case.body.iter() {
+            match exec_stmt(&case.ast, stmt, env.clone()) {
+                Ok(ExecSignal::None) => {}
+                Ok(ExecSignal::Return(_)) => break,
+                Ok(ExecSignal::Throw(err)) => return Err(format!("threw {}", value_to_string(&err))),
+                Err(e) => return Err(e.message),
+            }
+        }
+        Ok(())
+    }));
+
+    CURRENT.with(|c| *c.borrow_mut() = None);
+    result.unwrap_or_else(|payload| Err(panic_message(payload.as_ref())))
+}
+
+/// Colors a `- expected` / `+ actual` diff line red or green when `color`
+/// is set.
+fn colorize_diff_line(line: &str, color: bool) -> String {
+    let code = match line.trim_start().as_bytes() {
+        [b'-', b' ', ..] if color => "31",
+        [b'+', b' ', ..] if color => "32",
+        _ => return line.to_string(),
+    };
+
+    format!("\x1b[{}m{}\x1b[0m", code, line)
+}
+
+/// Runs every registered test, printing one line per test and a summary.
+///
+/// Tests are skipped when marked `skip`, when other tests are marked `only`,
+/// or when their name does not contain `filter`. Each test's result is
+/// recorded under `suite` for the reporters.
+///
+/// `after_each` runs after every test (the interpreter pumps timers there).
+pub fn run_registered(
+    suite: &str,
+    filter: Option<&str>,
+    mut after_each: impl FnMut(),
+) -> (TestSummary, Vec<TestRecord>) {
+    let tests = TESTS.with(|t| t.borrow_mut().as_mut().map(std::mem::take).unwrap_or_default());
+    let focused = tests.iter().any(|case| case.mode == TestMode::Only);
+    let mut summary = TestSummary::default();
+    let mut records = Vec::new();
+    let color = std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none();
+
+    // Failures are reported below; keep the default hook from printing
+    // every assertion panic (and backtrace) as it happens.
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+
+    for case in &tests {
+        let selected = case.mode != TestMode::Skip
+            && (!focused || case.mode == TestMode::Only)
+            && filter.is_none_or(|pattern| case.name.contains(pattern));
+
+        let mut record = TestRecord {
+            suite: suite.to_string(),
+            name: case.name.clone(),
+            status: TestStatus::Skipped,
+            message: None,
+            duration_ms: 0.0,
+        };
+
+        if !selected {
+            summary.skipped += 1;
+            println!("  ○ {}", case.name);
+            records.push(record);
+            continue;
+        }
+
+        let started = Instant::now();
+        let result = run_case(case);
+        after_each();
+        run_cleanups();
+        record.duration_ms = started.elapsed().as_secs_f64() * 1000.0;
+
+        match result {
+            Ok(()) => {
+                summary.passed += 1;
+                record.status = TestStatus::Passed;
+                println!("  ✓ {}", case.name);
+            }
+            Err(message) => {
+                summary.failed += 1;
+                println!("  ✗ {}", case.name);
+                for line in message.lines() {
+                    println!("      {}", colorize_diff_line(line, color));
+                }
+                record.status = TestStatus::Failed;
+                record.message = Some(message);
+            }
+        }
+        records.push(record);
+    }
+
+    panic::set_hook(hook);
+
+    println!();
+    let mut counts = format!("{} passed", summary.passed);
+    if summary.failed > 0 {
+        counts.push_str(&format!(", {} failed", summary.failed));
+    }
+    if summary.skipped > 0 {
+        counts.push_str(&format!(", {} skipped", summary.skipped));
+    }
+    println!("{}", counts);
+
+    // Skipped tests never reach their snapshots, so they cannot tell which
+    // ones are obsolete.
+    if let Some(report) = snapshots::finish(summary.failed == 0 && summary.skipped == 0) {
+        println!("snapshots: {}", report);
+    }
+
+    (summary, records)
+}
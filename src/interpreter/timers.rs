@@ -37,100 +37,181 @@
  *  • clearTimeout(id)
  *  • clearInterval(id)
  * 
- * The runtime is thread-backed but **event execution is always dispatched
- * back onto the main interpreter thread** via a message pump.
- * 
- * This design keeps:
- *  - Deterministic execution
- *  - No race conditions in the interpreter
- *  - Safe cancellation
+ * Timers live in a queue on the interpreter thread; there are no timer
+ * threads. The interpreter pumps the queue between top-level statements,
+ * and once the program body has finished it keeps running as an **event
+ * loop**, sleeping until the next timer is due, until no timers are left:
+ *
+ * ```pawx
+ * purr later -> () -> {
+ *     setTimeout(() -> { meow("done") }, 100);
+ * }
+ * later();   // the program waits 100ms and prints "done" before exiting
+ * ```
+ *
+ * Due timers fire in order of due time, ties broken by creation order.
+ * Intervals are rescheduled before their callback runs, so a callback may
+ * clear its own interval.
+ *
+ * Every timer accepts an optional trailing `CancelToken`; cancelling the
+ * token clears the timer.
+ *
+ * Callbacks run in the `Context` scope that was current when the timer was
+ * set, so values stored for a request follow its timers.
+ *
+ * In `--virtual-time` mode timers are scheduled on a `VirtualClock` (see
+ * clock.rs) instead and fire when the clock is advanced.
  */
 
+use crate::error::PawxError;
+use crate::interpreter::calls::raise_error;
+use crate::interpreter::clock::VirtualClock;
 use crate::interpreter::environment::Environment;
+use crate::prototypes::cancel::{take_cancel_token, CancelToken};
+use crate::prototypes::context;
 use crate::value::Value;
-use crate::interpreter::environment::FunctionDef;
 
-use std::cell::RefCell;
-use std::collections::HashMap;
+use std::cell::{Cell, RefCell};
 use std::rc::Rc;
 use std::sync::Arc;
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::mpsc::{self, Sender, Receiver};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 /* ============================================================================
- * Internal Timer Message Types
+ * Timer Queue
  * ============================================================================
  */
 
-/// Messages sent from background timer threads back to the main interpreter.
-#[derive(Debug, Clone, Copy)]
-pub enum TimerMessage {
-    /// Fired once after a timeout delay.
-    Timeout(u64),
-
-    /// Fired repeatedly at a fixed interval.
-    IntervalTick(u64),
-}
-
-/// Internal record for each active timer.
-#[derive(Clone)]
+/// A timer waiting on the real-time queue.
+#[derive(Debug, Clone)]
 pub struct TimerEntry {
-    /// Callback function invoked when the timer fires.
-    pub callback: Value,
+    /// Timer ID returned to PAWX code.
+    pub id: u64,
+
+    /// When the timer fires next.
+    pub due: Instant,
 
-    /// True if this is an interval timer (repeats).
-    pub is_interval: bool,
+    /// Repeat period for intervals, `None` for one-shot timeouts.
+    pub interval: Option<Duration>,
 
-    /// Cancellation flag used for intervals.
-    pub cancel_flag: Option<Arc<AtomicBool>>,
+    /// Callback invoked when the timer fires.
+    pub callback: Value,
 }
 
 /// Shared runtime timer state.
+///
+/// Cloning a `TimerRuntime` produces another handle to the **same** queue.
+#[derive(Debug, Clone, Default)]
 pub struct TimerRuntime {
-    pub tx: Sender<TimerMessage>,
-    pub rx: Receiver<TimerMessage>,
-    pub timers: Rc<RefCell<HashMap<u64, TimerEntry>>>,
-    pub next_id: Rc<RefCell<u64>>,
+    timers: Rc<RefCell<Vec<TimerEntry>>>,
+    next_id: Rc<Cell<u64>>,
 }
 
 impl TimerRuntime {
     /// Creates and initializes a new PAWX timer runtime.
     pub fn new() -> Self {
-        let (tx, rx) = mpsc::channel();
+        Self::default()
+    }
+
+    /// Allocates the next timer ID (IDs start at 1).
+    fn allocate_id(&self) -> u64 {
+        let id = self.next_id.get() + 1;
+        self.next_id.set(id);
+        id
+    }
+
+    /// Queues `callback` to run `delay` from now, then every `interval`.
+    fn schedule(&self, id: u64, delay: Duration, interval: Option<Duration>, callback: Value) {
+        self.timers.borrow_mut().push(TimerEntry {
+            id,
+            due: Instant::now() + delay,
+            interval,
+            callback,
+        });
+    }
+
+    /// Removes a timer from the queue.
+    ///
+    /// # Returns
+    /// - `true` if a timer with the given ID was pending
+    pub fn cancel(&self, id: u64) -> bool {
+        let mut timers = self.timers.borrow_mut();
+        let before = timers.len();
+        timers.retain(|t| t.id != id);
+        timers.len() != before
+    }
 
-        Self {
-            timers: Rc::new(RefCell::new(HashMap::new())),
-            next_id: Rc::new(RefCell::new(1)),
-            tx,
-            rx,
+    /// Returns when the earliest pending timer is due.
+    pub fn next_due(&self) -> Option<Instant> {
+        self.timers.borrow().iter().map(|t| t.due).min()
+    }
+
+    /// Pops the next timer due at or before `now`, rescheduling it first if
+    /// it is an interval.
+    fn take_due(&self, now: Instant) -> Option<Value> {
+        let mut timers = self.timers.borrow_mut();
+
+        let index = timers
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| t.due <= now)
+            .min_by_key(|(_, t)| (t.due, t.id))
+            .map(|(i, _)| i)?;
+
+        let callback = timers[index].callback.clone();
+
+        match timers[index].interval {
+            // Zero-length intervals still move forward to avoid spinning forever.
+            Some(period) => timers[index].due += period.max(Duration::from_millis(1)),
+            None => {
+                timers.remove(index);
+            }
         }
+
+        Some(callback)
     }
 }
 
 /* ============================================================================
- * Timer Runtime Initialization
+ * Installing Built-in Timer Functions
  * ============================================================================
  */
 
-/// Creates a fresh timer runtime instance.
+/// Reads the `(fn, ms, token?)` arguments shared by `setTimeout` and
+/// `setInterval`.
 ///
-/// This should be created once per interpreter execution.
-pub fn create_timer_runtime() -> TimerRuntime {
-    let (tx, rx) = mpsc::channel();
-
-    TimerRuntime {
-        tx,
-        rx,
-        timers: Rc::new(RefCell::new(HashMap::new())),
-        next_id: Rc::new(RefCell::new(1)),
+/// # Errors
+/// A message describing the first bad argument.
+fn timer_args(name: &str, args: Vec<Value>) -> Result<(Value, u64, Option<CancelToken>), String> {
+    let (args, token) = take_cancel_token(args);
+
+    if args.len() != 2 {
+        return Err(format!("{}(fn, ms, token?) requires 2 arguments", name));
+    }
+
+    let callback = args[0].clone();
+    let ms = match crate::prototypes::duration::millis(&args[1]) {
+        Some(n) if n >= 0.0 => n as u64,
+        _ => return Err(format!("{} delay must be a non-negative number or Duration", name)),
+    };
+
+    if !matches!(callback, Value::NativeFunction(_)) {
+        return Err(format!("{} requires a function as first argument", name));
     }
+
+    // The callback runs in the Context scope that scheduled it
+    Ok((context::bind(callback), ms, token))
 }
 
-/* ============================================================================
- * Installing Built-in Timer Functions
- * ============================================================================
- */
+/// Reads the numeric ID passed to `clearTimeout` / `clearInterval`.
+///
+/// # Errors
+/// If the ID is not a number.
+fn timer_id(name: &str, args: &[Value]) -> Result<u64, String> {
+    match args.first() {
+        Some(Value::Number(n)) => Ok(*n as u64),
+        _ => Err(format!("{}(id) requires a numeric id", name)),
+    }
+}
 
 /// Installs PAWX timer functions into the global environment.
 ///
@@ -139,228 +220,123 @@ pub fn create_timer_runtime() -> TimerRuntime {
 ///  • setInterval
 ///  • clearTimeout
 ///  • clearInterval
+///
+/// The returned runtime must be pumped with [`pump_timers`] for callbacks
+/// to run.
 pub fn install_timers(env: Rc<RefCell<Environment>>) -> TimerRuntime {
     let runtime = TimerRuntime::new();
 
-    let timers = runtime.timers.clone();
-    let tx = runtime.tx.clone();
-    let next_id = runtime.next_id.clone();
-
-    env.borrow_mut().define_public(
-        "setTimeout".to_string(),
-        Value::NativeFunction(Arc::new(move |args: Vec<Value>| -> Value {
-            if args.len() != 2 {
-                panic!("setTimeout(fn, ms) requires 2 arguments");
-            }
-
-            let callback = args[0].clone();
-            let ms = match args[1] {
-                Value::Number(n) => n as u64,
-                _ => panic!("setTimeout delay must be a number"),
-            };
-
-            if !matches!(callback, Value::NativeFunction(_)) {
-                panic!("setTimeout requires a function as first argument");
-            }
-
-            let id = {
-                let mut counter = next_id.borrow_mut();
-                let id = *counter;
-                *counter += 1;
-                id
-            };
-
-            timers.borrow_mut().insert(
-                id,
-                TimerEntry {
-                    callback,
-                    is_interval: false,
-                    cancel_flag: None,
-                },
-            );
-
-            let tx_cloned = tx.clone();
-            std::thread::spawn(move || {
-                std::thread::sleep(std::time::Duration::from_millis(ms));
-                let _ = tx_cloned.send(TimerMessage::Timeout(id));
-            });
-
-            Value::Number(id as f64)
-        })),
-    );
-
-    runtime
-}
-
-/* --------------------------------------------------------------------------
- * setTimeout(fn, ms)
- * ----------------------------------------------------------------------- */
-
-fn install_set_timeout(env: &mut Environment, runtime: &TimerRuntime) {
-    let timers = runtime.timers.clone();
-    let next_id = runtime.next_id.clone();
-    let tx = runtime.tx.clone();
-
-    env.define_public(
-        "setTimeout".to_string(),
-        Value::NativeFunction(Arc::new(move |args: Vec<Value>| -> Value {
-            if args.len() != 2 {
-                panic!("setTimeout(fn, ms) requires 2 arguments");
-            }
-
-            let callback = args[0].clone();
-            let delay_ms = match args[1] {
-                Value::Number(n) => n as u64,
-                _ => panic!("setTimeout delay must be a number"),
-            };
+    for (name, is_interval) in [("setTimeout", false), ("setInterval", true)] {
+        let runtime = runtime.clone();
 
-            if !matches!(callback, Value::NativeFunction(_)) {
-                panic!("setTimeout requires a function as first argument");
-            }
-
-            // Allocate unique timer ID
-            let id = {
-                let mut counter = next_id.borrow_mut();
-                let id = *counter;
-                *counter += 1;
-                id
-            };
-
-            timers.borrow_mut().insert(
-                id,
-                TimerEntry {
-                    callback,
-                    is_interval: false,
-                    cancel_flag: None,
-                },
-            );
-
-            let tx_cloned = tx.clone();
-            std::thread::spawn(move || {
-                std::thread::sleep(Duration::from_millis(delay_ms));
-                let _ = tx_cloned.send(TimerMessage::Timeout(id));
-            });
-
-            Value::Number(id as f64)
-        })),
-    );
-}
-
-/* --------------------------------------------------------------------------
- * setInterval(fn, ms)
- * ----------------------------------------------------------------------- */
-
-fn install_set_interval(env: &mut Environment, runtime: &TimerRuntime) {
-    let timers = runtime.timers.clone();
-    let next_id = runtime.next_id.clone();
-    let tx = runtime.tx.clone();
-
-    env.define_public(
-        "setInterval".to_string(),
-        Value::NativeFunction(Arc::new(move |args: Vec<Value>| -> Value {
-            if args.len() != 2 {
-                panic!("setInterval(fn, ms) requires 2 arguments");
-            }
+        env.borrow_mut().define_public(
+            name.to_string(),
+            Value::NativeFunction(Arc::new(move |args: Vec<Value>| -> Result<Value, PawxError> {
+                let (callback, ms, token) = match timer_args(name, args) {
+                    Ok(parsed) => parsed,
+                    Err(message) => return raise_error(message),
+                };
+                let id = runtime.allocate_id();
+
+                let delay = Duration::from_millis(ms);
+                runtime.schedule(id, delay, is_interval.then_some(delay), callback);
+
+                // Cancelling the token drops the timer before it can fire.
+                if let Some(token) = token {
+                    let runtime = runtime.clone();
+                    token.on_cancel(Value::NativeFunction(Arc::new(move |_args| {
+                        runtime.cancel(id);
+                        Ok(Value::Null)
+                    })))?;
+                }
 
-            let callback = args[0].clone();
-            let delay_ms = match args[1] {
-                Value::Number(n) => n as u64,
-                _ => panic!("setInterval delay must be a number"),
-            };
+                Ok(Value::Number(id as f64))
+            })),
+        );
+    }
 
-            if !matches!(callback, Value::NativeFunction(_)) {
-                panic!("setInterval requires a function as first argument");
-            }
+    for name in ["clearTimeout", "clearInterval"] {
+        let runtime = runtime.clone();
 
-            let id = {
-                let mut counter = next_id.borrow_mut();
-                let id = *counter;
-                *counter += 1;
-                id
-            };
-
-            let stop_flag = Arc::new(AtomicBool::new(false));
-
-            timers.borrow_mut().insert(
-                id,
-                TimerEntry {
-                    callback,
-                    is_interval: true,
-                    cancel_flag: Some(stop_flag.clone()),
-                },
-            );
-
-            let tx_cloned = tx.clone();
-            std::thread::spawn(move || {
-                loop {
-                    std::thread::sleep(Duration::from_millis(delay_ms));
-
-                    if stop_flag.load(Ordering::SeqCst) {
-                        break;
+        env.borrow_mut().define_public(
+            name.to_string(),
+            Value::NativeFunction(Arc::new(move |args: Vec<Value>| -> Result<Value, PawxError> {
+                match timer_id(name, &args) {
+                    Ok(id) => {
+                        runtime.cancel(id);
+                        Ok(Value::Null)
                     }
-
-                    let _ = tx_cloned.send(TimerMessage::IntervalTick(id));
+                    Err(message) => raise_error(message),
                 }
-            });
+            })),
+        );
+    }
 
-            Value::Number(id as f64)
-        })),
-    );
+    runtime
 }
 
-/* --------------------------------------------------------------------------
- * clearTimeout(id)
- * ----------------------------------------------------------------------- */
-
-fn install_clear_timeout(env: &mut Environment, runtime: &TimerRuntime) {
-    let timers = runtime.timers.clone();
-
-    env.define_public(
-        "clearTimeout".to_string(),
-        Value::NativeFunction(Arc::new(move |args: Vec<Value>| -> Value {
-            if args.len() != 1 {
-                panic!("clearTimeout(id) requires 1 argument");
-            }
+/// Installs PAWX timer functions driven by a **virtual clock**.
+///
+/// Used by `--virtual-time` mode. Instead of queueing real-time timers,
+/// every timer is scheduled on the given [`VirtualClock`] and only fires when
+/// the clock is advanced (for example through `Clock.advance(ms)`). The
+/// returned runtime stays empty, so the event loop ends with the program.
+///
+/// This registers:
+///  • setTimeout
+///  • setInterval
+///  • clearTimeout
+///  • clearInterval
+pub fn install_virtual_timers(env: Rc<RefCell<Environment>>, clock: &VirtualClock) -> TimerRuntime {
+    let runtime = TimerRuntime::new();
 
-            let id = match args[0] {
-                Value::Number(n) => n as u64,
-                _ => panic!("clearTimeout(id) requires a numeric id"),
-            };
+    for (name, is_interval) in [("setTimeout", false), ("setInterval", true)] {
+        let clock = clock.clone();
+        let runtime = runtime.clone();
 
-            timers.borrow_mut().remove(&id);
-            Value::Null
-        })),
-    );
-}
+        env.borrow_mut().define_public(
+            name.to_string(),
+            Value::NativeFunction(Arc::new(move |args: Vec<Value>| -> Result<Value, PawxError> {
+                let (callback, ms, token) = match timer_args(name, args) {
+                    Ok(parsed) => parsed,
+                    Err(message) => return raise_error(message),
+                };
+                let id = runtime.allocate_id();
 
-/* --------------------------------------------------------------------------
- * clearInterval(id)
- * ----------------------------------------------------------------------- */
+                let interval = if is_interval { Some(ms) } else { None };
+                clock.schedule(id, ms, interval, callback);
 
-fn install_clear_interval(env: &mut Environment, runtime: &TimerRuntime) {
-    let timers = runtime.timers.clone();
+                if let Some(token) = token {
+                    let clock = clock.clone();
+                    token.on_cancel(Value::NativeFunction(Arc::new(move |_args| {
+                        clock.cancel(id);
+                        Ok(Value::Null)
+                    })))?;
+                }
 
-    env.define_public(
-        "clearInterval".to_string(),
-        Value::NativeFunction(Arc::new(move |args: Vec<Value>| -> Value {
-            if args.len() != 1 {
-                panic!("clearInterval(id) requires 1 argument");
-            }
+                Ok(Value::Number(id as f64))
+            })),
+        );
+    }
 
-            let id = match args[0] {
-                Value::Number(n) => n as u64,
-                _ => panic!("clearInterval(id) requires a numeric id"),
-            };
+    for name in ["clearTimeout", "clearInterval"] {
+        let clock = clock.clone();
 
-            if let Some(entry) = timers.borrow_mut().remove(&id) {
-                if let Some(flag) = entry.cancel_flag {
-                    flag.store(true, Ordering::SeqCst);
+        env.borrow_mut().define_public(
+            name.to_string(),
+            Value::NativeFunction(Arc::new(move |args: Vec<Value>| -> Result<Value, PawxError> {
+                match timer_id(name, &args) {
+                    Ok(id) => {
+                        clock.cancel(id);
+                        Ok(Value::Null)
+                    }
+                    Err(message) => raise_error(message),
                 }
-            }
+            })),
+        );
+    }
 
-            Value::Null
-        })),
-    );
+    runtime
 }
 
 /* ============================================================================
@@ -368,36 +344,35 @@ fn install_clear_interval(env: &mut Environment, runtime: &TimerRuntime) {
  * ============================================================================
  */
 
-/// Dispatches any pending timer events onto the main interpreter thread.
+/// Runs the callback of every timer that is due, on the interpreter thread.
 ///
-/// This **must be called regularly** from the interpreter execution loop.
-pub fn pump_timers(runtime: &TimerRuntime) {
-    loop {
-        let msg = match runtime.rx.try_recv() {
-            Ok(m) => m,
-            Err(_) => break,
-        };
-
-        match msg {
-            TimerMessage::Timeout(id) => {
-                let entry = runtime.timers.borrow_mut().remove(&id);
-                if let Some(entry) = entry {
-                    if let Value::NativeFunction(f) = entry.callback {
-                        f(vec![]);
-                    }
-                }
-            }
+/// Timers a callback schedules wait for the next pump, even with a zero
+/// delay, so a `setTimeout(fn, 0)` chain cannot starve the program.
+///
+/// # Errors
+/// - The first error a callback fails with
+pub fn pump_timers(runtime: &TimerRuntime) -> Result<(), PawxError> {
+    let now = Instant::now();
+
+    while let Some(callback) = runtime.take_due(now) {
+        if let Value::NativeFunction(f) = callback {
+            f(vec![])?;
+        }
+    }
 
-            TimerMessage::IntervalTick(id) => {
-                let callback = {
-                    let map = runtime.timers.borrow();
-                    map.get(&id).map(|e| e.callback.clone())
-                };
+    Ok(())
+}
 
-                if let Some(Value::NativeFunction(f)) = callback {
-                    f(vec![]);
-                }
-            }
+/// Blocks until the next timer is due.
+///
+/// # Returns
+/// - `false` without waiting if no timers are pending
+pub fn wait_for_next(runtime: &TimerRuntime) -> bool {
+    match runtime.next_due() {
+        Some(due) => {
+            std::thread::sleep(due.saturating_duration_since(Instant::now()));
+            true
         }
+        None => false,
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,183 @@
+/*
+ * ==========================================================================
+ * PAWX - Code with Claws!
+ * ==========================================================================
+ *
+ * Tutorial Lessons
+ * ----------------
+ * The lessons `pawx learn` walks through, in order. Each one explains a
+ * single idea, then asks for a snippet whose `meow` output must match
+ * `expected` exactly (trailing whitespace aside).
+ *
+ * Lesson ids are stored in the progress file, so keep them stable when
+ * editing or reordering lessons.
+ *
+ * --------------------------------------------------------------------------
+ * Author:   Sam Wilcox
+ * Email:    sam@pawx-lang.com
+ * Website:  https://www.pawx-lang.com
+ * Github:   https://github.com/samwilcox/pawx
+ *
+ * License:
+ * This file is part of the PAWX programming language project.
+ *
+ * PAWX is dual-licensed under the terms of:
+ *   - The MIT License
+ *   - The Apache License, Version 2.0
+ *
+ * You may choose either license to govern your use of this software.
+ * Full license text available at:
+ *    https://license.pawx-lang.com
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under these licenses is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *
+ * ==========================================================================
+ */
+
+/// One step of the tutorial.
+#[derive(Debug, Clone, Copy)]
+pub struct Lesson {
+    /// Stable identifier, recorded in the progress file.
+    pub id: &'static str,
+
+    pub title: &'static str,
+
+    /// What the lesson teaches, with a small example.
+    pub text: &'static str,
+
+    /// What the learner's snippet has to do.
+    pub task: &'static str,
+
+    /// The output the snippet must print.
+    pub expected: &'static str,
+
+    /// Shown on `:hint`.
+    pub hint: &'static str,
+
+    /// Shown on `:solution`.
+    pub solution: &'static str,
+}
+
+pub const LESSONS: &[Lesson] = &[
+    Lesson {
+        id: "hello",
+        title: "Saying hello",
+        text: "\
+`meow` prints its arguments on one line, separated by spaces:
+
+    meow(\"Hi\", 42);      // Hi 42",
+        task: "Print the line: Hello, PAWX!",
+        expected: "Hello, PAWX!",
+        hint: "Strings go in double quotes: meow(\"...\");",
+        solution: "meow(\"Hello, PAWX!\");",
+    },
+    Lesson {
+        id: "variables",
+        title: "Variables",
+        text: "\
+`snuggle` declares a variable. `+` joins strings:
+
+    snuggle greeting = \"Hi\";
+    meow(greeting + \"!\");   // Hi!",
+        task: "Store \"Mittens\" in a variable called name, then print: Hello, Mittens",
+        expected: "Hello, Mittens",
+        hint: "snuggle name = \"Mittens\"; then join \"Hello, \" and name with +.",
+        solution: "snuggle name = \"Mittens\";\nmeow(\"Hello, \" + name);",
+    },
+    Lesson {
+        id: "functions",
+        title: "Functions",
+        text: "\
+`purr` declares a function: its name, its parameters, then its body:
+
+    purr add -> (a, b) -> {
+        return a + b;
+    }
+    meow(add(2, 3));        // 5",
+        task: "Write a function double that returns twice its argument, and print double(21).",
+        expected: "42",
+        hint: "purr double -> (n) -> { return n * 2; }",
+        solution: "purr double -> (n) -> {\n    return n * 2;\n}\nmeow(double(21));",
+    },
+    Lesson {
+        id: "conditions",
+        title: "Making decisions",
+        text: "\
+`if` runs a block when its condition is true, and `else` otherwise:
+
+    snuggle lives = 9;
+    if (lives > 0) {
+        meow(\"still going\");
+    } else {
+        meow(\"out of lives\");
+    }",
+        task: "Set age to 3. Print kitten if age is below 1, otherwise print cat.",
+        expected: "cat",
+        hint: "if (age < 1) { ... } else { ... }",
+        solution: "snuggle age = 3;\nif (age < 1) {\n    meow(\"kitten\");\n} else {\n    meow(\"cat\");\n}",
+    },
+    Lesson {
+        id: "loops",
+        title: "Loops",
+        text: "\
+`while` repeats a block as long as its condition holds. Don't forget to
+move towards the end, or the loop never stops:
+
+    snuggle n = 3;
+    while (n > 0) {
+        meow(n);
+        n = n - 1;
+    }",
+        task: "Print the numbers 1, 2 and 3, one per line.",
+        expected: "1\n2\n3",
+        hint: "Start a counter at 1 and loop while it is <= 3, adding 1 each time.",
+        solution: "snuggle i = 1;\nwhile (i <= 3) {\n    meow(i);\n    i = i + 1;\n}",
+    },
+    Lesson {
+        id: "arrays",
+        title: "Arrays",
+        text: "\
+Arrays hold a list of values. Index them from 0, grow them with `push`
+and count them with `length`:
+
+    snuggle naps = [\"sofa\", \"box\"];
+    naps.push(\"sunbeam\");
+    meow(naps[0], naps.length);   // sofa 3",
+        task: "Make an array with \"ball\" and \"mouse\", push \"string\" onto it, then print its length.",
+        expected: "3",
+        hint: "snuggle toys = [\"ball\", \"mouse\"]; toys.push(...); meow(toys.length);",
+        solution: "snuggle toys = [\"ball\", \"mouse\"];\ntoys.push(\"string\");\nmeow(toys.length);",
+    },
+    Lesson {
+        id: "objects",
+        title: "Objects",
+        text: "\
+Objects group named values. `meow` can also fill in `$` placeholders:
+
+    snuggle cat = { name: \"Tom\", lives: 9 };
+    meow(\"$ has $ lives\", cat.name, cat.lives);   // Tom has 9 lives",
+        task: "Make an object with name \"Tom\" and age 4, then print: Tom is 4 years old",
+        expected: "Tom is 4 years old",
+        hint: "meow(\"$ is $ years old\", cat.name, cat.age);",
+        solution: "snuggle cat = { name: \"Tom\", age: 4 };\nmeow(\"$ is $ years old\", cat.name, cat.age);",
+    },
+    Lesson {
+        id: "errors",
+        title: "Handling errors",
+        text: "\
+`throw` stops the current code with a value; the nearest `try` around it
+can `catch` that value and carry on:
+
+    try {
+        throw \"no treats left\";
+    } catch (e) {
+        meow(\"oh no:\", e);
+    }",
+        task: "Throw \"hairball\" inside a try block, catch it, and print: caught hairball",
+        expected: "caught hairball",
+        hint: "try { throw ...; } catch (e) { meow(\"caught\", e); }",
+        solution: "try {\n    throw \"hairball\";\n} catch (e) {\n    meow(\"caught\", e);\n}",
+    },
+];
@@ -0,0 +1,277 @@
+/*
+ * ==========================================================================
+ * PAWX - Code with Claws!
+ * ==========================================================================
+ *
+ * Interactive Tutorial (`pawx learn`)
+ * -----------------------------------
+ * A guided tour of the language in the terminal. Each lesson explains an
+ * idea and sets a small task; the learner types a snippet, which runs in
+ * the sandbox (see `sandbox.rs`), and its output is checked against the
+ * lesson's expected output:
+ *
+ * ```text
+ * Lesson 3/8: Functions
+ * ...
+ * Task: Write a function double that returns twice its argument, ...
+ *
+ * > purr double -> (n) -> { return n * 2; }
+ * . meow(double(21));
+ * .
+ * 42
+ * ✅ Correct!
+ * ```
+ *
+ * Finished lessons are recorded in `.pawx-learn.json` in the current
+ * directory, so `pawx learn` picks up where the learner left off.
+ *
+ * --------------------------------------------------------------------------
+ * Author:   Sam Wilcox
+ * Email:    sam@pawx-lang.com
+ * Website:  https://www.pawx-lang.com
+ * Github:   https://github.com/samwilcox/pawx
+ *
+ * License:
+ * This file is part of the PAWX programming language project.
+ *
+ * PAWX is dual-licensed under the terms of:
+ *   - The MIT License
+ *   - The Apache License, Version 2.0
+ *
+ * You may choose either license to govern your use of this software.
+ * Full license text available at:
+ *    https://license.pawx-lang.com
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under these licenses is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *
+ * ==========================================================================
+ */
+
+pub mod lessons;
+pub mod sandbox;
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::diagnostics::DiagnosticPrinter;
+use lessons::{Lesson, LESSONS};
+use sandbox::Outcome;
+
+/// File name of the progress file, in the directory `pawx learn` runs in.
+pub const PROGRESS_FILE: &str = ".pawx-learn.json";
+
+/* ============================================================================
+ * Progress
+ * ============================================================================
+ */
+
+/// The lessons a learner has finished.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Progress {
+    /// Ids of finished lessons.
+    #[serde(default)]
+    pub completed: BTreeSet<String>,
+}
+
+impl Progress {
+    /// Reads `dir/.pawx-learn.json`, or starts afresh if there is none.
+    ///
+    /// # Errors
+    /// If the file exists but cannot be read or parsed.
+    pub fn load(dir: &Path) -> Result<Progress, String> {
+        let path = dir.join(PROGRESS_FILE);
+        if !path.is_file() {
+            return Ok(Progress::default());
+        }
+
+        let text = fs::read_to_string(&path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        serde_json::from_str(&text).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    /// Writes `dir/.pawx-learn.json`.
+    pub fn save(&self, dir: &Path) -> Result<(), String> {
+        let path = dir.join(PROGRESS_FILE);
+        let text = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        fs::write(&path, text + "\n").map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    pub fn is_complete(&self, lesson: &Lesson) -> bool {
+        self.completed.contains(lesson.id)
+    }
+
+    /// Index of the first lesson not yet finished.
+    pub fn next_lesson(&self) -> Option<usize> {
+        LESSONS.iter().position(|lesson| !self.is_complete(lesson))
+    }
+}
+
+/// Finds a lesson by number (counted from 1) or id.
+pub fn find_lesson(query: &str) -> Option<usize> {
+    match query.parse::<usize>() {
+        Ok(n) => (1..=LESSONS.len()).contains(&n).then(|| n - 1),
+        Err(_) => LESSONS.iter().position(|lesson| lesson.id == query),
+    }
+}
+
+/// Whether `actual` output matches `expected`, ignoring trailing
+/// whitespace on each line and blank lines at the end.
+pub fn output_matches(actual: &str, expected: &str) -> bool {
+    fn normalize(text: &str) -> Vec<&str> {
+        let mut lines: Vec<&str> = text.lines().map(str::trim_end).collect();
+        while lines.last() == Some(&"") {
+            lines.pop();
+        }
+        lines
+    }
+
+    normalize(actual) == normalize(expected)
+}
+
+/* ============================================================================
+ * Session
+ * ============================================================================
+ */
+
+/// What the learner chose to do at the prompt.
+enum Entry {
+    Snippet(String),
+    Hint,
+    Solution,
+    Skip,
+    Quit,
+}
+
+/// Reads a snippet, ending at an empty line, or a `:command`.
+///
+/// End of input counts as `:quit`.
+fn read_entry(input: &mut impl BufRead) -> io::Result<Entry> {
+    let mut snippet = String::new();
+
+    loop {
+        print!("{}", if snippet.is_empty() { "> " } else { ". " });
+        io::stdout().flush()?;
+
+        let mut line = String::new();
+        if input.read_line(&mut line)? == 0 {
+            return Ok(Entry::Quit);
+        }
+        let trimmed = line.trim();
+
+        if snippet.is_empty() {
+            match trimmed {
+                "" => continue,
+                ":hint" => return Ok(Entry::Hint),
+                ":solution" => return Ok(Entry::Solution),
+                ":skip" => return Ok(Entry::Skip),
+                ":quit" | ":q" => return Ok(Entry::Quit),
+                _ => {}
+            }
+        } else if trimmed.is_empty() {
+            return Ok(Entry::Snippet(snippet));
+        }
+
+        snippet.push_str(&line);
+    }
+}
+
+fn print_lesson(index: usize) {
+    let lesson = &LESSONS[index];
+
+    println!();
+    println!("📘 Lesson {}/{}: {}", index + 1, LESSONS.len(), lesson.title);
+    println!();
+    println!("{}", lesson.text);
+    println!();
+    println!("Task: {}", lesson.task);
+    println!();
+    println!("Type your code and finish with an empty line (:hint, :solution, :skip, :quit).");
+}
+
+/// Runs lessons from `start` on, reading snippets from `input` and saving
+/// `progress` to `dir` after each lesson is passed.
+///
+/// Returns when the learner quits or the last lesson is done.
+pub fn session(start: usize, progress: &mut Progress, dir: &Path, input: &mut impl BufRead) -> Result<(), String> {
+    let mut index = start;
+
+    while index < LESSONS.len() {
+        let lesson = &LESSONS[index];
+        print_lesson(index);
+
+        loop {
+            let snippet = match read_entry(input).map_err(|e| e.to_string())? {
+                Entry::Snippet(snippet) => snippet,
+                Entry::Hint => {
+                    println!("💡 {}", lesson.hint);
+                    continue;
+                }
+                Entry::Solution => {
+                    println!("{}", lesson.solution);
+                    continue;
+                }
+                Entry::Skip => break,
+                Entry::Quit => {
+                    println!("See you next time! Run `pawx learn` to continue.");
+                    return Ok(());
+                }
+            };
+
+            let output = match sandbox::run(&snippet) {
+                Outcome::Finished { output } => output,
+                Outcome::Failed { output, error } => {
+                    print!("{}", output);
+                    DiagnosticPrinter::new("snippet.px", snippet.as_str()).print(&error);
+                    println!("Not quite: fix the error and try again.");
+                    continue;
+                }
+                Outcome::TimedOut => {
+                    println!(
+                        "⏱️  Still running after {}s. Is there a loop that never ends?",
+                        sandbox::TIME_LIMIT.as_secs()
+                    );
+                    continue;
+                }
+            };
+
+            print!("{}", output);
+
+            if output_matches(&output, lesson.expected) {
+                println!("✅ Correct!");
+                progress.completed.insert(lesson.id.to_string());
+                progress.save(dir)?;
+                break;
+            }
+
+            println!("Not quite. Expected:");
+            println!("{}", lesson.expected);
+        }
+
+        index += 1;
+    }
+
+    match progress.next_lesson() {
+        None => println!("\n🎉 You finished every lesson!"),
+        Some(next) => println!(
+            "\nThat was the last lesson. Lesson {} ({}) is still unfinished.",
+            next + 1,
+            LESSONS[next].title
+        ),
+    }
+    Ok(())
+}
+
+/// Prints every lesson, marking the finished ones.
+pub fn list(progress: &Progress) {
+    for (i, lesson) in LESSONS.iter().enumerate() {
+        let mark = if progress.is_complete(lesson) { "✅" } else { "  " };
+        println!("{} {:>2}. {:<18} ({})", mark, i + 1, lesson.title, lesson.id);
+    }
+    let finished = LESSONS.iter().filter(|lesson| progress.is_complete(lesson)).count();
+    println!("\n{}/{} lessons finished", finished, LESSONS.len());
+}
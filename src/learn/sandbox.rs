@@ -0,0 +1,124 @@
+/*
+ * ==========================================================================
+ * PAWX - Code with Claws!
+ * ==========================================================================
+ *
+ * Tutorial Sandbox
+ * ----------------
+ * Runs a learner's snippet the way `pawx test --sandbox-fs --deterministic`
+ * runs a test file, on its own interpreter thread like an isolate:
+ *
+ * - `Fs` sees an empty in-memory filesystem, so nothing on disk changes.
+ * - `Math.random` is seeded and time is virtual, so answers are repeatable.
+ * - Whatever `meow` prints is captured instead of written to the terminal.
+ * - A snippet that runs longer than `TIME_LIMIT` is abandoned.
+ *
+ * --------------------------------------------------------------------------
+ * Author:   Sam Wilcox
+ * Email:    sam@pawx-lang.com
+ * Website:  https://www.pawx-lang.com
+ * Github:   https://github.com/samwilcox/pawx
+ *
+ * License:
+ * This file is part of the PAWX programming language project.
+ *
+ * PAWX is dual-licensed under the terms of:
+ *   - The MIT License
+ *   - The Apache License, Version 2.0
+ *
+ * You may choose either license to govern your use of this software.
+ * Full license text available at:
+ *    https://license.pawx-lang.com
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under these licenses is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *
+ * ==========================================================================
+ */
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+
+use crate::error::PawxError;
+use crate::interpreter::prelude::Prelude;
+use crate::interpreter::testing::panic_message;
+use crate::span::Span;
+
+/// How long a snippet may run.
+pub const TIME_LIMIT: Duration = Duration::from_secs(5);
+
+/// How a snippet's run ended.
+#[derive(Debug)]
+pub enum Outcome {
+    /// Ran to completion, printing `output`.
+    Finished { output: String },
+
+    /// Stopped with `error` after printing `output`.
+    Failed { output: String, error: PawxError },
+
+    /// Still running after [`TIME_LIMIT`].
+    TimedOut,
+}
+
+/// Runs `source` in a fresh sandboxed interpreter and waits for it.
+///
+/// A snippet that times out keeps its thread until the process exits;
+/// there is no way to stop an interpreter from outside.
+pub fn run(source: &str) -> Outcome {
+    let source = source.to_string();
+    let (tx, rx) = mpsc::channel();
+
+    let spawned = thread::Builder::new()
+        .name("pawx-learn".to_string())
+        .stack_size(crate::interpreter::limits::STACK_SIZE)
+        .spawn(move || {
+            let output = Rc::new(RefCell::new(String::new()));
+            let prelude = Prelude::new()
+                .with_argv(vec!["snippet.px".to_string()])
+                .with_memory_fs()
+                .with_deterministic(0)
+                .with_captured_output(output.clone());
+
+            // Parsed directly: snippets don't belong in `.pawx-cache/`.
+            let result = crate::lexer::tokenize(&source)
+                .and_then(crate::parser::parse)
+                .and_then(|statements| crate::interpreter::run_with_prelude(statements, &prelude));
+
+            let output = output.borrow().clone();
+            let _ = tx.send(match result {
+                Ok(()) => Outcome::Finished { output },
+                Err(error) => Outcome::Failed { output, error },
+            });
+        });
+
+    let handle = match spawned {
+        Ok(handle) => handle,
+        Err(e) => {
+            return Outcome::Failed {
+                output: String::new(),
+                error: PawxError::runtime_error(format!("could not start the sandbox: {}", e), Span::default()),
+            }
+        }
+    };
+
+    match rx.recv_timeout(TIME_LIMIT) {
+        Ok(outcome) => outcome,
+        Err(RecvTimeoutError::Timeout) => Outcome::TimedOut,
+
+        // The interpreter itself panicked before reporting back.
+        Err(RecvTimeoutError::Disconnected) => {
+            let message = match handle.join() {
+                Err(payload) => panic_message(payload.as_ref()),
+                Ok(()) => "the snippet stopped without a result".to_string(),
+            };
+            Outcome::Failed {
+                output: String::new(),
+                error: PawxError::runtime_error(message, Span::default()),
+            }
+        }
+    }
+}
@@ -29,6 +29,52 @@
  * ==========================================================================
  */
 
+/// Every reserved word in PAWX, in no particular order.
+///
+/// The lexer classifies these as `TokenKind::Keyword`, and the grammars
+/// `pawx highlight --grammar` generates are built from the same list.
+/// Any future language keywords should be added here.
+pub const KEYWORDS: &[&str] = &[
+    "den",
+    "lair",
+    "pride",
+    "purr",
+    "zoom", // async
+    "snuggle",
+    "return",
+    "if",
+    "else",
+    "while",
+    "when",
+    "match",
+    "case",
+    "true",
+    "false",
+    "null",
+    "nap",
+    "try",
+    "catch",
+    "finally",
+    "throw",
+    "new",
+    "clowder",
+    "instinct",
+    "inherits",
+    "super",
+    "instanceof",
+    "practices",
+    "static",
+    "get",
+    "set",
+    "this",
+    "exports",
+    "tap",
+    "default",
+];
+
+/// Keywords that name a value rather than introduce syntax.
+pub const CONSTANTS: &[&str] = &["true", "false", "null"];
+
 /// Determines whether a given identifier is a **reserved keyword** in PAWX.
 ///
 /// This function is used exclusively by the lexer during tokenization to
@@ -43,11 +89,9 @@
 /// - `false` if the word should be treated as a normal identifier.
 ///
 /// # Behavior
-/// - This function performs a **constant-time lookup** using Rust’s `matches!`
-///   macro.
-/// - All keywords defined here are treated as `TokenKind::Keyword` during
+/// - Looks the word up in [`KEYWORDS`].
+/// - All keywords listed there are treated as `TokenKind::Keyword` during
 ///   lexing.
-/// - Any future language keywords should be added here.
 ///
 /// # PAWX Examples
 /// ```text
@@ -61,37 +105,5 @@
 /// This function is intended for use by the lexer and should not be called
 /// directly by user code.
 pub fn is_keyword(word: &str) -> bool {
-    matches!(
-        word,
-        "den" |
-        "lair" |
-        "pride" |
-        "purr" |
-        "zoom" |      // async
-        "snuggle" |
-        "return" |
-        "if" |
-        "else" |
-        "while" |
-        "true" |
-        "false" |
-        "null" |
-        "nap" |
-        "try" |
-        "catch" |
-        "finally" |
-        "throw" |
-        "new" |
-        "clowder" |
-        "instinct" |
-        "inherits" |
-        "practices" |
-        "static" |
-        "get" |
-        "set" |
-        "this" |
-        "exports" |
-        "tap" |
-        "default"
-    )
-}
\ No newline at end of file
+    KEYWORDS.contains(&word)
+}
@@ -26,6 +26,7 @@
  * ==========================================================================
  */
 
+use crate::error::PawxError;
 use crate::lexer::token::{Token, TokenKind};
 use crate::lexer::keywords::is_keyword;
 use crate::span::Span;
@@ -34,6 +35,13 @@ pub struct Lexer {
     chars: Vec<char>,
     current: usize,
     line: usize,
+
+    /// Index of the first character of the current line.
+    line_start: usize,
+
+    /// Where the token being scanned began.
+    start: Span,
+
     pub tokens: Vec<Token>,
 }
 
@@ -59,6 +67,8 @@ impl Lexer {
             chars: source.chars().collect(),
             current: 0,
             line: 1,
+            line_start: 0,
+            start: Span::new(1, 0),
             tokens: Vec::new(),
         }
     }
@@ -76,20 +86,33 @@ impl Lexer {
     /// # Output
     /// Results are written into `self.tokens`.
     ///
+    /// # Errors
+    /// A syntax error for an unterminated string or block comment.
+    ///
     /// # Safety
     /// This function **must be called exactly once** per lexer instance.
-    pub fn scan_tokens(&mut self) {
+    pub fn scan_tokens(&mut self) -> Result<(), PawxError> {
         while !self.is_at_end() {
-            self.scan_token();
+            self.start = self.position();
+            self.scan_token()?;
         }
 
+        self.start = self.position();
+        self.push(TokenKind::Eof, "".to_string());
+        Ok(())
+    }
+
+    /// Returns the position of the next character.
+    fn position(&self) -> Span {
+        Span::new(self.line, self.current - self.line_start)
+    }
+
+    /// Emits a token that starts where the current scan began.
+    fn push(&mut self, kind: TokenKind, lexeme: String) {
         self.tokens.push(Token {
-            kind: TokenKind::Eof,
-            lexeme: "".to_string(),
-            span: Span {
-                line: self.line,
-                column: 0,
-            },
+            kind,
+            lexeme,
+            span: self.start,
         });
     }
 
@@ -108,18 +131,18 @@ impl Lexer {
     ///
     /// # Behavior
     /// - Handles `//` and `/* */` comments
-    /// - Supports multi-character operators (`==`, `===`, `!=`, `->`, `++`, `--`)
+    /// - Supports multi-character operators (`==`, `===`, `!=`, `->`, `++`, `--`, `...`,
+    ///   `**`, `~/`, `<<`, `>>`, `>>>`)
     /// - Updates line counter automatically
     ///
-    /// # Panics
-    /// May panic on unterminated strings or block comments.
-    fn scan_token(&mut self) {
+    /// # Errors
+    /// On unterminated strings or block comments.
+    fn scan_token(&mut self) -> Result<(), PawxError> {
         let ch = self.advance();
 
         match ch {
-            // Whitespace
-            ' ' | '\r' | '\t' => {}
-            '\n' => self.line += 1,
+            // Whitespace (`advance` counts lines)
+            ' ' | '\r' | '\t' | '\n' => {}
 
             // Single-line or block comment
             '/' => {
@@ -128,16 +151,9 @@ impl Lexer {
                         self.advance();
                     }
                 } else if self.match_char('*') {
-                    self.block_comment();
+                    self.block_comment()?;
                 } else {
-                    self.tokens.push(Token {
-                        kind: TokenKind::Symbol,
-                        lexeme: "/".to_string(),
-                        span: Span {
-                            line: self.line,
-                            column: 0
-                        },
-                    });
+                    self.push(TokenKind::Symbol, "/".to_string());
                 }
             }
 
@@ -145,56 +161,61 @@ impl Lexer {
             '-' => {
                 if self.peek() == '>' {
                     self.advance();
-                    self.tokens.push(Token {
-                        kind: TokenKind::Symbol,
-                        lexeme: "->".to_string(),
-                        span: Span {
-                            line: self.line,
-                            column: 0
-                        },
-                    });
+                    self.push(TokenKind::Symbol, "->".to_string());
                 } else if self.peek() == '-' {
                     self.advance();
-                    self.tokens.push(Token {
-                        kind: TokenKind::Symbol,
-                        lexeme: "--".to_string(),
-                        span: Span {
-                            line: self.line,
-                            column: 0
-                        },
-                    });
+                    self.push(TokenKind::Symbol, "--".to_string());
                 } else {
-                    self.tokens.push(Token {
-                        kind: TokenKind::Symbol,
-                        lexeme: "-".to_string(),
-                        span: Span {
-                            line: self.line,
-                            column: 0
-                        },
-                    });
+                    self.push(TokenKind::Symbol, "-".to_string());
                 }
             }
 
             '+' => {
                 if self.peek() == '+' {
                     self.advance();
-                    self.tokens.push(Token {
-                        kind: TokenKind::Symbol,
-                        lexeme: "++".to_string(),
-                        span: Span {
-                            line: self.line,
-                            column: 0
-                        },
-                    });
+                    self.push(TokenKind::Symbol, "++".to_string());
+                } else {
+                    self.push(TokenKind::Symbol, "+".to_string());
+                }
+            }
+
+            // Spread / rest: ...
+            '.' if self.peek() == '.' && self.peek_next() == '.' => {
+                self.advance();
+                self.advance();
+                self.push(TokenKind::Symbol, "...".to_string());
+            }
+
+            // Power: **
+            '*' => {
+                if self.match_char('*') {
+                    self.push(TokenKind::Symbol, "**".to_string());
+                } else {
+                    self.push(TokenKind::Symbol, "*".to_string());
+                }
+            }
+
+            // Bitwise not (~) or integer division (~/)
+            '~' => {
+                if self.match_char('/') {
+                    self.push(TokenKind::Symbol, "~/".to_string());
                 } else {
-                    self.tokens.push(Token {
-                        kind: TokenKind::Symbol,
-                        lexeme: "+".to_string(),
-                        span: Span {
-                            line: self.line,
-                            column: 0
-                        },
-                    });
+                    self.push(TokenKind::Symbol, "~".to_string());
+                }
+            }
+
+            // Shifts: <<, >>, >>>
+            '<' if self.peek() == '<' => {
+                self.advance();
+                self.push(TokenKind::Symbol, "<<".to_string());
+            }
+
+            '>' if self.peek() == '>' => {
+                self.advance();
+                if self.match_char('>') {
+                    self.push(TokenKind::Symbol, ">>>".to_string());
+                } else {
+                    self.push(TokenKind::Symbol, ">>".to_string());
                 }
             }
 
@@ -211,18 +232,11 @@ impl Lexer {
                     }
                 }
 
-                self.tokens.push(Token {
-                    kind: TokenKind::Symbol,
-                    lexeme: lex,
-                    span: Span {
-                        line: self.line,
-                        column: 0
-                    },
-                });
+                self.push(TokenKind::Symbol, lex);
             }
 
             // Strings
-            '"' | '\'' => self.string_with_delimiter(ch),
+            '"' | '\'' => self.string_with_delimiter(ch)?,
 
             // Numbers
             '0'..='9' => self.number(),
@@ -233,61 +247,28 @@ impl Lexer {
             '&' => {
                 if self.peek() == '&' {
                     self.advance();
-                    self.tokens.push(Token {
-                        kind: TokenKind::Symbol,
-                        lexeme: "&&".to_string(),
-                        span: Span {
-                            line: self.line,
-                            column: 0,
-                        },
-                    });
+                    self.push(TokenKind::Symbol, "&&".to_string());
                 } else {
-                    self.tokens.push(Token {
-                        kind: TokenKind::Symbol,
-                        lexeme: "&".to_string(),
-                        span: Span {
-                            line: self.line,
-                            column: 0
-                        },
-                    });
+                    self.push(TokenKind::Symbol, "&".to_string());
                 }
             }
 
             '|' => {
                 if self.peek() == '|' {
                     self.advance();
-                    self.tokens.push(Token {
-                        kind: TokenKind::Symbol,
-                        lexeme: "||".to_string(),
-                        span: Span {
-                            line: self.line,
-                            column: 0
-                        },
-                    });
+                    self.push(TokenKind::Symbol, "||".to_string());
                 } else {
-                    self.tokens.push(Token {
-                        kind: TokenKind::Symbol,
-                        lexeme: "|".to_string(),
-                        span: Span {
-                            line: self.line,
-                            column: 0
-                        },
-                    });
+                    self.push(TokenKind::Symbol, "|".to_string());
                 }
             }
 
             // Everything else = single-char symbol
             _ => {
-                self.tokens.push(Token {
-                    kind: TokenKind::Symbol,
-                    lexeme: ch.to_string(),
-                    span: Span {
-                        line: self.line,
-                        column: 0
-                    },
-                });
+                self.push(TokenKind::Symbol, ch.to_string());
             }
         }
+
+        Ok(())
     }
 
     /// Parses a string literal using the provided quote delimiter.
@@ -300,34 +281,25 @@ impl Lexer {
     /// - Tracks line numbers for multi-line strings
     /// - Emits a `TokenKind::String` token
     ///
-    /// # Panics
+    /// # Errors
     /// If the string is not properly terminated before EOF.
-    fn string_with_delimiter(&mut self, delimiter: char) {
+    fn string_with_delimiter(&mut self, delimiter: char) -> Result<(), PawxError> {
         let start = self.current;
 
         while self.peek() != delimiter && !self.is_at_end() {
-            if self.peek() == '\n' {
-                self.line += 1;
-            }
             self.advance();
         }
 
         if self.is_at_end() {
-            panic!("Unterminated string at line {}", self.line);
+            return Err(PawxError::syntax_error("unterminated string", self.start));
         }
 
         self.advance(); // closing quote
 
         let value: String = self.chars[start..self.current - 1].iter().collect();
 
-        self.tokens.push(Token {
-            kind: TokenKind::String,
-            lexeme: value,
-            span: Span {
-                line: self.line,
-                column: 0
-            },
-        });
+        self.push(TokenKind::String, value);
+        Ok(())
     }
 
     /// Parses an identifier or keyword token.
@@ -356,14 +328,7 @@ impl Lexer {
             TokenKind::Identifier
         };
 
-        self.tokens.push(Token {
-            kind,
-            lexeme: text,
-            span: Span {
-                line: self.line,
-                column: 0
-            },
-        });
+        self.push(kind, text);
     }
 
     /// Parses an integer or floating-point numeric literal.
@@ -392,14 +357,7 @@ impl Lexer {
 
         let value: String = self.chars[start..self.current].iter().collect();
 
-        self.tokens.push(Token {
-            kind: TokenKind::Number,
-            lexeme: value,
-            span: Span {
-                line: self.line,
-                column: 0
-            },
-        });
+        self.push(TokenKind::Number, value);
     }
 
     /// Conditionally matches the next character without emitting a token.
@@ -431,24 +389,20 @@ impl Lexer {
     /// - Consumes characters until closing delimiter is found
     /// - Tracks line numbers correctly
     ///
-    /// # Panics
+    /// # Errors
     /// If the block comment is not terminated before EOF.
-    fn block_comment(&mut self) {
+    fn block_comment(&mut self) -> Result<(), PawxError> {
         while !self.is_at_end() {
             if self.peek() == '*' && self.peek_next() == '/' {
                 self.advance();
                 self.advance();
-                return;
-            }
-
-            if self.peek() == '\n' {
-                self.line += 1;
+                return Ok(());
             }
 
             self.advance();
         }
 
-        panic!("Unterminated block comment at line {}", self.line);
+        Err(PawxError::syntax_error("unterminated block comment", self.start))
     }
 
     /// Advances the lexer cursor by one character.
@@ -456,11 +410,18 @@ impl Lexer {
     /// # Returns
     /// The character that was consumed.
     ///
+    /// # Behavior
+    /// Consuming a newline moves to the next line.
+    ///
     /// # Safety
     /// Caller must ensure EOF has not been reached.
     fn advance(&mut self) -> char {
         let ch = self.chars[self.current];
         self.current += 1;
+        if ch == '\n' {
+            self.line += 1;
+            self.line_start = self.current;
+        }
         ch
     }
 
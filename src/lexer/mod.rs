@@ -28,14 +28,19 @@
 
 pub mod token;
 pub mod keywords;
+pub mod operators;
 pub mod lexer;
 
 use lexer::Lexer;
 use token::Token;
 
+use crate::error::PawxError;
+
 /// Public entry function used by the parser
-pub fn tokenize(source: &str) -> Vec<Token> {
+///
+/// Fails with a syntax error on an unterminated string or block comment.
+pub fn tokenize(source: &str) -> Result<Vec<Token>, PawxError> {
     let mut lexer = Lexer::new(source);
-    lexer.scan_tokens();
-    lexer.tokens
+    lexer.scan_tokens()?;
+    Ok(lexer.tokens)
 }
\ No newline at end of file
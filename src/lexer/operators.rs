@@ -0,0 +1,44 @@
+/*
+ * ==========================================================================
+ * PAWX - Code with Claws!
+ * ==========================================================================
+ * 
+ * File:      operators.rs
+ * Purpose:   Lists the operator and punctuation tokens the PAWX lexer emits.
+ * 
+ * Author:    Sam Wilcox
+ * Email:     sam@pawx-lang.com
+ * Website:   https://www.pawx-lang.com
+ * GitHub:    https://github.com/samwilcox/pawx
+ * 
+ * License:
+ * This file is part of the PAWX programming language project.
+ * 
+ * PAWX is dual-licensed under the terms of:
+ *   - The MIT License
+ *   - The Apache License, Version 2.0
+ * 
+ * You may choose either license to govern your use of this software.
+ * Full license text available at:
+ *    https://license.pawx-lang.com
+ * 
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under these licenses is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * 
+ * ==========================================================================
+ */
+
+/// Operator tokens, longest first so a generated pattern that tries them
+/// in order matches `===` before `==`.
+///
+/// These are the symbols `Lexer::scan_token` combines into one token or
+/// that the parser treats as an operator; keep the two in sync.
+pub const OPERATORS: &[&str] = &[
+    "===", "!==", "...", ">>>",
+    "->", "==", "!=", "<=", ">=", "&&", "||", "++", "--", "**", "~/", "<<", ">>",
+    "+", "-", "*", "/", "%", "=", "<", ">", "!", "&", "|", "^", "~", "@",
+];
+
+/// Single-character structural symbols.
+pub const PUNCTUATION: &[&str] = &["(", ")", "{", "}", "[", "]", ",", ";", ":", "."];
@@ -33,6 +33,8 @@
 use crate::span::Span;
 use std::fmt;
 
+use serde::{Deserialize, Serialize};
+
 /// Represents the **category of a lexical token** in the PAWX language.
 ///
 /// `TokenKind` identifies how a sequence of characters from the source
@@ -48,7 +50,7 @@ use std::fmt;
 /// - Operator precedence
 /// - Statement classification
 /// - Error reporting
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum TokenKind {
     /// A numeric literal.
     ///
@@ -121,7 +123,7 @@ pub enum TokenKind {
 /// - Statements
 /// - Control flow
 /// - Function and class declarations
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Token {
     /// The classified category of the token.
     pub kind: TokenKind,
@@ -36,9 +36,39 @@ pub mod value;
 pub mod error;
 pub mod prototypes;
 pub mod span;
+pub mod manifest;
+pub mod scaffold;
+pub mod lockfile;
+pub mod registry;
+pub mod bench;
+pub mod ast_cache;
+pub mod diagnostics;
+pub mod explain;
+pub mod learn;
+pub mod highlight;
+pub mod grep;
+pub mod fix;
+pub mod repl;
+pub mod engine;
 
-pub fn run(source: &str) {
-    let tokens = lexer::tokenize(source);
-    let ast = parser::parse(tokens);
-    interpreter::run(ast);
+pub use engine::{Engine, Program};
+pub use error::{ErrorCode, PawxError};
+pub use interpreter::hooks::InterpreterHooks;
+pub use interpreter::prelude::Prelude;
+
+/// Runs PAWX source code.
+///
+/// Syntax errors and uncaught runtime errors are returned with the span
+/// they occurred at; `diagnostics::DiagnosticPrinter` can render them.
+pub fn run(source: &str) -> Result<(), PawxError> {
+    let tokens = lexer::tokenize(source)?;
+    let ast = parser::parse(tokens)?;
+    interpreter::run_with_prelude(ast, &Prelude::new())
+}
+
+/// Runs PAWX source code with a custom set of global objects.
+pub fn run_with_prelude(source: &str, prelude: &Prelude) -> Result<(), PawxError> {
+    let tokens = lexer::tokenize(source)?;
+    let ast = parser::parse(tokens)?;
+    interpreter::run_with_prelude(ast, prelude)
 }
\ No newline at end of file
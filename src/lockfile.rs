@@ -0,0 +1,393 @@
+/*
+ * ==========================================================================
+ * PAWX - Code with Claws!
+ * ==========================================================================
+ *
+ * Dependency Lockfile (`paw.lock`)
+ * --------------------------------
+ * Records the exact version and a content hash of every package installed
+ * under `paw_modules/`, so a project resolves to the same code everywhere:
+ *
+ * ```text
+ * {
+ *   "lockfileVersion": 1,
+ *   "packages": {
+ *     "left-pad": {
+ *       "version": "1.3.0",
+ *       "integrity": "sha256-…",
+ *       "resolved": "https://registry.pawx-lang.com/left-pad/1.3.0.tar"
+ *     }
+ *   }
+ * }
+ * ```
+ *
+ * A package's integrity hash covers every file in its directory (relative
+ * path and contents, in sorted order), so any edit, addition or removal
+ * changes it.
+ *
+ * In `--frozen` mode the lockfile is the source of truth: a package that is
+ * missing, unlocked or modified stops the program before it runs, and each
+ * package is checked again the first time a `tap` loads it.
+ *
+ * --------------------------------------------------------------------------
+ * Author:   Sam Wilcox
+ * Email:    sam@pawx-lang.com
+ * Website:  https://www.pawx-lang.com
+ * Github:   https://github.com/samwilcox/pawx
+ *
+ * License:
+ * This file is part of the PAWX programming language project.
+ *
+ * PAWX is dual-licensed under the terms of:
+ *   - The MIT License
+ *   - The Apache License, Version 2.0
+ *
+ * You may choose either license to govern your use of this software.
+ * Full license text available at:
+ *    https://license.pawx-lang.com
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under these licenses is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *
+ * ==========================================================================
+ */
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::manifest::{Manifest, MANIFEST_FILE};
+
+/// File name of the lockfile, next to `paw.json`.
+pub const LOCK_FILE: &str = "paw.lock";
+
+/// Directory packages are installed into, next to `paw.json`.
+pub const MODULES_DIR: &str = "paw_modules";
+
+/// Current lockfile format.
+const LOCKFILE_VERSION: u32 = 1;
+
+/// A parsed `paw.lock`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Lockfile {
+    pub lockfile_version: u32,
+
+    /// Package name → what was installed.
+    #[serde(default)]
+    pub packages: BTreeMap<String, LockedPackage>,
+}
+
+/// One installed package.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LockedPackage {
+    pub version: String,
+
+    /// `sha256-<base64>` hash of the package directory.
+    pub integrity: String,
+
+    /// Where the package was downloaded from, if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resolved: Option<String>,
+}
+
+impl Default for Lockfile {
+    fn default() -> Self {
+        Self {
+            lockfile_version: LOCKFILE_VERSION,
+            packages: BTreeMap::new(),
+        }
+    }
+}
+
+/* ============================================================================
+ * Hashing
+ * ============================================================================
+ */
+
+fn collect_files(dir: &Path, out: &mut Vec<PathBuf>) -> Result<(), String> {
+    let entries = fs::read_dir(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+
+    for entry in entries {
+        let path = entry.map_err(|e| e.to_string())?.path();
+        if path.is_dir() {
+            collect_files(&path, out)?;
+        } else {
+            out.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+/// Hashes every file under `dir` into an `sha256-<base64>` integrity string.
+///
+/// # Errors
+/// If the directory or one of its files cannot be read.
+pub fn hash_dir(dir: &Path) -> Result<String, String> {
+    let mut files = Vec::new();
+    collect_files(dir, &mut files)?;
+    files.sort();
+
+    let mut hasher = Sha256::new();
+    for file in files {
+        let rel = file.strip_prefix(dir).unwrap_or(&file);
+        // Hash with `/` separators so lockfiles match across platforms.
+        let rel = rel.to_string_lossy().replace('\\', "/");
+        let contents = fs::read(&file).map_err(|e| format!("{}: {}", file.display(), e))?;
+
+        hasher.update(rel.as_bytes());
+        hasher.update([0]);
+        hasher.update((contents.len() as u64).to_le_bytes());
+        hasher.update(&contents);
+    }
+
+    Ok(format!(
+        "sha256-{}",
+        base64::engine::general_purpose::STANDARD.encode(hasher.finalize())
+    ))
+}
+
+/* ============================================================================
+ * Installed Packages
+ * ============================================================================
+ */
+
+/// Lists the packages installed under `root/paw_modules` as
+/// `(name, directory)`, including scoped `@scope/name` packages.
+pub fn installed_packages(root: &Path) -> Result<Vec<(String, PathBuf)>, String> {
+    let modules = root.join(MODULES_DIR);
+    let mut packages = Vec::new();
+
+    if !modules.is_dir() {
+        return Ok(packages);
+    }
+
+    let mut dirs: Vec<PathBuf> = fs::read_dir(&modules)
+        .map_err(|e| format!("{}: {}", modules.display(), e))?
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.is_dir())
+        .collect();
+    dirs.sort();
+
+    for dir in dirs {
+        let name = dir.file_name().unwrap_or_default().to_string_lossy().into_owned();
+
+        if name.starts_with('@') {
+            for (inner, path) in installed_packages_in(&dir)? {
+                packages.push((format!("{}/{}", name, inner), path));
+            }
+        } else if !name.starts_with('.') {
+            packages.push((name, dir));
+        }
+    }
+
+    Ok(packages)
+}
+
+fn installed_packages_in(scope: &Path) -> Result<Vec<(String, PathBuf)>, String> {
+    let mut packages: Vec<(String, PathBuf)> = fs::read_dir(scope)
+        .map_err(|e| format!("{}: {}", scope.display(), e))?
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.is_dir())
+        .map(|p| (p.file_name().unwrap_or_default().to_string_lossy().into_owned(), p))
+        .collect();
+    packages.sort();
+    Ok(packages)
+}
+
+/// Reads an installed package's version from its `paw.json`.
+fn package_version(dir: &Path) -> Result<String, String> {
+    let manifest = dir.join(MANIFEST_FILE);
+    if manifest.is_file() {
+        Ok(Manifest::load(&manifest)?.version)
+    } else {
+        Err(format!("{} has no {}", dir.display(), MANIFEST_FILE))
+    }
+}
+
+/* ============================================================================
+ * Lockfile
+ * ============================================================================
+ */
+
+impl Lockfile {
+    /// Reads `root/paw.lock`, or returns `None` if there is none.
+    ///
+    /// # Errors
+    /// If the lockfile exists but cannot be parsed.
+    pub fn load(root: &Path) -> Result<Option<Lockfile>, String> {
+        let path = root.join(LOCK_FILE);
+        if !path.is_file() {
+            return Ok(None);
+        }
+
+        let text = fs::read_to_string(&path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        let lock: Lockfile =
+            serde_json::from_str(&text).map_err(|e| format!("{}: {}", path.display(), e))?;
+
+        if lock.lockfile_version > LOCKFILE_VERSION {
+            return Err(format!(
+                "{} uses lockfile version {}, this pawx supports up to {}",
+                path.display(),
+                lock.lockfile_version,
+                LOCKFILE_VERSION
+            ));
+        }
+
+        Ok(Some(lock))
+    }
+
+    /// Writes `root/paw.lock`.
+    ///
+    /// # Errors
+    /// If the file cannot be written.
+    pub fn save(&self, root: &Path) -> Result<(), String> {
+        let path = root.join(LOCK_FILE);
+        let text = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        fs::write(&path, text + "\n").map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    /// Builds a lockfile from what is installed under `root/paw_modules`.
+    ///
+    /// `resolved` URLs are carried over from `previous` for packages whose
+    /// version has not changed.
+    ///
+    /// # Errors
+    /// If a package cannot be read or has no `paw.json`.
+    pub fn generate(root: &Path, previous: Option<&Lockfile>) -> Result<Lockfile, String> {
+        let mut lock = Lockfile::default();
+
+        for (name, dir) in installed_packages(root)? {
+            let version = package_version(&dir)?;
+            let resolved = previous
+                .and_then(|p| p.packages.get(&name))
+                .filter(|p| p.version == version)
+                .and_then(|p| p.resolved.clone());
+
+            lock.packages.insert(
+                name,
+                LockedPackage {
+                    version,
+                    integrity: hash_dir(&dir)?,
+                    resolved,
+                },
+            );
+        }
+
+        Ok(lock)
+    }
+
+    /// Checks the one package `name`, installed at `dir`, against the
+    /// lockfile.
+    ///
+    /// # Errors
+    /// One message per problem: the package is not locked, or its version
+    /// or contents differ from what was locked.
+    pub fn verify_package(&self, name: &str, dir: &Path) -> Result<(), Vec<String>> {
+        let problems = match self.packages.get(name) {
+            Some(locked) => locked.problems(name, dir),
+            None => vec![format!("{} is installed but not in {}", name, LOCK_FILE)],
+        };
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(problems)
+        }
+    }
+
+    /// Checks the installed packages against the lockfile.
+    ///
+    /// # Errors
+    /// One message per problem: a dependency in `manifest` that is not
+    /// locked, a locked package that is missing or modified, or an
+    /// installed package that is not locked.
+    pub fn verify(&self, root: &Path, manifest: &Manifest) -> Result<(), Vec<String>> {
+        let mut problems = Vec::new();
+
+        for name in manifest.dependencies.keys() {
+            if !self.packages.contains_key(name) {
+                problems.push(format!("{} is a dependency but is not in {}", name, LOCK_FILE));
+            }
+        }
+
+        let installed: BTreeMap<String, PathBuf> = match installed_packages(root) {
+            Ok(packages) => packages.into_iter().collect(),
+            Err(e) => return Err(vec![e]),
+        };
+
+        for (name, locked) in &self.packages {
+            let Some(dir) = installed.get(name) else {
+                problems.push(format!("{}@{} is locked but not installed", name, locked.version));
+                continue;
+            };
+
+            problems.extend(locked.problems(name, dir));
+        }
+
+        for name in installed.keys() {
+            if !self.packages.contains_key(name) {
+                problems.push(format!("{} is installed but not in {}", name, LOCK_FILE));
+            }
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(problems)
+        }
+    }
+}
+
+impl LockedPackage {
+    /// What differs between this entry and the package installed at `dir`.
+    fn problems(&self, name: &str, dir: &Path) -> Vec<String> {
+        let mut problems = Vec::new();
+
+        match package_version(dir) {
+            Ok(version) if version != self.version => problems.push(format!(
+                "{} is installed at {} but locked at {}",
+                name, version, self.version
+            )),
+            Ok(_) => {}
+            Err(e) => problems.push(e),
+        }
+
+        match hash_dir(dir) {
+            Ok(hash) if hash != self.integrity => problems.push(format!(
+                "{}@{} does not match its integrity hash (modified since install?)",
+                name, self.version
+            )),
+            Ok(_) => {}
+            Err(e) => problems.push(e),
+        }
+
+        problems
+    }
+}
+
+/// Verifies the project containing `start` for `--frozen` runs.
+///
+/// A project without a manifest has nothing to verify.
+///
+/// # Errors
+/// If there is a manifest but no lockfile, or the lockfile does not match.
+pub fn verify_frozen(start: &Path) -> Result<(), Vec<String>> {
+    let (root, manifest) = match Manifest::find(start) {
+        Ok(Some(found)) => found,
+        Ok(None) => return Ok(()),
+        Err(e) => return Err(vec![e]),
+    };
+
+    match Lockfile::load(&root) {
+        Ok(Some(lock)) => lock.verify(&root, &manifest),
+        Ok(None) => Err(vec![format!("--frozen requires {} in {}", LOCK_FILE, root.display())]),
+        Err(e) => Err(vec![e]),
+    }
+}
@@ -34,16 +34,1462 @@ mod value;
 mod error;
 mod prototypes;
 mod span;
+mod manifest;
+mod scaffold;
+mod lockfile;
+mod registry;
+mod bench;
+mod ast_cache;
+mod diagnostics;
+mod explain;
+mod learn;
+mod highlight;
+mod grep;
+mod fix;
+mod repl;
 
+use std::cell::RefCell;
 use std::env;
 use std::fs;
+use std::io;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use diagnostics::{DiagnosticPrinter, ErrorFormat};
+use error::{ErrorCode, PawxError};
+use interpreter::conditional::Defines;
+use interpreter::prelude::{canonical_module_name, Prelude, BUILTIN_MODULES};
+use interpreter::profiler::Profiler;
+use interpreter::reporters::{self, Reporter, TestRecord};
+use lockfile::Lockfile;
+use manifest::Manifest;
+use prototypes::memfs::FsBackend;
+
+/// Options collected from the `pawx` command line.
+struct CliOptions {
+    /// Path to the PAWX script to execute.
+    script: String,
+
+    /// Globals installed before the script runs.
+    prelude: Prelude,
+
+    /// Refuse to run unless installed packages match `paw.lock`.
+    frozen: bool,
+
+    /// Always parse from source instead of using `.pawx-cache/`.
+    no_cache: bool,
+
+    /// Print statement and call counts when the script exits.
+    profile: bool,
+
+    /// How syntax and runtime errors are reported.
+    error_format: ErrorFormat,
+}
+
+/// Prints CLI usage information to stderr.
+fn print_usage() {
+    eprintln!("Usage: pawx [options] <file.px>");
+    eprintln!("       pawx new <api|cli|lib> <dir> [--version <v>] [--port <n>] [--force]");
+    eprintln!("       pawx run [--frozen] [task] [args...]");
+    eprintln!("       pawx test [--workspace] [--jobs <n>] [--filter <pattern>] [--reporter <format>:<file>] [--update-snapshots] [--sandbox-fs] [--deterministic] [files...]");
+    eprintln!("       pawx bench [--runs <n>] [--save <name>] [--compare <name>] [--threshold <pct>] [--json] <files...>");
+    eprintln!("       pawx install [pkg[@version]...] [--registry <url>] [--frozen]");
+    eprintln!("       pawx lock [--check]");
+    eprintln!("       pawx ssg [site.px] [--watch]");
+    eprintln!("       pawx repl");
+    eprintln!("       pawx explain [code]");
+    eprintln!("       pawx learn [list | reset | <lesson>]");
+    eprintln!("       pawx highlight <file.px> [--ansi|--html]");
+    eprintln!("       pawx highlight --grammar <textmate|tree-sitter|tree-sitter-highlights>");
+    eprintln!("       pawx grep '<pattern>' [paths...]");
+    eprintln!("       pawx fix [--check] [--list] [--rules <file>]... [paths...]");
+    eprintln!();
+    eprintln!("Options:");
+    eprintln!("  --no-default-globals       Do not install the standard global objects");
+    eprintln!("  --global <name>=<module>   Install a built-in module under a global name");
+    eprintln!("  --without <name>           Do not install the global <name>");
+    eprintln!("  --virtual-time             Run timers and Time.now() on a virtual clock");
+    eprintln!("  --deterministic            Seed Math.random, sort object keys and use virtual time");
+    eprintln!("  --seed <n>                 Seed for --deterministic (default 0; implies it)");
+    eprintln!("  --no-eval                  Make eval() and compile() throw instead of running code");
+    eprintln!("  --max-depth <n>            Deepest call stack or value nesting allowed (default 1000)");
+    eprintln!("  --frozen                   Fail if paw_modules does not match paw.lock");
+    eprintln!("  --define <name>[=<value>]  Set a name for `when` blocks (default value \"true\")");
+    eprintln!("  --no-cache                 Do not read or write parsed ASTs in .pawx-cache/");
+    eprintln!("  --profile                  Print the busiest lines and functions on exit");
+    eprintln!("  --error-format <format>    Report errors as 'human' (default) or 'json'");
+    eprintln!();
+    eprintln!("Modules: {}", BUILTIN_MODULES.join(", "));
+}
+
+/// Parses command-line arguments into [`CliOptions`].
+///
+/// # Returns
+/// - `Ok(CliOptions)` on success
+/// - `Err(String)` describing the invalid argument
+fn parse_args(args: &[String]) -> Result<CliOptions, String> {
+    let mut use_defaults = true;
+    let mut virtual_time = false;
+    let mut seed: Option<u64> = None;
+    let mut no_eval = false;
+    let mut max_depth: Option<usize> = None;
+    let mut frozen = false;
+    let mut no_cache = false;
+    let mut profile = false;
+    let mut error_format = ErrorFormat::Human;
+    let mut defines: Vec<(String, String)> = Vec::new();
+    let mut globals: Vec<(String, String)> = Vec::new();
+    let mut removed: Vec<String> = Vec::new();
+    let mut script: Option<String> = None;
+    let mut script_args: Vec<String> = Vec::new();
+
+    let mut iter = args.iter().skip(1);
+
+    while let Some(arg) = iter.next() {
+        if script.is_some() {
+            // Everything after the script path belongs to the script.
+            script_args.push(arg.clone());
+            continue;
+        }
+
+        match arg.as_str() {
+            "--no-default-globals" => use_defaults = false,
+
+            "--virtual-time" => virtual_time = true,
+
+            "--deterministic" => {
+                seed.get_or_insert(0);
+            }
+
+            "--seed" => {
+                let value = iter.next().ok_or_else(|| "--seed expects a number".to_string())?;
+                seed = Some(value.parse().map_err(|_| format!("Invalid --seed '{}'", value))?);
+            }
+
+            "--no-eval" => no_eval = true,
+
+            "--max-depth" => {
+                let value = iter.next().ok_or_else(|| "--max-depth expects a number".to_string())?;
+                max_depth = Some(value.parse().map_err(|_| format!("Invalid --max-depth '{}'", value))?);
+            }
+
+            "--frozen" => frozen = true,
+
+            "--no-cache" => no_cache = true,
+
+            "--profile" => profile = true,
+
+            "--error-format" => {
+                let name = iter
+                    .next()
+                    .ok_or_else(|| "--error-format expects human or json".to_string())?;
+                error_format = parse_error_format(name)?;
+            }
+
+            _ if arg.starts_with("--error-format=") => {
+                error_format = parse_error_format(&arg["--error-format=".len()..])?;
+            }
+
+            "--define" => {
+                let spec = iter
+                    .next()
+                    .ok_or_else(|| "--define expects <name>[=<value>]".to_string())?;
+                defines.push(parse_define_spec(spec)?);
+            }
+
+            _ if arg.starts_with("--define=") => {
+                defines.push(parse_define_spec(&arg["--define=".len()..])?);
+            }
+
+            "--global" => {
+                let spec = iter
+                    .next()
+                    .ok_or_else(|| "--global expects <name>=<module>".to_string())?;
+                globals.push(parse_global_spec(spec)?);
+            }
+
+            _ if arg.starts_with("--global=") => {
+                globals.push(parse_global_spec(&arg["--global=".len()..])?);
+            }
+
+            "--without" => {
+                let name = iter.next().ok_or_else(|| "--without expects a global name".to_string())?;
+                removed.push(name.clone());
+            }
+
+            _ if arg.starts_with("--without=") => removed.push(arg["--without=".len()..].to_string()),
+
+            _ if arg.starts_with("--") => {
+                return Err(format!("Unknown option '{}'", arg));
+            }
+
+            _ => script = Some(arg.clone()),
+        }
+    }
+
+    let script = script.ok_or_else(|| "No script file given".to_string())?;
+
+    let mut prelude = if use_defaults { Prelude::new() } else { Prelude::empty() };
+
+    for (name, module) in globals {
+        prelude = prelude.with(name, module);
+    }
+
+    for name in &removed {
+        prelude = prelude.without(name);
+    }
+
+    if virtual_time {
+        prelude = prelude.with_virtual_time();
+    }
+
+    if let Some(seed) = seed {
+        prelude = prelude.with_deterministic(seed);
+    }
+
+    if no_eval {
+        prelude = prelude.without_eval();
+    }
+
+    if let Some(depth) = max_depth {
+        prelude = prelude.with_max_depth(depth);
+    }
+
+    if frozen {
+        prelude = prelude.with_frozen();
+    }
+
+    let argv = std::iter::once(script.clone()).chain(script_args).collect();
+    prelude = prelude.with_argv(argv).with_defines(Defines::new(defines));
+
+    Ok(CliOptions {
+        script,
+        prelude,
+        frozen,
+        no_cache,
+        profile,
+        error_format,
+    })
+}
+
+/// Parses an `--error-format` value.
+fn parse_error_format(name: &str) -> Result<ErrorFormat, String> {
+    ErrorFormat::parse(name)
+        .ok_or_else(|| format!("Invalid --error-format '{}', expected human or json", name))
+}
+
+/// Parses a `<name>=<module>` global binding.
+fn parse_global_spec(spec: &str) -> Result<(String, String), String> {
+    let (name, module) = spec
+        .split_once('=')
+        .ok_or_else(|| format!("Invalid --global '{}', expected <name>=<module>", spec))?;
+
+    let name = name.trim();
+    let module = module.trim();
+
+    if name.is_empty() {
+        return Err(format!("Invalid --global '{}', global name is empty", spec));
+    }
+
+    let module = canonical_module_name(module).ok_or_else(|| {
+        format!(
+            "Unknown module '{}' in --global '{}' (available: {})",
+            module,
+            spec,
+            BUILTIN_MODULES.join(", ")
+        )
+    })?;
+
+    Ok((name.to_string(), module.to_string()))
+}
+
+/// Parses a `<name>[=<value>]` define; a bare name is set to `"true"`.
+fn parse_define_spec(spec: &str) -> Result<(String, String), String> {
+    let (name, value) = spec.split_once('=').unwrap_or((spec, "true"));
+    let name = name.trim();
+
+    if name.is_empty() {
+        return Err(format!("Invalid --define '{}', name is empty", spec));
+    }
+
+    Ok((name.to_string(), value.to_string()))
+}
+
+/* ============================================================================
+ * pawx new
+ * ============================================================================
+ */
+
+/// Runs `pawx new <template> <dir> [--version <v>] [--port <n>] [--force]`.
+fn run_new(args: &[String]) -> Result<(), String> {
+    let mut positional = Vec::new();
+    let mut version = None;
+    let mut port = None;
+    let mut force = false;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--force" => force = true,
+            "--version" => version = Some(iter.next().ok_or("--version expects a value")?.clone()),
+            "--port" => {
+                let value = iter.next().ok_or("--port expects a number")?;
+                port = Some(value.parse::<u16>().map_err(|_| format!("Invalid --port '{}'", value))?);
+            }
+            _ if arg.starts_with("--") => return Err(format!("Unknown new option '{}'", arg)),
+            _ => positional.push(arg.clone()),
+        }
+    }
+
+    let [template, dir] = positional.as_slice() else {
+        return Err(format!(
+            "expected a template and a directory (templates: {})",
+            scaffold::TEMPLATES.join(", ")
+        ));
+    };
+
+    let mut options = scaffold::ScaffoldOptions::new(template.clone(), dir);
+    options.force = force;
+    if let Some(version) = version {
+        options.version = version;
+    }
+    if let Some(port) = port {
+        options.port = port;
+    }
+
+    let written = scaffold::generate(&options)?;
+
+    println!("🐾 Created {} project '{}'", template, options.name());
+    for path in &written {
+        println!("  {}", scaffold::display_path(path, &options.dir));
+    }
+    println!();
+    println!("  cd {}", dir);
+    println!("  pawx run start");
+
+    Ok(())
+}
+
+/* ============================================================================
+ * pawx run
+ * ============================================================================
+ */
+
+/// Runs `pawx run [task] [args...]`.
+///
+/// Finds the nearest `paw.json`, changes into its directory and runs the
+/// task's script with `args` as `Process.argv`. Without a task, lists the
+/// tasks the manifest defines. A leading `--frozen` verifies `paw.lock`
+/// before the task runs.
+fn run_task(args: &[String]) -> Result<(), String> {
+    let (frozen, args) = match args.first().map(String::as_str) {
+        Some("--frozen") => (true, &args[1..]),
+        _ => (false, args),
+    };
+
+    let cwd = env::current_dir().map_err(|e| e.to_string())?;
+    let (root, manifest) = Manifest::find(&cwd)?
+        .ok_or_else(|| format!("no {} found in {} or its parents", manifest::MANIFEST_FILE, cwd.display()))?;
+
+    let Some(task) = args.first() else {
+        println!("Tasks in {} {}:", manifest.name, manifest.version);
+        if !manifest.tasks.contains_key("start") {
+            println!("  start  {}", manifest.entry);
+        }
+        for (name, script) in &manifest.tasks {
+            println!("  {}  {}", name, script);
+        }
+        if !manifest.dependencies.is_empty() {
+            println!("Dependencies:");
+            for (name, version) in &manifest.dependencies {
+                println!("  {}@{}", name, version);
+            }
+        }
+        return Ok(());
+    };
+
+    let script = manifest.task_script(task).ok_or_else(|| {
+        let known: Vec<&str> = manifest.tasks.keys().map(String::as_str).collect();
+        format!("unknown task '{}' (available: start, {})", task, known.join(", "))
+    })?;
+
+    if frozen {
+        check_frozen(&root);
+    }
+
+    env::set_current_dir(&root).map_err(|e| format!("{}: {}", root.display(), e))?;
+    let source = fs::read_to_string(script).map_err(|e| format!("{}: {}", script, e))?;
+
+    println!("🐾 {} {} › {} ({})", manifest.name, manifest.version, task, script);
+
+    let argv = std::iter::once(script.to_string()).chain(args[1..].iter().cloned()).collect();
+    // The `test` task runs its script's `test` blocks, like `pawx test`.
+    let prelude = Prelude::new().with_argv(argv);
+    let prelude = if task == "test" { prelude.with_tests() } else { prelude };
+    let prelude = if frozen { prelude.with_frozen() } else { prelude };
+    if !run(script, &source, &prelude, ErrorFormat::Human) {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/* ============================================================================
+ * pawx test
+ * ============================================================================
+ */
+
+/// Runs `pawx test [--workspace] [--jobs N] [--filter <pattern>] [--reporter <format>:<file>]
+/// [--update-snapshots] [--sandbox-fs] [files...]`.
+///
+/// Runs each file in test mode, so its `test` blocks execute after its
+/// top-level code. Without files, runs the project's `test` task script.
+/// `--filter` only runs tests whose name contains the pattern.
+/// `--reporter` (repeatable) writes a `junit`, `tap` or `json` report.
+/// `--update-snapshots` rewrites snapshots that no longer match.
+/// `--sandbox-fs` gives `Fs` an empty in-memory filesystem.
+/// `--deterministic` seeds `Math.random`, sorts object keys and uses
+/// virtual time, so output is byte-identical across runs.
+/// Several files (or `--workspace`, which covers the `test` task of every
+/// workspace member) each get their own `pawx` process, up to `--jobs` at a
+/// time (default: one per CPU), and a summary.
+fn run_test(args: &[String]) -> Result<(), String> {
+    let mut workspace = false;
+    let mut update_snapshots = false;
+    let mut sandbox_fs = false;
+    let mut deterministic = false;
+    let mut jobs = thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+    let mut filter = None;
+    let mut reporters = Vec::new();
+    let mut files = Vec::new();
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--workspace" => workspace = true,
+            "--jobs" => {
+                let value = iter.next().ok_or("--jobs expects a number")?;
+                jobs = value
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|&n| n > 0)
+                    .ok_or_else(|| format!("Invalid --jobs '{}'", value))?;
+            }
+            "--filter" => filter = Some(iter.next().ok_or("--filter expects a pattern")?.clone()),
+            "--reporter" => {
+                let spec = iter.next().ok_or("--reporter expects <format>:<file>")?;
+                reporters.push(Reporter::parse(spec)?);
+            }
+            "--update-snapshots" => update_snapshots = true,
+            "--sandbox-fs" => sandbox_fs = true,
+            "--deterministic" => deterministic = true,
+            _ if arg.starts_with("--") => return Err(format!("Unknown test option '{}'", arg)),
+            _ => files.push(arg.clone()),
+        }
+    }
+
+    let cwd = env::current_dir().map_err(|e| e.to_string())?;
+
+    // Flags forwarded to the `pawx test` processes spawned below.
+    let mut forwarded: Vec<String> = [
+        ("--update-snapshots", update_snapshots),
+        ("--sandbox-fs", sandbox_fs),
+        ("--deterministic", deterministic),
+    ]
+    .iter()
+    .filter(|(_, on)| *on)
+    .map(|(flag, _)| flag.to_string())
+    .collect();
+    if let Some(pattern) = &filter {
+        forwarded.extend(["--filter".to_string(), pattern.clone()]);
+    }
+
+    if workspace {
+        let (root, manifest) = Manifest::find_workspace(&cwd)?
+            .ok_or_else(|| format!("no workspace {} found above {}", manifest::MANIFEST_FILE, cwd.display()))?;
+
+        let members = manifest
+            .workspace_members(&root)?
+            .into_iter()
+            .filter(|(_, member)| member.task_script("test").is_some())
+            .map(|(dir, member)| {
+                let label = format!("{} ({})", member.name, scaffold::display_path(&dir, &root));
+                (label, dir, forwarded.clone())
+            })
+            .collect();
+        return run_test_processes(members, jobs, &reporters);
+    }
+
+    if files.is_empty() {
+        let (root, manifest) = Manifest::find(&cwd)?
+            .ok_or_else(|| format!("no test files given and no {} found", manifest::MANIFEST_FILE))?;
+        let script = manifest
+            .task_script("test")
+            .ok_or_else(|| format!("{} has no \"test\" task", manifest::MANIFEST_FILE))?;
+
+        env::set_current_dir(&root).map_err(|e| format!("{}: {}", root.display(), e))?;
+        files.push(script.to_string());
+    }
+
+    if let [file] = files.as_slice() {
+        let source = fs::read_to_string(file).map_err(|e| format!("{}: {}", file, e))?;
+        println!("🧪 {}", file);
+
+        let mut prelude = Prelude::new().with_argv(vec![file.clone()]).with_tests();
+        if update_snapshots {
+            prelude = prelude.with_snapshot_updates();
+        }
+        if sandbox_fs {
+            prelude = prelude.with_memory_fs();
+        }
+        if deterministic {
+            prelude = prelude.with_deterministic(0);
+        }
+        if let Some(pattern) = filter {
+            prelude = prelude.with_test_filter(pattern);
+        }
+        for reporter in reporters {
+            prelude = prelude.with_reporter(reporter);
+        }
+        if !run(file, &source, &prelude, ErrorFormat::Human) {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    let runs = files
+        .into_iter()
+        .map(|f| {
+            let mut args = forwarded.clone();
+            args.push(f.clone());
+            (f, cwd.clone(), args)
+        })
+        .collect();
+    run_test_processes(runs, jobs, &reporters)
+}
+
+/// Output and outcome of one `pawx test` process.
+struct TestRun {
+    index: usize,
+    label: String,
+    output: String,
+    passed: bool,
+    counts: TestCounts,
+}
+
+/// Passed, failed and skipped tests.
+#[derive(Default, Clone, Copy)]
+struct TestCounts {
+    passed: usize,
+    failed: usize,
+    skipped: usize,
+}
+
+/// Reads the `N passed[, M failed][, K skipped]` line a test process prints.
+fn parse_test_counts(output: &str) -> TestCounts {
+    let parse = |line: &str| {
+        let mut counts = TestCounts::default();
+        for (i, part) in line.trim().split(", ").enumerate() {
+            let (n, what) = part.split_once(' ')?;
+            let n = n.parse().ok()?;
+            match what {
+                "passed" if i == 0 => counts.passed = n,
+                "failed" if i > 0 => counts.failed = n,
+                "skipped" if i > 0 => counts.skipped = n,
+                _ => return None,
+            }
+        }
+        Some(counts)
+    };
+
+    output.lines().rev().find_map(parse).unwrap_or_default()
+}
+
+/// Runs `pawx test <args>` in each `(label, dir, args)`, up to `jobs` at a
+/// time, and summarizes.
+///
+/// Each process has its own interpreter, so files cannot see each other's
+/// globals, mocks or snapshots. Output is captured and printed per file as
+/// each one finishes, so concurrent files never interleave. For
+/// `reporters`, each process writes a `json` report that is merged here.
+fn run_test_processes(
+    runs: Vec<(String, PathBuf, Vec<String>)>,
+    jobs: usize,
+    reporters: &[Reporter],
+) -> Result<(), String> {
+    let exe = env::current_exe().map_err(|e| e.to_string())?;
+    let total = runs.len();
+
+    let disk = FsBackend::Disk;
+    let report_dir = match reporters {
+        [] => None,
+        _ => Some(disk.temp_dir("pawx-reports").map_err(|e| e.to_string())?),
+    };
+    let report_path = |index: usize| report_dir.as_ref().map(|dir| format!("{}/{}.json", dir, index));
+
+    let queue = std::sync::Mutex::new(runs.into_iter().enumerate());
+    let (done, results) = std::sync::mpsc::channel::<Result<TestRun, String>>();
+
+    let mut failed = Vec::new();
+    let mut labels = vec![String::new(); total];
+    let mut counts = TestCounts::default();
+
+    thread::scope(|scope| {
+        for _ in 0..jobs.min(total) {
+            let (queue, done, exe, report_path) = (&queue, done.clone(), &exe, &report_path);
+            scope.spawn(move || loop {
+                let Some((index, (label, dir, mut args))) = queue.lock().unwrap().next() else {
+                    break;
+                };
+                if let Some(path) = report_path(index) {
+                    args.splice(0..0, ["--reporter".to_string(), format!("json:{}", path)]);
+                }
+
+                let run = std::process::Command::new(exe)
+                    .arg("test")
+                    .args(&args)
+                    .current_dir(&dir)
+                    .output()
+                    .map_err(|e| format!("{}: {}", exe.display(), e))
+                    .map(|out| {
+                        let mut output = String::from_utf8_lossy(&out.stdout).into_owned();
+                        output.push_str(&String::from_utf8_lossy(&out.stderr));
+                        TestRun {
+                            index,
+                            label,
+                            counts: parse_test_counts(&output),
+                            output,
+                            passed: out.status.success(),
+                        }
+                    });
+
+                if done.send(run).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(done);
+
+        for run in results {
+            let run = run?;
+            println!("━━ {}", run.label);
+            print!("{}", run.output);
+            println!();
+
+            counts.passed += run.counts.passed;
+            counts.failed += run.counts.failed;
+            counts.skipped += run.counts.skipped;
+            labels[run.index] = run.label.clone();
+            if !run.passed {
+                failed.push(run.label);
+            }
+        }
+        Ok::<(), String>(())
+    })?;
+
+    if let Some(dir) = &report_dir {
+        // A file that crashed before its tests ran has no report.
+        let mut records = Vec::new();
+        for (index, label) in labels.iter().enumerate() {
+            if let Some(Ok(file_records)) = report_path(index).map(|p| reporters::read_json(&p)) {
+                records.extend(file_records.into_iter().map(|r| TestRecord { suite: label.clone(), ..r }));
+            }
+        }
+        let _ = disk.remove(dir, true);
+
+        for reporter in reporters {
+            reporter.write(&records)?;
+        }
+    }
+
+    let mut summary = format!("{} tests passed", counts.passed);
+    if counts.failed > 0 {
+        summary.push_str(&format!(", {} failed", counts.failed));
+    }
+    if counts.skipped > 0 {
+        summary.push_str(&format!(", {} skipped", counts.skipped));
+    }
+
+    if failed.is_empty() {
+        println!("✅ {} of {} files passed ({})", total, total, summary);
+        Ok(())
+    } else {
+        Err(format!("{} of {} files failed ({}): {}", failed.len(), total, summary, failed.join(", ")))
+    }
+}
+
+/* ============================================================================
+ * pawx bench
+ * ============================================================================
+ */
+
+/// Runs `pawx bench [--runs N] [--warmup N] [--save <name>] [--compare <name>]
+/// [--threshold <pct>] [--json] <files...>`.
+///
+/// Times `--runs` whole runs of each script (after `--warmup` untimed runs),
+/// each in a fresh `pawx` process with its output discarded. `--save`
+/// stores the samples as a named baseline; `--compare` checks them against
+/// one and fails if any benchmark regressed (see the `bench` module).
+fn run_bench(args: &[String]) -> Result<(), String> {
+    let mut runs = 10;
+    let mut warmup = 1;
+    let mut save = None;
+    let mut compare_to = None;
+    let mut threshold = 5.0;
+    let mut as_json = false;
+    let mut files = Vec::new();
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--runs" | "--warmup" => {
+                let value = iter.next().ok_or_else(|| format!("{} expects a number", arg))?;
+                let n = value.parse::<usize>().map_err(|_| format!("Invalid {} '{}'", arg, value))?;
+                if arg == "--runs" {
+                    runs = n.max(1);
+                } else {
+                    warmup = n;
+                }
+            }
+            "--save" => save = Some(iter.next().ok_or("--save expects a baseline name")?.clone()),
+            "--compare" => compare_to = Some(iter.next().ok_or("--compare expects a baseline name")?.clone()),
+            "--threshold" => {
+                let value = iter.next().ok_or("--threshold expects a percentage")?;
+                threshold = value
+                    .trim_end_matches('%')
+                    .parse::<f64>()
+                    .map_err(|_| format!("Invalid --threshold '{}'", value))?;
+            }
+            "--json" => as_json = true,
+            _ if arg.starts_with("--") => return Err(format!("Unknown bench option '{}'", arg)),
+            _ => files.push(arg.clone()),
+        }
+    }
+
+    if files.is_empty() {
+        return Err("no benchmark files given".to_string());
+    }
+
+    let cwd = env::current_dir().map_err(|e| e.to_string())?;
+    let root = Manifest::find(&cwd)?.map(|(root, _)| root).unwrap_or(cwd);
+    let baseline = compare_to
+        .as_ref()
+        .map(|name| bench::Baseline::load(&bench::baseline_path(&root, name)))
+        .transpose()?;
+
+    let exe = env::current_exe().map_err(|e| e.to_string())?;
+    let time_once = |file: &str| -> Result<f64, String> {
+        let started = std::time::Instant::now();
+        let status = std::process::Command::new(&exe)
+            .arg(file)
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .status()
+            .map_err(|e| format!("{}: {}", exe.display(), e))?;
+        let elapsed = started.elapsed().as_secs_f64() * 1000.0;
+
+        if !status.success() {
+            return Err(format!("{} failed ({})", file, status));
+        }
+        Ok(elapsed)
+    };
+
+    let mut results = Vec::new();
+    for file in &files {
+        if !as_json {
+            println!("⏱  {} ({} runs)", file, runs);
+        }
+        for _ in 0..warmup {
+            time_once(file)?;
+        }
+        let samples = (0..runs).map(|_| time_once(file)).collect::<Result<Vec<_>, _>>()?;
+        results.push((file.clone(), samples));
+    }
+
+    let comparisons: Vec<_> = results
+        .iter()
+        .map(|(name, samples)| {
+            let saved = baseline.as_ref().and_then(|b| b.samples(name));
+            bench::compare(name, samples, saved, threshold)
+        })
+        .collect();
+
+    if as_json {
+        println!("{}", bench::to_json(&comparisons));
+    } else {
+        println!();
+        for c in &comparisons {
+            let s = &c.stats;
+            println!(
+                "{}: {:.2} ms ± {:.2} (median {:.2}, min {:.2}, max {:.2})",
+                c.name, s.mean, s.stddev, s.median, s.min, s.max
+            );
+            if let (Some(base), Some(change)) = (&c.baseline, c.change_pct) {
+                let p = c.p_value.map(|p| format!(", p = {:.3}", p)).unwrap_or_default();
+                println!("    vs {:.2} ms: {:+.1}%{} — {}", base.mean, change, p, c.verdict.as_str());
+            }
+        }
+    }
+
+    if let Some(name) = &save {
+        let path = bench::baseline_path(&root, name);
+        bench::Baseline::from_samples(&results).save(&path)?;
+        if !as_json {
+            println!("💾 saved baseline '{}' ({})", name, scaffold::display_path(&path, &root));
+        }
+    }
+
+    let regressed: Vec<_> = comparisons
+        .iter()
+        .filter(|c| c.verdict == bench::Verdict::Regression)
+        .map(|c| c.name.as_str())
+        .collect();
+    match regressed.as_slice() {
+        [] => Ok(()),
+        names => Err(format!(
+            "{} of {} benchmarks regressed by more than {}%: {}",
+            names.len(),
+            comparisons.len(),
+            threshold,
+            names.join(", ")
+        )),
+    }
+}
+
+/* ============================================================================
+ * pawx lock
+ * ============================================================================
+ */
+
+/// Exits with a report if the project at `start` fails `--frozen` checks.
+fn check_frozen(start: &Path) {
+    if let Err(problems) = lockfile::verify_frozen(start) {
+        eprintln!("pawx: --frozen: installed packages do not match {}", lockfile::LOCK_FILE);
+        for problem in problems {
+            eprintln!("  • {}", problem);
+        }
+        std::process::exit(1);
+    }
+}
+
+/// Runs `pawx lock [--check]`.
+///
+/// Writes `paw.lock` for the packages installed under `paw_modules`, or
+/// with `--check` verifies them against the existing lockfile.
+fn run_lock(args: &[String]) -> Result<(), String> {
+    let mut check = false;
+    for arg in args {
+        match arg.as_str() {
+            "--check" => check = true,
+            _ => return Err(format!("Unknown lock option '{}'", arg)),
+        }
+    }
+
+    let cwd = env::current_dir().map_err(|e| e.to_string())?;
+    let (root, manifest) = Manifest::find(&cwd)?
+        .ok_or_else(|| format!("no {} found in {} or its parents", manifest::MANIFEST_FILE, cwd.display()))?;
+
+    let previous = Lockfile::load(&root)?;
+
+    if check {
+        let lock = previous.ok_or_else(|| format!("no {} in {}", lockfile::LOCK_FILE, root.display()))?;
+        return match lock.verify(&root, &manifest) {
+            Ok(()) => {
+                println!("🔒 {} packages match {}", lock.packages.len(), lockfile::LOCK_FILE);
+                Ok(())
+            }
+            Err(problems) => Err(format!(
+                "installed packages do not match {}:\n  • {}",
+                lockfile::LOCK_FILE,
+                problems.join("\n  • ")
+            )),
+        };
+    }
+
+    let lock = Lockfile::generate(&root, previous.as_ref())?;
+    lock.save(&root)?;
+    println!("🔒 Locked {} packages in {}", lock.packages.len(), lockfile::LOCK_FILE);
+
+    Ok(())
+}
+
+/* ============================================================================
+ * pawx install
+ * ============================================================================
+ */
+
+/// Runs `pawx install [pkg[@version]...] [--registry <url>] [--frozen]`.
+///
+/// With packages, installs each one and records it in `paw.json`. Without,
+/// installs every dependency `paw.json` lists, pinned to `paw.lock` when it
+/// has them. `--frozen` installs exactly what the lockfile records and
+/// fails if it is missing or out of date. Either way `paw.lock` is then
+/// rewritten (except in `--frozen` mode).
+fn run_install(args: &[String]) -> Result<(), String> {
+    let mut specs = Vec::new();
+    let mut registry_flag = None;
+    let mut frozen = false;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--frozen" => frozen = true,
+            "--registry" => registry_flag = Some(iter.next().ok_or("--registry expects a URL")?.clone()),
+            _ if arg.starts_with("--registry=") => registry_flag = Some(arg["--registry=".len()..].to_string()),
+            _ if arg.starts_with("--") => return Err(format!("Unknown install option '{}'", arg)),
+            _ => specs.push(registry::PackageSpec::parse(arg)?),
+        }
+    }
+
+    let cwd = env::current_dir().map_err(|e| e.to_string())?;
+    let (root, manifest) = Manifest::find(&cwd)?
+        .ok_or_else(|| format!("no {} found in {} or its parents", manifest::MANIFEST_FILE, cwd.display()))?;
+    let registry = registry::registry_url(registry_flag.as_deref());
+    let lock = Lockfile::load(&root)?;
+
+    if frozen && !specs.is_empty() {
+        return Err("--frozen installs from paw.lock and takes no packages".to_string());
+    }
+
+    let record = !specs.is_empty();
+
+    if specs.is_empty() {
+        for (name, version) in &manifest.dependencies {
+            let locked = lock.as_ref().and_then(|l| l.packages.get(name));
+            let pinned = match (locked, frozen) {
+                (Some(locked), _) => Some(locked.version.as_str()),
+                (None, true) => {
+                    return Err(format!("{} is a dependency but is not in {}", name, lockfile::LOCK_FILE))
+                }
+                (None, false) => Some(version.as_str()),
+            };
+            specs.push(registry::PackageSpec::new(name, pinned)?);
+        }
+    }
+
+    let mut installed = Vec::new();
+
+    for spec in &specs {
+        let resolved = registry::resolve(&registry, spec)?;
+        let locked = lock.as_ref().and_then(|l| l.packages.get(&resolved.name));
+        let package = registry::install(&root, &resolved, locked)?;
+
+        println!("📦 {}@{}", resolved.name, package.version);
+
+        if record {
+            Manifest::set_dependency(&root.join(manifest::MANIFEST_FILE), &resolved.name, &package.version)?;
+        }
+        installed.push((resolved.name, package));
+    }
+
+    if frozen {
+        let manifest = Manifest::load(&root.join(manifest::MANIFEST_FILE))?;
+        lock.as_ref()
+            .ok_or_else(|| format!("--frozen requires {}", lockfile::LOCK_FILE))?
+            .verify(&root, &manifest)
+            .map_err(|problems| problems.join("; "))?;
+    } else {
+        let mut updated = Lockfile::generate(&root, lock.as_ref())?;
+        for (name, package) in installed {
+            updated.packages.insert(name, package);
+        }
+        updated.save(&root)?;
+    }
+
+    println!("🔒 {} packages installed", specs.len());
+
+    Ok(())
+}
+
+/* ============================================================================
+ * pawx ssg
+ * ============================================================================
+ */
+
+/// Modification times of every file a site build depends on.
+fn site_snapshot(config: &Path) -> Vec<(PathBuf, Option<SystemTime>)> {
+    let mut files = vec![config.to_path_buf()];
+    for root in prototypes::site::watch_roots() {
+        files.extend(prototypes::site::walk_files(&root));
+    }
+
+    files
+        .into_iter()
+        .map(|f| {
+            let modified = fs::metadata(&f).and_then(|m| m.modified()).ok();
+            (f, modified)
+        })
+        .collect()
+}
+
+/// Runs `pawx ssg [site.px] [--watch]`.
+///
+/// The config script runs from its own directory, so the paths it passes
+/// to `Site.build` are relative to it. With `--watch` the script re-runs
+/// whenever it or any file under the content, layout or asset directories
+/// changes; a failing build is reported and the watcher keeps going.
+fn run_ssg(args: &[String]) -> Result<(), String> {
+    let mut watch = false;
+    let mut config = "site.px".to_string();
+
+    for arg in args {
+        match arg.as_str() {
+            "--watch" => watch = true,
+            _ if arg.starts_with("--") => return Err(format!("Unknown ssg option '{}'", arg)),
+            _ => config = arg.clone(),
+        }
+    }
+
+    let config = Path::new(&config);
+    if let Some(dir) = config.parent().filter(|d| !d.as_os_str().is_empty()) {
+        env::set_current_dir(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+    }
+    let config = PathBuf::from(config.file_name().ok_or("ssg expects a config script")?);
+
+    let build = || -> Result<(), String> {
+        let source = fs::read_to_string(&config).map_err(|e| format!("{}: {}", config.display(), e))?;
+        if run(&config.display().to_string(), &source, &Prelude::new(), ErrorFormat::Human) {
+            Ok(())
+        } else {
+            Err("the config script failed".to_string())
+        }
+    };
+
+    if !watch {
+        return build();
+    }
+
+    loop {
+        // A panic's message has already been printed by the default hook.
+        if let Ok(Err(message)) = panic::catch_unwind(AssertUnwindSafe(&build)) {
+            eprintln!("pawx ssg: {}", message);
+        }
+
+        let snapshot = site_snapshot(&config);
+        println!("👀 Watching for changes (Ctrl+C to stop)");
+
+        while site_snapshot(&config) == snapshot {
+            thread::sleep(Duration::from_millis(300));
+        }
+    }
+}
+
+/* ============================================================================
+ * pawx repl
+ * ============================================================================
+ */
+
+/// Runs `pawx repl`: an interactive session with the standard globals.
+fn run_repl(args: &[String]) -> Result<(), String> {
+    if let Some(arg) = args.first() {
+        return Err(format!("Unexpected argument '{}'", arg));
+    }
+
+    let prelude = Prelude::new().with_argv(vec!["<repl>".to_string()]);
+    repl::run(&prelude);
+    Ok(())
+}
+
+/* ============================================================================
+ * pawx explain
+ * ============================================================================
+ */
+
+/// Runs `pawx explain [code]`.
+///
+/// Prints the extended description of an error code, or with no code, a
+/// one-line summary of every code.
+fn run_explain(args: &[String]) -> Result<(), String> {
+    let Some(query) = args.first() else {
+        for code in explain::ALL {
+            let summary = explain::explanation(code).lines().next().unwrap_or("");
+            println!("{}  {:<24} {}", code, code.name(), summary);
+        }
+        return Ok(());
+    };
+
+    let code = explain::lookup(query)
+        .ok_or_else(|| format!("'{}' is not a PAWX error code (run `pawx explain` for a list)", query))?;
+
+    println!("{} {}\n", code, code.name());
+    print!("{}", explain::explanation(code));
+    Ok(())
+}
+
+/* ============================================================================
+ * pawx highlight
+ * ============================================================================
+ */
+
+/// Runs `pawx highlight <file.px> [--ansi | --html]` or
+/// `pawx highlight --grammar <textmate | tree-sitter | tree-sitter-highlights>`.
+///
+/// Output goes to stdout; ANSI is the default format.
+fn run_highlight(args: &[String]) -> Result<(), String> {
+    let mut html = false;
+    let mut file: Option<&String> = None;
+    let mut iter = args.iter();
+
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--ansi" => html = false,
+            "--html" => html = true,
+            "--grammar" => {
+                let kind = iter.next().ok_or("--grammar expects textmate, tree-sitter or tree-sitter-highlights")?;
+                let grammar = match kind.as_str() {
+                    "textmate" => {
+                        serde_json::to_string_pretty(&highlight::textmate_grammar()).map_err(|e| e.to_string())? + "\n"
+                    }
+                    "tree-sitter" => highlight::tree_sitter_grammar(),
+                    "tree-sitter-highlights" => highlight::tree_sitter_highlights(),
+                    other => return Err(format!("Unknown grammar '{}'", other)),
+                };
+                print!("{}", grammar);
+                return Ok(());
+            }
+            _ if arg.starts_with("--") => return Err(format!("Unknown highlight option '{}'", arg)),
+            _ => file = Some(arg),
+        }
+    }
+
+    let file = file.ok_or("highlight expects a file")?;
+    let source = fs::read_to_string(file).map_err(|e| format!("{}: {}", file, e))?;
+
+    let rendered = if html { highlight::to_html(&source) } else { highlight::to_ansi(&source) };
+    print!("{}", rendered.map_err(|e| format!("{}: {}", file, e))?);
+    Ok(())
+}
+
+/* ============================================================================
+ * pawx grep
+ * ============================================================================
+ */
+
+/// Runs `pawx grep '<pattern>' [paths...]`, printing `file:line:column:` and
+/// the first line of each match. Paths default to the current directory.
+///
+/// Returns whether anything matched; like `grep`, the exit status is 1 when
+/// nothing did. Files that fail to parse are reported and skipped.
+fn run_grep(args: &[String]) -> Result<bool, String> {
+    let (pattern, paths) = args.split_first().ok_or("grep expects a pattern")?;
+    let pattern = grep::Pattern::parse(pattern).map_err(|e| format!("invalid pattern: {}", e.message))?;
+
+    let paths: Vec<PathBuf> = if paths.is_empty() {
+        vec![PathBuf::from(".")]
+    } else {
+        paths.iter().map(PathBuf::from).collect()
+    };
+
+    let mut matched = false;
+    for file in grep::source_files(&paths) {
+        let name = file.strip_prefix(".").unwrap_or(&file).display().to_string();
+        let source = match fs::read_to_string(&file) {
+            Ok(source) => source,
+            Err(e) => {
+                eprintln!("pawx grep: {}: {}", name, e);
+                continue;
+            }
+        };
+
+        match pattern.find(&source) {
+            Ok(spans) => {
+                for span in spans {
+                    let line = source.lines().nth(span.line.saturating_sub(1)).unwrap_or("");
+                    println!("{}:{}:{}: {}", name, span.line, span.column + 1, line.trim());
+                    matched = true;
+                }
+            }
+            Err(e) => eprintln!("pawx grep: {}:{}: {}", name, e.span.line, e.message),
+        }
+    }
+
+    Ok(matched)
+}
+
+/* ============================================================================
+ * pawx fix
+ * ============================================================================
+ */
+
+/// Runs `pawx fix [--check] [--list] [--rules <file>]... [paths...]`.
+///
+/// Applies the built-in fixes, those shipped by installed packages and any
+/// `--rules` files to every `.px` file under `paths` (default: the current
+/// directory), printing `file:line:column: rule` for each change.
+///
+/// With `--check` nothing is written and the result is `false` when a file
+/// would change, for CI; `--list` prints the rules and exits. Files that do
+/// not parse, or that a rule would break, are reported and left alone.
+fn run_fix(args: &[String]) -> Result<bool, String> {
+    let mut check = false;
+    let mut list = false;
+    let mut rule_files = Vec::new();
+    let mut paths = Vec::new();
+    let mut iter = args.iter();
+
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--check" => check = true,
+            "--list" => list = true,
+            "--rules" => rule_files.push(iter.next().ok_or("--rules expects a file")?),
+            _ if arg.starts_with("--") => return Err(format!("Unknown fix option '{}'", arg)),
+            _ => paths.push(PathBuf::from(arg)),
+        }
+    }
+
+    let cwd = env::current_dir().map_err(|e| e.to_string())?;
+    let root = Manifest::find(&cwd)?.map(|(dir, _)| dir).unwrap_or(cwd);
+
+    let mut rules = fix::builtin_rules();
+    rules.extend(fix::package_rules(&root)?);
+    for file in rule_files {
+        rules.extend(fix::load_rules(Path::new(file))?);
+    }
+
+    if list {
+        for rule in &rules {
+            println!("{}  {}", rule.name, rule.description);
+        }
+        return Ok(true);
+    }
+
+    if rules.is_empty() {
+        eprintln!("pawx fix: no rules to apply");
+        return Ok(true);
+    }
+
+    if paths.is_empty() {
+        paths.push(PathBuf::from("."));
+    }
+
+    let mut changed = 0;
+    let mut failed = 0;
+    for file in grep::source_files(&paths) {
+        let name = file.strip_prefix(".").unwrap_or(&file).display().to_string();
+        let source = fs::read_to_string(&file).map_err(|e| format!("{}: {}", name, e))?;
+
+        let (fixed, edits) = match fix::apply(&rules, &source) {
+            Ok(result) => result,
+            Err(e) => {
+                eprintln!("pawx fix: {}:{}: {}", name, e.span.line, e.message);
+                failed += 1;
+                continue;
+            }
+        };
+
+        for edit in &edits {
+            println!("{}:{}:{}: {}", name, edit.span.line, edit.span.column + 1, edit.rule);
+        }
+
+        if fixed != source {
+            changed += 1;
+            if !check {
+                fs::write(&file, fixed).map_err(|e| format!("{}: {}", name, e))?;
+            }
+        }
+    }
+
+    if failed > 0 {
+        return Err(format!("{} file(s) could not be fixed", failed));
+    }
+
+    if check && changed > 0 {
+        eprintln!("pawx fix: {} file(s) need fixing", changed);
+        return Ok(false);
+    }
+
+    Ok(true)
+}
+
+/* ============================================================================
+ * pawx learn
+ * ============================================================================
+ */
+
+/// Runs `pawx learn [list | reset | <lesson>]`.
+///
+/// With no argument the tutorial resumes at the first unfinished lesson;
+/// `<lesson>` is a lesson number or id.
+fn run_learn(args: &[String]) -> Result<(), String> {
+    let dir = env::current_dir().map_err(|e| e.to_string())?;
+    let mut progress = learn::Progress::load(&dir)?;
+
+    let start = match args.first().map(String::as_str) {
+        Some("list") => {
+            learn::list(&progress);
+            return Ok(());
+        }
+        Some("reset") => {
+            learn::Progress::default().save(&dir)?;
+            println!("Progress reset.");
+            return Ok(());
+        }
+        Some(query) => learn::find_lesson(query)
+            .ok_or_else(|| format!("no lesson '{}' (run `pawx learn list`)", query))?,
+        None => match progress.next_lesson() {
+            Some(next) => next,
+            None => {
+                println!("🎉 You have finished every lesson. Run `pawx learn <n>` to revisit one.");
+                return Ok(());
+            }
+        },
+    };
+
+    learn::session(start, &mut progress, &dir, &mut io::stdin().lock())
+}
 
 fn main() {
+    // PAWX calls recurse on the Rust stack; give them room for the deepest
+    // call stack `--max-depth` allows.
+    let cli = thread::Builder::new()
+        .name("pawx".to_string())
+        .stack_size(interpreter::limits::STACK_SIZE)
+        .spawn(run_cli)
+        .expect("Failed to start the interpreter thread");
+
+    if cli.join().is_err() {
+        std::process::exit(101);
+    }
+}
+
+fn run_cli() {
     let args: Vec<String> = env::args().collect();
+    install_panic_hook();
 
-    if args.len() < 2 {
-        eprintln!("Usage: pawx <file.px>");
-        std::process::exit(1);
+    if args.get(1).map(String::as_str) == Some("new") {
+        if let Err(message) = run_new(&args[2..]) {
+            eprintln!("pawx new: {}", message);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    if args.get(1).map(String::as_str) == Some("run") {
+        if let Err(message) = run_task(&args[2..]) {
+            eprintln!("pawx run: {}", message);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    if args.get(1).map(String::as_str) == Some("test") {
+        if let Err(message) = run_test(&args[2..]) {
+            eprintln!("pawx test: {}", message);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    if args.get(1).map(String::as_str) == Some("bench") {
+        if let Err(message) = run_bench(&args[2..]) {
+            eprintln!("pawx bench: {}", message);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    if args.get(1).map(String::as_str) == Some("install") {
+        if let Err(message) = run_install(&args[2..]) {
+            eprintln!("pawx install: {}", message);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    if args.get(1).map(String::as_str) == Some("lock") {
+        if let Err(message) = run_lock(&args[2..]) {
+            eprintln!("pawx lock: {}", message);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    if args.get(1).map(String::as_str) == Some("ssg") {
+        if let Err(message) = run_ssg(&args[2..]) {
+            eprintln!("pawx ssg: {}", message);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    if args.get(1).map(String::as_str) == Some("highlight") {
+        if let Err(message) = run_highlight(&args[2..]) {
+            eprintln!("pawx highlight: {}", message);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    if args.get(1).map(String::as_str) == Some("grep") {
+        match run_grep(&args[2..]) {
+            Ok(true) => {}
+            Ok(false) => std::process::exit(1),
+            Err(message) => {
+                eprintln!("pawx grep: {}", message);
+                std::process::exit(2);
+            }
+        }
+        return;
+    }
+
+    if args.get(1).map(String::as_str) == Some("fix") {
+        match run_fix(&args[2..]) {
+            Ok(true) => {}
+            Ok(false) => std::process::exit(1),
+            Err(message) => {
+                eprintln!("pawx fix: {}", message);
+                std::process::exit(2);
+            }
+        }
+        return;
+    }
+
+    if args.get(1).map(String::as_str) == Some("learn") {
+        if let Err(message) = run_learn(&args[2..]) {
+            eprintln!("pawx learn: {}", message);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    if args.get(1).map(String::as_str) == Some("repl") {
+        if let Err(message) = run_repl(&args[2..]) {
+            eprintln!("pawx repl: {}", message);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    if args.get(1).map(String::as_str) == Some("explain") {
+        if let Err(message) = run_explain(&args[2..]) {
+            eprintln!("pawx explain: {}", message);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    let options = match parse_args(&args) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("pawx: {}", message);
+            eprintln!();
+            print_usage();
+            std::process::exit(1);
+        }
+    };
+
+    if options.no_cache {
+        ast_cache::disable();
+    }
+
+    if options.frozen {
+        let script_dir = fs::canonicalize(&options.script)
+            .ok()
+            .and_then(|p| p.parent().map(Path::to_path_buf))
+            .unwrap_or_else(|| PathBuf::from("."));
+        check_frozen(&script_dir);
     }
 
     let banner = r#"
@@ -59,17 +1505,88 @@ fn main() {
     println!("{banner}");
     println!("VERSION -> {}", env!("CARGO_PKG_VERSION"));
     println!("AUTHOR -> Sam Wilcox");
-    println!("RUNNING -> {}", &args[1]);
+    println!("RUNNING -> {}", &options.script);
     println!();
 
-    let source = fs::read_to_string(&args[1])
+    let source = fs::read_to_string(&options.script)
         .expect("Failed to read Pawx source file");
 
-    run(&source);
+    let profiler = options.profile.then(|| Rc::new(Profiler::default()));
+    let prelude = match &profiler {
+        Some(profiler) => options.prelude.with_hooks(profiler.clone()),
+        None => options.prelude,
+    };
+
+    let completed = run(&options.script, &source, &prelude, options.error_format);
+
+    if let Some(profiler) = profiler {
+        profiler.report();
+    }
+    if !completed {
+        std::process::exit(1);
+    }
+}
+
+thread_local! {
+    /// File name, source and error format of the script `run` is
+    /// executing, for the panic hook.
+    static RUNNING: RefCell<Option<(String, String, ErrorFormat)>> = const { RefCell::new(None) };
+}
+
+/// Parses and runs `source`, printing any syntax or runtime error as a
+/// diagnostic against `file`.
+///
+/// Returns whether the program ran to completion.
+fn run(file: &str, source: &str, prelude: &Prelude, format: ErrorFormat) -> bool {
+    RUNNING.with(|r| *r.borrow_mut() = Some((file.to_string(), source.to_string(), format)));
+
+    let result = ast_cache::parse(source).and_then(|ast| interpreter::run_with_prelude(ast, prelude));
+
+    RUNNING.with(|r| *r.borrow_mut() = None);
+
+    match result {
+        Ok(()) => true,
+        Err(error) => {
+            report(file, source, format, &error);
+            false
+        }
+    }
+}
+
+/// Prints `error` as a diagnostic against `file`, pointing human readers
+/// at `pawx explain` for the longer story.
+fn report(file: &str, source: &str, format: ErrorFormat, error: &PawxError) {
+    DiagnosticPrinter::new(file, source).with_format(format).print(error);
+
+    if format == ErrorFormat::Human {
+        eprintln!("\nFor more information about this error, try `pawx explain {}`.", error.code);
+    }
 }
 
-fn run(source: &str) {
-    let tokens = lexer::tokenize(source);
-    let ast = parser::parse(tokens);
-    interpreter::run(ast);
+/// Reports a real panic (a bug in the interpreter) raised while a script
+/// runs as a diagnostic at the statement that was executing.
+///
+/// Script errors never panic, so this only fires for bugs, including
+/// ones inside native functions. Panics on other threads, or outside
+/// `run`, keep the default report. With `RUST_BACKTRACE` set, the
+/// default report follows the diagnostic.
+fn install_panic_hook() {
+    let default = panic::take_hook();
+
+    panic::set_hook(Box::new(move |info| {
+        let backtrace = env::var_os("RUST_BACKTRACE").is_some();
+
+        let reported = RUNNING.with(|r| {
+            let running = r.borrow();
+            let (file, source, format) = running.as_ref()?;
+            let message = interpreter::testing::panic_message(info.payload());
+            let error = PawxError::new(ErrorCode::BuiltinError, message, interpreter::current_span());
+            report(file, source, *format, &error);
+            Some(())
+        });
+
+        if reported.is_none() || backtrace {
+            default(info);
+        }
+    }));
 }
\ No newline at end of file
@@ -0,0 +1,242 @@
+/*
+ * ==========================================================================
+ * PAWX - Code with Claws!
+ * ==========================================================================
+ *
+ * Project Manifest (`paw.json`)
+ * -----------------------------
+ * Describes a PAWX project:
+ *
+ * ```text
+ * {
+ *   "name": "my-app",
+ *   "version": "0.1.0",
+ *   "entry": "src/main.px",
+ *   "tasks": {
+ *     "build": "scripts/build.px",
+ *     "test": "tests/main.px"
+ *   },
+ *   "dependencies": {}
+ * }
+ * ```
+ *
+ * Task values are PAWX scripts, relative to the manifest's directory, run
+ * by `pawx run <task>`. A project without a `start` task can still be
+ * started: `start` falls back to `entry`.
+ *
+ * A manifest that lists directory patterns under `workspaces` is a
+ * workspace root: every matching directory that holds its own `paw.json`
+ * is a member package, which `tap` finds by its `name`.
+ *
+ * --------------------------------------------------------------------------
+ * Author:   Sam Wilcox
+ * Email:    sam@pawx-lang.com
+ * Website:  https://www.pawx-lang.com
+ * Github:   https://github.com/samwilcox/pawx
+ *
+ * License:
+ * This file is part of the PAWX programming language project.
+ *
+ * PAWX is dual-licensed under the terms of:
+ *   - The MIT License
+ *   - The Apache License, Version 2.0
+ *
+ * You may choose either license to govern your use of this software.
+ * Full license text available at:
+ *    https://license.pawx-lang.com
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under these licenses is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *
+ * ==========================================================================
+ */
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+/// File name of a project manifest.
+pub const MANIFEST_FILE: &str = "paw.json";
+
+/// A parsed `paw.json`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Manifest {
+    pub name: String,
+
+    #[serde(default = "default_version")]
+    pub version: String,
+
+    #[serde(default = "default_entry")]
+    pub entry: String,
+
+    /// Task name → script path.
+    #[serde(default)]
+    pub tasks: BTreeMap<String, String>,
+
+    /// Package name → version requirement.
+    #[serde(default)]
+    pub dependencies: BTreeMap<String, String>,
+
+    /// Member package directories of a workspace root, as paths or
+    /// patterns with `*` components (`"packages/*"`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub workspaces: Vec<String>,
+
+    /// Rewrite rules `pawx fix` applies to projects that install this
+    /// package, so a breaking release can ship its own upgrade (see
+    /// `fix.rs`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fixes: Option<String>,
+}
+
+fn default_version() -> String {
+    "0.1.0".to_string()
+}
+
+fn default_entry() -> String {
+    "src/main.px".to_string()
+}
+
+impl Manifest {
+    /// Reads and parses a manifest file.
+    ///
+    /// # Errors
+    /// If the file cannot be read or is not a valid manifest.
+    pub fn load(path: &Path) -> Result<Manifest, String> {
+        let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        serde_json::from_str(&text).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    /// Finds the nearest `paw.json` in `start` or any parent directory.
+    ///
+    /// # Returns
+    /// - `Ok(Some((dir, manifest)))` with the directory holding it
+    /// - `Ok(None)` if there is no manifest
+    ///
+    /// # Errors
+    /// If a manifest is found but cannot be parsed.
+    pub fn find(start: &Path) -> Result<Option<(PathBuf, Manifest)>, String> {
+        for dir in start.ancestors() {
+            let path = dir.join(MANIFEST_FILE);
+            if path.is_file() {
+                return Manifest::load(&path).map(|m| Some((dir.to_path_buf(), m)));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Finds the nearest workspace root: the closest `paw.json` in `start`
+    /// or a parent directory that lists `workspaces`.
+    ///
+    /// # Errors
+    /// If a manifest on the way cannot be parsed.
+    pub fn find_workspace(start: &Path) -> Result<Option<(PathBuf, Manifest)>, String> {
+        let mut search = Some(start.to_path_buf());
+
+        while let Some(dir) = search {
+            match Manifest::find(&dir)? {
+                Some((root, manifest)) if !manifest.workspaces.is_empty() => return Ok(Some((root, manifest))),
+                Some((root, _)) => search = root.parent().map(Path::to_path_buf),
+                None => search = None,
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Writes the manifest as pretty-printed JSON.
+    ///
+    /// # Errors
+    /// If the file cannot be written.
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let text = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        fs::write(path, text + "\n").map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    /// Records `name` at `version` in the `dependencies` of the manifest at
+    /// `path`, leaving every other field as written.
+    ///
+    /// # Errors
+    /// If the file cannot be read, parsed or written.
+    pub fn set_dependency(path: &Path, name: &str, version: &str) -> Result<(), String> {
+        let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        let mut json: serde_json::Value =
+            serde_json::from_str(&text).map_err(|e| format!("{}: {}", path.display(), e))?;
+
+        let root = json
+            .as_object_mut()
+            .ok_or_else(|| format!("{}: expected an object", path.display()))?;
+        let deps = root
+            .entry("dependencies")
+            .or_insert_with(|| serde_json::Value::Object(Default::default()));
+        let deps = deps
+            .as_object_mut()
+            .ok_or_else(|| format!("{}: \"dependencies\" must be an object", path.display()))?;
+        deps.insert(name.to_string(), serde_json::Value::String(version.to_string()));
+
+        let text = serde_json::to_string_pretty(&json).map_err(|e| e.to_string())?;
+        fs::write(path, text + "\n").map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    /// Lists the workspace members under `root`, sorted by directory.
+    ///
+    /// # Errors
+    /// If a member's manifest cannot be parsed.
+    pub fn workspace_members(&self, root: &Path) -> Result<Vec<(PathBuf, Manifest)>, String> {
+        let mut dirs = Vec::new();
+        for pattern in &self.workspaces {
+            expand_pattern(root, pattern, &mut dirs);
+        }
+        dirs.sort();
+        dirs.dedup();
+
+        dirs.into_iter()
+            .filter(|dir| dir.join(MANIFEST_FILE).is_file())
+            .map(|dir| Manifest::load(&dir.join(MANIFEST_FILE)).map(|m| (dir, m)))
+            .collect()
+    }
+
+    /// Returns the script for `task`, falling back to `entry` for `start`.
+    pub fn task_script(&self, task: &str) -> Option<&str> {
+        match self.tasks.get(task) {
+            Some(script) => Some(script),
+            None if task == "start" => Some(&self.entry),
+            None => None,
+        }
+    }
+}
+
+/// Collects the directories under `root` matching `pattern`.
+///
+/// Each `/`-separated component is either a literal name or `*`, which
+/// matches any directory that is not hidden.
+fn expand_pattern(root: &Path, pattern: &str, out: &mut Vec<PathBuf>) {
+    let mut dirs = vec![root.to_path_buf()];
+
+    for part in pattern.split('/').filter(|p| !p.is_empty() && *p != ".") {
+        dirs = dirs
+            .into_iter()
+            .flat_map(|dir| -> Vec<PathBuf> {
+                if part != "*" {
+                    return vec![dir.join(part)];
+                }
+
+                fs::read_dir(&dir)
+                    .map(|entries| {
+                        entries
+                            .filter_map(|e| e.ok().map(|e| e.path()))
+                            .filter(|p| p.is_dir())
+                            .filter(|p| !p.file_name().is_some_and(|n| n.to_string_lossy().starts_with('.')))
+                            .collect()
+                    })
+                    .unwrap_or_default()
+            })
+            .collect();
+    }
+
+    out.extend(dirs.into_iter().filter(|d| d.is_dir()));
+}
@@ -51,7 +51,14 @@
  *
  * Parsing order follows strict mathematical precedence:
  *
- *   assignment → equality → comparison → term → factor → unary → call → primary
+ *   assignment → logical_or → logical_and → bit_or → bit_xor → bit_and
+ *     → equality → comparison → shift → term → factor → unary → power
+ *     → call → primary
+ *
+ * The bitwise and shift levels sit where JavaScript puts them, so
+ * `a & b == c` is `a & (b == c)`: parenthesize masks before comparing.
+ * `**` is right-associative and binds tighter than a unary operator on
+ * its left, so `-2 ** 2` is `-4` and `2 ** -1` is `0.5`.
  *
  * This guarantees:
  *  - Correct operator precedence
@@ -62,59 +69,117 @@
  * ==========================================================================
  */
 
-use std::string::ParseError;
-
-use crate::ast::Expr;
+use crate::ast::{Expr, Stmt};
+use crate::error::PawxError;
 use crate::lexer::token::TokenKind;
-use crate::parser::parser::Parser;
-use crate::span::Span;
+use crate::parser::parser::{ParseResult, Parser};
 use crate::value::Value;
 
 impl Parser {
     /// expression → assignment
-    pub fn expression(&mut self) -> Expr {
+    pub fn expression(&mut self) -> ParseResult<Expr> {
         self.assignment()
     }
 
     /// assignment → logical_or ( "=" assignment )?
-    fn assignment(&mut self) -> Expr {
-        let expr = self.logical_or();
+    fn assignment(&mut self) -> ParseResult<Expr> {
+        let expr = self.logical_or()?;
 
         if self.match_symbol('=') {
             let equals = self.previous().clone();
-            let value = self.assignment();
+            let value = self.assignment()?;
 
             match expr {
-                Expr::Identifier { name, .. } => Expr::Assign {
+                Expr::Identifier { name, .. } => Ok(Expr::Assign {
                     name,
                     value: Box::new(value),
                     span: equals.span,
-                },
+                }),
 
-                Expr::Get { object, name, .. } => Expr::Set {
+                Expr::Get { object, name, .. } => Ok(Expr::Set {
                     object,
                     name,
                     value: Box::new(value),
                     span: equals.span,
-                },
+                }),
 
-                Expr::Index { object, index, .. } => Expr::IndexAssign {
+                Expr::Index { object, index, .. } => Ok(Expr::IndexAssign {
                     object,
                     index,
                     value: Box::new(value),
                     span: equals.span,
-                },
+                }),
 
-                _ => panic!("Invalid assignment target"),
+                _ => Err(PawxError::syntax_error("invalid assignment target", equals.span)),
             }
         } else {
-            expr
+            Ok(expr)
+        }
+    }
+
+    /// bit_or → bit_xor ( "|" bit_xor )*
+    fn bit_or(&mut self) -> ParseResult<Expr> {
+        let mut expr = self.bit_xor()?;
+
+        while self.match_operator("|") {
+            let op = self.previous().clone();
+            let right = self.bit_xor()?;
+            let span = op.span;
+
+            expr = Expr::Binary {
+                left: Box::new(expr),
+                operator: op,
+                right: Box::new(right),
+                span,
+            };
+        }
+
+        Ok(expr)
+    }
+
+    /// bit_xor → bit_and ( "^" bit_and )*
+    fn bit_xor(&mut self) -> ParseResult<Expr> {
+        let mut expr = self.bit_and()?;
+
+        while self.match_operator("^") {
+            let op = self.previous().clone();
+            let right = self.bit_and()?;
+            let span = op.span;
+
+            expr = Expr::Binary {
+                left: Box::new(expr),
+                operator: op,
+                right: Box::new(right),
+                span,
+            };
         }
+
+        Ok(expr)
+    }
+
+    /// bit_and → equality ( "&" equality )*
+    fn bit_and(&mut self) -> ParseResult<Expr> {
+        let mut expr = self.equality()?;
+
+        while self.match_operator("&") {
+            let op = self.previous().clone();
+            let right = self.equality()?;
+            let span = op.span;
+
+            expr = Expr::Binary {
+                left: Box::new(expr),
+                operator: op,
+                right: Box::new(right),
+                span,
+            };
+        }
+
+        Ok(expr)
     }
 
     /// equality → comparison ( ( "==" | "!=" | "===" | "!==" ) comparison )*
-    fn equality(&mut self) -> Expr {
-        let mut expr = self.comparison();
+    fn equality(&mut self) -> ParseResult<Expr> {
+        let mut expr = self.comparison()?;
 
         while self.match_operator("==")
             || self.match_operator("!=")
@@ -122,7 +187,7 @@ impl Parser {
             || self.match_operator("!==")
         {
             let op = self.previous().clone();
-            let right = self.comparison();
+            let right = self.comparison()?;
             let span = op.span;
 
             expr = Expr::Binary {
@@ -133,20 +198,21 @@ impl Parser {
             };
         }
 
-        expr
+        Ok(expr)
     }
 
-    /// comparison → term ( ( ">" | ">=" | "<" | "<=" ) term )*
-    fn comparison(&mut self) -> Expr {
-        let mut expr = self.term();
+    /// comparison → shift ( ( ">" | ">=" | "<" | "<=" | "instanceof" ) shift )*
+    fn comparison(&mut self) -> ParseResult<Expr> {
+        let mut expr = self.shift()?;
 
         while self.match_operator(">")
             || self.match_operator(">=")
             || self.match_operator("<")
             || self.match_operator("<=")
+            || self.match_keyword("instanceof")
         {
             let op = self.previous().clone();
-            let right = self.term();
+            let right = self.shift()?;
             let span = op.span;
 
             expr = Expr::Binary {
@@ -157,16 +223,38 @@ impl Parser {
             };
         }
 
-        expr
+        Ok(expr)
+    }
+
+    /// shift → term ( ( "<<" | ">>" | ">>>" ) term )*
+    fn shift(&mut self) -> ParseResult<Expr> {
+        let mut expr = self.term()?;
+
+        while self.match_operator("<<")
+            || self.match_operator(">>")
+            || self.match_operator(">>>") {
+            let op = self.previous().clone();
+            let right = self.term()?;
+            let span = op.span;
+
+            expr = Expr::Binary {
+                left: Box::new(expr),
+                operator: op,
+                right: Box::new(right),
+                span,
+            };
+        }
+
+        Ok(expr)
     }
 
     /// term → factor ( ( "+" | "-" ) factor )*
-    fn term(&mut self) -> Expr {
-        let mut expr = self.factor();
+    fn term(&mut self) -> ParseResult<Expr> {
+        let mut expr = self.factor()?;
 
         while self.match_operator("+") || self.match_operator("-") {
             let op = self.previous().clone();
-            let right = self.factor();
+            let right = self.factor()?;
             let span = op.span;
 
             expr = Expr::Binary {
@@ -177,19 +265,20 @@ impl Parser {
             };
         }
 
-        expr
+        Ok(expr)
     }
 
-    /// factor → unary ( ( "*" | "/" | "%" ) unary )*
-    fn factor(&mut self) -> Expr {
-        let mut expr = self.unary();
+    /// factor → unary ( ( "*" | "/" | "%" | "~/" ) unary )*
+    fn factor(&mut self) -> ParseResult<Expr> {
+        let mut expr = self.unary()?;
 
         while self.match_operator("*")
             || self.match_operator("/")
             || self.match_operator("%")
+            || self.match_operator("~/")
         {
             let op = self.previous().clone();
-            let right = self.unary();
+            let right = self.unary()?;
             let span = op.span;
 
             expr = Expr::Binary {
@@ -200,29 +289,68 @@ impl Parser {
             };
         }
 
-        expr
+        Ok(expr)
     }
 
-    /// unary → ( "!" | "-" ) unary | call
-    fn unary(&mut self) -> Expr {
-        if self.match_operator("!") || self.match_operator("-") {
+    /// unary → ( "!" | "-" | "~" | "nap" ) unary | power
+    fn unary(&mut self) -> ParseResult<Expr> {
+        if self.match_keyword("nap") {
+            let span = self.previous().span;
+            let expr = self.unary()?;
+
+            return Ok(Expr::Nap { expr: Box::new(expr), span });
+        }
+
+        if self.match_operator("!") || self.match_operator("-") || self.match_operator("~") {
             let op = self.previous().clone();
-            let right = self.unary();
+            let right = self.unary()?;
             let span = op.span;
 
-            return Expr::Unary {
+            return Ok(Expr::Unary {
                 operator: op,
                 right: Box::new(right),
                 span,
-            };
+            });
+        }
+
+        self.power()
+    }
+
+    /// power → call ( "**" unary )?
+    ///
+    /// The right operand is parsed as a unary, which makes `**`
+    /// right-associative (`2 ** 3 ** 2` is `2 ** 9`) and allows `2 ** -1`.
+    fn power(&mut self) -> ParseResult<Expr> {
+        let expr = self.call()?;
+
+        if self.match_operator("**") {
+            let op = self.previous().clone();
+            let right = self.unary()?;
+            let span = op.span;
+
+            return Ok(Expr::Binary {
+                left: Box::new(expr),
+                operator: op,
+                right: Box::new(right),
+                span,
+            });
         }
 
-        self.call()
+        Ok(expr)
     }
 
     /// call → primary ( "(" arguments? ")" | "." identifier | "[" expression "]" )*
-    fn call(&mut self) -> Expr {
-        let mut expr = self.primary();
+    ///
+    /// The lexer does not emit newline tokens, so a chain may be split
+    /// across lines in fluent style:
+    ///
+    /// ```text
+    /// arr
+    ///     .map(x -> x * 2)
+    ///     .filter(x -> x > 2)
+    /// ```
+    fn call(&mut self) -> ParseResult<Expr> {
+        let mut expr = self.primary()?;
 
         loop {
             // function call
@@ -232,14 +360,14 @@ impl Parser {
 
                 if !self.check_symbol(')') {
                     loop {
-                        args.push(self.expression());
+                        args.push(self.argument()?);
                         if !self.match_symbol(',') {
                             break;
                         }
                     }
                 }
 
-                self.consume_symbol(')');
+                self.consume_symbol(')')?;
 
                 expr = Expr::Call {
                     callee: Box::new(expr),
@@ -267,8 +395,8 @@ impl Parser {
             // index access
             if self.match_symbol('[') {
                 let lbracket = self.previous().clone();
-                let index = self.expression();
-                self.consume_symbol(']');
+                let index = self.expression()?;
+                self.consume_symbol(']')?;
 
                 expr = Expr::Index {
                     object: Box::new(expr),
@@ -281,30 +409,80 @@ impl Parser {
             break;
         }
 
-        expr
+        Ok(expr)
+    }
+
+    /// argument → ( identifier ":" )? expression
+    ///
+    /// `connect(host: "x", port: 8080)` passes arguments by parameter name.
+    fn argument(&mut self) -> ParseResult<Expr> {
+        if self.tokens[self.current].kind == TokenKind::Identifier && self.peek_is(":") {
+            let name_token = self.advance();
+            self.consume_symbol(':')?;
+            let value = self.expression()?;
+
+            return Ok(Expr::NamedArgument {
+                name: name_token.lexeme,
+                value: Box::new(value),
+                span: name_token.span,
+            });
+        }
+
+        self.expression()
     }
 
-    fn primary(&mut self) -> Expr {
+    fn primary(&mut self) -> ParseResult<Expr> {
+        // lambda: `x -> { ... }`, `(a, b) -> { ... }`, `() -> expr`
+        if self.is_lambda_start() {
+            return self.lambda();
+        }
+
         // tap
         if self.match_keyword("tap") {
             let tap_token = self.previous().clone();
 
             let path = if self.match_symbol('(') {
-                let expr = self.expression();
-                self.consume_symbol(')');
+                let expr = self.expression()?;
+                self.consume_symbol(')')?;
                 expr
             } else {
                 let token = self.advance();
                 Expr::Literal {
-                    value: Value::String(token.lexeme),
+                    value: Value::String(token.lexeme.into()),
                     span: token.span,
                 }
             };
 
-            return Expr::Tap {
+            return Ok(Expr::Tap {
                 path: Box::new(path),
                 span: tap_token.span,
-            };
+            });
+        }
+
+        // new Class(args)
+        if self.check_keyword("new")
+            && self.tokens.get(self.current + 1).is_some_and(|t| t.kind == TokenKind::Identifier)
+        {
+            let new_token = self.advance();
+            let class_name = self.consume_identifier()?;
+            let mut arguments = Vec::new();
+
+            self.consume_symbol('(')?;
+            if !self.check_symbol(')') {
+                loop {
+                    arguments.push(self.argument()?);
+                    if !self.match_symbol(',') {
+                        break;
+                    }
+                }
+            }
+            self.consume_symbol(')')?;
+
+            return Ok(Expr::New {
+                class_name,
+                arguments,
+                span: new_token.span,
+            });
         }
 
         // array literal
@@ -314,19 +492,19 @@ impl Parser {
 
             if !self.check_symbol(']') {
                 loop {
-                    values.push(self.expression());
+                    values.push(self.expression()?);
                     if !self.match_symbol(',') {
                         break;
                     }
                 }
             }
 
-            self.consume_symbol(']');
+            self.consume_symbol(']')?;
 
-            return Expr::ArrayLiteral {
+            return Ok(Expr::ArrayLiteral {
                 values,
                 span: start.span,
-            };
+            });
         }
 
         // object literal
@@ -335,71 +513,82 @@ impl Parser {
             let mut fields = Vec::new();
 
             if self.match_symbol('}') {
-                return Expr::ObjectLiteral {
+                return Ok(Expr::ObjectLiteral {
                     fields,
                     span: start.span,
-                };
+                });
             }
 
             loop {
                 let key = self.advance().lexeme.clone();
-                self.consume_symbol(':');
-                let value = self.expression();
+                self.consume_symbol(':')?;
+                let value = self.expression()?;
                 fields.push((key, value));
 
                 if self.match_symbol('}') {
                     break;
                 }
 
-                self.consume_symbol(',');
+                self.consume_symbol(',')?;
             }
 
-            return Expr::ObjectLiteral {
+            return Ok(Expr::ObjectLiteral {
                 fields,
                 span: start.span,
-            };
+            });
         }
 
         // literals / identifiers / grouping / tuple
+        if self.is_at_end() {
+            return Err(self.error_here("expected expression"));
+        }
         let token = self.advance();
 
         match token.kind {
-            TokenKind::Number => Expr::Literal {
+            TokenKind::Number => Ok(Expr::Literal {
                 value: Value::Number(token.lexeme.parse().unwrap()),
                 span: token.span,
-            },
+            }),
 
-            TokenKind::String => Expr::Literal {
-                value: Value::String(token.lexeme),
+            TokenKind::String => Ok(Expr::Literal {
+                value: Value::String(token.lexeme.into()),
                 span: token.span,
-            },
+            }),
+
+            TokenKind::Keyword if token.lexeme == "this" && self.in_static => Err(PawxError::syntax_error(
+                "'this' cannot be used in a static member; refer to the clowder by name instead",
+                token.span,
+            )),
 
             TokenKind::Identifier | TokenKind::Keyword => {
-                Expr::Identifier {
+                Ok(Expr::Identifier {
                     name: token.lexeme,
                     span: token.span,
-                }
+                })
             }
 
             TokenKind::Symbol if token.lexeme == "(" => {
-                let expr = self.expression();
-                self.consume_symbol(')');
-                Expr::Grouping {
+                let expr = self.expression()?;
+                self.consume_symbol(')')?;
+                Ok(Expr::Grouping {
                     expr: Box::new(expr),
                     span: token.span,
-                }
+                })
             }
 
-            _ => panic!("Unexpected token: {:?}", token),
+            _ => Err(PawxError::syntax_error(
+                format!("unexpected '{}'", token.lexeme),
+                token.span,
+            )),
         }
     }
 
-    fn logical_or(&mut self) -> Expr {
-        let mut expr = self.logical_and();
+    fn logical_or(&mut self) -> ParseResult<Expr> {
+        let mut expr = self.logical_and()?;
 
         while self.match_symbol_lexeme("||") {
             let op = self.previous().clone();
-            let right = self.logical_and();
+            let right = self.logical_and()?;
             let span = op.span;
 
             expr = Expr::Logical {
@@ -410,15 +599,15 @@ impl Parser {
             };
         }
 
-        expr
+        Ok(expr)
     }
 
-    fn logical_and(&mut self) -> Expr {
-        let mut expr = self.equality();
+    fn logical_and(&mut self) -> ParseResult<Expr> {
+        let mut expr = self.bit_or()?;
 
         while self.match_symbol_lexeme("&&") {
             let op = self.previous().clone();
-            let right = self.equality();
+            let right = self.bit_or()?;
             let span = op.span;
 
             expr = Expr::Logical {
@@ -429,6 +618,98 @@ impl Parser {
             };
         }
 
-        expr
+        Ok(expr)
+    }
+
+    /// Detects whether the upcoming tokens begin a lambda expression.
+    ///
+    /// This is a **pure lookahead** check that never consumes tokens, so
+    /// grouping expressions such as `(a + b)` are left untouched.
+    ///
+    /// Recognized forms:
+    /// ```pawx
+    /// x -> { ... }
+    /// () -> { ... }
+    /// (a, b) -> { ... }
+    /// ```
+    fn is_lambda_start(&self) -> bool {
+        let token_at = |i: usize| self.tokens.get(i);
+        let is_symbol = |i: usize, s: &str| {
+            token_at(i).is_some_and(|t| t.kind == TokenKind::Symbol && t.lexeme == s)
+        };
+        let is_ident = |i: usize| token_at(i).is_some_and(|t| t.kind == TokenKind::Identifier);
+
+        let start = self.current;
+
+        // Single parameter: `x -> ...`
+        if is_ident(start) {
+            return is_symbol(start + 1, "->");
+        }
+
+        if !is_symbol(start, "(") {
+            return false;
+        }
+
+        // Parameter list: `()` or `(a, b, ...)`
+        let mut i = start + 1;
+
+        if !is_symbol(i, ")") {
+            loop {
+                if !is_ident(i) {
+                    return false;
+                }
+                i += 1;
+
+                if is_symbol(i, ",") {
+                    i += 1;
+                    continue;
+                }
+                break;
+            }
+        }
+
+        is_symbol(i, ")") && is_symbol(i + 1, "->")
+    }
+
+    /// Parses a lambda expression.
+    ///
+    /// The body is either a `{ ... }` block or a single expression, which is
+    /// treated as an implicit `return`.
+    ///
+    /// # Returns
+    /// An `Expr::Lambda` AST node.
+    fn lambda(&mut self) -> ParseResult<Expr> {
+        let start = self.tokens[self.current].clone();
+        let mut params = Vec::new();
+
+        if self.match_symbol('(') {
+            if !self.check_symbol(')') {
+                loop {
+                    params.push(self.consume_identifier()?);
+                    if !self.match_symbol(',') {
+                        break;
+                    }
+                }
+            }
+            self.consume_symbol(')')?;
+        } else {
+            params.push(self.consume_identifier()?);
+        }
+
+        self.consume_arrow()?;
+
+        let body = if self.check_symbol('{') {
+            self.block()?
+        } else {
+            let value = self.expression()?;
+            let span = value.span();
+            vec![Stmt::Return { value: Some(value), span }]
+        };
+
+        Ok(Expr::Lambda {
+            params,
+            body,
+            span: start.span,
+        })
     }
-}
\ No newline at end of file
+}
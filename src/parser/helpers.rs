@@ -26,7 +26,9 @@
  * ==========================================================================
  */
 
-use crate::parser::parser::Parser;
+use crate::ast::Stmt;
+use crate::error::PawxError;
+use crate::parser::parser::{ParseResult, Parser};
 use crate::lexer::token::{Token, TokenKind};
 
 impl Parser {
@@ -81,28 +83,61 @@ impl Parser {
             && self.tokens[self.current].lexeme == ch.to_string()
     }
 
-    /// Consumes a required symbol or panics.
-    pub fn consume_symbol(&mut self, ch: char) {
+    /// Consumes a required symbol or fails with a syntax error.
+    pub fn consume_symbol(&mut self, ch: char) -> ParseResult<()> {
         if self.check_symbol(ch) {
             self.advance();
+            Ok(())
         } else {
-            panic!("Expected '{}'", ch);
+            Err(self.error_here(format!("expected '{}'", ch)))
         }
     }
 
-    /// Consumes and returns an identifier or panics.
-    pub fn consume_identifier(&mut self) -> String {
-        let token = self.advance();
-        if token.kind != TokenKind::Identifier {
-            panic!("Expected identifier");
+    /// Consumes and returns an identifier or fails with a syntax error.
+    pub fn consume_identifier(&mut self) -> ParseResult<String> {
+        if self.tokens[self.current].kind != TokenKind::Identifier {
+            return Err(self.error_here("expected identifier"));
         }
-        token.lexeme
+        Ok(self.advance().lexeme)
+    }
+
+    /// Parses a `{ ... }` block of statements.
+    pub fn block(&mut self) -> ParseResult<Vec<Stmt>> {
+        self.consume_symbol('{')?;
+
+        let mut body = Vec::new();
+        while !self.check_symbol('}') {
+            if self.is_at_end() {
+                return Err(self.error_here("expected '}'"));
+            }
+            body.push(self.statement()?);
+        }
+        self.consume_symbol('}')?;
+
+        Ok(body)
+    }
+
+    /// Builds a syntax error at the current token:
+    /// `expected ')', found ';'`.
+    pub fn error_here(&self, message: impl Into<String>) -> PawxError {
+        let token = &self.tokens[self.current];
+        let found = match token.kind {
+            TokenKind::Eof => "end of input".to_string(),
+            TokenKind::String => format!("string \"{}\"", token.lexeme),
+            _ => format!("'{}'", token.lexeme),
+        };
+
+        PawxError::syntax_error(format!("{}, found {}", message.into(), found), token.span)
     }
 
     /// Advances one token forward.
+    ///
+    /// Never moves past the final EOF token.
     pub fn advance(&mut self) -> Token {
         let t = self.tokens[self.current].clone();
-        self.current += 1;
+        if t.kind != TokenKind::Eof {
+            self.current += 1;
+        }
         t
     }
 
@@ -193,10 +228,10 @@ impl Parser {
     /// This is a **strict consumption** helper used when the grammar
     /// *requires* the presence of an arrow token.
     ///
-    /// Internally, this delegates to `match_arrow()` and panics if no match
+    /// Internally, this delegates to `match_arrow()` and fails if no match
     /// is found.
     ///
-    /// # Panics
+    /// # Errors
     /// - If the current token is not `"->"`
     ///
     /// # Usage Examples
@@ -205,9 +240,11 @@ impl Parser {
     /// - Lambda parsing
     /// - Getters and setters
     /// - Method signatures
-    pub fn consume_arrow(&mut self) {
-        if !self.match_arrow() {
-            panic!("Expected '->'");
+    pub fn consume_arrow(&mut self) -> ParseResult<()> {
+        if self.match_arrow() {
+            Ok(())
+        } else {
+            Err(self.error_here("expected '->'"))
         }
     }
 
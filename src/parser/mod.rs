@@ -10,6 +10,7 @@
  *   - Core parser control logic
  *   - Statement parsing
  *   - Expression parsing
+ *   - Pattern parsing (match arms)
  *   - Shared helper utilities
  * 
  * Author:   Sam Wilcox
@@ -41,7 +42,7 @@
 pub mod parser;
 
 /// Statement-level parsing:
-/// - if / while / return / try / throw
+/// - if / while / match / return / try / throw
 /// - clowder / instinct
 /// - variable declarations
 pub mod statements;
@@ -51,6 +52,12 @@ pub mod statements;
 /// - lambdas, arrays, objects, indexing, calls, etc.
 pub mod expressions;
 
+/// Pattern parsing for `match` arms:
+/// - literals, bindings, `_`
+/// - type patterns (`Number n`)
+/// - object / array destructuring
+pub mod patterns;
+
 /// Shared parser helpers:
 /// - token matching
 /// - lookahead checks
@@ -41,8 +41,13 @@
  */
 
 use crate::ast::Stmt;
+use crate::error::PawxError;
 use crate::lexer::token::{Token};
 
+/// Result of a parsing step. Syntax errors carry the span of the
+/// offending token.
+pub type ParseResult<T> = Result<T, PawxError>;
+
 /// The core PAWX recursive-descent parser.
 ///
 /// This structure maintains:
@@ -57,6 +62,10 @@ pub struct Parser {
 
     /// Current cursor position within the token stream.
     pub current: usize,
+
+    /// Whether a static method or static field initializer is being
+    /// parsed, where `this` does not exist.
+    pub in_static: bool,
 }
 
 /// Public entry point for the PAWX parsing phase.
@@ -70,7 +79,8 @@ pub struct Parser {
 /// - `tokens`: The full token stream produced by the lexer
 ///
 /// # Returns
-/// A vector of fully parsed top-level `Stmt` nodes.
+/// A vector of fully parsed top-level `Stmt` nodes, or the first syntax
+/// error encountered.
 ///
 /// # PAWX Compilation Pipeline
 /// ```text
@@ -79,11 +89,11 @@ pub struct Parser {
 ///
 /// # Example
 /// ```rust
-/// let tokens = tokenize(source_code);
-/// let ast = parse(tokens);
+/// let tokens = tokenize(source_code)?;
+/// let ast = parse(tokens)?;
 /// ```
-pub fn parse(tokens: Vec<Token>) -> Vec<Stmt> {
-    let mut parser = Parser { tokens, current: 0 };
+pub fn parse(tokens: Vec<Token>) -> ParseResult<Vec<Stmt>> {
+    let mut parser = Parser { tokens, current: 0, in_static: false };
     parser.parse()
 }
 
@@ -100,14 +110,14 @@ impl Parser {
     /// # Behavior
     /// - Guarantees full token consumption.
     /// - Statements are parsed in strict left-to-right order.
-    /// - Structural errors will trigger immediate panics.
-    pub fn parse(&mut self) -> Vec<Stmt> {
+    /// - Parsing stops at the first structural error.
+    pub fn parse(&mut self) -> ParseResult<Vec<Stmt>> {
         let mut stmts = Vec::new();
 
         while !self.is_at_end() {
-            stmts.push(self.statement());
+            stmts.push(self.statement()?);
         }
 
-        stmts
+        Ok(stmts)
     }
 }
\ No newline at end of file
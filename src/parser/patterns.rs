@@ -0,0 +1,184 @@
+/*
+ * ==========================================================================
+ * PAWX - Code with Claws!
+ * ==========================================================================
+ *
+ * Pattern Parsing Logic
+ *
+ * This file contains the grammar rules for the patterns used by `match`
+ * arms (`case <pattern> { ... }`).
+ *
+ * Pattern grammar:
+ *
+ *   pattern  → "_"
+ *            | literal                      (number, string, true/false/null)
+ *            | identifier                   (lower-case: binding)
+ *            | TypeName identifier?         (upper-case: type pattern)
+ *            | "{" field ("," field)* "}"
+ *            | "[" items? "]"
+ *
+ *   field    → key (":" pattern)?           (`{ body }` binds `body`)
+ *   items    → pattern ("," pattern)* ("," "..." identifier?)?
+ *
+ * --------------------------------------------------------------------------
+ * Author:   Sam Wilcox
+ * Email:    sam@pawx-lang.com
+ * Website:  https://www.pawx-lang.com
+ * Github:   https://github.com/samwilcox/pawx
+ *
+ * License:
+ * This file is part of the PAWX programming language project.
+ *
+ * PAWX is dual-licensed under the terms of:
+ *   - The MIT license
+ *   - The Apache License, Version 2.0
+ *
+ * You may choose either license to govern your use of this software.
+ * Full license text available at:
+ *    https://license.pawx-lang.com
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under these licenses is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *
+ * ==========================================================================
+ */
+
+use crate::ast::Pattern;
+use crate::error::PawxError;
+use crate::lexer::token::TokenKind;
+use crate::parser::parser::{ParseResult, Parser};
+use crate::value::Value;
+
+impl Parser {
+    /// Parses a single `match` pattern.
+    ///
+    /// # Errors
+    /// If the tokens do not form a valid pattern.
+    pub fn pattern(&mut self) -> ParseResult<Pattern> {
+        if self.match_symbol('[') {
+            return self.array_pattern();
+        }
+
+        if self.match_symbol('{') {
+            return self.object_pattern();
+        }
+
+        // Negative number literal: -1
+        if self.check_symbol('-')
+            && self.tokens.get(self.current + 1).is_some_and(|t| t.kind == TokenKind::Number)
+        {
+            self.advance();
+            let token = self.advance();
+            return Ok(Pattern::Literal(Value::Number(-token.lexeme.parse::<f64>().unwrap())));
+        }
+
+        if self.is_at_end() {
+            return Err(self.error_here("expected a match pattern"));
+        }
+        let token = self.advance();
+
+        let pattern = match token.kind {
+            TokenKind::Number => Pattern::Literal(Value::Number(token.lexeme.parse().unwrap())),
+
+            TokenKind::String => Pattern::Literal(Value::String(token.lexeme.into())),
+
+            TokenKind::Keyword => match token.lexeme.as_str() {
+                "true" => Pattern::Literal(Value::Bool(true)),
+                "false" => Pattern::Literal(Value::Bool(false)),
+                "null" => Pattern::Literal(Value::Null),
+                other => {
+                    return Err(PawxError::syntax_error(
+                        format!("unexpected keyword '{}' in match pattern", other),
+                        token.span,
+                    ))
+                }
+            },
+
+            TokenKind::Identifier if token.lexeme == "_" => Pattern::Wildcard,
+
+            // Upper-case names are types: `Number`, `Number n`, `Cat c`
+            TokenKind::Identifier if token.lexeme.starts_with(|c: char| c.is_ascii_uppercase()) => {
+                let binding = if self.tokens[self.current].kind == TokenKind::Identifier {
+                    Some(self.consume_identifier()?)
+                } else {
+                    None
+                };
+
+                Pattern::Type {
+                    type_name: token.lexeme,
+                    binding,
+                }
+            }
+
+            TokenKind::Identifier => Pattern::Binding(token.lexeme),
+
+            _ => {
+                return Err(PawxError::syntax_error(
+                    format!("unexpected '{}' in match pattern", token.lexeme),
+                    token.span,
+                ))
+            }
+        };
+
+        Ok(pattern)
+    }
+
+    /// Parses the rest of an array pattern after `[`.
+    fn array_pattern(&mut self) -> ParseResult<Pattern> {
+        let mut elements = Vec::new();
+        let mut rest = None;
+
+        while !self.check_symbol(']') {
+            if self.match_symbol_lexeme("...") {
+                rest = Some(if self.tokens[self.current].kind == TokenKind::Identifier {
+                    Some(self.consume_identifier()?)
+                } else {
+                    None
+                });
+
+                if !self.check_symbol(']') {
+                    return Err(self.error_here("a rest element ('...') must be the last item in an array pattern"));
+                }
+                break;
+            }
+
+            elements.push(self.pattern()?);
+
+            if !self.match_symbol(',') {
+                break;
+            }
+        }
+
+        self.consume_symbol(']')?;
+
+        Ok(Pattern::Array { elements, rest })
+    }
+
+    /// Parses the rest of an object pattern after `{`.
+    ///
+    /// A key without `: pattern` binds the field under its own name.
+    fn object_pattern(&mut self) -> ParseResult<Pattern> {
+        let mut fields = Vec::new();
+
+        while !self.check_symbol('}') {
+            let key = self.advance().lexeme;
+
+            let pattern = if self.match_symbol(':') {
+                self.pattern()?
+            } else {
+                Pattern::Binding(key.clone())
+            };
+
+            fields.push((key, pattern));
+
+            if !self.match_symbol(',') {
+                break;
+            }
+        }
+
+        self.consume_symbol('}')?;
+
+        Ok(Pattern::Object { fields })
+    }
+}
@@ -11,7 +11,7 @@
  * It handles:
  * - Function declarations (`purr`, `zoom purr`)
  * - Variables (`den`, `lair`, `snuggle`, `pride`)
- * - Control flow (`if`, `while`, `try`, `catch`, `finally`)
+ * - Control flow (`if`, `while`, `match`, `try`, `catch`, `finally`)
  * - Classes (`clowder`)
  * - Interfaces (`instinct`)
  * - Module exports
@@ -44,8 +44,10 @@
  * ==========================================================================
  */
 
-use crate::parser::parser::Parser;
-use crate::ast::{Stmt, Param, ClassMember, AccessLevel, InstinctMember, InstinctMemberKind};
+use crate::error::PawxError;
+use crate::parser::parser::{ParseResult, Parser};
+use crate::lexer::token::TokenKind;
+use crate::ast::{Stmt, Param, ClassMember, AccessLevel, InstinctMember, InstinctMemberKind, MatchArm, Pattern, TestMode};
 
 impl  Parser {
     /// Parses a single top-level PAWX statement.
@@ -60,7 +62,17 @@ impl  Parser {
     /// - Class & interface declarations
     /// - Export statements
     /// - Expression statements as a fallback
-    pub fn statement(&mut self) -> Stmt {
+    pub fn statement(&mut self) -> ParseResult<Stmt> {
+        let span = self.tokens[self.current].span;
+
+        // ------------------------------------------------------------
+        // DECORATED FUNCTION:
+        // @decorator purr name -> (...) -> { body }
+        // ------------------------------------------------------------
+        if self.check_symbol('@') {
+            return self.decorated_function();
+        }
+
         // ------------------------------------------------------------
         // ASYNC FUNCTION:
         // zoom purr name -> (...) -> [:type ->] { body }
@@ -97,11 +109,15 @@ impl  Parser {
         }
 
         if self.match_keyword("snuggle") {
-            let name = self.consume_identifier();
-            self.consume_symbol('=');
-            let value = self.expression();
+            if self.check_symbol('{') {
+                return self.destructure_var();
+            }
+
+            let name = self.consume_identifier()?;
+            self.consume_symbol('=')?;
+            let value = self.expression()?;
             self.match_symbol(';');
-            return Stmt::PublicVar { name, value };
+            return Ok(Stmt::PublicVar { name, value, span });
         }
 
         // ------------------------------------------------------------
@@ -136,7 +152,7 @@ impl  Parser {
                 return self.instinct_declaration(true, is_default);
             }
 
-            panic!("Expected 'clowder' or 'instinct' after 'exports'");
+            return Err(self.error_here("expected 'clowder' or 'instinct' after 'exports'"));
         }
 
         // ------------------------------------------------------------
@@ -161,19 +177,40 @@ impl  Parser {
             return self.while_statement();
         }
 
+        if self.match_keyword("when") {
+            return self.when_statement();
+        }
+
         // ------------------------------------------------------------
-        // FLOW CONTROL
+        // TEST BLOCK:
+        // test "name" { body }
+        // test.only "name" { body } / test.skip "name" { body }
+        //
+        // `it` works like `test`. Both stay ordinary identifiers
+        // everywhere else.
         // ------------------------------------------------------------
-        if self.match_keyword("nap") {
-            let expr = self.expression();
-            self.match_symbol(';');
-            return Stmt::Nap(expr);
+        if let Some((mode, len)) = self.test_block_head() {
+            for _ in 0..len {
+                self.advance();
+            }
+            let name = self.advance().lexeme;
+
+            let body = self.block()?;
+
+            return Ok(Stmt::Test { name, body, mode, span });
         }
 
+        if self.match_keyword("match") {
+            return self.match_statement();
+        }
+
+        // ------------------------------------------------------------
+        // FLOW CONTROL
+        // ------------------------------------------------------------
         if self.match_keyword("throw") {
-            let expr = self.expression();
+            let expr = self.expression()?;
             self.match_symbol(';');
-            return Stmt::Throw(expr);
+            return Ok(Stmt::Throw(expr));
         }
 
         if self.match_keyword("return") {
@@ -186,27 +223,54 @@ impl  Parser {
         self.expression_statement()
     }
 
+    /// Parses one or more `@decorator` expressions followed by the `purr`
+    /// (or `zoom purr`) declaration they apply to.
+    ///
+    /// # Errors
+    /// If the decorators are not followed by a function declaration.
+    pub fn decorated_function(&mut self) -> ParseResult<Stmt> {
+        let span = self.tokens[self.current].span;
+        let mut decorators = Vec::new();
+
+        while self.match_symbol('@') {
+            decorators.push(self.expression()?);
+        }
+
+        match self.statement()? {
+            function @ Stmt::Function { .. } => Ok(Stmt::Decorated {
+                decorators,
+                function: Box::new(function),
+                span,
+            }),
+            other => Err(PawxError::syntax_error(
+                "decorators can only be applied to 'purr' function declarations",
+                other.span(),
+            )),
+        }
+    }
+
     /// Parses a function declaration with optional async support.
     ///
     /// This handles both:
     /// - `purr name -> (...) { ... }`
     /// - `zoom purr name -> (...) { ... }`
-    pub fn function_declaration_with_async(&mut self, is_async: bool) -> Stmt {
+    pub fn function_declaration_with_async(&mut self, is_async: bool) -> ParseResult<Stmt> {
+        let span = self.previous().span;
         // Optional redundant purr after zoom
-        let name = self.consume_identifier();
+        let name = self.consume_identifier()?;
 
-        self.consume_arrow();     // name ->
-        self.consume_symbol('(');
+        self.consume_arrow()?;     // name ->
+        self.consume_symbol('(')?;
 
         let mut params = Vec::new();
 
         if !self.check_symbol(')') {
             loop {
-                let param_name = self.consume_identifier();
+                let param_name = self.consume_identifier()?;
                 let mut default = None;
 
                 if self.match_symbol('=') {
-                    default = Some(self.expression());
+                    default = Some(self.expression()?);
                 }
 
                 params.push(Param {
@@ -221,31 +285,26 @@ impl  Parser {
             }
         }
 
-        self.consume_symbol(')');
-        self.consume_arrow();
+        self.consume_symbol(')')?;
+        self.consume_arrow()?;
 
         let mut return_type = None;
         if self.match_symbol(':') {
             let t = self.advance();
             return_type = Some(t.lexeme);
-            self.consume_arrow();
+            self.consume_arrow()?;
         }
 
-        self.consume_symbol('{');
-
-        let mut body = Vec::new();
-        while !self.check_symbol('}') {
-            body.push(self.statement());
-        }
-        self.consume_symbol('}');
+        let body = self.block()?;
 
-        Stmt::Function {
+        Ok(Stmt::Function {
             name,
             params,
             body,
             return_type,
             is_async,
-        }
+            span,
+        })
     }
 
     /// Parses a full PAWX `clowder` declaration (class definition).
@@ -283,11 +342,12 @@ impl  Parser {
     /// # Returns
     /// A fully constructed `Stmt::Clowder` AST node.
     ///
-    /// # Panics
+    /// # Errors
     /// - If invalid class syntax is detected
     /// - If getters/setters use illegal modifiers
     /// - If malformed inheritance or method blocks occur
-    pub fn clowder_declaration(&mut self, is_exported: bool, is_default: bool) -> Stmt {
+    pub fn clowder_declaration(&mut self, is_exported: bool, is_default: bool) -> ParseResult<Stmt> {
+        let span = self.previous().span;
         // ---------------------------------------------
         // Class Name
         // ---------------------------------------------
@@ -297,7 +357,7 @@ impl  Parser {
         let name = if self.match_keyword("new") {
             "new".to_string()
         } else {
-            self.consume_identifier()
+            self.consume_identifier()?
         };
 
         // ---------------------------------------------
@@ -307,7 +367,7 @@ impl  Parser {
         //   clowder Dog inherits Animal { ... }
         let mut base = None;
         if self.match_keyword("inherits") {
-            base = Some(self.consume_identifier());
+            base = Some(self.consume_identifier()?);
         }
 
         // ---------------------------------------------
@@ -318,7 +378,7 @@ impl  Parser {
         let mut interfaces = Vec::new();
         if self.match_keyword("practices") {
             loop {
-                interfaces.push(self.consume_identifier());
+                interfaces.push(self.consume_identifier()?);
                 if !self.match_symbol(',') {
                     break;
                 }
@@ -328,7 +388,7 @@ impl  Parser {
         // ---------------------------------------------
         // Begin Class Body
         // ---------------------------------------------
-        self.consume_symbol('{');
+        self.consume_symbol('{')?;
         let mut members = Vec::new();
 
         while !self.check_symbol('}') && !self.is_at_end() {
@@ -355,28 +415,23 @@ impl  Parser {
             // ---------------------------------------------
             if self.match_keyword("get") {
                 if is_static {
-                    panic!("static getters not supported yet");
+                    return Err(PawxError::syntax_error("static getters not supported yet", self.previous().span));
                 }
                 if access.is_some() {
-                    panic!("getters cannot use access modifiers (pride/den/lair)");
+                    return Err(PawxError::syntax_error("getters cannot use access modifiers (pride/den/lair)", self.previous().span));
                 }
 
-                let prop_name = self.consume_identifier();
-                self.consume_arrow();
+                let prop_name = self.consume_identifier()?;
+                self.consume_arrow()?;
 
                 let mut return_type = None;
                 if self.match_symbol(':') {
-                    let t = self.consume_identifier();
+                    let t = self.consume_identifier()?;
                     return_type = Some(t);
-                    self.consume_arrow();
+                    self.consume_arrow()?;
                 }
 
-                self.consume_symbol('{');
-                let mut body = Vec::new();
-                while !self.check_symbol('}') {
-                    body.push(self.statement());
-                }
-                self.consume_symbol('}');
+                let body = self.member_body(false)?;
 
                 members.push(ClassMember::Getter {
                     name: prop_name,
@@ -392,32 +447,35 @@ impl  Parser {
             // ---------------------------------------------
             if self.match_keyword("set") {
                 if is_static {
-                    panic!("static setters not supported yet");
+                    return Err(PawxError::syntax_error("static setters not supported yet", self.previous().span));
                 }
                 if access.is_some() {
-                    panic!("setters cannot use access modifiers (pride/den/lair)");
+                    return Err(PawxError::syntax_error("setters cannot use access modifiers (pride/den/lair)", self.previous().span));
                 }
 
-                let prop_name = self.consume_identifier();
-                self.consume_arrow();
-                self.consume_symbol('(');
+                let prop_name = self.consume_identifier()?;
+                self.consume_arrow()?;
+                self.consume_symbol('(')?;
 
-                let param_name = self.consume_identifier();
+                let param_name = self.consume_identifier()?;
                 let mut param_type = None;
                 if self.match_symbol(':') {
-                    let t = self.consume_identifier();
+                    let t = self.consume_identifier()?;
                     param_type = Some(t);
                 }
 
-                self.consume_symbol(')');
-                self.consume_arrow();
-
-                self.consume_symbol('{');
-                let mut body = Vec::new();
-                while !self.check_symbol('}') {
-                    body.push(self.statement());
+                // A setter always receives the assigned value
+                if self.match_symbol('=') {
+                    return Err(PawxError::syntax_error(
+                        format!("setter '{}' cannot give its parameter a default value", prop_name),
+                        self.previous().span,
+                    ));
                 }
-                self.consume_symbol('}');
+
+                self.consume_symbol(')')?;
+                self.consume_arrow()?;
+
+                let body = self.member_body(false)?;
 
                 members.push(ClassMember::Setter {
                     name: prop_name,
@@ -438,51 +496,23 @@ impl  Parser {
                     let name = if self.match_keyword("new") {
                         "new".to_string()
                     } else {
-                        self.consume_identifier()
+                        self.consume_identifier()?
                     };
 
-                    self.consume_arrow();
-                    self.consume_symbol('(');
-
-                    let mut params = Vec::new();
-                    if !self.check_symbol(')') {
-                        loop {
-                            let param_name = self.consume_identifier();
-                            let mut type_annotation = None;
-
-                            if self.match_symbol(':') {
-                                let t = self.consume_identifier();
-                                type_annotation = Some(t);
-                            }
-
-                            params.push(Param {
-                                name: param_name,
-                                default: None,
-                                type_annotation,
-                            });
-
-                            if !self.match_symbol(',') {
-                                break;
-                            }
-                        }
-                    }
+                    self.consume_arrow()?;
+                    self.consume_symbol('(')?;
 
-                    self.consume_symbol(')');
-                    self.consume_arrow();
+                    let params = self.method_params()?;
+                    self.consume_arrow()?;
 
                     let mut return_type = None;
                     if self.match_symbol(':') {
-                        let t = self.consume_identifier();
+                        let t = self.consume_identifier()?;
                         return_type = Some(t);
-                        self.consume_arrow();
+                        self.consume_arrow()?;
                     }
 
-                    self.consume_symbol('{');
-                    let mut body = Vec::new();
-                    while !self.check_symbol('}') {
-                        body.push(self.statement());
-                    }
-                    self.consume_symbol('}');
+                    let body = self.member_body(is_static)?;
 
                     members.push(ClassMember::Method {
                         name,
@@ -494,17 +524,20 @@ impl  Parser {
                     });
                 } else {
                     // Field
-                    let field_name = self.consume_identifier();
+                    let field_name = self.consume_identifier()?;
                     let mut type_annotation = None;
 
                     if self.match_symbol(':') {
-                        let t = self.consume_identifier();
+                        let t = self.consume_identifier()?;
                         type_annotation = Some(t);
                     }
 
                     let mut value = None;
                     if self.match_symbol('=') {
-                        value = Some(self.expression());
+                        let outer = std::mem::replace(&mut self.in_static, is_static);
+                        let expr = self.expression();
+                        self.in_static = outer;
+                        value = Some(expr?);
                     }
 
                     self.match_symbol(';');
@@ -528,51 +561,23 @@ impl  Parser {
                 let name = if self.match_keyword("new") {
                     "new".to_string()
                 } else {
-                    self.consume_identifier()
+                    self.consume_identifier()?
                 };
 
-                self.consume_arrow();
-                self.consume_symbol('(');
-
-                let mut params = Vec::new();
-                if !self.check_symbol(')') {
-                    loop {
-                        let param_name = self.consume_identifier();
-                        let mut type_annotation = None;
-
-                        if self.match_symbol(':') {
-                            let t = self.consume_identifier();
-                            type_annotation = Some(t);
-                        }
-
-                        params.push(Param {
-                            name: param_name,
-                            default: None,
-                            type_annotation,
-                        });
-
-                        if !self.match_symbol(',') {
-                            break;
-                        }
-                    }
-                }
+                self.consume_arrow()?;
+                self.consume_symbol('(')?;
 
-                self.consume_symbol(')');
-                self.consume_arrow();
+                let params = self.method_params()?;
+                self.consume_arrow()?;
 
                 let mut return_type = None;
                 if self.match_symbol(':') {
-                    let t = self.consume_identifier();
+                    let t = self.consume_identifier()?;
                     return_type = Some(t);
-                    self.consume_arrow();
+                    self.consume_arrow()?;
                 }
 
-                self.consume_symbol('{');
-                let mut body = Vec::new();
-                while !self.check_symbol('}') {
-                    body.push(self.statement());
-                }
-                self.consume_symbol('}');
+                let body = self.member_body(is_static)?;
 
                 members.push(ClassMember::Method {
                     name,
@@ -586,19 +591,68 @@ impl  Parser {
                 continue;
             }
 
-            panic!("Unexpected token in clowder body");
+            return Err(self.error_here("unexpected token in clowder body"));
         }
 
-        self.consume_symbol('}');
+        self.consume_symbol('}')?;
 
-        Stmt::Clowder {
+        Ok(Stmt::Clowder {
             name,
             base,
             interfaces,
             members,
             is_exported,
             is_default,
+            span,
+        })
+    }
+
+    /// Parses the block body of a clowder member, rejecting `this` inside
+    /// it if the member is static.
+    fn member_body(&mut self, is_static: bool) -> ParseResult<Vec<Stmt>> {
+        let outer = std::mem::replace(&mut self.in_static, is_static);
+        let body = self.block();
+        self.in_static = outer;
+        body
+    }
+
+    /// Parses a clowder method's parameter list after `(`, including the
+    /// closing `)`.
+    ///
+    /// Each parameter may carry a type and a default:
+    /// `purr greet -> (name: String, greeting = "Hello") -> { ... }`
+    fn method_params(&mut self) -> ParseResult<Vec<Param>> {
+        let mut params = Vec::new();
+
+        if !self.check_symbol(')') {
+            loop {
+                let param_name = self.consume_identifier()?;
+                let mut type_annotation = None;
+
+                if self.match_symbol(':') {
+                    let t = self.consume_identifier()?;
+                    type_annotation = Some(t);
+                }
+
+                let mut default = None;
+                if self.match_symbol('=') {
+                    default = Some(self.expression()?);
+                }
+
+                params.push(Param {
+                    name: param_name,
+                    default,
+                    type_annotation,
+                });
+
+                if !self.match_symbol(',') {
+                    break;
+                }
+            }
         }
+
+        self.consume_symbol(')')?;
+        Ok(params)
     }
 
     /// Parses a full PAWX `instinct` declaration (interface definition).
@@ -606,40 +660,69 @@ impl  Parser {
     /// Example:
     /// ```pawx
     /// instinct Animal {
-    ///     purr speak -> () -> :String;
+    ///     purr speak -> () -> :String -> ;
+    ///     get name -> ;
+    ///     set name -> (value) -> ;
     /// }
     /// ```
-    pub fn instinct_declaration(&mut self, is_exported: bool, is_default: bool) -> Stmt {
+    pub fn instinct_declaration(&mut self, is_exported: bool, is_default: bool) -> ParseResult<Stmt> {
+        let span = self.previous().span;
+
         // ---------------------------------------------
         // Interface Name
         // ---------------------------------------------
-        let name = self.consume_identifier();
+        let name = self.consume_identifier()?;
 
         // ---------------------------------------------
         // Begin Interface Body
         // ---------------------------------------------
-        self.consume_symbol('{');
+        self.consume_symbol('{')?;
         let mut members = Vec::new();
 
         while !self.check_symbol('}') && !self.is_at_end() {
-            // Only method signatures are allowed in instincts
-            let name = if self.match_keyword("purr") {
-                self.consume_identifier()
+            // Only method, getter and setter signatures are allowed in instincts
+            let kind = if self.match_keyword("purr") {
+                InstinctMemberKind::Method
+            } else if self.match_keyword("get") {
+                InstinctMemberKind::Getter
+            } else if self.match_keyword("set") {
+                InstinctMemberKind::Setter
             } else {
-                panic!("Only 'purr' method signatures allowed in instinct bodies");
+                return Err(self.error_here("only 'purr', 'get' and 'set' signatures are allowed in instinct bodies"));
             };
 
-            self.consume_arrow();
-            self.consume_symbol('(');
+            let name = self.consume_identifier()?;
+            self.consume_arrow()?;
+
+            // get name -> (:Type ->)? ;
+            if kind == InstinctMemberKind::Getter {
+                let mut return_type = None;
+                if self.match_symbol(':') {
+                    return_type = Some(self.consume_identifier()?);
+                    self.consume_arrow()?;
+                }
+
+                self.match_symbol(';');
+
+                members.push(InstinctMember {
+                    name,
+                    params: Vec::new(),
+                    return_type,
+                    kind,
+                });
+                continue;
+            }
+
+            self.consume_symbol('(')?;
 
             let mut params = Vec::new();
             if !self.check_symbol(')') {
                 loop {
-                    let param_name = self.consume_identifier();
+                    let param_name = self.consume_identifier()?;
                     let mut type_annotation = None;
 
                     if self.match_symbol(':') {
-                        let t = self.consume_identifier();
+                        let t = self.consume_identifier()?;
                         type_annotation = Some(t);
                     }
 
@@ -655,34 +738,42 @@ impl  Parser {
                 }
             }
 
-            self.consume_symbol(')');
-            self.consume_arrow();
+            self.consume_symbol(')')?;
+            self.consume_arrow()?;
 
             let mut return_type = None;
             if self.match_symbol(':') {
-                let t = self.consume_identifier();
+                let t = self.consume_identifier()?;
                 return_type = Some(t);
-                self.consume_arrow();
+                self.consume_arrow()?;
             }
 
             self.match_symbol(';');
 
+            if kind == InstinctMemberKind::Setter && params.len() != 1 {
+                return Err(PawxError::syntax_error(
+                    format!("setter '{}' must take exactly one parameter", name),
+                    self.previous().span,
+                ));
+            }
+
             members.push(InstinctMember {
                 name,
                 params,
                 return_type,
-                kind: InstinctMemberKind::Method,
+                kind,
             });
         }
 
-        self.consume_symbol('}');
+        self.consume_symbol('}')?;
 
-        Stmt::Instinct {
+        Ok(Stmt::Instinct {
             name,
             members,
             is_exported,
             is_default,
-        }
+            span,
+        })
     }
 
     /// Resolves a `pride` declaration into either:
@@ -694,45 +785,73 @@ impl  Parser {
     /// pride cats = 10;
     /// pride Config { ... }
     /// ```
-    pub fn pride_dispatch(&mut self) -> Stmt {
-        let name = self.consume_identifier();
+    pub fn pride_dispatch(&mut self) -> ParseResult<Stmt> {
+        let span = self.previous().span;
+        let name = self.consume_identifier()?;
 
         // Variable assignment form
         if self.match_symbol('=') {
-            let value = self.expression();
+            let value = self.expression()?;
             self.match_symbol(';');
-            return Stmt::PublicVar { name, value };
+            return Ok(Stmt::PublicVar { name, value, span });
         }
 
         // Named scope form
-        if self.match_symbol('{') {
-            let mut body = Vec::new();
-            while !self.check_symbol('}') {
-                body.push(self.statement());
+        if self.check_symbol('{') {
+            let body = self.block()?;
+            return Ok(Stmt::Pride { name, body, span });
+        }
+
+        Err(self.error_here(format!("expected '=' or '{{' after pride {}", name)))
+    }
+
+    /// Parses the names of a destructuring `snuggle`, after the keyword.
+    ///
+    /// Supported Forms:
+    /// ```pawx
+    /// snuggle { slugify, titleCase } = tap("utils");
+    /// ```
+    pub fn destructure_var(&mut self) -> ParseResult<Stmt> {
+        let span = self.previous().span;
+        self.consume_symbol('{')?;
+
+        let mut names = Vec::new();
+        while !self.check_symbol('}') {
+            names.push(self.consume_identifier()?);
+            if !self.match_symbol(',') {
+                break;
             }
-            self.consume_symbol('}');
-            return Stmt::Pride { name, body };
         }
+        self.consume_symbol('}')?;
 
-        panic!("Expected '=' or '{{' after pride {}", name);
+        if names.is_empty() {
+            return Err(self.error_here("expected at least one name between '{' and '}'"));
+        }
+
+        self.consume_symbol('=')?;
+        let value = self.expression()?;
+        self.match_symbol(';');
+        Ok(Stmt::DestructureVar { names, value, span })
     }
 
     /// Parses a `den` private variable declaration.
-    pub fn private_var(&mut self) -> Stmt {
-        let name = self.consume_identifier();
-        self.consume_symbol('=');
-        let value = self.expression();
+    pub fn private_var(&mut self) -> ParseResult<Stmt> {
+        let span = self.previous().span;
+        let name = self.consume_identifier()?;
+        self.consume_symbol('=')?;
+        let value = self.expression()?;
         self.match_symbol(';');
-        Stmt::PrivateVar { name, value }
+        Ok(Stmt::PrivateVar { name, value, span })
     }
 
     /// Parses a `lair` protected variable declaration.
-    pub fn protected_var(&mut self) -> Stmt {
-        let name = self.consume_identifier();
-        self.consume_symbol('=');
-        let value = self.expression();
+    pub fn protected_var(&mut self) -> ParseResult<Stmt> {
+        let span = self.previous().span;
+        let name = self.consume_identifier()?;
+        self.consume_symbol('=')?;
+        let value = self.expression()?;
         self.match_symbol(';');
-        Stmt::ProtectedVar { name, value }
+        Ok(Stmt::ProtectedVar { name, value, span })
     }
 
     /// Parses an `if / else if / else` control-flow construct.
@@ -744,133 +863,216 @@ impl  Parser {
     /// else { ... }
     /// else if x < 3 { ... }
     /// ```
-    pub fn if_statement(&mut self) -> Stmt {
+    pub fn if_statement(&mut self) -> ParseResult<Stmt> {
+        let span = self.previous().span;
         let condition = if self.match_symbol('(') {
-            let cond = self.expression();
-            self.consume_symbol(')');
+            let cond = self.expression()?;
+            self.consume_symbol(')')?;
             cond
         } else {
-            self.expression()
+            self.expression()?
         };
 
-        self.consume_symbol('{');
-        let mut then_branch = Vec::new();
-        while !self.check_symbol('}') {
-            then_branch.push(self.statement());
-        }
-        self.consume_symbol('}');
+        let then_branch = self.block()?;
 
         let mut else_branch = None;
 
         if self.match_keyword("else") {
             if self.match_keyword("if") {
-                let nested_if = self.if_statement();
+                let nested_if = self.if_statement()?;
                 else_branch = Some(vec![nested_if]);
             } else {
-                self.consume_symbol('{');
-                let mut else_body = Vec::new();
-                while !self.check_symbol('}') {
-                    else_body.push(self.statement());
-                }
-                self.consume_symbol('}');
+                let else_body = self.block()?;
+                else_branch = Some(else_body);
+            }
+        }
+
+        Ok(Stmt::If {
+            condition,
+            then_branch,
+            else_branch,
+            span,
+        })
+    }
+
+    /// Parses a compile-time `when / else when / else` block.
+    ///
+    /// Supported Forms:
+    /// ```pawx
+    /// when ENV == "production" { ... }
+    /// when (DEBUG) { ... } else { ... }
+    /// else when TARGET == "wasm" { ... }
+    /// ```
+    pub fn when_statement(&mut self) -> ParseResult<Stmt> {
+        let span = self.previous().span;
+        let condition = self.expression()?;
+
+        let then_branch = self.block()?;
+
+        let mut else_branch = None;
+
+        if self.match_keyword("else") {
+            if self.match_keyword("when") {
+                else_branch = Some(vec![self.when_statement()?]);
+            } else {
+                let else_body = self.block()?;
                 else_branch = Some(else_body);
             }
         }
 
-        Stmt::If {
+        Ok(Stmt::When {
             condition,
             then_branch,
             else_branch,
+            span,
+        })
+    }
+
+    /// Recognizes the head of a test block without consuming it.
+    ///
+    /// Returns the block's mode and how many tokens precede its name:
+    /// `test` / `it` (1), or `test.only` / `test.skip` (3).
+    fn test_block_head(&self) -> Option<(TestMode, usize)> {
+        let token = |offset: usize| self.tokens.get(self.current + offset);
+
+        let head = token(0)?;
+        if head.kind != TokenKind::Identifier || (head.lexeme != "test" && head.lexeme != "it") {
+            return None;
         }
+
+        let (mode, len) = match (token(1).map(|t| t.lexeme.as_str()), token(2).map(|t| t.lexeme.as_str())) {
+            (Some("."), Some("only")) => (TestMode::Only, 3),
+            (Some("."), Some("skip")) => (TestMode::Skip, 3),
+            _ => (TestMode::Normal, 1),
+        };
+
+        let named = token(len).is_some_and(|t| t.kind == TokenKind::String)
+            && token(len + 1).is_some_and(|t| t.lexeme == "{");
+        named.then_some((mode, len))
     }
 
     /// Parses a `while` loop.
-    pub fn while_statement(&mut self) -> Stmt {
-        self.consume_symbol('(');
-        let condition = self.expression();
-        self.consume_symbol(')');
+    pub fn while_statement(&mut self) -> ParseResult<Stmt> {
+        let span = self.previous().span;
+        self.consume_symbol('(')?;
+        let condition = self.expression()?;
+        self.consume_symbol(')')?;
+
+        let body = self.block()?;
+
+        Ok(Stmt::While { condition, body, span })
+    }
 
-        self.consume_symbol('{');
-        let mut body = Vec::new();
+    /// Parses a `match` statement.
+    ///
+    /// ```pawx
+    /// match (response) {
+    ///     case {status: 404} { meow("missing") }
+    ///     case {status: 200, body} { meow(body) }
+    ///     case {status: 301}, {status: 302} { meow("moved") }
+    ///     case {status} if status >= 500 { meow("server error") }
+    ///     default { meow("unexpected") }
+    /// }
+    /// ```
+    pub fn match_statement(&mut self) -> ParseResult<Stmt> {
+        let span = self.previous().span;
+        let subject = if self.match_symbol('(') {
+            let subject = self.expression()?;
+            self.consume_symbol(')')?;
+            subject
+        } else {
+            self.expression()?
+        };
+
+        self.consume_symbol('{')?;
+
+        let mut arms = Vec::new();
         while !self.check_symbol('}') {
-            body.push(self.statement());
+            let mut patterns = Vec::new();
+            let mut guard = None;
+
+            if self.match_keyword("case") {
+                patterns.push(self.pattern()?);
+                while self.match_symbol(',') {
+                    patterns.push(self.pattern()?);
+                }
+
+                if self.match_keyword("if") {
+                    guard = Some(self.expression()?);
+                }
+            } else if self.match_keyword("default") {
+                patterns.push(Pattern::Wildcard);
+            } else {
+                return Err(self.error_here("expected 'case' or 'default' in match"));
+            }
+
+            let body = self.block()?;
+
+            arms.push(MatchArm { patterns, guard, body });
         }
-        self.consume_symbol('}');
+        self.consume_symbol('}')?;
 
-        Stmt::While { condition, body }
+        Ok(Stmt::Match { subject, arms, span })
     }
 
     /// Parses a function `return` statement.
-    pub fn return_statement(&mut self) -> Stmt {
+    pub fn return_statement(&mut self) -> ParseResult<Stmt> {
+        let span = self.previous().span;
         if self.match_symbol(';') {
-            return Stmt::Return(None);
+            return Ok(Stmt::Return { value: None, span });
         }
 
-        let expr = self.expression();
+        let expr = self.expression()?;
         self.match_symbol(';');
-        Stmt::Return(Some(expr))
+        Ok(Stmt::Return { value: Some(expr), span })
     }
 
     /// Parses a standalone expression used as a statement.
-    pub fn expression_statement(&mut self) -> Stmt {
-        let expr = self.expression();
+    pub fn expression_statement(&mut self) -> ParseResult<Stmt> {
+        let expr = self.expression()?;
         self.match_symbol(';');
-        Stmt::Expression(expr)
+        Ok(Stmt::Expression(expr))
     }
 
     /// Parses a full exception-handling block:
     /// - `try {}`
     /// - optional `catch(e) {}`
     /// - optional `finally {}`
-    pub fn try_statement(&mut self) -> Stmt {
-        self.consume_symbol('{');
-
-        let mut try_block = Vec::new();
-        while !self.check_symbol('}') {
-            try_block.push(self.statement());
-        }
-        self.consume_symbol('}');
+    pub fn try_statement(&mut self) -> ParseResult<Stmt> {
+        let span = self.previous().span;
+        let try_block = self.block()?;
 
         let mut catch_param = None;
         let mut catch_block = None;
 
         if self.match_keyword("catch") {
-            self.consume_symbol('(');
+            self.consume_symbol('(')?;
 
             let name = if self.match_keyword("new") {
                 "new".to_string()
             } else {
-                self.consume_identifier()
+                self.consume_identifier()?
             };
 
-            self.consume_symbol(')');
+            self.consume_symbol(')')?;
             catch_param = Some(name);
 
-            self.consume_symbol('{');
-            let mut body = Vec::new();
-            while !self.check_symbol('}') {
-                body.push(self.statement());
-            }
-            self.consume_symbol('}');
+            let body = self.block()?;
             catch_block = Some(body);
         }
 
         let mut finally_block = None;
         if self.match_keyword("finally") {
-            self.consume_symbol('{');
-            let mut body = Vec::new();
-            while !self.check_symbol('}') {
-                body.push(self.statement());
-            }
-            self.consume_symbol('}');
+            let body = self.block()?;
             finally_block = Some(body);
         }
 
-        Stmt::Try {
+        Ok(Stmt::Try {
             try_block,
             catch_param,
             catch_block,
             finally_block,
-        }
+            span,
+        })
     }   
 }
\ No newline at end of file
@@ -8,11 +8,13 @@
  * JavaScript-style `Array` prototype used by the PAWX runtime.
  * 
  * It provides core methods such as:
- *   - push, pop, sort
+ *   - push, pop, sort (comparator, or { natural, ignoreCase, descending })
  *   - map, filter, slice, join
  *   - forEach, find, includes
  *   - some, every
  *   - reduce, reduceRight
+ *   - groupBy, countBy, partition, chunk, zip, unique, flatten
+ *   - iter (lazy chains, see lazy.rs)
  * 
  * These functions are installed once onto the global Array prototype
  * and are shared by all array instances in PAWX.
@@ -42,11 +44,15 @@
  */
 
 use std::cell::RefCell;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::rc::Rc;
 use std::sync::Arc;
 
-use crate::value::Value;
+use crate::error::PawxError;
+use crate::interpreter::calls::{builtin_error, raise_error};
+use crate::prototypes::lazy;
+use crate::prototypes::slice;
+use crate::value::{NativeFn, Value};
 
  /// Installs all built-in Array prototype methods into the PAWX runtime.
  /// 
@@ -63,8 +69,7 @@ use crate::value::Value;
  /// - `proto`: The root Array prototype object used by the VM.
  /// 
  /// # Behavior
- /// This function **mutates the prototype object in-place** by attaching
- /// native Rust-backed functions as callable PAWX methods.
+ /// The prototype is built once per thread; every array shares it.
  /// 
  /// # PAWX Example
  /// ```pawx
@@ -72,7 +77,14 @@ use crate::value::Value;
  /// nums.push(4);
  /// meow(nums);
  /// ```
-pub fn create_array_proto() -> HashMap<String, Value> {
+pub fn create_array_proto() -> Rc<HashMap<String, Value>> {
+    thread_local! {
+        static PROTO: Rc<HashMap<String, Value>> = Rc::new(build_array_proto());
+    }
+    PROTO.with(Rc::clone)
+}
+
+fn build_array_proto() -> HashMap<String, Value> {
     let mut proto = HashMap::new();
 
     // Mutating methods
@@ -102,6 +114,18 @@ pub fn create_array_proto() -> HashMap<String, Value> {
         Value::NativeFunction(Arc::new(array_reduce_right)),
     );
 
+    // Data wrangling
+    proto.insert("groupBy".to_string(), Value::NativeFunction(Arc::new(array_group_by)));
+    proto.insert("countBy".to_string(), Value::NativeFunction(Arc::new(array_count_by)));
+    proto.insert("partition".to_string(), Value::NativeFunction(Arc::new(array_partition)));
+    proto.insert("chunk".to_string(), Value::NativeFunction(Arc::new(array_chunk)));
+    proto.insert("zip".to_string(), Value::NativeFunction(Arc::new(array_zip)));
+    proto.insert("unique".to_string(), Value::NativeFunction(Arc::new(array_unique)));
+    proto.insert("flatten".to_string(), Value::NativeFunction(Arc::new(array_flatten)));
+
+    // Lazy chains
+    proto.insert("iter".to_string(), Value::NativeFunction(Arc::new(lazy::array_iter)));
+
     proto.insert(
         "toString".to_string(),
         Value::NativeFunction(Arc::new(array_to_string)),
@@ -119,9 +143,9 @@ pub fn create_global_array_object() -> Value {
         "isArray".to_string(),
         Value::NativeFunction(Arc::new(|args| {
             if let Some(Value::Array { .. }) = args.get(0) {
-                Value::Bool(true)
+                Ok(Value::Bool(true))
             } else {
-                Value::Bool(false)
+                Ok(Value::Bool(false))
             }
         })),
     );
@@ -154,10 +178,10 @@ pub fn create_global_array_object() -> Value {
 /// nums.push(3);
 /// meow(nums); // [1, 2, 3]
 /// ```
-fn array_push(args: Vec<Value>) -> Value {
+fn array_push(args: Vec<Value>) -> Result<Value, PawxError> {
     let array = match &args[0] {
         Value::Array { values, .. } => values.clone(),
-        _ => panic!("push() must be called on an array"),
+        _ => return raise_error("push() must be called on an array"),
     };
 
     let val = args.get(1).cloned().unwrap_or(Value::Null);
@@ -174,7 +198,7 @@ fn array_push(args: Vec<Value>) -> Value {
         borrowed.len()
     };
 
-    Value::Number(len as f64)
+    Ok(Value::Number(len as f64))
 }
 
 /// Native implementation of `Array.prototype.pop()` for PAWX.
@@ -200,10 +224,10 @@ fn array_push(args: Vec<Value>) -> Value {
 /// meow(last); // 30
 /// meow(nums); // [10, 20]
 /// ```
-fn array_pop(args: Vec<Value>) -> Value {
+fn array_pop(args: Vec<Value>) -> Result<Value, PawxError> {
     let array = match &args[0] {
         Value::Array { values, .. } => values.clone(),
-        _ => panic!("pop() must be called on an array"),
+        _ => return raise_error("pop() must be called on an array"),
     };
 
     // Force the mutable borrow to drop before return
@@ -212,7 +236,7 @@ fn array_pop(args: Vec<Value>) -> Value {
         borrowed.pop()
     };
 
-    result.unwrap_or(Value::Null)
+    Ok(result.unwrap_or(Value::Null))
 }
 
 /// Native implementation of `Array.prototype.map()` for PAWX.
@@ -238,10 +262,10 @@ fn array_pop(args: Vec<Value>) -> Value {
 /// snuggle doubled = nums.map(n -> { return n * 2; });
 /// meow(doubled); // [2, 4, 6]
 /// ```
-fn array_map(args: Vec<Value>) -> Value {
+fn array_map(args: Vec<Value>) -> Result<Value, PawxError> {
     let array = match &args[0] {
         Value::Array { values, .. } => values.borrow().clone(),
-        _ => panic!("map() must be called on an array"),
+        _ => return raise_error("map() must be called on an array"),
     };
 
     let callback = args.get(1).cloned().unwrap();
@@ -249,14 +273,14 @@ fn array_map(args: Vec<Value>) -> Value {
     let mut new_vals = Vec::new();
     for v in array {
         if let Value::NativeFunction(f) = &callback {
-            new_vals.push(f(vec![v]));
+            new_vals.push(f(vec![v])?);
         }
     }
 
-    Value::Array {
+    Ok(Value::Array {
         values: Rc::new(RefCell::new(new_vals)),
         proto: create_array_proto(),
-    }
+    })
 }
 
 
@@ -269,43 +293,40 @@ fn array_map(args: Vec<Value>) -> Value {
 /// - `args[0]`: The source array.
 /// - `args[1]` (optional): Start index.
 /// - `args[2]` (optional): End index (non-inclusive).
+/// - `args[3]` (optional): Step; negative walks backwards.
 ///
 /// # Returns
 /// A **new PAWX array** containing the selected elements.
 ///
 /// # Behavior
-/// - Supports negative indices.
+/// - Supports negative indices; out-of-range indices are clamped.
+/// - `null` start / end mean "from the edge".
 /// - Does not mutate the original array.
-/// - Fully matches JavaScript slice semantics.
+/// - Follows Python slice rules (see `prototypes::slice`).
 ///
 /// # PAWX Example
 /// ```pawx
 /// snuggle nums = [1, 2, 3, 4];
-/// snuggle part = nums.slice(1, 3);
-/// meow(part); // [2, 3]
+/// meow(nums.slice(1, 3));           // [2, 3]
+/// meow(nums.slice(-2));             // [3, 4]
+/// meow(nums.slice(0, 4, 2));        // [1, 3]
+/// meow(nums.slice(null, null, -1)); // [4, 3, 2, 1]
 /// ```
-fn array_slice(args: Vec<Value>) -> Value {
+fn array_slice(args: Vec<Value>) -> Result<Value, PawxError> {
     let array = match &args[0] {
         Value::Array { values, .. } => values.borrow().clone(),
-        _ => panic!("slice() must be called on an array"),
+        _ => return raise_error("slice() must be called on an array"),
     };
 
-    let start = match args.get(1) {
-        Some(Value::Number(n)) => *n as usize,
-        _ => 0,
-    };
+    let sliced = slice::positions(&args[1..], array.len(), "slice(start?, end?, step?)")?
+        .into_iter()
+        .map(|i| array[i].clone())
+        .collect();
 
-    let end = match args.get(2) {
-        Some(Value::Number(n)) => *n as usize,
-        _ => array.len(),
-    };
-
-    let sliced = array[start..end].to_vec();
-
-    Value::Array {
+    Ok(Value::Array {
         values: Rc::new(RefCell::new(sliced)),
         proto: create_array_proto(),
-    }
+    })
 }
 
 /// Native implementation of `Array.prototype.forEach()` for PAWX.
@@ -330,21 +351,21 @@ fn array_slice(args: Vec<Value>) -> Value {
 ///     meow(n);
 /// });
 /// ```
-fn array_foreach(args: Vec<Value>) -> Value {
+fn array_foreach(args: Vec<Value>) -> Result<Value, PawxError> {
     let array = match &args[0] {
         Value::Array { values, .. } => values.borrow().clone(),
-        _ => panic!("forEach() must be called on an array"),
+        _ => return raise_error("forEach() must be called on an array"),
     };
 
     let callback = args.get(1).cloned().unwrap();
 
     for v in array {
         if let Value::NativeFunction(f) = &callback {
-            f(vec![v]);
+            f(vec![v])?;
         }
     }
 
-    Value::Null
+    Ok(Value::Null)
 }
 
 /// Native implementation of `Array.prototype.filter()` for PAWX.
@@ -369,10 +390,10 @@ fn array_foreach(args: Vec<Value>) -> Value {
 /// snuggle evens = [1, 2, 3, 4].filter(n -> n % 2 == 0);
 /// meow(evens); // [2, 4]
 /// ```
-fn array_filter(args: Vec<Value>) -> Value {
+fn array_filter(args: Vec<Value>) -> Result<Value, PawxError> {
     let array = match &args[0] {
         Value::Array { values, .. } => values.borrow().clone(),
-        _ => panic!("filter() must be called on an array"),
+        _ => return raise_error("filter() must be called on an array"),
     };
 
     let callback = args.get(1).cloned().unwrap();
@@ -380,17 +401,17 @@ fn array_filter(args: Vec<Value>) -> Value {
     let mut new_vals = Vec::new();
     for v in array {
         if let Value::NativeFunction(f) = &callback {
-            let keep = f(vec![v.clone()]);
+            let keep = f(vec![v.clone()])?;
             if matches!(keep, Value::Bool(true)) {
                 new_vals.push(v);
             }
         }
     }
 
-    Value::Array {
+    Ok(Value::Array {
         values: Rc::new(RefCell::new(new_vals)),
         proto: create_array_proto(),
-    }
+    })
 }
 
 /// Native implementation of `Array.prototype.find()` for PAWX.
@@ -415,24 +436,24 @@ fn array_filter(args: Vec<Value>) -> Value {
 /// snuggle found = [5, 12, 8].find(n -> n > 10);
 /// meow(found); // 12
 /// ```
-fn array_find(args: Vec<Value>) -> Value {
+fn array_find(args: Vec<Value>) -> Result<Value, PawxError> {
     let array = match &args[0] {
         Value::Array { values, .. } => values.borrow().clone(),
-        _ => panic!("find() must be called on an array"),
+        _ => return raise_error("find() must be called on an array"),
     };
 
     let callback = args.get(1).cloned().unwrap();
 
     for v in array {
         if let Value::NativeFunction(f) = &callback {
-            let found = f(vec![v.clone()]);
+            let found = f(vec![v.clone()])?;
             if matches!(found, Value::Bool(true)) {
-                return v;
+                return Ok(v);
             }
         }
     }
 
-    Value::Null
+    Ok(Value::Null)
 }
 
 /// Native implementation of `Array.prototype.reduce()` for PAWX.
@@ -457,10 +478,10 @@ fn array_find(args: Vec<Value>) -> Value {
 /// snuggle sum = [1, 2, 3].reduce((a, b) -> a + b);
 /// meow(sum); // 6
 /// ```
-fn array_reduce(args: Vec<Value>) -> Value {
+fn array_reduce(args: Vec<Value>) -> Result<Value, PawxError> {
     let array = match &args[0] {
         Value::Array { values, .. } => values.borrow().clone(),
-        _ => panic!("reduce() must be called on array"),
+        _ => return raise_error("reduce() must be called on array"),
     };
 
     let callback = args.get(1).cloned().unwrap();
@@ -468,11 +489,11 @@ fn array_reduce(args: Vec<Value>) -> Value {
 
     for v in array {
         if let Value::NativeFunction(f) = &callback {
-            acc = f(vec![acc, v]);
+            acc = f(vec![acc, v])?;
         }
     }
 
-    acc
+    Ok(acc)
 }
 
 /// Native implementation of `Array.prototype.reduceRight()` for PAWX.
@@ -497,10 +518,10 @@ fn array_reduce(args: Vec<Value>) -> Value {
 /// snuggle result = ["a", "b", "c"].reduceRight((a, b) -> a + b);
 /// meow(result); // "cba"
 /// ```
-fn array_reduce_right(args: Vec<Value>) -> Value {
+fn array_reduce_right(args: Vec<Value>) -> Result<Value, PawxError> {
     let array = match &args[0] {
         Value::Array { values, .. } => values.borrow().clone(),
-        _ => panic!("reduceRight() must be called on array"),
+        _ => return raise_error("reduceRight() must be called on array"),
     };
 
     let callback = args.get(1).cloned().unwrap();
@@ -508,11 +529,11 @@ fn array_reduce_right(args: Vec<Value>) -> Value {
 
     for v in array.into_iter().rev() {
         if let Value::NativeFunction(f) = &callback {
-            acc = f(vec![acc, v]);
+            acc = f(vec![acc, v])?;
         }
     }
 
-    acc
+    Ok(acc)
 }
 
 /// Native implementation of `Array.prototype.includes()` for PAWX.
@@ -534,21 +555,21 @@ fn array_reduce_right(args: Vec<Value>) -> Value {
 /// ```pawx
 /// meow([1, 2, 3].includes(2)); // true
 /// ```
-fn array_includes(args: Vec<Value>) -> Value {
+fn array_includes(args: Vec<Value>) -> Result<Value, PawxError> {
     let array = match &args[0] {
         Value::Array { values, .. } => values.borrow(),
-        _ => panic!("includes() must be called on an array"),
+        _ => return raise_error("includes() must be called on an array"),
     };
 
     let target = args.get(1).cloned().unwrap_or(Value::Null);
 
     for v in array.iter() {
-        if Value::equals_strict(v, &target) {
-            return Value::Bool(true);
+        if Value::equals_strict(v, &target)? {
+            return Ok(Value::Bool(true));
         }
     }
 
-    Value::Bool(false)
+    Ok(Value::Bool(false))
 }
 
 /// Native implementation of `Array.prototype.some()` for PAWX.
@@ -570,23 +591,23 @@ fn array_includes(args: Vec<Value>) -> Value {
 /// ```pawx
 /// meow([1, 3, 5].some(n -> n % 2 == 0)); // false
 /// ```
-fn array_some(args: Vec<Value>) -> Value {
+fn array_some(args: Vec<Value>) -> Result<Value, PawxError> {
     let array = match &args[0] {
         Value::Array { values, .. } => values.borrow().clone(),
-        _ => panic!("some() must be called on an array"),
+        _ => return raise_error("some() must be called on an array"),
     };
 
     let callback = args.get(1).cloned().unwrap();
 
     for v in array {
         if let Value::NativeFunction(f) = &callback {
-            if let Value::Bool(true) = f(vec![v]) {
-                return Value::Bool(true);
+            if let Value::Bool(true) = f(vec![v])? {
+                return Ok(Value::Bool(true));
             }
         }
     }
 
-    Value::Bool(false)
+    Ok(Value::Bool(false))
 }
 
 /// Native implementation of `Array.prototype.every()` for PAWX.
@@ -608,23 +629,23 @@ fn array_some(args: Vec<Value>) -> Value {
 /// ```pawx
 /// meow([2, 4, 6].every(n -> n % 2 == 0)); // true
 /// ```
-fn array_every(args: Vec<Value>) -> Value {
+fn array_every(args: Vec<Value>) -> Result<Value, PawxError> {
     let array = match &args[0] {
         Value::Array { values, .. } => values.borrow().clone(),
-        _ => panic!("every() must be called on an array"),
+        _ => return raise_error("every() must be called on an array"),
     };
 
     let callback = args.get(1).cloned().unwrap();
 
     for v in array {
         if let Value::NativeFunction(f) = &callback {
-            if let Value::Bool(false) = f(vec![v]) {
-                return Value::Bool(false);
+            if let Value::Bool(false) = f(vec![v])? {
+                return Ok(Value::Bool(false));
             }
         }
     }
 
-    Value::Bool(true)
+    Ok(Value::Bool(true))
 }
 
 /// Native implementation of `Array.prototype.join()` for PAWX.
@@ -648,21 +669,21 @@ fn array_every(args: Vec<Value>) -> Value {
 /// snuggle s = [1, 2, 3].join("-");
 /// meow(s); // "1-2-3"
 /// ```
-fn array_join(args: Vec<Value>) -> Value {
+fn array_join(args: Vec<Value>) -> Result<Value, PawxError> {
     let array = match &args[0] {
         Value::Array { values, .. } => values.borrow().clone(),
-        _ => panic!("join() must be called on an array"),
+        _ => return raise_error("join() must be called on an array"),
     };
 
     let sep = match args.get(1) {
-        Some(Value::String(s)) => s.clone(),
+        Some(Value::String(s)) => s.to_string(),
         _ => ",".to_string(),
     };
 
     let mut strings = Vec::new();
     for v in array {
         strings.push(match v {
-            Value::String(s) => s,
+            Value::String(s) => s.to_string(),
             Value::Number(n) => n.to_string(),
             Value::Bool(b) => b.to_string(),
             Value::Null => "null".to_string(),
@@ -670,7 +691,7 @@ fn array_join(args: Vec<Value>) -> Value {
         });
     }
 
-    Value::String(strings.join(&sep))
+    Ok(Value::String(strings.join(&sep).into()))
 }
 
 /// Native implementation of `Array.prototype.sort()` for PAWX.
@@ -679,7 +700,8 @@ fn array_join(args: Vec<Value>) -> Value {
 ///
 /// # Parameters (via `args`)
 /// - `args[0]`: The source array.
-/// - `args[1]` (optional): Comparison function `(a, b) -> number`
+/// - `args[1]` (optional): Comparison function `(a, b) -> number`, or an
+///   options object `{ natural?, ignoreCase?, descending? }`
 ///
 /// # Returns
 /// The **mutated** sorted array (JS-compatible).
@@ -687,30 +709,50 @@ fn array_join(args: Vec<Value>) -> Value {
 /// # Behavior
 /// - Defaults to lexicographical sort.
 /// - Fully supports custom comparator functions.
+/// - `natural` orders digit runs by value ("img2" before "img10") and
+///   `ignoreCase` compares strings without case; neither depends on the
+///   machine's locale. Both only change how strings compare.
 ///
 /// # PAWX Example
 /// ```pawx
 /// snuggle nums = [3, 1, 2];
 /// nums.sort();
 /// meow(nums); // [1, 2, 3]
+///
+/// Fs.readdir("./shots").sort({ natural: true, ignoreCase: true });
 /// ```
-fn array_sort(args: Vec<Value>) -> Value {
+fn array_sort(args: Vec<Value>) -> Result<Value, PawxError> {
     let array_rc = match &args[0] {
         Value::Array { values, .. } => values.clone(),
-        _ => panic!("sort() must be called on an array"),
+        _ => return raise_error("sort() must be called on an array"),
     };
 
     let maybe_cmp = args.get(1).cloned();
 
+    let (natural, ignore_case, descending) = match &maybe_cmp {
+        Some(Value::Object { fields }) => {
+            let fields = fields.borrow();
+            let flag = |name: &str| fields.get(name).is_some_and(Value::is_truthy);
+            (flag("natural"), flag("ignoreCase"), flag("descending"))
+        }
+        None | Some(Value::Null) | Some(Value::NativeFunction(_)) => (false, false, false),
+        Some(_) => return raise_error("sort() expects a comparator function or an options object"),
+    };
+
     let mut borrowed = array_rc.borrow_mut();
 
+    // The first error from the comparator; later comparisons are skipped
+    let mut failed = None;
+
     borrowed.sort_by(|a, b| {
         // If user provided comparator: use it
         if let Some(Value::NativeFunction(f)) = &maybe_cmp {
-            let result = f(vec![a.clone(), b.clone()]);
+            if failed.is_some() {
+                return std::cmp::Ordering::Equal;
+            }
 
-            match result {
-                Value::Number(n) => {
+            match f(vec![a.clone(), b.clone()]) {
+                Ok(Value::Number(n)) => {
                     if n < 0.0 {
                         std::cmp::Ordering::Less
                     } else if n > 0.0 {
@@ -719,32 +761,47 @@ fn array_sort(args: Vec<Value>) -> Value {
                         std::cmp::Ordering::Equal
                     }
                 }
-                _ => panic!("sort() comparator must return a number"),
+                Ok(_) => {
+                    failed = Some(builtin_error("sort() comparator must return a number"));
+                    std::cmp::Ordering::Equal
+                }
+                Err(error) => {
+                    failed = Some(error);
+                    std::cmp::Ordering::Equal
+                }
             }
         }
         // Default JS-like sort behavior
         else {
-            match (a, b) {
+            let order = match (a, b) {
                 (Value::Number(x), Value::Number(y)) => {
                     x.partial_cmp(y).unwrap_or(std::cmp::Ordering::Equal)
                 }
-                (Value::String(x), Value::String(y)) => x.cmp(y),
+                (Value::String(x), Value::String(y)) => {
+                    crate::prototypes::string::compare_strings(x, y, natural, ignore_case)
+                }
                 _ => std::cmp::Ordering::Equal,
-            }
+            };
+
+            if descending { order.reverse() } else { order }
         }
     });
 
+    if let Some(error) = failed {
+        return Err(error);
+    }
+
     // Return same array (chainable)
-    Value::Array {
+    Ok(Value::Array {
         values: array_rc.clone(),
         proto: create_array_proto(),
-    }
+    })
 }
 
-fn array_to_string(args: Vec<Value>) -> Value {
+fn array_to_string(args: Vec<Value>) -> Result<Value, PawxError> {
     let array = match &args[0] {
         Value::Array { values, .. } => values.borrow(),
-        _ => panic!("toString() must be called on an array"),
+        _ => return raise_error("toString() must be called on an array"),
     };
 
     // JS behavior: join with commas, no brackets
@@ -754,5 +811,267 @@ fn array_to_string(args: Vec<Value>) -> Value {
         .collect::<Vec<_>>()
         .join(",");
 
-    Value::String(inner)
-}
\ No newline at end of file
+    Ok(Value::String(inner.into()))
+}
+/* ============================================================================
+ * DATA WRANGLING
+ * ============================================================================
+ */
+
+/// Wraps `values` in a new PAWX array.
+fn new_array(values: Vec<Value>) -> Value {
+    Value::Array {
+        values: Rc::new(RefCell::new(values)),
+        proto: create_array_proto(),
+    }
+}
+
+/// The elements of the receiver, copied out of the array.
+fn elements(args: &[Value], usage: &str) -> Result<Vec<Value>, PawxError> {
+    match args.first() {
+        Some(Value::Array { values, .. }) => Ok(values.borrow().clone()),
+        _ => raise_error(format!("{} must be called on an array", usage)),
+    }
+}
+
+/// The callback passed as the first argument.
+fn callback(args: &[Value], usage: &str) -> Result<Arc<NativeFn>, PawxError> {
+    match args.get(1) {
+        Some(Value::NativeFunction(f)) => Ok(f.clone()),
+        _ => raise_error(format!("{} expects a function", usage)),
+    }
+}
+
+/// Native implementation of `Array.prototype.groupBy()` for PAWX.
+///
+/// Groups elements by the key `fn(value, index)` returns.
+///
+/// # Returns
+/// An `Immutable.map` from each key (as a string) to an array of the
+/// elements that produced it, in their original order. Like every
+/// immutable map, its keys iterate in sorted order, so the groups come out
+/// the same way on every run.
+///
+/// # PAWX Example
+/// ```pawx
+/// snuggle byLen = ["cat", "dog", "mouse"].groupBy(s -> s.length);
+/// meow(byLen.get("3")); // [cat, dog]
+/// meow(byLen.keys());   // [3, 5]
+/// ```
+fn array_group_by(args: Vec<Value>) -> Result<Value, PawxError> {
+    let array = elements(&args, "groupBy(fn)")?;
+    let f = callback(&args, "groupBy(fn)")?;
+
+    let mut groups: BTreeMap<String, Vec<Value>> = BTreeMap::new();
+    for (i, v) in array.into_iter().enumerate() {
+        let key = f(vec![v.clone(), Value::Number(i as f64)])?.stringify();
+        groups.entry(key).or_default().push(v);
+    }
+
+    Ok(Value::ImmutableMap(groups.into_iter().map(|(k, vs)| (k, new_array(vs))).collect()))
+}
+
+/// Native implementation of `Array.prototype.countBy()` for PAWX.
+///
+/// Like `groupBy`, but each key maps to how many elements produced it.
+///
+/// # PAWX Example
+/// ```pawx
+/// meow([1, 2, 3, 4, 5].countBy(n -> n % 2 == 0).toObject()); // { false: 3, true: 2 }
+/// ```
+fn array_count_by(args: Vec<Value>) -> Result<Value, PawxError> {
+    let array = elements(&args, "countBy(fn)")?;
+    let f = callback(&args, "countBy(fn)")?;
+
+    let mut counts: BTreeMap<String, f64> = BTreeMap::new();
+    for (i, v) in array.into_iter().enumerate() {
+        let key = f(vec![v, Value::Number(i as f64)])?.stringify();
+        *counts.entry(key).or_default() += 1.0;
+    }
+
+    Ok(Value::ImmutableMap(counts.into_iter().map(|(k, n)| (k, Value::Number(n))).collect()))
+}
+
+/// Native implementation of `Array.prototype.partition()` for PAWX.
+///
+/// Splits the array by a predicate `fn(value, index)`.
+///
+/// # Returns
+/// `[matching, rest]`, both in their original order.
+///
+/// # PAWX Example
+/// ```pawx
+/// snuggle split = [1, 2, 3, 4].partition(n -> n % 2 == 0);
+/// meow(split[0], split[1]); // [2, 4] [1, 3]
+/// ```
+fn array_partition(args: Vec<Value>) -> Result<Value, PawxError> {
+    let array = elements(&args, "partition(fn)")?;
+    let f = callback(&args, "partition(fn)")?;
+
+    let (mut yes, mut no) = (Vec::new(), Vec::new());
+    for (i, v) in array.into_iter().enumerate() {
+        if f(vec![v.clone(), Value::Number(i as f64)])?.is_truthy() {
+            yes.push(v);
+        } else {
+            no.push(v);
+        }
+    }
+
+    Ok(new_array(vec![new_array(yes), new_array(no)]))
+}
+
+/// Native implementation of `Array.prototype.chunk()` for PAWX.
+///
+/// Splits the array into arrays of `n` elements; the last one holds
+/// whatever is left over.
+///
+/// # PAWX Example
+/// ```pawx
+/// meow([1, 2, 3, 4, 5].chunk(2)); // [[1, 2], [3, 4], [5]]
+/// ```
+fn array_chunk(args: Vec<Value>) -> Result<Value, PawxError> {
+    let array = elements(&args, "chunk(n)")?;
+    let size = match args.get(1) {
+        Some(Value::Number(n)) if *n >= 1.0 && n.fract() == 0.0 => *n as usize,
+        _ => return raise_error("chunk(n) expects a positive integer size"),
+    };
+
+    Ok(new_array(array.chunks(size).map(|c| new_array(c.to_vec())).collect()))
+}
+
+/// Native implementation of `Array.prototype.zip()` for PAWX.
+///
+/// Pairs each element with the element at the same index of `other`.
+/// The result is as long as the shorter array.
+///
+/// # PAWX Example
+/// ```pawx
+/// meow(["a", "b", "c"].zip([1, 2])); // [["a", 1], ["b", 2]]
+/// ```
+fn array_zip(args: Vec<Value>) -> Result<Value, PawxError> {
+    let array = elements(&args, "zip(other)")?;
+    let other = match args.get(1) {
+        Some(Value::Array { values, .. }) => values.borrow().clone(),
+        _ => return raise_error("zip(other) expects an array"),
+    };
+
+    Ok(new_array(
+        array
+            .into_iter()
+            .zip(other)
+            .map(|(a, b)| new_array(vec![a, b]))
+            .collect(),
+    ))
+}
+
+/// Hashable stand-in for a scalar, so `unique()` stays linear on the
+/// values it is usually called with.
+#[derive(PartialEq, Eq, Hash)]
+enum ScalarKey {
+    Number(u64),
+    String(Rc<str>),
+    Bool(bool),
+    Null,
+}
+
+impl ScalarKey {
+    fn of(v: &Value) -> Option<ScalarKey> {
+        match v {
+            // NaN never equals itself, so every NaN is kept
+            Value::Number(n) if n.is_nan() => None,
+            // -0 and 0 are equal
+            Value::Number(n) => Some(ScalarKey::Number((n + 0.0).to_bits())),
+            Value::String(s) => Some(ScalarKey::String(s.clone())),
+            Value::Bool(b) => Some(ScalarKey::Bool(*b)),
+            Value::Null => Some(ScalarKey::Null),
+            _ => None,
+        }
+    }
+}
+
+/// Native implementation of `Array.prototype.unique()` for PAWX.
+///
+/// Drops repeated elements, keeping the first of each. Elements compare
+/// like `===`: scalars by value, arrays and objects by identity, so two
+/// separately built `[1]`s are both kept.
+///
+/// # PAWX Example
+/// ```pawx
+/// meow([3, 1, 3, 2, 1].unique()); // [3, 1, 2]
+/// snuggle one = [1];
+/// meow([one, one, [1]].unique()); // [[1], [1]]
+/// ```
+fn array_unique(args: Vec<Value>) -> Result<Value, PawxError> {
+    let array = elements(&args, "unique()")?;
+
+    let mut seen = HashSet::new();
+    let mut others: Vec<Value> = Vec::new();
+    let mut kept = Vec::new();
+
+    for v in array {
+        let fresh = match ScalarKey::of(&v) {
+            Some(key) => seen.insert(key),
+            None if matches!(v, Value::Number(_)) => true,
+            None => {
+                let mut fresh = true;
+                for o in &others {
+                    if Value::equals_strict(o, &v)? {
+                        fresh = false;
+                        break;
+                    }
+                }
+                if fresh {
+                    others.push(v.clone());
+                }
+                fresh
+            }
+        };
+
+        if fresh {
+            kept.push(v);
+        }
+    }
+
+    Ok(new_array(kept))
+}
+
+/// Native implementation of `Array.prototype.flatten()` for PAWX.
+///
+/// Splices nested arrays into their parent, `depth` levels deep
+/// (default 1; a depth beyond the deepest nesting flattens completely).
+///
+/// # Behavior
+/// - Elements that are not arrays are kept as they are.
+/// - Walks with an explicit stack, so deep arrays cannot overflow.
+///
+/// # PAWX Example
+/// ```pawx
+/// meow([1, [2, [3, [4]]]].flatten());  // [1, 2, [3, [4]]]
+/// meow([1, [2, [3, [4]]]].flatten(2)); // [1, 2, 3, [4]]
+/// ```
+fn array_flatten(args: Vec<Value>) -> Result<Value, PawxError> {
+    let array = elements(&args, "flatten(depth?)")?;
+    let depth = match args.get(1) {
+        None | Some(Value::Null) => 1.0,
+        Some(Value::Number(n)) if *n >= 0.0 => n.trunc(),
+        _ => return raise_error("flatten(depth?) expects a non-negative depth"),
+    };
+
+    let mut flat = Vec::new();
+    let mut stack = vec![array.into_iter()];
+
+    while let Some(items) = stack.last_mut() {
+        match items.next() {
+            Some(Value::Array { values, .. }) if ((stack.len() - 1) as f64) < depth => {
+                let inner = values.borrow().clone();
+                stack.push(inner.into_iter());
+            }
+            Some(v) => flat.push(v),
+            None => {
+                stack.pop();
+            }
+        }
+    }
+
+    Ok(new_array(flat))
+}
@@ -0,0 +1,394 @@
+/*
+ * ============================================================================
+ * PAWX - Code with Claws! 🐾
+ * ============================================================================
+ *
+ * Assertions for PAWX (`assert`, `assertEq`, `assertNe`, `expect`)
+ *
+ * Supports:
+ *   - assert(condition, message?)
+ *   - assertEq(actual, expected, message?)
+ *   - assertNe(actual, unexpected, message?)
+ *   - expect(actual).toEqual(expected)
+ *   - expect(actual).toBe(expected)
+ *   - expect(actual).toMatchSnapshot(name?)
+ *
+ * `assertEq` compares structurally: arrays, objects, instances and tuples
+ * are equal when their contents are. A failed assertion panics with a
+ * message showing both values, which fails the surrounding `test` block.
+ *
+ * When `assertEq` or `toEqual` fails on two arrays, objects, instances or
+ * tuples, the message lists only the paths that differ instead:
+ *
+ *   assertEq failed: 2 differences (- expected, + actual)
+ *     [1].name
+ *       - "Tom"
+ *       + "Tim"
+ *     owner
+ *       + "sam"
+ *
+ * `pawx test` colors the `-` and `+` lines on a terminal.
+ *
+ * --------------------------------------------------------------------------
+ * Author:   Sam Wilcox
+ * Email:    sam@pawx-lang.com
+ * Website:  https://www.pawx-lang.com
+ * GitHub:   https://github.com/samwilcox/pawx
+ *
+ * License:
+ * This file is part of the PAWX programming language project.
+ *
+ * PAWX is dual-licensed under the terms of:
+ *   - The MIT License
+ *   - The Apache License, Version 2.0
+ *
+ * You may choose either license to govern your use of this software.
+ * Full license text available at:
+ *     https://license.pawx-lang.com
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under these licenses is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *
+ * ==========================================================================
+ */
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use crate::error::PawxError;
+use crate::interpreter::calls::raise_error;
+use crate::interpreter::display::{value_to_json, value_to_string};
+use crate::interpreter::limits;
+use crate::interpreter::snapshots;
+use crate::value::Value;
+
+fn native(f: impl Fn(Vec<Value>) -> Result<Value, PawxError> + 'static) -> Value {
+    Value::NativeFunction(Arc::new(f))
+}
+
+/// Structural equality used by `assertEq`.
+///
+/// Pairs still to compare wait on an explicit stack, so deep values cannot
+/// overflow the Rust stack.
+///
+/// # Errors
+/// If the values are nested deeper than `--max-depth`.
+pub fn deep_equal(a: &Value, b: &Value) -> Result<bool, String> {
+    let mut pending = vec![(a.clone(), b.clone(), 0)];
+
+    while let Some((a, b, depth)) = pending.pop() {
+        let nested = |x: &[Value], y: &[Value], pending: &mut Vec<(Value, Value, usize)>| {
+            limits::check_nesting(depth + 1, "deep equality")?;
+            pending.extend(x.iter().cloned().zip(y.iter().cloned()).map(|(a, b)| (a, b, depth + 1)));
+            Ok::<(), String>(())
+        };
+
+        let equal = match (&a, &b) {
+            (Value::Array { values: x, .. }, Value::Array { values: y, .. }) => {
+                let (x, y) = (x.borrow(), y.borrow());
+                let same_len = x.len() == y.len();
+                if same_len {
+                    nested(&x, &y, &mut pending)?;
+                }
+                same_len
+            }
+
+            (Value::Object { fields: x }, Value::Object { fields: y })
+            | (Value::Instance { fields: x, .. }, Value::Instance { fields: y, .. }) => {
+                let (x, y) = (x.borrow(), y.borrow());
+                let same_keys = x.len() == y.len() && x.keys().all(|k| y.contains_key(k));
+                if same_keys {
+                    let (xs, ys): (Vec<Value>, Vec<Value>) = x.iter().map(|(k, v)| (v.clone(), y[k].clone())).unzip();
+                    nested(&xs, &ys, &mut pending)?;
+                }
+                same_keys
+            }
+
+            (Value::Tuple(x), Value::Tuple(y)) => {
+                let same_len = x.len() == y.len();
+                if same_len {
+                    nested(x, y, &mut pending)?;
+                }
+                same_len
+            }
+
+            (Value::Float64Array(x), Value::Float64Array(y)) => *x.borrow() == *y.borrow(),
+
+            (Value::Error { message: x }, Value::Error { message: y }) => x == y,
+
+            _ => Value::try_equals(&a, &b, true)?,
+        };
+
+        if !equal {
+            return Ok(false);
+        }
+    }
+
+    Ok(true)
+}
+
+/// Differences listed before the rest are summarized.
+const MAX_DIFFERENCES: usize = 20;
+
+/// One path at which two values differ.
+struct Difference {
+    path: String,
+    expected: Option<Value>,
+    actual: Option<Value>,
+}
+
+/// Values whose contents [`diff`] can compare path by path.
+fn is_container(value: &Value) -> bool {
+    matches!(
+        value,
+        Value::Array { .. } | Value::Object { .. } | Value::Instance { .. } | Value::Tuple(_)
+    )
+}
+
+/// `path` extended by the object key `key`.
+fn key_path(path: &str, key: &str) -> String {
+    let plain = key.chars().next().is_some_and(|c| c.is_alphabetic() || c == '_')
+        && key.chars().all(|c| c.is_alphanumeric() || c == '_');
+
+    match (plain, path.is_empty()) {
+        (true, true) => key.to_string(),
+        (true, false) => format!("{}.{}", path, key),
+        (false, _) => format!("{}[{}]", path, serde_json::to_string(key).unwrap_or_default()),
+    }
+}
+
+/// Compares two sequences index by index.
+fn diff_items(expected: &[Value], actual: &[Value], path: &str, depth: usize, out: &mut Vec<Difference>) {
+    for i in 0..expected.len().max(actual.len()) {
+        let item = format!("{}[{}]", path, i);
+        match (expected.get(i), actual.get(i)) {
+            (Some(e), Some(a)) => diff(e, a, item, depth + 1, out),
+            (e, a) => out.push(Difference { path: item, expected: e.cloned(), actual: a.cloned() }),
+        }
+    }
+}
+
+/// Collects the paths at which `expected` and `actual` differ, descending
+/// into containers of the same kind and comparing everything else with
+/// [`deep_equal`]. Containers at `--max-depth` are compared whole.
+fn diff(expected: &Value, actual: &Value, path: String, depth: usize, out: &mut Vec<Difference>) {
+    let descend = depth < limits::max_depth();
+
+    match (expected, actual) {
+        (Value::Array { values: x, .. }, Value::Array { values: y, .. }) if descend => {
+            diff_items(&x.borrow(), &y.borrow(), &path, depth, out);
+        }
+
+        (Value::Tuple(x), Value::Tuple(y)) if descend => diff_items(x, y, &path, depth, out),
+
+        (Value::Object { fields: x }, Value::Object { fields: y })
+        | (Value::Instance { fields: x, .. }, Value::Instance { fields: y, .. })
+            if descend =>
+        {
+            let (x, y) = (x.borrow(), y.borrow());
+
+            let mut keys: Vec<&String> = x.keys().chain(y.keys().filter(|k| !x.contains_key(*k))).collect();
+            keys.sort();
+
+            for key in keys {
+                let field = key_path(&path, key);
+                match (x.get(key), y.get(key)) {
+                    (Some(e), Some(a)) => diff(e, a, field, depth + 1, out),
+                    (e, a) => out.push(Difference { path: field, expected: e.cloned(), actual: a.cloned() }),
+                }
+            }
+        }
+
+        _ if deep_equal(expected, actual).unwrap_or(false) => {}
+
+        _ => out.push(Difference {
+            path,
+            expected: Some(expected.clone()),
+            actual: Some(actual.clone()),
+        }),
+    }
+}
+
+/// The failure message for `what` (e.g. `assertEq failed`) after a
+/// structural comparison: a diff of the two values when both are
+/// containers of the same kind, otherwise both values in full.
+fn equality_failure(what: &str, expected: &Value, actual: &Value) -> String {
+    let comparable = is_container(expected)
+        && is_container(actual)
+        && std::mem::discriminant(expected) == std::mem::discriminant(actual);
+
+    if !comparable {
+        return format!(
+            "{}\n  expected: {}\n  actual:   {}",
+            what,
+            shown(expected),
+            shown(actual)
+        );
+    }
+
+    let mut differences = Vec::new();
+    diff(expected, actual, String::new(), 0, &mut differences);
+
+    let mut out = format!(
+        "{}: {} difference{} (- expected, + actual)",
+        what,
+        differences.len(),
+        if differences.len() == 1 { "" } else { "s" }
+    );
+
+    for difference in differences.iter().take(MAX_DIFFERENCES) {
+        out.push_str(&format!("\n  {}", difference.path));
+        if let Some(value) = &difference.expected {
+            out.push_str(&format!("\n    - {}", shown(value)));
+        }
+        if let Some(value) = &difference.actual {
+            out.push_str(&format!("\n    + {}", shown(value)));
+        }
+    }
+
+    if differences.len() > MAX_DIFFERENCES {
+        out.push_str(&format!("\n  ... and {} more", differences.len() - MAX_DIFFERENCES));
+    }
+
+    out
+}
+
+/// A value as JSON for a failure message, or as printed if it is too deep
+/// to encode.
+fn shown(value: &Value) -> String {
+    value_to_json(value).unwrap_or_else(|_| value_to_string(value))
+}
+
+fn message(args: &[Value], index: usize) -> String {
+    match args.get(index) {
+        Some(Value::String(s)) => format!("{}\n", s),
+        _ => String::new(),
+    }
+}
+
+/// Builds the global `assert` function.
+pub fn create_global_assert_value() -> Value {
+    native(|args| {
+        if !args.first().is_some_and(Value::is_truthy) {
+            let condition = args.first().cloned().unwrap_or(Value::Null);
+            return raise_error(format!(
+                "{}assert failed: condition was {}",
+                message(&args, 1),
+                shown(&condition)
+            ));
+        }
+        Ok(Value::Null)
+    })
+}
+
+/// Builds the global `assertEq` function.
+pub fn create_global_assert_eq_value() -> Value {
+    native(|args| {
+        let actual = args.first().cloned().unwrap_or(Value::Null);
+        let expected = args.get(1).cloned().unwrap_or(Value::Null);
+
+        let equal = match deep_equal(&actual, &expected) {
+            Ok(equal) => equal,
+            Err(message) => return raise_error(message),
+        };
+
+        if !equal {
+            return raise_error(format!(
+                "{}{}",
+                message(&args, 2),
+                equality_failure("assertEq failed", &expected, &actual)
+            ));
+        }
+        Ok(Value::Null)
+    })
+}
+
+/// Builds the global `assertNe` function.
+pub fn create_global_assert_ne_value() -> Value {
+    native(|args| {
+        let actual = args.first().cloned().unwrap_or(Value::Null);
+        let unexpected = args.get(1).cloned().unwrap_or(Value::Null);
+
+        let equal = match deep_equal(&actual, &unexpected) {
+            Ok(equal) => equal,
+            Err(message) => return raise_error(message),
+        };
+
+        if equal {
+            return raise_error(format!(
+                "{}assertNe failed: both values are {}",
+                message(&args, 2),
+                shown(&actual)
+            ));
+        }
+        Ok(Value::Null)
+    })
+}
+
+/// Builds the global `expect` function.
+///
+/// `expect(actual)` returns an object whose matchers check `actual`:
+/// `toEqual` compares structurally, `toBe` strictly, and
+/// `toMatchSnapshot` against the snapshot stored for the running test.
+pub fn create_global_expect_value() -> Value {
+    native(|args| {
+        let actual = args.first().cloned().unwrap_or(Value::Null);
+        let mut matchers = HashMap::new();
+
+        let value = actual.clone();
+        matchers.insert(
+            "toEqual".to_string(),
+            native(move |args| {
+                let expected = args.first().cloned().unwrap_or(Value::Null);
+                let equal = match deep_equal(&value, &expected) {
+                    Ok(equal) => equal,
+                    Err(message) => return raise_error(message),
+                };
+
+                if !equal {
+                    return raise_error(equality_failure("expect(...).toEqual failed", &expected, &value).to_string());
+                }
+                Ok(Value::Null)
+            }),
+        );
+
+        let value = actual.clone();
+        matchers.insert(
+            "toBe".to_string(),
+            native(move |args| {
+                let expected = args.first().cloned().unwrap_or(Value::Null);
+                if !Value::equals_strict(&value, &expected)? {
+                    return raise_error(format!(
+                        "expect(...).toBe failed\n  expected: {}\n  actual:   {}",
+                        shown(&expected),
+                        shown(&value)
+                    ));
+                }
+                Ok(Value::Null)
+            }),
+        );
+
+        matchers.insert(
+            "toMatchSnapshot".to_string(),
+            native(move |args| {
+                let label = match args.first() {
+                    Some(Value::String(s)) => Some(&**s),
+                    None | Some(Value::Null) => None,
+                    _ => return raise_error("toMatchSnapshot(name?) expects a string"),
+                };
+
+                if let Err(e) = snapshots::check(&actual, label) {
+                    return raise_error(e.to_string());
+                }
+                Ok(Value::Null)
+            }),
+        );
+
+        Ok(Value::Object {
+            fields: Rc::new(RefCell::new(matchers)),
+        })
+    })
+}
@@ -0,0 +1,750 @@
+/*
+ * ============================================================================
+ * PAWX - Code with Claws! 🐾
+ * ============================================================================
+ *
+ * Auth Prototype for PAWX (JWT + OAuth2)
+ *
+ * Supports:
+ *   - Auth.sign(payload, key, options?)    → JWT string (HS256 / RS256)
+ *   - Auth.verify(token, key, options?)    → payload or Error
+ *   - Auth.decode(token)                   → { header, payload } (unverified)
+ *   - Auth.pkce()                          → { verifier, challenge }
+ *   - Auth.oauth2(config)                  → OAuth2 client
+ *
+ * HS256 keys are shared secrets. RS256 keys are PEM encoded (PKCS#1 or
+ * PKCS#8 private keys, SPKI or PKCS#1 public keys).
+ *
+ * OAuth2 token requests go through the default HTTP client.
+ *
+ * --------------------------------------------------------------------------
+ * Author:   Sam Wilcox
+ * Email:    sam@pawx-lang.com
+ * Website:  https://www.pawx-lang.com
+ * GitHub:   https://github.com/samwilcox/pawx
+ *
+ * License:
+ * This file is part of the PAWX programming language project.
+ *
+ * PAWX is dual-licensed under the terms of:
+ *   - The MIT License
+ *   - The Apache License, Version 2.0
+ *
+ * You may choose either license to govern your use of this software.
+ * Full license text available at:
+ *     https://license.pawx-lang.com
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under these licenses is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *
+ * ==========================================================================
+ */
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use rand::Rng;
+use rsa::pkcs1::{DecodeRsaPrivateKey, DecodeRsaPublicKey};
+use rsa::pkcs1v15::{Signature, SigningKey, VerifyingKey};
+use rsa::pkcs8::{DecodePrivateKey, DecodePublicKey};
+use rsa::signature::{SignatureEncoding, Signer, Verifier};
+use rsa::{RsaPrivateKey, RsaPublicKey};
+use serde_json::{json, Value as JsonValue};
+use sha2::Sha256;
+
+use crate::error::PawxError;
+use crate::interpreter::calls::{builtin_error, raise_error};
+use crate::prototypes::cancel::cancellable;
+use crate::prototypes::crypto::{
+    base64url_decode, base64url_encode, hmac_sha256, hmac_sha256_verify, sha256,
+};
+use crate::prototypes::http::{json_to_value, value_to_json_http};
+use crate::prototypes::http_client::{default_client, parse_url, HttpRequest};
+use crate::value::Value;
+
+/* ============================================================================
+ * HELPERS
+ * ============================================================================
+ */
+
+fn object(fields: HashMap<String, Value>) -> Value {
+    Value::Object {
+        fields: Rc::new(RefCell::new(fields)),
+    }
+}
+
+fn options_map(value: Option<&Value>, method: &str) -> Result<HashMap<String, Value>, PawxError> {
+    match value {
+        Some(Value::Object { fields }) => Ok(fields.borrow().clone()),
+        Some(Value::Null) | None => Ok(HashMap::new()),
+        _ => raise_error(format!("{}: options must be an object", method)),
+    }
+}
+
+fn string_option(options: &HashMap<String, Value>, name: &str, method: &str) -> Result<Option<String>, PawxError> {
+    match options.get(name) {
+        Some(Value::String(s)) => Ok(Some(s.to_string())),
+        Some(Value::Null) | None => Ok(None),
+        _ => raise_error(format!("{}: `{}` must be a string", method, name)),
+    }
+}
+
+fn number_option(options: &HashMap<String, Value>, name: &str, method: &str) -> Result<Option<i64>, PawxError> {
+    match options.get(name) {
+        Some(Value::Number(n)) => Ok(Some(*n as i64)),
+        Some(Value::Null) | None => Ok(None),
+        _ => raise_error(format!("{}: `{}` must be a number of seconds", method, name)),
+    }
+}
+
+fn now_seconds() -> i64 {
+    chrono::Utc::now().timestamp()
+}
+
+fn random_token(bytes: usize) -> String {
+    let data: Vec<u8> = (0..bytes).map(|_| rand::thread_rng().gen()).collect();
+    base64url_encode(&data)
+}
+
+/// Percent-encodes a value for `application/x-www-form-urlencoded` bodies
+/// and query strings.
+fn form_encode(text: &str) -> String {
+    let mut out = String::new();
+    for b in text.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                out.push(b as char)
+            }
+            _ => out.push_str(&format!("%{:02X}", b)),
+        }
+    }
+    out
+}
+
+fn form_body(pairs: &[(&str, String)]) -> String {
+    pairs
+        .iter()
+        .map(|(k, v)| format!("{}={}", form_encode(k), form_encode(v)))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+/* ============================================================================
+ * JWT
+ * ============================================================================
+ */
+
+/// Supported JWT signing algorithms.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Algorithm {
+    Hs256,
+    Rs256,
+}
+
+impl Algorithm {
+    fn parse(name: &str) -> Result<Self, String> {
+        match name.to_ascii_uppercase().as_str() {
+            "HS256" => Ok(Algorithm::Hs256),
+            "RS256" => Ok(Algorithm::Rs256),
+            other => Err(format!("unsupported algorithm '{}' (expected HS256 or RS256)", other)),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Algorithm::Hs256 => "HS256",
+            Algorithm::Rs256 => "RS256",
+        }
+    }
+
+    /// Picks the algorithm implied by the key: PEM keys mean RS256.
+    fn for_key(key: &str) -> Self {
+        if key.trim_start().starts_with("-----BEGIN") {
+            Algorithm::Rs256
+        } else {
+            Algorithm::Hs256
+        }
+    }
+}
+
+fn private_key(pem: &str) -> Result<RsaPrivateKey, String> {
+    RsaPrivateKey::from_pkcs8_pem(pem)
+        .or_else(|_| RsaPrivateKey::from_pkcs1_pem(pem))
+        .map_err(|_| "invalid RSA private key (expected PKCS#1 or PKCS#8 PEM)".to_string())
+}
+
+/// Accepts a public key, or a private key whose public half is used.
+fn public_key(pem: &str) -> Result<RsaPublicKey, String> {
+    RsaPublicKey::from_public_key_pem(pem)
+        .or_else(|_| RsaPublicKey::from_pkcs1_pem(pem))
+        .or_else(|_| private_key(pem).map(|k| k.to_public_key()))
+        .map_err(|_| "invalid RSA public key (expected SPKI or PKCS#1 PEM)".to_string())
+}
+
+fn sign_bytes(alg: Algorithm, key: &str, data: &[u8]) -> Result<Vec<u8>, String> {
+    match alg {
+        Algorithm::Hs256 => Ok(hmac_sha256(key.as_bytes(), data)),
+        Algorithm::Rs256 => {
+            let signer = SigningKey::<Sha256>::new(private_key(key)?);
+            Ok(signer.sign(data).to_vec())
+        }
+    }
+}
+
+fn verify_bytes(alg: Algorithm, key: &str, data: &[u8], sig: &[u8]) -> Result<bool, String> {
+    match alg {
+        Algorithm::Hs256 => Ok(hmac_sha256_verify(key.as_bytes(), data, sig)),
+        Algorithm::Rs256 => {
+            let verifier = VerifyingKey::<Sha256>::new(public_key(key)?);
+            Ok(match Signature::try_from(sig) {
+                Ok(sig) => verifier.verify(data, &sig).is_ok(),
+                Err(_) => false,
+            })
+        }
+    }
+}
+
+/// Builds and signs a JWT.
+///
+/// # Options
+/// - `algorithm` → `"HS256"` or `"RS256"` (default: inferred from the key)
+/// - `expiresIn` → seconds until `exp`
+/// - `notBefore` → seconds until `nbf`
+/// - `issuer` / `audience` / `subject` → `iss` / `aud` / `sub`
+/// - `header`    → extra header fields (e.g. `kid`)
+///
+/// `iat` is always set to the current time unless already present.
+fn jwt_sign(payload: &Value, key: &str, options: &HashMap<String, Value>) -> Result<String, String> {
+    let method = "Auth.sign";
+
+    let alg = match string_option(options, "algorithm", method).map_err(|e| e.message)? {
+        Some(name) => Algorithm::parse(&name)?,
+        None => Algorithm::for_key(key),
+    };
+
+    let mut claims = match value_to_json_http(payload)? {
+        JsonValue::Object(map) => map,
+        _ => return Err("payload must be an object".to_string()),
+    };
+
+    let now = now_seconds();
+    claims.entry("iat").or_insert(json!(now));
+
+    if let Some(secs) = number_option(options, "expiresIn", method).map_err(|e| e.message)? {
+        claims.insert("exp".to_string(), json!(now + secs));
+    }
+    if let Some(secs) = number_option(options, "notBefore", method).map_err(|e| e.message)? {
+        claims.insert("nbf".to_string(), json!(now + secs));
+    }
+    for (option, claim) in [("issuer", "iss"), ("audience", "aud"), ("subject", "sub")] {
+        if let Some(v) = string_option(options, option, method).map_err(|e| e.message)? {
+            claims.insert(claim.to_string(), json!(v));
+        }
+    }
+
+    let mut header = serde_json::Map::new();
+    if let Some(extra) = options.get("header") {
+        if let JsonValue::Object(extra) = value_to_json_http(extra)? {
+            header.extend(extra);
+        }
+    }
+    header.insert("alg".to_string(), json!(alg.name()));
+    header.insert("typ".to_string(), json!("JWT"));
+
+    let signing_input = format!(
+        "{}.{}",
+        base64url_encode(JsonValue::Object(header).to_string().as_bytes()),
+        base64url_encode(JsonValue::Object(claims).to_string().as_bytes())
+    );
+
+    let signature = sign_bytes(alg, key, signing_input.as_bytes())?;
+
+    Ok(format!("{}.{}", signing_input, base64url_encode(&signature)))
+}
+
+/// Splits a token and decodes its header and payload without verifying.
+fn jwt_parts(token: &str) -> Result<(JsonValue, JsonValue, String, Vec<u8>), String> {
+    let parts: Vec<&str> = token.split('.').collect();
+    if parts.len() != 3 {
+        return Err("malformed token".to_string());
+    }
+
+    let decode = |part: &str| -> Result<JsonValue, String> {
+        let bytes = base64url_decode(part)?;
+        serde_json::from_slice(&bytes).map_err(|_| "malformed token".to_string())
+    };
+
+    let header = decode(parts[0])?;
+    let payload = decode(parts[1])?;
+    let signature = base64url_decode(parts[2])?;
+
+    Ok((header, payload, format!("{}.{}", parts[0], parts[1]), signature))
+}
+
+/// Verifies a JWT and returns its claims.
+///
+/// The header's `alg` must match the expected algorithm, so a token can
+/// never pick a weaker check than the caller intended.
+///
+/// # Options
+/// - `algorithm` → expected algorithm (default: inferred from the key)
+/// - `leeway`    → clock skew allowance in seconds (default 0)
+/// - `issuer` / `audience` / `subject` → required claim values
+fn jwt_verify(token: &str, key: &str, options: &HashMap<String, Value>) -> Result<JsonValue, String> {
+    let method = "Auth.verify";
+
+    let expected = match string_option(options, "algorithm", method).map_err(|e| e.message)? {
+        Some(name) => Algorithm::parse(&name)?,
+        None => Algorithm::for_key(key),
+    };
+
+    let (header, payload, signing_input, signature) = jwt_parts(token)?;
+
+    let alg = header.get("alg").and_then(JsonValue::as_str).unwrap_or("none");
+    if Algorithm::parse(alg).ok() != Some(expected) {
+        return Err(format!(
+            "algorithm mismatch (token uses {}, expected {})",
+            alg,
+            expected.name()
+        ));
+    }
+
+    if !verify_bytes(expected, key, signing_input.as_bytes(), &signature)? {
+        return Err("invalid signature".to_string());
+    }
+
+    let now = now_seconds();
+    let leeway = number_option(options, "leeway", method).map_err(|e| e.message)?.unwrap_or(0);
+
+    if let Some(exp) = payload.get("exp").and_then(JsonValue::as_f64) {
+        if now - leeway >= exp as i64 {
+            return Err("token expired".to_string());
+        }
+    }
+
+    if let Some(nbf) = payload.get("nbf").and_then(JsonValue::as_f64) {
+        if now + leeway < nbf as i64 {
+            return Err("token not yet valid".to_string());
+        }
+    }
+
+    for (option, claim) in [("issuer", "iss"), ("subject", "sub")] {
+        if let Some(required) = string_option(options, option, method).map_err(|e| e.message)? {
+            if payload.get(claim).and_then(JsonValue::as_str) != Some(required.as_str()) {
+                return Err(format!("{} mismatch", option));
+            }
+        }
+    }
+
+    // `aud` may be a single string or an array of strings.
+    if let Some(required) = string_option(options, "audience", method).map_err(|e| e.message)? {
+        let matches = match payload.get("aud") {
+            Some(JsonValue::String(aud)) => *aud == required,
+            Some(JsonValue::Array(auds)) => auds.iter().any(|a| a.as_str() == Some(&required)),
+            _ => false,
+        };
+        if !matches {
+            return Err("audience mismatch".to_string());
+        }
+    }
+
+    Ok(payload)
+}
+
+/* ============================================================================
+ * OAUTH2
+ * ============================================================================
+ */
+
+/// How client credentials are sent to the token endpoint.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ClientAuth {
+    /// `Authorization: Basic base64(id:secret)` (RFC 6749 §2.3.1).
+    Basic,
+
+    /// `client_id` / `client_secret` in the form body.
+    Post,
+}
+
+/// Settings for an OAuth2 client.
+#[derive(Debug, Clone)]
+struct OAuth2Config {
+    client_id: String,
+    client_secret: Option<String>,
+    token_url: String,
+    authorize_url: Option<String>,
+    redirect_uri: Option<String>,
+    scope: Option<String>,
+    client_auth: ClientAuth,
+}
+
+/// A cached client-credentials token.
+#[derive(Debug, Clone)]
+struct CachedToken {
+    scope: Option<String>,
+    value: Value,
+    expires_at: Option<i64>,
+}
+
+fn parse_oauth2_config(value: Option<&Value>) -> Result<OAuth2Config, PawxError> {
+    let method = "Auth.oauth2";
+    let options = options_map(value, method)?;
+
+    let required = |name: &str| -> Result<String, PawxError> {
+        string_option(&options, name, method)?
+            .ok_or_else(|| builtin_error(format!("{}(config): `{}` is required", method, name)))
+    };
+
+    let client_auth = match string_option(&options, "authMethod", method)?.as_deref() {
+        None | Some("basic") => ClientAuth::Basic,
+        Some("post") => ClientAuth::Post,
+        Some(other) => return raise_error(format!("{}: authMethod must be \"basic\" or \"post\", got '{}'", method, other)),
+    };
+
+    Ok(OAuth2Config {
+        client_id: required("clientId")?,
+        client_secret: string_option(&options, "clientSecret", method)?,
+        token_url: required("tokenUrl")?,
+        authorize_url: string_option(&options, "authorizeUrl", method)?,
+        redirect_uri: string_option(&options, "redirectUri", method)?,
+        scope: string_option(&options, "scope", method)?,
+        client_auth,
+    })
+}
+
+/// Posts a grant to the token endpoint and converts the reply.
+///
+/// # Returns
+/// - `{ accessToken, tokenType, expiresIn, expiresAt, refreshToken, scope, raw }`
+/// - `Err` carrying the OAuth2 `error` / `error_description`
+fn request_token(config: &OAuth2Config, mut params: Vec<(&str, String)>) -> Result<(Value, Option<i64>), String> {
+    let mut headers = vec![
+        ("Content-Type".to_string(), "application/x-www-form-urlencoded".to_string()),
+        ("Accept".to_string(), "application/json".to_string()),
+    ];
+
+    match (config.client_auth, &config.client_secret) {
+        (ClientAuth::Basic, Some(secret)) => {
+            use base64::Engine;
+            let credentials = format!("{}:{}", form_encode(&config.client_id), form_encode(secret));
+            headers.push((
+                "Authorization".to_string(),
+                format!(
+                    "Basic {}",
+                    base64::engine::general_purpose::STANDARD.encode(credentials)
+                ),
+            ));
+        }
+        (ClientAuth::Post, Some(secret)) => {
+            params.push(("client_id", config.client_id.clone()));
+            params.push(("client_secret", secret.clone()));
+        }
+        // Public clients only identify themselves.
+        (_, None) => params.push(("client_id", config.client_id.clone())),
+    }
+
+    let response = default_client().send(&HttpRequest {
+        method: "POST".to_string(),
+        url: parse_url(&config.token_url)?,
+        headers,
+        body: Some(form_body(&params)),
+        multipart: None,
+        timeout: None,
+    })?;
+
+    let reply: JsonValue = serde_json::from_str(&response.body).map_err(|_| {
+        format!(
+            "OAuth2: token endpoint returned HTTP {} {}",
+            response.status, response.status_text
+        )
+    })?;
+
+    if let Some(error) = reply.get("error").and_then(JsonValue::as_str) {
+        return Err(match reply.get("error_description").and_then(JsonValue::as_str) {
+            Some(desc) => format!("OAuth2: {}: {}", error, desc),
+            None => format!("OAuth2: {}", error),
+        });
+    }
+
+    let access_token = reply
+        .get("access_token")
+        .and_then(JsonValue::as_str)
+        .ok_or_else(|| "OAuth2: token response is missing access_token".to_string())?;
+
+    let expires_in = reply.get("expires_in").and_then(JsonValue::as_f64);
+    let expires_at = expires_in.map(|secs| now_seconds() + secs as i64);
+
+    let text = |name: &str| match reply.get(name).and_then(JsonValue::as_str) {
+        Some(s) => Value::String(s.into()),
+        None => Value::Null,
+    };
+
+    let mut fields = HashMap::new();
+    fields.insert("accessToken".to_string(), Value::String(access_token.into()));
+    fields.insert("tokenType".to_string(), text("token_type"));
+    fields.insert("refreshToken".to_string(), text("refresh_token"));
+    fields.insert("scope".to_string(), text("scope"));
+    fields.insert(
+        "expiresIn".to_string(),
+        expires_in.map(Value::Number).unwrap_or(Value::Null),
+    );
+    fields.insert(
+        "expiresAt".to_string(),
+        expires_at.map(|t| Value::Number(t as f64)).unwrap_or(Value::Null),
+    );
+    fields.insert("raw".to_string(), json_to_value(reply.clone()));
+
+    Ok((object(fields), expires_at))
+}
+
+fn to_furure(result: Result<Value, String>) -> Value {
+    match result {
+        Ok(v) => Value::Furure(Box::new(v)),
+        Err(message) => Value::Furure(Box::new(Value::Error { message })),
+    }
+}
+
+/// Builds the PAWX OAuth2 client object.
+///
+/// # Installed Functions
+/// - `clientCredentials(scope?, token?)` → Furure(token); cached until
+///   30 seconds before expiry
+/// - `authorizationUrl(options?)` → URL string; options
+///   `{ state, scope, codeChallenge }`
+/// - `exchangeCode(code, codeVerifier?, token?)` → Furure(token)
+/// - `refresh(refreshToken, token?)` → Furure(token)
+fn create_oauth2_value(config: OAuth2Config) -> Value {
+    let config = Rc::new(config);
+    let cache: Rc<RefCell<Option<CachedToken>>> = Rc::new(RefCell::new(None));
+    let mut fields = HashMap::new();
+
+    let (cfg, cached) = (config.clone(), cache.clone());
+    fields.insert(
+        "clientCredentials".to_string(),
+        cancellable(Arc::new(move |args: Vec<Value>| -> Result<Value, PawxError> {
+            let scope = match args.first() {
+                Some(Value::String(s)) => Some(s.to_string()),
+                _ => cfg.scope.clone(),
+            };
+
+            // Reuse a cached token for the same scope with at least 30
+            // seconds left.
+            if let Some(token) = cached.borrow().as_ref() {
+                if token.scope == scope && token.expires_at.is_none_or(|t| t - 30 > now_seconds()) {
+                    return Ok(Value::Furure(Box::new(token.value.clone())));
+                }
+            }
+
+            let mut params = vec![("grant_type", "client_credentials".to_string())];
+            if let Some(scope) = &scope {
+                params.push(("scope", scope.clone()));
+            }
+
+            Ok(to_furure(request_token(&cfg, params).map(|(value, expires_at)| {
+                *cached.borrow_mut() = Some(CachedToken {
+                    scope,
+                    value: value.clone(),
+                    expires_at,
+                });
+                value
+            })))
+        })),
+    );
+
+    let cfg = config.clone();
+    fields.insert(
+        "authorizationUrl".to_string(),
+        Value::NativeFunction(Arc::new(move |args: Vec<Value>| -> Result<Value, PawxError> {
+            let method = "oauth2.authorizationUrl";
+            let options = options_map(args.first(), method)?;
+
+            let base = cfg
+                .authorize_url
+                .clone()
+                .ok_or_else(|| builtin_error(format!("{}: the client has no authorizeUrl", method)))?;
+
+            let mut params = vec![
+                ("response_type", "code".to_string()),
+                ("client_id", cfg.client_id.clone()),
+            ];
+            if let Some(uri) = &cfg.redirect_uri {
+                params.push(("redirect_uri", uri.clone()));
+            }
+            if let Some(scope) = string_option(&options, "scope", method)?.or_else(|| cfg.scope.clone()) {
+                params.push(("scope", scope));
+            }
+            if let Some(state) = string_option(&options, "state", method)? {
+                params.push(("state", state));
+            }
+            if let Some(challenge) = string_option(&options, "codeChallenge", method)? {
+                params.push(("code_challenge", challenge));
+                params.push(("code_challenge_method", "S256".to_string()));
+            }
+
+            let separator = if base.contains('?') { '&' } else { '?' };
+            Ok(Value::String(format!("{}{}{}", base, separator, form_body(&params)).into()))
+        })),
+    );
+
+    let cfg = config.clone();
+    fields.insert(
+        "exchangeCode".to_string(),
+        cancellable(Arc::new(move |args: Vec<Value>| -> Result<Value, PawxError> {
+            let code = match args.first() {
+                Some(Value::String(c)) => c.to_string(),
+                _ => return raise_error("oauth2.exchangeCode(code, codeVerifier?) requires a code string"),
+            };
+
+            let mut params = vec![("grant_type", "authorization_code".to_string()), ("code", code.to_string())];
+            if let Some(uri) = &cfg.redirect_uri {
+                params.push(("redirect_uri", uri.clone()));
+            }
+            if let Some(Value::String(verifier)) = args.get(1) {
+                params.push(("code_verifier", verifier.to_string()));
+            }
+
+            Ok(to_furure(request_token(&cfg, params).map(|(value, _)| value)))
+        })),
+    );
+
+    let cfg = config;
+    fields.insert(
+        "refresh".to_string(),
+        cancellable(Arc::new(move |args: Vec<Value>| -> Result<Value, PawxError> {
+            let refresh_token = match args.first() {
+                Some(Value::String(t)) => t.to_string(),
+                _ => return raise_error("oauth2.refresh(refreshToken) requires a string"),
+            };
+
+            let params = vec![
+                ("grant_type", "refresh_token".to_string()),
+                ("refresh_token", refresh_token.to_string()),
+            ];
+
+            Ok(to_furure(request_token(&cfg, params).map(|(value, _)| value)))
+        })),
+    );
+
+    object(fields)
+}
+
+/* ============================================================================
+ * GLOBAL OBJECT
+ * ============================================================================
+ */
+
+fn key_arg(args: &[Value], index: usize, method: &str) -> Result<String, PawxError> {
+    match args.get(index) {
+        Some(Value::String(k)) => Ok(k.to_string()),
+        _ => raise_error(format!("{}: key must be a string (secret or PEM)", method)),
+    }
+}
+
+/// Creates the global `Auth` object.
+///
+/// # PAWX Example
+/// ```pawx
+/// snuggle token = Auth.sign({ sub: "sam" }, "secret", { expiresIn: 3600 });
+/// snuggle claims = Auth.verify(token, "secret");
+/// meow(claims.sub);
+///
+/// snuggle api = Auth.oauth2({
+///     clientId: "id",
+///     clientSecret: "secret",
+///     tokenUrl: "http://localhost:9000/oauth/token"
+/// });
+/// api.clientCredentials().then(t -> { meow(t.accessToken) });
+/// ```
+pub fn create_global_auth_object() -> HashMap<String, Value> {
+    let mut map = HashMap::new();
+
+    // Auth.sign(payload, key, options?)
+    map.insert(
+        "sign".to_string(),
+        Value::NativeFunction(Arc::new(|args: Vec<Value>| -> Result<Value, PawxError> {
+            let method = "Auth.sign(payload, key, options?)";
+            let payload = args.first().cloned().unwrap_or(Value::Null);
+            let key = key_arg(&args, 1, method)?;
+            let options = options_map(args.get(2), method)?;
+
+            match jwt_sign(&payload, &key, &options) {
+                Ok(token) => Ok(Value::String(token.into())),
+                Err(e) => raise_error(format!("{}: {}", method, e)),
+            }
+        })),
+    );
+
+    // Auth.verify(token, key, options?)
+    //
+    // Invalid, expired or tampered tokens yield an Error value rather than
+    // aborting, so callers can branch on the result.
+    map.insert(
+        "verify".to_string(),
+        Value::NativeFunction(Arc::new(|args: Vec<Value>| -> Result<Value, PawxError> {
+            let method = "Auth.verify(token, key, options?)";
+            let token = match args.first() {
+                Some(Value::String(t)) => t.to_string(),
+                _ => return raise_error(format!("{}: token must be a string", method)),
+            };
+            let key = key_arg(&args, 1, method)?;
+            let options = options_map(args.get(2), method)?;
+
+            match jwt_verify(&token, &key, &options) {
+                Ok(payload) => Ok(json_to_value(payload)),
+                Err(e) => Ok(Value::Error {
+                    message: format!("JwtError: {}", e),
+                }),
+            }
+        })),
+    );
+
+    // Auth.decode(token)
+    map.insert(
+        "decode".to_string(),
+        Value::NativeFunction(Arc::new(|args: Vec<Value>| -> Result<Value, PawxError> {
+            let token = match args.first() {
+                Some(Value::String(t)) => t.to_string(),
+                _ => return raise_error("Auth.decode(token): token must be a string"),
+            };
+
+            match jwt_parts(&token) {
+                Ok((header, payload, _, _)) => {
+                    let mut fields = HashMap::new();
+                    fields.insert("header".to_string(), json_to_value(header));
+                    fields.insert("payload".to_string(), json_to_value(payload));
+                    Ok(object(fields))
+                }
+                Err(e) => Ok(Value::Error {
+                    message: format!("JwtError: {}", e),
+                }),
+            }
+        })),
+    );
+
+    // Auth.pkce() → { verifier, challenge }
+    map.insert(
+        "pkce".to_string(),
+        Value::NativeFunction(Arc::new(|_args: Vec<Value>| -> Result<Value, PawxError> {
+            let verifier = random_token(32);
+            let challenge = base64url_encode(&sha256(verifier.as_bytes()));
+
+            let mut fields = HashMap::new();
+            fields.insert("verifier".to_string(), Value::String(verifier.into()));
+            fields.insert("challenge".to_string(), Value::String(challenge.into()));
+            Ok(object(fields))
+        })),
+    );
+
+    // Auth.oauth2(config)
+    map.insert(
+        "oauth2".to_string(),
+        Value::NativeFunction(Arc::new(|args: Vec<Value>| -> Result<Value, PawxError> {
+            Ok(create_oauth2_value(parse_oauth2_config(args.first())?))
+        })),
+    );
+
+    map
+}
@@ -0,0 +1,265 @@
+/*
+ * ==========================================================================
+ * PAWX - Code with Claws!
+ * CancelToken Implementation
+ * ==========================================================================
+ *
+ * This module defines cooperative cancellation for PAWX async operations.
+ *
+ * A `CancelToken` is created from PAWX code and passed as the **last
+ * argument** to cancellable natives:
+ *
+ *   - setTimeout(fn, ms, token) / setInterval(fn, ms, token)
+ *   - Fs.*Async(..., token)
+ *
+ * Cancelling the token:
+ *   - Clears any timers bound to it
+ *   - Makes async operations resolve to a `CancelledError`
+ *   - Causes `nap` on such a Furure to throw, so it can be caught
+ *
+ * --------------------------------------------------------------------------
+ * Author:   Sam Wilcox
+ * Email:    sam@pawx-lang.com
+ * Website:  https://www.pawx-lang.com
+ * GitHub:   https://github.com/samwilcox/pawx
+ *
+ * License:
+ * This file is part of the PAWX programming language project.
+ *
+ * PAWX is dual-licensed under the terms of:
+ *   - The MIT License
+ *   - The Apache License, Version 2.0
+ *
+ * You may choose either license to govern your use of this software.
+ * Full license text available at:
+ *     https://license.pawx-lang.com
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under these licenses is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *
+ * ==========================================================================
+ */
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::sync::Arc;
+
+use crate::error::PawxError;
+use crate::interpreter::calls::raise_error;
+use crate::value::{NativeFn, Value};
+
+/// Message prefix identifying cancellation errors.
+pub const CANCELLED_ERROR_PREFIX: &str = "CancelledError";
+
+/// Default reason used when `cancel()` is called without one.
+const DEFAULT_REASON: &str = "Operation was cancelled";
+
+/// Shared state behind every handle to the same token.
+struct CancelState {
+    cancelled: Cell<bool>,
+    reason: RefCell<String>,
+    listeners: RefCell<Vec<Value>>,
+}
+
+/// A cooperative cancellation token.
+///
+/// Cloning a `CancelToken` produces another handle to the **same** token.
+#[derive(Clone)]
+pub struct CancelToken {
+    state: Rc<CancelState>,
+}
+
+impl Default for CancelToken {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CancelToken {
+    /// Creates a new, non-cancelled token.
+    pub fn new() -> Self {
+        Self {
+            state: Rc::new(CancelState {
+                cancelled: Cell::new(false),
+                reason: RefCell::new(String::new()),
+                listeners: RefCell::new(Vec::new()),
+            }),
+        }
+    }
+
+    /// Returns `true` once `cancel()` has been called.
+    pub fn is_cancelled(&self) -> bool {
+        self.state.cancelled.get()
+    }
+
+    /// Returns the cancellation reason (empty until cancelled).
+    pub fn reason(&self) -> String {
+        self.state.reason.borrow().clone()
+    }
+
+    /// Cancels the token and runs every registered listener once.
+    ///
+    /// Cancelling an already-cancelled token has no effect.
+    ///
+    /// # Errors
+    /// The first error raised by a listener; the rest still run.
+    pub fn cancel(&self, reason: Option<String>) -> Result<(), PawxError> {
+        if self.is_cancelled() {
+            return Ok(());
+        }
+
+        self.state.cancelled.set(true);
+        *self.state.reason.borrow_mut() = reason.unwrap_or_else(|| DEFAULT_REASON.to_string());
+
+        let listeners: Vec<Value> = self.state.listeners.borrow_mut().drain(..).collect();
+        let reason = Value::String(self.reason().into());
+
+        let mut outcome = Ok(());
+        for listener in listeners {
+            if let Value::NativeFunction(f) = listener {
+                if let Err(error) = f(vec![reason.clone()]) {
+                    outcome = outcome.and(Err(error));
+                }
+            }
+        }
+
+        outcome
+    }
+
+    /// Registers a callback invoked when the token is cancelled.
+    ///
+    /// If the token is already cancelled, the callback runs immediately.
+    ///
+    /// # Errors
+    /// Whatever the callback raises when it runs immediately.
+    pub fn on_cancel(&self, callback: Value) -> Result<(), PawxError> {
+        if self.is_cancelled() {
+            if let Value::NativeFunction(f) = callback {
+                f(vec![Value::String(self.reason().into())])?;
+            }
+            return Ok(());
+        }
+
+        self.state.listeners.borrow_mut().push(callback);
+        Ok(())
+    }
+
+    /// Builds the `CancelledError` value for this token.
+    pub fn to_error(&self) -> Value {
+        cancelled_error(&self.reason())
+    }
+
+    /// Returns `true` if both handles refer to the same token.
+    pub fn ptr_eq(&self, other: &CancelToken) -> bool {
+        Rc::ptr_eq(&self.state, &other.state)
+    }
+}
+
+/// Creates a `CancelledError` value carrying the given reason.
+pub fn cancelled_error(reason: &str) -> Value {
+    Value::Error {
+        message: format!("{}: {}", CANCELLED_ERROR_PREFIX, reason),
+    }
+}
+
+/// Extracts a trailing `CancelToken` argument, if present.
+///
+/// Cancellable natives accept the token as their **last** argument.
+///
+/// # Returns
+/// - The remaining arguments
+/// - The token, if one was passed
+pub fn take_cancel_token(mut args: Vec<Value>) -> (Vec<Value>, Option<CancelToken>) {
+    match args.last() {
+        Some(Value::CancelToken(token)) => {
+            let token = token.clone();
+            args.pop();
+            (args, Some(token))
+        }
+        _ => (args, None),
+    }
+}
+
+/// Wraps an async native so it honours a trailing `CancelToken`.
+///
+/// # Behavior
+/// - If the token is already cancelled, the operation is skipped and a
+///   `Furure` holding a `CancelledError` is returned
+/// - Otherwise the token is stripped and the native runs normally
+pub fn cancellable(native: Arc<NativeFn>) -> Value {
+    Value::NativeFunction(Arc::new(move |args: Vec<Value>| -> Result<Value, PawxError> {
+        let (args, token) = take_cancel_token(args);
+
+        if let Some(token) = token {
+            if token.is_cancelled() {
+                return Ok(Value::Furure(Box::new(token.to_error())));
+            }
+        }
+
+        native(args)
+    }))
+}
+
+/// Creates the global `CancelToken` constructor.
+///
+/// # PAWX Example
+/// ```pawx
+/// snuggle token = CancelToken();
+/// setTimeout(() -> { meow("never runs") }, 1000, token);
+/// token.cancel("user aborted");
+/// ```
+pub fn create_global_cancel_token_value() -> Value {
+    Value::NativeFunction(Arc::new(|_args: Vec<Value>| -> Result<Value, PawxError> {
+        Ok(Value::CancelToken(CancelToken::new()))
+    }))
+}
+
+/// Resolves a property access on a `CancelToken`.
+///
+/// # Supported Members
+/// - `token.cancel(reason?)`
+/// - `token.isCancelled()`
+/// - `token.onCancel(fn)`
+/// - `token.reason`
+///
+/// # Errors
+/// - If the property does not exist
+pub fn cancel_token_property(token: &CancelToken, name: &str) -> Result<Value, PawxError> {
+    let token = token.clone();
+
+    match name {
+        "cancel" => Ok(Value::NativeFunction(Arc::new(move |args: Vec<Value>| -> Result<Value, PawxError> {
+            let reason = match args.first() {
+                Some(Value::String(s)) => Some(s.to_string()),
+                Some(Value::Null) | None => None,
+                Some(other) => Some(other.stringify()),
+            };
+
+            token.cancel(reason)?;
+            Ok(Value::Null)
+        }))),
+
+        "isCancelled" => Ok(Value::NativeFunction(Arc::new(move |_args: Vec<Value>| -> Result<Value, PawxError> {
+            Ok(Value::Bool(token.is_cancelled()))
+        }))),
+
+        "onCancel" => Ok(Value::NativeFunction(Arc::new(move |args: Vec<Value>| -> Result<Value, PawxError> {
+            match args.first() {
+                Some(cb @ Value::NativeFunction(_)) => token.on_cancel(cb.clone())?,
+                _ => return raise_error("CancelToken.onCancel(fn) expects a function"),
+            }
+            Ok(Value::Null)
+        }))),
+
+        "reason" => {
+            if token.is_cancelled() {
+                Ok(Value::String(token.reason().into()))
+            } else {
+                Ok(Value::Null)
+            }
+        }
+
+        other => raise_error(format!("Property '{}' not supported on CancelToken", other)),
+    }
+}
@@ -0,0 +1,628 @@
+/*
+ * ============================================================================
+ * PAWX - Code with Claws! 🐾
+ * ============================================================================
+ *
+ * Binary Value Codec for PAWX (`Codec`)
+ *
+ * Supports:
+ *   - Codec.encode(value)   -> array<number> (bytes)
+ *   - Codec.decode(bytes)   -> value
+ *
+ * The wire format is MessagePack. PAWX-only data types travel as
+ * MessagePack extension types, so every data value round-trips exactly:
+ *
+ *   ext 1  Decimal         UTF-8 digits
+ *   ext 2  Tuple           MessagePack array
+ *   ext 3  Float64Array    little-endian f64s
+ *   ext 4  ImmutableList   MessagePack array
+ *   ext 5  ImmutableMap    MessagePack map
+ *   ext 6  Regex           source, NUL, flags
+ *   ext 7  Error           UTF-8 message
+ *   ext 8  Duration        little-endian f64 milliseconds
+ *
+ * Integral numbers are written as MessagePack integers, everything else as
+ * float64. Functions, classes, instances and other runtime handles cannot
+ * be encoded, and neither can cyclic values. Values may be nested at most
+ * `--max-depth` deep, both ways.
+ *
+ * Bytes are arrays of numbers 0-255, the same shape `Fs.readBytes` and
+ * `Fs.writeBytes` use. The encoder and decoder are shared with `Msgpack`,
+ * which uses the plain (extension-free) mode for interop.
+ *
+ * --------------------------------------------------------------------------
+ * Author:   Sam Wilcox
+ * Email:    sam@pawx-lang.com
+ * Website:  https://www.pawx-lang.com
+ * GitHub:   https://github.com/samwilcox/pawx
+ *
+ * License:
+ * This file is part of the PAWX programming language project.
+ *
+ * PAWX is dual-licensed under the terms of:
+ *   - The MIT License
+ *   - The Apache License, Version 2.0
+ *
+ * You may choose either license to govern your use of this software.
+ * Full license text available at:
+ *     https://license.pawx-lang.com
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under these licenses is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *
+ * ==========================================================================
+ */
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use crate::error::PawxError;
+use crate::interpreter::calls::{builtin_error, raise_error};
+use crate::interpreter::limits;
+use crate::prototypes::array::create_array_proto;
+use crate::prototypes::regex::PawxRegex;
+use crate::value::Value;
+
+fn native(f: impl Fn(Vec<Value>) -> Result<Value, PawxError> + 'static) -> Value {
+    Value::NativeFunction(Arc::new(f))
+}
+
+const EXT_DECIMAL: i8 = 1;
+const EXT_TUPLE: i8 = 2;
+const EXT_FLOAT64_ARRAY: i8 = 3;
+const EXT_IMMUTABLE_LIST: i8 = 4;
+const EXT_IMMUTABLE_MAP: i8 = 5;
+const EXT_REGEX: i8 = 6;
+const EXT_ERROR: i8 = 7;
+const EXT_DURATION: i8 = 8;
+
+/// How PAWX-only types are written.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// Exact round-trips through extension types (`Codec`).
+    Pawx,
+
+    /// Plain MessagePack for other languages (`Msgpack`): decimals and
+    /// regexes become strings, tuples and lists become arrays, immutable
+    /// maps become maps.
+    Plain,
+}
+
+/* ============================================================================
+ * BYTES <-> ARRAYS
+ * ============================================================================
+ */
+
+/// Wraps bytes as a PAWX array of numbers.
+pub fn bytes_to_array(bytes: &[u8]) -> Value {
+    Value::Array {
+        values: Rc::new(RefCell::new(
+            bytes.iter().map(|b| Value::Number(*b as f64)).collect(),
+        )),
+        proto: create_array_proto(),
+    }
+}
+
+/// Reads a PAWX array of numbers 0-255 as bytes.
+pub fn array_to_bytes(value: Option<&Value>, usage: &str) -> Result<Vec<u8>, PawxError> {
+    let values = match value {
+        Some(Value::Array { values, .. }) => values.borrow().clone(),
+        _ => return raise_error(format!("{} expects an array of bytes", usage)),
+    };
+
+    values
+        .iter()
+        .map(|v| Ok(match v {
+            Value::Number(n) if n.fract() == 0.0 && (0.0..=255.0).contains(n) => *n as u8,
+            other => return raise_error(format!("{}: invalid byte {}", usage, other.stringify())),
+        }))
+        .collect()
+}
+
+/* ============================================================================
+ * ENCODER
+ * ============================================================================
+ */
+
+fn write_len(out: &mut Vec<u8>, len: usize, fix: Option<(u8, usize)>, tags: [u8; 3]) {
+    match fix {
+        Some((base, max)) if len <= max => out.push(base | len as u8),
+        _ if len <= u8::MAX as usize && tags[0] != 0 => {
+            out.push(tags[0]);
+            out.push(len as u8);
+        }
+        _ if len <= u16::MAX as usize => {
+            out.push(tags[1]);
+            out.extend_from_slice(&(len as u16).to_be_bytes());
+        }
+        _ => {
+            out.push(tags[2]);
+            out.extend_from_slice(&(len as u32).to_be_bytes());
+        }
+    }
+}
+
+fn write_str(out: &mut Vec<u8>, s: &str) {
+    write_len(out, s.len(), Some((0xa0, 31)), [0xd9, 0xda, 0xdb]);
+    out.extend_from_slice(s.as_bytes());
+}
+
+fn write_array_header(out: &mut Vec<u8>, len: usize) {
+    write_len(out, len, Some((0x90, 15)), [0, 0xdc, 0xdd]);
+}
+
+fn write_map_header(out: &mut Vec<u8>, len: usize) {
+    write_len(out, len, Some((0x80, 15)), [0, 0xde, 0xdf]);
+}
+
+fn write_ext(out: &mut Vec<u8>, kind: i8, data: &[u8]) {
+    match data.len() {
+        1 => out.push(0xd4),
+        2 => out.push(0xd5),
+        4 => out.push(0xd6),
+        8 => out.push(0xd7),
+        16 => out.push(0xd8),
+        len => write_len(out, len, None, [0xc7, 0xc8, 0xc9]),
+    }
+    out.push(kind as u8);
+    out.extend_from_slice(data);
+}
+
+fn write_number(out: &mut Vec<u8>, n: f64) {
+    if n.fract() != 0.0 || !n.is_finite() || n.abs() > 9_007_199_254_740_991.0 || (n == 0.0 && n.is_sign_negative()) {
+        out.push(0xcb);
+        out.extend_from_slice(&n.to_be_bytes());
+        return;
+    }
+
+    let i = n as i64;
+    match i {
+        0..=127 => out.push(i as u8),
+        -32..=-1 => out.push(i as i8 as u8),
+        128..=0xff => {
+            out.push(0xcc);
+            out.push(i as u8);
+        }
+        0x100..=0xffff => {
+            out.push(0xcd);
+            out.extend_from_slice(&(i as u16).to_be_bytes());
+        }
+        0x1_0000..=0xffff_ffff => {
+            out.push(0xce);
+            out.extend_from_slice(&(i as u32).to_be_bytes());
+        }
+        0x1_0000_0000.. => {
+            out.push(0xcf);
+            out.extend_from_slice(&(i as u64).to_be_bytes());
+        }
+        -128..=-33 => {
+            out.push(0xd0);
+            out.push(i as i8 as u8);
+        }
+        -32768..=-129 => {
+            out.push(0xd1);
+            out.extend_from_slice(&(i as i16).to_be_bytes());
+        }
+        -2_147_483_648..=-32769 => {
+            out.push(0xd2);
+            out.extend_from_slice(&(i as i32).to_be_bytes());
+        }
+        _ => {
+            out.push(0xd3);
+            out.extend_from_slice(&i.to_be_bytes());
+        }
+    }
+}
+
+/// The containers being encoded around the current value, innermost last.
+///
+/// Arrays and objects are recorded by address so a value holding itself is
+/// rejected instead of recursing forever; other containers only count
+/// towards the depth limit.
+struct Open {
+    ids: Vec<*const ()>,
+    mode: Mode,
+}
+
+impl Open {
+    fn enter(&mut self, id: *const ()) -> Result<(), String> {
+        if !id.is_null() && self.ids.contains(&id) {
+            return Err("cannot encode a cyclic value".to_string());
+        }
+        if self.ids.len() >= limits::max_depth() {
+            return Err(format!("value is nested more than {} levels deep", limits::max_depth()));
+        }
+        self.ids.push(id);
+        Ok(())
+    }
+
+    fn seq<'a>(&mut self, out: &mut Vec<u8>, id: *const (), items: impl ExactSizeIterator<Item = &'a Value>) -> Result<(), String> {
+        self.enter(id)?;
+        write_array_header(out, items.len());
+        for item in items {
+            encode_into(item, out, self)?;
+        }
+        self.ids.pop();
+        Ok(())
+    }
+
+    fn entries<'a>(
+        &mut self,
+        out: &mut Vec<u8>,
+        id: *const (),
+        entries: impl ExactSizeIterator<Item = (&'a String, &'a Value)>,
+    ) -> Result<(), String> {
+        self.enter(id)?;
+        write_map_header(out, entries.len());
+        for (k, v) in entries {
+            write_str(out, k);
+            encode_into(v, out, self)?;
+        }
+        self.ids.pop();
+        Ok(())
+    }
+}
+
+/// Appends the MessagePack encoding of `value` to `out`.
+///
+/// # Errors
+/// A message naming the first value that cannot be encoded.
+fn encode_into(value: &Value, out: &mut Vec<u8>, open: &mut Open) -> Result<(), String> {
+    let mode = open.mode;
+    let none = std::ptr::null();
+
+    match value {
+        Value::Null => out.push(0xc0),
+        Value::Bool(false) => out.push(0xc2),
+        Value::Bool(true) => out.push(0xc3),
+        Value::Number(n) => write_number(out, *n),
+        Value::String(s) => write_str(out, s),
+
+        Value::Array { values, .. } => open.seq(out, Rc::as_ptr(values) as *const (), values.borrow().iter())?,
+
+        Value::Object { fields } => {
+            // Sorted keys keep encodings deterministic
+            let id = Rc::as_ptr(fields) as *const ();
+            let fields = fields.borrow();
+            let mut entries: Vec<(&String, &Value)> = fields.iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(b.0));
+            open.entries(out, id, entries.into_iter())?;
+        }
+
+        Value::Decimal(d) => match mode {
+            Mode::Pawx => write_ext(out, EXT_DECIMAL, d.to_string().as_bytes()),
+            Mode::Plain => write_str(out, &d.to_string()),
+        },
+
+        Value::Tuple(values) => match mode {
+            Mode::Pawx => {
+                let mut inner = Vec::new();
+                open.seq(&mut inner, none, values.iter())?;
+                write_ext(out, EXT_TUPLE, &inner);
+            }
+            Mode::Plain => open.seq(out, none, values.iter())?,
+        },
+
+        Value::Float64Array(data) => match mode {
+            Mode::Pawx => {
+                let bytes: Vec<u8> = data.borrow().iter().flat_map(|n| n.to_le_bytes()).collect();
+                write_ext(out, EXT_FLOAT64_ARRAY, &bytes);
+            }
+            Mode::Plain => {
+                let data = data.borrow();
+                write_array_header(out, data.len());
+                for n in data.iter() {
+                    out.push(0xcb);
+                    out.extend_from_slice(&n.to_be_bytes());
+                }
+            }
+        },
+
+        Value::ImmutableList(list) => match mode {
+            Mode::Pawx => {
+                let mut inner = Vec::new();
+                open.seq(&mut inner, none, list.iter())?;
+                write_ext(out, EXT_IMMUTABLE_LIST, &inner);
+            }
+            Mode::Plain => open.seq(out, none, list.iter())?,
+        },
+
+        Value::ImmutableMap(map) => match mode {
+            Mode::Pawx => {
+                let mut inner = Vec::new();
+                open.entries(&mut inner, none, map.iter())?;
+                write_ext(out, EXT_IMMUTABLE_MAP, &inner);
+            }
+            Mode::Plain => open.entries(out, none, map.iter())?,
+        },
+
+        Value::Regex(re) => {
+            let text = format!("{}\0{}", re.as_str(), re.flags());
+            match mode {
+                Mode::Pawx => write_ext(out, EXT_REGEX, text.as_bytes()),
+                Mode::Plain => write_str(out, &format!("/{}/{}", re.as_str(), re.flags())),
+            }
+        }
+
+        Value::Error { message } => match mode {
+            Mode::Pawx => write_ext(out, EXT_ERROR, message.as_bytes()),
+            Mode::Plain => write_str(out, message),
+        },
+
+        Value::Duration(ms) => match mode {
+            Mode::Pawx => write_ext(out, EXT_DURATION, &ms.to_le_bytes()),
+            Mode::Plain => write_number(out, *ms),
+        },
+
+        other => return Err(format!("cannot encode a {}", other.type_name())),
+    }
+
+    Ok(())
+}
+
+/* ============================================================================
+ * DECODER
+ * ============================================================================
+ */
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    mode: Mode,
+    /// Arrays and maps open around the current value.
+    depth: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], String> {
+        let end = self.pos.checked_add(n).filter(|end| *end <= self.bytes.len());
+        match end {
+            Some(end) => {
+                let slice = &self.bytes[self.pos..end];
+                self.pos = end;
+                Ok(slice)
+            }
+            None => Err(format!("unexpected end of input at byte {}", self.pos)),
+        }
+    }
+
+    fn byte(&mut self) -> Result<u8, String> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn uint(&mut self, width: usize) -> Result<u64, String> {
+        Ok(self.take(width)?.iter().fold(0u64, |acc, b| (acc << 8) | *b as u64))
+    }
+
+    fn int(&mut self, width: usize) -> Result<i64, String> {
+        let raw = self.uint(width)?;
+        let shift = 64 - width * 8;
+        Ok(((raw << shift) as i64) >> shift)
+    }
+
+    fn string(&mut self, len: usize) -> Result<String, String> {
+        let bytes = self.take(len)?;
+        String::from_utf8(bytes.to_vec()).map_err(|_| "invalid UTF-8 in string".to_string())
+    }
+
+    fn enter(&mut self) -> Result<(), String> {
+        if self.depth >= limits::max_depth() {
+            return Err(format!("value is nested more than {} levels deep", limits::max_depth()));
+        }
+        self.depth += 1;
+        Ok(())
+    }
+
+    fn array(&mut self, len: usize) -> Result<Vec<Value>, String> {
+        self.enter()?;
+        let values = (0..len).map(|_| self.value()).collect();
+        self.depth -= 1;
+        values
+    }
+
+    fn map(&mut self, len: usize) -> Result<Vec<(String, Value)>, String> {
+        self.enter()?;
+        let entries = (0..len)
+            .map(|_| {
+                let key = match self.value()? {
+                    Value::String(s) => s.to_string(),
+                    Value::Number(n) => n.to_string(),
+                    Value::Bool(b) => b.to_string(),
+                    Value::Null => "null".to_string(),
+                    other => return Err(format!("unsupported map key type {}", other.type_name())),
+                };
+                Ok((key, self.value()?))
+            })
+            .collect();
+        self.depth -= 1;
+        entries
+    }
+
+    fn ext(&mut self, len: usize) -> Result<Value, String> {
+        let kind = self.byte()? as i8;
+        let data = self.take(len)?;
+
+        if self.mode == Mode::Plain {
+            return Ok(bytes_to_array(data));
+        }
+
+        let nested = |data: &[u8]| -> Result<Value, String> {
+            let mut inner = Reader { bytes: data, pos: 0, mode: Mode::Pawx, depth: self.depth };
+            inner.value()
+        };
+
+        let text = || String::from_utf8(data.to_vec()).map_err(|_| "invalid UTF-8 in extension".to_string());
+
+        match kind {
+            EXT_DECIMAL => text()?
+                .parse::<rust_decimal::Decimal>()
+                .map(Value::Decimal)
+                .map_err(|e| format!("invalid decimal: {}", e)),
+
+            EXT_TUPLE => match nested(data)? {
+                Value::Array { values, .. } => Ok(Value::Tuple(values.borrow().clone())),
+                _ => Err("invalid tuple payload".to_string()),
+            },
+
+            EXT_FLOAT64_ARRAY => {
+                if data.len() % 8 != 0 {
+                    return Err("invalid Float64Array payload".to_string());
+                }
+                let values = data
+                    .chunks_exact(8)
+                    .map(|c| f64::from_le_bytes(c.try_into().unwrap()))
+                    .collect();
+                Ok(Value::Float64Array(Rc::new(RefCell::new(values))))
+            }
+
+            EXT_IMMUTABLE_LIST => match nested(data)? {
+                Value::Array { values, .. } => {
+                    Ok(Value::ImmutableList(Box::new(values.borrow().iter().cloned().collect())))
+                }
+                _ => Err("invalid ImmutableList payload".to_string()),
+            },
+
+            EXT_IMMUTABLE_MAP => match nested(data)? {
+                Value::Object { fields } => Ok(Value::ImmutableMap(
+                    fields.borrow().iter().map(|(k, v)| (k.clone(), v.clone())).collect(),
+                )),
+                _ => Err("invalid ImmutableMap payload".to_string()),
+            },
+
+            EXT_REGEX => {
+                let text = text()?;
+                let (source, flags) = text.split_once('\0').unwrap_or((&text, ""));
+                PawxRegex::new(source, flags).map(Value::Regex)
+            }
+
+            EXT_ERROR => Ok(Value::Error { message: text()? }),
+
+            EXT_DURATION => data
+                .try_into()
+                .map(|bytes| Value::Duration(f64::from_le_bytes(bytes)))
+                .map_err(|_| "invalid Duration payload".to_string()),
+
+            other => Err(format!("unknown extension type {}", other)),
+        }
+    }
+
+    fn value(&mut self) -> Result<Value, String> {
+        let tag = self.byte()?;
+
+        let array = |values: Vec<Value>| Value::Array {
+            values: Rc::new(RefCell::new(values)),
+            proto: create_array_proto(),
+        };
+
+        let object = |entries: Vec<(String, Value)>| Value::Object {
+            fields: Rc::new(RefCell::new(entries.into_iter().collect::<HashMap<_, _>>())),
+        };
+
+        Ok(match tag {
+            0x00..=0x7f => Value::Number(tag as f64),
+            0xe0..=0xff => Value::Number(tag as i8 as f64),
+            0x80..=0x8f => object(self.map((tag & 0x0f) as usize)?),
+            0x90..=0x9f => array(self.array((tag & 0x0f) as usize)?),
+            0xa0..=0xbf => Value::String(self.string((tag & 0x1f) as usize)?.into()),
+
+            0xc0 => Value::Null,
+            0xc2 => Value::Bool(false),
+            0xc3 => Value::Bool(true),
+
+            // bin 8/16/32 → byte arrays
+            0xc4..=0xc6 => {
+                let len = self.uint(1 << (tag - 0xc4))? as usize;
+                bytes_to_array(self.take(len)?)
+            }
+
+            0xc7..=0xc9 => {
+                let len = self.uint(1 << (tag - 0xc7))? as usize;
+                self.ext(len)?
+            }
+
+            0xca => Value::Number(f32::from_be_bytes(self.take(4)?.try_into().unwrap()) as f64),
+            0xcb => Value::Number(f64::from_be_bytes(self.take(8)?.try_into().unwrap())),
+
+            0xcc..=0xcf => Value::Number(self.uint(1 << (tag - 0xcc))? as f64),
+            0xd0..=0xd3 => Value::Number(self.int(1 << (tag - 0xd0))? as f64),
+
+            0xd4..=0xd8 => self.ext(1 << (tag - 0xd4))?,
+
+            0xd9..=0xdb => {
+                let len = self.uint(1 << (tag - 0xd9))? as usize;
+                Value::String(self.string(len)?.into())
+            }
+
+            0xdc | 0xdd => {
+                let len = self.uint(if tag == 0xdc { 2 } else { 4 })? as usize;
+                array(self.array(len)?)
+            }
+
+            0xde | 0xdf => {
+                let len = self.uint(if tag == 0xde { 2 } else { 4 })? as usize;
+                object(self.map(len)?)
+            }
+
+            other => return Err(format!("invalid type tag 0x{:02x} at byte {}", other, self.pos - 1)),
+        })
+    }
+}
+
+/// Decodes exactly one value from `bytes`.
+///
+/// # Errors
+/// On malformed input or trailing bytes.
+pub fn decode(bytes: &[u8], mode: Mode) -> Result<Value, String> {
+    let mut reader = Reader { bytes, pos: 0, mode, depth: 0 };
+    let value = reader.value()?;
+
+    if reader.pos != bytes.len() {
+        return Err(format!("{} trailing bytes after value", bytes.len() - reader.pos));
+    }
+
+    Ok(value)
+}
+
+/// Encodes one value.
+///
+/// # Errors
+/// If the value (or anything inside it) cannot be encoded.
+pub fn encode(value: &Value, mode: Mode) -> Result<Vec<u8>, String> {
+    let mut out = Vec::new();
+    encode_into(value, &mut out, &mut Open { ids: Vec::new(), mode })?;
+    Ok(out)
+}
+
+/* ============================================================================
+ * GLOBAL OBJECT
+ * ============================================================================
+ */
+
+/// Builds the global `Codec` object.
+pub fn create_global_codec_object() -> HashMap<String, Value> {
+    let mut codec = HashMap::new();
+
+    codec.insert(
+        "encode".to_string(),
+        native(|args| {
+            let value = args.first().cloned().unwrap_or(Value::Null);
+            match encode(&value, Mode::Pawx) {
+                Ok(bytes) => Ok(bytes_to_array(&bytes)),
+                Err(e) => raise_error(format!("Codec.encode(value): {}", e)),
+            }
+        }),
+    );
+
+    codec.insert(
+        "decode".to_string(),
+        native(|args| {
+            let bytes = array_to_bytes(args.first(), "Codec.decode(bytes)")?;
+            decode(&bytes, Mode::Pawx).map_err(|e| builtin_error(format!("Codec.decode(bytes): {}", e)))
+        }),
+    );
+
+    codec
+}
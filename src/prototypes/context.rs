@@ -0,0 +1,218 @@
+/*
+ * ============================================================================
+ * PAWX - Code with Claws! 🐾
+ * ============================================================================
+ *
+ * Async-local Context Storage for PAWX (`Context`)
+ *
+ * Supports:
+ *   - Context.run(values?, fn)   run fn in a new scope, returning its result
+ *   - Context.set(key, value)    store a value in the current scope
+ *   - Context.get(key)           the value, or null
+ *   - Context.bind(fn)           fn, always run in the scope current now
+ *
+ * A scope starts with a copy of its parent's values, so `set` inside
+ * `Context.run` is never seen outside it. The scope follows the work it
+ * started through the event loop: timers, and Semaphore callbacks that
+ * wait for a permit, run in the scope they were created in, even if
+ * another scope is current by the time they fire.
+ *
+ * The HTTP server runs every request's handler in its own scope holding
+ * `requestId`, taken from the request's `X-Request-Id` header or
+ * generated, and echoes it back in the response. The HTTP client sends
+ * the current `requestId` as `X-Request-Id`, so logs from every service
+ * one request passes through can be correlated:
+ *
+ * ```pawx
+ * purr log -> (msg) -> { meow("[" + Context.get("requestId") + "] " + msg); }
+ * ```
+ *
+ * --------------------------------------------------------------------------
+ * Author:   Sam Wilcox
+ * Email:    sam@pawx-lang.com
+ * Website:  https://www.pawx-lang.com
+ * GitHub:   https://github.com/samwilcox/pawx
+ *
+ * License:
+ * This file is part of the PAWX programming language project.
+ *
+ * PAWX is dual-licensed under the terms of:
+ *   - The MIT License
+ *   - The Apache License, Version 2.0
+ *
+ * You may choose either license to govern your use of this software.
+ * Full license text available at:
+ *     https://license.pawx-lang.com
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under these licenses is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *
+ * ==========================================================================
+ */
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use rand::Rng;
+
+use crate::error::PawxError;
+use crate::interpreter::calls::raise_error;
+use crate::value::Value;
+
+/// The context key the HTTP server and client use for the request id.
+pub const REQUEST_ID: &str = "requestId";
+
+/// The values of one scope.
+type Scope = Rc<RefCell<HashMap<String, Value>>>;
+
+thread_local! {
+    /// The scope code on this thread is running in.
+    static CURRENT: RefCell<Scope> = RefCell::new(Scope::default());
+}
+
+fn current() -> Scope {
+    CURRENT.with(|c| c.borrow().clone())
+}
+
+/// Makes `scope` current until dropped, then puts the previous one back,
+/// also when the code in between fails.
+struct Enter {
+    previous: Scope,
+}
+
+impl Enter {
+    fn new(scope: Scope) -> Self {
+        Enter {
+            previous: CURRENT.with(|c| c.replace(scope)),
+        }
+    }
+}
+
+impl Drop for Enter {
+    fn drop(&mut self) {
+        CURRENT.with(|c| *c.borrow_mut() = self.previous.clone());
+    }
+}
+
+/* ============================================================================
+ * RUST API
+ * ============================================================================
+ */
+
+/// Runs `f` in a new scope: the current values plus `values`.
+pub fn scope<R>(values: Vec<(String, Value)>, f: impl FnOnce() -> R) -> R {
+    let mut inherited = current().borrow().clone();
+    inherited.extend(values);
+
+    let _enter = Enter::new(Rc::new(RefCell::new(inherited)));
+    f()
+}
+
+/// Returns `key` in the current scope.
+pub fn get(key: &str) -> Option<Value> {
+    current().borrow().get(key).cloned()
+}
+
+/// Wraps `callback` to run in the current scope whenever it is called.
+///
+/// Anything that calls back later from the event loop binds its callback
+/// when it is handed over. Values other than functions are returned as
+/// they are.
+pub fn bind(callback: Value) -> Value {
+    let Value::NativeFunction(f) = callback else {
+        return callback;
+    };
+
+    let scope = current();
+    Value::NativeFunction(Arc::new(move |args| {
+        let _enter = Enter::new(scope.clone());
+        f(args)
+    }))
+}
+
+/// The id of the request being handled, if any.
+pub fn request_id() -> Option<String> {
+    match get(REQUEST_ID) {
+        Some(Value::String(id)) => Some(id.to_string()),
+        _ => None,
+    }
+}
+
+/// A new random request id, formatted as a version 4 UUID.
+pub fn new_request_id() -> String {
+    let mut bits = rand::thread_rng().gen::<u128>();
+    bits = (bits & !(0xF << 76)) | (0x4 << 76); // version 4
+    bits = (bits & !(0x3 << 62)) | (0x2 << 62); // RFC 4122 variant
+
+    let hex = format!("{:032x}", bits);
+    format!("{}-{}-{}-{}-{}", &hex[..8], &hex[8..12], &hex[12..16], &hex[16..20], &hex[20..])
+}
+
+/* ============================================================================
+ * PAWX API
+ * ============================================================================
+ */
+
+fn native(f: impl Fn(Vec<Value>) -> Result<Value, PawxError> + 'static) -> Value {
+    Value::NativeFunction(Arc::new(f))
+}
+
+fn key_arg(name: &str, args: &[Value]) -> Result<String, PawxError> {
+    match args.first() {
+        Some(Value::String(key)) => Ok(key.to_string()),
+        _ => raise_error(format!("Context.{}(key) expects a string key", name)),
+    }
+}
+
+/// Creates the global `Context` object.
+pub fn create_global_context_object() -> HashMap<String, Value> {
+    let mut context = HashMap::new();
+
+    context.insert(
+        "run".into(),
+        native(|args| {
+            let (values, callback) = match args.as_slice() {
+                [callback] => (Vec::new(), callback),
+                [Value::Null, callback] => (Vec::new(), callback),
+                [Value::Object { fields }, callback] => {
+                    let values = fields.borrow().iter().map(|(k, v)| (k.clone(), v.clone())).collect();
+                    (values, callback)
+                }
+                _ => return raise_error("Context.run(values?, fn) expects an optional object and a function"),
+            };
+
+            match callback {
+                Value::NativeFunction(f) => scope(values, || f(Vec::new())),
+                other => raise_error(format!("Context.run(values?, fn) expects a function, got {}", other.type_name())),
+            }
+        }),
+    );
+
+    context.insert(
+        "set".into(),
+        native(|args| {
+            let key = key_arg("set", &args)?;
+            let value = args.get(1).cloned().unwrap_or(Value::Null);
+            current().borrow_mut().insert(key, value);
+            Ok(Value::Null)
+        }),
+    );
+
+    context.insert(
+        "get".into(),
+        native(|args| Ok(get(&key_arg("get", &args)?).unwrap_or(Value::Null))),
+    );
+
+    context.insert(
+        "bind".into(),
+        native(|args| match args.first() {
+            Some(f @ Value::NativeFunction(_)) => Ok(bind(f.clone())),
+            _ => raise_error("Context.bind(fn) expects a function"),
+        }),
+    );
+
+    context
+}
@@ -0,0 +1,422 @@
+/*
+ * ============================================================================
+ * PAWX - Code with Claws! 🐾
+ * ============================================================================
+ *
+ * Exact Decimal Arithmetic for PAWX
+ *
+ * `Decimal` values hold base-10 numbers exactly (up to 28 significant
+ * digits), so sums like `0.1 + 0.2` come out as `0.3` and money never
+ * picks up float noise.
+ *
+ * Supports:
+ *   - Construction from strings, numbers, and other decimals
+ *   - `+ - * / %` and comparisons through the normal operators
+ *   - Rounding to a number of places with explicit rounding modes
+ *   - Fixed-point string formatting
+ *
+ * Installed API:
+ *   - Decimal.new(value)
+ *   - Decimal.parse(string)
+ *   - Decimal.isDecimal(value)
+ *   - Decimal.sum(array)
+ *   - Decimal.min(...values) / Decimal.max(...values)
+ *
+ * Numbers mixed into decimal arithmetic are converted through their
+ * shortest printed form, so `Decimal.new("1.10") + 0.1` is exactly `1.20`.
+ *
+ * --------------------------------------------------------------------------
+ * Author:   Sam Wilcox
+ * Email:    sam@pawx-lang.com
+ * Website:  https://www.pawx-lang.com
+ * GitHub:   https://github.com/samwilcox/pawx
+ *
+ * License:
+ * This file is part of the PAWX programming language project.
+ *
+ * PAWX is dual-licensed under the terms of:
+ *   - The MIT License
+ *   - The Apache License, Version 2.0
+ *
+ * You may choose either license to govern your use of this software.
+ * Full license text available at:
+ *     https://license.pawx-lang.com
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under these licenses is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *
+ * ==========================================================================
+ */
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::{Decimal, RoundingStrategy};
+
+use crate::error::PawxError;
+use crate::interpreter::calls::{builtin_error, raise_error};
+use crate::value::Value;
+
+/// Largest number of decimal places a `Decimal` can carry.
+const MAX_SCALE: u32 = 28;
+
+/* ============================================================================
+ * CONVERSION
+ * ============================================================================
+ */
+
+/// Parses a decimal string.
+///
+/// Accepts plain (`"-12.50"`) and scientific (`"1.5e3"`) notation, with
+/// `_` allowed as a digit separator. Inputs with more precision than a
+/// `Decimal` can hold are rejected rather than silently rounded.
+pub fn parse_decimal(text: &str) -> Result<Decimal, String> {
+    let cleaned = text.trim().replace('_', "");
+
+    let parsed = if cleaned.contains(['e', 'E']) {
+        Decimal::from_scientific(&cleaned)
+    } else {
+        Decimal::from_str_exact(&cleaned)
+    };
+
+    parsed.map_err(|e| format!("invalid decimal '{}': {}", text, e))
+}
+
+/// Converts a PAWX number to a decimal via its shortest printed form.
+pub fn decimal_from_number(n: f64) -> Result<Decimal, String> {
+    if !n.is_finite() {
+        return Err(format!("cannot convert {} to a Decimal", n));
+    }
+
+    Decimal::from_str(&n.to_string()).map_err(|_| format!("{} is out of range for a Decimal", n))
+}
+
+/// Converts a Number, String, or Decimal value to a decimal.
+pub fn to_decimal(value: &Value) -> Result<Decimal, String> {
+    match value {
+        Value::Decimal(d) => Ok(*d),
+        Value::Number(n) => decimal_from_number(*n),
+        Value::String(s) => parse_decimal(s),
+        other => Err(format!("cannot convert {} to a Decimal", other.type_name())),
+    }
+}
+
+fn expect_decimal(value: Option<&Value>, context: &str) -> Result<Decimal, PawxError> {
+    match value {
+        Some(v) => Ok(to_decimal(v).map_err(|e| builtin_error(format!("{}: {}", context, e)))?),
+        None => raise_error(format!("{} expects a value", context)),
+    }
+}
+
+/* ============================================================================
+ * ARITHMETIC
+ * ============================================================================
+ */
+
+/// Applies an arithmetic operator with overflow and zero-division checks.
+fn arithmetic(a: Decimal, b: Decimal, op: &str) -> Result<Decimal, String> {
+    let result = match op {
+        "+" => a.checked_add(b),
+        "-" => a.checked_sub(b),
+        "*" => a.checked_mul(b),
+        "/" | "%" if b.is_zero() => return Err("division by zero".to_string()),
+        "/" => a.checked_div(b),
+        "%" => a.checked_rem(b),
+        _ => unreachable!("not an arithmetic operator: {}", op),
+    };
+
+    result.ok_or_else(|| format!("decimal overflow in {} {} {}", a, op, b))
+}
+
+/// Evaluates a binary operator where at least one side is a `Decimal`.
+///
+/// # Returns
+/// - `None` if the operator / operand combination is not a decimal
+///   operation, so the caller falls back to its usual rules
+/// - `Some(Err(..))` on overflow, division by zero, or an operand that
+///   cannot be converted (e.g. `NaN`)
+pub fn binary_op(left: &Value, right: &Value, op: &str) -> Option<Result<Value, String>> {
+    // String concatenation keeps working: "Total: " + price
+    if op == "+" {
+        match (left, right) {
+            (Value::String(s), Value::Decimal(d)) => return Some(Ok(Value::String(format!("{}{}", s, d).into()))),
+            (Value::Decimal(d), Value::String(s)) => return Some(Ok(Value::String(format!("{}{}", d, s).into()))),
+            _ => {}
+        }
+    }
+
+    let numeric = |v: &Value| matches!(v, Value::Decimal(_) | Value::Number(_));
+    if !numeric(left) || !numeric(right) {
+        return None;
+    }
+
+    let operands = to_decimal(left).and_then(|a| Ok((a, to_decimal(right)?)));
+
+    let value = match op {
+        "+" | "-" | "*" | "/" | "%" => operands
+            .and_then(|(a, b)| arithmetic(a, b, op))
+            .map(Value::Decimal),
+
+        "==" | "!=" | "<" | ">" | "<=" | ">=" => operands.map(|(a, b)| {
+            let ordering = a.cmp(&b);
+            Value::Bool(match op {
+                "==" => ordering == Ordering::Equal,
+                "!=" => ordering != Ordering::Equal,
+                "<" => ordering == Ordering::Less,
+                ">" => ordering == Ordering::Greater,
+                "<=" => ordering != Ordering::Greater,
+                _ => ordering != Ordering::Less,
+            })
+        }),
+
+        _ => return None,
+    };
+
+    Some(value)
+}
+
+/* ============================================================================
+ * ROUNDING
+ * ============================================================================
+ */
+
+/// Maps a PAWX rounding mode name to a strategy.
+///
+/// # Modes
+/// - `"halfUp"` (default) → ties away from zero: 2.5 → 3, -2.5 → -3
+/// - `"halfEven"` → banker's rounding: 2.5 → 2, 3.5 → 4
+/// - `"halfDown"` → ties toward zero: 2.5 → 2
+/// - `"up"` → away from zero
+/// - `"down"` → toward zero (truncate)
+/// - `"ceil"` / `"floor"` → toward +∞ / −∞
+fn rounding_mode(value: Option<&Value>, context: &str) -> Result<RoundingStrategy, PawxError> {
+    match value {
+        Some(Value::Null) | None => Ok(RoundingStrategy::MidpointAwayFromZero),
+        Some(Value::String(mode)) => match &**mode {
+            "halfUp" => Ok(RoundingStrategy::MidpointAwayFromZero),
+            "halfEven" => Ok(RoundingStrategy::MidpointNearestEven),
+            "halfDown" => Ok(RoundingStrategy::MidpointTowardZero),
+            "up" => Ok(RoundingStrategy::AwayFromZero),
+            "down" => Ok(RoundingStrategy::ToZero),
+            "ceil" => Ok(RoundingStrategy::ToPositiveInfinity),
+            "floor" => Ok(RoundingStrategy::ToNegativeInfinity),
+            other => raise_error(format!(
+                "{}: unknown rounding mode '{}' (use halfUp, halfEven, halfDown, up, down, ceil or floor)",
+                context, other
+            )),
+        },
+        _ => raise_error(format!("{}: rounding mode must be a string", context)),
+    }
+}
+
+fn places(value: Option<&Value>, context: &str) -> Result<u32, PawxError> {
+    match value {
+        Some(Value::Null) | None => Ok(0),
+        Some(Value::Number(n)) if n.fract() == 0.0 && (0.0..=MAX_SCALE as f64).contains(n) => Ok(*n as u32),
+        _ => raise_error(format!("{}: places must be an integer between 0 and {}", context, MAX_SCALE)),
+    }
+}
+
+/* ============================================================================
+ * DECIMAL VALUES
+ * ============================================================================
+ */
+
+fn method(f: impl Fn(Vec<Value>) -> Result<Value, PawxError> + 'static) -> Value {
+    Value::NativeFunction(Arc::new(f))
+}
+
+/// Resolves a property or method on a decimal value.
+///
+/// # Supported Members
+/// - `add(x)`, `sub(x)`, `mul(x)`, `div(x)`, `rem(x)`
+/// - `neg()`, `abs()`
+/// - `round(places?, mode?)` → rounded decimal (see rounding modes)
+/// - `toFixed(places, mode?)` → string with exactly `places` decimals
+/// - `normalize()` → same value without trailing zeros
+/// - `cmp(x)` → -1, 0, or 1
+/// - `eq(x)`, `lt(x)`, `lte(x)`, `gt(x)`, `gte(x)`
+/// - `isZero()`, `isNegative()`
+/// - `toNumber()`, `toString()`
+/// - `scale` → number of digits after the decimal point
+///
+/// Arguments to arithmetic and comparison methods may be decimals,
+/// numbers, or decimal strings.
+///
+/// # Errors
+/// - On overflow, division by zero, or an unknown property
+pub fn decimal_property(d: &Decimal, name: &str) -> Result<Value, PawxError> {
+    let d = *d;
+
+    match name {
+        "add" | "sub" | "mul" | "div" | "rem" => {
+            let op = match name {
+                "add" => "+",
+                "sub" => "-",
+                "mul" => "*",
+                "div" => "/",
+                _ => "%",
+            };
+            let name = name.to_string();
+
+            Ok(method(move |args| {
+                let context = format!("Decimal.{}(x)", name);
+                let other = expect_decimal(args.first(), &context)?;
+                match arithmetic(d, other, op) {
+                    Ok(result) => Ok(Value::Decimal(result)),
+                    Err(e) => raise_error(format!("{}: {}", context, e)),
+                }
+            }))
+        }
+
+        "neg" => Ok(method(move |_| Ok(Value::Decimal(-d)))),
+        "abs" => Ok(method(move |_| Ok(Value::Decimal(d.abs())))),
+
+        "round" => Ok(method(move |args| {
+            let dp = places(args.first(), "Decimal.round(places?, mode?)")?;
+            let mode = rounding_mode(args.get(1), "Decimal.round(places?, mode?)")?;
+            Ok(Value::Decimal(d.round_dp_with_strategy(dp, mode)))
+        })),
+
+        "toFixed" => Ok(method(move |args| {
+            let dp = places(args.first(), "Decimal.toFixed(places, mode?)")?;
+            let mode = rounding_mode(args.get(1), "Decimal.toFixed(places, mode?)")?;
+
+            let mut rounded = d.round_dp_with_strategy(dp, mode);
+            rounded.rescale(dp);
+            Ok(Value::String(rounded.to_string().into()))
+        })),
+
+        "normalize" => Ok(method(move |_| Ok(Value::Decimal(d.normalize())))),
+
+        "cmp" => Ok(method(move |args| {
+            let other = expect_decimal(args.first(), "Decimal.cmp(x)")?;
+            Ok(Value::Number(match d.cmp(&other) {
+                Ordering::Less => -1.0,
+                Ordering::Equal => 0.0,
+                Ordering::Greater => 1.0,
+            }))
+        })),
+
+        "eq" | "lt" | "lte" | "gt" | "gte" => {
+            let name = name.to_string();
+
+            Ok(method(move |args| {
+                let other = expect_decimal(args.first(), &format!("Decimal.{}(x)", name))?;
+                let ordering = d.cmp(&other);
+                Ok(Value::Bool(match name.as_str() {
+                    "eq" => ordering == Ordering::Equal,
+                    "lt" => ordering == Ordering::Less,
+                    "lte" => ordering != Ordering::Greater,
+                    "gt" => ordering == Ordering::Greater,
+                    _ => ordering != Ordering::Less,
+                }))
+            }))
+        }
+
+        "isZero" => Ok(method(move |_| Ok(Value::Bool(d.is_zero())))),
+        "isNegative" => Ok(method(move |_| Ok(Value::Bool(d.is_sign_negative() && !d.is_zero())))),
+
+        "toNumber" => Ok(method(move |_| Ok(Value::Number(d.to_f64().unwrap_or(f64::NAN))))),
+        "toString" => Ok(method(move |_| Ok(Value::String(d.to_string().into())))),
+
+        "scale" => Ok(Value::Number(d.scale() as f64)),
+
+        other => raise_error(format!("Property '{}' not supported on Decimal", other)),
+    }
+}
+
+/* ============================================================================
+ * GLOBAL DECIMAL NAMESPACE
+ * ============================================================================
+ */
+
+/// Collects decimals from either a single array argument or varargs.
+fn decimal_list(args: &[Value], context: &str) -> Result<Vec<Decimal>, PawxError> {
+    let values: Vec<Value> = match args {
+        [Value::Array { values, .. }] => values.borrow().clone(),
+        _ => args.to_vec(),
+    };
+
+    values
+        .iter()
+        .map(|v| to_decimal(v).map_err(|e| builtin_error(format!("{}: {}", context, e))))
+        .collect()
+}
+
+/// Creates and returns the global `Decimal` namespace for the PAWX runtime.
+///
+/// # Installed Functions
+/// - `Decimal.new(value)` → Decimal from a string, number, or decimal
+/// - `Decimal.parse(str)` → Like `new`, but returns an `Error` value on
+///   invalid input instead of aborting
+/// - `Decimal.isDecimal(value)` → `true` for decimal values
+/// - `Decimal.sum(values)` → Exact sum of an array (or arguments)
+/// - `Decimal.min(values)` / `Decimal.max(values)`
+///
+/// # PAWX Example
+/// ```pawx
+/// snuggle price = Decimal.new("19.99");
+/// snuggle total = price * 3 + Decimal.new("0.10");
+/// meow(total.toFixed(2));   // 60.07
+/// ```
+///
+/// # Returns
+/// A `HashMap<String, Value>` representing the `Decimal` global namespace.
+pub fn create_global_decimal_object() -> HashMap<String, Value> {
+    let mut decimal = HashMap::new();
+
+    decimal.insert(
+        "new".to_string(),
+        method(|args| Ok(Value::Decimal(expect_decimal(args.first(), "Decimal.new(value)")?))),
+    );
+
+    decimal.insert(
+        "parse".to_string(),
+        method(|args| match args.first() {
+            Some(Value::String(s)) => match parse_decimal(s) {
+                Ok(d) => Ok(Value::Decimal(d)),
+                Err(message) => Ok(Value::Error { message }),
+            },
+            _ => raise_error("Decimal.parse(str) expects a string"),
+        }),
+    );
+
+    decimal.insert(
+        "isDecimal".to_string(),
+        method(|args| Ok(Value::Bool(matches!(args.first(), Some(Value::Decimal(_)))))),
+    );
+
+    decimal.insert(
+        "sum".to_string(),
+        method(|args| {
+            let total = decimal_list(&args, "Decimal.sum")?
+                .into_iter()
+                .try_fold(Decimal::ZERO, |acc, d| acc.checked_add(d))
+                .ok_or_else(|| builtin_error("Decimal.sum: decimal overflow"))?;
+            Ok(Value::Decimal(total))
+        }),
+    );
+
+    decimal.insert(
+        "min".to_string(),
+        method(|args| match decimal_list(&args, "Decimal.min")?.into_iter().min() {
+            Some(d) => Ok(Value::Decimal(d)),
+            None => Ok(Value::Null),
+        }),
+    );
+
+    decimal.insert(
+        "max".to_string(),
+        method(|args| match decimal_list(&args, "Decimal.max")?.into_iter().max() {
+            Some(d) => Ok(Value::Decimal(d)),
+            None => Ok(Value::Null),
+        }),
+    );
+
+    decimal
+}
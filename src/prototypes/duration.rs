@@ -0,0 +1,376 @@
+/*
+ * ============================================================================
+ * PAWX - Code with Claws! 🐾
+ * ============================================================================
+ *
+ * Durations for PAWX (`Duration`)
+ *
+ * Supports:
+ *   - Duration.ms / seconds / minutes / hours / days / weeks(n)
+ *   - Duration.parse("1h30m")        units w, d, h, m, s, ms; may be signed
+ *                                    or fractional ("-1.5h", "2m 30s")
+ *   - Duration.between(a, b)         b - a, for two epoch-ms timestamps
+ *   - Duration.since(t) / until(t)   now - t / t - now
+ *   - Duration.isDuration(value)
+ *   - d.ms, d.seconds, d.minutes, d.hours, d.days   the whole length in
+ *                                                   each unit (fractional)
+ *   - d.humanize(relative?)          "2 hours"; relative: "2 hours ago" /
+ *                                    "in 2 hours"
+ *   - d.abs(), d.toString()          toString() gives "1h30m", which parses
+ *                                    back to the same duration
+ *
+ * A duration is a length of time held as milliseconds. It works with the
+ * normal operators:
+ *
+ *   duration ± duration        → Duration
+ *   duration * n, duration / n → Duration
+ *   duration / duration        → Number (the ratio)
+ *   timestamp ± duration       → Number (a timestamp in epoch ms)
+ *   <, <=, ==, ...             → compares lengths
+ *
+ *   snuggle timeout = Duration.parse("1m30s");
+ *   setTimeout(() -> { meow("done"); }, timeout);
+ *   meow(Duration.until(Time.now() - timeout).humanize(true));   // 2 minutes ago
+ *
+ * Anything that takes a delay or a TTL in milliseconds (setTimeout,
+ * setInterval, Time.sleep, Clock.advance, memoize's ttl, a static mount's
+ * maxAge) takes a Duration too. Durations are written to JSON as their
+ * length in milliseconds.
+ *
+ * --------------------------------------------------------------------------
+ * Author:   Sam Wilcox
+ * Email:    sam@pawx-lang.com
+ * Website:  https://www.pawx-lang.com
+ * GitHub:   https://github.com/samwilcox/pawx
+ *
+ * License:
+ * This file is part of the PAWX programming language project.
+ *
+ * PAWX is dual-licensed under the terms of:
+ *   - The MIT License
+ *   - The Apache License, Version 2.0
+ *
+ * You may choose either license to govern your use of this software.
+ * Full license text available at:
+ *     https://license.pawx-lang.com
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under these licenses is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *
+ * ==========================================================================
+ */
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use chrono::Utc;
+
+use crate::error::PawxError;
+use crate::interpreter::calls::raise_error;
+use crate::interpreter::clock::VirtualClock;
+use crate::value::Value;
+
+const SECOND: f64 = 1_000.0;
+const MINUTE: f64 = 60.0 * SECOND;
+const HOUR: f64 = 60.0 * MINUTE;
+const DAY: f64 = 24.0 * HOUR;
+const WEEK: f64 = 7.0 * DAY;
+
+/// Units accepted by `Duration.parse`, longest suffix first so `ms` is not
+/// read as minutes.
+const UNITS: &[(&str, f64)] = &[("ms", 1.0), ("w", WEEK), ("d", DAY), ("h", HOUR), ("m", MINUTE), ("s", SECOND)];
+
+/// Units used by `humanize()`; months and years are approximate.
+const HUMAN_UNITS: &[(&str, f64)] = &[
+    ("year", 365.0 * DAY),
+    ("month", 30.0 * DAY),
+    ("week", WEEK),
+    ("day", DAY),
+    ("hour", HOUR),
+    ("minute", MINUTE),
+    ("second", SECOND),
+];
+
+fn native(f: impl Fn(Vec<Value>) -> Result<Value, PawxError> + 'static) -> Value {
+    Value::NativeFunction(Arc::new(f))
+}
+
+/* ============================================================================
+ * CONVERSION
+ * ============================================================================
+ */
+
+/// The milliseconds in a Number or a Duration, for natives that take a
+/// delay or TTL either way.
+pub fn millis(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(n) => Some(*n),
+        Value::Duration(ms) => Some(*ms),
+        _ => None,
+    }
+}
+
+/// Parses a duration string such as `"1h30m"`, `"-2.5s"` or `"1d 12h"`.
+///
+/// Every number needs a unit; whitespace between parts is ignored.
+pub fn parse_duration(text: &str) -> Result<f64, String> {
+    let trimmed = text.trim();
+    let (sign, mut rest) = match trimmed.strip_prefix('-') {
+        Some(rest) => (-1.0, rest),
+        None => (1.0, trimmed.strip_prefix('+').unwrap_or(trimmed)),
+    };
+
+    if rest.trim().is_empty() {
+        return Err(format!("'{}' is not a duration", text));
+    }
+
+    let mut total = 0.0;
+    while !rest.is_empty() {
+        rest = rest.trim_start();
+
+        let digits = rest.find(|c: char| !(c.is_ascii_digit() || c == '.')).unwrap_or(rest.len());
+        let amount: f64 = rest[..digits]
+            .parse()
+            .map_err(|_| format!("'{}' is not a duration (expected a number at '{}')", text, rest))?;
+        rest = rest[digits..].trim_start();
+
+        let (suffix, unit) = UNITS
+            .iter()
+            .find(|(suffix, _)| rest.starts_with(suffix))
+            .ok_or_else(|| format!("'{}' is not a duration (use units w, d, h, m, s or ms)", text))?;
+
+        total += amount * unit;
+        rest = rest[suffix.len()..].trim_start();
+    }
+
+    Ok(sign * total)
+}
+
+/// Formats a duration the way `Duration.parse` reads it, e.g. `"1h30m"`.
+pub fn format_duration(ms: f64) -> String {
+    if !ms.is_finite() {
+        return format!("{}ms", ms);
+    }
+
+    let mut out = String::new();
+    if ms < 0.0 {
+        out.push('-');
+    }
+
+    let mut rest = ms.abs();
+    for (suffix, unit) in [("d", DAY), ("h", HOUR), ("m", MINUTE), ("s", SECOND)] {
+        let whole = (rest / unit).floor();
+        if whole > 0.0 {
+            out.push_str(&format!("{}{}", whole, suffix));
+            rest -= whole * unit;
+        }
+    }
+
+    if rest > 0.0 || out.len() <= 1 {
+        out.push_str(&format!("{}ms", rest));
+    }
+
+    out
+}
+
+/// Reads a duration in words: `"2 hours"`, or with `relative`,
+/// `"in 2 hours"` / `"2 hours ago"`.
+///
+/// The length is rounded in the largest unit it fills; under a second
+/// reads as `"a moment"` (`"just now"` when relative).
+fn humanize(ms: f64, relative: bool) -> String {
+    let length = ms.abs();
+
+    let Some((name, unit)) = HUMAN_UNITS.iter().find(|(_, unit)| length >= *unit) else {
+        return if relative { "just now" } else { "a moment" }.to_string();
+    };
+
+    let count = (length / unit).round();
+    let words = format!("{} {}{}", count, name, if count == 1.0 { "" } else { "s" });
+
+    match (relative, ms < 0.0) {
+        (false, _) => words,
+        (true, true) => format!("{} ago", words),
+        (true, false) => format!("in {}", words),
+    }
+}
+
+fn checked(ms: f64) -> Result<Value, String> {
+    if ms.is_finite() {
+        Ok(Value::Duration(ms))
+    } else {
+        Err("the result is not a finite duration".to_string())
+    }
+}
+
+/* ============================================================================
+ * OPERATORS
+ * ============================================================================
+ */
+
+/// Evaluates a binary operator with at least one duration operand.
+///
+/// # Returns
+/// - `None` if the operator / operand combination is not a duration
+///   operation, so the caller falls back to its usual rules
+/// - `Some(Err(..))` on division by zero or a non-finite result
+pub fn binary_op(left: &Value, right: &Value, op: &str) -> Option<Result<Value, String>> {
+    let value = match (left, right, op) {
+        (Value::String(s), Value::Duration(d), "+") => Ok(Value::String(format!("{}{}", s, format_duration(*d)).into())),
+        (Value::Duration(d), Value::String(s), "+") => Ok(Value::String(format!("{}{}", format_duration(*d), s).into())),
+
+        (Value::Duration(a), Value::Duration(b), "+") => checked(a + b),
+        (Value::Duration(a), Value::Duration(b), "-") => checked(a - b),
+        (Value::Duration(_), Value::Duration(b), "/" | "%") if *b == 0.0 => Err("division by zero".to_string()),
+        (Value::Duration(a), Value::Duration(b), "/") => Ok(Value::Number(a / b)),
+        (Value::Duration(a), Value::Duration(b), "%") => checked(a % b),
+
+        (Value::Duration(d), Value::Number(n), "*") | (Value::Number(n), Value::Duration(d), "*") => checked(d * n),
+        (Value::Duration(_), Value::Number(n), "/") if *n == 0.0 => Err("division by zero".to_string()),
+        (Value::Duration(d), Value::Number(n), "/") => checked(d / n),
+
+        // Timestamps in epoch milliseconds move by the duration
+        (Value::Number(t), Value::Duration(d), "+") | (Value::Duration(d), Value::Number(t), "+") => Ok(Value::Number(t + d)),
+        (Value::Number(t), Value::Duration(d), "-") => Ok(Value::Number(t - d)),
+
+        (Value::Duration(a), Value::Duration(b), "==" | "!=" | "<" | ">" | "<=" | ">=") => Ok(Value::Bool(match op {
+            "==" => a == b,
+            "!=" => a != b,
+            "<" => a < b,
+            ">" => a > b,
+            "<=" => a <= b,
+            _ => a >= b,
+        })),
+
+        _ => return None,
+    };
+
+    Some(value)
+}
+
+/* ============================================================================
+ * DURATION VALUES
+ * ============================================================================
+ */
+
+/// Resolves a property or method on a duration value.
+///
+/// # Supported Members
+/// - `ms`, `seconds`, `minutes`, `hours`, `days` → the length in that unit
+/// - `humanize(relative?)` → `"2 hours"`, `"2 hours ago"`, `"in 2 hours"`
+/// - `abs()` → the duration without its sign
+/// - `toString()` → `"1h30m"`
+///
+/// # Errors
+/// - On an unknown property
+pub fn duration_property(ms: f64, name: &str) -> Result<Value, PawxError> {
+    match name {
+        "ms" => Ok(Value::Number(ms)),
+        "seconds" => Ok(Value::Number(ms / SECOND)),
+        "minutes" => Ok(Value::Number(ms / MINUTE)),
+        "hours" => Ok(Value::Number(ms / HOUR)),
+        "days" => Ok(Value::Number(ms / DAY)),
+
+        "humanize" => Ok(native(move |args| {
+            let relative = args.first().is_some_and(Value::is_truthy);
+            Ok(Value::String(humanize(ms, relative).into()))
+        })),
+
+        "abs" => Ok(native(move |_| Ok(Value::Duration(ms.abs())))),
+
+        "toString" => Ok(native(move |_| Ok(Value::String(format_duration(ms).into())))),
+
+        _ => raise_error(format!("Unknown Duration property '{}'", name)),
+    }
+}
+
+/* ============================================================================
+ * GLOBAL OBJECT
+ * ============================================================================
+ */
+
+fn timestamp(value: Option<&Value>, context: &str) -> Result<f64, PawxError> {
+    match value {
+        Some(Value::Number(n)) if n.is_finite() => Ok(*n),
+        _ => raise_error(format!("{} expects a timestamp in epoch milliseconds", context)),
+    }
+}
+
+/// Creates the global `Duration` object.
+///
+/// `Duration.since` and `Duration.until` measure from the virtual clock when
+/// one is given, so they agree with `Time.now()` under `--virtual-time`.
+///
+/// # PAWX Example
+/// ```pawx
+/// snuggle d = Duration.hours(1) + Duration.parse("30m");
+/// meow(d);              // 1h30m
+/// meow(d.minutes);      // 90
+/// ```
+pub fn create_global_duration_object(clock: Option<&VirtualClock>) -> HashMap<String, Value> {
+    let mut duration = HashMap::new();
+
+    for (name, unit) in [
+        ("ms", 1.0),
+        ("seconds", SECOND),
+        ("minutes", MINUTE),
+        ("hours", HOUR),
+        ("days", DAY),
+        ("weeks", WEEK),
+    ] {
+        duration.insert(
+            name.to_string(),
+            native(move |args| match args.first() {
+                Some(Value::Number(n)) if n.is_finite() => Ok(Value::Duration(n * unit)),
+                _ => raise_error(format!("Duration.{}(n) expects a number", name)),
+            }),
+        );
+    }
+
+    duration.insert(
+        "parse".to_string(),
+        native(|args| match args.first() {
+            Some(Value::String(s)) => match parse_duration(s) {
+                Ok(ms) => Ok(Value::Duration(ms)),
+                Err(message) => Ok(Value::Error { message }),
+            },
+            _ => raise_error("Duration.parse(str) expects a string"),
+        }),
+    );
+
+    duration.insert(
+        "between".to_string(),
+        native(|args| {
+            let from = timestamp(args.first(), "Duration.between(a, b)")?;
+            let to = timestamp(args.get(1), "Duration.between(a, b)")?;
+            Ok(Value::Duration(to - from))
+        }),
+    );
+
+    let now = {
+        let clock = clock.cloned();
+        move || match &clock {
+            Some(clock) => clock.now() as f64,
+            None => Utc::now().timestamp_millis() as f64,
+        }
+    };
+
+    let n = now.clone();
+    duration.insert(
+        "since".to_string(),
+        native(move |args| Ok(Value::Duration(n() - timestamp(args.first(), "Duration.since(t)")?))),
+    );
+
+    duration.insert(
+        "until".to_string(),
+        native(move |args| Ok(Value::Duration(timestamp(args.first(), "Duration.until(t)")? - now()))),
+    );
+
+    duration.insert(
+        "isDuration".to_string(),
+        native(|args| Ok(Value::Bool(matches!(args.first(), Some(Value::Duration(_)))))),
+    );
+
+    duration
+}
@@ -0,0 +1,234 @@
+/*
+ * ============================================================================
+ * PAWX - Code with Claws! 🐾
+ * ============================================================================
+ *
+ * eval() and compile() for PAWX
+ *
+ * Supports:
+ *   - eval(source)                    run code, return its last expression
+ *   - eval(source, { a: 1 })          ...with `a` defined while it runs
+ *   - compile(source)                 parse once, run on every call
+ *   - compile(source, ["a", "b"])     ...binding the call's arguments
+ *
+ * Code runs in a fresh scope whose parent is the program's global scope:
+ * it sees the globals the prelude installed (so an in-memory `Fs`,
+ * deterministic mode and the like still apply) and top-level declarations,
+ * but none of the caller's local variables. Names it declares disappear
+ * when it finishes.
+ *
+ * The result is the value of a top-level `return`, or else of the final
+ * statement if that is an expression, or else `null`. Syntax errors,
+ * runtime errors and throws surface as catchable errors at the call site.
+ *
+ * Embedders that run untrusted programs can turn both off with
+ * `Prelude::without_eval()` (`pawx --no-eval`); they then throw instead of
+ * running anything, in isolates too.
+ *
+ * --------------------------------------------------------------------------
+ * Author:   Sam Wilcox
+ * Email:    sam@pawx-lang.com
+ * Website:  https://www.pawx-lang.com
+ * GitHub:   https://github.com/samwilcox/pawx
+ *
+ * License:
+ * This file is part of the PAWX programming language project.
+ *
+ * PAWX is dual-licensed under the terms of:
+ *   - The MIT License
+ *   - The Apache License, Version 2.0
+ *
+ * You may choose either license to govern your use of this software.
+ * Full license text available at:
+ *     https://license.pawx-lang.com
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under these licenses is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *
+ * ==========================================================================
+ */
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::sync::Arc;
+
+use crate::ast::arena::{Ast, StmtList, StmtNode};
+use crate::error::PawxError;
+use crate::interpreter::calls::{builtin_error, raise_error};
+use crate::interpreter::display::value_to_string;
+use crate::interpreter::environment::Environment;
+use crate::interpreter::expressions::eval_expr;
+use crate::interpreter::statements::{exec_stmt, ExecSignal};
+use crate::interpreter::{current_span, global_env, set_current_span};
+use crate::span::Span;
+use crate::value::Value;
+use crate::{lexer, parser};
+
+fn native(f: impl Fn(Vec<Value>) -> Result<Value, PawxError> + 'static) -> Value {
+    Value::NativeFunction(Arc::new(f))
+}
+
+thread_local! {
+    static ENABLED: Cell<bool> = const { Cell::new(true) };
+}
+
+/// Allows or forbids `eval` and `compile` for the program running on this
+/// thread.
+pub fn set_enabled(enabled: bool) {
+    ENABLED.with(|e| e.set(enabled));
+}
+
+/// Whether `eval` and `compile` may run code on this thread.
+pub fn enabled() -> bool {
+    ENABLED.with(Cell::get)
+}
+
+/// # Errors
+/// If the host turned `eval` and `compile` off.
+fn ensure_enabled(name: &str) -> Result<(), PawxError> {
+    if !enabled() {
+        return raise_error(format!("{}() is disabled by the host", name));
+    }
+    Ok(())
+}
+
+/* ============================================================================
+ * COMPILED CODE
+ * ============================================================================
+ */
+
+/// Source that has been parsed and can be run any number of times.
+struct Compiled {
+    ast: Rc<Ast>,
+    body: StmtList,
+}
+
+/// Puts the caller's source location back once the code has run, so its
+/// later errors are not reported at a line of the evaluated string.
+struct RestoreSpan(Span);
+
+impl Drop for RestoreSpan {
+    fn drop(&mut self) {
+        set_current_span(self.0);
+    }
+}
+
+impl Compiled {
+    /// # Errors
+    /// If `source` does not lex or parse.
+    fn new(name: &str, source: &str) -> Result<Self, PawxError> {
+        let statements = lexer::tokenize(source)
+            .and_then(parser::parse)
+            .map_err(|e| builtin_error(format!("{}(): {}", name, e)))?;
+
+        let (ast, body) = Ast::lower(statements);
+        Ok(Compiled { ast, body })
+    }
+
+    /// Runs the code in a fresh scope under the globals, with `bindings`
+    /// defined in it, and returns its result (see module docs).
+    ///
+    /// # Errors
+    /// If the code fails or throws.
+    fn run(&self, name: &str, bindings: Vec<(String, Value)>) -> Result<Value, PawxError> {
+        let _restore = RestoreSpan(current_span());
+
+        let env = Rc::new(RefCell::new(Environment::new(Some(global_env()))));
+        for (binding, value) in bindings {
+            env.borrow_mut().define_public(binding, value);
+        }
+
+        let last = self.body.iter().next_back();
+
+        for stmt in self.body.iter() {
+            // The final expression statement is the result
+            if Some(stmt) == last {
+                if let StmtNode::Expression(expr) = &self.ast[stmt] {
+                    set_current_span(self.ast.span(stmt));
+                    return eval_expr(&self.ast, *expr, env).map_err(|e| builtin_error(format!("{}(): {}", name, e)));
+                }
+            }
+
+            match exec_stmt(&self.ast, stmt, env.clone()) {
+                Ok(ExecSignal::None) => {}
+                Ok(ExecSignal::Return(value)) => return Ok(value),
+                Ok(ExecSignal::Throw(Value::Error { message })) => return raise_error(message),
+                Ok(ExecSignal::Throw(value)) => return raise_error(value_to_string(&value)),
+                Err(e) => return raise_error(format!("{}(): {}", name, e)),
+            }
+        }
+
+        Ok(Value::Null)
+    }
+}
+
+/* ============================================================================
+ * GLOBALS
+ * ============================================================================
+ */
+
+/// `eval(source, scope?)`
+fn eval(args: Vec<Value>) -> Result<Value, PawxError> {
+    ensure_enabled("eval")?;
+
+    let source = match args.first() {
+        Some(Value::String(s)) => s.clone(),
+        _ => return raise_error("eval(source, scope?) expects source to be a string"),
+    };
+
+    let bindings = match args.get(1) {
+        None | Some(Value::Null) => Vec::new(),
+        Some(Value::Object { fields }) => fields.borrow().iter().map(|(k, v)| (k.clone(), v.clone())).collect(),
+        Some(other) => return raise_error(format!("eval(source, scope?) expects scope to be an object, got {}", other.type_name())),
+    };
+
+    Compiled::new("eval", &source)?.run("eval", bindings)
+}
+
+/// `compile(source, params?)`
+fn compile(args: Vec<Value>) -> Result<Value, PawxError> {
+    ensure_enabled("compile")?;
+
+    let source = match args.first() {
+        Some(Value::String(s)) => s.clone(),
+        _ => return raise_error("compile(source, params?) expects source to be a string"),
+    };
+
+    let params: Vec<String> = match args.get(1) {
+        None | Some(Value::Null) => Vec::new(),
+        Some(Value::Array { values, .. }) => values
+            .borrow()
+            .iter()
+            .map(|v| match v {
+                Value::String(s) => Ok(s.to_string()),
+                other => raise_error(format!("compile(source, params?) expects parameter names, got {}", other.type_name())),
+            })
+            .collect::<Result<_, _>>()?,
+        Some(other) => return raise_error(format!("compile(source, params?) expects params to be an array, got {}", other.type_name())),
+    };
+
+    let compiled = Compiled::new("compile", &source)?;
+
+    Ok(native(move |args| {
+        ensure_enabled("compile")?;
+
+        let bindings = params
+            .iter()
+            .enumerate()
+            .map(|(i, name)| (name.clone(), args.get(i).cloned().unwrap_or(Value::Null)))
+            .collect();
+
+        compiled.run("compile", bindings)
+    }))
+}
+
+/// Builds the global `eval` function.
+pub fn create_global_eval_value() -> Value {
+    native(eval)
+}
+
+/// Builds the global `compile` function.
+pub fn create_global_compile_value() -> Value {
+    native(compile)
+}
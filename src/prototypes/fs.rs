@@ -45,8 +45,22 @@
  *   - Fs.readJsonAsync(path, encoding?)       -> Furure(any)
  *   - Fs.writeJsonAsync(path, value, pretty?, enc?) -> Furure(null)
  *
+ * --------------------------------------------------------------------------
+ *  Streaming API
+ * --------------------------------------------------------------------------
+ *   - Fs.createReadStream(path, chunkSize?)   -> Stream<string>
+ *   - Fs.createWriteStream(path, append?)     -> Stream
+ *   - Fs.stream(path, type?)                  -> file part for uploads
+ *
+ * Every async variant also accepts an optional trailing `CancelToken`.
+ * A cancelled token resolves the Furure to a `CancelledError`.
+ *
  * All file paths are interpreted relative to the PAWX process working
  * directory unless absolute paths are provided.
+ *
+ * Files are read and written through an `FsBackend` (see memfs.rs): the
+ * disk by default, or an in-memory tree when the interpreter is started
+ * with one (`pawx test --sandbox-fs`).
  * 
  * --------------------------------------------------------------------------
  * Author:   Sam Wilcox
@@ -74,19 +88,56 @@
 
 use std::cell::RefCell;
 use std::collections::HashMap;
-use std::fs::{self, OpenOptions};
-use std::io::Write;
-use std::path::Path;
+use std::io::{Read, Write};
 use std::rc::Rc;
 use std::sync::Arc;
 use std::thread;
 
 use serde_json::{self, Value as JsonValue};
 
+use crate::error::PawxError;
+use crate::interpreter::calls::{builtin_error, raise_error};
 use crate::prototypes::array::create_array_proto;
+use crate::prototypes::cancel::cancellable;
+use crate::prototypes::json_writer;
+use crate::prototypes::memfs::FsBackend;
+use crate::prototypes::stream::{stream_from_source, stream_to_sink};
 use crate::value::Value;
 
 
+// ===============================================
+// Storage Backend
+// ===============================================
+
+thread_local! {
+    /// Backend of the `Fs` object whose function is running.
+    static ACTIVE_BACKEND: RefCell<FsBackend> = const { RefCell::new(FsBackend::Disk) };
+}
+
+/// Returns the backend the running `Fs` function works against.
+fn backend() -> FsBackend {
+    ACTIVE_BACKEND.with(|b| b.borrow().clone())
+}
+
+/// Puts the previous backend back, even when an `Fs` function fails.
+struct BackendGuard(Option<FsBackend>);
+
+impl Drop for BackendGuard {
+    fn drop(&mut self) {
+        if let Some(previous) = self.0.take() {
+            ACTIVE_BACKEND.with(|b| *b.borrow_mut() = previous);
+        }
+    }
+}
+
+/// Runs `f` with `backend` as the active backend.
+fn with_backend<R>(backend: &FsBackend, f: impl FnOnce() -> R) -> R {
+    let previous = ACTIVE_BACKEND.with(|b| b.replace(backend.clone()));
+    let _guard = BackendGuard(Some(previous));
+    f()
+}
+
+
 /// ===============================================
 /// Argument Helpers
 /// ===============================================
@@ -96,15 +147,15 @@ use crate::value::Value;
 /// This helper is used by all FS functions that expect string inputs
 /// such as file paths, encodings, or text content.
 ///
-/// # Panics
+/// # Errors
 /// - If the value is not a `Value::String`.
-fn expect_string(arg: &Value, method: &str, position: usize) -> String {
+fn expect_string(arg: &Value, method: &str, position: usize) -> Result<String, PawxError> {
     match arg {
-        Value::String(s) => s.clone(),
-        other => panic!(
+        Value::String(s) => Ok(s.to_string()),
+        other => raise_error(format!(
             "Fs.{}: argument #{} expected string, got {:?}",
             method, position, other
-        ),
+        )),
     }
 }
 
@@ -113,15 +164,15 @@ fn expect_string(arg: &Value, method: &str, position: usize) -> String {
 /// Used by functions such as `Fs.mkdir`/`Fs.rm` when handling the
 /// optional `recursive` flag.
 ///
-/// # Panics
+/// # Errors
 /// - If the value is not a `Value::Bool`.
-fn expect_bool(arg: &Value, method: &str, position: usize) -> bool {
+fn expect_bool(arg: &Value, method: &str, position: usize) -> Result<bool, PawxError> {
     match arg {
-        Value::Bool(b) => *b,
-        other => panic!(
+        Value::Bool(b) => Ok(*b),
+        other => raise_error(format!(
             "Fs.{}: argument #{} expected boolean, got {:?}",
             method, position, other
-        ),
+        )),
     }
 }
 
@@ -130,20 +181,20 @@ fn expect_bool(arg: &Value, method: &str, position: usize) -> bool {
 /// Each element must be a numeric value (0–255), which is truncated
 /// to `u8`.
 ///
-/// # Panics
+/// # Errors
 /// - If the value is not an array
 /// - If any element is not a number
-fn expect_bytes(arg: &Value, method: &str) -> Vec<u8> {
+fn expect_bytes(arg: &Value, method: &str) -> Result<Vec<u8>, PawxError> {
     match arg {
         Value::Array { values, .. } => values
             .borrow()
             .iter()
-            .map(|v| match v {
+            .map(|v| Ok(match v {
                 Value::Number(n) => *n as u8,
-                other => panic!("Fs.{}: expected byte array, got {:?}", method, other),
-            })
+                other => return raise_error(format!("Fs.{}: expected byte array, got {:?}", method, other)),
+            }))
             .collect(),
-        other => panic!("Fs.{}: expected byte array, got {:?}", method, other),
+        other => raise_error(format!("Fs.{}: expected byte array, got {:?}", method, other)),
     }
 }
 
@@ -154,23 +205,24 @@ fn expect_bytes(arg: &Value, method: &str) -> Vec<u8> {
 
 /// Reads the full contents of a file as raw binary bytes.
 ///
-/// # Panics
+/// # Errors
 /// - If the file cannot be opened or read.
-fn fs_read_bytes_sync(path: &str) -> Vec<u8> {
-    match fs::read(path) {
-        Ok(bytes) => bytes,
-        Err(e) => panic!("Fs.readBytes('{}'): {}", path, e),
+fn fs_read_bytes_sync(path: &str) -> Result<Vec<u8>, PawxError> {
+    match backend().read(path) {
+        Ok(bytes) => Ok(bytes),
+        Err(e) => raise_error(format!("Fs.readBytes('{}'): {}", path, e)),
     }
 }
 
 /// Writes raw binary bytes to a file, creating or truncating it.
 ///
-/// # Panics
+/// # Errors
 /// - If the file cannot be created or written.
-fn fs_write_bytes_sync(path: &str, bytes: &[u8]) {
-    if let Err(e) = fs::write(path, bytes) {
-        panic!("Fs.writeBytes('{}'): {}", path, e);
+fn fs_write_bytes_sync(path: &str, bytes: &[u8]) -> Result<(), PawxError> {
+    if let Err(e) = backend().write(path, bytes) {
+        return raise_error(format!("Fs.writeBytes('{}'): {}", path, e));
     }
+    Ok(())
 }
 
 /// Reads a text file using a specified encoding.
@@ -180,106 +232,92 @@ fn fs_write_bytes_sync(path: &str, bytes: &[u8]) {
 /// - `"ascii"`
 /// - `"latin1"`
 ///
-/// # Panics
+/// # Errors
 /// - If the file cannot be read
 /// - If the text is not valid UTF-8 when `"utf8"` is selected
 /// - If an unsupported encoding is requested.
-fn fs_read_text_sync(path: &str, encoding: &str) -> Value {
-    let bytes = fs_read_bytes_sync(path);
+fn fs_read_text_sync(path: &str, encoding: &str) -> Result<Value, PawxError> {
+    let bytes = fs_read_bytes_sync(path)?;
 
     let text = match encoding {
         "utf8" | "utf-8" => String::from_utf8(bytes)
-            .unwrap_or_else(|_| panic!("Fs.readText('{}'): invalid UTF-8", path)),
+            .map_err(|_| builtin_error(format!("Fs.readText('{}'): invalid UTF-8", path)))?,
 
         "ascii" => bytes.iter().map(|b| *b as char).collect(),
 
         "latin1" => bytes.iter().map(|b| *b as char).collect(),
 
-        other => panic!("Fs.readText: unsupported encoding '{}'", other),
+        other => return raise_error(format!("Fs.readText: unsupported encoding '{}'", other)),
     };
 
-    Value::String(text)
+    Ok(Value::String(text.into()))
 }
 
 /// Writes text to a file using a specified encoding.
 ///
-/// # Panics
+/// # Errors
 /// - If the encoding is unsupported
 /// - If the file cannot be written.
-fn fs_write_text_sync(path: &str, text: &str, encoding: &str) {
+fn fs_write_text_sync(path: &str, text: &str, encoding: &str) -> Result<(), PawxError> {
     let bytes: Vec<u8> = match encoding {
         "utf8" | "utf-8" => text.as_bytes().to_vec(),
         "ascii" | "latin1" => text.chars().map(|c| c as u8).collect(),
-        other => panic!("Fs.writeText: unsupported encoding '{}'", other),
+        other => return raise_error(format!("Fs.writeText: unsupported encoding '{}'", other)),
     };
 
-    fs_write_bytes_sync(path, &bytes);
+    fs_write_bytes_sync(path, &bytes)
 }
 
 /// Appends text to a file using a specified encoding.
 ///
 /// If the file does not exist, it is created.
 ///
-/// # Panics
+/// # Errors
 /// - If the file cannot be opened or written.
-fn fs_append_text_sync(path: &str, text: &str, encoding: &str) -> Value {
+fn fs_append_text_sync(path: &str, text: &str, encoding: &str) -> Result<Value, PawxError> {
     let bytes: Vec<u8> = match encoding {
         "utf8" | "utf-8" => text.as_bytes().to_vec(),
         "ascii" | "latin1" => text.chars().map(|c| c as u8).collect(),
-        other => panic!("Fs.appendText: unsupported encoding '{}'", other),
+        other => return raise_error(format!("Fs.appendText: unsupported encoding '{}'", other)),
     };
 
-    let mut file = OpenOptions::new()
-        .create(true)
-        .append(true)
-        .open(path)
-        .unwrap_or_else(|e| panic!("Fs.appendText('{}'): {}", path, e));
-
-    if let Err(e) = file.write_all(&bytes) {
-        panic!("Fs.appendText('{}'): {}", path, e);
+    if let Err(e) = backend().append(path, &bytes) {
+        return raise_error(format!("Fs.appendText('{}'): {}", path, e));
     }
 
-    Value::Null
+    Ok(Value::Null)
 }
 
 /// Determines whether a file or directory exists.
 fn fs_exists_sync(path: &str) -> Value {
-    Value::Bool(Path::new(path).exists())
+    Value::Bool(backend().exists(path))
 }
 
 /// Reads the contents of a directory into an array of filenames.
-fn fs_readdir_sync(path: &str) -> Value {
-    let entries = match fs::read_dir(path) {
-        Ok(iter) => iter,
-        Err(e) => panic!("Fs.readdir('{}'): {}", path, e),
+fn fs_readdir_sync(path: &str) -> Result<Value, PawxError> {
+    let names = match backend().read_dir(path) {
+        Ok(names) => names.into_iter().map(|n| Value::String(n.into())).collect(),
+        Err(e) => return raise_error(format!("Fs.readdir('{}'): {}", path, e)),
     };
 
-    let mut names = Vec::new();
-    for entry in entries {
-        let entry = entry.unwrap();
-        names.push(Value::String(entry.file_name().to_string_lossy().to_string()));
-    }
-
-    Value::Array {
+    Ok(Value::Array {
         values: Rc::new(RefCell::new(names)),
         proto: create_array_proto(),
-    }
+    })
 }
 
 /// Creates a directory at the given path.
 ///
 /// If `recursive` is true, parent directories are created as needed.
-fn fs_mkdir_sync(path: &str, recursive: bool) -> Value {
-    if recursive {
-        if let Err(e) = fs::create_dir_all(path) {
-            panic!("Fs.mkdir('{}', recursive): {}", path, e);
-        }
-    } else {
-        if let Err(e) = fs::create_dir(path) {
-            panic!("Fs.mkdir('{}'): {}", path, e);
+fn fs_mkdir_sync(path: &str, recursive: bool) -> Result<Value, PawxError> {
+    if let Err(e) = backend().create_dir(path, recursive) {
+        if recursive {
+            return raise_error(format!("Fs.mkdir('{}', recursive): {}", path, e));
+        } else {
+            return raise_error(format!("Fs.mkdir('{}'): {}", path, e));
         }
     }
-    Value::Null
+    Ok(Value::Null)
 }
 
 /// Removes a file or directory.
@@ -291,32 +329,23 @@ fn fs_mkdir_sync(path: &str, recursive: bool) -> Value {
 /// If `recursive` is false:
 /// - Files are removed normally
 /// - Directories must be empty.
-fn fs_rm_sync(path: &str, recursive: bool) -> Value {
-    let p = Path::new(path);
+fn fs_rm_sync(path: &str, recursive: bool) -> Result<Value, PawxError> {
+    let backend = backend();
 
-    if recursive {
-        if p.is_dir() {
-            if let Err(e) = fs::remove_dir_all(p) {
-                panic!("Fs.rm('{}', recursive): {}", path, e);
-            }
-        } else if p.is_file() {
-            if let Err(e) = fs::remove_file(p) {
-                panic!("Fs.rm('{}', recursive): {}", path, e);
-            }
-        }
-    } else {
-        if p.is_dir() {
-            if let Err(e) = fs::remove_dir(p) {
-                panic!("Fs.rm('{}'): {}", path, e);
-            }
-        } else if p.is_file() {
-            if let Err(e) = fs::remove_file(p) {
-                panic!("Fs.rm('{}'): {}", path, e);
-            }
+    // Removing something that does not exist is not an error.
+    if !backend.exists(path) {
+        return Ok(Value::Null);
+    }
+
+    if let Err(e) = backend.remove(path, recursive) {
+        if recursive {
+            return raise_error(format!("Fs.rm('{}', recursive): {}", path, e));
+        } else {
+            return raise_error(format!("Fs.rm('{}'): {}", path, e));
         }
     }
 
-    Value::Null
+    Ok(Value::Null)
 }
 
 /// Converts a JSON value into a PAWX runtime `Value`.
@@ -325,7 +354,7 @@ fn json_to_pawx(j: &JsonValue) -> Value {
         JsonValue::Null => Value::Null,
         JsonValue::Bool(b) => Value::Bool(*b),
         JsonValue::Number(n) => Value::Number(n.as_f64().unwrap_or(0.0)),
-        JsonValue::String(s) => Value::String(s.clone()),
+        JsonValue::String(s) => Value::String(s.as_str().into()),
         JsonValue::Array(arr) => {
             let values: Vec<Value> = arr.iter().map(json_to_pawx).collect();
             Value::Array {
@@ -345,41 +374,13 @@ fn json_to_pawx(j: &JsonValue) -> Value {
     }
 }
 
-/// Converts a PAWX runtime `Value` into a JSON value.
-///
-/// Non-JSON-compatible values (functions, classes, futures, etc.) are
-/// serialized as `null`.
-fn pawx_to_json(v: &Value) -> JsonValue {
-    match v {
-        Value::Null => JsonValue::Null,
-        Value::Bool(b) => JsonValue::Bool(*b),
-        Value::Number(n) => {
-            serde_json::Number::from_f64(*n).map(JsonValue::Number).unwrap_or(JsonValue::Null)
-        }
-        Value::String(s) => JsonValue::String(s.clone()),
-        Value::Array { values, .. } => {
-            let arr = values.borrow().iter().map(pawx_to_json).collect();
-            JsonValue::Array(arr)
-        }
-        Value::Object { fields } => {
-            let mut map = serde_json::Map::new();
-            for (k, v2) in fields.borrow().iter() {
-                map.insert(k.clone(), pawx_to_json(v2));
-            }
-            JsonValue::Object(map)
-        }
-        // Fallback for non-serializable values
-        _ => JsonValue::Null,
-    }
-}
-
 /// Reads a JSON file from disk and converts it into a PAWX `Value`.
-fn fs_read_json_sync(path: &str, encoding: &str) -> Value {
-    let text_val = fs_read_text_sync(path, encoding);
+fn fs_read_json_sync(path: &str, encoding: &str) -> Result<Value, PawxError> {
+    let text_val = fs_read_text_sync(path, encoding)?;
     if let Value::String(s) = text_val {
         let parsed: JsonValue =
-            serde_json::from_str(&s).unwrap_or_else(|e| panic!("Fs.readJson('{}'): {}", path, e));
-        json_to_pawx(&parsed)
+            serde_json::from_str(&s).map_err(|e| builtin_error(format!("Fs.readJson('{}'): {}", path, e)))?;
+        Ok(json_to_pawx(&parsed))
     } else {
         unreachable!("fs_read_text_sync did not return a string");
     }
@@ -387,27 +388,108 @@ fn fs_read_json_sync(path: &str, encoding: &str) -> Value {
 
 /// Serializes a PAWX `Value` to JSON and writes it to disk.
 ///
-/// If `pretty` is true, the JSON is formatted with indentation.
+/// If `pretty` is true, the JSON is formatted with two-space indentation.
 fn fs_write_json_sync(
     path: &str,
     value: &Value,
     pretty: bool,
     encoding: &str,
-) -> Value {
-    let json_val = pawx_to_json(value);
-
+) -> Result<Value, PawxError> {
     let text = if pretty {
-        serde_json::to_string_pretty(&json_val)
-            .unwrap_or_else(|e| panic!("Fs.writeJson('{}'): {}", path, e))
+        json_writer::to_json_pretty(value, 2)
     } else {
-        serde_json::to_string(&json_val)
-            .unwrap_or_else(|e| panic!("Fs.writeJson('{}'): {}", path, e))
+        json_writer::to_json(value)
     };
+    let text = match text {
+        Ok(text) => text,
+        Err(message) => return raise_error(message),
+    };
+
+    fs_write_text_sync(path, &text, encoding)?;
+    Ok(Value::Null)
+}
+
+
+/// ===============================================
+/// Stream Helpers
+/// ===============================================
+
+/// Creates a lazy read stream emitting UTF-8 text chunks.
+///
+/// The file is opened on first read and only read while the stream is
+/// flowing. Multi-byte characters split across chunk boundaries are
+/// carried over to the next chunk.
+fn fs_read_stream(path: String, chunk_size: usize) -> Value {
+    let backend = backend();
+    let mut file: Option<Box<dyn Read>> = None;
+    let mut carry: Vec<u8> = Vec::new();
+
+    stream_from_source(Box::new(move || {
+        if file.is_none() {
+            let f = backend
+                .open_read(&path)
+                .map_err(|e| format!("Fs.createReadStream('{}'): {}", path, e))?;
+            file = Some(f);
+        }
+
+        let reader = file.as_mut().expect("file opened above");
+        let mut buf = vec![0u8; chunk_size];
+
+        let n = reader
+            .read(&mut buf)
+            .map_err(|e| format!("Fs.createReadStream('{}'): {}", path, e))?;
+
+        if n == 0 {
+            if carry.is_empty() {
+                return Ok(None);
+            }
+            let rest = String::from_utf8_lossy(&carry).to_string();
+            carry.clear();
+            return Ok(Some(Value::String(rest.into())));
+        }
+
+        carry.extend_from_slice(&buf[..n]);
+
+        let valid = match std::str::from_utf8(&carry) {
+            Ok(_) => carry.len(),
+            Err(e) if e.error_len().is_none() => e.valid_up_to(),
+            Err(_) => return Err(format!("Fs.createReadStream('{}'): invalid UTF-8", path)),
+        };
 
-    fs_write_text_sync(path, &text, encoding);
-    Value::Null
+        let text = String::from_utf8(carry.drain(..valid).collect())
+            .expect("validated UTF-8 above");
+
+        Ok(Some(Value::String(text.into())))
+    }))
 }
 
+/// Creates a write stream that writes every chunk to a file.
+///
+/// Strings are written as-is; other values use their PAWX string form.
+fn fs_write_stream(path: String, append: bool) -> Result<Value, PawxError> {
+    let file = backend()
+        .open_write(&path, append)
+        .map_err(|e| builtin_error(format!("Fs.createWriteStream('{}'): {}", path, e)))?;
+
+    let file = Rc::new(RefCell::new(file));
+    let flush_file = file.clone();
+
+    Ok(stream_to_sink(
+        move |chunk| {
+            let text = match chunk {
+                Value::String(s) => s,
+                other => other.to_pawx_string().into(),
+            };
+
+            file.borrow_mut()
+                .write_all(text.as_bytes())
+                .map_err(|e| format!("Fs.createWriteStream('{}'): {}", path, e))
+        },
+        move || {
+            let _ = flush_file.borrow_mut().flush();
+        },
+    ))
+}
 
 /// ===============================================
 /// Async Helper – Thread-backed Furure
@@ -430,13 +512,14 @@ where
 {
     // Wrap the job so it can be "taken" exactly once
     let job_cell = std::cell::RefCell::new(Some(job));
+    let backend = backend();
 
-    let deferred = Value::NativeFunction(Arc::new(move |_args: Vec<Value>| -> Value {
+    let deferred = Value::NativeFunction(Arc::new(move |_args: Vec<Value>| -> Result<Value, PawxError> {
         let job_opt = job_cell
             .take()
-            .expect("Furure has already been resolved");
+            .ok_or_else(|| builtin_error("Furure has already been resolved"))?;
 
-        job_opt()
+        Ok(with_backend(&backend, job_opt))
     }));
 
     Value::Furure(Box::new(deferred))
@@ -453,9 +536,11 @@ where
 ///
 /// ```rust
 /// global.borrow_mut()
-///     .define_public("Fs".to_string(), create_fs_global());
+///     .define_public("Fs".to_string(), create_fs_global(FsBackend::Disk));
 /// ```
-pub fn create_fs_global() -> Value {
+///
+/// Every function reads and writes through `backend`.
+pub fn create_fs_global(backend: FsBackend) -> Value {
     let mut map: HashMap<String, Value> = HashMap::new();
 
     // ============================================================
@@ -467,12 +552,12 @@ pub fn create_fs_global() -> Value {
         "readText".to_string(),
         Value::NativeFunction(Arc::new(|args| {
             if args.is_empty() {
-                panic!("Fs.readText(path, encoding?): missing `path` argument");
+                return raise_error("Fs.readText(path, encoding?): missing `path` argument");
             }
 
-            let path = expect_string(&args[0], "readText", 1);
+            let path = expect_string(&args[0], "readText", 1)?;
             let encoding = if args.len() > 1 {
-                expect_string(&args[1], "readText", 2)
+                expect_string(&args[1], "readText", 2)?
             } else {
                 "utf8".to_string()
             };
@@ -484,24 +569,24 @@ pub fn create_fs_global() -> Value {
     /// Fs.writeTextAsync(path, text, encoding?) -> Furure(null)
     map.insert(
         "writeTextAsync".to_string(),
-        Value::NativeFunction(Arc::new(|args: Vec<Value>| -> Value {
+        Value::NativeFunction(Arc::new(|args: Vec<Value>| -> Result<Value, PawxError> {
             if args.len() < 2 {
-                panic!("Fs.writeTextAsync(path, text, encoding?): expected at least 2 arguments");
+                return raise_error("Fs.writeTextAsync(path, text, encoding?): expected at least 2 arguments");
             }
 
-            let path = expect_string(&args[0], "writeTextAsync", 1);
-            let text = expect_string(&args[1], "writeTextAsync", 2);
+            let path = expect_string(&args[0], "writeTextAsync", 1)?;
+            let text = expect_string(&args[1], "writeTextAsync", 2)?;
             let encoding = if args.len() > 2 {
-                expect_string(&args[2], "writeTextAsync", 3)
+                expect_string(&args[2], "writeTextAsync", 3)?
             } else {
                 "utf8".to_string()
             };
 
             // Do the real work RIGHT HERE
-            fs_write_text_sync(&path, &text, &encoding);
+            fs_write_text_sync(&path, &text, &encoding)?;
 
             // Wrap resolved value (null) for the Furure pipeline
-            Value::Furure(Box::new(Value::Null))
+            Ok(Value::Furure(Box::new(Value::Null)))
         })),
     );
 
@@ -510,13 +595,13 @@ pub fn create_fs_global() -> Value {
         "appendText".to_string(),
         Value::NativeFunction(Arc::new(|args| {
             if args.len() < 2 {
-                panic!("Fs.appendText(path, text, encoding?): expected at least 2 arguments");
+                return raise_error("Fs.appendText(path, text, encoding?): expected at least 2 arguments");
             }
 
-            let path = expect_string(&args[0], "appendText", 1);
-            let text = expect_string(&args[1], "appendText", 2);
+            let path = expect_string(&args[0], "appendText", 1)?;
+            let text = expect_string(&args[1], "appendText", 2)?;
             let encoding = if args.len() > 2 {
-                expect_string(&args[2], "appendText", 3)
+                expect_string(&args[2], "appendText", 3)?
             } else {
                 "utf8".to_string()
             };
@@ -534,18 +619,18 @@ pub fn create_fs_global() -> Value {
         "readBytes".to_string(),
         Value::NativeFunction(Arc::new(|args| {
             if args.is_empty() {
-                panic!("Fs.readBytes(path): missing `path` argument");
+                return raise_error("Fs.readBytes(path): missing `path` argument");
             }
 
-            let path = expect_string(&args[0], "readBytes", 1);
-            let bytes = fs_read_bytes_sync(&path);
+            let path = expect_string(&args[0], "readBytes", 1)?;
+            let bytes = fs_read_bytes_sync(&path)?;
 
             let values = bytes.into_iter().map(|b| Value::Number(b as f64)).collect();
 
-            Value::Array {
+            Ok(Value::Array {
                 values: Rc::new(RefCell::new(values)),
                 proto: create_array_proto(),
-            }
+            })
         })),
     );
 
@@ -554,14 +639,14 @@ pub fn create_fs_global() -> Value {
         "writeBytes".to_string(),
         Value::NativeFunction(Arc::new(|args| {
             if args.len() < 2 {
-                panic!("Fs.writeBytes(path, bytes): expected 2 arguments");
+                return raise_error("Fs.writeBytes(path, bytes): expected 2 arguments");
             }
 
-            let path = expect_string(&args[0], "writeBytes", 1);
-            let bytes = expect_bytes(&args[1], "writeBytes");
+            let path = expect_string(&args[0], "writeBytes", 1)?;
+            let bytes = expect_bytes(&args[1], "writeBytes")?;
 
-            fs_write_bytes_sync(&path, &bytes);
-            Value::Null
+            fs_write_bytes_sync(&path, &bytes)?;
+            Ok(Value::Null)
         })),
     );
 
@@ -574,11 +659,11 @@ pub fn create_fs_global() -> Value {
         "exists".to_string(),
         Value::NativeFunction(Arc::new(|args| {
             if args.is_empty() {
-                panic!("Fs.exists(path): missing `path` argument");
+                return raise_error("Fs.exists(path): missing `path` argument");
             }
 
-            let path = expect_string(&args[0], "exists", 1);
-            fs_exists_sync(&path)
+            let path = expect_string(&args[0], "exists", 1)?;
+            Ok(fs_exists_sync(&path))
         })),
     );
 
@@ -587,10 +672,10 @@ pub fn create_fs_global() -> Value {
         "readdir".to_string(),
         Value::NativeFunction(Arc::new(|args| {
             if args.is_empty() {
-                panic!("Fs.readdir(path): missing `path` argument");
+                return raise_error("Fs.readdir(path): missing `path` argument");
             }
 
-            let path = expect_string(&args[0], "readdir", 1);
+            let path = expect_string(&args[0], "readdir", 1)?;
             fs_readdir_sync(&path)
         })),
     );
@@ -600,12 +685,12 @@ pub fn create_fs_global() -> Value {
         "mkdir".to_string(),
         Value::NativeFunction(Arc::new(|args| {
             if args.is_empty() {
-                panic!("Fs.mkdir(path, recursive?): missing `path` argument");
+                return raise_error("Fs.mkdir(path, recursive?): missing `path` argument");
             }
 
-            let path = expect_string(&args[0], "mkdir", 1);
+            let path = expect_string(&args[0], "mkdir", 1)?;
             let recursive = if args.len() > 1 {
-                expect_bool(&args[1], "mkdir", 2)
+                expect_bool(&args[1], "mkdir", 2)?
             } else {
                 false
             };
@@ -619,12 +704,12 @@ pub fn create_fs_global() -> Value {
         "rm".to_string(),
         Value::NativeFunction(Arc::new(|args| {
             if args.is_empty() {
-                panic!("Fs.rm(path, recursive?): missing `path` argument");
+                return raise_error("Fs.rm(path, recursive?): missing `path` argument");
             }
 
-            let path = expect_string(&args[0], "rm", 1);
+            let path = expect_string(&args[0], "rm", 1)?;
             let recursive = if args.len() > 1 {
-                expect_bool(&args[1], "rm", 2)
+                expect_bool(&args[1], "rm", 2)?
             } else {
                 false
             };
@@ -638,12 +723,12 @@ pub fn create_fs_global() -> Value {
         "readJson".to_string(),
         Value::NativeFunction(Arc::new(|args| {
             if args.is_empty() {
-                panic!("Fs.readJson(path, encoding?): missing `path` argument");
+                return raise_error("Fs.readJson(path, encoding?): missing `path` argument");
             }
 
-            let path = expect_string(&args[0], "readJson", 1);
+            let path = expect_string(&args[0], "readJson", 1)?;
             let encoding = if args.len() > 1 {
-                expect_string(&args[1], "readJson", 2)
+                expect_string(&args[1], "readJson", 2)?
             } else {
                 "utf8".to_string()
             };
@@ -657,20 +742,20 @@ pub fn create_fs_global() -> Value {
         "writeJson".to_string(),
         Value::NativeFunction(Arc::new(|args| {
             if args.len() < 2 {
-                panic!("Fs.writeJson(path, value, pretty?, encoding?): expected at least 2 arguments");
+                return raise_error("Fs.writeJson(path, value, pretty?, encoding?): expected at least 2 arguments");
             }
 
-            let path = expect_string(&args[0], "writeJson", 1);
+            let path = expect_string(&args[0], "writeJson", 1)?;
             let value = &args[1];
 
             let pretty = if args.len() > 2 {
-                expect_bool(&args[2], "writeJson", 3)
+                expect_bool(&args[2], "writeJson", 3)?
             } else {
                 false
             };
 
             let encoding = if args.len() > 3 {
-                expect_string(&args[3], "writeJson", 4)
+                expect_string(&args[3], "writeJson", 4)?
             } else {
                 "utf8".to_string()
             };
@@ -687,21 +772,21 @@ pub fn create_fs_global() -> Value {
         "readTextAsync".to_string(),
         Value::NativeFunction(Arc::new(|args| {
             if args.is_empty() {
-                panic!("Fs.readTextAsync(path, encoding?): missing `path` argument");
+                return raise_error("Fs.readTextAsync(path, encoding?): missing `path` argument");
             }
 
-            let path = expect_string(&args[0], "readTextAsync", 1);
+            let path = expect_string(&args[0], "readTextAsync", 1)?;
             let encoding = if args.len() > 1 {
-                expect_string(&args[1], "readTextAsync", 2)
+                expect_string(&args[1], "readTextAsync", 2)?
             } else {
                 "utf8".to_string()
             };
 
             // ✅ Do the real work immediately
-            let result = fs_read_text_sync(&path, &encoding);
+            let result = fs_read_text_sync(&path, &encoding)?;
 
             // ✅ Store the *resolved* value in the Furure
-            Value::Furure(Box::new(result))
+            Ok(Value::Furure(Box::new(result)))
         })),
     );
 
@@ -709,22 +794,22 @@ pub fn create_fs_global() -> Value {
         "writeTextAsync".to_string(),
         Value::NativeFunction(Arc::new(|args| {
             if args.len() < 2 {
-                panic!("Fs.writeTextAsync(path, text, encoding?): expected at least 2 arguments");
+                return raise_error("Fs.writeTextAsync(path, text, encoding?): expected at least 2 arguments");
             }
 
-            let path = expect_string(&args[0], "writeTextAsync", 1);
-            let text = expect_string(&args[1], "writeTextAsync", 2);
+            let path = expect_string(&args[0], "writeTextAsync", 1)?;
+            let text = expect_string(&args[1], "writeTextAsync", 2)?;
             let encoding = if args.len() > 2 {
-                expect_string(&args[2], "writeTextAsync", 3)
+                expect_string(&args[2], "writeTextAsync", 3)?
             } else {
                 "utf8".to_string()
             };
 
             // ✅ Actually write now
-            fs_write_text_sync(&path, &text, &encoding);
+            fs_write_text_sync(&path, &text, &encoding)?;
 
             // ✅ The async result is just `null`
-            Value::Furure(Box::new(Value::Null))
+            Ok(Value::Furure(Box::new(Value::Null)))
         })),
     );
 
@@ -732,19 +817,19 @@ pub fn create_fs_global() -> Value {
         "appendTextAsync".to_string(),
         Value::NativeFunction(Arc::new(|args| {
             if args.len() < 2 {
-                panic!("Fs.appendTextAsync(path, text, encoding?): expected at least 2 arguments");
+                return raise_error("Fs.appendTextAsync(path, text, encoding?): expected at least 2 arguments");
             }
 
-            let path = expect_string(&args[0], "appendTextAsync", 1);
-            let text = expect_string(&args[1], "appendTextAsync", 2);
+            let path = expect_string(&args[0], "appendTextAsync", 1)?;
+            let text = expect_string(&args[1], "appendTextAsync", 2)?;
             let encoding = if args.len() > 2 {
-                expect_string(&args[2], "appendTextAsync", 3)
+                expect_string(&args[2], "appendTextAsync", 3)?
             } else {
                 "utf8".to_string()
             };
 
-            let result = fs_append_text_sync(&path, &text, &encoding);
-            Value::Furure(Box::new(result))
+            let result = fs_append_text_sync(&path, &text, &encoding)?;
+            Ok(Value::Furure(Box::new(result)))
         })),
     );
 
@@ -752,11 +837,11 @@ pub fn create_fs_global() -> Value {
         "readBytesAsync".to_string(),
         Value::NativeFunction(Arc::new(|args| {
             if args.is_empty() {
-                panic!("Fs.readBytesAsync(path): missing `path` argument");
+                return raise_error("Fs.readBytesAsync(path): missing `path` argument");
             }
 
-            let path = expect_string(&args[0], "readBytesAsync", 1);
-            let bytes = fs_read_bytes_sync(&path);
+            let path = expect_string(&args[0], "readBytesAsync", 1)?;
+            let bytes = fs_read_bytes_sync(&path)?;
 
             let values = bytes.into_iter().map(|b| Value::Number(b as f64)).collect();
 
@@ -765,7 +850,7 @@ pub fn create_fs_global() -> Value {
                 proto: create_array_proto(),
             };
 
-            Value::Furure(Box::new(arr))
+            Ok(Value::Furure(Box::new(arr)))
         })),
     );
 
@@ -773,14 +858,14 @@ pub fn create_fs_global() -> Value {
         "writeBytesAsync".to_string(),
         Value::NativeFunction(Arc::new(|args| {
             if args.len() < 2 {
-                panic!("Fs.writeBytesAsync(path, bytes): expected 2 arguments");
+                return raise_error("Fs.writeBytesAsync(path, bytes): expected 2 arguments");
             }
 
-            let path = expect_string(&args[0], "writeBytesAsync", 1);
-            let bytes = expect_bytes(&args[1], "writeBytesAsync");
+            let path = expect_string(&args[0], "writeBytesAsync", 1)?;
+            let bytes = expect_bytes(&args[1], "writeBytesAsync")?;
 
-            fs_write_bytes_sync(&path, &bytes);
-            Value::Furure(Box::new(Value::Null))
+            fs_write_bytes_sync(&path, &bytes)?;
+            Ok(Value::Furure(Box::new(Value::Null)))
         })),
     );
 
@@ -788,12 +873,12 @@ pub fn create_fs_global() -> Value {
         "existsAsync".to_string(),
         Value::NativeFunction(Arc::new(|args| {
             if args.is_empty() {
-                panic!("Fs.existsAsync(path): missing `path` argument");
+                return raise_error("Fs.existsAsync(path): missing `path` argument");
             }
 
-            let path = expect_string(&args[0], "existsAsync", 1);
+            let path = expect_string(&args[0], "existsAsync", 1)?;
             let result = fs_exists_sync(&path);
-            Value::Furure(Box::new(result))
+            Ok(Value::Furure(Box::new(result)))
         })),
     );
 
@@ -801,12 +886,12 @@ pub fn create_fs_global() -> Value {
         "readdirAsync".to_string(),
         Value::NativeFunction(Arc::new(|args| {
             if args.is_empty() {
-                panic!("Fs.readdirAsync(path): missing `path` argument");
+                return raise_error("Fs.readdirAsync(path): missing `path` argument");
             }
 
-            let path = expect_string(&args[0], "readdirAsync", 1);
-            let result = fs_readdir_sync(&path);
-            Value::Furure(Box::new(result))
+            let path = expect_string(&args[0], "readdirAsync", 1)?;
+            let result = fs_readdir_sync(&path)?;
+            Ok(Value::Furure(Box::new(result)))
         })),
     );
 
@@ -814,18 +899,18 @@ pub fn create_fs_global() -> Value {
         "mkdirAsync".to_string(),
         Value::NativeFunction(Arc::new(|args| {
             if args.is_empty() {
-                panic!("Fs.mkdirAsync(path, recursive?): missing `path` argument");
+                return raise_error("Fs.mkdirAsync(path, recursive?): missing `path` argument");
             }
 
-            let path = expect_string(&args[0], "mkdirAsync", 1);
+            let path = expect_string(&args[0], "mkdirAsync", 1)?;
             let recursive = if args.len() > 1 {
-                expect_bool(&args[1], "mkdirAsync", 2)
+                expect_bool(&args[1], "mkdirAsync", 2)?
             } else {
                 false
             };
 
-            let result = fs_mkdir_sync(&path, recursive);
-            Value::Furure(Box::new(result))
+            let result = fs_mkdir_sync(&path, recursive)?;
+            Ok(Value::Furure(Box::new(result)))
         })),
     );
 
@@ -833,18 +918,18 @@ pub fn create_fs_global() -> Value {
         "rmAsync".to_string(),
         Value::NativeFunction(Arc::new(|args| {
             if args.is_empty() {
-                panic!("Fs.rmAsync(path, recursive?): missing `path` argument");
+                return raise_error("Fs.rmAsync(path, recursive?): missing `path` argument");
             }
 
-            let path = expect_string(&args[0], "rmAsync", 1);
+            let path = expect_string(&args[0], "rmAsync", 1)?;
             let recursive = if args.len() > 1 {
-                expect_bool(&args[1], "rmAsync", 2)
+                expect_bool(&args[1], "rmAsync", 2)?
             } else {
                 false
             };
 
-            let result = fs_rm_sync(&path, recursive);
-            Value::Furure(Box::new(result))
+            let result = fs_rm_sync(&path, recursive)?;
+            Ok(Value::Furure(Box::new(result)))
         })),
     );
 
@@ -852,18 +937,18 @@ pub fn create_fs_global() -> Value {
         "readJsonAsync".to_string(),
         Value::NativeFunction(Arc::new(|args| {
             if args.is_empty() {
-                panic!("Fs.readJsonAsync(path, encoding?): missing `path` argument");
+                return raise_error("Fs.readJsonAsync(path, encoding?): missing `path` argument");
             }
 
-            let path = expect_string(&args[0], "readJsonAsync", 1);
+            let path = expect_string(&args[0], "readJsonAsync", 1)?;
             let encoding = if args.len() > 1 {
-                expect_string(&args[1], "readJsonAsync", 2)
+                expect_string(&args[1], "readJsonAsync", 2)?
             } else {
                 "utf8".to_string()
             };
 
-            let result = fs_read_json_sync(&path, &encoding);
-            Value::Furure(Box::new(result))
+            let result = fs_read_json_sync(&path, &encoding)?;
+            Ok(Value::Furure(Box::new(result)))
         })),
     );
 
@@ -871,29 +956,120 @@ pub fn create_fs_global() -> Value {
         "writeJsonAsync".to_string(),
         Value::NativeFunction(Arc::new(|args| {
             if args.len() < 2 {
-                panic!("Fs.writeJsonAsync(path, value, pretty?, encoding?): expected at least 2 arguments");
+                return raise_error("Fs.writeJsonAsync(path, value, pretty?, encoding?): expected at least 2 arguments");
             }
 
-            let path = expect_string(&args[0], "writeJsonAsync", 1);
+            let path = expect_string(&args[0], "writeJsonAsync", 1)?;
             let value = args[1].clone();
 
             let pretty = if args.len() > 2 {
-                expect_bool(&args[2], "writeJsonAsync", 3)
+                expect_bool(&args[2], "writeJsonAsync", 3)?
             } else {
                 false
             };
 
             let encoding = if args.len() > 3 {
-                expect_string(&args[3], "writeJsonAsync", 4)
+                expect_string(&args[3], "writeJsonAsync", 4)?
             } else {
                 "utf8".to_string()
             };
 
-            let result = fs_write_json_sync(&path, &value, pretty, &encoding);
-            Value::Furure(Box::new(result))
+            let result = fs_write_json_sync(&path, &value, pretty, &encoding)?;
+            Ok(Value::Furure(Box::new(result)))
+        })),
+    );
+
+    // ============================================================
+    // STREAMS
+    // ============================================================
+
+    // Fs.createReadStream(path, chunkSize = 65536) -> Stream<string>
+    map.insert(
+        "createReadStream".to_string(),
+        Value::NativeFunction(Arc::new(|args| {
+            if args.is_empty() {
+                return raise_error("Fs.createReadStream(path, chunkSize?): missing `path` argument");
+            }
+
+            let path = expect_string(&args[0], "createReadStream", 1)?;
+            let chunk_size = match args.get(1) {
+                Some(Value::Number(n)) if *n >= 1.0 => *n as usize,
+                Some(Value::Null) | None => 64 * 1024,
+                _ => return raise_error("Fs.createReadStream(path, chunkSize?): chunkSize must be a positive number"),
+            };
+
+            Ok(fs_read_stream(path, chunk_size))
         })),
     );
 
+    // Fs.createWriteStream(path, append = false) -> Stream
+    map.insert(
+        "createWriteStream".to_string(),
+        Value::NativeFunction(Arc::new(|args| {
+            if args.is_empty() {
+                return raise_error("Fs.createWriteStream(path, append?): missing `path` argument");
+            }
+
+            let path = expect_string(&args[0], "createWriteStream", 1)?;
+            let append = if args.len() > 1 {
+                expect_bool(&args[1], "createWriteStream", 2)?
+            } else {
+                false
+            };
+
+            fs_write_stream(path, append)
+        })),
+    );
+
+    // Fs.stream(path, type?) -> file part for `Http.post(url, { multipart })`
+    map.insert(
+        "stream".to_string(),
+        Value::NativeFunction(Arc::new(|args| {
+            if args.is_empty() {
+                return raise_error("Fs.stream(path, type?): missing `path` argument");
+            }
+
+            let path = expect_string(&args[0], "stream", 1)?;
+            let content_type = match args.get(1) {
+                Some(Value::Null) | None => None,
+                Some(arg) => Some(expect_string(arg, "stream", 2)?),
+            };
+
+            let active = self::backend();
+            if let Err(e) = active.file_size(&path) {
+                return raise_error(format!("Fs.stream('{}'): {}", path, e));
+            }
+
+            Ok(crate::prototypes::multipart::file_part_value(path, content_type, active))
+        })),
+    );
+
+    // ============================================================
+    // CANCELLATION
+    // ============================================================
+
+    // Every async variant accepts an optional trailing CancelToken.
+    for (name, value) in map.iter_mut() {
+        if name.ends_with("Async") {
+            if let Value::NativeFunction(f) = value {
+                *value = cancellable(f.clone());
+            }
+        }
+    }
+
+    // ============================================================
+    // BACKEND
+    // ============================================================
+
+    // Every function works against this object's backend.
+    for value in map.values_mut() {
+        if let Value::NativeFunction(f) = value {
+            let f = f.clone();
+            let backend = backend.clone();
+            *value = Value::NativeFunction(Arc::new(move |args| with_backend(&backend, || f(args))));
+        }
+    }
+
     Value::Object {
         fields: Rc::new(RefCell::new(map)),
     }
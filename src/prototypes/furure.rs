@@ -0,0 +1,180 @@
+/*
+ * ==========================================================================
+ * PAWX - Code with Claws!
+ * Pending Furures
+ * ==========================================================================
+ *
+ * Most async natives do their work up front and return a settled
+ * `Furure`. A `PendingFurure` is the other kind: its value arrives later,
+ * when something on the interpreter thread settles it (a timer callback,
+ * a released semaphore permit, ...).
+ *
+ * Both kinds look the same to PAWX code:
+ *   - `then` / `catch` / `finally` callbacks run once the value is known
+ *   - `nap` runs the event loop until the Furure settles, then unwraps it
+ *
+ * --------------------------------------------------------------------------
+ * Author:   Sam Wilcox
+ * Email:    sam@pawx-lang.com
+ * Website:  https://www.pawx-lang.com
+ * GitHub:   https://github.com/samwilcox/pawx
+ *
+ * License:
+ * This file is part of the PAWX programming language project.
+ *
+ * PAWX is dual-licensed under the terms of:
+ *   - The MIT License
+ *   - The Apache License, Version 2.0
+ *
+ * You may choose either license to govern your use of this software.
+ * Full license text available at:
+ *     https://license.pawx-lang.com
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under these licenses is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *
+ * ==========================================================================
+ */
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use crate::error::PawxError;
+use crate::interpreter::calls::raise_error;
+use crate::prototypes::context;
+use crate::value::Value;
+
+/// Shared state behind every handle to the same Furure.
+#[derive(Default)]
+struct PendingState {
+    settled: RefCell<Option<Value>>,
+    then: RefCell<Vec<Value>>,
+    catch: RefCell<Vec<Value>>,
+    finally: RefCell<Vec<Value>>,
+}
+
+/// A Furure whose value is not known yet.
+///
+/// Cloning a `PendingFurure` produces another handle to the **same**
+/// Furure.
+#[derive(Clone, Default)]
+pub struct PendingFurure {
+    state: Rc<PendingState>,
+}
+
+fn run(callback: &Value, args: Vec<Value>) -> Result<(), PawxError> {
+    if let Value::NativeFunction(f) = callback {
+        f(args)?;
+    }
+    Ok(())
+}
+
+impl PendingFurure {
+    /// Creates a Furure that has not settled yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the value, once settled. An `Error` value is a rejection.
+    pub fn value(&self) -> Option<Value> {
+        self.state.settled.borrow().clone()
+    }
+
+    /// Settles the Furure with `value` and runs every queued callback once.
+    ///
+    /// Settling an already-settled Furure has no effect.
+    ///
+    /// # Errors
+    /// The first error raised by a callback; the rest still run.
+    pub fn settle(&self, value: Value) -> Result<(), PawxError> {
+        if self.state.settled.borrow().is_some() {
+            return Ok(());
+        }
+        *self.state.settled.borrow_mut() = Some(value.clone());
+
+        let is_error = matches!(value, Value::Error { .. });
+        let then: Vec<Value> = self.state.then.borrow_mut().drain(..).collect();
+        let catch: Vec<Value> = self.state.catch.borrow_mut().drain(..).collect();
+        let finally: Vec<Value> = self.state.finally.borrow_mut().drain(..).collect();
+
+        let calls = then
+            .iter()
+            .map(|cb| (cb, vec![value.clone()]))
+            .chain(catch.iter().filter(|_| is_error).map(|cb| (cb, vec![value.clone()])))
+            .chain(finally.iter().map(|cb| (cb, vec![])));
+
+        let mut outcome = Ok(());
+        for (callback, args) in calls {
+            if let Err(error) = run(callback, args) {
+                outcome = outcome.and(Err(error));
+            }
+        }
+
+        outcome
+    }
+
+    /// Registers `callback` to run with the value: immediately if the Furure
+    /// has settled, otherwise once it does.
+    ///
+    /// # Errors
+    /// Whatever the callback raises when it runs immediately.
+    pub fn then(&self, callback: Value) -> Result<(), PawxError> {
+        self.listen("then", callback)
+    }
+
+    /// Registers a `then` / `catch` / `finally` callback. Queued callbacks
+    /// run in the `Context` scope registering them.
+    fn listen(&self, kind: &str, callback: Value) -> Result<(), PawxError> {
+        let state = &self.state;
+        match (self.value(), kind) {
+            (Some(v), "then") => run(&callback, vec![v])?,
+            (Some(v @ Value::Error { .. }), "catch") => run(&callback, vec![v])?,
+            (Some(_), "catch") => {}
+            (Some(_), _) => run(&callback, vec![])?,
+            (None, "then") => state.then.borrow_mut().push(context::bind(callback)),
+            (None, "catch") => state.catch.borrow_mut().push(context::bind(callback)),
+            (None, _) => state.finally.borrow_mut().push(context::bind(callback)),
+        }
+        Ok(())
+    }
+
+    /// Returns `true` if both handles refer to the same Furure.
+    pub fn ptr_eq(&self, other: &PendingFurure) -> bool {
+        Rc::ptr_eq(&self.state, &other.state)
+    }
+}
+
+/// Resolves a property on a pending Furure.
+///
+/// # Installed Functions
+/// - `then(fn)`    → runs with the value
+/// - `catch(fn)`   → runs with the error, if the Furure is rejected
+/// - `finally(fn)` → runs either way, without arguments
+///
+/// Each returns the same Furure, so calls can be chained. Callbacks added
+/// after the Furure settled run immediately; the others run when it
+/// settles.
+///
+/// # Errors
+/// - If the property does not exist
+pub fn pending_furure_property(furure: &PendingFurure, name: &str) -> Result<Value, PawxError> {
+    let name = match name {
+        "then" => "then",
+        "catch" => "catch",
+        "finally" => "finally",
+        other => return raise_error(format!("property '{}' is not supported on Furure", other)),
+    };
+    let furure = furure.clone();
+
+    Ok(Value::NativeFunction(Arc::new(move |args: Vec<Value>| -> Result<Value, PawxError> {
+        let callback = match args.first() {
+            Some(cb @ Value::NativeFunction(_)) => cb.clone(),
+            _ => return raise_error(format!("{}(...) expects a function", name)),
+        };
+
+        furure.listen(name, callback)?;
+        Ok(Value::PendingFurure(furure.clone()))
+    })))
+}
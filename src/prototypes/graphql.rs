@@ -0,0 +1,289 @@
+/*
+ * ============================================================================
+ * PAWX - Code with Claws! 🐾
+ * ============================================================================
+ *
+ * GraphQL Client Helper for PAWX
+ *
+ * Supports:
+ *   - Http.graphql(url, query, variables?, options?)
+ *   - client.graphql(...) on pooled clients
+ *   - `errors` responses surfaced as rejected Furures
+ *   - Automatic persisted queries (sha256 hash, query sent on miss)
+ *
+ * Requests are sent as `POST` with a JSON body through the HTTP client.
+ *
+ * --------------------------------------------------------------------------
+ * Author:   Sam Wilcox
+ * Email:    sam@pawx-lang.com
+ * Website:  https://www.pawx-lang.com
+ * GitHub:   https://github.com/samwilcox/pawx
+ *
+ * License:
+ * This file is part of the PAWX programming language project.
+ *
+ * PAWX is dual-licensed under the terms of:
+ *   - The MIT License
+ *   - The Apache License, Version 2.0
+ *
+ * You may choose either license to govern your use of this software.
+ * Full license text available at:
+ *     https://license.pawx-lang.com
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under these licenses is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *
+ * ==========================================================================
+ */
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::time::Duration;
+
+use serde_json::{json, Value as JsonValue};
+
+use crate::prototypes::crypto::{sha256, to_hex};
+use crate::prototypes::http::{json_to_value, value_to_json_http};
+use crate::prototypes::http_client::{parse_url, HttpClient, HttpRequest, ParsedUrl};
+use crate::value::Value;
+
+/// Error code servers return when a persisted query hash is unknown.
+const PERSISTED_QUERY_NOT_FOUND: &str = "PersistedQueryNotFound";
+
+/* ============================================================================
+ * OPTIONS
+ * ============================================================================
+ */
+
+/// Per-call GraphQL settings.
+#[derive(Debug, Default)]
+struct GraphqlOptions {
+    headers: Vec<(String, String)>,
+    operation_name: Option<String>,
+    timeout: Option<Duration>,
+
+    /// Send only the query hash first, falling back to the full query.
+    persisted: bool,
+
+    /// Resolve to `{ data, errors }` instead of rejecting on errors.
+    raw: bool,
+}
+
+fn parse_options(value: Option<&Value>) -> Result<GraphqlOptions, String> {
+    let mut options = GraphqlOptions::default();
+
+    let fields = match value {
+        Some(Value::Object { fields }) => fields.borrow().clone(),
+        Some(Value::Null) | None => return Ok(options),
+        _ => return Err("Http.graphql: options must be an object".to_string()),
+    };
+
+    if let Some(Value::Object { fields: h }) = fields.get("headers") {
+        for (k, v) in h.borrow().iter() {
+            options.headers.push((k.clone(), v.to_pawx_string()));
+        }
+    }
+
+    match fields.get("operationName") {
+        Some(Value::String(name)) => options.operation_name = Some(name.to_string()),
+        Some(Value::Null) | None => {}
+        _ => return Err("Http.graphql: operationName must be a string".to_string()),
+    }
+
+    match fields.get("timeout") {
+        Some(Value::Number(n)) if *n > 0.0 => options.timeout = Some(Duration::from_millis(*n as u64)),
+        Some(Value::Number(_)) | Some(Value::Null) | None => {}
+        _ => return Err("Http.graphql: timeout must be a number of milliseconds".to_string()),
+    }
+
+    options.persisted = fields.get("persisted").is_some_and(Value::is_truthy);
+    options.raw = fields.get("raw").is_some_and(Value::is_truthy);
+
+    Ok(options)
+}
+
+/* ============================================================================
+ * EXECUTION
+ * ============================================================================
+ */
+
+/// Posts one GraphQL payload and parses the JSON reply.
+fn post(
+    client: &HttpClient,
+    url: &ParsedUrl,
+    payload: &JsonValue,
+    options: &GraphqlOptions,
+) -> Result<JsonValue, String> {
+    let mut headers = vec![
+        ("Content-Type".to_string(), "application/json".to_string()),
+        ("Accept".to_string(), "application/json".to_string()),
+    ];
+
+    for (k, v) in &options.headers {
+        headers.retain(|(existing, _)| !existing.eq_ignore_ascii_case(k));
+        headers.push((k.clone(), v.clone()));
+    }
+
+    let response = client.send(&HttpRequest {
+        method: "POST".to_string(),
+        url: url.clone(),
+        headers,
+        body: Some(payload.to_string()),
+        multipart: None,
+        timeout: options.timeout,
+    })?;
+
+    // GraphQL servers may answer errors with 4xx/5xx and a JSON body, so
+    // only fall back to the HTTP status when the body is not JSON.
+    match serde_json::from_str::<JsonValue>(&response.body) {
+        Ok(json @ JsonValue::Object(_)) => Ok(json),
+        _ => Err(format!(
+            "GraphQL: HTTP {} {}",
+            response.status, response.status_text
+        )),
+    }
+}
+
+/// Returns the `errors` array of a reply, if it is non-empty.
+fn reply_errors(reply: &JsonValue) -> Option<&Vec<JsonValue>> {
+    match reply.get("errors") {
+        Some(JsonValue::Array(errors)) if !errors.is_empty() => Some(errors),
+        _ => None,
+    }
+}
+
+/// Returns `true` if the server does not know the persisted query hash.
+fn is_persisted_miss(reply: &JsonValue) -> bool {
+    reply_errors(reply).is_some_and(|errors| {
+        errors.iter().any(|e| {
+            e.get("message").and_then(JsonValue::as_str) == Some(PERSISTED_QUERY_NOT_FOUND)
+                || e.pointer("/extensions/code").and_then(JsonValue::as_str)
+                    == Some("PERSISTED_QUERY_NOT_FOUND")
+        })
+    })
+}
+
+/// Converts a reply into the resolved PAWX value.
+///
+/// # Behavior
+/// - `raw`: always `{ data, errors }`
+/// - Otherwise `errors` become an `Err` joining every message, and a clean
+///   reply resolves to its `data`
+fn finish(reply: JsonValue, raw: bool) -> Result<Value, String> {
+    if raw {
+        let mut fields = HashMap::new();
+        fields.insert(
+            "data".to_string(),
+            json_to_value(reply.get("data").cloned().unwrap_or(JsonValue::Null)),
+        );
+        fields.insert(
+            "errors".to_string(),
+            json_to_value(reply.get("errors").cloned().unwrap_or(json!([]))),
+        );
+        return Ok(Value::Object {
+            fields: Rc::new(RefCell::new(fields)),
+        });
+    }
+
+    if let Some(errors) = reply_errors(&reply) {
+        let messages: Vec<String> = errors
+            .iter()
+            .map(|e| match e.get("message").and_then(JsonValue::as_str) {
+                Some(m) => m.to_string(),
+                None => e.to_string(),
+            })
+            .collect();
+
+        return Err(format!("GraphQL: {}", messages.join("; ")));
+    }
+
+    Ok(json_to_value(reply.get("data").cloned().unwrap_or(JsonValue::Null)))
+}
+
+/// Runs a query, using automatic persisted queries when enabled.
+fn execute(
+    client: &HttpClient,
+    url: &ParsedUrl,
+    query: &str,
+    variables: JsonValue,
+    options: &GraphqlOptions,
+) -> Result<Value, String> {
+    let mut payload = json!({ "variables": variables });
+
+    if let Some(name) = &options.operation_name {
+        payload["operationName"] = json!(name);
+    }
+
+    if options.persisted {
+        payload["extensions"] = json!({
+            "persistedQuery": {
+                "version": 1,
+                "sha256Hash": to_hex(&sha256(query.as_bytes())),
+            }
+        });
+
+        let reply = post(client, url, &payload, options)?;
+        if !is_persisted_miss(&reply) {
+            return finish(reply, options.raw);
+        }
+    }
+
+    payload["query"] = json!(query);
+    finish(post(client, url, &payload, options)?, options.raw)
+}
+
+/* ============================================================================
+ * PAWX BINDING
+ * ============================================================================
+ */
+
+/// Native implementation of `Http.graphql` and `client.graphql`.
+///
+/// # Parameters
+/// - `url`: GraphQL endpoint
+/// - `query`: Query or mutation document
+/// - `variables`: Optional object of variables
+/// - `options`: `{ headers, operationName, timeout, persisted, raw }`
+///
+/// # Returns
+/// - A Furure resolving to the reply's `data`
+/// - A Furure holding an `Error` if the reply has `errors` (unless `raw`)
+///
+/// # PAWX Example
+/// ```pawx
+/// Http.graphql("http://localhost:4000/graphql",
+///     "query($id: ID!) { user(id: $id) { name } }",
+///     { id: 1 })
+///     .then(data -> { meow(data.user.name) })
+///     .catch(e -> { meow(e) });
+/// ```
+pub fn graphql_request(client: &HttpClient, args: Vec<Value>) -> Value {
+    let result = (|| {
+        let url = match args.first() {
+            Some(Value::String(u)) => parse_url(u)?,
+            _ => return Err("Http.graphql(url, query, variables?, options?): url must be a string".to_string()),
+        };
+
+        let query = match args.get(1) {
+            Some(Value::String(q)) => q.to_string(),
+            _ => return Err("Http.graphql(url, query, variables?, options?): query must be a string".to_string()),
+        };
+
+        let variables = match args.get(2) {
+            Some(Value::Null) | None => json!({}),
+            Some(v @ Value::Object { .. }) => value_to_json_http(v)?,
+            _ => return Err("Http.graphql: variables must be an object".to_string()),
+        };
+
+        let options = parse_options(args.get(3))?;
+
+        execute(client, &url, &query, variables, &options)
+    })();
+
+    match result {
+        Ok(v) => Value::Furure(Box::new(v)),
+        Err(message) => Value::Furure(Box::new(Value::Error { message })),
+    }
+}
@@ -0,0 +1,319 @@
+/*
+ * ============================================================================
+ * PAWX - Code with Claws! 🐾
+ * ============================================================================
+ *
+ * Native Dialogs for PAWX (`Gui`)
+ *
+ * Only compiled with the `gui` cargo feature.
+ *
+ * Supports:
+ *   - Gui.alert(message, options?)       -> null
+ *   - Gui.confirm(message, options?)     -> bool
+ *   - Gui.prompt(message, options?)      -> string, or null if cancelled
+ *   - Gui.openFile(options?)             -> path, or null
+ *   - Gui.openFiles(options?)            -> array<path>, or null
+ *   - Gui.openFolder(options?)           -> path, or null
+ *   - Gui.saveFile(options?)             -> path, or null
+ *
+ * Message options:  { title, level ("info" | "warning" | "error"),
+ *                     buttons ("okCancel" | "yesNo") }
+ * Prompt options:   { title, default }
+ * File options:     { title, directory, fileName,
+ *                     filters: { "Images": ["png", "jpg"] } }
+ *
+ * Message boxes and file dialogs use the platform's native dialogs. Text
+ * prompts use zenity or kdialog on Linux, AppleScript on macOS and
+ * PowerShell on Windows, and fall back to reading a line from the
+ * terminal when none is available.
+ *
+ * Every call blocks the interpreter until the dialog is closed.
+ *
+ * --------------------------------------------------------------------------
+ * Author:   Sam Wilcox
+ * Email:    sam@pawx-lang.com
+ * Website:  https://www.pawx-lang.com
+ * GitHub:   https://github.com/samwilcox/pawx
+ *
+ * License:
+ * This file is part of the PAWX programming language project.
+ *
+ * PAWX is dual-licensed under the terms of:
+ *   - The MIT License
+ *   - The Apache License, Version 2.0
+ *
+ * You may choose either license to govern your use of this software.
+ * Full license text available at:
+ *     https://license.pawx-lang.com
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under these licenses is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *
+ * ==========================================================================
+ */
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::io::{BufRead, Write};
+use std::path::PathBuf;
+use std::process::Command;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use rfd::{FileDialog, MessageButtons, MessageDialog, MessageDialogResult, MessageLevel};
+
+use crate::error::PawxError;
+use crate::interpreter::calls::raise_error;
+use crate::prototypes::array::create_array_proto;
+use crate::value::Value;
+
+fn native(f: impl Fn(Vec<Value>) -> Result<Value, PawxError> + 'static) -> Value {
+    Value::NativeFunction(Arc::new(f))
+}
+
+fn options(value: Option<&Value>, usage: &str) -> Result<HashMap<String, Value>, PawxError> {
+    match value {
+        Some(Value::Object { fields }) => Ok(fields.borrow().clone()),
+        Some(Value::Null) | None => Ok(HashMap::new()),
+        _ => raise_error(format!("{}: options must be an object", usage)),
+    }
+}
+
+fn string_option(opts: &HashMap<String, Value>, key: &str) -> Option<String> {
+    match opts.get(key) {
+        Some(Value::String(s)) => Some(s.to_string()),
+        _ => None,
+    }
+}
+
+fn message_text(args: &[Value], usage: &str) -> Result<String, PawxError> {
+    match args.first() {
+        Some(Value::String(s)) => Ok(s.to_string()),
+        Some(other) => Ok(other.to_pawx_string()),
+        None => raise_error(format!("{} expects a message", usage)),
+    }
+}
+
+fn path_value(path: Option<PathBuf>) -> Value {
+    match path {
+        Some(path) => Value::String(path.to_string_lossy().into()),
+        None => Value::Null,
+    }
+}
+
+/* ============================================================================
+ * MESSAGE BOXES
+ * ============================================================================
+ */
+
+fn message_dialog(message: String, opts: &HashMap<String, Value>, usage: &str) -> Result<MessageDialog, PawxError> {
+    let level = match string_option(opts, "level").as_deref() {
+        None | Some("info") => MessageLevel::Info,
+        Some("warning") => MessageLevel::Warning,
+        Some("error") => MessageLevel::Error,
+        Some(other) => return raise_error(format!("{}: unknown level '{}'", usage, other)),
+    };
+
+    Ok(MessageDialog::new()
+        .set_title(string_option(opts, "title").unwrap_or_else(|| "PAWX".to_string()))
+        .set_description(message)
+        .set_level(level))
+}
+
+fn alert(args: Vec<Value>) -> Result<Value, PawxError> {
+    let usage = "Gui.alert(message, options?)";
+    let opts = options(args.get(1), usage)?;
+
+    message_dialog(message_text(&args, usage)?, &opts, usage)?
+        .set_buttons(MessageButtons::Ok)
+        .show();
+
+    Ok(Value::Null)
+}
+
+fn confirm(args: Vec<Value>) -> Result<Value, PawxError> {
+    let usage = "Gui.confirm(message, options?)";
+    let opts = options(args.get(1), usage)?;
+
+    let buttons = match string_option(&opts, "buttons").as_deref() {
+        None | Some("okCancel") => MessageButtons::OkCancel,
+        Some("yesNo") => MessageButtons::YesNo,
+        Some(other) => return raise_error(format!("{}: unknown buttons '{}'", usage, other)),
+    };
+
+    let result = message_dialog(message_text(&args, usage)?, &opts, usage)?
+        .set_buttons(buttons)
+        .show();
+
+    Ok(Value::Bool(matches!(result, MessageDialogResult::Ok | MessageDialogResult::Yes)))
+}
+
+/* ============================================================================
+ * TEXT PROMPTS
+ * ============================================================================
+ */
+
+/// Runs a dialog command; `Some(None)` means the user cancelled and `None`
+/// that the tool is unavailable.
+fn run_prompt_command(command: &mut Command) -> Option<Option<String>> {
+    let output = command.output().ok()?;
+
+    if !output.status.success() {
+        return Some(None);
+    }
+
+    let text = String::from_utf8_lossy(&output.stdout);
+    Some(Some(text.trim_end_matches(['\r', '\n']).to_string()))
+}
+
+#[cfg(target_os = "macos")]
+fn native_prompt(title: &str, message: &str, default: &str) -> Option<Option<String>> {
+    let quote = |s: &str| format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""));
+    let script = format!(
+        "text returned of (display dialog {} default answer {} with title {})",
+        quote(message),
+        quote(default),
+        quote(title)
+    );
+
+    run_prompt_command(Command::new("osascript").args(["-e", &script]))
+}
+
+#[cfg(target_os = "windows")]
+fn native_prompt(title: &str, message: &str, default: &str) -> Option<Option<String>> {
+    let quote = |s: &str| format!("'{}'", s.replace('\'', "''"));
+    let script = format!(
+        "Add-Type -AssemblyName Microsoft.VisualBasic; \
+         $r = [Microsoft.VisualBasic.Interaction]::InputBox({}, {}, {}); \
+         if ($r -eq '') {{ exit 1 }} else {{ $r }}",
+        quote(message),
+        quote(title),
+        quote(default)
+    );
+
+    run_prompt_command(Command::new("powershell").args(["-NoProfile", "-Command", &script]))
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn native_prompt(title: &str, message: &str, default: &str) -> Option<Option<String>> {
+    run_prompt_command(Command::new("zenity").args([
+        "--entry",
+        "--title",
+        title,
+        "--text",
+        message,
+        "--entry-text",
+        default,
+    ]))
+    .or_else(|| {
+        run_prompt_command(Command::new("kdialog").args(["--title", title, "--inputbox", message, default]))
+    })
+}
+
+/// Asks on the terminal; an empty answer takes the default.
+fn terminal_prompt(message: &str, default: &str) -> Option<String> {
+    if default.is_empty() {
+        print!("{} ", message);
+    } else {
+        print!("{} [{}] ", message, default);
+    }
+    let _ = std::io::stdout().flush();
+
+    let mut line = String::new();
+    match std::io::stdin().lock().read_line(&mut line) {
+        Ok(0) | Err(_) => None,
+        Ok(_) => {
+            let answer = line.trim_end_matches(['\r', '\n']);
+            Some(if answer.is_empty() { default } else { answer }.to_string())
+        }
+    }
+}
+
+fn prompt(args: Vec<Value>) -> Result<Value, PawxError> {
+    let usage = "Gui.prompt(message, options?)";
+    let opts = options(args.get(1), usage)?;
+    let message = message_text(&args, usage)?;
+    let title = string_option(&opts, "title").unwrap_or_else(|| "PAWX".to_string());
+    let default = string_option(&opts, "default").unwrap_or_default();
+
+    let answer = native_prompt(&title, &message, &default)
+        .unwrap_or_else(|| terminal_prompt(&message, &default));
+
+    Ok(answer.map(|s| Value::String(s.into())).unwrap_or(Value::Null))
+}
+
+/* ============================================================================
+ * FILE DIALOGS
+ * ============================================================================
+ */
+
+fn file_dialog(value: Option<&Value>, usage: &str) -> Result<FileDialog, PawxError> {
+    let opts = options(value, usage)?;
+    let mut dialog = FileDialog::new();
+
+    if let Some(title) = string_option(&opts, "title") {
+        dialog = dialog.set_title(title);
+    }
+    if let Some(directory) = string_option(&opts, "directory") {
+        dialog = dialog.set_directory(directory);
+    }
+    if let Some(file_name) = string_option(&opts, "fileName") {
+        dialog = dialog.set_file_name(file_name);
+    }
+
+    match opts.get("filters") {
+        Some(Value::Object { fields }) => {
+            for (name, extensions) in fields.borrow().iter() {
+                let extensions: Vec<String> = match extensions {
+                    Value::Array { values, .. } => values.borrow().iter().map(Value::to_pawx_string).collect(),
+                    other => vec![other.to_pawx_string()],
+                };
+                dialog = dialog.add_filter(name.clone(), &extensions);
+            }
+        }
+        None | Some(Value::Null) => {}
+        _ => return raise_error(format!("{}: filters must be an object of extension arrays", usage)),
+    }
+
+    Ok(dialog)
+}
+
+/// Builds the global `Gui` object.
+pub fn create_global_gui_object() -> HashMap<String, Value> {
+    let mut gui = HashMap::new();
+
+    gui.insert("alert".to_string(), native(alert));
+    gui.insert("confirm".to_string(), native(confirm));
+    gui.insert("prompt".to_string(), native(prompt));
+
+    gui.insert(
+        "openFile".to_string(),
+        native(|args| Ok(path_value(file_dialog(args.first(), "Gui.openFile(options?)")?.pick_file()))),
+    );
+
+    gui.insert(
+        "openFiles".to_string(),
+        native(|args| match file_dialog(args.first(), "Gui.openFiles(options?)")?.pick_files() {
+            Some(paths) => Ok(Value::Array {
+                values: Rc::new(RefCell::new(
+                    paths.into_iter().map(|p| path_value(Some(p))).collect(),
+                )),
+                proto: create_array_proto(),
+            }),
+            None => Ok(Value::Null),
+        }),
+    );
+
+    gui.insert(
+        "openFolder".to_string(),
+        native(|args| Ok(path_value(file_dialog(args.first(), "Gui.openFolder(options?)")?.pick_folder()))),
+    );
+
+    gui.insert(
+        "saveFile".to_string(),
+        native(|args| Ok(path_value(file_dialog(args.first(), "Gui.saveFile(options?)")?.save_file()))),
+    );
+
+    gui
+}
@@ -5,11 +5,29 @@
  *
  * HTTP Server + Request Parsing for PAWX
  *
+ * The HTTP client (`Http.client`, `Http.get`, `Http.post`, `Http.request`)
+ * lives in http_client.rs and the
+ * GraphQL helper (`Http.graphql`) in graphql.rs.
+ *
  * Supports:
  *   - Query parsing      → req.query
  *   - JSON body parsing  → req.body
  *   - Form parsing       → req.body
  *   - Raw text fallback → req.body
+ *   - Multipart parsing  → req.body
+ *   - Body validation    → req.validate(schema)
+ *   - Static files       → server.static(prefix, dir) (http_static.rs)
+ *   - Reverse proxy      → server.proxy(prefix, url) (http_proxy.rs)
+ *   - Routing            → server.get(pattern, handler), req.params
+ *                          (http_router.rs)
+ *   - Responses          → res.status(code), res.setHeader(name, value),
+ *                          res.json(data), res.send(text), res.html(text),
+ *                          res.redirect(url, status?)
+ *   - Handler errors     → 500 Internal Server Error
+ *   - Concurrent clients → connections read / written on worker threads
+ *                          (http_conn.rs)
+ *   - Request bodies     → Content-Length and chunked, read in full
+ *   - Keep-alive         → server.keepAlive(idleMs | false)
  * 
  * --------------------------------------------------------------------------
  * Author:   Sam Wilcox
@@ -35,8 +53,7 @@
  * ==========================================================================
  */
 
-use std::net::TcpListener;
-use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
 use std::sync::Arc;
 use std::cell::RefCell;
 use std::collections::HashMap;
@@ -44,9 +61,18 @@ use std::rc::Rc;
 
 use crate::interpreter::display::value_to_json;
 use crate::value::Value;
-use crate::interpreter::calls::call_value;
+use crate::interpreter::calls::{call_value, raise_error};
+use crate::interpreter::limits;
 use crate::prototypes::array::create_array_proto;
+use crate::prototypes::context;
+use crate::prototypes::http_conn::{Connections, KeepAlive, Outcome};
+use crate::prototypes::http_lifecycle::{self, answer_health, HealthCheck, Lifecycle, SignalWatch};
+use crate::prototypes::http_proxy::{forward as forward_proxy, ProxyRoute};
+use crate::prototypes::http_router::{Dispatch, Router};
+use crate::prototypes::http_static::{serve as serve_static, StaticMount};
+use crate::prototypes::json_writer;
 use crate::ast::Expr;
+use crate::error::PawxError;
 use crate::span::Span;
 
 use serde_json;
@@ -59,30 +85,149 @@ use serde_json;
 pub fn create_global_http_object() -> Value {
     let mut map = HashMap::new();
 
-    // Http.createServer(handler)
+    // Http.createServer(handler?)
     map.insert(
         "createServer".into(),
         Value::NativeFunction(Arc::new(|args| {
             let handler = args.get(0).cloned().unwrap_or(Value::Null);
+            let options: Rc<RefCell<ServerOptions>> = Rc::new(RefCell::new(ServerOptions::default()));
 
             let mut server = HashMap::new();
 
+            // server.health(path, check?)
+            let health_options = options.clone();
+            server.insert(
+                "health".into(),
+                Value::NativeFunction(Arc::new(move |health_args| {
+                    health_options.borrow_mut().health = Some(HealthCheck::from_args(&health_args)?);
+                    Ok(Value::Null)
+                })),
+            );
+
+            // server.drain(timeoutMs?)
+            // Kept outside the options cell, which is borrowed while listening
+            let lifecycle = options.borrow().lifecycle.clone();
+            server.insert(
+                "drain".into(),
+                Value::NativeFunction(Arc::new(move |drain_args| {
+                    lifecycle.drain(http_lifecycle::drain_timeout(&drain_args)?);
+                    Ok(Value::Null)
+                })),
+            );
+
+            // server.static(prefix, dir, options?)
+            let static_options = options.clone();
+            server.insert(
+                "static".into(),
+                Value::NativeFunction(Arc::new(move |static_args| {
+                    static_options.borrow_mut().static_mounts.push(StaticMount::from_args(&static_args)?);
+                    Ok(Value::Null)
+                })),
+            );
+
+            // server.proxy(prefix, url, options?)
+            let proxy_options = options.clone();
+            server.insert(
+                "proxy".into(),
+                Value::NativeFunction(Arc::new(move |proxy_args| {
+                    proxy_options.borrow_mut().proxies.push(ProxyRoute::from_args(&proxy_args)?);
+                    Ok(Value::Null)
+                })),
+            );
+
+            // server.keepAlive(idleMs | false)
+            let keep_alive_options = options.clone();
+            server.insert(
+                "keepAlive".into(),
+                Value::NativeFunction(Arc::new(move |keep_alive_args| {
+                    match KeepAlive::from_args(&keep_alive_args) {
+                        Ok(keep_alive) => {
+                            keep_alive_options.borrow_mut().keep_alive = keep_alive;
+                            Ok(Value::Null)
+                        }
+                        Err(message) => raise_error(message),
+                    }
+                })),
+            );
+
+            // server.get / post / put / patch / delete / all(pattern, handler)
+            for (name, method) in [
+                ("get", Some("GET")),
+                ("post", Some("POST")),
+                ("put", Some("PUT")),
+                ("patch", Some("PATCH")),
+                ("delete", Some("DELETE")),
+                ("all", None),
+            ] {
+                let route_options = options.clone();
+                server.insert(
+                    name.into(),
+                    Value::NativeFunction(Arc::new(move |route_args| {
+                        route_options.borrow_mut().router.add(method, &route_args)?;
+                        Ok(Value::Null)
+                    })),
+                );
+            }
+
+            // server.notFound(handler)
+            let not_found_options = options.clone();
+            server.insert(
+                "notFound".into(),
+                Value::NativeFunction(Arc::new(move |not_found_args| {
+                    not_found_options.borrow_mut().router.set_not_found(&not_found_args)?;
+                    Ok(Value::Null)
+                })),
+            );
+
             // server.listen(port)
             server.insert(
                 "listen".into(),
                 Value::NativeFunction(Arc::new(move |listen_args| {
-                    let port = match listen_args.get(0) {
+                    let port = match listen_args.first() {
                         Some(Value::Number(n)) => *n as u16,
-                        _ => panic!("listen(port) requires a number"),
+                        _ => return raise_error("listen(port) requires a number"),
                     };
 
-                    server_bind(port, handler.clone())
+                    Ok(server_bind(port, handler.clone(), &options.borrow()))
                 })),
             );
 
-            Value::Object {
+            Ok(Value::Object {
                 fields: Rc::new(RefCell::new(server)),
-            }
+            })
+        })),
+    );
+
+    // Http.client(options?)
+    map.insert(
+        "client".into(),
+        Value::NativeFunction(Arc::new(crate::prototypes::http_client::http_client)),
+    );
+
+    // Http.get(url, options?)
+    map.insert(
+        "get".into(),
+        crate::prototypes::cancel::cancellable(Arc::new(crate::prototypes::http_client::http_get)),
+    );
+
+    // Http.post(url, body?, headers?)
+    map.insert(
+        "post".into(),
+        crate::prototypes::cancel::cancellable(Arc::new(crate::prototypes::http_client::http_post)),
+    );
+
+    // Http.request(urlOrOptions)
+    map.insert(
+        "request".into(),
+        crate::prototypes::cancel::cancellable(Arc::new(crate::prototypes::http_client::http_request)),
+    );
+
+    // Http.graphql(url, query, variables?, options?)
+    map.insert(
+        "graphql".into(),
+        crate::prototypes::cancel::cancellable(Arc::new(|args| {
+            let client = crate::prototypes::http_client::default_client();
+            Ok(crate::prototypes::graphql::graphql_request(&client, args))
         })),
     );
 
@@ -96,76 +241,287 @@ pub fn create_global_http_object() -> Value {
  * ============================================================================
  */
 
-fn server_bind(port: u16, handler: Value) -> Value {
+/// What a server answers before its handler is called.
+#[derive(Default, Clone)]
+pub(crate) struct ServerOptions {
+    pub static_mounts: Vec<StaticMount>,
+    pub proxies: Vec<ProxyRoute>,
+    pub health: Option<HealthCheck>,
+    pub lifecycle: Rc<Lifecycle>,
+    pub router: Router,
+    pub keep_alive: KeepAlive,
+}
+
+/// The `X-Request-Id` a caller sent, if it is safe to echo back.
+fn incoming_request_id(raw: &str) -> Option<String> {
+    let head = raw.split("\r\n\r\n").next().unwrap_or(raw);
+
+    head.lines().skip(1).find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        let value = value.trim();
+        let usable = !value.is_empty() && value.len() <= 200 && value.bytes().all(|b| b.is_ascii_graphic());
+        (key.trim().eq_ignore_ascii_case("X-Request-Id") && usable).then(|| value.to_string())
+    })
+}
+
+pub(crate) fn server_bind(port: u16, handler: Value, options: &ServerOptions) -> Value {
     let listener = TcpListener::bind(("127.0.0.1", port)).unwrap();
+    let streamed = options.proxies.iter().map(|p| p.prefix().to_string()).collect();
+    let connections = Connections::start(&listener, port, options.keep_alive, streamed).unwrap();
 
     println!("🐾 PAWX HTTP listening on http://localhost:{port}");
 
-    for stream in listener.incoming() {
-        let mut stream = match stream {
-            Ok(s) => s,
-            Err(_) => continue,
-        };
+    let lifecycle = &options.lifecycle;
+    let _signals = SignalWatch::start();
 
-        // Capture client IP safely
-        let peer_ip = stream.peer_addr().ok().map(|a| a.ip());
+    // Connections are read and written by workers (http_conn.rs); this
+    // thread only answers the requests they hand over
+    while !lifecycle.is_draining() {
+        if http_lifecycle::take_signal() {
+            lifecycle.drain(http_lifecycle::DEFAULT_DRAIN_TIMEOUT);
+            break;
+        }
 
-        // Read request safely (prevents hanging)
-        let mut buffer = [0u8; 8192];
-        let bytes_read = match stream.read(&mut buffer) {
-            Ok(n) if n > 0 => n,
-            _ => continue,
+        let Some(mut job) = connections.next(http_lifecycle::SIGNAL_POLL) else {
+            continue;
         };
 
-        let raw_request = String::from_utf8_lossy(&buffer[..bytes_read]).to_string();
+        let outcome = answer_request(&job.raw, job.peer_ip, &mut job.stream, &handler, options);
+        job.answer(outcome);
+    }
+
+    // Draining (the handler may have called server.drain()): turn away
+    // every request already read and every connection still waiting
+    println!("🐾 PAWX HTTP draining http://localhost:{port}");
+    connections.stop();
+    lifecycle.refuse_waiting(&listener);
 
-        let (req_val, res_val, response_body) = build_req_res(&raw_request, peer_ip);
+    Value::Null
+}
+
+/// Answers one request: a health check, proxied or static path, or a call
+/// to the handler chosen by the router.
+fn answer_request(
+    raw: &[u8],
+    peer_ip: Option<std::net::IpAddr>,
+    stream: &mut TcpStream,
+    handler: &Value,
+    options: &ServerOptions,
+) -> Outcome {
+    // Health checks are answered without calling the handler
+    let health = answer_health(options.health.as_ref(), &options.lifecycle, &String::from_utf8_lossy(raw));
+    if let Some(response) = health {
+        return Outcome::Send(response);
+    }
+
+    // Proxied prefixes go upstream without calling the handler
+    if forward_proxy(&options.proxies, raw, stream, peer_ip) {
+        return Outcome::Done;
+    }
+
+    let raw_request = String::from_utf8_lossy(raw).to_string();
+
+    // Static files are answered without calling the handler
+    if let Some(response) = serve_static(&options.static_mounts, &raw_request) {
+        return Outcome::Send(response);
+    }
+
+    let (req_val, res_val, reply) = build_req_res(&raw_request, peer_ip);
+
+    // Keep the caller's request id, or start one, for correlating logs
+    let request_id = incoming_request_id(&raw_request).unwrap_or_else(context::new_request_id);
+    if let Value::Object { fields } = &req_val {
+        fields.borrow_mut().insert("requestId".into(), Value::String(request_id.as_str().into()));
+    }
 
+    // Routes come first, then notFound, then the createServer handler
+    let (method, path) = match &req_val {
+        Value::Object { fields } => {
+            let fields = fields.borrow();
+            (
+                fields.get("method").map(Value::to_pawx_string).unwrap_or_default(),
+                fields.get("path").map(Value::to_pawx_string).unwrap_or_default(),
+            )
+        }
+        _ => (String::new(), String::new()),
+    };
+
+    let mut params = HashMap::new();
+    let chosen = match options.router.dispatch(&method, &path) {
+        Dispatch::Route(route_handler, route_params) => {
+            params = route_params;
+            Some(route_handler)
+        }
+        Dispatch::NotFound(not_found) => {
+            reply.borrow_mut().status = 404;
+            Some(not_found)
+        }
+        Dispatch::Unrouted if !matches!(handler, Value::Null) => Some(handler.clone()),
+        Dispatch::Unrouted => None,
+    };
+
+    if let Value::Object { fields } = &req_val {
+        fields.borrow_mut().insert(
+            "params".into(),
+            Value::Object {
+                fields: Rc::new(RefCell::new(params)),
+            },
+        );
+    }
+
+    if let Some(chosen) = chosen {
         let handler_env = Rc::new(RefCell::new(
             crate::interpreter::environment::Environment::new(None),
         ));
 
-        // Call handler(req, res) — we IGNORE whatever it returns.
-        let _ = call_value(
-            handler.clone(),
-            vec![
-                Expr::Literal {
-                    value: req_val,
-                    span: Span { line: 0, column: 0 },
-                },
-                Expr::Literal {
-                    value: res_val,
-                    span: Span { line: 0, column: 0 },
-                },
-            ],
-            handler_env,
-        );
+        // Call handler(req, res); what it returns is ignored
+        let request_scope = vec![(context::REQUEST_ID.to_string(), Value::String(request_id.as_str().into()))];
+        let outcome = context::scope(request_scope, || {
+            call_value(
+                chosen,
+                vec![
+                    Expr::Literal {
+                        value: req_val,
+                        span: Span { line: 0, column: 0 },
+                    },
+                    Expr::Literal {
+                        value: res_val,
+                        span: Span { line: 0, column: 0 },
+                    },
+                ],
+                handler_env,
+            )
+        });
+
+        // A handler that fails answers 500 instead of what it had set.
+        // Lambdas report their own errors and return them as values.
+        let failed = match outcome {
+            Ok(Value::Error { .. }) => true,
+            Ok(_) => false,
+            Err(err) => {
+                eprintln!("🐾 PAWX HTTP handler failed [{}]: {}", request_id, err.message);
+                true
+            }
+        };
+        if failed {
+            let mut reply = reply.borrow_mut();
+            *reply = Reply::default();
+            reply.status = 500;
+            reply.body = Some(r#"{"error":"Internal Server Error"}"#.to_string());
+        }
+    } else {
+        let mut reply = reply.borrow_mut();
+        reply.status = 404;
+        reply.body = Some(format!(
+            "{{\"error\":\"Not Found\",\"path\":{}}}",
+            {
+                let mut quoted = String::new();
+                json_writer::write_string(&path, &mut quoted);
+                quoted
+            }
+        ));
+    }
+
+    let response = reply.borrow().to_response(&request_id);
+    Outcome::Send(response)
+}
 
-        // Prefer what res.json() stored; fall back to simple JSON
-        let body_value = response_body.borrow().clone();
+/* ============================================================================
+ * RESPONSE
+ * ============================================================================
+ */
 
-        let body = match body_value {
-            // res.json wrote a proper JSON string
-            Value::String(s) => s,
+/// What a handler has set through `res`.
+pub(crate) struct Reply {
+    pub status: u16,
+    /// Headers from `res.setHeader`, in the order first set.
+    pub headers: Vec<(String, String)>,
+    /// `None` until the handler sends something.
+    pub body: Option<String>,
+    pub content_type: &'static str,
+}
 
-            // res.json was never called: send a minimal JSON object
-            Value::Null => "{}".to_string(),
+impl Default for Reply {
+    fn default() -> Self {
+        Reply {
+            status: 200,
+            headers: Vec::new(),
+            body: None,
+            content_type: "application/json",
+        }
+    }
+}
 
-            // Some other value: stringify it once as JSON
-            other => serde_json::to_string(&value_to_json_http(&other)).unwrap(),
-        };
+impl Reply {
+    /// Sets a header, replacing any earlier value of the same name.
+    fn set_header(&mut self, name: &str, value: String) {
+        match self.headers.iter_mut().find(|(k, _)| k.eq_ignore_ascii_case(name)) {
+            Some(header) => header.1 = value,
+            None => self.headers.push((name.to_string(), value)),
+        }
+    }
 
-        let response = format!(
-            "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nContent-Type: application/json\r\n\r\n{}",
-            body.len(),
-            body
-        );
+    fn has_header(&self, name: &str) -> bool {
+        self.headers.iter().any(|(k, _)| k.eq_ignore_ascii_case(name))
+    }
 
-        let _ = stream.write_all(response.as_bytes());
-        let _ = stream.flush();
+    /// The full HTTP response. A handler that sent nothing gets `{}`.
+    fn to_response(&self, request_id: &str) -> Vec<u8> {
+        let body = self.body.as_deref().unwrap_or("{}");
+
+        let mut head = format!("HTTP/1.1 {} {}\r\n", self.status, reason(self.status));
+        head.push_str(&format!("Content-Length: {}\r\n", body.len()));
+        if !self.has_header("Content-Type") {
+            head.push_str(&format!("Content-Type: {}\r\n", self.content_type));
+        }
+        if !self.has_header("X-Request-Id") {
+            head.push_str(&format!("X-Request-Id: {}\r\n", request_id));
+        }
+        for (name, value) in &self.headers {
+            if !name.eq_ignore_ascii_case("Content-Length") {
+                head.push_str(&format!("{}: {}\r\n", name, value));
+            }
+        }
+        head.push_str("\r\n");
+
+        let mut response = head.into_bytes();
+        response.extend_from_slice(body.as_bytes());
+        response
     }
+}
 
-    Value::Null
+/// The standard reason phrase for `status`, or `""` for unusual codes.
+pub(crate) fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        201 => "Created",
+        202 => "Accepted",
+        204 => "No Content",
+        301 => "Moved Permanently",
+        302 => "Found",
+        303 => "See Other",
+        304 => "Not Modified",
+        307 => "Temporary Redirect",
+        308 => "Permanent Redirect",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        409 => "Conflict",
+        410 => "Gone",
+        413 => "Payload Too Large",
+        415 => "Unsupported Media Type",
+        422 => "Unprocessable Entity",
+        429 => "Too Many Requests",
+        431 => "Request Header Fields Too Large",
+        500 => "Internal Server Error",
+        501 => "Not Implemented",
+        502 => "Bad Gateway",
+        503 => "Service Unavailable",
+        504 => "Gateway Timeout",
+        _ => "",
+    }
 }
 
 /* ============================================================================
@@ -176,51 +532,46 @@ fn server_bind(port: u16, handler: Value) -> Value {
 fn build_req_res(
     raw: &str,
     peer_ip: Option<std::net::IpAddr>,
-) -> (Value, Value, Rc<RefCell<Value>>) {
-    let mut lines = raw.lines();
+) -> (Value, Value, Rc<RefCell<Reply>>) {
+    // The body is kept byte for byte: multipart bodies need their CRLFs
+    let (head, body) = raw
+        .split_once("\r\n\r\n")
+        .or_else(|| raw.split_once("\n\n"))
+        .unwrap_or((raw, ""));
+
+    let mut lines = head.lines();
     let request_line = lines.next().unwrap_or("");
     let parts: Vec<&str> = request_line.split_whitespace().collect();
 
-    let method = parts.get(0).unwrap_or(&"GET").to_string();
+    let method = parts.first().unwrap_or(&"GET").to_string();
     let full_path = parts.get(1).unwrap_or(&"/");
     let (path, query_str) = split_path_query(full_path);
 
     let mut headers: HashMap<String, Value> = HashMap::new();
-    let mut body = String::new();
-    let mut reading_body = false;
 
     for line in lines {
-        if line.is_empty() {
-            reading_body = true;
-            continue;
-        }
-
-        if reading_body {
-            body.push_str(line);
-        } else {
-            let mut parts = line.splitn(2, ':');
-            let k = parts.next().unwrap_or("").trim();
-            let v = parts.next().unwrap_or("").trim();
-            headers.insert(k.to_string(), Value::String(v.to_string()));
-        }
+        let mut parts = line.splitn(2, ':');
+        let k = parts.next().unwrap_or("").trim();
+        let v = parts.next().unwrap_or("").trim();
+        headers.insert(k.to_string(), Value::String(v.into()));
     }
 
     let content_type = headers
         .get("Content-Type")
-        .and_then(|v| if let Value::String(s) = v { Some(s.as_str()) } else { None })
+        .and_then(|v| if let Value::String(s) = v { Some(&**s) } else { None })
         .unwrap_or("");
 
     let hostname = headers
         .get("Host")
         .and_then(|v| if let Value::String(s) = v { Some(s.clone()) } else { None })
-        .unwrap_or_else(|| "localhost".to_string());
+        .unwrap_or_else(|| "localhost".into());
 
     let user_agent = headers
         .get("User-Agent")
         .and_then(|v| if let Value::String(s) = v { Some(s.clone()) } else { None })
-        .unwrap_or_else(|| "Unknown".to_string());
+        .unwrap_or_else(|| "Unknown".into());
 
-    let body_value = parse_body(&body, content_type);
+    let body_value = parse_body(body, content_type);
 
     /* -------------------------------
        IP OBJECT
@@ -230,12 +581,12 @@ fn build_req_res(
     if let Some(ip) = peer_ip {
         match ip {
             std::net::IpAddr::V4(v4) => {
-                ip_fields.insert("v4".into(), Value::String(v4.to_string()));
+                ip_fields.insert("v4".into(), Value::String(v4.to_string().into()));
                 ip_fields.insert("v6".into(), Value::Null);
             }
             std::net::IpAddr::V6(v6) => {
                 ip_fields.insert("v4".into(), Value::Null);
-                ip_fields.insert("v6".into(), Value::String(v6.to_string()));
+                ip_fields.insert("v6".into(), Value::String(v6.to_string().into()));
             }
         }
     } else {
@@ -251,9 +602,9 @@ fn build_req_res(
        REQUEST OBJECT
     -------------------------------- */
     let mut req_fields = HashMap::new();
-    req_fields.insert("method".into(), Value::String(method));
-    req_fields.insert("path".into(), Value::String(path.clone()));
-    req_fields.insert("url".into(), Value::String(path));
+    req_fields.insert("method".into(), Value::String(method.into()));
+    req_fields.insert("path".into(), Value::String(path.as_str().into()));
+    req_fields.insert("url".into(), Value::String(path.into()));
     req_fields.insert("ip".into(), ip_value);
     req_fields.insert("hostname".into(), Value::String(hostname));
     req_fields.insert("userAgent".into(), Value::String(user_agent));
@@ -272,72 +623,119 @@ fn build_req_res(
         },
     );
 
-    req_fields.insert("body".into(), body_value);
+    req_fields.insert("body".into(), body_value.clone());
+
+    // req.validate(schema) → { ok, value, errors }
+    req_fields.insert(
+        "validate".into(),
+        Value::NativeFunction(Arc::new(move |args| {
+            crate::prototypes::schema::validate_with(args.first(), body_value.clone())
+        })),
+    );
 
     let req = Value::Object {
         fields: Rc::new(RefCell::new(req_fields)),
     };
 
     /* -------------------------------
-       RESPONSE OBJECT + BODY CELL
+       RESPONSE OBJECT + REPLY CELL
     -------------------------------- */
-    let response_body: Rc<RefCell<Value>> = Rc::new(RefCell::new(Value::Null));
+    let reply: Rc<RefCell<Reply>> = Rc::new(RefCell::new(Reply::default()));
     let res_fields = Rc::new(RefCell::new(HashMap::new()));
 
-    // Copies of shared cells for closures
-    let res_fields_for_status = res_fields.clone();
-    let res_fields_for_json = res_fields.clone();
-    let body_for_json = response_body.clone();
+    // Every method returns res, for chaining: res.status(201).json(...)
+    let method = |name: &str, f: fn(&mut Reply, &[Value]) -> Result<(), PawxError>| {
+        let fields = Rc::downgrade(&res_fields);
+        let reply = reply.clone();
 
-    // --- res.status(code) ---
-    {
-        let fields = res_fields_for_status.clone();
         res_fields.borrow_mut().insert(
-            "status".into(),
-            Value::NativeFunction(Arc::new(move |_args| {
-                // You can later wire status code into response if you want.
-                Value::Object {
-                    fields: fields.clone(),
-                }
+            name.into(),
+            Value::NativeFunction(Arc::new(move |args| {
+                f(&mut reply.borrow_mut(), &args)?;
+                Ok(fields.upgrade().map_or(Value::Null, |fields| Value::Object { fields }))
             })),
         );
-    }
-
-    // --- res.json(data) ---
-    {
-        let fields = res_fields_for_json.clone();
-        let body_cell = body_for_json.clone();
-
-        res_fields.borrow_mut().insert(
-            "json".into(),
-            Value::NativeFunction(Arc::new(move |args| {
-                // Accept either plain String or any Value
-                let json_str = match args.get(0) {
-                    // Handler passed a raw string: use it as-is
-                    Some(Value::String(s)) => s.clone(),
-
-                    // Handler passed some structured Value: convert ONCE
-                    Some(v) => serde_json::to_string(&value_to_json_http(v)).unwrap(),
+    };
 
-                    // Nothing: send empty object
-                    None => "{}".to_string(),
-                };
+    // --- res.status(code) ---
+    method("status", |reply, args| {
+        reply.status = match args.first() {
+            Some(Value::Number(n)) if (100.0..=999.0).contains(n) => *n as u16,
+            _ => return raise_error("res.status(code) expects a status code from 100 to 999"),
+        };
+        Ok(())
+    });
+
+    // --- res.setHeader(name, value) ---
+    method("setHeader", |reply, args| {
+        let (name, value) = match (args.first(), args.get(1)) {
+            (Some(Value::String(name)), Some(value)) => (name.to_string(), value.to_pawx_string()),
+            _ => return raise_error("res.setHeader(name, value) expects a header name and a value"),
+        };
+        if name.contains(['\r', '\n', ':']) || value.contains(['\r', '\n']) {
+            return raise_error(format!("res.setHeader: invalid header '{}'", name));
+        }
+        reply.set_header(&name, value);
+        Ok(())
+    });
 
-                *body_cell.borrow_mut() = Value::String(json_str);
+    // --- res.json(data) ---
+    method("json", |reply, args| {
+        let json_str = match args.first() {
+            // Handler passed a raw string: use it as-is
+            Some(Value::String(s)) => s.to_string(),
+
+            // Handler passed some structured Value: convert ONCE
+            Some(v) => match json_writer::to_json(v) {
+                Ok(json) => json,
+                Err(message) => return raise_error(message),
+            },
+
+            // Nothing: send empty object
+            None => "{}".to_string(),
+        };
 
-                // Return res for chaining: res.status(...).json(...)
-                Value::Object {
-                    fields: fields.clone(),
-                }
-            })),
-        );
-    }
+        reply.body = Some(json_str);
+        reply.content_type = "application/json";
+        Ok(())
+    });
+
+    // --- res.send(text) ---
+    method("send", |reply, args| {
+        reply.body = Some(args.first().map(Value::to_pawx_string).unwrap_or_default());
+        reply.content_type = "text/plain; charset=utf-8";
+        Ok(())
+    });
+
+    // --- res.html(text) ---
+    method("html", |reply, args| {
+        reply.body = Some(args.first().map(Value::to_pawx_string).unwrap_or_default());
+        reply.content_type = "text/html; charset=utf-8";
+        Ok(())
+    });
+
+    // --- res.redirect(url, status = 302) ---
+    method("redirect", |reply, args| {
+        let url = match args.first() {
+            Some(Value::String(url)) if !url.contains(['\r', '\n']) => url.to_string(),
+            _ => return raise_error("res.redirect(url, status?) expects a URL string"),
+        };
+        reply.status = match args.get(1) {
+            None | Some(Value::Null) => 302,
+            Some(Value::Number(n)) if (300.0..400.0).contains(n) => *n as u16,
+            _ => return raise_error("res.redirect(url, status?) expects a 3xx status"),
+        };
+        reply.set_header("Location", url);
+        reply.body = Some(String::new());
+        reply.content_type = "text/plain; charset=utf-8";
+        Ok(())
+    });
 
     let res = Value::Object {
         fields: res_fields.clone(),
     };
 
-    (req, res, response_body)
+    (req, res, reply)
 }
 
 /* ============================================================================
@@ -345,42 +743,139 @@ fn build_req_res(
  * ============================================================================
  */
 
-fn value_to_json_http(val: &Value) -> serde_json::Value {
-    match val {
+/// Converts a value to a `serde_json::Value` for the helpers that need one
+/// (Auth.sign, Http.graphql, health reports). `toJson()` hooks apply at
+/// every level.
+///
+/// Containers are filled from an explicit stack rather than by recursion,
+/// so deep values cannot overflow the Rust stack.
+///
+/// # Errors
+/// If the value is nested deeper than `--max-depth`.
+pub(crate) fn value_to_json_http(val: &Value) -> Result<serde_json::Value, String> {
+    // Containers still being filled, innermost last
+    let mut stack: Vec<JsonFrame> = Vec::new();
+    let mut next = (None, val.clone());
+
+    loop {
+        let (key, value) = next;
+        let mut done = match json_http_node(&value)? {
+            JsonNode::Leaf(json) => Some((key, json)),
+            JsonNode::Container(built, items) => {
+                limits::check_nesting(stack.len() + 1, "JSON encoding")?;
+                stack.push(JsonFrame { key, built, rest: items.into_iter() });
+                None
+            }
+        };
+
+        // Hand finished values to their parents until one has more to fill
+        next = loop {
+            if let Some((key, json)) = done.take() {
+                match stack.last_mut() {
+                    Some(parent) => parent.built.insert(key, json),
+                    None => return Ok(json),
+                }
+            }
+
+            let frame = stack.last_mut().expect("a finished value either returns or has a parent");
+
+            match frame.rest.next() {
+                Some(item) => break item,
+                None => {
+                    let frame = stack.pop().expect("frame was just inspected");
+                    done = Some((frame.key, frame.built.finish()));
+                }
+            }
+        };
+    }
+}
+
+/// A container of [`value_to_json_http`] still being filled.
+struct JsonFrame {
+    /// Its key in the enclosing object, if any.
+    key: Option<String>,
+    built: JsonContainer,
+    rest: std::vec::IntoIter<(Option<String>, Value)>,
+}
+
+enum JsonContainer {
+    Array(Vec<serde_json::Value>),
+    Object(serde_json::Map<String, serde_json::Value>),
+}
+
+impl JsonContainer {
+    fn insert(&mut self, key: Option<String>, json: serde_json::Value) {
+        match self {
+            JsonContainer::Array(items) => items.push(json),
+            JsonContainer::Object(map) => {
+                map.insert(key.unwrap_or_default(), json);
+            }
+        }
+    }
+
+    fn finish(self) -> serde_json::Value {
+        match self {
+            JsonContainer::Array(items) => serde_json::Value::Array(items),
+            JsonContainer::Object(map) => serde_json::Value::Object(map),
+        }
+    }
+}
+
+/// One value as [`value_to_json_http`] sees it.
+enum JsonNode {
+    Leaf(serde_json::Value),
+    /// An empty container and the elements to fill it with.
+    Container(JsonContainer, Vec<(Option<String>, Value)>),
+}
+
+/// Converts a scalar, or lists the elements of a container, after its
+/// `toJson()` hook.
+fn json_http_node(val: &Value) -> Result<JsonNode, String> {
+    let replaced = crate::prototypes::json::to_json_hook(val)?;
+    let val = replaced.as_ref().unwrap_or(val);
+
+    let number = |n: f64| {
+        serde_json::Number::from_f64(n)
+            .map(serde_json::Value::Number)
+            .unwrap_or(serde_json::Value::Null)
+    };
+    let array = |items: Vec<Value>| {
+        let items = items.into_iter().map(|v| (None, v)).collect();
+        JsonNode::Container(JsonContainer::Array(Vec::new()), items)
+    };
+    let object = |entries: Vec<(String, Value)>| {
+        let entries = entries.into_iter().map(|(k, v)| (Some(k), v)).collect();
+        JsonNode::Container(JsonContainer::Object(serde_json::Map::new()), entries)
+    };
+
+    Ok(JsonNode::Leaf(match val {
         Value::Null => serde_json::Value::Null,
         Value::Bool(b) => serde_json::Value::Bool(*b),
+        Value::Number(n) => number(*n),
+        Value::String(s) => serde_json::Value::String(s.to_string()),
 
-        Value::Number(n) => {
-            serde_json::Number::from_f64(*n)
-                .map(serde_json::Value::Number)
-                .unwrap_or(serde_json::Value::Null)
-        }
+        // Decimals travel as strings so no digits are lost
+        Value::Decimal(d) => serde_json::Value::String(d.to_string()),
 
-        Value::String(s) => serde_json::Value::String(s.clone()),
+        // Durations travel as their length in milliseconds
+        Value::Duration(ms) => number(*ms),
 
-        Value::Array { values, .. } => {
-            let elems = values
-                .borrow()
-                .iter()
-                .map(|v| value_to_json_http(v))
-                .collect();
-            serde_json::Value::Array(elems)
-        }
+        Value::Float64Array(data) => serde_json::Value::Array(data.borrow().iter().map(|n| number(*n)).collect()),
+
+        Value::Array { values, .. } => return Ok(array(values.borrow().clone())),
+        Value::ImmutableList(list) => return Ok(array(list.iter().cloned().collect())),
 
         Value::Object { fields } => {
-            let mut map = serde_json::Map::new();
-            for (k, v) in fields.borrow().iter() {
-                map.insert(k.clone(), value_to_json_http(v));
-            }
-            serde_json::Value::Object(map)
+            return Ok(object(fields.borrow().iter().map(|(k, v)| (k.clone(), v.clone())).collect()))
         }
+        Value::ImmutableMap(map) => return Ok(object(map.iter().map(|(k, v)| (k.clone(), v.clone())).collect())),
 
         // For functions, classes, modules, etc – just give a readable marker
         _ => serde_json::Value::String("[non-json]".to_string()),
-    }
+    }))
 }
 
-fn split_path_query(path: &str) -> (String, &str) {
+pub(crate) fn split_path_query(path: &str) -> (String, &str) {
     if let Some(i) = path.find('?') {
         (path[..i].to_string(), &path[i + 1..])
     } else {
@@ -388,7 +883,7 @@ fn split_path_query(path: &str) -> (String, &str) {
     }
 }
 
-fn parse_query(q: &str) -> HashMap<String, Value> {
+pub(crate) fn parse_query(q: &str) -> HashMap<String, Value> {
     let mut map = HashMap::new();
 
     for pair in q.split('&') {
@@ -397,29 +892,31 @@ fn parse_query(q: &str) -> HashMap<String, Value> {
         let v = parts.next().unwrap_or("");
 
         if !k.is_empty() {
-            map.insert(k.to_string(), Value::String(url_decode(v)));
+            map.insert(k.to_string(), Value::String(url_decode(v).into()));
         }
     }
 
     map
 }
 
-fn parse_body(body: &str, ct: &str) -> Value {
+pub(crate) fn parse_body(body: &str, ct: &str) -> Value {
     if ct.contains("application/json") {
         match serde_json::from_str::<serde_json::Value>(body) {
             Ok(v) => json_to_value(v),
             Err(_) => Value::Null,
         }
+    } else if ct.contains("multipart/form-data") {
+        crate::prototypes::multipart::parse(body, ct).unwrap_or(Value::Null)
     } else if ct.contains("application/x-www-form-urlencoded") {
         Value::Object {
             fields: Rc::new(RefCell::new(parse_query(body))),
         }
     } else {
-        Value::String(body.to_string())
+        Value::String(body.into())
     }
 }
 
-fn json_to_value(v: serde_json::Value) -> Value {
+pub(crate) fn json_to_value(v: serde_json::Value) -> Value {
     match v {
         serde_json::Value::Null => Value::Null,
 
@@ -433,7 +930,7 @@ fn json_to_value(v: serde_json::Value) -> Value {
             }
         }
 
-        serde_json::Value::String(s) => Value::String(s),
+        serde_json::Value::String(s) => Value::String(s.into()),
 
         serde_json::Value::Array(arr) => Value::Array {
             values: Rc::new(RefCell::new(
@@ -455,7 +952,7 @@ fn json_to_value(v: serde_json::Value) -> Value {
     }
 }
 
-fn url_decode(s: &str) -> String {
+pub(crate) fn url_decode(s: &str) -> String {
     let mut result = String::new();
     let mut chars = s.chars();
 
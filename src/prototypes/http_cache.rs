@@ -0,0 +1,260 @@
+/*
+ * ============================================================================
+ * PAWX - Code with Claws! 🐾
+ * ============================================================================
+ *
+ * On-disk Response Cache for the PAWX HTTP Client
+ *
+ * Supports:
+ *   - Http.client({ cache: "./.http-cache" })   cache GET responses on disk
+ *   - Cache-Control: max-age, no-cache and no-store, and Expires
+ *   - Revalidation with If-None-Match / If-Modified-Since on stale entries
+ *
+ * A fresh entry is answered from disk without touching the network. A
+ * stale one that has an ETag or Last-Modified is revalidated: a `304`
+ * refreshes the entry and the cached response is returned as if the
+ * server had sent it in full. Responses without either a lifetime or a
+ * validator are not stored, since they could never be reused.
+ *
+ * Only `200` responses to `GET` are cached, one JSON file per URL. A
+ * request with `Cache-Control: no-cache` skips the freshness check;
+ * `no-store`, or conditional headers of its own, bypass the cache
+ * entirely. The cache is best effort: a directory that cannot be written
+ * just means nothing is cached.
+ *
+ * --------------------------------------------------------------------------
+ * Author:   Sam Wilcox
+ * Email:    sam@pawx-lang.com
+ * Website:  https://www.pawx-lang.com
+ * GitHub:   https://github.com/samwilcox/pawx
+ *
+ * License:
+ * This file is part of the PAWX programming language project.
+ *
+ * PAWX is dual-licensed under the terms of:
+ *   - The MIT License
+ *   - The Apache License, Version 2.0
+ *
+ * You may choose either license to govern your use of this software.
+ * Full license text available at:
+ *     https://license.pawx-lang.com
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under these licenses is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *
+ * ==========================================================================
+ */
+
+use std::fs;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::prototypes::http_client::{HttpRequest, HttpResponse};
+use crate::prototypes::http_static::parse_http_date;
+
+/// Headers a `304` may update on a stored response.
+const REFRESHED_HEADERS: [&str; 5] = ["Cache-Control", "Date", "ETag", "Expires", "Last-Modified"];
+
+/// The directives of a `Cache-Control` header that the cache acts on.
+#[derive(Default)]
+struct CacheControl {
+    no_cache: bool,
+    no_store: bool,
+    max_age: Option<i64>,
+}
+
+impl CacheControl {
+    fn parse<'a>(headers: impl Iterator<Item = &'a str>) -> Self {
+        let mut control = CacheControl::default();
+
+        for directive in headers.flat_map(|h| h.split(',')) {
+            let (name, value) = match directive.split_once('=') {
+                Some((name, value)) => (name.trim(), Some(value.trim().trim_matches('"'))),
+                None => (directive.trim(), None),
+            };
+
+            match name.to_ascii_lowercase().as_str() {
+                "no-cache" => control.no_cache = true,
+                "no-store" => control.no_store = true,
+                "max-age" => control.max_age = value.and_then(|v| v.parse().ok()),
+                _ => {}
+            }
+        }
+
+        control
+    }
+}
+
+fn header_values<'a>(headers: &'a [(String, String)], name: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+    headers
+        .iter()
+        .filter(move |(k, _)| k.eq_ignore_ascii_case(name))
+        .map(|(_, v)| v.as_str())
+}
+
+fn header<'a>(headers: &'a [(String, String)], name: &'a str) -> Option<&'a str> {
+    header_values(headers, name).next()
+}
+
+/// A stored response.
+#[derive(Serialize, Deserialize)]
+struct Entry {
+    status: u16,
+    status_text: String,
+    headers: Vec<(String, String)>,
+    body: String,
+    url: String,
+
+    /// When the response was received or last revalidated, in Unix ms.
+    stored_at: i64,
+}
+
+impl Entry {
+    fn from_response(response: &HttpResponse, now: i64) -> Self {
+        Entry {
+            status: response.status,
+            status_text: response.status_text.clone(),
+            headers: response.headers.clone(),
+            body: response.body.clone(),
+            url: response.url.clone(),
+            stored_at: now,
+        }
+    }
+
+    fn control(&self) -> CacheControl {
+        CacheControl::parse(header_values(&self.headers, "Cache-Control"))
+    }
+
+    /// Whether the entry may be used without asking the server.
+    fn is_fresh(&self, now: i64) -> bool {
+        let control = self.control();
+        if control.no_cache {
+            return false;
+        }
+
+        match control.max_age {
+            Some(seconds) => now - self.stored_at < seconds * 1000,
+            None => header(&self.headers, "Expires")
+                .and_then(parse_http_date)
+                .is_some_and(|expires| now < expires * 1000),
+        }
+    }
+
+    /// Takes the updated headers of a `304` and restarts the lifetime.
+    fn refresh(&mut self, not_modified: &HttpResponse, now: i64) {
+        for name in REFRESHED_HEADERS {
+            if let Some(value) = not_modified.header(name) {
+                self.headers.retain(|(k, _)| !k.eq_ignore_ascii_case(name));
+                self.headers.push((name.to_string(), value.to_string()));
+            }
+        }
+        self.stored_at = now;
+    }
+
+    fn to_response(&self) -> HttpResponse {
+        HttpResponse {
+            status: self.status,
+            status_text: self.status_text.clone(),
+            headers: self.headers.clone(),
+            body: self.body.clone(),
+            url: self.url.clone(),
+            redirects: 0,
+        }
+    }
+}
+
+/// Whether `response` is worth storing (see module docs).
+fn storable(response: &HttpResponse) -> bool {
+    let control = CacheControl::parse(response.header_all("Cache-Control"));
+
+    response.status == 200
+        && !control.no_store
+        && (control.max_age.is_some()
+            || ["ETag", "Last-Modified", "Expires"].iter().any(|h| response.header(h).is_some()))
+}
+
+/// A directory of cached responses.
+#[derive(Debug, Clone)]
+pub struct HttpCache {
+    dir: PathBuf,
+}
+
+impl HttpCache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        HttpCache { dir: dir.into() }
+    }
+
+    fn path_for(&self, url: &str) -> PathBuf {
+        let digest = Sha256::digest(url.as_bytes());
+        let name: String = digest.iter().map(|b| format!("{:02x}", b)).collect();
+        self.dir.join(format!("{}.json", name))
+    }
+
+    fn load(&self, url: &str) -> Option<Entry> {
+        let text = fs::read_to_string(self.path_for(url)).ok()?;
+        serde_json::from_str(&text).ok()
+    }
+
+    fn store(&self, url: &str, entry: &Entry) {
+        if let Ok(json) = serde_json::to_string(entry) {
+            let _ = fs::create_dir_all(&self.dir).and_then(|_| fs::write(self.path_for(url), json));
+        }
+    }
+
+    /// Answers `req` from the cache where possible, otherwise through
+    /// `send`, storing what comes back.
+    pub fn send(
+        &self,
+        req: &HttpRequest,
+        send: impl FnOnce(&HttpRequest) -> Result<HttpResponse, String>,
+    ) -> Result<HttpResponse, String> {
+        let control = CacheControl::parse(header_values(&req.headers, "Cache-Control"));
+        let conditional_already =
+            header(&req.headers, "If-None-Match").is_some() || header(&req.headers, "If-Modified-Since").is_some();
+
+        if req.method != "GET" || control.no_store || conditional_already {
+            return send(req);
+        }
+
+        let url = req.url.to_url_string();
+        let now = chrono::Utc::now().timestamp_millis();
+        let cached = self.load(&url);
+
+        if let Some(entry) = &cached {
+            if !control.no_cache && entry.is_fresh(now) {
+                return Ok(entry.to_response());
+            }
+        }
+
+        // Ask the server whether the stored copy is still good
+        let mut conditional = req.clone();
+        if let Some(entry) = &cached {
+            let validators = [("If-None-Match", "ETag"), ("If-Modified-Since", "Last-Modified")];
+            for (condition, validator) in validators {
+                if let Some(value) = header(&entry.headers, validator) {
+                    conditional.headers.push((condition.to_string(), value.to_string()));
+                }
+            }
+        }
+
+        let response = send(&conditional)?;
+
+        match cached {
+            Some(mut entry) if response.status == 304 => {
+                entry.refresh(&response, now);
+                self.store(&url, &entry);
+                Ok(entry.to_response())
+            }
+
+            _ => {
+                if storable(&response) {
+                    self.store(&url, &Entry::from_response(&response, now));
+                }
+                Ok(response)
+            }
+        }
+    }
+}
@@ -0,0 +1,1511 @@
+/*
+ * ============================================================================
+ * PAWX - Code with Claws! 🐾
+ * ============================================================================
+ *
+ * HTTP/1.1 Client + Connection Pool for PAWX
+ *
+ * Supports:
+ *   - Connection pooling keyed by host:port
+ *   - Keep-alive connection reuse
+ *   - Per-client and per-request timeouts
+ *   - Request pipelining (client.pipeline([...]))
+ *   - Content-Length, chunked and read-to-close response bodies
+ *   - HTTP_PROXY / NO_PROXY environment variables and manual proxies
+ *   - Automatic redirect following with a hop limit
+ *   - A per-client cookie jar
+ *   - An on-disk response cache honouring Cache-Control (http_cache.rs)
+ *   - multipart/form-data bodies with streamed file parts (multipart.rs)
+ *   - One-off requests on a shared default client:
+ *       Http.get(url, options?), Http.post(url, body?, headers?),
+ *       Http.request(urlOrOptions)
+ *
+ * Only plain `http://` URLs are supported.
+ *
+ * --------------------------------------------------------------------------
+ * Author:   Sam Wilcox
+ * Email:    sam@pawx-lang.com
+ * Website:  https://www.pawx-lang.com
+ * GitHub:   https://github.com/samwilcox/pawx
+ *
+ * License:
+ * This file is part of the PAWX programming language project.
+ *
+ * PAWX is dual-licensed under the terms of:
+ *   - The MIT License
+ *   - The Apache License, Version 2.0
+ *
+ * You may choose either license to govern your use of this software.
+ * Full license text available at:
+ *     https://license.pawx-lang.com
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under these licenses is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *
+ * ==========================================================================
+ */
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::rc::Rc;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::error::PawxError;
+use crate::interpreter::calls::raise_error;
+use crate::interpreter::display::value_to_json;
+use crate::prototypes::array::create_array_proto;
+use crate::prototypes::cancel::cancellable;
+use crate::prototypes::http_cache::HttpCache;
+use crate::prototypes::json::parse_text;
+use crate::prototypes::multipart::Multipart;
+use crate::value::Value;
+
+/* ============================================================================
+ * CONFIGURATION
+ * ============================================================================
+ */
+
+/// Connection pool settings for an HTTP client.
+#[derive(Debug, Clone)]
+pub struct PoolConfig {
+    /// Maximum idle connections kept per host.
+    pub max_connections: usize,
+
+    /// Reuse connections between requests.
+    pub keep_alive: bool,
+
+    /// Default timeout (connect, read and write) for each request.
+    pub timeout: Option<Duration>,
+}
+
+impl Default for PoolConfig {
+    fn default() -> Self {
+        Self {
+            max_connections: 6,
+            keep_alive: true,
+            timeout: Some(Duration::from_secs(30)),
+        }
+    }
+}
+
+/// Counters exposed through `client.stats()`.
+#[derive(Debug, Clone, Default)]
+pub struct PoolStats {
+    pub created: u64,
+    pub reused: u64,
+    pub requests: u64,
+}
+
+/// How a client chooses its proxy.
+#[derive(Debug, Clone)]
+pub enum ProxySetting {
+    /// Use `http_proxy` / `HTTP_PROXY`, honouring `no_proxy` / `NO_PROXY`.
+    Env,
+
+    /// Always use the given proxy.
+    Manual(ParsedUrl),
+
+    /// Never use a proxy.
+    Disabled,
+}
+
+/// Full client settings.
+#[derive(Debug, Clone)]
+pub struct ClientConfig {
+    pub pool: PoolConfig,
+    pub proxy: ProxySetting,
+
+    /// Follow 3xx responses carrying a `Location` header.
+    pub follow_redirects: bool,
+
+    /// Maximum redirect hops before failing.
+    pub max_redirects: usize,
+
+    /// Store `Set-Cookie` responses and send matching cookies.
+    pub cookies: bool,
+
+    /// Cache `GET` responses on disk.
+    pub cache: Option<HttpCache>,
+}
+
+impl Default for ClientConfig {
+    fn default() -> Self {
+        Self {
+            pool: PoolConfig::default(),
+            proxy: ProxySetting::Env,
+            follow_redirects: true,
+            max_redirects: 10,
+            cookies: true,
+            cache: None,
+        }
+    }
+}
+
+/* ============================================================================
+ * CONNECTION POOL
+ * ============================================================================
+ */
+
+type Connection = BufReader<TcpStream>;
+
+/// Idle keep-alive connections grouped by `host:port`.
+pub struct ConnectionPool {
+    config: PoolConfig,
+    idle: HashMap<String, Vec<Connection>>,
+    stats: PoolStats,
+}
+
+impl ConnectionPool {
+    /// Creates an empty pool.
+    pub fn new(config: PoolConfig) -> Self {
+        Self {
+            config,
+            idle: HashMap::new(),
+            stats: PoolStats::default(),
+        }
+    }
+
+    /// Takes an idle connection for `key`, if one is available.
+    fn checkout(&mut self, key: &str) -> Option<Connection> {
+        let conn = self.idle.get_mut(key)?.pop()?;
+        self.stats.reused += 1;
+        Some(conn)
+    }
+
+    /// Returns a connection to the pool, dropping it if the pool is full
+    /// or keep-alive is disabled.
+    fn checkin(&mut self, key: &str, conn: Connection) {
+        if !self.config.keep_alive {
+            return;
+        }
+
+        let idle = self.idle.entry(key.to_string()).or_default();
+        if idle.len() < self.config.max_connections {
+            idle.push(conn);
+        }
+    }
+
+    /// Opens a fresh connection.
+    fn connect(&mut self, url: &ParsedUrl, timeout: Option<Duration>) -> Result<Connection, String> {
+        let stream = open_stream(url, timeout)?;
+        self.stats.created += 1;
+        Ok(BufReader::new(stream))
+    }
+
+    /// Number of idle connections across all hosts.
+    fn idle_count(&self) -> usize {
+        self.idle.values().map(Vec::len).sum()
+    }
+}
+
+/// Opens a TCP connection to `url`'s host and port.
+///
+/// # Errors
+/// If the host does not resolve or refuses the connection.
+pub(crate) fn open_stream(url: &ParsedUrl, timeout: Option<Duration>) -> Result<TcpStream, String> {
+    let addr = (url.host.as_str(), url.port)
+        .to_socket_addrs()
+        .map_err(|e| format!("Http: cannot resolve '{}': {}", url.host, e))?
+        .next()
+        .ok_or_else(|| format!("Http: cannot resolve '{}'", url.host))?;
+
+    match timeout {
+        Some(t) => TcpStream::connect_timeout(&addr, t),
+        None => TcpStream::connect(addr),
+    }
+    .map_err(|e| format!("Http: cannot connect to {}: {}", url.key(), e))
+}
+
+/* ============================================================================
+ * REQUEST / RESPONSE MODEL
+ * ============================================================================
+ */
+
+/// Parsed `http://host:port/path` URL.
+#[derive(Debug, Clone)]
+pub struct ParsedUrl {
+    pub host: String,
+    pub port: u16,
+    pub path: String,
+}
+
+impl ParsedUrl {
+    /// Pool key for this URL.
+    pub fn key(&self) -> String {
+        format!("{}:{}", self.host, self.port)
+    }
+
+    /// Absolute URL form (`http://host[:port]/path`).
+    pub fn to_url_string(&self) -> String {
+        if self.port == 80 {
+            format!("http://{}{}", self.host, self.path)
+        } else {
+            format!("http://{}:{}{}", self.host, self.port, self.path)
+        }
+    }
+
+    /// Path without the query string.
+    fn path_only(&self) -> &str {
+        self.path.split('?').next().unwrap_or("/")
+    }
+}
+
+/// A fully described outgoing request.
+#[derive(Debug, Clone)]
+pub struct HttpRequest {
+    pub method: String,
+    pub url: ParsedUrl,
+    pub headers: Vec<(String, String)>,
+    pub body: Option<String>,
+
+    /// A multipart body, sent instead of `body`.
+    pub multipart: Option<Multipart>,
+
+    pub timeout: Option<Duration>,
+}
+
+/// A received response.
+#[derive(Debug, Clone)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub status_text: String,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+
+    /// Final URL after redirects.
+    pub url: String,
+
+    /// Number of redirects followed.
+    pub redirects: usize,
+}
+
+impl HttpResponse {
+    /// Returns the first header with the given (case-insensitive) name.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Returns every header with the given (case-insensitive) name.
+    pub fn header_all<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        self.headers
+            .iter()
+            .filter(move |(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// Resolves a `Location` header against the URL that produced it.
+fn resolve_location(base: &ParsedUrl, location: &str) -> Result<ParsedUrl, String> {
+    if location.starts_with("http://") || location.starts_with("https://") {
+        return parse_url(location);
+    }
+
+    if let Some(rest) = location.strip_prefix("//") {
+        return parse_url(&format!("http://{}", rest));
+    }
+
+    let path = if location.starts_with('/') {
+        location.to_string()
+    } else {
+        let dir = match base.path_only().rfind('/') {
+            Some(i) => &base.path_only()[..=i],
+            None => "/",
+        };
+        format!("{}{}", dir, location)
+    };
+
+    Ok(ParsedUrl {
+        host: base.host.clone(),
+        port: base.port,
+        path,
+    })
+}
+
+/* ============================================================================
+ * PROXIES
+ * ============================================================================
+ */
+
+fn env_var(names: &[&str]) -> Option<String> {
+    names
+        .iter()
+        .filter_map(|n| std::env::var(n).ok())
+        .find(|v| !v.trim().is_empty())
+}
+
+/// Returns `true` if `host` is excluded by a `NO_PROXY` list.
+fn no_proxy_matches(no_proxy: &str, host: &str) -> bool {
+    no_proxy.split(',').map(str::trim).any(|entry| {
+        if entry.is_empty() {
+            return false;
+        }
+        if entry == "*" {
+            return true;
+        }
+
+        let entry = entry.split(':').next().unwrap_or(entry);
+        let domain = entry.trim_start_matches('.');
+
+        host.eq_ignore_ascii_case(domain)
+            || host.to_ascii_lowercase().ends_with(&format!(".{}", domain.to_ascii_lowercase()))
+    })
+}
+
+/// Reads the proxy for `url` from the environment.
+fn proxy_from_env(url: &ParsedUrl) -> Option<ParsedUrl> {
+    if let Some(no_proxy) = env_var(&["no_proxy", "NO_PROXY"]) {
+        if no_proxy_matches(&no_proxy, &url.host) {
+            return None;
+        }
+    }
+
+    let proxy = env_var(&["http_proxy", "HTTP_PROXY"])?;
+
+    // Accept bare `host:port` as well as `http://host:port`.
+    let proxy = if proxy.contains("://") {
+        proxy
+    } else {
+        format!("http://{}", proxy)
+    };
+
+    parse_url(&proxy).ok()
+}
+
+/* ============================================================================
+ * COOKIE JAR
+ * ============================================================================
+ */
+
+#[derive(Debug, Clone)]
+struct Cookie {
+    name: String,
+    value: String,
+    domain: String,
+    host_only: bool,
+    path: String,
+    secure: bool,
+    /// Expiry as Unix milliseconds; `None` for session cookies.
+    expires: Option<i64>,
+}
+
+impl Cookie {
+    fn is_expired(&self, now: i64) -> bool {
+        self.expires.is_some_and(|e| e <= now)
+    }
+
+    fn matches(&self, url: &ParsedUrl) -> bool {
+        let host = url.host.to_ascii_lowercase();
+
+        let domain_ok = if self.host_only {
+            host == self.domain
+        } else {
+            host == self.domain || host.ends_with(&format!(".{}", self.domain))
+        };
+
+        let path = url.path_only();
+        let path_ok = path == self.path
+            || (path.starts_with(&self.path)
+                && (self.path.ends_with('/') || path[self.path.len()..].starts_with('/')));
+
+        // Only plain http is supported, so secure cookies are never sent.
+        domain_ok && path_ok && !self.secure
+    }
+}
+
+/// Cookies received by a client, sent back on matching requests.
+#[derive(Debug, Default)]
+pub struct CookieJar {
+    cookies: Vec<Cookie>,
+}
+
+impl CookieJar {
+    /// Stores one `Set-Cookie` header received from `url`.
+    pub fn store(&mut self, url: &ParsedUrl, header: &str) {
+        let now = chrono::Utc::now().timestamp_millis();
+        let mut parts = header.split(';');
+
+        let (name, value) = match parts.next().and_then(|p| p.split_once('=')) {
+            Some((n, v)) if !n.trim().is_empty() => (n.trim().to_string(), v.trim().to_string()),
+            _ => return,
+        };
+
+        let host = url.host.to_ascii_lowercase();
+        let mut cookie = Cookie {
+            name,
+            value,
+            domain: host.clone(),
+            host_only: true,
+            path: match url.path_only().rfind('/') {
+                Some(0) | None => "/".to_string(),
+                Some(i) => url.path_only()[..i].to_string(),
+            },
+            secure: false,
+            expires: None,
+        };
+
+        let mut max_age: Option<i64> = None;
+
+        for attr in parts {
+            let (key, val) = match attr.split_once('=') {
+                Some((k, v)) => (k.trim().to_ascii_lowercase(), v.trim()),
+                None => (attr.trim().to_ascii_lowercase(), ""),
+            };
+
+            match key.as_str() {
+                "domain" if !val.is_empty() => {
+                    let domain = val.trim_start_matches('.').to_ascii_lowercase();
+                    // Reject cookies for domains the host does not belong to.
+                    if host != domain && !host.ends_with(&format!(".{}", domain)) {
+                        return;
+                    }
+                    cookie.domain = domain;
+                    cookie.host_only = false;
+                }
+                "path" if val.starts_with('/') => cookie.path = val.to_string(),
+                "max-age" => max_age = val.parse().ok(),
+                "expires" => {
+                    if let Ok(t) = chrono::DateTime::parse_from_rfc2822(val) {
+                        cookie.expires = Some(t.timestamp_millis());
+                    }
+                }
+                "secure" => cookie.secure = true,
+                _ => {}
+            }
+        }
+
+        // Max-Age takes precedence over Expires.
+        if let Some(seconds) = max_age {
+            cookie.expires = Some(now + seconds * 1000);
+        }
+
+        self.cookies.retain(|c| {
+            !(c.name == cookie.name && c.domain == cookie.domain && c.path == cookie.path)
+        });
+
+        if !cookie.is_expired(now) {
+            self.cookies.push(cookie);
+        }
+    }
+
+    /// Builds the `Cookie` header for a request to `url`, if any cookie
+    /// applies. Longer paths are sent first.
+    pub fn header_for(&mut self, url: &ParsedUrl) -> Option<String> {
+        let now = chrono::Utc::now().timestamp_millis();
+        self.cookies.retain(|c| !c.is_expired(now));
+
+        let mut matching: Vec<&Cookie> = self.cookies.iter().filter(|c| c.matches(url)).collect();
+        if matching.is_empty() {
+            return None;
+        }
+
+        matching.sort_by_key(|c| std::cmp::Reverse(c.path.len()));
+
+        Some(
+            matching
+                .iter()
+                .map(|c| format!("{}={}", c.name, c.value))
+                .collect::<Vec<_>>()
+                .join("; "),
+        )
+    }
+
+    /// Removes every cookie.
+    pub fn clear(&mut self) {
+        self.cookies.clear();
+    }
+}
+
+/// Parses an `http://` URL.
+pub fn parse_url(url: &str) -> Result<ParsedUrl, String> {
+    let rest = if let Some(rest) = url.strip_prefix("http://") {
+        rest
+    } else if url.starts_with("https://") {
+        return Err(format!("Http: https is not supported ('{}')", url));
+    } else {
+        return Err(format!("Http: invalid URL '{}'", url));
+    };
+
+    let (authority, path) = match rest.find('/') {
+        Some(i) => (&rest[..i], &rest[i..]),
+        None => (rest, "/"),
+    };
+
+    let (host, port) = match authority.rsplit_once(':') {
+        Some((h, p)) => (
+            h.to_string(),
+            p.parse::<u16>()
+                .map_err(|_| format!("Http: invalid port in '{}'", url))?,
+        ),
+        None => (authority.to_string(), 80),
+    };
+
+    if host.is_empty() {
+        return Err(format!("Http: missing host in '{}'", url));
+    }
+
+    Ok(ParsedUrl {
+        host,
+        port,
+        path: path.to_string(),
+    })
+}
+
+/* ============================================================================
+ * WIRE PROTOCOL
+ * ============================================================================
+ */
+
+/// Serializes a request into HTTP/1.1 wire format.
+///
+/// Requests sent through a proxy use the absolute URL as request target.
+fn encode_request(req: &HttpRequest, keep_alive: bool, via_proxy: bool) -> Vec<u8> {
+    let target = if via_proxy {
+        req.url.to_url_string()
+    } else {
+        req.url.path.clone()
+    };
+
+    let mut out = format!("{} {} HTTP/1.1\r\n", req.method, target);
+
+    let has = |name: &str| req.headers.iter().any(|(k, _)| k.eq_ignore_ascii_case(name));
+
+    if !has("Host") {
+        if req.url.port == 80 {
+            out.push_str(&format!("Host: {}\r\n", req.url.host));
+        } else {
+            out.push_str(&format!("Host: {}:{}\r\n", req.url.host, req.url.port));
+        }
+    }
+
+    if !has("Connection") {
+        let conn = if keep_alive { "keep-alive" } else { "close" };
+        out.push_str(&format!("Connection: {}\r\n", conn));
+    }
+
+    if !has("User-Agent") {
+        out.push_str(&format!("User-Agent: pawx/{}\r\n", env!("CARGO_PKG_VERSION")));
+    }
+
+    for (k, v) in &req.headers {
+        out.push_str(&format!("{}: {}\r\n", k, v));
+    }
+
+    // A multipart body is streamed after the head by `exchange`
+    let body = req.body.as_deref().unwrap_or("");
+    let length = match &req.multipart {
+        Some(multipart) => multipart.content_length(),
+        None => body.len() as u64,
+    };
+    if req.body.is_some() || req.multipart.is_some() || !matches!(req.method.as_str(), "GET" | "HEAD") {
+        out.push_str(&format!("Content-Length: {}\r\n", length));
+    }
+
+    out.push_str("\r\n");
+    out.push_str(body);
+    out.into_bytes()
+}
+
+/// Reads one response from the connection.
+///
+/// # Returns
+/// - The response
+/// - `true` if the connection may be reused afterwards
+fn read_response(conn: &mut Connection, method: &str) -> Result<(HttpResponse, bool), ExchangeError> {
+    let mut status_line = String::new();
+    match conn.read_line(&mut status_line) {
+        Ok(0) => return Err(ExchangeError::Stale("Http: connection closed before response".to_string())),
+        Ok(_) => {}
+        Err(e) if is_timeout(&e) => return Err(ExchangeError::Failed(io_err(e))),
+        Err(e) => return Err(ExchangeError::Stale(io_err(e))),
+    }
+
+    let mut parts = status_line.trim_end().splitn(3, ' ');
+    let version = parts.next().unwrap_or("");
+    let status = parts
+        .next()
+        .and_then(|s| s.parse::<u16>().ok())
+        .ok_or_else(|| format!("Http: malformed status line '{}'", status_line.trim_end()))?;
+    let status_text = parts.next().unwrap_or("").to_string();
+
+    let mut headers = Vec::new();
+    loop {
+        let mut line = String::new();
+        if conn.read_line(&mut line).map_err(io_err)? == 0 {
+            break;
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((k, v)) = line.split_once(':') {
+            headers.push((k.trim().to_string(), v.trim().to_string()));
+        }
+    }
+
+    let mut response = HttpResponse {
+        status,
+        status_text,
+        headers,
+        body: String::new(),
+        url: String::new(),
+        redirects: 0,
+    };
+
+    let close_requested = response
+        .header("Connection")
+        .is_some_and(|v| v.eq_ignore_ascii_case("close"));
+    let mut reusable = version == "HTTP/1.1" && !close_requested;
+
+    let no_body = method == "HEAD" || status == 204 || status == 304 || (100..200).contains(&status);
+
+    let bytes = if no_body {
+        Vec::new()
+    } else if response
+        .header("Transfer-Encoding")
+        .is_some_and(|v| v.to_ascii_lowercase().contains("chunked"))
+    {
+        read_chunked(conn)?
+    } else if let Some(len) = response.header("Content-Length") {
+        let len: usize = len
+            .parse()
+            .map_err(|_| format!("Http: invalid Content-Length '{}'", len))?;
+        let mut buf = vec![0u8; len];
+        conn.read_exact(&mut buf).map_err(io_err)?;
+        buf
+    } else {
+        // No framing: the body runs until the server closes the connection.
+        reusable = false;
+        let mut buf = Vec::new();
+        conn.read_to_end(&mut buf).map_err(io_err)?;
+        buf
+    };
+
+    response.body = String::from_utf8_lossy(&bytes).to_string();
+    Ok((response, reusable))
+}
+
+/// Reads a `Transfer-Encoding: chunked` body.
+fn read_chunked(conn: &mut Connection) -> Result<Vec<u8>, String> {
+    let mut body = Vec::new();
+
+    loop {
+        let mut size_line = String::new();
+        conn.read_line(&mut size_line).map_err(io_err)?;
+
+        let size_str = size_line.trim().split(';').next().unwrap_or("");
+        let size = usize::from_str_radix(size_str, 16)
+            .map_err(|_| format!("Http: invalid chunk size '{}'", size_str))?;
+
+        if size == 0 {
+            // Skip trailers up to the terminating blank line.
+            loop {
+                let mut line = String::new();
+                if conn.read_line(&mut line).map_err(io_err)? == 0 || line.trim().is_empty() {
+                    break;
+                }
+            }
+            return Ok(body);
+        }
+
+        let start = body.len();
+        body.resize(start + size, 0);
+        conn.read_exact(&mut body[start..]).map_err(io_err)?;
+
+        let mut crlf = [0u8; 2];
+        conn.read_exact(&mut crlf).map_err(io_err)?;
+    }
+}
+
+/// Why a request/response exchange failed.
+enum ExchangeError {
+    /// The connection was unusable (e.g. closed by the server while idle);
+    /// the request may be retried on a fresh connection.
+    Stale(String),
+
+    /// Any other failure, including timeouts.
+    Failed(String),
+}
+
+impl From<String> for ExchangeError {
+    fn from(message: String) -> Self {
+        ExchangeError::Failed(message)
+    }
+}
+
+impl ExchangeError {
+    fn message(self) -> String {
+        match self {
+            ExchangeError::Stale(m) | ExchangeError::Failed(m) => m,
+        }
+    }
+}
+
+fn is_timeout(e: &std::io::Error) -> bool {
+    matches!(e.kind(), std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut)
+}
+
+fn io_err(e: std::io::Error) -> String {
+    if is_timeout(&e) {
+        "Http: request timed out".to_string()
+    } else {
+        format!("Http: {}", e)
+    }
+}
+
+fn apply_timeout(conn: &Connection, timeout: Option<Duration>) -> Result<(), String> {
+    let stream = conn.get_ref();
+    stream
+        .set_read_timeout(timeout)
+        .and_then(|_| stream.set_write_timeout(timeout))
+        .map_err(io_err)
+}
+
+/* ============================================================================
+ * CLIENT
+ * ============================================================================
+ */
+
+/// A pooled HTTP/1.1 client.
+#[derive(Clone)]
+pub struct HttpClient {
+    pool: Rc<RefCell<ConnectionPool>>,
+    config: Rc<ClientConfig>,
+    jar: Rc<RefCell<CookieJar>>,
+}
+
+thread_local! {
+    /// Client shared by the module-level `Http` helpers.
+    static DEFAULT_CLIENT: HttpClient = HttpClient::new(ClientConfig::default());
+}
+
+/// Returns a handle to the shared default client.
+pub fn default_client() -> HttpClient {
+    DEFAULT_CLIENT.with(|c| c.clone())
+}
+
+/// Status codes that carry a `Location` to follow.
+fn is_redirect(status: u16) -> bool {
+    matches!(status, 301 | 302 | 303 | 307 | 308)
+}
+
+/// Passes the id of the request being handled (see `Context`) on to the
+/// next service, unless the request sets its own.
+fn with_request_id(mut req: HttpRequest) -> HttpRequest {
+    if !req.headers.iter().any(|(k, _)| k.eq_ignore_ascii_case("X-Request-Id")) {
+        if let Some(id) = crate::prototypes::context::request_id() {
+            req.headers.push(("X-Request-Id".to_string(), id));
+        }
+    }
+    req
+}
+
+impl HttpClient {
+    /// Creates a client with its own connection pool and cookie jar.
+    pub fn new(config: ClientConfig) -> Self {
+        Self {
+            pool: Rc::new(RefCell::new(ConnectionPool::new(config.pool.clone()))),
+            config: Rc::new(config),
+            jar: Rc::new(RefCell::new(CookieJar::default())),
+        }
+    }
+
+    /// Returns the proxy to use for `url`, if any.
+    fn proxy_for(&self, url: &ParsedUrl) -> Option<ParsedUrl> {
+        match &self.config.proxy {
+            ProxySetting::Env => proxy_from_env(url),
+            ProxySetting::Manual(proxy) => Some(proxy.clone()),
+            ProxySetting::Disabled => None,
+        }
+    }
+
+    /// Sends one request, reusing a pooled connection when possible.
+    ///
+    /// A reused connection that turns out to be stale (closed by the server)
+    /// is discarded and the request is retried once on a fresh connection.
+    ///
+    /// Redirects are followed up to `max_redirects` hops:
+    /// - 303, and 301/302 for methods other than GET/HEAD, switch to GET
+    ///   and drop the body
+    /// - 307/308 repeat the original method and body
+    /// - `Authorization` is dropped when the redirect changes host
+    ///
+    /// With a cache configured, `GET` requests go through it first.
+    pub fn send(&self, req: &HttpRequest) -> Result<HttpResponse, String> {
+        match &self.config.cache {
+            Some(cache) => cache.send(req, |req| self.send_uncached(req)),
+            None => self.send_uncached(req),
+        }
+    }
+
+    fn send_uncached(&self, req: &HttpRequest) -> Result<HttpResponse, String> {
+        let mut current = req.clone();
+        let mut hops = 0;
+
+        loop {
+            let mut response = self.send_all(std::slice::from_ref(&current))?.remove(0);
+            response.redirects = hops;
+
+            if !self.config.follow_redirects || !is_redirect(response.status) {
+                return Ok(response);
+            }
+
+            let location = match response.header("Location") {
+                Some(l) => l.to_string(),
+                None => return Ok(response),
+            };
+
+            if hops >= self.config.max_redirects {
+                return Err(format!(
+                    "Http: too many redirects (limit {})",
+                    self.config.max_redirects
+                ));
+            }
+
+            let next = resolve_location(&current.url, &location)?;
+
+            let to_get = response.status == 303
+                || (matches!(response.status, 301 | 302)
+                    && current.method != "GET"
+                    && current.method != "HEAD");
+
+            if to_get {
+                if current.method != "HEAD" {
+                    current.method = "GET".to_string();
+                }
+                current.body = None;
+                current.multipart = None;
+                current.headers.retain(|(k, _)| {
+                    !k.eq_ignore_ascii_case("Content-Type") && !k.eq_ignore_ascii_case("Content-Length")
+                });
+            }
+
+            if next.key() != current.url.key() {
+                current
+                    .headers
+                    .retain(|(k, _)| !k.eq_ignore_ascii_case("Authorization"));
+            }
+
+            current.url = next;
+            hops += 1;
+        }
+    }
+
+    /// Sends several requests to the **same host** on one connection using
+    /// HTTP/1.1 pipelining, returning responses in request order.
+    ///
+    /// Pipelined requests are routed through the proxy and cookie jar, but
+    /// redirects are **not** followed.
+    pub fn send_all(&self, reqs: &[HttpRequest]) -> Result<Vec<HttpResponse>, String> {
+        let first = match reqs.first() {
+            Some(r) => r,
+            None => return Ok(Vec::new()),
+        };
+
+        if reqs.iter().any(|r| r.url.key() != first.url.key()) {
+            return Err("Http: pipelined requests must target the same host".to_string());
+        }
+
+        let reqs: Vec<HttpRequest> = reqs.iter().map(|r| with_request_id(self.with_cookies(r))).collect();
+
+        let mut responses = match crate::prototypes::http_test::intercept(&reqs)? {
+            Some(responses) => responses,
+            None => self.send_over_network(&reqs)?,
+        };
+
+        for (req, res) in reqs.iter().zip(responses.iter_mut()) {
+            res.url = req.url.to_url_string();
+
+            if self.config.cookies {
+                let mut jar = self.jar.borrow_mut();
+                for header in res.header_all("Set-Cookie") {
+                    jar.store(&req.url, header);
+                }
+            }
+        }
+
+        Ok(responses)
+    }
+
+    /// Sends `reqs` (all to one host) on a pooled connection.
+    fn send_over_network(&self, reqs: &[HttpRequest]) -> Result<Vec<HttpResponse>, String> {
+        let first = &reqs[0];
+        let proxy = self.proxy_for(&first.url);
+        let target = proxy.as_ref().unwrap_or(&first.url).clone();
+        let key = target.key();
+        let via_proxy = proxy.is_some();
+
+        let keep_alive = self.config.pool.keep_alive;
+        let timeout = first.timeout.or(self.config.pool.timeout);
+
+        self.pool.borrow_mut().stats.requests += reqs.len() as u64;
+
+        let pooled = self.pool.borrow_mut().checkout(&key);
+        let was_pooled = pooled.is_some();
+
+        let mut conn = match pooled {
+            Some(conn) => conn,
+            None => self.pool.borrow_mut().connect(&target, timeout)?,
+        };
+
+        let responses = match Self::exchange(&mut conn, reqs, keep_alive, via_proxy, timeout) {
+            Ok((responses, reusable)) => {
+                if reusable {
+                    self.pool.borrow_mut().checkin(&key, conn);
+                }
+                responses
+            }
+
+            // Stale keep-alive connection: retry once on a fresh one.
+            Err(ExchangeError::Stale(_)) if was_pooled => {
+                let mut conn = self.pool.borrow_mut().connect(&target, timeout)?;
+                let (responses, reusable) =
+                    Self::exchange(&mut conn, reqs, keep_alive, via_proxy, timeout)
+                        .map_err(ExchangeError::message)?;
+                if reusable {
+                    self.pool.borrow_mut().checkin(&key, conn);
+                }
+                responses
+            }
+
+            Err(e) => return Err(e.message()),
+        };
+
+        Ok(responses)
+    }
+
+    /// Adds a `Cookie` header from the jar unless the request sets one.
+    fn with_cookies(&self, req: &HttpRequest) -> HttpRequest {
+        let mut req = req.clone();
+
+        if self.config.cookies && !req.headers.iter().any(|(k, _)| k.eq_ignore_ascii_case("Cookie")) {
+            if let Some(cookie) = self.jar.borrow_mut().header_for(&req.url) {
+                req.headers.push(("Cookie".to_string(), cookie));
+            }
+        }
+
+        req
+    }
+
+    /// Writes every request, then reads every response in order.
+    fn exchange(
+        conn: &mut Connection,
+        reqs: &[HttpRequest],
+        keep_alive: bool,
+        via_proxy: bool,
+        timeout: Option<Duration>,
+    ) -> Result<(Vec<HttpResponse>, bool), ExchangeError> {
+        apply_timeout(conn, timeout)?;
+
+        let stream = conn.get_mut();
+        let mut wire = Vec::new();
+        for (i, req) in reqs.iter().enumerate() {
+            // Only the last pipelined request may ask to close.
+            let keep = keep_alive || i + 1 < reqs.len();
+            wire.extend(encode_request(req, keep, via_proxy));
+
+            if let Some(multipart) = &req.multipart {
+                stream
+                    .write_all(&wire)
+                    .map_err(|e| ExchangeError::Stale(io_err(e)))?;
+                wire.clear();
+
+                multipart
+                    .write_to(stream)
+                    .map_err(|e| ExchangeError::Failed(format!("Http: upload failed: {}", e)))?;
+            }
+        }
+
+        stream
+            .write_all(&wire)
+            .and_then(|_| stream.flush())
+            .map_err(|e| ExchangeError::Stale(io_err(e)))?;
+
+        // Let a `Test.httpServer` on this thread answer before we block.
+        crate::prototypes::http_test::serve_pending();
+
+        let mut responses = Vec::with_capacity(reqs.len());
+        let mut reusable = keep_alive;
+
+        for req in reqs {
+            let (response, ok) = read_response(conn, &req.method)?;
+            reusable &= ok;
+            responses.push(response);
+        }
+
+        Ok((responses, reusable))
+    }
+
+    /// Returns pool statistics.
+    pub fn stats(&self) -> (PoolStats, usize) {
+        let pool = self.pool.borrow();
+        (pool.stats.clone(), pool.idle_count())
+    }
+
+    /// Drops every idle connection.
+    pub fn close(&self) {
+        self.pool.borrow_mut().idle.clear();
+    }
+
+    /// Returns `(name, value, domain, path)` for every stored cookie.
+    pub fn cookies(&self) -> Vec<(String, String, String, String)> {
+        let now = chrono::Utc::now().timestamp_millis();
+        self.jar
+            .borrow()
+            .cookies
+            .iter()
+            .filter(|c| !c.is_expired(now))
+            .map(|c| (c.name.clone(), c.value.clone(), c.domain.clone(), c.path.clone()))
+            .collect()
+    }
+
+    /// Empties the cookie jar.
+    pub fn clear_cookies(&self) {
+        self.jar.borrow_mut().clear();
+    }
+}
+
+/* ============================================================================
+ * PAWX BINDINGS
+ * ============================================================================
+ */
+
+fn object(fields: HashMap<String, Value>) -> Value {
+    Value::Object {
+        fields: Rc::new(RefCell::new(fields)),
+    }
+}
+
+fn number_ms(value: Option<&Value>, what: &str) -> Result<Option<Duration>, PawxError> {
+    match value {
+        Some(Value::Number(n)) if *n > 0.0 => Ok(Some(Duration::from_millis(*n as u64))),
+        Some(Value::Number(_)) | Some(Value::Null) | None => Ok(None),
+        _ => raise_error(format!("{} must be a number of milliseconds", what)),
+    }
+}
+
+/// Reads `Http.client(options)` settings.
+fn parse_client_config(options: Option<&Value>) -> Result<ClientConfig, PawxError> {
+    let mut config = ClientConfig::default();
+
+    let fields = match options {
+        Some(Value::Object { fields }) => fields.borrow().clone(),
+        Some(Value::Null) | None => return Ok(config),
+        _ => return raise_error("Http.client(options?) expects an object"),
+    };
+
+    if let Some(v) = fields.get("maxConnections") {
+        match v {
+            Value::Number(n) if *n >= 1.0 => config.pool.max_connections = *n as usize,
+            _ => return raise_error("Http.client: maxConnections must be a positive number"),
+        }
+    }
+
+    if let Some(v) = fields.get("keepAlive") {
+        config.pool.keep_alive = v.is_truthy();
+    }
+
+    if fields.contains_key("timeout") {
+        config.pool.timeout = number_ms(fields.get("timeout"), "Http.client: timeout")?;
+    }
+
+    match fields.get("proxy") {
+        None => {}
+        Some(Value::String(url)) => match parse_url(url) {
+            Ok(proxy) => config.proxy = ProxySetting::Manual(proxy),
+            Err(e) => return raise_error(format!("Http.client: invalid proxy: {}", e)),
+        },
+        Some(Value::Bool(false)) | Some(Value::Null) => config.proxy = ProxySetting::Disabled,
+        Some(_) => return raise_error("Http.client: proxy must be a URL string or false"),
+    }
+
+    if let Some(v) = fields.get("followRedirects") {
+        config.follow_redirects = v.is_truthy();
+    }
+
+    if let Some(v) = fields.get("maxRedirects") {
+        match v {
+            Value::Number(n) if *n >= 0.0 => config.max_redirects = *n as usize,
+            _ => return raise_error("Http.client: maxRedirects must be a non-negative number"),
+        }
+    }
+
+    if let Some(v) = fields.get("cookies") {
+        config.cookies = v.is_truthy();
+    }
+
+    match fields.get("cache") {
+        None | Some(Value::Null) | Some(Value::Bool(false)) => {}
+        Some(Value::String(dir)) => config.cache = Some(HttpCache::new(dir.to_string())),
+        Some(_) => return raise_error("Http.client: cache must be a directory path"),
+    }
+
+    Ok(config)
+}
+
+/// Builds an [`HttpRequest`] from a URL string or an options object:
+/// `{ method, url, headers, body, multipart, timeout }`.
+///
+/// Non-string bodies are sent as JSON; `multipart` builds a
+/// multipart/form-data body instead (see multipart.rs).
+pub fn parse_request(value: &Value) -> Result<HttpRequest, String> {
+    let fields = match value {
+        Value::String(url) => {
+            return Ok(HttpRequest {
+                method: "GET".to_string(),
+                url: parse_url(url)?,
+                headers: Vec::new(),
+                body: None,
+                multipart: None,
+                timeout: None,
+            })
+        }
+        Value::Object { fields } => fields.borrow().clone(),
+        _ => return Err("Http: request must be a URL string or an options object".to_string()),
+    };
+
+    let url = match fields.get("url") {
+        Some(Value::String(u)) => parse_url(u)?,
+        _ => return Err("Http: request options require a `url` string".to_string()),
+    };
+
+    let method = match fields.get("method") {
+        Some(Value::String(m)) => m.to_ascii_uppercase(),
+        _ => "GET".to_string(),
+    };
+
+    let mut headers = Vec::new();
+    if let Some(Value::Object { fields: h }) = fields.get("headers") {
+        for (k, v) in h.borrow().iter() {
+            headers.push((k.clone(), v.to_pawx_string()));
+        }
+    }
+
+    let body = match fields.get("body") {
+        None | Some(Value::Null) => None,
+        Some(Value::String(s)) => Some(s.to_string()),
+        Some(other) => {
+            if !headers.iter().any(|(k, _)| k.eq_ignore_ascii_case("Content-Type")) {
+                headers.push(("Content-Type".to_string(), "application/json".to_string()));
+            }
+            Some(value_to_json(other)?)
+        }
+    };
+
+    let multipart = match fields.get("multipart") {
+        None | Some(Value::Null) => None,
+        Some(_) if body.is_some() => {
+            return Err("Http: a request cannot have both `body` and `multipart`".to_string())
+        }
+        Some(value) => {
+            let multipart = Multipart::from_value(value)?;
+            headers.retain(|(k, _)| !k.eq_ignore_ascii_case("Content-Type"));
+            headers.push(("Content-Type".to_string(), multipart.content_type()));
+            Some(multipart)
+        }
+    };
+
+    let timeout = number_ms(fields.get("timeout"), "Http: request timeout").map_err(|e| e.message)?;
+
+    Ok(HttpRequest {
+        method,
+        url,
+        headers,
+        body,
+        multipart,
+        timeout,
+    })
+}
+
+/// Converts a response into a PAWX object:
+/// `{ status, statusText, ok, headers, body, url, redirected, json() }`.
+///
+/// Header names are lower-cased; repeated headers keep the last value.
+/// `json()` parses the body and fails if it is not valid JSON.
+pub fn response_to_value(res: HttpResponse) -> Value {
+    let mut headers = HashMap::new();
+    for (k, v) in &res.headers {
+        headers.insert(k.to_ascii_lowercase(), Value::String(v.as_str().into()));
+    }
+
+    let mut fields = HashMap::new();
+    fields.insert("status".to_string(), Value::Number(res.status as f64));
+    fields.insert("statusText".to_string(), Value::String(res.status_text.into()));
+    fields.insert("ok".to_string(), Value::Bool((200..300).contains(&res.status)));
+    fields.insert("headers".to_string(), object(headers));
+    let body = res.body;
+    fields.insert("body".to_string(), Value::String(body.as_str().into()));
+    fields.insert(
+        "json".to_string(),
+        Value::NativeFunction(Arc::new(move |_args| {
+            parse_text(&[Value::String(body.as_str().into())], "res.json()")
+        })),
+    );
+    fields.insert("url".to_string(), Value::String(res.url.into()));
+    fields.insert("redirected".to_string(), Value::Bool(res.redirects > 0));
+
+    object(fields)
+}
+
+fn to_furure(result: Result<Value, String>) -> Value {
+    match result {
+        Ok(v) => Value::Furure(Box::new(v)),
+        Err(message) => Value::Furure(Box::new(Value::Error { message })),
+    }
+}
+
+/// Creates the PAWX object for a pooled client.
+///
+/// # Installed Functions
+/// - `client.request(urlOrOptions, token?)` → Furure(response)
+/// - `client.pipeline([req, ...], token?)`  → Furure(array<response>)
+/// - `client.stats()` → `{ created, reused, requests, idle }`
+/// - `client.close()` → drops idle connections
+/// - `client.cookies()` → array of `{ name, value, domain, path }`
+/// - `client.clearCookies()` → empties the cookie jar
+/// - `client.graphql(url, query, variables?, options?)` → see `Http.graphql`
+pub fn create_http_client_value(config: ClientConfig) -> Value {
+    let client = HttpClient::new(config);
+    let mut fields = HashMap::new();
+
+    let c = client.clone();
+    fields.insert(
+        "request".to_string(),
+        cancellable(Arc::new(move |args: Vec<Value>| -> Result<Value, PawxError> {
+            let req = args
+                .first()
+                .ok_or_else(|| "client.request(urlOrOptions): missing request".to_string())
+                .and_then(parse_request);
+
+            Ok(to_furure(req.and_then(|r| c.send(&r)).map(response_to_value)))
+        })),
+    );
+
+    let c = client.clone();
+    fields.insert(
+        "pipeline".to_string(),
+        cancellable(Arc::new(move |args: Vec<Value>| -> Result<Value, PawxError> {
+            let reqs = match args.first() {
+                Some(Value::Array { values, .. }) => values
+                    .borrow()
+                    .iter()
+                    .map(parse_request)
+                    .collect::<Result<Vec<_>, _>>(),
+                _ => Err("client.pipeline(requests): expected an array".to_string()),
+            };
+
+            Ok(to_furure(reqs.and_then(|r| c.send_all(&r)).map(|responses| Value::Array {
+                values: Rc::new(RefCell::new(
+                    responses.into_iter().map(response_to_value).collect(),
+                )),
+                proto: create_array_proto(),
+            })))
+        })),
+    );
+
+    let c = client.clone();
+    fields.insert(
+        "stats".to_string(),
+        Value::NativeFunction(Arc::new(move |_args| {
+            let (stats, idle) = c.stats();
+            let mut out = HashMap::new();
+            out.insert("created".to_string(), Value::Number(stats.created as f64));
+            out.insert("reused".to_string(), Value::Number(stats.reused as f64));
+            out.insert("requests".to_string(), Value::Number(stats.requests as f64));
+            out.insert("idle".to_string(), Value::Number(idle as f64));
+            Ok(object(out))
+        })),
+    );
+
+    let c = client.clone();
+    fields.insert(
+        "close".to_string(),
+        Value::NativeFunction(Arc::new(move |_args| {
+            c.close();
+            Ok(Value::Null)
+        })),
+    );
+
+    let c = client.clone();
+    fields.insert(
+        "cookies".to_string(),
+        Value::NativeFunction(Arc::new(move |_args| {
+            let cookies = c
+                .cookies()
+                .into_iter()
+                .map(|(name, value, domain, path)| {
+                    let mut out = HashMap::new();
+                    out.insert("name".to_string(), Value::String(name.into()));
+                    out.insert("value".to_string(), Value::String(value.into()));
+                    out.insert("domain".to_string(), Value::String(domain.into()));
+                    out.insert("path".to_string(), Value::String(path.into()));
+                    object(out)
+                })
+                .collect();
+
+            Ok(Value::Array {
+                values: Rc::new(RefCell::new(cookies)),
+                proto: create_array_proto(),
+            })
+        })),
+    );
+
+    let c = client.clone();
+    fields.insert(
+        "clearCookies".to_string(),
+        Value::NativeFunction(Arc::new(move |_args| {
+            c.clear_cookies();
+            Ok(Value::Null)
+        })),
+    );
+
+    let c = client;
+    fields.insert(
+        "graphql".to_string(),
+        cancellable(Arc::new(move |args: Vec<Value>| -> Result<Value, PawxError> {
+            Ok(crate::prototypes::graphql::graphql_request(&c, args))
+        })),
+    );
+
+    object(fields)
+}
+
+/// Sends `options` as a one-off request on the default client.
+fn send_once(options: HashMap<String, Value>) -> Value {
+    let req = parse_request(&object(options));
+    to_furure(req.and_then(|r| default_client().send(&r)).map(response_to_value))
+}
+
+/// The URL argument of a one-off request.
+fn url_arg(args: &[Value], usage: &str) -> Result<Value, PawxError> {
+    match args.first() {
+        Some(url @ Value::String(_)) => Ok(url.clone()),
+        _ => raise_error(format!("{} expects a URL string", usage)),
+    }
+}
+
+/// The fields of an optional options object.
+fn options_arg(value: Option<&Value>, usage: &str) -> Result<HashMap<String, Value>, PawxError> {
+    match value {
+        None | Some(Value::Null) => Ok(HashMap::new()),
+        Some(Value::Object { fields }) => Ok(fields.borrow().clone()),
+        Some(other) => raise_error(format!("{} expects options to be an object, got {}", usage, other.type_name())),
+    }
+}
+
+/// Whether `Http.post`'s second argument is the older options object
+/// (`{ body }` or `{ multipart }`, plus `headers` / `timeout`) rather
+/// than the body itself.
+fn is_post_options(value: Option<&Value>) -> bool {
+    let Some(Value::Object { fields }) = value else {
+        return false;
+    };
+    let fields = fields.borrow();
+
+    (fields.contains_key("body") || fields.contains_key("multipart"))
+        && fields
+            .keys()
+            .all(|k| matches!(k.as_str(), "body" | "multipart" | "headers" | "timeout"))
+}
+
+/// Native implementation of `Http.get(url, options?)`: a one-off `GET`
+/// on the default client. `options` takes `headers` and `timeout`, as in
+/// `client.request`.
+///
+/// # PAWX Example
+/// ```pawx
+/// Http.get("http://localhost:8080/cats").then(res -> {
+///     meow(res.status, res.json());
+/// });
+/// ```
+pub fn http_get(args: Vec<Value>) -> Result<Value, PawxError> {
+    let url = url_arg(&args, "Http.get(url, options?)")?;
+    let mut options = options_arg(args.get(1), "Http.get(url, options?)")?;
+
+    options.insert("url".to_string(), url);
+    options.insert("method".to_string(), Value::String("GET".into()));
+    Ok(send_once(options))
+}
+
+/// Native implementation of `Http.post(url, body?, headers?)`: a one-off
+/// `POST` on the default client.
+///
+/// A string body is sent as is; anything else as JSON. The older form
+/// `Http.post(url, { body | multipart, headers?, timeout? })` still works.
+///
+/// # PAWX Example
+/// ```pawx
+/// Http.post("http://localhost:8080/cats", { name: "Whiskers" }, { "X-Owner": "sam" })
+///     .then(res -> { meow(res.status) });
+///
+/// Http.post("http://localhost:8080/upload", {
+///     multipart: { title: "Whiskers", photo: Fs.stream("cat.png") }
+/// });
+/// ```
+pub fn http_post(args: Vec<Value>) -> Result<Value, PawxError> {
+    let usage = "Http.post(url, body?, headers?)";
+    let url = url_arg(&args, usage)?;
+
+    let mut options = if is_post_options(args.get(1)) {
+        options_arg(args.get(1), usage)?
+    } else {
+        let mut options = HashMap::new();
+        if let Some(body) = args.get(1) {
+            options.insert("body".to_string(), body.clone());
+        }
+        if let Some(headers) = args.get(2) {
+            options.insert("headers".to_string(), headers.clone());
+        }
+        options
+    };
+
+    options.insert("url".to_string(), url);
+    options.insert("method".to_string(), Value::String("POST".into()));
+    Ok(send_once(options))
+}
+
+/// Native implementation of `Http.request(urlOrOptions)`: any one-off
+/// request on the default client, with the options of `client.request`
+/// (`method`, `url`, `headers`, `body`, `multipart`, `timeout`).
+///
+/// # PAWX Example
+/// ```pawx
+/// Http.request({ method: "DELETE", url: "http://localhost:8080/cats/1" })
+///     .then(res -> { meow(res.ok) });
+/// ```
+pub fn http_request(args: Vec<Value>) -> Result<Value, PawxError> {
+    let req = args
+        .first()
+        .ok_or_else(|| "Http.request(urlOrOptions): missing request".to_string())
+        .and_then(parse_request);
+
+    Ok(to_furure(req.and_then(|r| default_client().send(&r)).map(response_to_value)))
+}
+
+/// Native implementation of `Http.client(options?)`.
+///
+/// # Options
+/// - `maxConnections` → idle connections kept per host (default 6)
+/// - `keepAlive`      → reuse connections (default true)
+/// - `timeout`        → default request timeout in ms (default 30000)
+/// - `proxy`          → `"http://host:port"`, or `false` to ignore the
+///   environment (default: read `HTTP_PROXY`)
+/// - `followRedirects` → follow 3xx responses (default true)
+/// - `maxRedirects`   → redirect hop limit (default 10)
+/// - `cookies`        → keep a cookie jar (default true)
+/// - `cache`          → directory for an on-disk response cache (default none)
+///
+/// # PAWX Example
+/// ```pawx
+/// snuggle api = Http.client({ maxConnections: 4, timeout: 5000 });
+/// api.request("http://localhost:8080/users").then(res -> { meow(res.status) });
+/// ```
+pub fn http_client(args: Vec<Value>) -> Result<Value, PawxError> {
+    Ok(create_http_client_value(parse_client_config(args.first())?))
+}
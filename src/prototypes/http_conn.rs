@@ -0,0 +1,530 @@
+/*
+ * ============================================================================
+ * PAWX - Code with Claws! 🐾
+ * ============================================================================
+ *
+ * Connection Workers for the PAWX HTTP Server
+ *
+ * PAWX values are `Rc`-based and stay on the interpreter thread, so a
+ * server's handlers all run there, one request at a time. Everything
+ * else a connection needs happens on other threads:
+ *
+ *   - an acceptor thread takes connections off the listener
+ *   - a pool of [`WORKERS`] threads reads each request and, once the
+ *     interpreter has an answer, writes the response
+ *   - the interpreter thread receives finished requests as [`Job`]s,
+ *     runs the handler and hands the response bytes back
+ *
+ * A client that is slow to send its request, or slow to read the
+ * response, ties up one worker instead of the whole server. Only the
+ * handler itself (and proxied requests, which stream through the
+ * interpreter thread) still run one after another.
+ *
+ * Workers read whole requests, however they arrive:
+ *   - a `Content-Length` body is read to its end (up to [`MAX_BODY`])
+ *   - a chunked body is decoded, and handed on with a `Content-Length`
+ *   - `Expect: 100-continue` is answered before the body is read
+ *   - bytes past the end of a request are kept for the next one
+ *
+ * Connections are kept alive between requests unless the client asks
+ * otherwise or `server.keepAlive(false)` is set; an idle connection is
+ * closed after `server.keepAlive(idleMs)` (5 seconds by default). Every
+ * response says which it is with a `Connection` header. Bodies under a
+ * `server.proxy` prefix are left on the connection for the proxy to
+ * stream, and that connection closes after the response.
+ *
+ * --------------------------------------------------------------------------
+ * Author:   Sam Wilcox
+ * Email:    sam@pawx-lang.com
+ * Website:  https://www.pawx-lang.com
+ * GitHub:   https://github.com/samwilcox/pawx
+ *
+ * License:
+ * This file is part of the PAWX programming language project.
+ *
+ * PAWX is dual-licensed under the terms of:
+ *   - The MIT License
+ *   - The Apache License, Version 2.0
+ *
+ * You may choose either license to govern your use of this software.
+ * Full license text available at:
+ *     https://license.pawx-lang.com
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under these licenses is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *
+ * ==========================================================================
+ */
+
+use std::io::{self, Read, Write};
+use std::net::{IpAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crate::prototypes::{http, http_lifecycle, http_proxy};
+use crate::value::Value;
+
+/// Connections read and written at the same time.
+pub const WORKERS: usize = 16;
+
+/// How long a client may take to send its request.
+const READ_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How long an idle connection stays open by default.
+const DEFAULT_IDLE: Duration = Duration::from_secs(5);
+
+/// The largest request head accepted.
+const MAX_HEAD: usize = 64 * 1024;
+
+/// The largest request body accepted.
+pub const MAX_BODY: usize = 16 * 1024 * 1024;
+
+/// Whether (and for how long) a server keeps idle connections open.
+#[derive(Clone, Copy)]
+pub struct KeepAlive {
+    /// `None` closes every connection after one response.
+    idle: Option<Duration>,
+}
+
+impl Default for KeepAlive {
+    fn default() -> Self {
+        KeepAlive { idle: Some(DEFAULT_IDLE) }
+    }
+}
+
+impl KeepAlive {
+    /// Reads the arguments of `server.keepAlive(idleMs | false)`.
+    ///
+    /// # Errors
+    /// If the argument is not a positive number or a boolean.
+    pub fn from_args(args: &[Value]) -> Result<Self, String> {
+        let idle = match args.first() {
+            Some(Value::Bool(false)) => None,
+            None | Some(Value::Bool(true)) => Some(DEFAULT_IDLE),
+            Some(Value::Number(ms)) if *ms > 0.0 => Some(Duration::from_millis(*ms as u64)),
+            _ => return Err("server.keepAlive(idleMs) expects a positive number of milliseconds or false".to_string()),
+        };
+        Ok(KeepAlive { idle })
+    }
+}
+
+/// What the interpreter thread decided for a request.
+pub enum Outcome {
+    /// Write these bytes; the connection may then carry another request.
+    Send(Vec<u8>),
+    /// The response was already written (proxied requests).
+    Done,
+}
+
+/// A request read by a worker, waiting for the interpreter thread.
+pub struct Job {
+    /// The request head and its whole (decoded) body.
+    pub raw: Vec<u8>,
+    pub peer_ip: Option<IpAddr>,
+    /// The client connection, for answers written as they are produced.
+    pub stream: TcpStream,
+    reply: Sender<Outcome>,
+}
+
+impl Job {
+    /// Hands the outcome back to the worker holding the connection.
+    pub fn answer(self, outcome: Outcome) {
+        let _ = self.reply.send(outcome);
+    }
+}
+
+/// What a worker needs to know about the server it reads for.
+struct Settings {
+    keep_alive: KeepAlive,
+    /// Path prefixes whose bodies the proxy streams itself.
+    streamed: Vec<String>,
+    stop: Arc<AtomicBool>,
+}
+
+/// The acceptor and workers of one listening server.
+pub struct Connections {
+    jobs: Receiver<Job>,
+    stop: Arc<AtomicBool>,
+    acceptor: Option<JoinHandle<()>>,
+    port: u16,
+}
+
+impl Connections {
+    /// Starts accepting on `listener` (bound to `port`). Bodies of requests
+    /// under one of `streamed` are left unread, for the proxy.
+    pub fn start(listener: &TcpListener, port: u16, keep_alive: KeepAlive, streamed: Vec<String>) -> io::Result<Self> {
+        let listener = listener.try_clone()?;
+        let stop = Arc::new(AtomicBool::new(false));
+        let (jobs_tx, jobs) = mpsc::channel();
+        let (streams_tx, streams_rx) = mpsc::channel::<TcpStream>();
+        let streams_rx = Arc::new(Mutex::new(streams_rx));
+        let settings = Arc::new(Settings {
+            keep_alive,
+            streamed,
+            stop: stop.clone(),
+        });
+
+        for _ in 0..WORKERS {
+            let streams = streams_rx.clone();
+            let jobs = jobs_tx.clone();
+            let settings = settings.clone();
+            thread::spawn(move || loop {
+                // The lock is only held while waiting for the next stream
+                let next = streams.lock().map(|rx| rx.recv());
+                match next {
+                    Ok(Ok(stream)) => serve_connection(stream, &jobs, &settings),
+                    _ => break,
+                }
+            });
+        }
+        let stopping = stop.clone();
+        let acceptor = thread::spawn(move || {
+            for stream in listener.incoming() {
+                if stopping.load(Ordering::SeqCst) {
+                    if let Ok(stream) = stream {
+                        http_lifecycle::refuse(stream);
+                    }
+                    break;
+                }
+                if let Ok(stream) = stream {
+                    if streams_tx.send(stream).is_err() {
+                        break;
+                    }
+                }
+            }
+        });
+
+        Ok(Connections {
+            jobs,
+            stop,
+            acceptor: Some(acceptor),
+            port,
+        })
+    }
+
+    /// The next request, or `None` if none arrives within `timeout`.
+    pub fn next(&self, timeout: Duration) -> Option<Job> {
+        self.jobs.recv_timeout(timeout).ok()
+    }
+
+    /// Stops accepting and answers every request already read with `503`.
+    ///
+    /// Connections the acceptor has not taken yet stay on the listener,
+    /// for [`http_lifecycle::Lifecycle::refuse_waiting`].
+    pub fn stop(mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+
+        // A blocked accept only notices the flag when something connects
+        let _ = TcpStream::connect(("127.0.0.1", self.port));
+        if let Some(acceptor) = self.acceptor.take() {
+            let _ = acceptor.join();
+        }
+
+        while let Ok(job) = self.jobs.try_recv() {
+            job.answer(Outcome::Send(http_lifecycle::refusal()));
+        }
+    }
+}
+
+/// Why no request could be read.
+enum ReadError {
+    /// The client went away or stayed silent; close without answering.
+    Closed,
+    /// The request cannot be served; answer with this status and close.
+    Reject(u16, &'static str),
+}
+
+/// A request read off a connection.
+struct Request {
+    raw: Vec<u8>,
+    /// Whether the client will take another response on this connection.
+    keep_alive: bool,
+}
+
+/// Serves requests from `stream` until either side closes it.
+fn serve_connection(mut stream: TcpStream, jobs: &Sender<Job>, settings: &Settings) {
+    let peer_ip = stream.peer_addr().ok().map(|a| a.ip());
+
+    // Bytes read past the end of the previous request
+    let mut pending = Vec::new();
+    let mut first = true;
+
+    loop {
+        // A kept-alive connection waits for its next request only so long
+        let wait = if first { Some(READ_TIMEOUT) } else { settings.keep_alive.idle };
+        let _ = stream.set_read_timeout(wait);
+        first = false;
+
+        let request = match read_request(&mut stream, &mut pending, settings) {
+            Ok(request) => request,
+            Err(ReadError::Closed) => return,
+            Err(ReadError::Reject(status, message)) => {
+                let _ = stream.write_all(&error_response(status, message));
+                return;
+            }
+        };
+
+        let Ok(handle) = stream.try_clone() else {
+            return;
+        };
+
+        let (reply, outcome) = mpsc::channel();
+        let job = Job {
+            raw: request.raw,
+            peer_ip,
+            stream: handle,
+            reply,
+        };
+
+        let response = if jobs.send(job).is_err() {
+            // The server stopped while this request was being read
+            Some(http_lifecycle::refusal())
+        } else {
+            match outcome.recv() {
+                Ok(Outcome::Send(bytes)) => Some(bytes),
+                Ok(Outcome::Done) => None,
+                Err(_) => Some(http_lifecycle::refusal()),
+            }
+        };
+
+        // Proxied responses are written already and always close
+        let Some(response) = response else {
+            return;
+        };
+
+        let keep_alive = request.keep_alive && !settings.stop.load(Ordering::SeqCst);
+        let (response, keep_alive) = with_connection_header(response, keep_alive);
+
+        if stream.write_all(&response).and_then(|_| stream.flush()).is_err() || !keep_alive {
+            return;
+        }
+    }
+}
+
+/// Reads one whole request, starting with whatever is in `pending`, and
+/// leaves any bytes after it there.
+fn read_request(stream: &mut TcpStream, pending: &mut Vec<u8>, settings: &Settings) -> Result<Request, ReadError> {
+    let head_end = loop {
+        match find(pending, b"\r\n\r\n", 0) {
+            Some(at) if at <= MAX_HEAD => break at + 4,
+            None if pending.len() <= MAX_HEAD => fill(stream, pending)?,
+            _ => return Err(ReadError::Reject(431, "request head is too large")),
+        }
+    };
+
+    let head = String::from_utf8_lossy(&pending[..head_end]).into_owned();
+    let mut lines = head.lines();
+    let mut request_line = lines.next().unwrap_or("").split_whitespace();
+    let target = request_line.nth(1).unwrap_or("/");
+    let version = request_line.next().unwrap_or("HTTP/1.1");
+
+    let headers: Vec<(&str, &str)> = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(k, v)| (k.trim(), v.trim()))
+        .collect();
+    let header = |name: &str| headers.iter().find(|(k, _)| k.eq_ignore_ascii_case(name)).map(|(_, v)| *v);
+    let has_token = |name: &str, token: &str| {
+        header(name).is_some_and(|v| v.split(',').any(|t| t.trim().eq_ignore_ascii_case(token)))
+    };
+
+    // HTTP/1.1 keeps connections open unless told not to; HTTP/1.0 only if asked
+    let wanted = if version == "HTTP/1.0" {
+        has_token("Connection", "keep-alive")
+    } else {
+        !has_token("Connection", "close")
+    };
+    let keep_alive = settings.keep_alive.idle.is_some() && wanted;
+
+    // The proxy streams these bodies itself, straight from the connection
+    if settings.streamed.iter().any(|prefix| http_proxy::covers(prefix, target)) {
+        return Ok(Request {
+            raw: std::mem::take(pending),
+            keep_alive: false,
+        });
+    }
+
+    let chunked = has_token("Transfer-Encoding", "chunked");
+    let length = match header("Content-Length") {
+        _ if chunked => None,
+        None => None,
+        Some(v) => match v.parse::<usize>() {
+            Ok(n) if n > MAX_BODY => return Err(ReadError::Reject(413, "request body is too large")),
+            Ok(n) => Some(n),
+            Err(_) => return Err(ReadError::Reject(400, "invalid Content-Length")),
+        },
+    };
+
+    let has_body = chunked || length.is_some_and(|n| n > 0);
+    let complete = length.is_some_and(|n| pending.len() >= head_end + n);
+    if has_body && !complete && has_token("Expect", "100-continue") {
+        stream
+            .write_all(b"HTTP/1.1 100 Continue\r\n\r\n")
+            .map_err(|_| ReadError::Closed)?;
+    }
+
+    let raw = if chunked {
+        let (body, end) = read_chunked(stream, pending, head_end)?;
+        pending.drain(..end);
+
+        // Handed on as if it had been sent with a length
+        let mut raw = without_header(&head[..head_end - 2], "Transfer-Encoding");
+        raw.push_str(&format!("Content-Length: {}\r\n\r\n", body.len()));
+        let mut raw = raw.into_bytes();
+        raw.extend_from_slice(&body);
+        raw
+    } else {
+        let end = head_end + length.unwrap_or(0);
+        while pending.len() < end {
+            fill(stream, pending)?;
+        }
+        pending.drain(..end).collect()
+    };
+
+    Ok(Request { raw, keep_alive })
+}
+
+/// Decodes the chunked body that starts at `pending[start..]`, reading more
+/// as needed. Returns the body and where the request ends in `pending`.
+fn read_chunked(stream: &mut TcpStream, pending: &mut Vec<u8>, start: usize) -> Result<(Vec<u8>, usize), ReadError> {
+    let malformed = ReadError::Reject(400, "malformed chunked body");
+    let mut body = Vec::new();
+    let mut at = start;
+
+    loop {
+        let line_end = read_line(stream, pending, at)?;
+        let size_line = String::from_utf8_lossy(&pending[at..line_end]).into_owned();
+        let size_hex = size_line.split(';').next().unwrap_or("").trim();
+        let Ok(size) = usize::from_str_radix(size_hex, 16) else {
+            return Err(malformed);
+        };
+        at = line_end + 2;
+
+        if size == 0 {
+            // Trailers (ignored), then the blank line that ends the body
+            loop {
+                let line_end = read_line(stream, pending, at)?;
+                let blank = line_end == at;
+                at = line_end + 2;
+                if blank {
+                    return Ok((body, at));
+                }
+            }
+        }
+
+        if body.len() + size > MAX_BODY {
+            return Err(ReadError::Reject(413, "request body is too large"));
+        }
+        while pending.len() < at + size + 2 {
+            fill(stream, pending)?;
+        }
+        if &pending[at + size..at + size + 2] != b"\r\n" {
+            return Err(malformed);
+        }
+        body.extend_from_slice(&pending[at..at + size]);
+        at += size + 2;
+    }
+}
+
+/// Where the line starting at `pending[from..]` ends (its CRLF), reading
+/// more as needed.
+fn read_line(stream: &mut TcpStream, pending: &mut Vec<u8>, from: usize) -> Result<usize, ReadError> {
+    loop {
+        if let Some(at) = find(pending, b"\r\n", from) {
+            return Ok(at);
+        }
+        if pending.len() - from > MAX_HEAD {
+            return Err(ReadError::Reject(400, "malformed chunked body"));
+        }
+        fill(stream, pending)?;
+    }
+}
+
+/// Reads what is available from `stream` onto the end of `pending`.
+fn fill(stream: &mut TcpStream, pending: &mut Vec<u8>) -> Result<(), ReadError> {
+    let mut buffer = [0u8; 8192];
+    match stream.read(&mut buffer) {
+        Ok(n) if n > 0 => {
+            pending.extend_from_slice(&buffer[..n]);
+            // Once a request has started, it gets the full read timeout
+            let _ = stream.set_read_timeout(Some(READ_TIMEOUT));
+            Ok(())
+        }
+        _ => Err(ReadError::Closed),
+    }
+}
+
+fn find(haystack: &[u8], needle: &[u8], from: usize) -> Option<usize> {
+    haystack
+        .get(from..)?
+        .windows(needle.len())
+        .position(|w| w == needle)
+        .map(|at| from + at)
+}
+
+/// `head` (ending in one CRLF) without any `name:` header lines.
+fn without_header(head: &str, name: &str) -> String {
+    head.split_inclusive("\r\n")
+        .filter(|line| {
+            !line
+                .split_once(':')
+                .is_some_and(|(k, _)| k.trim().eq_ignore_ascii_case(name))
+        })
+        .collect()
+}
+
+/// Adds a `Connection` header to `response` unless it has one, and says
+/// whether the connection can stay open after it.
+///
+/// A response that asks to close, or whose end the client could only find
+/// by the connection closing, closes it.
+fn with_connection_header(response: Vec<u8>, keep_alive: bool) -> (Vec<u8>, bool) {
+    let Some(head_end) = find(&response, b"\r\n\r\n", 0) else {
+        return (response, false);
+    };
+    let head = String::from_utf8_lossy(&response[..head_end]).into_owned();
+
+    let status = head.split_whitespace().nth(1).and_then(|s| s.parse::<u16>().ok()).unwrap_or(0);
+    let mut connection = None;
+    let mut has_length = matches!(status, 204 | 304);
+    for (name, value) in head.lines().skip(1).filter_map(|line| line.split_once(':')) {
+        if name.trim().eq_ignore_ascii_case("Connection") {
+            connection = Some(value.trim().to_ascii_lowercase());
+        }
+        has_length |= name.trim().eq_ignore_ascii_case("Content-Length");
+    }
+
+    match connection {
+        Some(value) => {
+            let keep_alive = keep_alive && has_length && !value.contains("close");
+            (response, keep_alive)
+        }
+        None => {
+            let keep_alive = keep_alive && has_length;
+            let header = if keep_alive { "Connection: keep-alive" } else { "Connection: close" };
+
+            let mut out = Vec::with_capacity(response.len() + header.len() + 2);
+            out.extend_from_slice(&response[..head_end]);
+            out.extend_from_slice(b"\r\n");
+            out.extend_from_slice(header.as_bytes());
+            out.extend_from_slice(&response[head_end..]);
+            (out, keep_alive)
+        }
+    }
+}
+
+/// A JSON error for a request that could not be read.
+fn error_response(status: u16, message: &str) -> Vec<u8> {
+    let body = serde_json::json!({ "error": http::reason(status), "message": message }).to_string();
+    format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        http::reason(status),
+        body.len(),
+        body
+    )
+    .into_bytes()
+}
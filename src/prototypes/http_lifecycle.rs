@@ -0,0 +1,286 @@
+/*
+ * ============================================================================
+ * PAWX - Code with Claws! 🐾
+ * ============================================================================
+ *
+ * Health Checks and Graceful Draining for the PAWX HTTP Server
+ *
+ * Supports:
+ *   - server.health(path, check?)   answer load-balancer health checks
+ *   - server.drain(timeoutMs?)      stop taking requests and let listen() return
+ *   - SIGTERM / SIGINT              start a drain while a server is listening
+ *
+ * A health check is answered without calling the server's handler. With
+ * no `check` it is always `200 {"status":"ok"}`. Otherwise `check()` runs
+ * on each request: `true` (or `null`) is healthy and `false` is not; an
+ * object is sent as the body and is healthy unless its `ok` is `false`.
+ * Unhealthy, throwing and draining servers answer `503`.
+ *
+ * Draining lets the request in flight finish, answers every connection
+ * already waiting with `503 Service Unavailable` and `Connection: close`,
+ * then closes the listener so `server.listen()` returns and the script
+ * can clean up. Connections still arriving after `timeoutMs` are left to
+ * the operating system.
+ *
+ * Container runtimes stop a service with SIGTERM, so while a server is
+ * listening the first SIGTERM or SIGINT starts a drain; a second one
+ * exits at once.
+ *
+ * --------------------------------------------------------------------------
+ * Author:   Sam Wilcox
+ * Email:    sam@pawx-lang.com
+ * Website:  https://www.pawx-lang.com
+ * GitHub:   https://github.com/samwilcox/pawx
+ *
+ * License:
+ * This file is part of the PAWX programming language project.
+ *
+ * PAWX is dual-licensed under the terms of:
+ *   - The MIT License
+ *   - The Apache License, Version 2.0
+ *
+ * You may choose either license to govern your use of this software.
+ * Full license text available at:
+ *     https://license.pawx-lang.com
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under these licenses is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *
+ * ==========================================================================
+ */
+
+use std::cell::Cell;
+use std::io::{self, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::error::PawxError;
+use crate::interpreter::calls::raise_error;
+use crate::prototypes::http::value_to_json_http;
+use crate::value::{NativeFn, Value};
+
+/// How long a drain waits when no timeout is given (or on a signal).
+pub const DEFAULT_DRAIN_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How often a listening server looks for a signal.
+pub const SIGNAL_POLL: Duration = Duration::from_millis(100);
+
+/* ============================================================================
+ * HEALTH CHECKS
+ * ============================================================================
+ */
+
+/// A path answered with the server's health.
+#[derive(Clone)]
+pub struct HealthCheck {
+    path: String,
+    check: Option<Arc<NativeFn>>,
+}
+
+impl HealthCheck {
+    /// Reads the arguments of `server.health(path, check?)`.
+    ///
+    /// # Errors
+    /// If an argument has the wrong type.
+    pub fn from_args(args: &[Value]) -> Result<Self, PawxError> {
+        let path = match args.first() {
+            Some(Value::String(path)) => format!("/{}", path.trim_matches('/')),
+            _ => return raise_error("server.health(path, check?) expects a path"),
+        };
+
+        let check = match args.get(1) {
+            None | Some(Value::Null) => None,
+            Some(Value::NativeFunction(f)) => Some(f.clone()),
+            Some(other) => return raise_error(format!("server.health(path, check?) expects check to be a function, got {}", other.type_name())),
+        };
+
+        Ok(HealthCheck { path, check })
+    }
+
+    /// Runs the check: whether the server is healthy, and the body to send.
+    fn run(&self) -> (bool, serde_json::Value) {
+        let Some(check) = &self.check else {
+            return (true, serde_json::json!({ "status": "ok" }));
+        };
+
+        let report = match check(Vec::new()) {
+            Ok(Value::Bool(false)) => return (false, serde_json::json!({ "status": "unhealthy" })),
+            Ok(Value::Bool(true) | Value::Null) => return (true, serde_json::json!({ "status": "ok" })),
+            Ok(Value::Error { message }) | Err(crate::error::PawxError { message, .. }) => Err(message),
+            Ok(report) => Ok(report),
+        };
+
+        let healthy = match &report {
+            Ok(Value::Object { fields }) => !matches!(fields.borrow().get("ok"), Some(Value::Bool(false))),
+            _ => true,
+        };
+
+        match report.and_then(|report| value_to_json_http(&report)) {
+            Ok(body) => (healthy, body),
+            Err(message) => (false, serde_json::json!({ "status": "unhealthy", "error": message })),
+        }
+    }
+}
+
+fn json_response(status: u16, body: &serde_json::Value, extra_headers: &str) -> Vec<u8> {
+    let reason = match status {
+        200 => "OK",
+        _ => "Service Unavailable",
+    };
+    let body = body.to_string();
+
+    format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nCache-Control: no-store\r\n{}\r\n{}",
+        status,
+        reason,
+        body.len(),
+        extra_headers,
+        body
+    )
+    .into_bytes()
+}
+
+/// Answers `raw` if it asks for `health`'s path.
+pub fn answer_health(health: Option<&HealthCheck>, lifecycle: &Lifecycle, raw: &str) -> Option<Vec<u8>> {
+    let health = health?;
+    let target = raw.lines().next()?.split_whitespace().nth(1)?;
+    if target.split('?').next() != Some(health.path.as_str()) {
+        return None;
+    }
+
+    if lifecycle.is_draining() {
+        return Some(json_response(503, &serde_json::json!({ "status": "draining" }), "Connection: close\r\n"));
+    }
+
+    let (healthy, body) = health.run();
+    Some(json_response(if healthy { 200 } else { 503 }, &body, ""))
+}
+
+/* ============================================================================
+ * DRAINING
+ * ============================================================================
+ */
+
+/// Whether a listening server has been asked to stop.
+#[derive(Default)]
+pub struct Lifecycle {
+    /// When a drain gives up waiting, once one has started.
+    drain_deadline: Cell<Option<Instant>>,
+}
+
+impl Lifecycle {
+    /// Starts draining; a drain already under way keeps its deadline.
+    pub fn drain(&self, timeout: Duration) {
+        if self.drain_deadline.get().is_none() {
+            self.drain_deadline.set(Some(Instant::now() + timeout));
+        }
+    }
+
+    pub fn is_draining(&self) -> bool {
+        self.drain_deadline.get().is_some()
+    }
+
+    /// Answers every connection waiting on `listener` with `503` until none
+    /// is left or the drain times out.
+    pub fn refuse_waiting(&self, listener: &TcpListener) {
+        let deadline = self.drain_deadline.get().unwrap_or_else(Instant::now);
+        if listener.set_nonblocking(true).is_err() {
+            return;
+        }
+
+        while Instant::now() < deadline {
+            match listener.accept() {
+                Ok((stream, _)) => refuse(stream),
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => break,
+                Err(_) => break,
+            }
+        }
+    }
+}
+
+/// The `503` sent to requests that arrive while the server drains.
+pub fn refusal() -> Vec<u8> {
+    let body = serde_json::json!({ "error": "Service Unavailable", "message": "server is shutting down" });
+    json_response(503, &body, "Connection: close\r\nRetry-After: 1\r\n")
+}
+
+/// Reads `stream`'s request and answers it with `503`.
+pub fn refuse(mut stream: TcpStream) {
+    let _ = stream.set_nonblocking(false);
+    let _ = stream.set_read_timeout(Some(Duration::from_millis(200)));
+
+    let mut buffer = [0u8; 8192];
+    let _ = stream.read(&mut buffer);
+
+    let _ = stream.write_all(&refusal());
+    let _ = stream.flush();
+}
+
+/// Reads the argument of `server.drain(timeoutMs?)`.
+///
+/// # Errors
+/// If the timeout is not a non-negative number.
+pub fn drain_timeout(args: &[Value]) -> Result<Duration, PawxError> {
+    match args.first() {
+        None | Some(Value::Null) => Ok(DEFAULT_DRAIN_TIMEOUT),
+        Some(Value::Number(ms)) if *ms >= 0.0 => Ok(Duration::from_millis(*ms as u64)),
+        Some(_) => raise_error("server.drain(timeoutMs?) expects a non-negative number of milliseconds"),
+    }
+}
+
+/* ============================================================================
+ * SIGNALS
+ * ============================================================================
+ */
+
+/// Set by the signal handler; read by [`take_signal`].
+static SIGNALLED: AtomicBool = AtomicBool::new(false);
+
+#[cfg(unix)]
+extern "C" fn on_signal(signal: libc::c_int) {
+    // A second signal while the first is still pending means "now"
+    if SIGNALLED.swap(true, Ordering::SeqCst) {
+        // SAFETY: `_exit` is async-signal-safe
+        unsafe { libc::_exit(128 + signal) };
+    }
+}
+
+/// Whether SIGTERM or SIGINT arrived since the last call.
+pub fn take_signal() -> bool {
+    SIGNALLED.swap(false, Ordering::SeqCst)
+}
+
+/// Catches SIGTERM and SIGINT while a server listens; the server polls
+/// [`take_signal`] between requests. Dropping the guard puts the default
+/// handlers back.
+pub struct SignalWatch(());
+
+impl SignalWatch {
+    pub fn start() -> Self {
+        SIGNALLED.store(false, Ordering::SeqCst);
+
+        #[cfg(unix)]
+        // SAFETY: the handler only touches an atomic and calls `_exit`
+        unsafe {
+            let handler = on_signal as extern "C" fn(libc::c_int) as libc::sighandler_t;
+            libc::signal(libc::SIGTERM, handler);
+            libc::signal(libc::SIGINT, handler);
+        }
+
+        SignalWatch(())
+    }
+}
+
+impl Drop for SignalWatch {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        // SAFETY: restores the default disposition
+        unsafe {
+            libc::signal(libc::SIGTERM, libc::SIG_DFL);
+            libc::signal(libc::SIGINT, libc::SIG_DFL);
+        }
+    }
+}
@@ -0,0 +1,449 @@
+/*
+ * ============================================================================
+ * PAWX - Code with Claws! 🐾
+ * ============================================================================
+ *
+ * Reverse Proxy for the PAWX HTTP Server
+ *
+ * Supports:
+ *   - server.proxy(prefix, url, { rewrite?, headers?, timeout? })
+ *   - X-Forwarded-For / -Host / -Proto on every forwarded request
+ *   - Request and response bodies streamed through, not buffered
+ *   - 502 Bad Gateway / 504 Gateway Timeout when the upstream fails
+ *
+ * Requests under `prefix` go to `url` instead of the server's handler,
+ * with their path unchanged unless `rewrite` says otherwise:
+ *
+ *   server.proxy("/api", "http://localhost:9000", {
+ *       rewrite: { "^/api": "" },             // regex → replacement
+ *       headers: { "X-Dev": "1", "Cookie": null }
+ *   });
+ *
+ * `rewrite` may also be a function from the request path (query string
+ * included) to the path to send. A `null` in `headers` removes a header.
+ *
+ * Each request gets a fresh upstream connection opened the way the HTTP
+ * client opens them, asked to close after one response, so whatever the
+ * upstream sends (fixed length, chunked or until close) can be copied to
+ * the client as it arrives. Hop-by-hop headers are not forwarded in
+ * either direction.
+ *
+ * --------------------------------------------------------------------------
+ * Author:   Sam Wilcox
+ * Email:    sam@pawx-lang.com
+ * Website:  https://www.pawx-lang.com
+ * GitHub:   https://github.com/samwilcox/pawx
+ *
+ * License:
+ * This file is part of the PAWX programming language project.
+ *
+ * PAWX is dual-licensed under the terms of:
+ *   - The MIT License
+ *   - The Apache License, Version 2.0
+ *
+ * You may choose either license to govern your use of this software.
+ * Full license text available at:
+ *     https://license.pawx-lang.com
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under these licenses is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *
+ * ==========================================================================
+ */
+
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{IpAddr, TcpStream};
+use std::sync::Arc;
+use std::time::Duration;
+
+use regex::Regex;
+
+use crate::error::PawxError;
+use crate::interpreter::calls::{builtin_error, raise_error};
+use crate::prototypes::http_client::{open_stream, parse_url, ParsedUrl};
+use crate::value::{NativeFn, Value};
+
+/// How long the upstream may take to connect or to send anything.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Headers that describe one connection rather than the message, and so
+/// are never forwarded (RFC 9110, section 7.6.1).
+const HOP_BY_HOP: [&str; 8] = [
+    "Connection",
+    "Keep-Alive",
+    "Proxy-Connection",
+    "Proxy-Authenticate",
+    "Proxy-Authorization",
+    "TE",
+    "Trailer",
+    "Upgrade",
+];
+
+/// How a request path is changed before it is forwarded.
+#[derive(Clone)]
+enum Rewrite {
+    None,
+    Patterns(Vec<(Regex, String)>),
+    Function(Arc<NativeFn>),
+}
+
+/// A URL prefix forwarded to another server.
+#[derive(Clone)]
+pub struct ProxyRoute {
+    prefix: String,
+    upstream: ParsedUrl,
+    rewrite: Rewrite,
+
+    /// Headers to set on forwarded requests; `None` removes one.
+    headers: Vec<(String, Option<String>)>,
+
+    timeout: Duration,
+}
+
+/// Why a request could not be forwarded, as the response to send instead.
+struct ProxyError {
+    status: u16,
+    message: String,
+}
+
+impl ProxyError {
+    fn bad_gateway(message: impl Into<String>) -> Self {
+        ProxyError {
+            status: 502,
+            message: message.into(),
+        }
+    }
+
+    /// 504 for timeouts, 502 for anything else.
+    fn from_io(e: io::Error, what: &str) -> Self {
+        match e.kind() {
+            io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut => ProxyError {
+                status: 504,
+                message: format!("upstream timed out {}", what),
+            },
+            _ => ProxyError::bad_gateway(format!("upstream failed {}: {}", what, e)),
+        }
+    }
+
+    fn to_response(&self) -> Vec<u8> {
+        let reason = match self.status {
+            400 => "Bad Request",
+            504 => "Gateway Timeout",
+            _ => "Bad Gateway",
+        };
+        let body = serde_json::json!({ "error": reason, "message": self.message }).to_string();
+
+        format!(
+            "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            self.status,
+            reason,
+            body.len(),
+            body
+        )
+        .into_bytes()
+    }
+}
+
+fn is_hop_by_hop(name: &str) -> bool {
+    HOP_BY_HOP.iter().any(|h| h.eq_ignore_ascii_case(name))
+}
+
+impl ProxyRoute {
+    /// Reads the arguments of `server.proxy(prefix, url, options?)`.
+    ///
+    /// # Errors
+    /// If an argument has the wrong type, or the URL or a rewrite pattern
+    /// is invalid.
+    pub fn from_args(args: &[Value]) -> Result<Self, PawxError> {
+        let (prefix, url) = match (args.first(), args.get(1)) {
+            (Some(Value::String(prefix)), Some(Value::String(url))) => (prefix.to_string(), url.to_string()),
+            _ => return raise_error("server.proxy(prefix, url, options?) expects a URL prefix and an upstream URL"),
+        };
+
+        let upstream = parse_url(&url).map_err(|e| builtin_error(format!("server.proxy: {}", e)))?;
+
+        let mut route = ProxyRoute {
+            prefix: format!("/{}", prefix.trim_matches('/')),
+            upstream,
+            rewrite: Rewrite::None,
+            headers: Vec::new(),
+            timeout: DEFAULT_TIMEOUT,
+        };
+
+        let fields = match args.get(2) {
+            None | Some(Value::Null) => return Ok(route),
+            Some(Value::Object { fields }) => fields.borrow().clone(),
+            Some(_) => return raise_error("server.proxy(prefix, url, options?) expects options to be an object"),
+        };
+
+        route.rewrite = match fields.get("rewrite") {
+            None | Some(Value::Null) => Rewrite::None,
+            Some(Value::NativeFunction(f)) => Rewrite::Function(f.clone()),
+            Some(Value::Object { fields }) => {
+                let mut patterns: Vec<(String, Value)> =
+                    fields.borrow().iter().map(|(k, v)| (k.clone(), v.clone())).collect();
+                patterns.sort_by(|a, b| a.0.cmp(&b.0));
+
+                Rewrite::Patterns(
+                    patterns
+                        .into_iter()
+                        .map(|(pattern, replacement)| {
+                            let regex = Regex::new(&pattern)
+                                .map_err(|e| builtin_error(format!("server.proxy: invalid rewrite pattern '{}': {}", pattern, e)))?;
+                            Ok((regex, replacement.to_pawx_string()))
+                        })
+                        .collect::<Result<_, PawxError>>()?,
+                )
+            }
+            Some(_) => return raise_error("server.proxy: rewrite must be a function or an object of patterns"),
+        };
+
+        route.headers = match fields.get("headers") {
+            None | Some(Value::Null) => Vec::new(),
+            Some(Value::Object { fields }) => fields
+                .borrow()
+                .iter()
+                .map(|(name, value)| match value {
+                    Value::Null => (name.clone(), None),
+                    other => (name.clone(), Some(other.to_pawx_string())),
+                })
+                .collect(),
+            Some(_) => return raise_error("server.proxy: headers must be an object"),
+        };
+
+        route.timeout = match fields.get("timeout") {
+            None | Some(Value::Null) => DEFAULT_TIMEOUT,
+            Some(Value::Number(ms)) if *ms > 0.0 => Duration::from_millis(*ms as u64),
+            Some(_) => return raise_error("server.proxy: timeout must be a positive number of milliseconds"),
+        };
+
+        Ok(route)
+    }
+
+    /// The path prefix this route forwards.
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    fn matches(&self, target: &str) -> bool {
+        covers(&self.prefix, target)
+    }
+
+    /// The request target to send upstream for `target`.
+    fn upstream_target(&self, target: &str) -> Result<String, ProxyError> {
+        let rewritten = match &self.rewrite {
+            Rewrite::None => target.to_string(),
+            Rewrite::Patterns(patterns) => patterns
+                .iter()
+                .fold(target.to_string(), |path, (regex, replacement)| {
+                    regex.replace(&path, replacement.as_str()).into_owned()
+                }),
+            Rewrite::Function(f) => match f(vec![Value::String(target.into())]) {
+                Ok(Value::String(path)) => path.to_string(),
+                Ok(other) => {
+                    return Err(ProxyError::bad_gateway(format!(
+                        "rewrite returned {} instead of a path",
+                        other.type_name()
+                    )))
+                }
+                Err(error) => return Err(ProxyError::bad_gateway(format!("rewrite failed: {}", error.message))),
+            },
+        };
+
+        // Under the upstream URL's own path, if it has one
+        let base = self.upstream.path.trim_end_matches('/');
+        Ok(format!("{}/{}", base, rewritten.strip_prefix('/').unwrap_or(&rewritten)))
+    }
+
+    /// Forwards `raw` (what has been read of the request so far) and
+    /// streams the upstream's response back to `client`.
+    fn relay(&self, raw: &[u8], client: &mut TcpStream, peer: Option<IpAddr>) -> Result<(), ProxyError> {
+        let bad_request = |message: &str| ProxyError {
+            status: 400,
+            message: message.to_string(),
+        };
+
+        let head_end = raw
+            .windows(4)
+            .position(|w| w == b"\r\n\r\n")
+            .ok_or_else(|| bad_request("request head is incomplete"))?;
+        let head = String::from_utf8_lossy(&raw[..head_end]);
+        let body_start = &raw[head_end + 4..];
+
+        let mut lines = head.lines();
+        let mut request_line = lines.next().unwrap_or("").split_whitespace();
+        let (Some(method), Some(target)) = (request_line.next(), request_line.next()) else {
+            return Err(bad_request("malformed request line"));
+        };
+
+        let headers: Vec<(&str, &str)> = lines
+            .filter_map(|line| line.split_once(':'))
+            .map(|(k, v)| (k.trim(), v.trim()))
+            .collect();
+        let header = |name: &str| headers.iter().find(|(k, _)| k.eq_ignore_ascii_case(name)).map(|(_, v)| *v);
+
+        // Headers named in `Connection` are hop-by-hop too
+        let connection_listed: Vec<String> = header("Connection")
+            .map(|v| v.split(',').map(|h| h.trim().to_ascii_lowercase()).collect())
+            .unwrap_or_default();
+
+        let mut out = format!("{} {} HTTP/1.1\r\n", method, self.upstream_target(target)?);
+        let host = match self.upstream.port {
+            80 => self.upstream.host.clone(),
+            port => format!("{}:{}", self.upstream.host, port),
+        };
+        out.push_str(&format!("Host: {}\r\n", host));
+
+        let overridden = |name: &str| self.headers.iter().any(|(h, _)| h.eq_ignore_ascii_case(name));
+        for (name, value) in &headers {
+            let lower = name.to_ascii_lowercase();
+            let forwarded = lower.starts_with("x-forwarded-");
+            if is_hop_by_hop(name) || connection_listed.contains(&lower) || lower == "host" || forwarded || overridden(name) {
+                continue;
+            }
+            out.push_str(&format!("{}: {}\r\n", name, value));
+        }
+
+        let forwarded_for = match (header("X-Forwarded-For"), peer) {
+            (Some(earlier), Some(ip)) => Some(format!("{}, {}", earlier, ip)),
+            (Some(earlier), None) => Some(earlier.to_string()),
+            (None, Some(ip)) => Some(ip.to_string()),
+            (None, None) => None,
+        };
+        if let Some(forwarded_for) = forwarded_for {
+            out.push_str(&format!("X-Forwarded-For: {}\r\n", forwarded_for));
+        }
+        if let Some(original_host) = header("X-Forwarded-Host").or(header("Host")) {
+            out.push_str(&format!("X-Forwarded-Host: {}\r\n", original_host));
+        }
+        out.push_str(&format!("X-Forwarded-Proto: {}\r\n", header("X-Forwarded-Proto").unwrap_or("http")));
+
+        for (name, value) in &self.headers {
+            if let Some(value) = value {
+                out.push_str(&format!("{}: {}\r\n", name, value));
+            }
+        }
+        out.push_str("Connection: close\r\n\r\n");
+
+        // Upstream
+        let upstream = open_stream(&self.upstream, Some(self.timeout)).map_err(ProxyError::bad_gateway)?;
+        upstream
+            .set_read_timeout(Some(self.timeout))
+            .and_then(|_| upstream.set_write_timeout(Some(self.timeout)))
+            .map_err(|e| ProxyError::from_io(e, "on connect"))?;
+        let mut upstream_writer = &upstream;
+
+        let sending = |e| ProxyError::from_io(e, "while receiving the request");
+        upstream_writer.write_all(out.as_bytes()).map_err(sending)?;
+        upstream_writer.write_all(body_start).map_err(sending)?;
+
+        // The rest of the body, straight from the client
+        let chunked = header("Transfer-Encoding").is_some_and(|v| v.to_ascii_lowercase().contains("chunked"));
+        if chunked {
+            copy_chunked(body_start, client, &mut upstream_writer).map_err(sending)?;
+        } else if let Some(length) = header("Content-Length").and_then(|v| v.parse::<u64>().ok()) {
+            let remaining = length.saturating_sub(body_start.len() as u64);
+            io::copy(&mut Read::take(&mut *client, remaining), &mut upstream_writer).map_err(sending)?;
+        }
+
+        // Response head, then everything after it as it arrives
+        let mut reader = BufReader::new(&upstream);
+        let mut status_line = String::new();
+        reader
+            .read_line(&mut status_line)
+            .map_err(|e| ProxyError::from_io(e, "before responding"))?;
+        if !status_line.starts_with("HTTP/") {
+            return Err(ProxyError::bad_gateway("upstream sent a malformed response"));
+        }
+
+        let mut response_head = status_line.trim_end().to_string() + "\r\n";
+        loop {
+            let mut line = String::new();
+            if reader
+                .read_line(&mut line)
+                .map_err(|e| ProxyError::from_io(e, "while responding"))?
+                == 0
+            {
+                break;
+            }
+            let line = line.trim_end();
+            if line.is_empty() {
+                break;
+            }
+            if let Some((name, _)) = line.split_once(':') {
+                if is_hop_by_hop(name.trim()) {
+                    continue;
+                }
+            }
+            response_head.push_str(line);
+            response_head.push_str("\r\n");
+        }
+        response_head.push_str("Connection: close\r\n\r\n");
+
+        // From here on the client has a response; failures just end it
+        if client.write_all(response_head.as_bytes()).is_ok() {
+            let _ = io::copy(&mut reader, client);
+        }
+        Ok(())
+    }
+}
+
+/// Whether `target` (a request target, query included) is under `prefix`.
+pub fn covers(prefix: &str, target: &str) -> bool {
+    let path = target.split('?').next().unwrap_or(target);
+    prefix == "/"
+        || path
+            .strip_prefix(prefix)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+}
+
+/// Copies a chunked request body from `client` to `upstream` until its
+/// last chunk, starting with the part already read.
+fn copy_chunked(already: &[u8], client: &mut TcpStream, upstream: &mut impl Write) -> io::Result<()> {
+    const END: &[u8] = b"0\r\n\r\n";
+
+    let mut tail: Vec<u8> = already[already.len().saturating_sub(END.len())..].to_vec();
+    let mut buffer = [0u8; 8192];
+
+    while !tail.ends_with(END) {
+        let n = client.read(&mut buffer)?;
+        if n == 0 {
+            return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "request body ended early"));
+        }
+        upstream.write_all(&buffer[..n])?;
+
+        tail.extend_from_slice(&buffer[..n]);
+        let keep = tail.len().saturating_sub(END.len());
+        tail.drain(..keep);
+    }
+
+    Ok(())
+}
+
+/// Forwards `raw` if a route covers it, returning whether one did.
+///
+/// The longest matching prefix wins. Failures before the upstream answers
+/// are sent to the client as a JSON 400, 502 or 504.
+pub fn forward(routes: &[ProxyRoute], raw: &[u8], client: &mut TcpStream, peer: Option<IpAddr>) -> bool {
+    let request_line = raw.split(|&b| b == b'\n').next().unwrap_or_default();
+    let target = String::from_utf8_lossy(request_line)
+        .split_whitespace()
+        .nth(1)
+        .unwrap_or("")
+        .to_string();
+
+    let Some(route) = routes
+        .iter()
+        .filter(|route| route.matches(&target))
+        .max_by_key(|route| route.prefix.len())
+    else {
+        return false;
+    };
+
+    if let Err(error) = route.relay(raw, client, peer) {
+        let _ = client.write_all(&error.to_response());
+    }
+    let _ = client.flush();
+    true
+}
@@ -0,0 +1,197 @@
+/*
+ * ============================================================================
+ * PAWX - Code with Claws! 🐾
+ * ============================================================================
+ *
+ * Request Routing for the PAWX HTTP Server
+ *
+ * Supports:
+ *   - server.get / post / put / patch / delete(pattern, handler)
+ *   - server.all(pattern, handler)       any method
+ *   - server.notFound(handler)           requests no route matches
+ *   - `:name` segments                   → req.params.name
+ *   - a trailing `*` or `*name` segment  → the rest of the path, in
+ *                                          req.params["*"] / req.params.name
+ *
+ *   snuggle server = Http.createServer();
+ *   server.get("/users/:id", (req, res) -> { res.json({ id: req.params.id }); });
+ *   server.notFound((req, res) -> { res.json({ error: "no such page" }); });
+ *   server.listen(8080);
+ *
+ * Routes are tried in the order they were added and the first match
+ * wins. A request none of them matches goes to the `notFound` handler
+ * (answered with status 404), then to the handler given to
+ * `Http.createServer`, and with neither the server answers 404 itself.
+ * Methods compare case-insensitively; a trailing slash is ignored.
+ *
+ * --------------------------------------------------------------------------
+ * Author:   Sam Wilcox
+ * Email:    sam@pawx-lang.com
+ * Website:  https://www.pawx-lang.com
+ * GitHub:   https://github.com/samwilcox/pawx
+ *
+ * License:
+ * This file is part of the PAWX programming language project.
+ *
+ * PAWX is dual-licensed under the terms of:
+ *   - The MIT License
+ *   - The Apache License, Version 2.0
+ *
+ * You may choose either license to govern your use of this software.
+ * Full license text available at:
+ *     https://license.pawx-lang.com
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under these licenses is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *
+ * ==========================================================================
+ */
+
+use std::collections::HashMap;
+
+use crate::error::PawxError;
+use crate::interpreter::calls::raise_error;
+use crate::prototypes::http::url_decode;
+use crate::value::Value;
+
+/// One `/`-separated piece of a route pattern.
+#[derive(Clone)]
+enum Segment {
+    Literal(String),
+    Param(String),
+    /// Matches the rest of the path (possibly nothing).
+    Rest(String),
+}
+
+/// A pattern and the handler for requests that match it.
+#[derive(Clone)]
+struct Route {
+    /// `None` for `server.all`.
+    method: Option<String>,
+    segments: Vec<Segment>,
+    handler: Value,
+}
+
+/// The routes of one server.
+#[derive(Default, Clone)]
+pub struct Router {
+    routes: Vec<Route>,
+    not_found: Option<Value>,
+}
+
+/// What the router chose for a request.
+pub enum Dispatch {
+    /// A route matched, with the values of its placeholders.
+    Route(Value, HashMap<String, Value>),
+    /// No route matched and a `notFound` handler is set.
+    NotFound(Value),
+    /// No route matched.
+    Unrouted,
+}
+
+fn pieces(path: &str) -> impl Iterator<Item = &str> {
+    path.split('/').filter(|p| !p.is_empty())
+}
+
+impl Router {
+    /// Adds a route from the arguments of `server.<method>(pattern, handler)`;
+    /// `method` is `None` for `server.all`.
+    ///
+    /// # Errors
+    /// If the arguments have the wrong types, or `*` is not the last segment.
+    pub fn add(&mut self, method: Option<&str>, args: &[Value]) -> Result<(), PawxError> {
+        let usage = format!("server.{}(pattern, handler)", method.map_or("all".to_string(), str::to_lowercase));
+
+        let (pattern, handler) = match (args.first(), args.get(1)) {
+            (Some(Value::String(p)), Some(h @ Value::NativeFunction(_))) => (p.to_string(), h.clone()),
+            _ => return raise_error(format!("{} expects a path pattern and a handler function", usage)),
+        };
+
+        let mut segments = Vec::new();
+        let mut parts = pieces(&pattern).peekable();
+
+        while let Some(part) = parts.next() {
+            segments.push(if let Some(name) = part.strip_prefix(':') {
+                Segment::Param(name.to_string())
+            } else if let Some(name) = part.strip_prefix('*') {
+                if parts.peek().is_some() {
+                    return raise_error(format!("{}: '*' must be the last segment of '{}'", usage, pattern));
+                }
+                Segment::Rest(if name.is_empty() { "*".to_string() } else { name.to_string() })
+            } else {
+                Segment::Literal(part.to_string())
+            });
+        }
+
+        self.routes.push(Route {
+            method: method.map(str::to_ascii_uppercase),
+            segments,
+            handler,
+        });
+        Ok(())
+    }
+
+    /// Sets the handler of `server.notFound(handler)`.
+    ///
+    /// # Errors
+    /// If `handler` is not a function.
+    pub fn set_not_found(&mut self, args: &[Value]) -> Result<(), PawxError> {
+        match args.first() {
+            Some(h @ Value::NativeFunction(_)) => {
+                self.not_found = Some(h.clone());
+                Ok(())
+            }
+            _ => raise_error("server.notFound(handler) expects a handler function"),
+        }
+    }
+
+    /// Chooses the handler for `method path` (path without the query).
+    pub fn dispatch(&self, method: &str, path: &str) -> Dispatch {
+        let parts: Vec<&str> = pieces(path).collect();
+
+        for route in &self.routes {
+            let method_ok = route.method.as_ref().is_none_or(|m| m.eq_ignore_ascii_case(method));
+            if !method_ok {
+                continue;
+            }
+
+            if let Some(params) = route.matches(&parts) {
+                return Dispatch::Route(route.handler.clone(), params);
+            }
+        }
+
+        match &self.not_found {
+            Some(handler) => Dispatch::NotFound(handler.clone()),
+            None => Dispatch::Unrouted,
+        }
+    }
+}
+
+impl Route {
+    /// The placeholder values if `parts` matches this route.
+    fn matches(&self, parts: &[&str]) -> Option<HashMap<String, Value>> {
+        let mut params = HashMap::new();
+
+        for (i, segment) in self.segments.iter().enumerate() {
+            match segment {
+                Segment::Rest(name) => {
+                    let rest = parts.get(i..).unwrap_or_default().join("/");
+                    params.insert(name.clone(), Value::String(url_decode(&rest).into()));
+                    return Some(params);
+                }
+                Segment::Literal(text) => {
+                    if parts.get(i) != Some(&text.as_str()) {
+                        return None;
+                    }
+                }
+                Segment::Param(name) => {
+                    let part = parts.get(i)?;
+                    params.insert(name.clone(), Value::String(url_decode(part).into()));
+                }
+            }
+        }
+
+        (parts.len() == self.segments.len()).then_some(params)
+    }
+}
@@ -0,0 +1,249 @@
+/*
+ * ============================================================================
+ * PAWX - Code with Claws! 🐾
+ * ============================================================================
+ *
+ * Static File Serving for the PAWX HTTP Server
+ *
+ * Supports:
+ *   - server.static(prefix, dir, { maxAge? })   serve files under `prefix`
+ *   - ETag and Last-Modified on every file response
+ *   - If-None-Match / If-Modified-Since answered with 304 Not Modified
+ *   - `index.html` for directory requests
+ *
+ * Only GET and HEAD requests are served. A request under a mounted prefix
+ * whose file does not exist falls through to the server's handler, so
+ * routes and static files can share a prefix. Paths that try to leave the
+ * directory (`..`) are refused with 404.
+ *
+ * The ETag is built from the file's size and modification time, so it
+ * changes whenever the file is rewritten without reading the file to
+ * compute it. `Cache-Control` is `no-cache` (always revalidate) unless the
+ * mount sets `maxAge` in seconds (or a Duration).
+ *
+ * --------------------------------------------------------------------------
+ * Author:   Sam Wilcox
+ * Email:    sam@pawx-lang.com
+ * Website:  https://www.pawx-lang.com
+ * GitHub:   https://github.com/samwilcox/pawx
+ *
+ * License:
+ * This file is part of the PAWX programming language project.
+ *
+ * PAWX is dual-licensed under the terms of:
+ *   - The MIT License
+ *   - The Apache License, Version 2.0
+ *
+ * You may choose either license to govern your use of this software.
+ * Full license text available at:
+ *     https://license.pawx-lang.com
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under these licenses is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *
+ * ==========================================================================
+ */
+
+use std::fs;
+use std::path::PathBuf;
+use std::time::UNIX_EPOCH;
+
+use chrono::{DateTime, TimeZone, Utc};
+
+use crate::error::PawxError;
+use crate::interpreter::calls::raise_error;
+use crate::prototypes::http::url_decode;
+use crate::value::Value;
+
+/// A directory served under a URL prefix.
+#[derive(Debug, Clone)]
+pub struct StaticMount {
+    prefix: String,
+    dir: PathBuf,
+
+    /// `Cache-Control: max-age` in seconds; `None` sends `no-cache`.
+    max_age: Option<u64>,
+}
+
+impl StaticMount {
+    /// Reads the arguments of `server.static(prefix, dir, options?)`.
+    ///
+    /// # Errors
+    /// If an argument has the wrong type.
+    pub fn from_args(args: &[Value]) -> Result<Self, PawxError> {
+        let (prefix, dir) = match (args.first(), args.get(1)) {
+            (Some(Value::String(prefix)), Some(Value::String(dir))) => (prefix.to_string(), dir.to_string()),
+            _ => return raise_error("server.static(prefix, dir, options?) expects a URL prefix and a directory"),
+        };
+
+        let max_age = match args.get(2) {
+            None | Some(Value::Null) => None,
+            Some(Value::Object { fields }) => match fields.borrow().get("maxAge") {
+                None | Some(Value::Null) => None,
+                Some(Value::Number(n)) if *n >= 0.0 => Some(*n as u64),
+                Some(Value::Duration(ms)) if *ms >= 0.0 => Some((ms / 1000.0) as u64),
+                Some(_) => return raise_error("server.static: maxAge must be a non-negative number of seconds or a Duration"),
+            },
+            Some(_) => return raise_error("server.static(prefix, dir, options?) expects options to be an object"),
+        };
+
+        Ok(StaticMount {
+            prefix: format!("/{}", prefix.trim_matches('/')),
+            dir: PathBuf::from(dir),
+            max_age,
+        })
+    }
+
+    /// The part of `path` after this mount's prefix, if it is under it.
+    fn strip<'a>(&self, path: &'a str) -> Option<&'a str> {
+        if self.prefix == "/" {
+            return Some(path.trim_start_matches('/'));
+        }
+
+        let rest = path.strip_prefix(&self.prefix)?;
+        if rest.is_empty() || rest.starts_with('/') {
+            Some(rest.trim_start_matches('/'))
+        } else {
+            None
+        }
+    }
+
+    fn cache_control(&self) -> String {
+        match self.max_age {
+            Some(seconds) => format!("public, max-age={}", seconds),
+            None => "no-cache".to_string(),
+        }
+    }
+}
+
+/// The media type usually served for `path`'s extension.
+pub fn content_type_for(path: &str) -> &'static str {
+    let extension = path.rsplit_once('.').map(|(_, ext)| ext.to_ascii_lowercase());
+
+    match extension.as_deref() {
+        Some("txt") => "text/plain",
+        Some("html" | "htm") => "text/html",
+        Some("css") => "text/css",
+        Some("csv") => "text/csv",
+        Some("js") => "text/javascript",
+        Some("json") => "application/json",
+        Some("xml") => "application/xml",
+        Some("pdf") => "application/pdf",
+        Some("zip") => "application/zip",
+        Some("png") => "image/png",
+        Some("jpg" | "jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("svg") => "image/svg+xml",
+        Some("webp") => "image/webp",
+        Some("ico") => "image/x-icon",
+        Some("wasm") => "application/wasm",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Formats a Unix time as an HTTP date, e.g. `Wed, 21 Oct 2015 07:28:00 GMT`.
+pub fn http_date(seconds: i64) -> String {
+    match Utc.timestamp_opt(seconds, 0).single() {
+        Some(time) => time.format("%a, %d %b %Y %H:%M:%S GMT").to_string(),
+        None => String::new(),
+    }
+}
+
+/// Parses an HTTP date into a Unix time.
+pub fn parse_http_date(date: &str) -> Option<i64> {
+    DateTime::parse_from_rfc2822(date.trim()).ok().map(|time| time.timestamp())
+}
+
+/// Whether an `If-None-Match` value matches `etag`; weak comparison, as
+/// RFC 9110 asks for GET.
+fn etag_matches(if_none_match: &str, etag: &str) -> bool {
+    let bare = |tag: &str| tag.trim().trim_start_matches("W/").to_string();
+    if_none_match
+        .split(',')
+        .any(|tag| tag.trim() == "*" || bare(tag) == bare(etag))
+}
+
+fn not_found() -> Vec<u8> {
+    b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_vec()
+}
+
+/// Answers `raw` from the first mount it falls under, or returns `None` to
+/// let the server's handler have it.
+pub fn serve(mounts: &[StaticMount], raw: &str) -> Option<Vec<u8>> {
+    let head = raw.split("\r\n\r\n").next().unwrap_or(raw);
+    let mut request_line = head.lines().next()?.split_whitespace();
+    let method = request_line.next()?;
+    let target = request_line.next()?;
+
+    if method != "GET" && method != "HEAD" {
+        return None;
+    }
+
+    let path = url_decode(target.split('?').next().unwrap_or("/"));
+
+    let header = |name: &str| {
+        head.lines().skip(1).find_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            key.trim().eq_ignore_ascii_case(name).then(|| value.trim())
+        })
+    };
+
+    // Longest prefix wins, so `/assets/img` beats `/assets` and `/`
+    let (mount, rest) = mounts
+        .iter()
+        .filter_map(|mount| Some((mount, mount.strip(&path)?)))
+        .max_by_key(|(mount, _)| mount.prefix.len())?;
+
+    if rest.split(['/', '\\']).any(|segment| segment == "..") {
+        return Some(not_found());
+    }
+
+    let mut file = mount.dir.join(rest);
+    if file.is_dir() {
+        file = file.join("index.html");
+    }
+
+    let metadata = fs::metadata(&file).ok().filter(|m| m.is_file())?;
+    let modified = metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .unwrap_or_default();
+
+    let etag = format!("\"{:x}-{:x}\"", metadata.len(), modified.as_millis());
+    let last_modified = http_date(modified.as_secs() as i64);
+
+    // If-None-Match takes precedence over If-Modified-Since
+    let not_modified = match (header("If-None-Match"), header("If-Modified-Since")) {
+        (Some(tags), _) => etag_matches(tags, &etag),
+        (None, Some(since)) => parse_http_date(since).is_some_and(|since| modified.as_secs() as i64 <= since),
+        (None, None) => false,
+    };
+
+    let common = format!(
+        "ETag: {}\r\nLast-Modified: {}\r\nCache-Control: {}\r\n",
+        etag,
+        last_modified,
+        mount.cache_control()
+    );
+
+    if not_modified {
+        return Some(format!("HTTP/1.1 304 Not Modified\r\n{}\r\n", common).into_bytes());
+    }
+
+    let body = fs::read(&file).ok()?;
+    let mut response = format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: {}\r\nContent-Length: {}\r\n{}\r\n",
+        content_type_for(&file.to_string_lossy()),
+        body.len(),
+        common
+    )
+    .into_bytes();
+
+    if method == "GET" {
+        response.extend(body);
+    }
+
+    Some(response)
+}
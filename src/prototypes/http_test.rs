@@ -0,0 +1,615 @@
+/*
+ * ============================================================================
+ * PAWX - Code with Claws! 🐾
+ * ============================================================================
+ *
+ * HTTP Test Fixtures for PAWX (`Test.httpServer`, `Test.httpMock`)
+ *
+ * Supports:
+ *   - Test.httpServer(routes)           local server on an ephemeral port
+ *   - Test.httpMock(pattern, response)  answers matching client requests
+ *   - Test.httpMockReset()              removes every HTTP mock
+ *
+ * Routes and mocks answer with a **response spec**, or a function taking
+ * the request and returning one:
+ *
+ * ```pawx
+ * snuggle api = Test.httpServer({
+ *     "GET /users/:id": (req) -> { return { id: req.params.id }; },
+ *     "POST /users":    { status: 201, body: { ok: true } }
+ * });
+ *
+ * snuggle mock = Test.httpMock("POST https://payments.example/charges", { status: 202 });
+ * ```
+ *
+ * A spec is an object with `status`, `headers` and/or `body`; any other
+ * value is sent as a `200` JSON body. String bodies are sent as-is.
+ *
+ * Both fixtures record what they receive in a live `requests` array of
+ * `{ method, url, path, query, params, headers, body, text }` objects, so
+ * tests can assert request bodies.
+ *
+ * Mocks intercept the `Http` client before it touches the network. Patterns
+ * are `[METHOD ]url`, where the URL is matched in full (or by path if it
+ * starts with `/`) and `*` matches anything. Once a mock is installed, a
+ * request to a non-local host that no mock matches fails instead of going
+ * out to the network.
+ *
+ * Test servers bind `127.0.0.1` on a port chosen by the OS. They run on the
+ * interpreter thread: pending requests are answered while the `Http` client
+ * waits for a response, so they serve requests made from PAWX code.
+ *
+ * --------------------------------------------------------------------------
+ * Author:   Sam Wilcox
+ * Email:    sam@pawx-lang.com
+ * Website:  https://www.pawx-lang.com
+ * GitHub:   https://github.com/samwilcox/pawx
+ *
+ * License:
+ * This file is part of the PAWX programming language project.
+ *
+ * PAWX is dual-licensed under the terms of:
+ *   - The MIT License
+ *   - The Apache License, Version 2.0
+ *
+ * You may choose either license to govern your use of this software.
+ * Full license text available at:
+ *     https://license.pawx-lang.com
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under these licenses is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *
+ * ==========================================================================
+ */
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::io::{ErrorKind, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::rc::Rc;
+use std::sync::Arc;
+
+use crate::error::PawxError;
+use crate::interpreter::calls::{builtin_error, raise_error};
+use crate::interpreter::display::value_to_json;
+use crate::prototypes::array::create_array_proto;
+use crate::prototypes::http::{parse_body, parse_query, reason, split_path_query};
+use crate::prototypes::http_client::{HttpRequest, HttpResponse};
+use crate::value::Value;
+
+fn native(f: impl Fn(Vec<Value>) -> Result<Value, PawxError> + 'static) -> Value {
+    Value::NativeFunction(Arc::new(f))
+}
+
+fn object(fields: HashMap<String, Value>) -> Value {
+    Value::Object {
+        fields: Rc::new(RefCell::new(fields)),
+    }
+}
+
+/* ============================================================================
+ * Requests + Response Specs
+ * ============================================================================
+ */
+
+/// Builds the request object handed to handlers and recorded in `requests`.
+fn request_value(
+    method: &str,
+    url: String,
+    target: &str,
+    headers: &[(String, String)],
+    body: &str,
+    params: HashMap<String, Value>,
+) -> Value {
+    let (path, query) = split_path_query(target);
+
+    let headers: HashMap<String, Value> = headers
+        .iter()
+        .map(|(k, v)| (k.to_ascii_lowercase(), Value::String(v.as_str().into())))
+        .collect();
+
+    let content_type = match headers.get("content-type") {
+        Some(Value::String(ct)) => ct.to_string(),
+        _ => String::new(),
+    };
+
+    // Bodies without a content type are still parsed when they are JSON.
+    let parsed = match parse_body(body, &content_type) {
+        Value::String(s) => serde_json::from_str::<serde_json::Value>(&s)
+            .map(crate::prototypes::http::json_to_value)
+            .unwrap_or(Value::String(s)),
+        other => other,
+    };
+
+    let mut fields = HashMap::new();
+    fields.insert("method".to_string(), Value::String(method.into()));
+    fields.insert("url".to_string(), Value::String(url.into()));
+    fields.insert("path".to_string(), Value::String(path.into()));
+    fields.insert("query".to_string(), object(parse_query(query)));
+    fields.insert("params".to_string(), object(params));
+    fields.insert("headers".to_string(), object(headers));
+    fields.insert("body".to_string(), if body.is_empty() { Value::Null } else { parsed });
+    fields.insert("text".to_string(), Value::String(body.into()));
+    object(fields)
+}
+
+/// Turns a response spec (or a function returning one) into a response.
+///
+/// # Errors
+/// If the body cannot be encoded as JSON.
+fn respond(spec: &Value, request: Value) -> Result<HttpResponse, String> {
+    let spec = match spec {
+        Value::NativeFunction(f) => f(vec![request]).map_err(|e| e.message)?,
+        other => other.clone(),
+    };
+
+    let fields = match &spec {
+        Value::Object { fields } if ["status", "headers", "body"].iter().any(|k| fields.borrow().contains_key(*k)) => {
+            fields.borrow().clone()
+        }
+        Value::Null => HashMap::new(),
+        body => HashMap::from([("body".to_string(), body.clone())]),
+    };
+
+    let status = match fields.get("status") {
+        Some(Value::Number(n)) => *n as u16,
+        _ => 200,
+    };
+
+    let mut headers = Vec::new();
+    if let Some(Value::Object { fields: h }) = fields.get("headers") {
+        for (k, v) in h.borrow().iter() {
+            headers.push((k.clone(), v.to_pawx_string()));
+        }
+    }
+
+    let body = match fields.get("body") {
+        None | Some(Value::Null) => String::new(),
+        Some(Value::String(s)) => s.to_string(),
+        Some(other) => {
+            if !headers.iter().any(|(k, _)| k.eq_ignore_ascii_case("Content-Type")) {
+                headers.push(("Content-Type".to_string(), "application/json".to_string()));
+            }
+            value_to_json(other)?
+        }
+    };
+
+    Ok(HttpResponse {
+        status,
+        status_text: reason(status).to_string(),
+        headers,
+        body,
+        url: String::new(),
+        redirects: 0,
+    })
+}
+
+/// Shared, live `requests` array.
+fn recorder() -> (Rc<RefCell<Vec<Value>>>, Value) {
+    let values = Rc::new(RefCell::new(Vec::new()));
+    let array = Value::Array {
+        values: values.clone(),
+        proto: create_array_proto(),
+    };
+    (values, array)
+}
+
+/* ============================================================================
+ * Client Mocks
+ * ============================================================================
+ */
+
+struct HttpMock {
+    id: usize,
+    method: Option<String>,
+    pattern: String,
+    response: Value,
+    requests: Rc<RefCell<Vec<Value>>>,
+}
+
+thread_local! {
+    static HTTP_MOCKS: RefCell<Vec<HttpMock>> = const { RefCell::new(Vec::new()) };
+    static NEXT_ID: RefCell<usize> = const { RefCell::new(0) };
+}
+
+/// `*` matches any run of characters.
+fn glob(pattern: &str, text: &str) -> bool {
+    match pattern.split_once('*') {
+        None => pattern == text,
+        Some((head, rest)) => {
+            text.starts_with(head)
+                && (0..=text.len() - head.len())
+                    .filter(|i| text.is_char_boundary(head.len() + i))
+                    .any(|i| glob(rest, &text[head.len() + i..]))
+        }
+    }
+}
+
+/// Splits `"GET /path"` into an optional method and the rest.
+fn split_method(pattern: &str) -> (Option<String>, String) {
+    match pattern.trim().split_once(' ') {
+        Some((method, rest)) if method.chars().all(|c| c.is_ascii_alphabetic()) => {
+            (Some(method.to_ascii_uppercase()), rest.trim().to_string())
+        }
+        _ => (None, pattern.trim().to_string()),
+    }
+}
+
+fn is_local(host: &str) -> bool {
+    matches!(host, "localhost" | "127.0.0.1" | "::1" | "[::1]")
+}
+
+/// Answers `reqs` from the installed mocks.
+///
+/// # Returns
+/// - `Ok(None)` when no mock applies and the requests may use the network
+///
+/// # Errors
+/// If a request to a non-local host matches no mock while mocks are
+/// installed.
+pub fn intercept(reqs: &[HttpRequest]) -> Result<Option<Vec<HttpResponse>>, String> {
+    if HTTP_MOCKS.with(|m| m.borrow().is_empty()) {
+        return Ok(None);
+    }
+
+    let mut responses = Vec::with_capacity(reqs.len());
+
+    for req in reqs {
+        let url = req.url.to_url_string();
+        let path = req.url.path.split('?').next().unwrap_or("/");
+
+        // Later mocks take precedence over earlier ones.
+        let found = HTTP_MOCKS.with(|m| {
+            m.borrow()
+                .iter()
+                .rev()
+                .find(|mock| {
+                    mock.method.as_ref().is_none_or(|method| *method == req.method)
+                        && if mock.pattern.starts_with('/') {
+                            glob(&mock.pattern, path) || glob(&mock.pattern, &req.url.path)
+                        } else {
+                            glob(&mock.pattern, &url)
+                        }
+                })
+                .map(|mock| (mock.response.clone(), mock.requests.clone()))
+        });
+
+        match found {
+            Some((response, requests)) => {
+                let body = match &req.multipart {
+                    Some(multipart) => String::from_utf8_lossy(&multipart.to_bytes().map_err(|e| e.to_string())?).into_owned(),
+                    None => req.body.clone().unwrap_or_default(),
+                };
+                let request = request_value(&req.method, url, &req.url.path, &req.headers, &body, HashMap::new());
+                requests.borrow_mut().push(request.clone());
+                responses.push(respond(&response, request)?);
+            }
+            None if is_local(&req.url.host) => {}
+            None => return Err(format!("Test.httpMock: no mock matches {} {}", req.method, url)),
+        }
+    }
+
+    match responses.len() {
+        0 => Ok(None),
+        n if n == reqs.len() => Ok(Some(responses)),
+        _ => Err("Test.httpMock: pipelined requests must all be mocked or all be local".to_string()),
+    }
+}
+
+fn create_http_mock(args: Vec<Value>) -> Result<Value, PawxError> {
+    let (method, pattern) = match args.first() {
+        Some(Value::String(p)) => split_method(p),
+        _ => return raise_error("Test.httpMock(pattern, response) expects a URL pattern"),
+    };
+
+    let (requests, requests_value) = recorder();
+    let id = NEXT_ID.with(|n| {
+        *n.borrow_mut() += 1;
+        *n.borrow()
+    });
+
+    HTTP_MOCKS.with(|m| {
+        m.borrow_mut().push(HttpMock {
+            id,
+            method,
+            pattern,
+            response: args.get(1).cloned().unwrap_or(Value::Null),
+            requests,
+        })
+    });
+
+    let mut handle = HashMap::new();
+    handle.insert("requests".to_string(), requests_value);
+    handle.insert(
+        "restore".to_string(),
+        native(move |_| {
+            HTTP_MOCKS.with(|m| m.borrow_mut().retain(|mock| mock.id != id));
+            Ok(Value::Null)
+        }),
+    );
+    Ok(object(handle))
+}
+
+/* ============================================================================
+ * Test Servers
+ * ============================================================================
+ */
+
+struct Route {
+    method: Option<String>,
+    segments: Vec<String>,
+    response: Value,
+}
+
+impl Route {
+    /// Matches `path`, returning the `:name` parameters.
+    fn matches(&self, method: &str, path: &str) -> Option<HashMap<String, Value>> {
+        if self.method.as_ref().is_some_and(|m| m != method) {
+            return None;
+        }
+
+        let parts: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+        let mut params = HashMap::new();
+
+        for (i, segment) in self.segments.iter().enumerate() {
+            if segment == "*" {
+                return Some(params);
+            }
+            let part = parts.get(i)?;
+            match segment.strip_prefix(':') {
+                Some(name) => {
+                    params.insert(name.to_string(), Value::String(part.to_string().into()));
+                }
+                None if segment == part => {}
+                None => return None,
+            }
+        }
+
+        (parts.len() == self.segments.len()).then_some(params)
+    }
+}
+
+struct Connection {
+    stream: TcpStream,
+    buffer: Vec<u8>,
+}
+
+struct TestServer {
+    id: usize,
+    port: u16,
+    listener: TcpListener,
+    connections: Vec<Connection>,
+    routes: Vec<Route>,
+    requests: Rc<RefCell<Vec<Value>>>,
+}
+
+thread_local! {
+    static SERVERS: RefCell<Vec<Rc<RefCell<TestServer>>>> = const { RefCell::new(Vec::new()) };
+}
+
+/// A request read off a connection: method, target, headers and body.
+type RawRequest = (String, String, Vec<(String, String)>, String);
+
+/// Takes one complete request from the front of `buffer`, if there is one.
+fn take_request(buffer: &mut Vec<u8>) -> Option<RawRequest> {
+    let end = buffer.windows(4).position(|w| w == b"\r\n\r\n")?;
+    let head = String::from_utf8_lossy(&buffer[..end]).to_string();
+
+    let mut lines = head.lines();
+    let mut start = lines.next()?.split_whitespace();
+    let method = start.next()?.to_string();
+    let target = start.next().unwrap_or("/").to_string();
+
+    let headers: Vec<(String, String)> = lines
+        .filter_map(|l| l.split_once(':'))
+        .map(|(k, v)| (k.trim().to_string(), v.trim().to_string()))
+        .collect();
+
+    let length = headers
+        .iter()
+        .find(|(k, _)| k.eq_ignore_ascii_case("Content-Length"))
+        .and_then(|(_, v)| v.parse::<usize>().ok())
+        .unwrap_or(0);
+
+    let body_start = end + 4;
+    if buffer.len() < body_start + length {
+        return None;
+    }
+
+    let body = String::from_utf8_lossy(&buffer[body_start..body_start + length]).to_string();
+    buffer.drain(..body_start + length);
+
+    Some((method, target, headers, body))
+}
+
+/// A 500 response for a route whose response could not be built.
+fn server_error(message: String) -> HttpResponse {
+    HttpResponse {
+        status: 500,
+        status_text: reason(500).to_string(),
+        headers: vec![("Content-Type".to_string(), "text/plain; charset=utf-8".to_string())],
+        body: message,
+        url: String::new(),
+        redirects: 0,
+    }
+}
+
+fn write_response(stream: &mut TcpStream, res: &HttpResponse) {
+    let mut out = format!("HTTP/1.1 {} {}\r\n", res.status, res.status_text);
+    for (k, v) in &res.headers {
+        out.push_str(&format!("{}: {}\r\n", k, v));
+    }
+    out.push_str(&format!("Content-Length: {}\r\n\r\n", res.body.len()));
+    out.push_str(&res.body);
+
+    let _ = stream.write_all(out.as_bytes());
+    let _ = stream.flush();
+}
+
+impl TestServer {
+    fn handle(&self, raw: RawRequest) -> HttpResponse {
+        let (method, target, headers, body) = raw;
+        let (path, _) = split_path_query(&target);
+        let url = format!("http://127.0.0.1:{}{}", self.port, target);
+
+        let route = self
+            .routes
+            .iter()
+            .find_map(|route| route.matches(&method, &path).map(|params| (route, params)));
+
+        let Some((route, params)) = route else {
+            let request = request_value(&method, url, &target, &headers, &body, HashMap::new());
+            self.requests.borrow_mut().push(request);
+
+            let message = format!("no route for {} {}", method, path);
+            let body = object(HashMap::from([("error".to_string(), Value::String(message.into()))]));
+            let spec = object(HashMap::from([
+                ("status".to_string(), Value::Number(404.0)),
+                ("body".to_string(), body),
+            ]));
+            return respond(&spec, Value::Null).unwrap_or_else(server_error);
+        };
+
+        let request = request_value(&method, url, &target, &headers, &body, params);
+        self.requests.borrow_mut().push(request.clone());
+        respond(&route.response, request).unwrap_or_else(server_error)
+    }
+
+    /// Accepts new connections and answers every complete request.
+    fn serve(&mut self) {
+        while let Ok((stream, _)) = self.listener.accept() {
+            let _ = stream.set_nonblocking(true);
+            self.connections.push(Connection {
+                stream,
+                buffer: Vec::new(),
+            });
+        }
+
+        let mut connections = std::mem::take(&mut self.connections);
+
+        connections.retain_mut(|conn| {
+            let mut open = true;
+            let mut chunk = [0u8; 8192];
+
+            loop {
+                match conn.stream.read(&mut chunk) {
+                    Ok(0) => {
+                        open = false;
+                        break;
+                    }
+                    Ok(n) => conn.buffer.extend_from_slice(&chunk[..n]),
+                    Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+                    Err(_) => {
+                        open = false;
+                        break;
+                    }
+                }
+            }
+
+            while let Some(raw) = take_request(&mut conn.buffer) {
+                let response = self.handle(raw);
+                write_response(&mut conn.stream, &response);
+            }
+
+            open
+        });
+
+        self.connections = connections;
+    }
+}
+
+/// Answers pending requests on every test server.
+///
+/// The `Http` client calls this after sending a request, so a test server
+/// running on the same thread can respond before the client reads.
+pub fn serve_pending() {
+    let servers = SERVERS.with(|s| s.borrow().clone());
+
+    for server in servers {
+        // A handler calling its own server is already being served.
+        if let Ok(mut server) = server.try_borrow_mut() {
+            server.serve();
+        }
+    }
+}
+
+fn create_http_server(args: Vec<Value>) -> Result<Value, PawxError> {
+    let routes = match args.first() {
+        Some(Value::Object { fields }) => fields.borrow().clone(),
+        None | Some(Value::Null) => HashMap::new(),
+        _ => return raise_error("Test.httpServer(routes) expects an object of routes"),
+    };
+
+    let mut routes: Vec<Route> = routes
+        .into_iter()
+        .map(|(key, response)| {
+            let (method, path) = split_method(&key);
+            Route {
+                method,
+                segments: path.split('/').filter(|s| !s.is_empty()).map(str::to_string).collect(),
+                response,
+            }
+        })
+        .collect();
+
+    // Literal segments win over parameters and wildcards.
+    routes.sort_by_key(|r| {
+        let dynamic = r.segments.iter().filter(|s| s.starts_with(':') || *s == "*").count();
+        (dynamic, std::cmp::Reverse(r.segments.len()), r.method.is_none())
+    });
+
+    let listener = TcpListener::bind(("127.0.0.1", 0)).map_err(|e| builtin_error(format!("Test.httpServer(): {}", e)))?;
+    let port = listener.local_addr().map(|a| a.port()).unwrap_or(0);
+    listener
+        .set_nonblocking(true)
+        .map_err(|e| builtin_error(format!("Test.httpServer(): {}", e)))?;
+
+    let (requests, requests_value) = recorder();
+    let id = NEXT_ID.with(|n| {
+        *n.borrow_mut() += 1;
+        *n.borrow()
+    });
+
+    SERVERS.with(|s| {
+        s.borrow_mut().push(Rc::new(RefCell::new(TestServer {
+            id,
+            port,
+            listener,
+            connections: Vec::new(),
+            routes,
+            requests,
+        })))
+    });
+
+    let mut server = HashMap::new();
+    server.insert("url".to_string(), Value::String(format!("http://127.0.0.1:{}", port).into()));
+    server.insert("port".to_string(), Value::Number(port as f64));
+    server.insert("requests".to_string(), requests_value);
+    server.insert(
+        "close".to_string(),
+        native(move |_| {
+            SERVERS.with(|s| s.borrow_mut().retain(|server| server.borrow().id != id));
+            Ok(Value::Null)
+        }),
+    );
+    Ok(object(server))
+}
+
+/* ============================================================================
+ * Test.* Bindings
+ * ============================================================================
+ */
+
+/// Adds `httpServer`, `httpMock` and `httpMockReset` to the `Test` object.
+pub fn install_http_fixtures(test: &mut HashMap<String, Value>) {
+    test.insert("httpServer".to_string(), native(create_http_server));
+    test.insert("httpMock".to_string(), native(create_http_mock));
+    test.insert(
+        "httpMockReset".to_string(),
+        native(|_| {
+            HTTP_MOCKS.with(|m| m.borrow_mut().clear());
+            Ok(Value::Null)
+        }),
+    );
+}
@@ -0,0 +1,608 @@
+/*
+ * ============================================================================
+ * PAWX - Code with Claws! 🐾
+ * ============================================================================
+ *
+ * Persistent Immutable Collections for PAWX (`Immutable`)
+ *
+ * `Immutable.list` and `Immutable.map` values never change once built.
+ * Every update (`set`, `push`, `delete`, ...) returns a NEW version that
+ * shares most of its structure with the old one, so updates cost
+ * O(log n) instead of a full copy, and old versions stay valid.
+ *
+ * Backed by the `im-rc` crate (RRB vectors and B-tree maps).
+ *
+ * Installed API:
+ *   - Immutable.list(array?)      new list (copies the array's elements)
+ *   - Immutable.map(object?)      new map with string keys
+ *   - Immutable.from(value)       deep conversion: arrays → lists,
+ *                                 objects → maps
+ *   - Immutable.isList(value) / Immutable.isMap(value)
+ *
+ * Lists:
+ *   size, get, set, update, push, pop, unshift, shift, insert, delete,
+ *   concat, slice, map, filter, reduce, forEach, indexOf, includes,
+ *   first, last, isEmpty, getIn, setIn, equals, toArray, toJS
+ *
+ * Maps:
+ *   size, get, has, set, update, delete, merge, keys, values, entries,
+ *   map, filter, forEach, isEmpty, getIn, setIn, equals, toObject, toJS
+ *
+ * Lists support `list[i]` reads. Map keys iterate in sorted order.
+ *
+ * --------------------------------------------------------------------------
+ * Author:   Sam Wilcox
+ * Email:    sam@pawx-lang.com
+ * Website:  https://www.pawx-lang.com
+ * GitHub:   https://github.com/samwilcox/pawx
+ *
+ * License:
+ * This file is part of the PAWX programming language project.
+ *
+ * PAWX is dual-licensed under the terms of:
+ *   - The MIT License
+ *   - The Apache License, Version 2.0
+ *
+ * You may choose either license to govern your use of this software.
+ * Full license text available at:
+ *     https://license.pawx-lang.com
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under these licenses is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *
+ * ==========================================================================
+ */
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use im_rc::{OrdMap, Vector};
+
+use crate::error::PawxError;
+use crate::interpreter::calls::{builtin_error, raise_error};
+use crate::prototypes::array::create_array_proto;
+use crate::prototypes::slice;
+use crate::value::{NativeFn, Value};
+
+/// Persistent list storage.
+pub type ImList = Vector<Value>;
+
+/// Persistent map storage.
+pub type ImMap = OrdMap<String, Value>;
+
+fn method(f: impl Fn(Vec<Value>) -> Result<Value, PawxError> + 'static) -> Value {
+    Value::NativeFunction(Arc::new(f))
+}
+
+fn array(values: Vec<Value>) -> Value {
+    Value::Array {
+        values: Rc::new(RefCell::new(values)),
+        proto: create_array_proto(),
+    }
+}
+
+fn object(fields: HashMap<String, Value>) -> Value {
+    Value::Object {
+        fields: Rc::new(RefCell::new(fields)),
+    }
+}
+
+fn callback(arg: Option<&Value>, usage: &str) -> Result<Arc<NativeFn>, PawxError> {
+    match arg {
+        Some(Value::NativeFunction(f)) => Ok(f.clone()),
+        _ => raise_error(format!("{} expects a function", usage)),
+    }
+}
+
+fn key(arg: Option<&Value>, usage: &str) -> Result<String, PawxError> {
+    match arg {
+        Some(Value::String(s)) => Ok(s.to_string()),
+        Some(Value::Number(n)) => Ok(n.to_string()),
+        _ => raise_error(format!("{} expects a string key", usage)),
+    }
+}
+
+/// Resolves a possibly negative index against `len`.
+///
+/// # Returns
+/// - `Some(index)` when it falls inside the list
+/// - `None` otherwise
+fn resolve_index(arg: Option<&Value>, len: usize, usage: &str) -> Result<Option<usize>, PawxError> {
+    let n = match arg {
+        Some(Value::Number(n)) if n.fract() == 0.0 => *n,
+        _ => return raise_error(format!("{} expects an integer index", usage)),
+    };
+
+    let index = if n < 0.0 { len as f64 + n } else { n };
+
+    if index >= 0.0 && (index as usize) < len {
+        Ok(Some(index as usize))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Reads an insertion position in `0..=len` (negative counts from the end).
+fn position(arg: Option<&Value>, len: usize, usage: &str) -> Result<usize, PawxError> {
+    let n = match arg {
+        Some(Value::Number(n)) if n.fract() == 0.0 => *n,
+        _ => return raise_error(format!("{} expects an integer index", usage)),
+    };
+
+    let index = if n < 0.0 { len as f64 + n } else { n };
+    Ok(index.clamp(0.0, len as f64) as usize)
+}
+
+/* ============================================================================
+ * CONVERSION
+ * ============================================================================
+ */
+
+/// Copies the elements of an array, tuple or list into a persistent list.
+fn list_from(value: Option<&Value>, usage: &str) -> Result<ImList, PawxError> {
+    match value {
+        None | Some(Value::Null) => Ok(Vector::new()),
+        Some(Value::Array { values, .. }) => Ok(values.borrow().iter().cloned().collect()),
+        Some(Value::Tuple(values)) => Ok(values.iter().cloned().collect()),
+        Some(Value::ImmutableList(list)) => Ok((**list).clone()),
+        Some(other) => raise_error(format!("{} expects an array, got {}", usage, other.type_name())),
+    }
+}
+
+/// Copies the fields of an object or map into a persistent map.
+fn map_from(value: Option<&Value>, usage: &str) -> Result<ImMap, PawxError> {
+    match value {
+        None | Some(Value::Null) => Ok(OrdMap::new()),
+        Some(Value::Object { fields }) => Ok(fields
+            .borrow()
+            .iter()
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect()),
+        Some(Value::ImmutableMap(map)) => Ok(map.clone()),
+        Some(other) => raise_error(format!("{} expects an object, got {}", usage, other.type_name())),
+    }
+}
+
+/// Deep conversion: arrays become lists and objects become maps.
+fn from_value(value: &Value) -> Value {
+    match value {
+        Value::Array { values, .. } => {
+            Value::ImmutableList(Box::new(values.borrow().iter().map(from_value).collect()))
+        }
+        Value::Object { fields } => Value::ImmutableMap(
+            fields
+                .borrow()
+                .iter()
+                .map(|(k, v)| (k.clone(), from_value(v)))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+/// Deep conversion back to mutable arrays and objects.
+fn to_js(value: &Value) -> Value {
+    match value {
+        Value::ImmutableList(list) => array(list.iter().map(to_js).collect()),
+        Value::ImmutableMap(map) => object(map.iter().map(|(k, v)| (k.clone(), to_js(v))).collect()),
+        other => other.clone(),
+    }
+}
+
+/// Reads the path array for `getIn` / `setIn`.
+fn path(arg: Option<&Value>, usage: &str) -> Result<Vec<Value>, PawxError> {
+    match arg {
+        Some(Value::Array { values, .. }) => Ok(values.borrow().clone()),
+        Some(Value::ImmutableList(list)) => Ok(list.iter().cloned().collect()),
+        _ => raise_error(format!("{} expects a path array", usage)),
+    }
+}
+
+fn get_in(value: &Value, path: &[Value]) -> Result<Value, PawxError> {
+    let Some((step, rest)) = path.split_first() else {
+        return Ok(value.clone());
+    };
+
+    let next = match value {
+        Value::ImmutableList(list) => match step {
+            Value::Number(_) => resolve_index(Some(step), list.len(), "getIn(path)")?
+                .map(|i| list[i].clone()),
+            _ => None,
+        },
+        Value::ImmutableMap(map) => map.get(&key(Some(step), "getIn(path)")?).cloned(),
+        _ => None,
+    };
+
+    match next {
+        Some(next) => get_in(&next, rest),
+        None => Ok(Value::Null),
+    }
+}
+
+/// Returns a new version of `value` with `new_value` stored at `path`.
+/// Missing intermediate levels are created as maps.
+fn set_in(value: &Value, path: &[Value], new_value: Value) -> Result<Value, PawxError> {
+    let Some((step, rest)) = path.split_first() else {
+        return Ok(new_value);
+    };
+
+    match value {
+        Value::ImmutableList(list) => {
+            let index = resolve_index(Some(step), list.len(), "setIn(path, value)")?
+                .ok_or_else(|| builtin_error("setIn(path, value): list index out of bounds"))?;
+            let child = set_in(&list[index], rest, new_value)?;
+            Ok(Value::ImmutableList(Box::new(list.update(index, child))))
+        }
+
+        Value::ImmutableMap(map) => {
+            let k = key(Some(step), "setIn(path, value)")?;
+            let current = map.get(&k).cloned().unwrap_or(Value::Null);
+            let child = set_in(&current, rest, new_value)?;
+            Ok(Value::ImmutableMap(map.update(k, child)))
+        }
+
+        Value::Null => {
+            let k = key(Some(step), "setIn(path, value)")?;
+            let child = set_in(&Value::Null, rest, new_value)?;
+            Ok(Value::ImmutableMap(OrdMap::unit(k, child)))
+        }
+
+        other => raise_error(format!("setIn(path, value): cannot descend into {}", other.type_name())),
+    }
+}
+
+fn list_equals(a: &ImList, b: &ImList) -> Result<bool, PawxError> {
+    Value::equals_strict(&Value::ImmutableList(Box::new(a.clone())), &Value::ImmutableList(Box::new(b.clone())))
+}
+
+fn map_equals(a: &ImMap, b: &ImMap) -> Result<bool, PawxError> {
+    Value::equals_strict(&Value::ImmutableMap(a.clone()), &Value::ImmutableMap(b.clone()))
+}
+
+/// Reads `list[i]`; out-of-range reads give `null`.
+pub fn list_index(list: &ImList, index: usize) -> Value {
+    list.get(index).cloned().unwrap_or(Value::Null)
+}
+
+/* ============================================================================
+ * LIST PROPERTIES
+ * ============================================================================
+ */
+
+/// Resolves a property or method on an `Immutable.list` value.
+///
+/// # Errors
+/// On unknown properties.
+pub fn list_property(list: &ImList, name: &str) -> Result<Value, PawxError> {
+    let list = list.clone();
+
+    match name {
+        "size" | "length" => Ok(Value::Number(list.len() as f64)),
+
+        "get" => Ok(method(move |args| {
+            Ok(resolve_index(args.first(), list.len(), "list.get(i)")?
+                .map(|i| list[i].clone())
+                .unwrap_or_else(|| args.get(1).cloned().unwrap_or(Value::Null)))
+        })),
+
+        "set" => Ok(method(move |args| {
+            let index = resolve_index(args.first(), list.len(), "list.set(i, value)")?
+                .ok_or_else(|| builtin_error("list.set(i, value): index out of bounds"))?;
+            let value = args.get(1).cloned().unwrap_or(Value::Null);
+            Ok(Value::ImmutableList(Box::new(list.update(index, value))))
+        })),
+
+        "update" => Ok(method(move |args| {
+            let index = resolve_index(args.first(), list.len(), "list.update(i, fn)")?
+                .ok_or_else(|| builtin_error("list.update(i, fn): index out of bounds"))?;
+            let f = callback(args.get(1), "list.update(i, fn)")?;
+            Ok(Value::ImmutableList(Box::new(list.update(index, f(vec![list[index].clone()])?))))
+        })),
+
+        "push" => Ok(method(move |args| {
+            let mut next = list.clone();
+            next.extend(args);
+            Ok(Value::ImmutableList(Box::new(next)))
+        })),
+
+        "pop" => Ok(method(move |_| {
+            let mut next = list.clone();
+            next.pop_back();
+            Ok(Value::ImmutableList(Box::new(next)))
+        })),
+
+        "unshift" => Ok(method(move |args| {
+            let mut next = list.clone();
+            for value in args.into_iter().rev() {
+                next.push_front(value);
+            }
+            Ok(Value::ImmutableList(Box::new(next)))
+        })),
+
+        "shift" => Ok(method(move |_| {
+            let mut next = list.clone();
+            next.pop_front();
+            Ok(Value::ImmutableList(Box::new(next)))
+        })),
+
+        "insert" => Ok(method(move |args| {
+            let index = position(args.first(), list.len(), "list.insert(i, value)")?;
+            let mut next = list.clone();
+            next.insert(index, args.get(1).cloned().unwrap_or(Value::Null));
+            Ok(Value::ImmutableList(Box::new(next)))
+        })),
+
+        "delete" | "remove" => Ok(method(move |args| {
+            let mut next = list.clone();
+            if let Some(index) = resolve_index(args.first(), list.len(), "list.delete(i)")? {
+                next.remove(index);
+            }
+            Ok(Value::ImmutableList(Box::new(next)))
+        })),
+
+        "concat" => Ok(method(move |args| {
+            let mut next = list.clone();
+            for other in &args {
+                next.append(list_from(Some(other), "list.concat(other)")?);
+            }
+            Ok(Value::ImmutableList(Box::new(next)))
+        })),
+
+        "slice" => Ok(method(move |args| {
+            let picked = slice::positions(&args, list.len(), "list.slice(start?, end?, step?)")?;
+
+            // A plain forward range shares structure with the original
+            match (picked.first(), picked.last()) {
+                (Some(&first), Some(&last)) if first <= last && picked.len() == last - first + 1 => {
+                    Ok(Value::ImmutableList(Box::new(list.clone().slice(first..=last))))
+                }
+                _ => Ok(Value::ImmutableList(Box::new(picked.into_iter().map(|i| list[i].clone()).collect()))),
+            }
+        })),
+
+        "map" => Ok(method(move |args| {
+            let f = callback(args.first(), "list.map(fn)")?;
+            let mapped = list
+                .iter()
+                .enumerate()
+                .map(|(i, v)| f(vec![v.clone(), Value::Number(i as f64)]))
+                .collect::<Result<ImList, PawxError>>()?;
+            Ok(Value::ImmutableList(Box::new(mapped)))
+        })),
+
+        "filter" => Ok(method(move |args| {
+            let f = callback(args.first(), "list.filter(fn)")?;
+            let mut kept = Vector::new();
+            for (i, v) in list.iter().enumerate() {
+                if f(vec![v.clone(), Value::Number(i as f64)])?.is_truthy() {
+                    kept.push_back(v.clone());
+                }
+            }
+            Ok(Value::ImmutableList(Box::new(kept)))
+        })),
+
+        "reduce" => Ok(method(move |args| {
+            let f = callback(args.first(), "list.reduce(fn, initial)")?;
+            let mut items = list.iter().cloned();
+
+            let mut acc = match args.get(1) {
+                Some(initial) => initial.clone(),
+                None => match items.next() {
+                    Some(first) => first,
+                    None => return raise_error("list.reduce(fn) of an empty list needs an initial value"),
+                },
+            };
+
+            for item in items {
+                acc = f(vec![acc, item])?;
+            }
+
+            Ok(acc)
+        })),
+
+        "forEach" => Ok(method(move |args| {
+            let f = callback(args.first(), "list.forEach(fn)")?;
+            for (i, v) in list.iter().enumerate() {
+                f(vec![v.clone(), Value::Number(i as f64)])?;
+            }
+            Ok(Value::Null)
+        })),
+
+        "indexOf" => Ok(method(move |args| {
+            let needle = args.first().cloned().unwrap_or(Value::Null);
+            for (i, v) in list.iter().enumerate() {
+                if Value::equals_strict(v, &needle)? {
+                    return Ok(Value::Number(i as f64));
+                }
+            }
+            Ok(Value::Number(-1.0))
+        })),
+
+        "includes" => Ok(method(move |args| {
+            let needle = args.first().cloned().unwrap_or(Value::Null);
+            for v in list.iter() {
+                if Value::equals_strict(v, &needle)? {
+                    return Ok(Value::Bool(true));
+                }
+            }
+            Ok(Value::Bool(false))
+        })),
+
+        "first" => Ok(list.front().cloned().unwrap_or(Value::Null)),
+        "last" => Ok(list.back().cloned().unwrap_or(Value::Null)),
+        "isEmpty" => Ok(Value::Bool(list.is_empty())),
+
+        "getIn" => Ok(method(move |args| {
+            get_in(&Value::ImmutableList(Box::new(list.clone())), &path(args.first(), "list.getIn(path)")?)
+        })),
+
+        "setIn" => Ok(method(move |args| {
+            let path = path(args.first(), "list.setIn(path, value)")?;
+            let value = args.get(1).cloned().unwrap_or(Value::Null);
+            set_in(&Value::ImmutableList(Box::new(list.clone())), &path, value)
+        })),
+
+        "equals" => Ok(method(move |args| match args.first() {
+            Some(Value::ImmutableList(other)) => Ok(Value::Bool(list_equals(&list, other)?)),
+            _ => Ok(Value::Bool(false)),
+        })),
+
+        "toArray" => Ok(method(move |_| Ok(array(list.iter().cloned().collect())))),
+
+        "toJS" => Ok(method(move |_| Ok(to_js(&Value::ImmutableList(Box::new(list.clone())))))),
+
+        other => raise_error(format!("Property '{}' not supported on Immutable.list", other)),
+    }
+}
+
+/* ============================================================================
+ * MAP PROPERTIES
+ * ============================================================================
+ */
+
+/// Resolves a property or method on an `Immutable.map` value.
+///
+/// # Errors
+/// On unknown properties.
+pub fn map_property(map: &ImMap, name: &str) -> Result<Value, PawxError> {
+    let map = map.clone();
+
+    match name {
+        "size" => Ok(Value::Number(map.len() as f64)),
+
+        "get" => Ok(method(move |args| {
+            Ok(map.get(&key(args.first(), "map.get(key)")?)
+                .cloned()
+                .unwrap_or_else(|| args.get(1).cloned().unwrap_or(Value::Null)))
+        })),
+
+        "has" => Ok(method(move |args| Ok(Value::Bool(map.contains_key(&key(args.first(), "map.has(key)")?))))),
+
+        "set" => Ok(method(move |args| {
+            let k = key(args.first(), "map.set(key, value)")?;
+            Ok(Value::ImmutableMap(map.update(k, args.get(1).cloned().unwrap_or(Value::Null))))
+        })),
+
+        "update" => Ok(method(move |args| {
+            let k = key(args.first(), "map.update(key, fn)")?;
+            let f = callback(args.get(1), "map.update(key, fn)")?;
+            let current = map.get(&k).cloned().unwrap_or(Value::Null);
+            Ok(Value::ImmutableMap(map.update(k, f(vec![current])?)))
+        })),
+
+        "delete" | "remove" => Ok(method(move |args| {
+            Ok(Value::ImmutableMap(map.without(&key(args.first(), "map.delete(key)")?)))
+        })),
+
+        "merge" => Ok(method(move |args| {
+            let mut next = map.clone();
+            for other in &args {
+                next = map_from(Some(other), "map.merge(other)")?.union(next);
+            }
+            Ok(Value::ImmutableMap(next))
+        })),
+
+        "keys" => Ok(method(move |_| Ok(array(map.keys().map(|k| Value::String(k.as_str().into())).collect())))),
+
+        "values" => Ok(method(move |_| Ok(array(map.values().cloned().collect())))),
+
+        "entries" => Ok(method(move |_| {
+            Ok(array(
+                map.iter()
+                    .map(|(k, v)| Value::Tuple(vec![Value::String(k.as_str().into()), v.clone()]))
+                    .collect(),
+            ))
+        })),
+
+        "map" => Ok(method(move |args| {
+            let f = callback(args.first(), "map.map(fn)")?;
+            let mut mapped = OrdMap::new();
+            for (k, v) in map.iter() {
+                mapped.insert(k.clone(), f(vec![v.clone(), Value::String(k.as_str().into())])?);
+            }
+            Ok(Value::ImmutableMap(mapped))
+        })),
+
+        "filter" => Ok(method(move |args| {
+            let f = callback(args.first(), "map.filter(fn)")?;
+            let mut kept = OrdMap::new();
+            for (k, v) in map.iter() {
+                if f(vec![v.clone(), Value::String(k.as_str().into())])?.is_truthy() {
+                    kept.insert(k.clone(), v.clone());
+                }
+            }
+            Ok(Value::ImmutableMap(kept))
+        })),
+
+        "forEach" => Ok(method(move |args| {
+            let f = callback(args.first(), "map.forEach(fn)")?;
+            for (k, v) in map.iter() {
+                f(vec![v.clone(), Value::String(k.as_str().into())])?;
+            }
+            Ok(Value::Null)
+        })),
+
+        "isEmpty" => Ok(Value::Bool(map.is_empty())),
+
+        "getIn" => Ok(method(move |args| {
+            get_in(&Value::ImmutableMap(map.clone()), &path(args.first(), "map.getIn(path)")?)
+        })),
+
+        "setIn" => Ok(method(move |args| {
+            let path = path(args.first(), "map.setIn(path, value)")?;
+            let value = args.get(1).cloned().unwrap_or(Value::Null);
+            set_in(&Value::ImmutableMap(map.clone()), &path, value)
+        })),
+
+        "equals" => Ok(method(move |args| match args.first() {
+            Some(Value::ImmutableMap(other)) => Ok(Value::Bool(map_equals(&map, other)?)),
+            _ => Ok(Value::Bool(false)),
+        })),
+
+        "toObject" => Ok(method(move |_| Ok(object(map.iter().map(|(k, v)| (k.clone(), v.clone())).collect())))),
+
+        "toJS" => Ok(method(move |_| Ok(to_js(&Value::ImmutableMap(map.clone()))))),
+
+        other => raise_error(format!("Property '{}' not supported on Immutable.map", other)),
+    }
+}
+
+/* ============================================================================
+ * GLOBAL OBJECT
+ * ============================================================================
+ */
+
+/// Builds the global `Immutable` object.
+pub fn create_global_immutable_object() -> HashMap<String, Value> {
+    let mut immutable = HashMap::new();
+
+    immutable.insert(
+        "list".to_string(),
+        method(|args| Ok(Value::ImmutableList(Box::new(list_from(args.first(), "Immutable.list(array)")?)))),
+    );
+
+    immutable.insert(
+        "map".to_string(),
+        method(|args| Ok(Value::ImmutableMap(map_from(args.first(), "Immutable.map(object)")?))),
+    );
+
+    immutable.insert(
+        "from".to_string(),
+        method(|args| Ok(from_value(args.first().unwrap_or(&Value::Null)))),
+    );
+
+    immutable.insert(
+        "isList".to_string(),
+        method(|args| Ok(Value::Bool(matches!(args.first(), Some(Value::ImmutableList(_)))))),
+    );
+
+    immutable.insert(
+        "isMap".to_string(),
+        method(|args| Ok(Value::Bool(matches!(args.first(), Some(Value::ImmutableMap(_)))))),
+    );
+
+    immutable
+}
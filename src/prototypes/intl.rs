@@ -0,0 +1,922 @@
+/*
+ * ============================================================================
+ * PAWX - Code with Claws! 🐾
+ * ============================================================================
+ *
+ * Locale-Aware Formatting for PAWX (`Intl`)
+ *
+ * Supports:
+ *   - Numbers with locale decimal / grouping separators
+ *   - Currencies with locale symbol placement
+ *   - Percentages
+ *   - Dates and times with localized month and day names
+ *
+ * Installed API:
+ *   - Intl.locales()
+ *   - Intl.defaultLocale()
+ *   - Intl.resolve(locale)
+ *   - Intl.formatNumber(n, options?)
+ *   - Intl.formatCurrency(n, currency, options?)
+ *   - Intl.formatPercent(n, options?)
+ *   - Intl.formatDate(date, options?)
+ *   - Intl.formatTime(date, options?)
+ *   - Intl.monthNames(options?)
+ *   - Intl.dayNames(options?)
+ *
+ * Locale data is compiled into the binary. Only `en-US` is always
+ * available; the other locales are built with the `intl-locales` Cargo
+ * feature (enabled by default). Unknown locales fall back to a locale with
+ * the same language, then to `en-US`.
+ *
+ * Output follows the CLDR conventions, including no-break spaces between
+ * numbers and suffixed currency / percent signs.
+ *
+ * --------------------------------------------------------------------------
+ * Author:   Sam Wilcox
+ * Email:    sam@pawx-lang.com
+ * Website:  https://www.pawx-lang.com
+ * GitHub:   https://github.com/samwilcox/pawx
+ *
+ * License:
+ * This file is part of the PAWX programming language project.
+ *
+ * PAWX is dual-licensed under the terms of:
+ *   - The MIT License
+ *   - The Apache License, Version 2.0
+ *
+ * You may choose either license to govern your use of this software.
+ * Full license text available at:
+ *     https://license.pawx-lang.com
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under these licenses is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *
+ * ==========================================================================
+ */
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use chrono::{DateTime, Datelike, Local, NaiveDate, Timelike, Utc};
+
+use crate::error::PawxError;
+use crate::interpreter::calls::{builtin_error, raise_error};
+use crate::prototypes::array::create_array_proto;
+use crate::value::Value;
+
+/// No-break space, used between numbers and suffixed symbols.
+const NBSP: char = '\u{a0}';
+
+/* ============================================================================
+ * LOCALE DATA
+ * ============================================================================
+ */
+
+/// Where a currency symbol goes relative to the amount.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(not(feature = "intl-locales"), allow(dead_code))]
+enum SymbolPosition {
+    /// `$1.00`
+    Prefix,
+    /// `R$ 1,00`
+    PrefixSpace,
+    /// `1,00 €`
+    SuffixSpace,
+}
+
+/// Formatting rules for one locale.
+///
+/// Date and time patterns use `{token}` placeholders:
+/// - `{YYYY}` year, `{MM}` / `{M}` month number, `{DD}` / `{D}` day
+/// - `{MMMM}` / `{MMM}` month name, `{dddd}` weekday name
+/// - `{HH}` 24-hour, `{h}` 12-hour, `{mm}` minutes, `{ss}` seconds,
+///   `{A}` AM/PM
+struct LocaleData {
+    tag: &'static str,
+    decimal: char,
+    group: char,
+    currency: SymbolPosition,
+    percent_space: Option<char>,
+    months: [&'static str; 12],
+    months_short: [&'static str; 12],
+    /// Sunday first.
+    days: [&'static str; 7],
+    days_short: [&'static str; 7],
+    /// short, medium, long, full
+    date: [&'static str; 4],
+    /// short (no seconds), medium (with seconds)
+    time: [&'static str; 2],
+    /// Joins a date and a time: `{date}` + separator + `{time}`.
+    date_time_separator: &'static str,
+}
+
+const EN_US: LocaleData = LocaleData {
+    tag: "en-US",
+    decimal: '.',
+    group: ',',
+    currency: SymbolPosition::Prefix,
+    percent_space: None,
+    months: [
+        "January", "February", "March", "April", "May", "June",
+        "July", "August", "September", "October", "November", "December",
+    ],
+    months_short: [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun",
+        "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ],
+    days: ["Sunday", "Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday"],
+    days_short: ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"],
+    date: [
+        "{M}/{D}/{YYYY}",
+        "{MMM} {D}, {YYYY}",
+        "{MMMM} {D}, {YYYY}",
+        "{dddd}, {MMMM} {D}, {YYYY}",
+    ],
+    time: ["{h}:{mm} {A}", "{h}:{mm}:{ss} {A}"],
+    date_time_separator: ", ",
+};
+
+#[cfg(feature = "intl-locales")]
+const EN_GB: LocaleData = LocaleData {
+    tag: "en-GB",
+    decimal: '.',
+    group: ',',
+    currency: SymbolPosition::Prefix,
+    percent_space: None,
+    months: EN_US.months,
+    months_short: EN_US.months_short,
+    days: EN_US.days,
+    days_short: EN_US.days_short,
+    date: [
+        "{DD}/{MM}/{YYYY}",
+        "{D} {MMM} {YYYY}",
+        "{D} {MMMM} {YYYY}",
+        "{dddd}, {D} {MMMM} {YYYY}",
+    ],
+    time: ["{HH}:{mm}", "{HH}:{mm}:{ss}"],
+    date_time_separator: ", ",
+};
+
+#[cfg(feature = "intl-locales")]
+const DE_DE: LocaleData = LocaleData {
+    tag: "de-DE",
+    decimal: ',',
+    group: '.',
+    currency: SymbolPosition::SuffixSpace,
+    percent_space: Some(NBSP),
+    months: [
+        "Januar", "Februar", "März", "April", "Mai", "Juni",
+        "Juli", "August", "September", "Oktober", "November", "Dezember",
+    ],
+    months_short: [
+        "Jan.", "Feb.", "März", "Apr.", "Mai", "Juni",
+        "Juli", "Aug.", "Sept.", "Okt.", "Nov.", "Dez.",
+    ],
+    days: ["Sonntag", "Montag", "Dienstag", "Mittwoch", "Donnerstag", "Freitag", "Samstag"],
+    days_short: ["So.", "Mo.", "Di.", "Mi.", "Do.", "Fr.", "Sa."],
+    date: [
+        "{DD}.{MM}.{YYYY}",
+        "{DD}.{MM}.{YYYY}",
+        "{D}. {MMMM} {YYYY}",
+        "{dddd}, {D}. {MMMM} {YYYY}",
+    ],
+    time: ["{HH}:{mm}", "{HH}:{mm}:{ss}"],
+    date_time_separator: ", ",
+};
+
+#[cfg(feature = "intl-locales")]
+const FR_FR: LocaleData = LocaleData {
+    tag: "fr-FR",
+    decimal: ',',
+    group: '\u{202f}',
+    currency: SymbolPosition::SuffixSpace,
+    percent_space: Some('\u{202f}'),
+    months: [
+        "janvier", "février", "mars", "avril", "mai", "juin",
+        "juillet", "août", "septembre", "octobre", "novembre", "décembre",
+    ],
+    months_short: [
+        "janv.", "févr.", "mars", "avr.", "mai", "juin",
+        "juil.", "août", "sept.", "oct.", "nov.", "déc.",
+    ],
+    days: ["dimanche", "lundi", "mardi", "mercredi", "jeudi", "vendredi", "samedi"],
+    days_short: ["dim.", "lun.", "mar.", "mer.", "jeu.", "ven.", "sam."],
+    date: [
+        "{DD}/{MM}/{YYYY}",
+        "{D} {MMM} {YYYY}",
+        "{D} {MMMM} {YYYY}",
+        "{dddd} {D} {MMMM} {YYYY}",
+    ],
+    time: ["{HH}:{mm}", "{HH}:{mm}:{ss}"],
+    date_time_separator: " ",
+};
+
+#[cfg(feature = "intl-locales")]
+const ES_ES: LocaleData = LocaleData {
+    tag: "es-ES",
+    decimal: ',',
+    group: '.',
+    currency: SymbolPosition::SuffixSpace,
+    percent_space: Some(NBSP),
+    months: [
+        "enero", "febrero", "marzo", "abril", "mayo", "junio",
+        "julio", "agosto", "septiembre", "octubre", "noviembre", "diciembre",
+    ],
+    months_short: [
+        "ene", "feb", "mar", "abr", "may", "jun",
+        "jul", "ago", "sept", "oct", "nov", "dic",
+    ],
+    days: ["domingo", "lunes", "martes", "miércoles", "jueves", "viernes", "sábado"],
+    days_short: ["dom", "lun", "mar", "mié", "jue", "vie", "sáb"],
+    date: [
+        "{D}/{M}/{YYYY}",
+        "{D} {MMM} {YYYY}",
+        "{D} de {MMMM} de {YYYY}",
+        "{dddd}, {D} de {MMMM} de {YYYY}",
+    ],
+    time: ["{H}:{mm}", "{H}:{mm}:{ss}"],
+    date_time_separator: ", ",
+};
+
+#[cfg(feature = "intl-locales")]
+const IT_IT: LocaleData = LocaleData {
+    tag: "it-IT",
+    decimal: ',',
+    group: '.',
+    currency: SymbolPosition::SuffixSpace,
+    percent_space: None,
+    months: [
+        "gennaio", "febbraio", "marzo", "aprile", "maggio", "giugno",
+        "luglio", "agosto", "settembre", "ottobre", "novembre", "dicembre",
+    ],
+    months_short: [
+        "gen", "feb", "mar", "apr", "mag", "giu",
+        "lug", "ago", "set", "ott", "nov", "dic",
+    ],
+    days: ["domenica", "lunedì", "martedì", "mercoledì", "giovedì", "venerdì", "sabato"],
+    days_short: ["dom", "lun", "mar", "mer", "gio", "ven", "sab"],
+    date: [
+        "{DD}/{MM}/{YYYY}",
+        "{D} {MMM} {YYYY}",
+        "{D} {MMMM} {YYYY}",
+        "{dddd} {D} {MMMM} {YYYY}",
+    ],
+    time: ["{HH}:{mm}", "{HH}:{mm}:{ss}"],
+    date_time_separator: ", ",
+};
+
+#[cfg(feature = "intl-locales")]
+const PT_BR: LocaleData = LocaleData {
+    tag: "pt-BR",
+    decimal: ',',
+    group: '.',
+    currency: SymbolPosition::PrefixSpace,
+    percent_space: None,
+    months: [
+        "janeiro", "fevereiro", "março", "abril", "maio", "junho",
+        "julho", "agosto", "setembro", "outubro", "novembro", "dezembro",
+    ],
+    months_short: [
+        "jan.", "fev.", "mar.", "abr.", "mai.", "jun.",
+        "jul.", "ago.", "set.", "out.", "nov.", "dez.",
+    ],
+    days: [
+        "domingo", "segunda-feira", "terça-feira", "quarta-feira",
+        "quinta-feira", "sexta-feira", "sábado",
+    ],
+    days_short: ["dom.", "seg.", "ter.", "qua.", "qui.", "sex.", "sáb."],
+    date: [
+        "{DD}/{MM}/{YYYY}",
+        "{D} de {MMM} de {YYYY}",
+        "{D} de {MMMM} de {YYYY}",
+        "{dddd}, {D} de {MMMM} de {YYYY}",
+    ],
+    time: ["{HH}:{mm}", "{HH}:{mm}:{ss}"],
+    date_time_separator: ", ",
+};
+
+#[cfg(feature = "intl-locales")]
+const JA_JP: LocaleData = LocaleData {
+    tag: "ja-JP",
+    decimal: '.',
+    group: ',',
+    currency: SymbolPosition::Prefix,
+    percent_space: None,
+    months: [
+        "1月", "2月", "3月", "4月", "5月", "6月",
+        "7月", "8月", "9月", "10月", "11月", "12月",
+    ],
+    months_short: [
+        "1月", "2月", "3月", "4月", "5月", "6月",
+        "7月", "8月", "9月", "10月", "11月", "12月",
+    ],
+    days: ["日曜日", "月曜日", "火曜日", "水曜日", "木曜日", "金曜日", "土曜日"],
+    days_short: ["日", "月", "火", "水", "木", "金", "土"],
+    date: [
+        "{YYYY}/{MM}/{DD}",
+        "{YYYY}/{MM}/{DD}",
+        "{YYYY}年{M}月{D}日",
+        "{YYYY}年{M}月{D}日{dddd}",
+    ],
+    time: ["{H}:{mm}", "{H}:{mm}:{ss}"],
+    date_time_separator: " ",
+};
+
+/// Every locale compiled into this build, `en-US` first.
+#[cfg(feature = "intl-locales")]
+static LOCALES: &[LocaleData] = &[EN_US, EN_GB, DE_DE, FR_FR, ES_ES, IT_IT, PT_BR, JA_JP];
+
+/// Every locale compiled into this build, `en-US` first.
+#[cfg(not(feature = "intl-locales"))]
+static LOCALES: &[LocaleData] = &[EN_US];
+
+/// Currency code → (symbol, fraction digits). Unknown codes display as
+/// the code itself with two fraction digits.
+const CURRENCIES: &[(&str, &str, usize)] = &[
+    ("USD", "$", 2),
+    ("EUR", "€", 2),
+    ("GBP", "£", 2),
+    ("JPY", "¥", 0),
+    ("CNY", "CN¥", 2),
+    ("KRW", "₩", 0),
+    ("INR", "₹", 2),
+    ("CAD", "CA$", 2),
+    ("AUD", "A$", 2),
+    ("MXN", "MX$", 2),
+    ("BRL", "R$", 2),
+    ("CHF", "CHF", 2),
+];
+
+/* ============================================================================
+ * LOCALE RESOLUTION
+ * ============================================================================
+ */
+
+/// Resolves a locale tag to compiled-in data.
+///
+/// # Behavior
+/// - Accepts `de-DE`, `de_DE`, and POSIX forms like `de_DE.UTF-8`
+/// - Exact match first (case-insensitive), then same language
+/// - Falls back to `en-US`
+fn resolve_locale(tag: &str) -> &'static LocaleData {
+    let normalized = tag
+        .split(['.', '@'])
+        .next()
+        .unwrap_or("")
+        .replace('_', "-");
+
+    if let Some(data) = LOCALES.iter().find(|l| l.tag.eq_ignore_ascii_case(&normalized)) {
+        return data;
+    }
+
+    let language = normalized.split('-').next().unwrap_or("");
+
+    LOCALES
+        .iter()
+        .find(|l| {
+            l.tag
+                .split('-')
+                .next()
+                .is_some_and(|lang| lang.eq_ignore_ascii_case(language))
+        })
+        .unwrap_or(&LOCALES[0])
+}
+
+/// Locale taken from `LC_ALL` / `LC_MESSAGES` / `LANG`, else `en-US`.
+fn default_locale() -> &'static LocaleData {
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|key| std::env::var(key).ok())
+        .find(|v| !v.is_empty() && v != "C" && v != "POSIX")
+        .map(|v| resolve_locale(&v))
+        .unwrap_or(&LOCALES[0])
+}
+
+/* ============================================================================
+ * OPTIONS
+ * ============================================================================
+ */
+
+/// Parsed formatting options shared by every `Intl` function.
+///
+/// The options argument may be a locale string or an object:
+/// `{ locale, minimumFractionDigits, maximumFractionDigits, useGrouping,
+///    display, style, time, timeZone, width }`.
+struct Options {
+    locale: &'static LocaleData,
+    fields: HashMap<String, Value>,
+}
+
+impl Options {
+    fn parse(value: Option<&Value>, function: &str) -> Result<Options, PawxError> {
+        match value {
+            Some(Value::String(tag)) => Ok(Options {
+                locale: resolve_locale(tag),
+                fields: HashMap::new(),
+            }),
+            Some(Value::Object { fields }) => {
+                let fields = fields.borrow().clone();
+                let locale = match fields.get("locale") {
+                    Some(Value::String(tag)) => resolve_locale(tag),
+                    Some(Value::Null) | None => default_locale(),
+                    _ => return raise_error(format!("Intl.{}: locale must be a string", function)),
+                };
+                Ok(Options { locale, fields })
+            }
+            Some(Value::Null) | None => Ok(Options {
+                locale: default_locale(),
+                fields: HashMap::new(),
+            }),
+            _ => raise_error(format!("Intl.{}: options must be a locale string or an object", function)),
+        }
+    }
+
+    fn digits(&self, key: &str, default: usize, function: &str) -> Result<usize, PawxError> {
+        match self.fields.get(key) {
+            Some(Value::Number(n)) if (0.0..=20.0).contains(n) && n.fract() == 0.0 => Ok(*n as usize),
+            Some(Value::Null) | None => Ok(default),
+            _ => raise_error(format!("Intl.{}: {} must be an integer between 0 and 20", function, key)),
+        }
+    }
+
+    /// Reads `minimumFractionDigits` / `maximumFractionDigits`.
+    fn fraction_digits(&self, min: usize, max: usize, function: &str) -> Result<(usize, usize), PawxError> {
+        let min = self.digits("minimumFractionDigits", min, function)?;
+        let max = self.digits("maximumFractionDigits", max.max(min), function)?;
+
+        if min > max {
+            return raise_error(format!(
+                "Intl.{}: minimumFractionDigits ({}) exceeds maximumFractionDigits ({})",
+                function, min, max
+            ));
+        }
+
+        Ok((min, max))
+    }
+
+    fn grouping(&self) -> bool {
+        self.fields.get("useGrouping").is_none_or(Value::is_truthy)
+    }
+
+    fn string(&self, key: &str, function: &str) -> Result<Option<String>, PawxError> {
+        match self.fields.get(key) {
+            Some(Value::String(s)) => Ok(Some(s.to_string())),
+            Some(Value::Null) | None => Ok(None),
+            _ => raise_error(format!("Intl.{}: {} must be a string", function, key)),
+        }
+    }
+}
+
+/* ============================================================================
+ * NUMBERS
+ * ============================================================================
+ */
+
+/// Formats the magnitude of `n` with locale separators.
+///
+/// Rounds to `max_frac` digits, then trims trailing zeros down to
+/// `min_frac`. The sign is left to the caller.
+fn format_magnitude(n: f64, min_frac: usize, max_frac: usize, grouping: bool, locale: &LocaleData) -> String {
+    if n.is_nan() {
+        return "NaN".to_string();
+    }
+    if n.is_infinite() {
+        return "∞".to_string();
+    }
+
+    // Round half away from zero, as CLDR formatting does; `{:.N}` alone
+    // would round half to even.
+    let scale = 10f64.powi(max_frac as i32);
+    let fixed = format!("{:.*}", max_frac, (n.abs() * scale).round() / scale);
+    let (int_part, frac_part) = match fixed.split_once('.') {
+        Some((i, f)) => (i, f.to_string()),
+        None => (fixed.as_str(), String::new()),
+    };
+
+    let mut frac = frac_part;
+    while frac.len() > min_frac && frac.ends_with('0') {
+        frac.pop();
+    }
+
+    let mut out = String::new();
+    let digits = int_part.len();
+    for (i, c) in int_part.chars().enumerate() {
+        if grouping && i > 0 && (digits - i) % 3 == 0 {
+            out.push(locale.group);
+        }
+        out.push(c);
+    }
+
+    if !frac.is_empty() {
+        out.push(locale.decimal);
+        out.push_str(&frac);
+    }
+
+    out
+}
+
+/// Returns `true` if `n` prints with a minus sign after rounding.
+fn is_negative(n: f64, max_frac: usize) -> bool {
+    let scale = 10f64.powi(max_frac as i32);
+    n < 0.0 && (n.abs() * scale).round() != 0.0
+}
+
+fn number_arg(args: &[Value], function: &str) -> Result<f64, PawxError> {
+    match args.first() {
+        Some(Value::Number(n)) => Ok(*n),
+        _ => raise_error(format!("Intl.{}(n, ...) expects a number", function)),
+    }
+}
+
+/// Native implementation of `Intl.formatNumber(n, options?)`.
+///
+/// # Options
+/// - `locale`
+/// - `minimumFractionDigits` (default 0)
+/// - `maximumFractionDigits` (default 3)
+/// - `useGrouping` (default true)
+///
+/// # PAWX Example
+/// ```pawx
+/// meow(Intl.formatNumber(1234567.891, "de-DE"));   // 1.234.567,891
+/// ```
+fn intl_format_number(args: Vec<Value>) -> Result<Value, PawxError> {
+    let n = number_arg(&args, "formatNumber")?;
+    let options = Options::parse(args.get(1), "formatNumber")?;
+    let (min, max) = options.fraction_digits(0, 3, "formatNumber")?;
+
+    let body = format_magnitude(n, min, max, options.grouping(), options.locale);
+    let sign = if is_negative(n, max) { "-" } else { "" };
+
+    Ok(Value::String(format!("{}{}", sign, body).into()))
+}
+
+/// Native implementation of `Intl.formatCurrency(n, currency, options?)`.
+///
+/// # Options
+/// - `locale`
+/// - `display`: `"symbol"` (default) or `"code"`
+/// - `minimumFractionDigits` / `maximumFractionDigits` (default: the
+///   currency's own precision, e.g. 2 for USD, 0 for JPY)
+/// - `useGrouping`
+///
+/// # PAWX Example
+/// ```pawx
+/// meow(Intl.formatCurrency(1234.5, "EUR", "fr-FR"));   // 1 234,50 €
+/// ```
+fn intl_format_currency(args: Vec<Value>) -> Result<Value, PawxError> {
+    let n = number_arg(&args, "formatCurrency")?;
+
+    let code = match args.get(1) {
+        Some(Value::String(c)) if c.len() == 3 && c.chars().all(|c| c.is_ascii_alphabetic()) => {
+            c.to_ascii_uppercase()
+        }
+        _ => return raise_error("Intl.formatCurrency(n, currency, options?) expects an ISO 4217 code like \"USD\""),
+    };
+
+    let options = Options::parse(args.get(2), "formatCurrency")?;
+
+    let (symbol, precision) = CURRENCIES
+        .iter()
+        .find(|(c, _, _)| *c == code)
+        .map(|(_, s, p)| (s.to_string(), *p))
+        .unwrap_or_else(|| (code.clone(), 2));
+
+    let (symbol, position) = match options.string("display", "formatCurrency")?.as_deref() {
+        None | Some("symbol") => (symbol, options.locale.currency),
+        Some("code") => {
+            let position = match options.locale.currency {
+                SymbolPosition::SuffixSpace => SymbolPosition::SuffixSpace,
+                _ => SymbolPosition::PrefixSpace,
+            };
+            (code, position)
+        }
+        Some(other) => return raise_error(format!("Intl.formatCurrency: unknown display '{}' (use \"symbol\" or \"code\")", other)),
+    };
+
+    let (min, max) = options.fraction_digits(precision, precision, "formatCurrency")?;
+    let body = format_magnitude(n, min, max, options.grouping(), options.locale);
+    let sign = if is_negative(n, max) { "-" } else { "" };
+
+    // Letter symbols such as `CHF` are kept apart from the digits.
+    let position = match position {
+        SymbolPosition::Prefix if symbol.ends_with(|c: char| c.is_alphabetic()) => SymbolPosition::PrefixSpace,
+        other => other,
+    };
+
+    let formatted = match position {
+        SymbolPosition::Prefix => format!("{}{}{}", sign, symbol, body),
+        SymbolPosition::PrefixSpace => format!("{}{}{}{}", sign, symbol, NBSP, body),
+        SymbolPosition::SuffixSpace => format!("{}{}{}{}", sign, body, NBSP, symbol),
+    };
+
+    Ok(Value::String(formatted.into()))
+}
+
+/// Native implementation of `Intl.formatPercent(n, options?)`.
+///
+/// `n` is a ratio: `0.25` formats as `25%`.
+///
+/// # Options
+/// - `locale`
+/// - `minimumFractionDigits` (default 0)
+/// - `maximumFractionDigits` (default 0)
+/// - `useGrouping`
+fn intl_format_percent(args: Vec<Value>) -> Result<Value, PawxError> {
+    let n = number_arg(&args, "formatPercent")? * 100.0;
+    let options = Options::parse(args.get(1), "formatPercent")?;
+    let (min, max) = options.fraction_digits(0, 0, "formatPercent")?;
+
+    let body = format_magnitude(n, min, max, options.grouping(), options.locale);
+    let sign = if is_negative(n, max) { "-" } else { "" };
+
+    let formatted = match options.locale.percent_space {
+        Some(space) => format!("{}{}{}%", sign, body, space),
+        None => format!("{}{}%", sign, body),
+    };
+
+    Ok(Value::String(formatted.into()))
+}
+
+/* ============================================================================
+ * DATES
+ * ============================================================================
+ */
+
+/// Calendar fields of the instant being formatted.
+struct DateParts {
+    year: i32,
+    month: u32,
+    day: u32,
+    weekday: usize,
+    hour: u32,
+    minute: u32,
+    second: u32,
+}
+
+impl DateParts {
+    fn from_datetime<T: Datelike + Timelike>(dt: &T) -> DateParts {
+        DateParts {
+            year: dt.year(),
+            month: dt.month(),
+            day: dt.day(),
+            weekday: dt.weekday().num_days_from_sunday() as usize,
+            hour: dt.hour(),
+            minute: dt.minute(),
+            second: dt.second(),
+        }
+    }
+}
+
+/// Reads a date argument in the requested time zone.
+///
+/// # Accepted Inputs
+/// - Milliseconds since the Unix epoch (as returned by `Time.now()`)
+/// - RFC 3339 strings (as returned by `Time.utc()` / `Time.local()`)
+/// - Plain `YYYY-MM-DD` dates (formatted as-is, without zone conversion)
+fn date_arg(value: Option<&Value>, utc: bool, function: &str) -> Result<DateParts, PawxError> {
+    let instant: DateTime<Utc> = match value {
+        Some(Value::Number(ms)) => DateTime::from_timestamp_millis(*ms as i64)
+            .ok_or_else(|| builtin_error(format!("Intl.{}: timestamp {} is out of range", function, ms)))?,
+
+        Some(Value::String(s)) => {
+            if let Ok(date) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+                let midnight = date.and_hms_opt(0, 0, 0).expect("midnight is valid");
+                return Ok(DateParts::from_datetime(&midnight));
+            }
+
+            DateTime::parse_from_rfc3339(s)
+                .map_err(|e| builtin_error(format!("Intl.{}: invalid date '{}': {}", function, s, e)))?
+                .with_timezone(&Utc)
+        }
+
+        _ => return raise_error(format!("Intl.{}(date, ...) expects a timestamp or a date string", function)),
+    };
+
+    if utc {
+        Ok(DateParts::from_datetime(&instant))
+    } else {
+        Ok(DateParts::from_datetime(&instant.with_timezone(&Local)))
+    }
+}
+
+/// Expands a `{token}` date/time pattern.
+fn render_pattern(pattern: &str, parts: &DateParts, locale: &LocaleData) -> Result<String, PawxError> {
+    let mut out = String::new();
+    let mut rest = pattern;
+
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let end = rest[start..].find('}').map(|e| start + e).expect("unterminated pattern token");
+        let token = &rest[start + 1..end];
+
+        let hour12 = match parts.hour % 12 {
+            0 => 12,
+            h => h,
+        };
+
+        let piece = match token {
+            "YYYY" => parts.year.to_string(),
+            "MM" => format!("{:02}", parts.month),
+            "M" => parts.month.to_string(),
+            "DD" => format!("{:02}", parts.day),
+            "D" => parts.day.to_string(),
+            "MMMM" => locale.months[parts.month as usize - 1].to_string(),
+            "MMM" => locale.months_short[parts.month as usize - 1].to_string(),
+            "dddd" => locale.days[parts.weekday].to_string(),
+            "HH" => format!("{:02}", parts.hour),
+            "H" => parts.hour.to_string(),
+            "h" => hour12.to_string(),
+            "mm" => format!("{:02}", parts.minute),
+            "ss" => format!("{:02}", parts.second),
+            "A" => if parts.hour < 12 { "AM" } else { "PM" }.to_string(),
+            other => return raise_error(format!("unknown pattern token '{}'", other)),
+        };
+
+        out.push_str(&piece);
+        rest = &rest[end + 1..];
+    }
+
+    out.push_str(rest);
+    Ok(out)
+}
+
+fn date_style_index(style: &str, function: &str) -> Result<usize, PawxError> {
+    match style {
+        "short" => Ok(0),
+        "medium" => Ok(1),
+        "long" => Ok(2),
+        "full" => Ok(3),
+        other => raise_error(format!(
+            "Intl.{}: unknown style '{}' (use short, medium, long or full)",
+            function, other
+        )),
+    }
+}
+
+fn time_pattern(locale: &LocaleData, seconds: bool) -> &'static str {
+    locale.time[seconds as usize]
+}
+
+fn uses_utc(options: &Options, function: &str) -> Result<bool, PawxError> {
+    match options.string("timeZone", function)?.as_deref() {
+        None | Some("local") => Ok(false),
+        Some(zone) if zone.eq_ignore_ascii_case("utc") => Ok(true),
+        Some(other) => raise_error(format!("Intl.{}: unsupported timeZone '{}' (use \"local\" or \"utc\")", function, other)),
+    }
+}
+
+/// Native implementation of `Intl.formatDate(date, options?)`.
+///
+/// # Options
+/// - `locale`
+/// - `style`: `"short"`, `"medium"` (default), `"long"`, or `"full"`
+/// - `time`: `true` / `"short"` to append the time, `"medium"` to include
+///   seconds
+/// - `timeZone`: `"local"` (default) or `"utc"`
+///
+/// # PAWX Example
+/// ```pawx
+/// meow(Intl.formatDate(Time.now(), { locale: "de-DE", style: "full" }));
+/// // Freitag, 5. Januar 2024
+/// ```
+fn intl_format_date(args: Vec<Value>) -> Result<Value, PawxError> {
+    let options = Options::parse(args.get(1), "formatDate")?;
+    let locale = options.locale;
+    let parts = date_arg(args.first(), uses_utc(&options, "formatDate")?, "formatDate")?;
+
+    let style = options.string("style", "formatDate")?.unwrap_or_else(|| "medium".to_string());
+    let mut out = render_pattern(locale.date[date_style_index(&style, "formatDate")?], &parts, locale)?;
+
+    let time = match options.fields.get("time") {
+        Some(Value::String(s)) if &**s == "short" => Some(false),
+        Some(Value::String(s)) if &**s == "medium" => Some(true),
+        Some(Value::String(s)) => return raise_error(format!("Intl.formatDate: unknown time style '{}' (use short or medium)", s)),
+        Some(v) if v.is_truthy() => Some(false),
+        _ => None,
+    };
+
+    if let Some(seconds) = time {
+        out.push_str(locale.date_time_separator);
+        out.push_str(&render_pattern(time_pattern(locale, seconds), &parts, locale)?);
+    }
+
+    Ok(Value::String(out.into()))
+}
+
+/// Native implementation of `Intl.formatTime(date, options?)`.
+///
+/// # Options
+/// - `locale`
+/// - `seconds`: include seconds (default false)
+/// - `timeZone`: `"local"` (default) or `"utc"`
+fn intl_format_time(args: Vec<Value>) -> Result<Value, PawxError> {
+    let options = Options::parse(args.get(1), "formatTime")?;
+    let parts = date_arg(args.first(), uses_utc(&options, "formatTime")?, "formatTime")?;
+    let seconds = options.fields.get("seconds").is_some_and(Value::is_truthy);
+
+    Ok(Value::String(render_pattern(time_pattern(options.locale, seconds), &parts, options.locale)?.into()))
+}
+
+/* ============================================================================
+ * NAMES
+ * ============================================================================
+ */
+
+fn name_array(names: &[&str]) -> Value {
+    Value::Array {
+        values: Rc::new(RefCell::new(
+            names.iter().map(|n| Value::String(n.to_string().into())).collect(),
+        )),
+        proto: create_array_proto(),
+    }
+}
+
+/// Reads `width`: `"long"` (default) or `"short"`.
+fn short_width(options: &Options, function: &str) -> Result<bool, PawxError> {
+    match options.string("width", function)?.as_deref() {
+        None | Some("long") => Ok(false),
+        Some("short") => Ok(true),
+        Some(other) => raise_error(format!("Intl.{}: unknown width '{}' (use long or short)", function, other)),
+    }
+}
+
+/// Native implementation of `Intl.monthNames(options?)`.
+fn intl_month_names(args: Vec<Value>) -> Result<Value, PawxError> {
+    let options = Options::parse(args.first(), "monthNames")?;
+
+    if short_width(&options, "monthNames")? {
+        Ok(name_array(&options.locale.months_short))
+    } else {
+        Ok(name_array(&options.locale.months))
+    }
+}
+
+/// Native implementation of `Intl.dayNames(options?)`. Sunday first.
+fn intl_day_names(args: Vec<Value>) -> Result<Value, PawxError> {
+    let options = Options::parse(args.first(), "dayNames")?;
+
+    if short_width(&options, "dayNames")? {
+        Ok(name_array(&options.locale.days_short))
+    } else {
+        Ok(name_array(&options.locale.days))
+    }
+}
+
+/* ============================================================================
+ * GLOBAL INTL NAMESPACE
+ * ============================================================================
+ */
+
+/// Creates and returns the global `Intl` namespace for the PAWX runtime.
+///
+/// # Installed Functions
+/// - `Intl.locales()` → Locale tags compiled into this build
+/// - `Intl.defaultLocale()` → Locale used when none is given
+/// - `Intl.resolve(locale)` → Locale tag that `locale` falls back to
+/// - `Intl.formatNumber` / `formatCurrency` / `formatPercent`
+/// - `Intl.formatDate` / `formatTime`
+/// - `Intl.monthNames` / `dayNames`
+///
+/// # Returns
+/// A `HashMap<String, Value>` representing the `Intl` global namespace.
+pub fn create_global_intl_object() -> HashMap<String, Value> {
+    let mut intl = HashMap::new();
+
+    intl.insert(
+        "locales".to_string(),
+        Value::NativeFunction(Arc::new(|_args: Vec<Value>| -> Result<Value, PawxError> {
+            Ok(name_array(&LOCALES.iter().map(|l| l.tag).collect::<Vec<_>>()))
+        })),
+    );
+
+    intl.insert(
+        "defaultLocale".to_string(),
+        Value::NativeFunction(Arc::new(|_args: Vec<Value>| -> Result<Value, PawxError> {
+            Ok(Value::String(default_locale().tag.into()))
+        })),
+    );
+
+    intl.insert(
+        "resolve".to_string(),
+        Value::NativeFunction(Arc::new(|args: Vec<Value>| -> Result<Value, PawxError> {
+            match args.first() {
+                Some(Value::String(tag)) => Ok(Value::String(resolve_locale(tag).tag.into())),
+                _ => raise_error("Intl.resolve(locale) expects a locale string"),
+            }
+        })),
+    );
+
+    intl.insert("formatNumber".to_string(), Value::NativeFunction(Arc::new(intl_format_number)));
+    intl.insert("formatCurrency".to_string(), Value::NativeFunction(Arc::new(intl_format_currency)));
+    intl.insert("formatPercent".to_string(), Value::NativeFunction(Arc::new(intl_format_percent)));
+    intl.insert("formatDate".to_string(), Value::NativeFunction(Arc::new(intl_format_date)));
+    intl.insert("formatTime".to_string(), Value::NativeFunction(Arc::new(intl_format_time)));
+    intl.insert("monthNames".to_string(), Value::NativeFunction(Arc::new(intl_month_names)));
+    intl.insert("dayNames".to_string(), Value::NativeFunction(Arc::new(intl_day_names)));
+
+    intl
+}
@@ -0,0 +1,162 @@
+/*
+ * ============================================================================
+ * PAWX - Code with Claws! 🐾
+ * ============================================================================
+ *
+ * Standard Input for PAWX (`Io`, `listen`)
+ *
+ * Supports:
+ *   - Io.readLine(prompt?)   the next line of stdin without its line ending,
+ *                            or null at the end of input
+ *   - listen(prompt?)        the same, as a global: meow's counterpart
+ *   - Io.readAll()           the rest of stdin as one string
+ *   - Io.lines()             the rest of stdin as an array of lines
+ *   - Io.isTerminal()        true when stdin is an interactive terminal
+ *
+ *   snuggle name = listen("What's your name? ");
+ *   meow("Hello, $!", name);
+ *
+ *   Io.lines().forEach((line) -> { meow(line.toUpperCase()); });
+ *
+ * A prompt is written to stdout (without a newline) before reading, and
+ * stdout is flushed so it shows up first. Input is read as UTF-8; invalid
+ * bytes are replaced rather than failing the read. All readers share
+ * one buffer, so mixing them never loses input.
+ *
+ * The REPL reads stdin for its own prompt, so these are meant for
+ * programs run from a file or a pipe.
+ *
+ * --------------------------------------------------------------------------
+ * Author:   Sam Wilcox
+ * Email:    sam@pawx-lang.com
+ * Website:  https://www.pawx-lang.com
+ * GitHub:   https://github.com/samwilcox/pawx
+ *
+ * License:
+ * This file is part of the PAWX programming language project.
+ *
+ * PAWX is dual-licensed under the terms of:
+ *   - The MIT License
+ *   - The Apache License, Version 2.0
+ *
+ * You may choose either license to govern your use of this software.
+ * Full license text available at:
+ *     https://license.pawx-lang.com
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under these licenses is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *
+ * ==========================================================================
+ */
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::io::{self, BufRead, IsTerminal, Read, Write};
+use std::rc::Rc;
+use std::sync::Arc;
+
+use crate::error::PawxError;
+use crate::interpreter::calls::raise_error;
+use crate::prototypes::array::create_array_proto;
+use crate::value::Value;
+
+fn native(f: impl Fn(Vec<Value>) -> Result<Value, PawxError> + 'static) -> Value {
+    Value::NativeFunction(Arc::new(f))
+}
+
+/// Writes `prompt` (if given) and flushes stdout.
+fn show_prompt(prompt: Option<&Value>, usage: &str) -> Result<(), PawxError> {
+    match prompt {
+        None | Some(Value::Null) => Ok(()),
+        Some(Value::String(s)) => {
+            print!("{}", s);
+            let _ = io::stdout().flush();
+            Ok(())
+        }
+        Some(_) => raise_error(format!("{} expects the prompt to be a string", usage)),
+    }
+}
+
+/// Reads one line from stdin without its `\n` / `\r\n`; `None` at the end
+/// of input.
+fn read_line(usage: &str) -> Result<Option<String>, PawxError> {
+    let mut bytes = Vec::new();
+    match io::stdin().lock().read_until(b'\n', &mut bytes) {
+        Ok(0) => Ok(None),
+        Ok(_) => {
+            if bytes.ends_with(b"\n") {
+                bytes.pop();
+                if bytes.ends_with(b"\r") {
+                    bytes.pop();
+                }
+            }
+            Ok(Some(String::from_utf8_lossy(&bytes).into_owned()))
+        }
+        Err(e) => raise_error(format!("{}: cannot read stdin: {}", usage, e)),
+    }
+}
+
+fn read_all(usage: &str) -> Result<String, PawxError> {
+    let mut bytes = Vec::new();
+    if let Err(e) = io::stdin().lock().read_to_end(&mut bytes) {
+        return raise_error(format!("{}: cannot read stdin: {}", usage, e));
+    }
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+fn line_value(usage: &'static str) -> Value {
+    native(move |args| {
+        show_prompt(args.first(), usage)?;
+        match read_line(usage)? {
+            Some(line) => Ok(Value::String(line.into())),
+            None => Ok(Value::Null),
+        }
+    })
+}
+
+/// Creates the global `listen(prompt?)` function.
+pub fn create_global_listen_value() -> Value {
+    line_value("listen(prompt?)")
+}
+
+/// Creates the global `Io` object.
+///
+/// # PAWX Example
+/// ```pawx
+/// snuggle answer = Io.readLine("Continue? [y/n] ");
+/// if (answer == "y") { meow("ok"); }
+/// ```
+pub fn create_global_io_object() -> HashMap<String, Value> {
+    let mut io = HashMap::new();
+
+    io.insert("readLine".to_string(), line_value("Io.readLine(prompt?)"));
+
+    io.insert(
+        "readAll".to_string(),
+        native(|_| Ok(Value::String(read_all("Io.readAll()")?.into()))),
+    );
+
+    io.insert(
+        "lines".to_string(),
+        native(|_| {
+            let text = read_all("Io.lines()")?;
+            let lines = text
+                .lines()
+                .map(|line| Value::String(line.into()))
+                .collect();
+
+            Ok(Value::Array {
+                values: Rc::new(RefCell::new(lines)),
+                proto: create_array_proto(),
+            })
+        }),
+    );
+
+    io.insert(
+        "isTerminal".to_string(),
+        native(|_| Ok(Value::Bool(io::stdin().is_terminal()))),
+    );
+
+    io
+}
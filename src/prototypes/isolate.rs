@@ -0,0 +1,305 @@
+/*
+ * ============================================================================
+ * PAWX - Code with Claws! 🐾
+ * ============================================================================
+ *
+ * Isolates for PAWX (`Isolate`)
+ *
+ * Only compiled with the `isolates` cargo feature (on by default).
+ *
+ * Supports:
+ *   - Isolate.spawn(path, args?)      -> isolate running a script file
+ *   - Isolate.spawnSource(code)       -> isolate running a source string
+ *   - isolate.send(message)
+ *   - isolate.receive(ms?)            -> next message, or null on timeout
+ *                                        or once the isolate has finished
+ *   - isolate.close()                 no more messages will be sent
+ *   - isolate.join()                  -> null, or an Error if it failed
+ *
+ * Inside an isolate:
+ *   - Isolate.isWorker                -> true
+ *   - Isolate.receive(ms?)            -> next message from the parent, or
+ *                                        null on timeout or once closed
+ *   - Isolate.post(message)           send a message to the parent
+ *
+ * Values are built on `Rc` / `RefCell` and never leave the thread that
+ * created them. An isolate is a complete interpreter (its own globals,
+ * timers and module cache) on its own OS thread, and isolates only talk
+ * by message passing.
+ *
+ * Messages travel in `Codec` form, so anything `Codec.encode` accepts can
+ * be sent and arrives as an exact copy. Functions, classes, instances and
+ * other handles cannot be sent, and neither can cyclic values.
+ *
+ * Isolates spawned by a `--deterministic` program are deterministic too,
+ * with the same seed.
+ *
+ * --------------------------------------------------------------------------
+ * Author:   Sam Wilcox
+ * Email:    sam@pawx-lang.com
+ * Website:  https://www.pawx-lang.com
+ * GitHub:   https://github.com/samwilcox/pawx
+ *
+ * License:
+ * This file is part of the PAWX programming language project.
+ *
+ * PAWX is dual-licensed under the terms of:
+ *   - The MIT License
+ *   - The Apache License, Version 2.0
+ *
+ * You may choose either license to govern your use of this software.
+ * Full license text available at:
+ *     https://license.pawx-lang.com
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under these licenses is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *
+ * ============================================================================
+ */
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crate::error::PawxError;
+use crate::interpreter::calls::{builtin_error, raise_error};
+use crate::interpreter::limits;
+use crate::interpreter::prelude::Prelude;
+use crate::interpreter::testing::panic_message;
+use crate::prototypes::codec::{self, Mode};
+use crate::value::Value;
+
+fn native(f: impl Fn(Vec<Value>) -> Result<Value, PawxError> + 'static) -> Value {
+    Value::NativeFunction(Arc::new(f))
+}
+
+fn object(fields: HashMap<String, Value>) -> Value {
+    Value::Object {
+        fields: Rc::new(RefCell::new(fields)),
+    }
+}
+
+/* ============================================================================
+ * MESSAGES
+ * ============================================================================
+ */
+
+/// A message on its way between threads, in `Codec` form.
+///
+/// The bytes own all of their data, so the two sides of a channel never
+/// share anything mutable.
+type Message = Vec<u8>;
+
+fn share(value: Option<&Value>, usage: &str) -> Result<Message, PawxError> {
+    codec::encode(value.unwrap_or(&Value::Null), Mode::Pawx).map_err(|e| builtin_error(format!("{}: {}", usage, e)))
+}
+
+fn timeout(value: Option<&Value>) -> Option<Duration> {
+    match value {
+        Some(Value::Number(ms)) if *ms >= 0.0 => Some(Duration::from_millis(*ms as u64)),
+        _ => None,
+    }
+}
+
+/// Waits for the next message; `null` on timeout or once the sender is gone.
+fn receive(rx: &Receiver<Message>, timeout: Option<Duration>, usage: &str) -> Result<Value, PawxError> {
+    let message = match timeout {
+        Some(t) => rx.recv_timeout(t).ok(),
+        None => rx.recv().ok(),
+    };
+    match message {
+        Some(bytes) => codec::decode(&bytes, Mode::Pawx).map_err(|e| builtin_error(format!("{}: {}", usage, e))),
+        None => Ok(Value::Null),
+    }
+}
+
+/* ============================================================================
+ * WORKER SIDE
+ * ============================================================================
+ */
+
+/// The channels an isolate uses to talk to its parent.
+struct Port {
+    inbox: Receiver<Message>,
+    outbox: Sender<Message>,
+}
+
+thread_local! {
+    /// Set on isolate threads before their program starts.
+    static PORT: RefCell<Option<Port>> = const { RefCell::new(None) };
+}
+
+fn with_port<T>(usage: &str, f: impl FnOnce(&Port) -> T) -> Result<T, PawxError> {
+    PORT.with(|p| Ok(match p.borrow().as_ref() {
+        Some(port) => f(port),
+        None => return raise_error(format!("{} can only be used inside an isolate", usage)),
+    }))
+}
+
+/* ============================================================================
+ * PARENT SIDE
+ * ============================================================================
+ */
+
+struct Isolate {
+    to_worker: RefCell<Option<Sender<Message>>>,
+    from_worker: Receiver<Message>,
+    thread: RefCell<Option<JoinHandle<Result<(), String>>>>,
+}
+
+impl Isolate {
+    /// Starts `source` on a new thread with the standard prelude.
+    fn spawn(source: String, argv: Vec<String>) -> Result<Rc<Isolate>, PawxError> {
+        let (to_worker, inbox) = mpsc::channel();
+        let (outbox, from_worker) = mpsc::channel();
+
+        // A deterministic program spawns deterministic isolates, and one
+        // that may not eval spawns isolates that may not either. The depth
+        // limit carries over as well.
+        let seed = crate::interpreter::determinism::seed();
+        let eval = crate::prototypes::eval::enabled();
+        let max_depth = limits::max_depth();
+
+        let thread = thread::Builder::new()
+            .name("pawx-isolate".to_string())
+            .stack_size(limits::STACK_SIZE)
+            .spawn(move || {
+                PORT.with(|p| *p.borrow_mut() = Some(Port { inbox, outbox }));
+                let mut prelude = Prelude::new().with_argv(argv).with_max_depth(max_depth);
+                if let Some(seed) = seed {
+                    prelude = prelude.with_deterministic(seed);
+                }
+                if !eval {
+                    prelude = prelude.without_eval();
+                }
+
+                // Reported by `join`, like any other failure in the isolate.
+                crate::ast_cache::parse(&source)
+                    .and_then(|statements| crate::interpreter::run_with_prelude(statements, &prelude))
+                    .map_err(|error| error.to_string())
+            })
+            .map_err(|e| builtin_error(format!("Isolate.spawn: {}", e)))?;
+
+        Ok(Rc::new(Isolate {
+            to_worker: RefCell::new(Some(to_worker)),
+            from_worker,
+            thread: RefCell::new(Some(thread)),
+        }))
+    }
+
+    fn send(&self, message: Message) {
+        // A finished isolate simply drops what it is sent.
+        if let Some(tx) = self.to_worker.borrow().as_ref() {
+            let _ = tx.send(message);
+        }
+    }
+
+    fn close(&self) {
+        self.to_worker.borrow_mut().take();
+    }
+
+    /// Closes the isolate's inbox and waits for its program to end.
+    fn join(&self) -> Value {
+        self.close();
+        match self.thread.borrow_mut().take().map(JoinHandle::join) {
+            Some(Ok(Err(message))) => Value::Error { message },
+            Some(Err(payload)) => Value::Error {
+                message: panic_message(payload.as_ref()),
+            },
+            _ => Value::Null,
+        }
+    }
+}
+
+/// Creates the PAWX object for a spawned isolate.
+fn isolate_value(isolate: Rc<Isolate>) -> Value {
+    let mut fields = HashMap::new();
+
+    let i = isolate.clone();
+    fields.insert(
+        "send".to_string(),
+        native(move |args| {
+            i.send(share(args.first(), "isolate.send(message)")?);
+            Ok(Value::Null)
+        }),
+    );
+
+    let i = isolate.clone();
+    fields.insert(
+        "receive".to_string(),
+        native(move |args| receive(&i.from_worker, timeout(args.first()), "isolate.receive(ms?)")),
+    );
+
+    let i = isolate.clone();
+    fields.insert(
+        "close".to_string(),
+        native(move |_| {
+            i.close();
+            Ok(Value::Null)
+        }),
+    );
+
+    let i = isolate;
+    fields.insert("join".to_string(), native(move |_| Ok(i.join())));
+
+    object(fields)
+}
+
+/// Builds the global `Isolate` object.
+pub fn create_global_isolate_object() -> HashMap<String, Value> {
+    let mut isolate = HashMap::new();
+
+    isolate.insert(
+        "spawn".to_string(),
+        native(|args| {
+            let path = match args.first() {
+                Some(Value::String(p)) => p.to_string(),
+                _ => return raise_error("Isolate.spawn(path, args?) expects a script path"),
+            };
+            let source = std::fs::read_to_string(&path).map_err(|e| builtin_error(format!("Isolate.spawn: {}: {}", path, e)))?;
+
+            let mut argv = vec![path];
+            if let Some(Value::Array { values, .. }) = args.get(1) {
+                argv.extend(values.borrow().iter().map(crate::interpreter::display::value_to_string));
+            }
+            Ok(isolate_value(Isolate::spawn(source, argv)?))
+        }),
+    );
+
+    isolate.insert(
+        "spawnSource".to_string(),
+        native(|args| match args.first() {
+            Some(Value::String(code)) => Ok(isolate_value(Isolate::spawn(code.to_string(), vec!["<isolate>".to_string()])?)),
+            _ => raise_error("Isolate.spawnSource(code) expects a string"),
+        }),
+    );
+
+    isolate.insert(
+        "isWorker".to_string(),
+        Value::Bool(PORT.with(|p| p.borrow().is_some())),
+    );
+
+    isolate.insert(
+        "receive".to_string(),
+        native(|args| with_port("Isolate.receive()", |port| receive(&port.inbox, timeout(args.first()), "Isolate.receive(ms?)"))?),
+    );
+
+    isolate.insert(
+        "post".to_string(),
+        native(|args| {
+            let message = share(args.first(), "Isolate.post(message)")?;
+            with_port("Isolate.post()", |port| {
+                // The parent may have stopped listening; that is not an error.
+                let _ = port.outbox.send(message);
+            })?;
+            Ok(Value::Null)
+        }),
+    );
+
+    isolate
+}
@@ -0,0 +1,239 @@
+/*
+ * ============================================================================
+ * PAWX - Code with Claws! 🐾
+ * ============================================================================
+ *
+ * JSON for PAWX (`Json`)
+ *
+ * Supports:
+ *   - Json.stringify(value, indent?)
+ *   - Json.parse(text)
+ *   - Json.parseAs(text, Target)
+ *
+ * Serialization hooks:
+ *   - An object with a `toJson` function, or a class instance with a
+ *     `toJson()` method, is serialized as whatever that hook returns.
+ *   - Instances without `toJson()` serialize their fields.
+ *
+ * These hooks apply everywhere PAWX produces JSON, including Http
+ * responses, so domain objects need no manual field copying.
+ *
+ * `Json.parseAs(text, Target)` parses `text` and hands the result to:
+ *   - `Target.fromJson(obj)` when the class (or object) defines it
+ *   - otherwise, for a class, a new instance whose fields are the class
+ *     defaults overlaid with the parsed keys
+ *   - a plain function, called as `Target(obj)`
+ *
+ * A top-level JSON array is revived element by element.
+ *
+ * --------------------------------------------------------------------------
+ * Author:   Sam Wilcox
+ * Email:    sam@pawx-lang.com
+ * Website:  https://www.pawx-lang.com
+ * GitHub:   https://github.com/samwilcox/pawx
+ *
+ * License:
+ * This file is part of the PAWX programming language project.
+ *
+ * PAWX is dual-licensed under the terms of:
+ *   - The MIT License
+ *   - The Apache License, Version 2.0
+ *
+ * You may choose either license to govern your use of this software.
+ * Full license text available at:
+ *     https://license.pawx-lang.com
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under these licenses is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *
+ * ==========================================================================
+ */
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use crate::error::PawxError;
+use crate::interpreter::calls::{builtin_error, raise_error};
+use crate::interpreter::classes::{call_member, call_static};
+use crate::interpreter::global_env;
+use crate::prototypes::array::create_array_proto;
+use crate::prototypes::http::json_to_value;
+use crate::prototypes::json_writer;
+use crate::value::Value;
+
+fn native(f: impl Fn(Vec<Value>) -> Result<Value, PawxError> + 'static) -> Value {
+    Value::NativeFunction(Arc::new(f))
+}
+
+/* ============================================================================
+ * SERIALIZATION HOOKS
+ * ============================================================================
+ */
+
+/// Returns the value that should be serialized in place of `val`, if any.
+///
+/// - Objects with a `toJson` function → the function's result
+/// - Instances with a `toJson()` method → the method's result
+/// - Instances without one → a plain object of their fields
+///
+/// The replacement itself is serialized without consulting its own hook,
+/// so a `toJson` that returns `this` cannot recurse forever.
+///
+/// # Errors
+/// If the `toJson` function or method fails.
+pub fn to_json_hook(val: &Value) -> Result<Option<Value>, String> {
+    match val {
+        Value::Object { fields } => {
+            let hook = match fields.borrow().get("toJson") {
+                Some(Value::NativeFunction(f)) => f.clone(),
+                _ => return Ok(None),
+            };
+
+            hook(vec![]).map(Some).map_err(|e| format!("toJson() failed: {}", e.message))
+        }
+
+        Value::Instance { class, fields } => match class.find_method("toJson") {
+            Some((method, owner)) => call_member(method, &owner, val.clone(), vec![], global_env())
+                .map(Some)
+                .map_err(|e| format!("toJson() failed: {}", e.message)),
+            None => Ok(Some(Value::Object {
+                fields: Rc::new(RefCell::new(fields.borrow().clone())),
+            })),
+        },
+
+        _ => Ok(None),
+    }
+}
+
+/* ============================================================================
+ * REVIVING
+ * ============================================================================
+ */
+
+/// Turns one parsed JSON value into a `Target` value (see module docs).
+///
+/// # Errors
+/// If `target` cannot build values, or a class without `fromJson` is given
+/// something other than an object.
+fn revive(parsed: Value, target: &Value) -> Result<Value, PawxError> {
+    if let Value::Array { values, .. } = &parsed {
+        let revived = values.borrow().iter().map(|v| revive(v.clone(), target)).collect::<Result<_, _>>()?;
+
+        return Ok(Value::Array {
+            values: Rc::new(RefCell::new(revived)),
+            proto: create_array_proto(),
+        });
+    }
+
+    match target {
+        Value::Class(class) => {
+            let name = &class.name;
+            if let Some((from_json, owner)) = class.find_static_method("fromJson") {
+                return call_static(from_json, &owner, vec![parsed], global_env())
+                    .map_err(|e| builtin_error(format!("{}.fromJson() failed: {}", name, e.message)));
+            }
+            if let Some((from_json, owner)) = class.find_method("fromJson") {
+                return call_member(from_json, &owner, target.clone(), vec![parsed], global_env())
+                    .map_err(|e| builtin_error(format!("{}.fromJson() failed: {}", name, e.message)));
+            }
+
+            let Value::Object { fields: parsed_fields } = parsed else {
+                return raise_error(format!(
+                    "Json.parseAs(text, {}): expected a JSON object, got {}",
+                    name,
+                    parsed.type_name()
+                ));
+            };
+
+            let mut instance_fields = class.fields.clone();
+            for (k, v) in parsed_fields.borrow().iter() {
+                instance_fields.insert(k.clone(), v.clone());
+            }
+
+            Ok(Value::Instance {
+                class: class.clone(),
+                fields: Rc::new(RefCell::new(instance_fields)),
+            })
+        }
+
+        Value::Object { fields } => match fields.borrow().get("fromJson") {
+            Some(Value::NativeFunction(f)) => f(vec![parsed]),
+            _ => raise_error("Json.parseAs(text, Target): object target has no fromJson function"),
+        },
+
+        Value::NativeFunction(f) => f(vec![parsed]),
+
+        other => raise_error(format!(
+            "Json.parseAs(text, Target): cannot build values from {}",
+            other.type_name()
+        )),
+    }
+}
+
+/* ============================================================================
+ * GLOBAL OBJECT
+ * ============================================================================
+ */
+
+/// Parses the JSON text in `args[0]`.
+///
+/// # Errors
+/// If the argument is not a string or not valid JSON.
+pub(crate) fn parse_text(args: &[Value], usage: &str) -> Result<Value, PawxError> {
+    let text = match args.first() {
+        Some(Value::String(s)) => s,
+        _ => return raise_error(format!("{} expects a string", usage)),
+    };
+
+    match serde_json::from_str::<serde_json::Value>(text) {
+        Ok(json) => Ok(json_to_value(json)),
+        Err(e) => raise_error(format!("{}: invalid JSON: {}", usage, e)),
+    }
+}
+
+/// Builds the global `Json` object.
+pub fn create_global_json_object() -> HashMap<String, Value> {
+    let mut json = HashMap::new();
+
+    // Json.stringify(value, indent?)
+    json.insert(
+        "stringify".to_string(),
+        native(|args| {
+            let value = args.first().cloned().unwrap_or(Value::Null);
+
+            let text = match args.get(1) {
+                Some(Value::Number(n)) if *n > 0.0 => json_writer::to_json_pretty(&value, *n as usize),
+                _ => json_writer::to_json(&value),
+            };
+
+            match text {
+                Ok(text) => Ok(Value::String(text.into())),
+                Err(message) => raise_error(message),
+            }
+        }),
+    );
+
+    // Json.parse(text)
+    json.insert(
+        "parse".to_string(),
+        native(|args| parse_text(&args, "Json.parse(text)")),
+    );
+
+    // Json.parseAs(text, Target)
+    json.insert(
+        "parseAs".to_string(),
+        native(|args| {
+            let parsed = parse_text(&args, "Json.parseAs(text, Target)")?;
+
+            match args.get(1) {
+                Some(target) => Ok(revive(parsed, target)?),
+                None => raise_error("Json.parseAs(text, Target) expects a target class or function"),
+            }
+        }),
+    );
+
+    json
+}
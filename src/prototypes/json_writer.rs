@@ -0,0 +1,317 @@
+/*
+ * ============================================================================
+ * PAWX - Code with Claws! 🐾
+ * ============================================================================
+ *
+ * Direct JSON Writer for PAWX Values
+ *
+ * Writes a `Value` straight to JSON text, without building an intermediate
+ * `serde_json::Value`. Every place PAWX produces JSON goes through it:
+ *   - Json.stringify(value, indent?)
+ *   - Fs.writeJson(path, value, pretty?)
+ *   - res.json(data) and other HTTP server responses
+ *   - value_to_json (assertion messages, mocks, RPC, ...)
+ *
+ * Output rules:
+ *   - `toJson` hooks apply at every level (see `prototypes::json`)
+ *   - object keys are written in sorted order, so equal values always
+ *     produce the same bytes (stable ETags, snapshots and diffs)
+ *   - numbers that are not finite are written as `null`
+ *   - decimals and regexes are written as strings, tuples, typed arrays and
+ *     immutable lists as arrays, immutable maps as objects
+ *   - anything else (functions, classes, ...) as a marker string such as
+ *     `"[function]"`
+ *
+ * The writer is built for the HTTP hot path: the output buffer starts at
+ * the size of the previous document written on this thread, integers skip
+ * float formatting, and strings with nothing to escape are copied whole.
+ * Containers are walked with an explicit stack, and one nested deeper than
+ * `--max-depth` is an error rather than a stack overflow.
+ *
+ * --------------------------------------------------------------------------
+ * Author:   Sam Wilcox
+ * Email:    sam@pawx-lang.com
+ * Website:  https://www.pawx-lang.com
+ * GitHub:   https://github.com/samwilcox/pawx
+ *
+ * License:
+ * This file is part of the PAWX programming language project.
+ *
+ * PAWX is dual-licensed under the terms of:
+ *   - The MIT License
+ *   - The Apache License, Version 2.0
+ *
+ * You may choose either license to govern your use of this software.
+ * Full license text available at:
+ *     https://license.pawx-lang.com
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under these licenses is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *
+ * ==========================================================================
+ */
+
+use std::cell::Cell;
+use std::fmt::Write;
+
+use crate::interpreter::limits;
+use crate::prototypes::json::to_json_hook;
+use crate::value::Value;
+
+/// Integers below this magnitude are written without float formatting.
+const EXACT_INTEGER: f64 = 9_007_199_254_740_992.0; // 2^53
+
+/// Bytes that must be escaped inside a JSON string.
+const ESCAPE: [bool; 256] = {
+    let mut table = [false; 256];
+    let mut b = 0;
+    while b < 0x20 {
+        table[b] = true;
+        b += 1;
+    }
+    table[b'"' as usize] = true;
+    table[b'\\' as usize] = true;
+    table
+};
+
+thread_local! {
+    /// Length of the last document written, used to size the next buffer.
+    static LAST_LEN: Cell<usize> = const { Cell::new(64) };
+}
+
+/* ============================================================================
+ * PUBLIC API
+ * ============================================================================
+ */
+
+/// Serializes `val` as compact JSON.
+///
+/// # Errors
+/// If `val` is nested deeper than the depth limit.
+pub fn to_json(val: &Value) -> Result<String, String> {
+    write_document(val, None)
+}
+
+/// Serializes `val` as JSON, one element per line, indented by `indent`
+/// spaces per level.
+///
+/// # Errors
+/// If `val` is nested deeper than the depth limit.
+pub fn to_json_pretty(val: &Value, indent: usize) -> Result<String, String> {
+    write_document(val, Some(indent))
+}
+
+/// Appends `s` to `out` as a quoted, escaped JSON string.
+pub fn write_string(s: &str, out: &mut String) {
+    out.push('"');
+
+    let bytes = s.as_bytes();
+    let mut start = 0;
+
+    for (i, &b) in bytes.iter().enumerate() {
+        if !ESCAPE[b as usize] {
+            continue;
+        }
+
+        // Everything since the last escape goes in one copy
+        out.push_str(&s[start..i]);
+        match b {
+            b'"' => out.push_str("\\\""),
+            b'\\' => out.push_str("\\\\"),
+            b'\n' => out.push_str("\\n"),
+            b'\r' => out.push_str("\\r"),
+            b'\t' => out.push_str("\\t"),
+            0x08 => out.push_str("\\b"),
+            0x0C => out.push_str("\\f"),
+            _ => {
+                let _ = write!(out, "\\u{:04x}", b);
+            }
+        }
+        start = i + 1;
+    }
+
+    out.push_str(&s[start..]);
+    out.push('"');
+}
+
+/* ============================================================================
+ * WRITER
+ * ============================================================================
+ */
+
+fn write_document(val: &Value, indent: Option<usize>) -> Result<String, String> {
+    let capacity = LAST_LEN.with(Cell::get);
+    let mut writer = Writer {
+        out: String::with_capacity(capacity),
+        indent,
+        stack: Vec::new(),
+    };
+
+    writer.value(val)?;
+
+    // Containers are written from an explicit stack rather than by
+    // recursion, so deep values cannot overflow the Rust stack.
+    while let Some(frame) = writer.stack.last_mut() {
+        let first = std::mem::replace(&mut frame.first, false);
+
+        match frame.next() {
+            Some((key, item)) => {
+                writer.separator(first);
+                if let Some(key) = key {
+                    write_string(&key, &mut writer.out);
+                    writer.out.push_str(if writer.indent.is_some() { ": " } else { ":" });
+                }
+                writer.value(&item)?;
+            }
+            None => {
+                let close = frame.close;
+                writer.stack.pop();
+                writer.newline();
+                writer.out.push(close);
+            }
+        }
+    }
+
+    LAST_LEN.with(|len| len.set(writer.out.len()));
+    Ok(writer.out)
+}
+
+/// What is left to write of a container.
+enum Rest {
+    Items(std::vec::IntoIter<Value>),
+    Entries(std::vec::IntoIter<(String, Value)>),
+}
+
+/// A container being written.
+struct Frame {
+    rest: Rest,
+    first: bool,
+    close: char,
+}
+
+impl Frame {
+    /// The next element, with its key for objects.
+    fn next(&mut self) -> Option<(Option<String>, Value)> {
+        match &mut self.rest {
+            Rest::Items(items) => items.next().map(|v| (None, v)),
+            Rest::Entries(entries) => entries.next().map(|(k, v)| (Some(k), v)),
+        }
+    }
+}
+
+struct Writer {
+    out: String,
+    /// Spaces per level, or `None` for compact output.
+    indent: Option<usize>,
+    /// Open containers, innermost last.
+    stack: Vec<Frame>,
+}
+
+impl Writer {
+    fn value(&mut self, val: &Value) -> Result<(), String> {
+        match to_json_hook(val)? {
+            Some(replaced) => self.body(&replaced),
+            None => self.body(val),
+        }
+    }
+
+    /// Writes a scalar, or opens a container whose elements the loop in
+    /// [`write_document`] writes next.
+    fn body(&mut self, val: &Value) -> Result<(), String> {
+        match val {
+            Value::Null => self.out.push_str("null"),
+            Value::Bool(b) => self.out.push_str(if *b { "true" } else { "false" }),
+            Value::Number(n) => self.number(*n),
+            Value::String(s) => write_string(s, &mut self.out),
+
+            // Decimals travel as strings so no digits are lost
+            Value::Decimal(d) => {
+                let _ = write!(self.out, "\"{}\"", d);
+            }
+
+            // Durations travel as their length in milliseconds
+            Value::Duration(ms) => self.number(*ms),
+
+            Value::Regex(r) => write_string(&format!("/{}/{}", r.as_str(), r.flags()), &mut self.out),
+
+            Value::Array { values, .. } => return self.array(values.borrow().clone()),
+            Value::Tuple(values) => return self.array(values.clone()),
+            Value::ImmutableList(list) => return self.array(list.iter().cloned().collect()),
+            Value::Float64Array(data) => return self.array(data.borrow().iter().map(|n| Value::Number(*n)).collect()),
+
+            Value::Object { fields } => {
+                let mut entries: Vec<(String, Value)> =
+                    fields.borrow().iter().map(|(k, v)| (k.clone(), v.clone())).collect();
+                entries.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+                return self.object(entries);
+            }
+
+            // OrdMap iterates in key order already
+            Value::ImmutableMap(map) => return self.object(map.iter().map(|(k, v)| (k.clone(), v.clone())).collect()),
+
+            Value::Error { message } => write_string(&format!("Error({})", message), &mut self.out),
+
+            Value::NativeFunction(_) => self.out.push_str("\"[function]\""),
+            Value::Class(class) => write_string(&format!("[class {}]", class.name), &mut self.out),
+            Value::Instinct(instinct) => write_string(&format!("[instinct {}]", instinct.name), &mut self.out),
+            Value::Instance { class, .. } => write_string(&format!("[instance {}]", class.name), &mut self.out),
+            Value::Furure(_) | Value::PendingFurure(_) => self.out.push_str("\"[future]\""),
+            Value::Module { .. } => self.out.push_str("\"[module]\""),
+            Value::CancelToken(_) => self.out.push_str("\"[CancelToken]\""),
+        }
+        Ok(())
+    }
+
+    fn number(&mut self, n: f64) {
+        if !n.is_finite() {
+            self.out.push_str("null");
+        } else if n.fract() == 0.0 && n.abs() < EXACT_INTEGER {
+            let _ = write!(self.out, "{}", n as i64);
+        } else {
+            let _ = write!(self.out, "{}", n);
+        }
+    }
+
+    fn array(&mut self, items: Vec<Value>) -> Result<(), String> {
+        if items.is_empty() {
+            self.out.push_str("[]");
+            return Ok(());
+        }
+        self.open('[', Rest::Items(items.into_iter()), ']')
+    }
+
+    fn object(&mut self, entries: Vec<(String, Value)>) -> Result<(), String> {
+        if entries.is_empty() {
+            self.out.push_str("{}");
+            return Ok(());
+        }
+        self.open('{', Rest::Entries(entries.into_iter()), '}')
+    }
+
+    /// # Errors
+    /// If the container is nested deeper than the depth limit.
+    fn open(&mut self, open: char, rest: Rest, close: char) -> Result<(), String> {
+        limits::check_nesting(self.stack.len() + 1, "JSON encoding")?;
+
+        self.out.push(open);
+        self.stack.push(Frame { rest, first: true, close });
+        Ok(())
+    }
+
+    /// Writes what goes before an element of a container.
+    fn separator(&mut self, first: bool) {
+        if !first {
+            self.out.push(',');
+        }
+        self.newline();
+    }
+
+    /// Starts a new line at the current depth (pretty output only).
+    fn newline(&mut self) {
+        if let Some(indent) = self.indent {
+            self.out.push('\n');
+            self.out.extend(std::iter::repeat_n(' ', indent * self.stack.len()));
+        }
+    }
+}
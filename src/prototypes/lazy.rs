@@ -0,0 +1,277 @@
+/*
+ * ============================================================================
+ * PAWX - Code with Claws! 🐾
+ * ============================================================================
+ *
+ * Lazy Iterator Chains for PAWX (`arr.iter()`)
+ *
+ * Supports:
+ *   - arr.iter()                      a lazy view of the array
+ *   - it.map(fn), it.filter(fn)       add a step; nothing runs yet
+ *   - it.take(n), it.skip(n)
+ *   - it.collect() / it.toArray()     run the chain into a new array
+ *   - it.forEach(fn), it.reduce(fn, initial?)
+ *   - it.first(), it.count()
+ *
+ * `arr.map(f).filter(g).slice(0, 10)` builds two full arrays before it
+ * keeps ten elements. The same chain on `arr.iter()` builds none: each
+ * element goes through every step in turn, straight into the result, and
+ * the run stops as soon as `take` (or `first`) has what it needs, so `f`
+ * and `g` are only called on the elements that get that far.
+ *
+ *   snuggle top = big.iter().map(f).filter(g).take(10).collect();
+ *
+ * Adding a step returns a new iterator, so a chain can be reused and
+ * branched. The array is read when the chain runs, not when `iter()` is
+ * called.
+ *
+ * --------------------------------------------------------------------------
+ * Author:   Sam Wilcox
+ * Email:    sam@pawx-lang.com
+ * Website:  https://www.pawx-lang.com
+ * GitHub:   https://github.com/samwilcox/pawx
+ *
+ * License:
+ * This file is part of the PAWX programming language project.
+ *
+ * PAWX is dual-licensed under the terms of:
+ *   - The MIT License
+ *   - The Apache License, Version 2.0
+ *
+ * You may choose either license to govern your use of this software.
+ * Full license text available at:
+ *     https://license.pawx-lang.com
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under these licenses is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *
+ * ==========================================================================
+ */
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use crate::error::PawxError;
+use crate::interpreter::calls::{builtin_error, raise_error};
+use crate::prototypes::array::create_array_proto;
+use crate::value::{NativeFn, Value};
+
+type Callback = Arc<NativeFn>;
+
+/// One step of a chain.
+#[derive(Clone)]
+enum Stage {
+    Map(Callback),
+    Filter(Callback),
+    Take(usize),
+    Skip(usize),
+}
+
+/// An array and the steps to run its elements through.
+#[derive(Clone)]
+struct Chain {
+    source: Rc<RefCell<Vec<Value>>>,
+    stages: Vec<Stage>,
+}
+
+fn method(f: impl Fn(Vec<Value>) -> Result<Value, PawxError> + 'static) -> Value {
+    Value::NativeFunction(Arc::new(f))
+}
+
+fn callback(arg: Option<&Value>, usage: &str) -> Result<Callback, PawxError> {
+    match arg {
+        Some(Value::NativeFunction(f)) => Ok(f.clone()),
+        _ => raise_error(format!("{} expects a function", usage)),
+    }
+}
+
+fn count(arg: Option<&Value>, usage: &str) -> Result<usize, PawxError> {
+    match arg {
+        Some(Value::Number(n)) if *n >= 0.0 => Ok(n.trunc() as usize),
+        _ => raise_error(format!("{} expects a non-negative number", usage)),
+    }
+}
+
+impl Chain {
+    fn with(&self, stage: Stage) -> Value {
+        let mut next = self.clone();
+        next.stages.push(stage);
+        iterator_value(next)
+    }
+
+    /// Runs every element through the chain, handing the survivors to
+    /// `sink` until it returns `false` or a `take` is used up.
+    ///
+    /// # Errors
+    /// The first error from a step's callback or from `sink`.
+    fn run(&self, mut sink: impl FnMut(Value) -> Result<bool, PawxError>) -> Result<(), PawxError> {
+        if self.stages.iter().any(|s| matches!(s, Stage::Take(0))) {
+            return Ok(());
+        }
+
+        // How many elements have reached each take / skip so far
+        let mut seen = vec![0usize; self.stages.len()];
+        let mut index = 0;
+
+        'elements: loop {
+            // Borrow per element, so callbacks may push to the array
+            let Some(mut value) = self.source.borrow().get(index).cloned() else {
+                return Ok(());
+            };
+            index += 1;
+
+            let mut last = false;
+            for (i, stage) in self.stages.iter().enumerate() {
+                match stage {
+                    Stage::Map(f) => value = f(vec![value])?,
+                    Stage::Filter(f) => {
+                        if !f(vec![value.clone()])?.is_truthy() {
+                            continue 'elements;
+                        }
+                    }
+                    Stage::Skip(n) => {
+                        if seen[i] < *n {
+                            seen[i] += 1;
+                            continue 'elements;
+                        }
+                    }
+                    Stage::Take(n) => {
+                        // A later step may have dropped the n-th element
+                        if seen[i] == *n {
+                            return Ok(());
+                        }
+                        seen[i] += 1;
+                        last |= seen[i] == *n;
+                    }
+                }
+            }
+
+            if !sink(value)? || last {
+                return Ok(());
+            }
+        }
+    }
+}
+
+/// Native implementation of `Array.prototype.iter()`.
+pub fn array_iter(args: Vec<Value>) -> Result<Value, PawxError> {
+    match args.first() {
+        Some(Value::Array { values, .. }) => Ok(iterator_value(Chain {
+            source: values.clone(),
+            stages: Vec::new(),
+        })),
+        _ => raise_error("iter() must be called on an array"),
+    }
+}
+
+/// Builds the PAWX object for a chain.
+fn iterator_value(chain: Chain) -> Value {
+    let mut fields = HashMap::new();
+
+    let c = chain.clone();
+    fields.insert(
+        "map".to_string(),
+        method(move |args| Ok(c.with(Stage::Map(callback(args.first(), "it.map(fn)")?)))),
+    );
+
+    let c = chain.clone();
+    fields.insert(
+        "filter".to_string(),
+        method(move |args| Ok(c.with(Stage::Filter(callback(args.first(), "it.filter(fn)")?)))),
+    );
+
+    let c = chain.clone();
+    fields.insert(
+        "take".to_string(),
+        method(move |args| Ok(c.with(Stage::Take(count(args.first(), "it.take(n)")?)))),
+    );
+
+    let c = chain.clone();
+    fields.insert(
+        "skip".to_string(),
+        method(move |args| Ok(c.with(Stage::Skip(count(args.first(), "it.skip(n)")?)))),
+    );
+
+    let collect = {
+        let c = chain.clone();
+        method(move |_| {
+            let mut out = Vec::new();
+            c.run(|v| {
+                out.push(v);
+                Ok(true)
+            })?;
+
+            Ok(Value::Array {
+                values: Rc::new(RefCell::new(out)),
+                proto: create_array_proto(),
+            })
+        })
+    };
+    fields.insert("toArray".to_string(), collect.clone());
+    fields.insert("collect".to_string(), collect);
+
+    let c = chain.clone();
+    fields.insert(
+        "forEach".to_string(),
+        method(move |args| {
+            let f = callback(args.first(), "it.forEach(fn)")?;
+            c.run(|v| {
+                f(vec![v])?;
+                Ok(true)
+            })?;
+            Ok(Value::Null)
+        }),
+    );
+
+    let c = chain.clone();
+    fields.insert(
+        "reduce".to_string(),
+        method(move |args| {
+            let f = callback(args.first(), "it.reduce(fn, initial?)")?;
+            let mut acc = args.get(1).cloned();
+
+            c.run(|v| {
+                acc = Some(match acc.take() {
+                    Some(a) => f(vec![a, v])?,
+                    None => v,
+                });
+                Ok(true)
+            })?;
+
+            acc.ok_or_else(|| builtin_error("it.reduce(fn) of an empty iterator needs an initial value"))
+        }),
+    );
+
+    let c = chain.clone();
+    fields.insert(
+        "first".to_string(),
+        method(move |_| {
+            let mut first = Value::Null;
+            c.run(|v| {
+                first = v;
+                Ok(false)
+            })?;
+            Ok(first)
+        }),
+    );
+
+    let c = chain;
+    fields.insert(
+        "count".to_string(),
+        method(move |_| {
+            let mut n = 0usize;
+            c.run(|_| {
+                n += 1;
+                Ok(true)
+            })?;
+            Ok(Value::Number(n as f64))
+        }),
+    );
+
+    Value::Object {
+        fields: Rc::new(RefCell::new(fields)),
+    }
+}
@@ -0,0 +1,479 @@
+/*
+ * ============================================================================
+ * PAWX - Code with Claws! 🐾
+ * ============================================================================
+ *
+ * Rate Limiter + Semaphore for PAWX
+ *
+ * Supports:
+ *   - RateLimit.perSecond(n) / perMinute(n) / create({ limit, interval })
+ *   - Semaphore(n)
+ *
+ * `RateLimit` is a token bucket: `acquire()` waits for the next free slot
+ * (sleeping, or advancing the clock under `--virtual-time`) and never spins.
+ *
+ * `Semaphore` is FIFO: when no permit is free, `acquire()` returns a pending
+ * Furure that settles as soon as a permit is released on the interpreter
+ * thread.
+ *
+ * --------------------------------------------------------------------------
+ * Author:   Sam Wilcox
+ * Email:    sam@pawx-lang.com
+ * Website:  https://www.pawx-lang.com
+ * GitHub:   https://github.com/samwilcox/pawx
+ *
+ * License:
+ * This file is part of the PAWX programming language project.
+ *
+ * PAWX is dual-licensed under the terms of:
+ *   - The MIT License
+ *   - The Apache License, Version 2.0
+ *
+ * You may choose either license to govern your use of this software.
+ * Full license text available at:
+ *     https://license.pawx-lang.com
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under these licenses is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *
+ * ==========================================================================
+ */
+
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, VecDeque};
+use std::rc::Rc;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::error::PawxError;
+use crate::interpreter::calls::raise_error;
+use crate::interpreter::clock::VirtualClock;
+use crate::prototypes::cancel::{cancellable, take_cancel_token};
+use crate::prototypes::furure::PendingFurure;
+use crate::value::Value;
+
+fn object(fields: HashMap<String, Value>) -> Value {
+    Value::Object {
+        fields: Rc::new(RefCell::new(fields)),
+    }
+}
+
+fn native(f: impl Fn(Vec<Value>) -> Result<Value, PawxError> + 'static) -> Value {
+    Value::NativeFunction(Arc::new(f))
+}
+
+/* ============================================================================
+ * RATE LIMITER
+ * ============================================================================
+ */
+
+/// Token bucket allowing `limit` acquisitions per `interval_ms`.
+struct RateLimiter {
+    limit: f64,
+    interval_ms: f64,
+    tokens: f64,
+    last_ms: u64,
+    clock: Option<VirtualClock>,
+    started: Instant,
+}
+
+impl RateLimiter {
+    fn new(limit: u64, interval_ms: u64, clock: Option<VirtualClock>) -> Self {
+        let mut limiter = Self {
+            limit: limit as f64,
+            interval_ms: interval_ms as f64,
+            tokens: limit as f64,
+            last_ms: 0,
+            clock,
+            started: Instant::now(),
+        };
+        limiter.last_ms = limiter.now_ms();
+        limiter
+    }
+
+    fn now_ms(&self) -> u64 {
+        match &self.clock {
+            Some(clock) => clock.now(),
+            None => self.started.elapsed().as_millis() as u64,
+        }
+    }
+
+    fn refill(&mut self) {
+        let now = self.now_ms();
+        let elapsed = now.saturating_sub(self.last_ms) as f64;
+        self.tokens = (self.tokens + elapsed * self.limit / self.interval_ms).min(self.limit);
+        self.last_ms = now;
+    }
+
+    /// Takes a token if one is free.
+    fn try_acquire(&mut self) -> bool {
+        self.refill();
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Milliseconds until the next token is free.
+    fn wait_ms(&mut self) -> u64 {
+        self.refill();
+        if self.tokens >= 1.0 {
+            0
+        } else {
+            ((1.0 - self.tokens) * self.interval_ms / self.limit).ceil() as u64
+        }
+    }
+}
+
+/// Waits `ms` without spinning: advances the virtual clock (firing due
+/// timers) or sleeps the thread.
+fn wait(clock: &Option<VirtualClock>, ms: u64) -> Result<(), PawxError> {
+    match clock {
+        Some(clock) => clock.advance(ms),
+        None => {
+            std::thread::sleep(Duration::from_millis(ms));
+            Ok(())
+        }
+    }
+}
+
+/// Builds the PAWX object for a rate limiter.
+///
+/// # Installed Functions
+/// - `acquire(token?)` → Furure(ms waited)
+/// - `tryAcquire()`    → bool, never waits
+/// - `available()`     → whole tokens currently free
+fn create_rate_limit_value(limit: u64, interval_ms: u64, clock: Option<VirtualClock>) -> Value {
+    let limiter = Rc::new(RefCell::new(RateLimiter::new(limit, interval_ms, clock)));
+    let mut fields = HashMap::new();
+
+    let l = limiter.clone();
+    fields.insert(
+        "acquire".to_string(),
+        cancellable(Arc::new(move |_args: Vec<Value>| -> Result<Value, PawxError> {
+            let mut waited = 0;
+
+            loop {
+                let ms = l.borrow_mut().wait_ms();
+                if ms == 0 {
+                    break;
+                }
+
+                // The limiter must not stay borrowed while the clock fires
+                // timers that may use it.
+                let clock = l.borrow().clock.clone();
+                wait(&clock, ms)?;
+                waited += ms;
+            }
+
+            l.borrow_mut().tokens -= 1.0;
+            Ok(Value::Furure(Box::new(Value::Number(waited as f64))))
+        })),
+    );
+
+    let l = limiter.clone();
+    fields.insert(
+        "tryAcquire".to_string(),
+        native(move |_args| Ok(Value::Bool(l.borrow_mut().try_acquire()))),
+    );
+
+    let l = limiter;
+    fields.insert(
+        "available".to_string(),
+        native(move |_args| {
+            let mut limiter = l.borrow_mut();
+            limiter.refill();
+            Ok(Value::Number(limiter.tokens.floor()))
+        }),
+    );
+
+    object(fields)
+}
+
+fn positive_count(value: Option<&Value>, method: &str) -> Result<u64, PawxError> {
+    match value {
+        Some(Value::Number(n)) if *n >= 1.0 => Ok(*n as u64),
+        _ => raise_error(format!("{} requires a positive number", method)),
+    }
+}
+
+/// Creates the global `RateLimit` object.
+///
+/// # Installed Functions
+/// - `RateLimit.perSecond(n)`
+/// - `RateLimit.perMinute(n)`
+/// - `RateLimit.create({ limit, interval })` → `interval` in ms
+///
+/// # PAWX Example
+/// ```pawx
+/// snuggle limit = RateLimit.perSecond(5);
+/// urls.forEach(url -> {
+///     limit.acquire();
+///     client.request(url);
+/// });
+/// ```
+pub fn create_global_rate_limit_object(clock: Option<&VirtualClock>) -> HashMap<String, Value> {
+    let mut map = HashMap::new();
+
+    for (name, interval_ms) in [("perSecond", 1_000), ("perMinute", 60_000)] {
+        let clock = clock.cloned();
+        map.insert(
+            name.to_string(),
+            native(move |args: Vec<Value>| -> Result<Value, PawxError> {
+                let limit = positive_count(args.first(), &format!("RateLimit.{}(n)", name))?;
+                Ok(create_rate_limit_value(limit, interval_ms, clock.clone()))
+            }),
+        );
+    }
+
+    let clock = clock.cloned();
+    map.insert(
+        "create".to_string(),
+        native(move |args: Vec<Value>| -> Result<Value, PawxError> {
+            let method = "RateLimit.create({ limit, interval })";
+            let fields = match args.first() {
+                Some(Value::Object { fields }) => fields.borrow().clone(),
+                _ => return raise_error(format!("{} expects an options object", method)),
+            };
+
+            let limit = positive_count(fields.get("limit"), &format!("{}: limit", method))?;
+            let interval = positive_count(fields.get("interval"), &format!("{}: interval", method))?;
+
+            Ok(create_rate_limit_value(limit, interval, clock.clone()))
+        }),
+    );
+
+    map
+}
+
+/* ============================================================================
+ * SEMAPHORE
+ * ============================================================================
+ */
+
+/// Shared semaphore state.
+struct SemaphoreState {
+    permits: usize,
+    available: usize,
+    waiters: VecDeque<(u64, PendingFurure)>,
+    next_waiter: u64,
+}
+
+type Semaphore = Rc<RefCell<SemaphoreState>>;
+
+/// Returns one permit: hands it to the oldest waiter, or frees it.
+fn release(sem: &Semaphore) -> Result<(), PawxError> {
+    let next = {
+        let mut state = sem.borrow_mut();
+        match state.waiters.pop_front() {
+            Some((_, waiter)) => Some(waiter),
+            None => {
+                if state.available >= state.permits {
+                    return raise_error("Semaphore.release(): released more permits than were acquired");
+                }
+                state.available += 1;
+                None
+            }
+        }
+    };
+
+    // Run the waiter's callbacks without holding the borrow.
+    if let Some(waiter) = next {
+        waiter.settle(release_handle(sem))?;
+    }
+    Ok(())
+}
+
+/// A function releasing exactly one permit, however often it is called.
+fn release_handle(sem: &Semaphore) -> Value {
+    let sem = sem.clone();
+    let released = Cell::new(false);
+
+    native(move |_args| {
+        if !released.replace(true) {
+            release(&sem)?;
+        }
+        Ok(Value::Null)
+    })
+}
+
+/// Acquires a permit.
+///
+/// # Returns
+/// - `Furure(release)` if a permit was free
+/// - A pending Furure settling with `release` once a permit is handed
+///   over, or with a `CancelledError` if the token is cancelled first
+fn acquire(sem: &Semaphore, token: Option<crate::prototypes::cancel::CancelToken>) -> Result<Value, PawxError> {
+    if let Some(token) = &token {
+        if token.is_cancelled() {
+            return Ok(Value::Furure(Box::new(token.to_error())));
+        }
+    }
+
+    {
+        let mut state = sem.borrow_mut();
+        if state.available > 0 && state.waiters.is_empty() {
+            state.available -= 1;
+            drop(state);
+            return Ok(Value::Furure(Box::new(release_handle(sem))));
+        }
+    }
+
+    let furure = PendingFurure::new();
+    let id = {
+        let mut state = sem.borrow_mut();
+        let id = state.next_waiter;
+        state.next_waiter += 1;
+        state.waiters.push_back((id, furure.clone()));
+        id
+    };
+
+    if let Some(token) = token {
+        let sem = sem.clone();
+        let waiter = furure.clone();
+        let t = token.clone();
+
+        token.on_cancel(native(move |_args| {
+            let removed = {
+                let mut state = sem.borrow_mut();
+                let before = state.waiters.len();
+                state.waiters.retain(|(w, _)| *w != id);
+                state.waiters.len() != before
+            };
+
+            if removed {
+                waiter.settle(t.to_error())?;
+            }
+            Ok(Value::Null)
+        }))?;
+    }
+
+    Ok(Value::PendingFurure(furure))
+}
+
+/// Builds the PAWX object for a semaphore.
+///
+/// # Installed Functions
+/// - `acquire(token?)` → Furure(release), pending while no permit is free
+/// - `release()`       → returns one permit
+/// - `tryAcquire()`    → bool, never queues
+/// - `run(fn)`         → Furure(fn's result); `fn` runs holding a permit,
+///   which is released afterwards, and a throw rejects the Furure
+/// - `available()` / `waiting()`
+fn create_semaphore_value(permits: usize) -> Value {
+    let sem: Semaphore = Rc::new(RefCell::new(SemaphoreState {
+        permits,
+        available: permits,
+        waiters: VecDeque::new(),
+        next_waiter: 0,
+    }));
+
+    let mut fields = HashMap::new();
+
+    let s = sem.clone();
+    fields.insert(
+        "acquire".to_string(),
+        native(move |args: Vec<Value>| -> Result<Value, PawxError> {
+            let (_, token) = take_cancel_token(args);
+            acquire(&s, token)
+        }),
+    );
+
+    let s = sem.clone();
+    fields.insert(
+        "release".to_string(),
+        native(move |_args| {
+            release(&s)?;
+            Ok(Value::Null)
+        }),
+    );
+
+    let s = sem.clone();
+    fields.insert(
+        "tryAcquire".to_string(),
+        native(move |_args| {
+            let mut state = s.borrow_mut();
+            if state.available > 0 && state.waiters.is_empty() {
+                state.available -= 1;
+                Ok(Value::Bool(true))
+            } else {
+                Ok(Value::Bool(false))
+            }
+        }),
+    );
+
+    let s = sem.clone();
+    fields.insert(
+        "run".to_string(),
+        native(move |args: Vec<Value>| -> Result<Value, PawxError> {
+            let task = match args.first() {
+                Some(Value::NativeFunction(f)) => f.clone(),
+                _ => return raise_error("Semaphore.run(fn) expects a function"),
+            };
+
+            let result = PendingFurure::new();
+            let r = result.clone();
+
+            let on_permit = native(move |args: Vec<Value>| -> Result<Value, PawxError> {
+                let release = args.into_iter().next().unwrap_or(Value::Null);
+                // A failing task rejects the result instead of the caller
+                // that happened to release the permit.
+                let value = task(vec![]).unwrap_or_else(PawxError::into_value);
+
+                if let Value::NativeFunction(release) = release {
+                    release(vec![])?;
+                }
+
+                r.settle(value)?;
+                Ok(Value::Null)
+            });
+
+            match acquire(&s, None)? {
+                Value::Furure(release) => {
+                    if let Value::NativeFunction(f) = &on_permit {
+                        f(vec![*release])?;
+                    }
+                    Ok(Value::Furure(Box::new(result.value().unwrap_or(Value::Null))))
+                }
+                Value::PendingFurure(permit) => {
+                    permit.then(on_permit)?;
+                    Ok(Value::PendingFurure(result))
+                }
+                other => Ok(other),
+            }
+        }),
+    );
+
+    let s = sem.clone();
+    fields.insert(
+        "available".to_string(),
+        native(move |_args| Ok(Value::Number(s.borrow().available as f64))),
+    );
+
+    let s = sem;
+    fields.insert(
+        "waiting".to_string(),
+        native(move |_args| Ok(Value::Number(s.borrow().waiters.len() as f64))),
+    );
+
+    object(fields)
+}
+
+/// Creates the global `Semaphore` constructor.
+///
+/// # PAWX Example
+/// ```pawx
+/// snuggle sem = Semaphore(2);
+/// sem.acquire().then(release -> {
+///     meow("working");
+///     release();
+/// });
+/// ```
+pub fn create_global_semaphore_value() -> Value {
+    native(|args: Vec<Value>| -> Result<Value, PawxError> {
+        Ok(create_semaphore_value(positive_count(args.first(), "Semaphore(n)")? as usize))
+    })
+}
@@ -0,0 +1,169 @@
+/*
+ * ============================================================================
+ * PAWX - Code with Claws! 🐾
+ * ============================================================================
+ *
+ * Markdown for PAWX (`Markdown`)
+ *
+ * Supports:
+ *   - Markdown.toHtml(text)    -> string
+ *   - Markdown.parse(text)     -> { data, body, html }
+ *
+ * Rendering follows CommonMark with the GitHub extensions for tables,
+ * strikethrough, task lists and footnotes.
+ *
+ * `Markdown.parse` also reads an optional front matter block at the top of
+ * the document:
+ *
+ *   ---
+ *   title: Hello
+ *   draft: false
+ *   ---
+ *
+ * Each `key: value` line becomes a field of `data`. `true` / `false` and
+ * numbers are converted; everything else stays a string (surrounding
+ * quotes are removed).
+ *
+ * --------------------------------------------------------------------------
+ * Author:   Sam Wilcox
+ * Email:    sam@pawx-lang.com
+ * Website:  https://www.pawx-lang.com
+ * GitHub:   https://github.com/samwilcox/pawx
+ *
+ * License:
+ * This file is part of the PAWX programming language project.
+ *
+ * PAWX is dual-licensed under the terms of:
+ *   - The MIT License
+ *   - The Apache License, Version 2.0
+ *
+ * You may choose either license to govern your use of this software.
+ * Full license text available at:
+ *     https://license.pawx-lang.com
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under these licenses is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *
+ * ==========================================================================
+ */
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use pulldown_cmark::{html, Options, Parser};
+
+use crate::error::PawxError;
+use crate::interpreter::calls::raise_error;
+use crate::value::Value;
+
+fn native(f: impl Fn(Vec<Value>) -> Result<Value, PawxError> + 'static) -> Value {
+    Value::NativeFunction(Arc::new(f))
+}
+
+/// Renders Markdown to HTML.
+pub fn to_html(text: &str) -> String {
+    let options = Options::ENABLE_TABLES
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_TASKLISTS
+        | Options::ENABLE_FOOTNOTES;
+
+    let mut out = String::new();
+    html::push_html(&mut out, Parser::new_ext(text, options));
+    out
+}
+
+fn front_matter_value(raw: &str) -> Value {
+    let raw = raw.trim();
+
+    match raw {
+        "true" => return Value::Bool(true),
+        "false" => return Value::Bool(false),
+        _ => {}
+    }
+
+    if let Ok(n) = raw.parse::<f64>() {
+        return Value::Number(n);
+    }
+
+    let unquoted = raw
+        .strip_prefix('"')
+        .and_then(|r| r.strip_suffix('"'))
+        .or_else(|| raw.strip_prefix('\'').and_then(|r| r.strip_suffix('\'')))
+        .unwrap_or(raw);
+
+    Value::String(unquoted.into())
+}
+
+/// Splits a leading `---` front matter block from `text`.
+///
+/// # Returns
+/// The parsed `key: value` fields and the remaining body. Text without a
+/// front matter block yields no fields and the whole text.
+pub fn split_front_matter(text: &str) -> (HashMap<String, Value>, &str) {
+    let mut data = HashMap::new();
+
+    let Some(rest) = text.strip_prefix("---\n").or_else(|| text.strip_prefix("---\r\n")) else {
+        return (data, text);
+    };
+
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        offset += line.len();
+        let line = line.trim_end_matches(['\r', '\n']);
+
+        if line == "---" {
+            return (data, &rest[offset..]);
+        }
+
+        if let Some((key, value)) = line.split_once(':') {
+            data.insert(key.trim().to_string(), front_matter_value(value));
+        }
+    }
+
+    // An unterminated block is not front matter
+    (HashMap::new(), text)
+}
+
+fn text_arg(args: &[Value], usage: &str) -> Result<String, PawxError> {
+    match args.first() {
+        Some(Value::String(s)) => Ok(s.to_string()),
+        _ => raise_error(format!("{} expects a string", usage)),
+    }
+}
+
+/// Builds the global `Markdown` object.
+pub fn create_global_markdown_object() -> HashMap<String, Value> {
+    let mut markdown = HashMap::new();
+
+    markdown.insert(
+        "toHtml".to_string(),
+        native(|args| Ok(Value::String(to_html(&text_arg(&args, "Markdown.toHtml(text)")?).into()))),
+    );
+
+    markdown.insert(
+        "parse".to_string(),
+        native(|args| {
+            let text = text_arg(&args, "Markdown.parse(text)")?;
+            let (data, body) = split_front_matter(&text);
+
+            let mut fields = HashMap::new();
+            fields.insert(
+                "data".to_string(),
+                Value::Object {
+                    fields: Rc::new(RefCell::new(data)),
+                },
+            );
+            fields.insert("body".to_string(), Value::String(body.into()));
+            fields.insert("html".to_string(), Value::String(to_html(body).into()));
+
+            Ok(Value::Object {
+                fields: Rc::new(RefCell::new(fields)),
+            })
+        }),
+    );
+
+    markdown
+}
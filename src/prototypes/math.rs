@@ -47,8 +47,23 @@ use std::sync::Arc;
 use std::cell::RefCell;
 use std::rc::Rc;
 
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::error::PawxError;
+use crate::interpreter::calls::raise_error;
 use crate::value::Value;
 
+thread_local! {
+    /// Seeded generator used by `Math.random()` in deterministic mode.
+    static SEEDED_RNG: RefCell<Option<StdRng>> = const { RefCell::new(None) };
+}
+
+/// Makes `Math.random()` on this thread repeat the same sequence for `seed`.
+pub fn seed_random(seed: u64) {
+    SEEDED_RNG.with(|rng| *rng.borrow_mut() = Some(StdRng::seed_from_u64(seed)));
+}
+
 /// Creates and returns the global `Math` object for the PAWX runtime.
 ///
 /// This function installs all math constants and functions as
@@ -123,8 +138,8 @@ pub fn create_global_math_value() -> Value {
 /// ```pawx
 /// meow(Math.PI); // 3.141592653589793
 /// ```
-pub fn math_PI(args: Vec<Value>) -> Value {
-    Value::Number(std::f64::consts::PI)
+pub fn math_PI(args: Vec<Value>) -> Result<Value, PawxError> {
+    Ok(Value::Number(std::f64::consts::PI))
 }
 
 /// Native implementation of the mathematical constant `Math.E9` for PAWX.
@@ -139,8 +154,8 @@ pub fn math_PI(args: Vec<Value>) -> Value {
 /// ```pawx
 /// meow(Math.E9);
 /// ```
-pub fn math_E9(args: Vec<Value>) -> Value {
-    Value::Number(std::f64::consts::E)
+pub fn math_E9(args: Vec<Value>) -> Result<Value, PawxError> {
+    Ok(Value::Number(std::f64::consts::E))
 }
 
 /// Native implementation of `Math.floor()` for PAWX.
@@ -157,13 +172,13 @@ pub fn math_E9(args: Vec<Value>) -> Value {
 /// ```pawx
 /// meow(Math.floor(4.9)); // 4
 /// ```
-pub fn math_floor(args: Vec<Value>) -> Value {
+pub fn math_floor(args: Vec<Value>) -> Result<Value, PawxError> {
     let x = match args.get(0) {
         Some(Value::Number(n)) => *n,
-        _ => panic!("Math.floor(x) expects a number"),
+        _ => return raise_error("Math.floor(x) expects a number"),
     };
 
-    Value::Number(x.floor())
+    Ok(Value::Number(x.floor()))
 }
 
 /// Native implementation of `Math.ceil()` for PAWX.
@@ -180,13 +195,13 @@ pub fn math_floor(args: Vec<Value>) -> Value {
 /// ```pawx
 /// meow(Math.ceil(4.1)); // 5
 /// ```
-pub fn math_ceil(args: Vec<Value>) -> Value {
+pub fn math_ceil(args: Vec<Value>) -> Result<Value, PawxError> {
     let x = match args.get(0) {
         Some(Value::Number(n)) => *n,
-        _ => panic!("Math.ceil(x) expects a number"),
+        _ => return raise_error("Math.ceil(x) expects a number"),
     };
 
-    Value::Number(x.ceil())
+    Ok(Value::Number(x.ceil()))
 }
 
 /// Native implementation of `Math.round()` for PAWX.
@@ -204,13 +219,13 @@ pub fn math_ceil(args: Vec<Value>) -> Value {
 /// meow(Math.round(4.5)); // 5
 /// meow(Math.round(4.4)); // 4
 /// ```
-pub fn math_round(args: Vec<Value>) -> Value {
+pub fn math_round(args: Vec<Value>) -> Result<Value, PawxError> {
     let x = match args.get(0) {
         Some(Value::Number(n)) => *n,
-        _ => panic!("Math.round(x) expects a number"),
+        _ => return raise_error("Math.round(x) expects a number"),
     };
 
-    Value::Number(x.round())
+    Ok(Value::Number(x.round()))
 }
 
 /// Native implementation of `Math.pow()` for PAWX.
@@ -228,18 +243,18 @@ pub fn math_round(args: Vec<Value>) -> Value {
 /// ```pawx
 /// meow(Math.pow(2, 3)); // 8
 /// ```
-pub fn math_pow(args: Vec<Value>) -> Value {
+pub fn math_pow(args: Vec<Value>) -> Result<Value, PawxError> {
     let base = match args.get(0) {
         Some(Value::Number(n)) => *n,
-        _ => panic!("Math.pow(x, y) expects numbers"),
+        _ => return raise_error("Math.pow(x, y) expects numbers"),
     };
 
     let exp = match args.get(1) {
         Some(Value::Number(n)) => *n,
-        _ => panic!("Math.pow(x, y) expects numbers"),
+        _ => return raise_error("Math.pow(x, y) expects numbers"),
     };
 
-    Value::Number(base.powf(exp))
+    Ok(Value::Number(base.powf(exp)))
 }
 
 /// Native implementation of `Math.sqrt()` for PAWX.
@@ -256,13 +271,13 @@ pub fn math_pow(args: Vec<Value>) -> Value {
 /// ```pawx
 /// meow(Math.sqrt(16)); // 4
 /// ```
-pub fn math_sqrt(args: Vec<Value>) -> Value {
+pub fn math_sqrt(args: Vec<Value>) -> Result<Value, PawxError> {
     let x = match args.get(0) {
         Some(Value::Number(n)) => *n,
-        _ => panic!("Math.sqrt(x) expects a number"),
+        _ => return raise_error("Math.sqrt(x) expects a number"),
     };
 
-    Value::Number(x.sqrt())
+    Ok(Value::Number(x.sqrt()))
 }
 
 /// Native implementation of `Math.abs()` for PAWX.
@@ -279,13 +294,13 @@ pub fn math_sqrt(args: Vec<Value>) -> Value {
 /// ```pawx
 /// meow(Math.abs(-10)); // 10
 /// ```
-pub fn math_abs(args: Vec<Value>) -> Value {
+pub fn math_abs(args: Vec<Value>) -> Result<Value, PawxError> {
     let x = match args.get(0) {
         Some(Value::Number(n)) => *n,
-        _ => panic!("Math.abs(x) expects a number"),
+        _ => return raise_error("Math.abs(x) expects a number"),
     };
 
-    Value::Number(x.abs())
+    Ok(Value::Number(x.abs()))
 }
 
 /// Native implementation of `Math.min()` for PAWX.
@@ -305,14 +320,14 @@ pub fn math_abs(args: Vec<Value>) -> Value {
 /// ```pawx
 /// meow(Math.min(4, 1, 9)); // 1
 /// ```
-pub fn math_min(args: Vec<Value>) -> Value {
+pub fn math_min(args: Vec<Value>) -> Result<Value, PawxError> {
     if args.is_empty() {
-        panic!("Math.min() requires at least one number");
+        return raise_error("Math.min() requires at least one number");
     }
 
     let mut min = match args[0] {
         Value::Number(n) => n,
-        _ => panic!("Math.min() only accepts numbers"),
+        _ => return raise_error("Math.min() only accepts numbers"),
     };
 
     for arg in &args[1..] {
@@ -322,11 +337,11 @@ pub fn math_min(args: Vec<Value>) -> Value {
                     min = *n;
                 }
             }
-            _ => panic!("Math.min() only accepts numbers"),
+            _ => return raise_error("Math.min() only accepts numbers"),
         }
     }
 
-    Value::Number(min)
+    Ok(Value::Number(min))
 }
 
 /// Native implementation of `Math.max()` for PAWX.
@@ -346,14 +361,14 @@ pub fn math_min(args: Vec<Value>) -> Value {
 /// ```pawx
 /// meow(Math.max(4, 1, 9)); // 9
 /// ```
-pub fn math_max(args: Vec<Value>) -> Value {
+pub fn math_max(args: Vec<Value>) -> Result<Value, PawxError> {
     if args.is_empty() {
-        panic!("Math.max() requires at least one number");
+        return raise_error("Math.max() requires at least one number");
     }
 
     let mut max = match args[0] {
         Value::Number(n) => n,
-        _ => panic!("Math.max() only accepts numbers"),
+        _ => return raise_error("Math.max() only accepts numbers"),
     };
 
     for arg in &args[1..] {
@@ -363,11 +378,11 @@ pub fn math_max(args: Vec<Value>) -> Value {
                     max = *n;
                 }
             }
-            _ => panic!("Math.max() only accepts numbers"),
+            _ => return raise_error("Math.max() only accepts numbers"),
         }
     }
 
-    Value::Number(max)
+    Ok(Value::Number(max))
 }
 
 /// Native implementation of `Math.random()` for PAWX.
@@ -381,13 +396,17 @@ pub fn math_max(args: Vec<Value>) -> Value {
 /// # Behavior
 /// - Suitable for non-cryptographic randomness.
 /// - Intended for general-purpose use.
+/// - Repeats the same sequence under `--deterministic`.
 ///
 /// # PAWX Example
 /// ```pawx
 /// snuggle r = Math.random();
 /// meow(r); // 0.0 -> 0.999...
 /// ```
-pub fn math_random(args: Vec<Value>) -> Value {
-    let r = rand::random::<f64>();
-    Value::Number(r)
+pub fn math_random(args: Vec<Value>) -> Result<Value, PawxError> {
+    let r = SEEDED_RNG.with(|rng| match rng.borrow_mut().as_mut() {
+        Some(rng) => rng.gen::<f64>(),
+        None => rand::random::<f64>(),
+    });
+    Ok(Value::Number(r))
 }
\ No newline at end of file
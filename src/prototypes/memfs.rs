@@ -0,0 +1,386 @@
+/*
+ * ==========================================================================
+ * PAWX - Code with Claws!
+ * ==========================================================================
+ *
+ * File:      memfs.rs
+ * Purpose:   Storage backends for the `Fs` global
+ *
+ * `Fs` reads and writes through an [`FsBackend`]:
+ *
+ *   - `Disk`    the real filesystem (the default)
+ *   - `Memory`  an in-memory tree that starts empty and disappears with
+ *               the interpreter
+ *
+ * The memory backend is chosen per interpreter with
+ * `Prelude::with_memory_fs()`, which `pawx test --sandbox-fs` uses, so
+ * tests can create, overwrite and delete files without touching the disk.
+ *
+ * In memory, every path is resolved against a virtual root `/`: relative
+ * paths start there, and `.` and `..` are applied lexically.
+ *
+ * --------------------------------------------------------------------------
+ * Author:   Sam Wilcox
+ * Email:    sam@pawx-lang.com
+ * Website:  https://www.pawx-lang.com
+ * Github:   https://github.com/samwilcox/pawx
+ *
+ * License:
+ * This file is part of the PAWX programming language project.
+ *
+ * PAWX is dual-licensed under the terms of:
+ *   - The MIT License
+ *   - The Apache License, Version 2.0
+ *
+ * You may choose either license to govern your use of this software.
+ * Full license text available at:
+ *    https://license.pawx-lang.com
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under these licenses is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *
+ * ==========================================================================
+ */
+
+use std::cell::{Cell, RefCell};
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Cursor, ErrorKind, Read, Write};
+use std::path::{Component, Path, PathBuf};
+use std::rc::Rc;
+
+/// A node in the in-memory tree.
+#[derive(Debug, Clone)]
+enum Node {
+    File(Vec<u8>),
+    Dir,
+}
+
+/// An in-memory filesystem. Clones share the same tree.
+#[derive(Debug, Clone)]
+pub struct MemoryFs {
+    nodes: Rc<RefCell<BTreeMap<PathBuf, Node>>>,
+}
+
+impl Default for MemoryFs {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn not_found(path: &Path) -> io::Error {
+    io::Error::new(ErrorKind::NotFound, format!("{}: No such file or directory", path.display()))
+}
+
+fn error(kind: ErrorKind, path: &Path, message: &str) -> io::Error {
+    io::Error::new(kind, format!("{}: {}", path.display(), message))
+}
+
+impl MemoryFs {
+    /// Creates an empty filesystem containing only `/`.
+    pub fn new() -> Self {
+        let mut nodes = BTreeMap::new();
+        nodes.insert(PathBuf::from("/"), Node::Dir);
+        Self {
+            nodes: Rc::new(RefCell::new(nodes)),
+        }
+    }
+
+    /// Resolves `path` against the virtual root.
+    fn resolve(path: &str) -> PathBuf {
+        let mut out = PathBuf::from("/");
+        for component in Path::new(path).components() {
+            match component {
+                Component::Normal(part) => out.push(part),
+                Component::ParentDir => {
+                    out.pop();
+                }
+                _ => {}
+            }
+        }
+        out
+    }
+
+    fn node(&self, path: &Path) -> Option<Node> {
+        self.nodes.borrow().get(path).cloned()
+    }
+
+    /// Fails unless the parent of `path` is an existing directory.
+    fn check_parent(&self, path: &Path) -> io::Result<()> {
+        match path.parent().map(|p| self.node(p)) {
+            Some(Some(Node::Dir)) | None => Ok(()),
+            Some(Some(Node::File(_))) => Err(error(ErrorKind::Other, path, "Not a directory")),
+            Some(None) => Err(not_found(path)),
+        }
+    }
+
+    fn read(&self, path: &str) -> io::Result<Vec<u8>> {
+        let path = Self::resolve(path);
+        match self.node(&path) {
+            Some(Node::File(bytes)) => Ok(bytes),
+            Some(Node::Dir) => Err(error(ErrorKind::Other, &path, "Is a directory")),
+            None => Err(not_found(&path)),
+        }
+    }
+
+    fn write(&self, path: &str, bytes: &[u8], append: bool) -> io::Result<()> {
+        let path = Self::resolve(path);
+        self.check_parent(&path)?;
+
+        let mut nodes = self.nodes.borrow_mut();
+        match nodes.get_mut(&path) {
+            Some(Node::Dir) => Err(error(ErrorKind::Other, &path, "Is a directory")),
+            Some(Node::File(existing)) if append => {
+                existing.extend_from_slice(bytes);
+                Ok(())
+            }
+            _ => {
+                nodes.insert(path, Node::File(bytes.to_vec()));
+                Ok(())
+            }
+        }
+    }
+
+    fn read_dir(&self, path: &str) -> io::Result<Vec<String>> {
+        let path = Self::resolve(path);
+        match self.node(&path) {
+            Some(Node::Dir) => Ok(self
+                .nodes
+                .borrow()
+                .keys()
+                .filter(|p| p.parent() == Some(path.as_path()))
+                .filter_map(|p| p.file_name().map(|n| n.to_string_lossy().into_owned()))
+                .collect()),
+            Some(Node::File(_)) => Err(error(ErrorKind::Other, &path, "Not a directory")),
+            None => Err(not_found(&path)),
+        }
+    }
+
+    fn create_dir(&self, path: &str, recursive: bool) -> io::Result<()> {
+        let path = Self::resolve(path);
+
+        if recursive {
+            for dir in path.ancestors().collect::<Vec<_>>().into_iter().rev() {
+                match self.node(dir) {
+                    Some(Node::Dir) => {}
+                    Some(Node::File(_)) => return Err(error(ErrorKind::AlreadyExists, dir, "File exists")),
+                    None => {
+                        self.nodes.borrow_mut().insert(dir.to_path_buf(), Node::Dir);
+                    }
+                }
+            }
+            return Ok(());
+        }
+
+        if self.node(&path).is_some() {
+            return Err(error(ErrorKind::AlreadyExists, &path, "File exists"));
+        }
+        self.check_parent(&path)?;
+        self.nodes.borrow_mut().insert(path, Node::Dir);
+        Ok(())
+    }
+
+    fn remove(&self, path: &str, recursive: bool) -> io::Result<()> {
+        let path = Self::resolve(path);
+        let mut nodes = self.nodes.borrow_mut();
+
+        match nodes.get(&path) {
+            None => Err(not_found(&path)),
+            Some(Node::File(_)) => {
+                nodes.remove(&path);
+                Ok(())
+            }
+            Some(Node::Dir) => {
+                let children: Vec<PathBuf> = nodes
+                    .keys()
+                    .filter(|p| *p != &path && p.starts_with(&path))
+                    .cloned()
+                    .collect();
+
+                if !children.is_empty() && !recursive {
+                    return Err(error(ErrorKind::Other, &path, "Directory not empty"));
+                }
+                if path == Path::new("/") {
+                    return Err(error(ErrorKind::PermissionDenied, &path, "cannot remove the root"));
+                }
+
+                for child in children {
+                    nodes.remove(&child);
+                }
+                nodes.remove(&path);
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Appends everything written to a file in a [`MemoryFs`].
+struct MemoryWriter {
+    fs: MemoryFs,
+    path: String,
+}
+
+impl Write for MemoryWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.fs.write(&self.path, buf, true)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Where the `Fs` global keeps its files.
+#[derive(Debug, Clone, Default)]
+pub enum FsBackend {
+    /// The real filesystem.
+    #[default]
+    Disk,
+
+    /// An in-memory tree.
+    Memory(MemoryFs),
+}
+
+impl FsBackend {
+    /// Reads a whole file.
+    pub fn read(&self, path: &str) -> io::Result<Vec<u8>> {
+        match self {
+            FsBackend::Disk => fs::read(path),
+            FsBackend::Memory(m) => m.read(path),
+        }
+    }
+
+    /// Size of a file in bytes.
+    pub fn file_size(&self, path: &str) -> io::Result<u64> {
+        match self {
+            FsBackend::Disk => fs::metadata(path).map(|m| m.len()),
+            FsBackend::Memory(m) => m.read(path).map(|bytes| bytes.len() as u64),
+        }
+    }
+
+    /// Creates or truncates a file with `bytes`.
+    pub fn write(&self, path: &str, bytes: &[u8]) -> io::Result<()> {
+        match self {
+            FsBackend::Disk => fs::write(path, bytes),
+            FsBackend::Memory(m) => m.write(path, bytes, false),
+        }
+    }
+
+    /// Appends `bytes` to a file, creating it if needed.
+    pub fn append(&self, path: &str, bytes: &[u8]) -> io::Result<()> {
+        match self {
+            FsBackend::Disk => OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .and_then(|mut f| f.write_all(bytes)),
+            FsBackend::Memory(m) => m.write(path, bytes, true),
+        }
+    }
+
+    /// Returns whether a file or directory exists at `path`.
+    pub fn exists(&self, path: &str) -> bool {
+        match self {
+            FsBackend::Disk => Path::new(path).exists(),
+            FsBackend::Memory(m) => m.node(&MemoryFs::resolve(path)).is_some(),
+        }
+    }
+
+    /// Lists the names in a directory.
+    pub fn read_dir(&self, path: &str) -> io::Result<Vec<String>> {
+        match self {
+            FsBackend::Disk => fs::read_dir(path)?
+                .map(|entry| entry.map(|e| e.file_name().to_string_lossy().into_owned()))
+                .collect(),
+            FsBackend::Memory(m) => m.read_dir(path),
+        }
+    }
+
+    /// Creates a directory, and its parents if `recursive`.
+    pub fn create_dir(&self, path: &str, recursive: bool) -> io::Result<()> {
+        match self {
+            FsBackend::Disk if recursive => fs::create_dir_all(path),
+            FsBackend::Disk => fs::create_dir(path),
+            FsBackend::Memory(m) => m.create_dir(path, recursive),
+        }
+    }
+
+    /// Removes a file, or a directory (with its contents if `recursive`).
+    pub fn remove(&self, path: &str, recursive: bool) -> io::Result<()> {
+        match self {
+            FsBackend::Disk => {
+                let p = Path::new(path);
+                match (p.is_dir(), recursive) {
+                    (true, true) => fs::remove_dir_all(p),
+                    (true, false) => fs::remove_dir(p),
+                    (false, _) => fs::remove_file(p),
+                }
+            }
+            FsBackend::Memory(m) => m.remove(path, recursive),
+        }
+    }
+
+    /// Opens a file for streaming reads.
+    pub fn open_read(&self, path: &str) -> io::Result<Box<dyn Read>> {
+        match self {
+            FsBackend::Disk => Ok(Box::new(fs::File::open(path)?)),
+            FsBackend::Memory(m) => Ok(Box::new(Cursor::new(m.read(path)?))),
+        }
+    }
+
+    /// Opens a file for streaming writes, truncating it unless `append`.
+    pub fn open_write(&self, path: &str, append: bool) -> io::Result<Box<dyn Write>> {
+        match self {
+            FsBackend::Disk => Ok(Box::new(
+                OpenOptions::new()
+                    .create(true)
+                    .write(true)
+                    .append(append)
+                    .truncate(!append)
+                    .open(path)?,
+            )),
+            FsBackend::Memory(m) => {
+                if !append || m.read(path).is_err() {
+                    m.write(path, &[], false)?;
+                }
+                Ok(Box::new(MemoryWriter {
+                    fs: m.clone(),
+                    path: path.to_string(),
+                }))
+            }
+        }
+    }
+
+    /// Creates a new, empty, uniquely named temporary directory.
+    ///
+    /// On disk it lives under the system temp directory; in memory under
+    /// `/tmp`.
+    pub fn temp_dir(&self, prefix: &str) -> io::Result<String> {
+        thread_local! {
+            static COUNTER: Cell<u64> = const { Cell::new(0) };
+        }
+        let n = COUNTER.with(|c| {
+            c.set(c.get() + 1);
+            c.get()
+        });
+
+        let path = match self {
+            FsBackend::Disk => {
+                let nanos = std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .map(|d| d.subsec_nanos())
+                    .unwrap_or(0);
+                std::env::temp_dir()
+                    .join(format!("{}-{}-{}-{:x}", prefix, std::process::id(), n, nanos))
+                    .to_string_lossy()
+                    .into_owned()
+            }
+            FsBackend::Memory(_) => format!("/tmp/{}-{}", prefix, n),
+        };
+
+        self.create_dir(&path, true)?;
+        Ok(path)
+    }
+}
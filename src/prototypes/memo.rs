@@ -0,0 +1,432 @@
+/*
+ * ============================================================================
+ * PAWX - Code with Claws! 🐾
+ * ============================================================================
+ *
+ * Memoization for PAWX
+ *
+ * Supports:
+ *   - memoize(fn)                     cache every result
+ *   - memoize(fn, { max, ttl })       bounded (LRU) and/or expiring cache
+ *   - memoize({ max, ttl })           returns a decorator
+ *   - @memoize purr fib -> (n) -> {}  decorator syntax
+ *   - Memo.stats(fn) / Memo.clear(fn)
+ *
+ * Results are keyed by a structural encoding of the arguments, so
+ * `f([1, 2])` and `f([1, 2])` share an entry even though the arrays are
+ * different objects. Functions and other reference-only values are keyed by
+ * identity. Error results are never cached.
+ *
+ * --------------------------------------------------------------------------
+ * Author:   Sam Wilcox
+ * Email:    sam@pawx-lang.com
+ * Website:  https://www.pawx-lang.com
+ * GitHub:   https://github.com/samwilcox/pawx
+ *
+ * License:
+ * This file is part of the PAWX programming language project.
+ *
+ * PAWX is dual-licensed under the terms of:
+ *   - The MIT License
+ *   - The Apache License, Version 2.0
+ *
+ * You may choose either license to govern your use of this software.
+ * Full license text available at:
+ *     https://license.pawx-lang.com
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under these licenses is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *
+ * ==========================================================================
+ */
+
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::rc::{Rc, Weak};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::error::PawxError;
+use crate::interpreter::calls::raise_error;
+use crate::value::{NativeFn, Value};
+
+fn native(f: impl Fn(Vec<Value>) -> Result<Value, PawxError> + 'static) -> Value {
+    Value::NativeFunction(Arc::new(f))
+}
+
+/* ============================================================================
+ * CACHE
+ * ============================================================================
+ */
+
+struct Entry {
+    value: Value,
+    stored_at: Instant,
+    last_used: u64,
+}
+
+/// The cache behind one memoized function.
+struct MemoCache {
+    entries: RefCell<HashMap<String, Entry>>,
+    max: Option<usize>,
+    ttl: Option<Duration>,
+    tick: Cell<u64>,
+    hits: Cell<u64>,
+    misses: Cell<u64>,
+    evictions: Cell<u64>,
+}
+
+impl MemoCache {
+    fn new(max: Option<usize>, ttl: Option<Duration>) -> Self {
+        Self {
+            entries: RefCell::new(HashMap::new()),
+            max,
+            ttl,
+            tick: Cell::new(0),
+            hits: Cell::new(0),
+            misses: Cell::new(0),
+            evictions: Cell::new(0),
+        }
+    }
+
+    fn next_tick(&self) -> u64 {
+        self.tick.set(self.tick.get() + 1);
+        self.tick.get()
+    }
+
+    /// Returns a live entry, dropping it first if it has expired.
+    fn get(&self, key: &str) -> Option<Value> {
+        let mut entries = self.entries.borrow_mut();
+
+        let expired = match (entries.get(key), self.ttl) {
+            (Some(entry), Some(ttl)) => entry.stored_at.elapsed() >= ttl,
+            (Some(_), None) => false,
+            (None, _) => return None,
+        };
+
+        if expired {
+            entries.remove(key);
+            return None;
+        }
+
+        let tick = self.next_tick();
+        entries.get_mut(key).map(|entry| {
+            entry.last_used = tick;
+            entry.value.clone()
+        })
+    }
+
+    /// Stores a result, evicting the least recently used entry when full.
+    fn insert(&self, key: String, value: Value) {
+        let tick = self.next_tick();
+        let mut entries = self.entries.borrow_mut();
+
+        if let Some(max) = self.max {
+            if max == 0 {
+                return;
+            }
+
+            while entries.len() >= max && !entries.contains_key(&key) {
+                let oldest = entries
+                    .iter()
+                    .min_by_key(|(_, entry)| entry.last_used)
+                    .map(|(k, _)| k.clone());
+
+                match oldest {
+                    Some(oldest) => {
+                        entries.remove(&oldest);
+                        self.evictions.set(self.evictions.get() + 1);
+                    }
+                    None => break,
+                }
+            }
+        }
+
+        entries.insert(
+            key,
+            Entry {
+                value,
+                stored_at: Instant::now(),
+                last_used: tick,
+            },
+        );
+    }
+
+    fn stats(&self) -> Value {
+        let mut fields = HashMap::new();
+
+        fields.insert("hits".into(), Value::Number(self.hits.get() as f64));
+        fields.insert("misses".into(), Value::Number(self.misses.get() as f64));
+        fields.insert("evictions".into(), Value::Number(self.evictions.get() as f64));
+        fields.insert("size".into(), Value::Number(self.entries.borrow().len() as f64));
+        fields.insert(
+            "max".into(),
+            self.max.map_or(Value::Null, |m| Value::Number(m as f64)),
+        );
+        fields.insert(
+            "ttl".into(),
+            self.ttl
+                .map_or(Value::Null, |t| Value::Number(t.as_millis() as f64)),
+        );
+
+        Value::Object {
+            fields: Rc::new(RefCell::new(fields)),
+        }
+    }
+
+    fn clear(&self) {
+        self.entries.borrow_mut().clear();
+        self.hits.set(0);
+        self.misses.set(0);
+        self.evictions.set(0);
+    }
+}
+
+thread_local! {
+    /// Maps each memoized function (by `Arc` address) to its cache so
+    /// `Memo.stats` / `Memo.clear` can find it.
+    static REGISTRY: RefCell<HashMap<usize, Weak<MemoCache>>> = RefCell::new(HashMap::new());
+}
+
+fn function_id(f: &Arc<NativeFn>) -> usize {
+    Arc::as_ptr(f) as *const () as usize
+}
+
+fn lookup(value: Option<&Value>) -> Option<Rc<MemoCache>> {
+    let Some(Value::NativeFunction(f)) = value else {
+        return None;
+    };
+
+    let id = function_id(f);
+    REGISTRY.with(|r| r.borrow().get(&id).and_then(Weak::upgrade))
+}
+
+/* ============================================================================
+ * ARGUMENT KEYS
+ * ============================================================================
+ */
+
+/// Appends a structural encoding of `value` to `out`.
+///
+/// Values that compare equal structurally produce the same key. Each value
+/// is tagged with its type, so `1` and `"1"` never collide.
+fn write_key(value: &Value, out: &mut String) {
+    match value {
+        Value::Null => out.push('n'),
+        Value::Bool(b) => out.push(if *b { 't' } else { 'f' }),
+        Value::Number(n) => {
+            let _ = write!(out, "#{};", n);
+        }
+        Value::Decimal(d) => {
+            let _ = write!(out, "d{};", d.normalize());
+        }
+        Value::Duration(ms) => {
+            let _ = write!(out, "D{};", ms);
+        }
+        Value::String(s) => {
+            let _ = write!(out, "s{}:{}", s.len(), s);
+        }
+
+        Value::Array { values, .. } => write_seq(&values.borrow(), '[', ']', out),
+
+        Value::Tuple(values) => write_seq(values, '(', ')', out),
+
+        Value::ImmutableList(list) => {
+            out.push('L');
+            write_seq(&list.iter().cloned().collect::<Vec<_>>(), '[', ']', out);
+        }
+
+        Value::ImmutableMap(map) => {
+            out.push_str("M{");
+            for (key, value) in map.iter() {
+                let _ = write!(out, "{}:{}", key.len(), key);
+                write_key(value, out);
+                out.push(',');
+            }
+            out.push('}');
+        }
+
+        Value::Float64Array(data) => {
+            out.push('F');
+            write_seq(
+                &data.borrow().iter().map(|n| Value::Number(*n)).collect::<Vec<_>>(),
+                '[',
+                ']',
+                out,
+            );
+        }
+
+        Value::Object { fields } | Value::Instance { fields, .. } => {
+            let fields = fields.borrow();
+            let mut keys: Vec<&String> = fields.keys().collect();
+            keys.sort();
+
+            out.push('{');
+            for key in keys {
+                let _ = write!(out, "{}:{}", key.len(), key);
+                write_key(&fields[key], out);
+                out.push(',');
+            }
+            out.push('}');
+        }
+
+        Value::NativeFunction(f) => {
+            let _ = write!(out, "fn@{:x};", function_id(f));
+        }
+
+        other => {
+            let _ = write!(out, "{}:{};", other.type_name(), other.stringify());
+        }
+    }
+}
+
+fn write_seq(values: &[Value], open: char, close: char, out: &mut String) {
+    out.push(open);
+    for value in values {
+        write_key(value, out);
+        out.push(',');
+    }
+    out.push(close);
+}
+
+fn args_key(args: &[Value]) -> String {
+    let mut key = String::new();
+    write_seq(args, '(', ')', &mut key);
+    key
+}
+
+/* ============================================================================
+ * MEMOIZE
+ * ============================================================================
+ */
+
+/// Reads `{ max, ttl }` options. `ttl` is in milliseconds, or a Duration.
+fn parse_options(options: Option<&Value>) -> Result<(Option<usize>, Option<Duration>), PawxError> {
+    let Some(Value::Object { fields }) = options else {
+        return Ok((None, None));
+    };
+
+    let fields = fields.borrow();
+
+    let max = match fields.get("max") {
+        Some(Value::Number(n)) if *n >= 0.0 => Some(*n as usize),
+        Some(Value::Null) | None => None,
+        Some(_) => return raise_error("memoize() option 'max' must be a non-negative number"),
+    };
+
+    let ttl = match fields.get("ttl") {
+        Some(Value::Null) | None => None,
+        Some(ttl) => match crate::prototypes::duration::millis(ttl) {
+            Some(n) if n >= 0.0 => Some(Duration::from_secs_f64(n / 1000.0)),
+            _ => return raise_error("memoize() option 'ttl' must be a non-negative number of milliseconds or a Duration"),
+        },
+    };
+
+    Ok((max, ttl))
+}
+
+/// Wraps `func` in a caching function.
+fn memoize_function(
+    func: Arc<NativeFn>,
+    max: Option<usize>,
+    ttl: Option<Duration>,
+) -> Value {
+    let cache = Rc::new(MemoCache::new(max, ttl));
+    let registered = Rc::downgrade(&cache);
+
+    let wrapper: Arc<NativeFn> = Arc::new(move |args: Vec<Value>| -> Result<Value, PawxError> {
+        let key = args_key(&args);
+
+        if let Some(hit) = cache.get(&key) {
+            cache.hits.set(cache.hits.get() + 1);
+            return Ok(hit);
+        }
+
+        cache.misses.set(cache.misses.get() + 1);
+
+        // No cache borrow is held here, so recursive calls can use it
+        let result = func(args)?;
+
+        if !matches!(result, Value::Error { .. }) {
+            cache.insert(key, result.clone());
+        }
+
+        Ok(result)
+    });
+
+    REGISTRY.with(|r| {
+        let mut registry = r.borrow_mut();
+        registry.retain(|_, cache| cache.strong_count() > 0);
+        registry.insert(function_id(&wrapper), registered);
+    });
+
+    Value::NativeFunction(wrapper)
+}
+
+/// `memoize(fn, options?)` / `memoize(options)`.
+///
+/// # Errors
+/// If the first argument is neither a function nor an options object.
+pub fn memoize(args: Vec<Value>) -> Result<Value, PawxError> {
+    match args.first() {
+        Some(Value::NativeFunction(f)) => {
+            let (max, ttl) = parse_options(args.get(1))?;
+            Ok(memoize_function(f.clone(), max, ttl))
+        }
+
+        // Options first: return a decorator (`@memoize({ max: 100 })`)
+        Some(options @ Value::Object { .. }) => {
+            let (max, ttl) = parse_options(Some(options))?;
+
+            Ok(native(move |args| match args.first() {
+                Some(Value::NativeFunction(f)) => Ok(memoize_function(f.clone(), max, ttl)),
+                _ => raise_error("memoize decorator expects a function"),
+            }))
+        }
+
+        _ => raise_error("memoize() expects a function or an options object"),
+    }
+}
+
+/// Builds the global `memoize` function.
+pub fn create_global_memoize_value() -> Value {
+    native(memoize)
+}
+
+/// Builds the global `Memo` object.
+pub fn create_global_memo_object() -> HashMap<String, Value> {
+    let mut memo = HashMap::new();
+
+    memo.insert("memoize".to_string(), native(memoize));
+
+    // Memo.stats(fn) -> { hits, misses, evictions, size, max, ttl }
+    memo.insert(
+        "stats".to_string(),
+        native(|args| match lookup(args.first()) {
+            Some(cache) => Ok(cache.stats()),
+            None => raise_error("Memo.stats() expects a memoized function"),
+        }),
+    );
+
+    // Memo.clear(fn) drops every cached result and resets the counters
+    memo.insert(
+        "clear".to_string(),
+        native(|args| match lookup(args.first()) {
+            Some(cache) => {
+                cache.clear();
+                Ok(Value::Null)
+            }
+            None => raise_error("Memo.clear() expects a memoized function"),
+        }),
+    );
+
+    // Memo.isMemoized(fn)
+    memo.insert(
+        "isMemoized".to_string(),
+        native(|args| Ok(Value::Bool(lookup(args.first()).is_some()))),
+    );
+
+    memo
+}
@@ -0,0 +1,397 @@
+/*
+ * ============================================================================
+ * PAWX - Code with Claws! 🐾
+ * ============================================================================
+ *
+ * Mocks and Spies for PAWX (`Mock`, `Test`)
+ *
+ * Supports:
+ *   - Mock.fn(impl?)                   recording function, calls impl if given
+ *   - Mock.spyOn(obj, "method")        wraps obj.method and records its calls
+ *   - Mock.calls(fn)                   array of argument arrays, oldest first
+ *   - Mock.results(fn)                 array of returned values
+ *   - Mock.callCount(fn)
+ *   - Mock.lastCall(fn)                arguments of the last call, or null
+ *   - Mock.returnValue(fn, value)      always return value
+ *   - Mock.implementation(fn, impl)    call impl instead
+ *   - Mock.reset(fn)                   forget recorded calls
+ *   - Mock.restore(spy)                put the original method back
+ *   - Mock.restoreAll()
+ *   - Test.mockModule(path, fake)      tap(path) returns fake
+ *   - Test.unmockModule(path)
+ *   - Test.resetModules()
+ *   - Test.tempDir(prefix?)            new empty directory, removed after the test
+ *   - Test.tempFile(contents?, name?)  new file in its own temp directory
+ *   - Test.httpServer / Test.httpMock  (see http_test.rs)
+ *
+ * Mock functions are ordinary function values, so they can be passed
+ * anywhere a callback or method is expected. Their call records are looked
+ * up by identity through the `Mock.*` helpers:
+ *
+ * ```pawx
+ * snuggle fetch = Mock.fn((url) -> { return { status: 200 }; });
+ * client.get = fetch;
+ * client.get("/users");
+ * assertEq(Mock.calls(fetch), [["/users"]]);
+ * ```
+ *
+ * --------------------------------------------------------------------------
+ * Author:   Sam Wilcox
+ * Email:    sam@pawx-lang.com
+ * Website:  https://www.pawx-lang.com
+ * GitHub:   https://github.com/samwilcox/pawx
+ *
+ * License:
+ * This file is part of the PAWX programming language project.
+ *
+ * PAWX is dual-licensed under the terms of:
+ *   - The MIT License
+ *   - The Apache License, Version 2.0
+ *
+ * You may choose either license to govern your use of this software.
+ * Full license text available at:
+ *     https://license.pawx-lang.com
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under these licenses is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *
+ * ==========================================================================
+ */
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use crate::error::PawxError;
+use crate::interpreter::calls::{builtin_error, raise_error};
+use crate::interpreter::{modules, testing};
+use crate::prototypes::array::create_array_proto;
+use crate::prototypes::memfs::FsBackend;
+use crate::value::{NativeFn, Value};
+
+fn native(f: impl Fn(Vec<Value>) -> Result<Value, PawxError> + 'static) -> Value {
+    Value::NativeFunction(Arc::new(f))
+}
+
+fn array(values: Vec<Value>) -> Value {
+    Value::Array {
+        values: Rc::new(RefCell::new(values)),
+        proto: create_array_proto(),
+    }
+}
+
+/// Where a spy was installed, so it can be put back.
+struct SpyTarget {
+    fields: Rc<RefCell<HashMap<String, Value>>>,
+    name: String,
+    original: Option<Value>,
+}
+
+/// Recorded calls and behaviour of one mock function.
+#[derive(Default)]
+struct MockState {
+    calls: Vec<Vec<Value>>,
+    results: Vec<Value>,
+    implementation: Option<Value>,
+    return_value: Option<Value>,
+    spy: Option<SpyTarget>,
+}
+
+thread_local! {
+    /// Every mock function, keyed by the address of its closure.
+    static MOCKS: RefCell<HashMap<usize, Rc<RefCell<MockState>>>> = RefCell::new(HashMap::new());
+}
+
+fn mock_id(f: &Arc<NativeFn>) -> usize {
+    Arc::as_ptr(f) as *const () as usize
+}
+
+/// Looks up the state of the mock passed as `args[0]`.
+///
+/// # Errors
+/// - If the argument is not a function created by `Mock.fn` or `Mock.spyOn`
+fn state_of(args: &[Value], method: &str) -> Result<Rc<RefCell<MockState>>, PawxError> {
+    let found = match args.first() {
+        Some(Value::NativeFunction(f)) => MOCKS.with(|m| m.borrow().get(&mock_id(f)).cloned()),
+        _ => None,
+    };
+
+    found.ok_or_else(|| builtin_error(format!("Mock.{}() expects a function created by Mock.fn or Mock.spyOn", method)))
+}
+
+/// Creates a recording function around `state`.
+fn create_mock(state: MockState) -> Value {
+    let state = Rc::new(RefCell::new(state));
+    let recorder = state.clone();
+
+    let f: Arc<NativeFn> = Arc::new(move |args: Vec<Value>| {
+        recorder.borrow_mut().calls.push(args.clone());
+
+        // Release the borrow before running user code, which may call the
+        // mock again.
+        let (fixed, implementation) = {
+            let s = recorder.borrow();
+            (s.return_value.clone(), s.implementation.clone())
+        };
+
+        let result = match (fixed, implementation) {
+            (Some(value), _) => value,
+            (None, Some(Value::NativeFunction(f))) => f(args)?,
+            _ => Value::Null,
+        };
+
+        recorder.borrow_mut().results.push(result.clone());
+        Ok(result)
+    });
+
+    MOCKS.with(|m| m.borrow_mut().insert(mock_id(&f), state));
+    Value::NativeFunction(f)
+}
+
+/// Puts a spied-on method back.
+fn restore(state: &Rc<RefCell<MockState>>) {
+    if let Some(spy) = state.borrow_mut().spy.take() {
+        let mut fields = spy.fields.borrow_mut();
+        match spy.original {
+            Some(original) => fields.insert(spy.name, original),
+            None => fields.remove(&spy.name),
+        };
+    }
+}
+
+/// Builds the global `Mock` object.
+pub fn create_global_mock_object() -> HashMap<String, Value> {
+    let mut mock = HashMap::new();
+
+    mock.insert(
+        "fn".to_string(),
+        native(|args| {
+            let implementation = match args.first() {
+                None | Some(Value::Null) => None,
+                Some(f @ Value::NativeFunction(_)) => Some(f.clone()),
+                _ => return raise_error("Mock.fn(impl?) expects a function"),
+            };
+
+            Ok(create_mock(MockState {
+                implementation,
+                ..MockState::default()
+            }))
+        }),
+    );
+
+    mock.insert(
+        "spyOn".to_string(),
+        native(|args| {
+            let (fields, name) = match (args.first(), args.get(1)) {
+                (Some(Value::Object { fields } | Value::Instance { fields, .. }), Some(Value::String(name))) => {
+                    (fields.clone(), name.to_string())
+                }
+                _ => return raise_error("Mock.spyOn(obj, method) expects an object and a method name"),
+            };
+
+            // Instance methods live on the class; a field of the same name
+            // shadows them, so the spy goes in the fields either way.
+            let original = fields.borrow().get(&name).cloned();
+            let implementation = match (&original, &args[0]) {
+                (Some(f @ Value::NativeFunction(_)), _) => f.clone(),
+                (None, instance @ Value::Instance { .. }) => {
+                    let env = crate::interpreter::global_env();
+                    crate::interpreter::classes::get_instance_property(instance.clone(), name.to_string(), crate::interpreter::current_span(), env)
+                        .map_err(|_| builtin_error(format!("Mock.spyOn(): '{}' is not a method", name)))?
+                }
+                _ => return raise_error(format!("Mock.spyOn(): '{}' is not a method", name)),
+            };
+
+            let spy = create_mock(MockState {
+                implementation: Some(implementation),
+                spy: Some(SpyTarget {
+                    fields: fields.clone(),
+                    name: name.to_string(),
+                    original,
+                }),
+                ..MockState::default()
+            });
+
+            fields.borrow_mut().insert(name.to_string(), spy.clone());
+            Ok(spy)
+        }),
+    );
+
+    mock.insert(
+        "calls".to_string(),
+        native(|args| {
+            let calls = state_of(&args, "calls")?.borrow().calls.clone();
+            Ok(array(calls.into_iter().map(array).collect()))
+        }),
+    );
+
+    mock.insert(
+        "results".to_string(),
+        native(|args| Ok(array(state_of(&args, "results")?.borrow().results.clone()))),
+    );
+
+    mock.insert(
+        "callCount".to_string(),
+        native(|args| Ok(Value::Number(state_of(&args, "callCount")?.borrow().calls.len() as f64))),
+    );
+
+    mock.insert(
+        "lastCall".to_string(),
+        native(|args| {
+            let last = state_of(&args, "lastCall")?.borrow().calls.last().cloned();
+            Ok(last.map(array).unwrap_or(Value::Null))
+        }),
+    );
+
+    mock.insert(
+        "returnValue".to_string(),
+        native(|args| {
+            let state = state_of(&args, "returnValue")?;
+            state.borrow_mut().return_value = Some(args.get(1).cloned().unwrap_or(Value::Null));
+            Ok(Value::Null)
+        }),
+    );
+
+    mock.insert(
+        "implementation".to_string(),
+        native(|args| {
+            let state = state_of(&args, "implementation")?;
+            let implementation = match args.get(1) {
+                Some(f @ Value::NativeFunction(_)) => f.clone(),
+                _ => return raise_error("Mock.implementation(fn, impl) expects a function"),
+            };
+
+            let mut s = state.borrow_mut();
+            s.implementation = Some(implementation);
+            s.return_value = None;
+            Ok(Value::Null)
+        }),
+    );
+
+    mock.insert(
+        "reset".to_string(),
+        native(|args| {
+            let state = state_of(&args, "reset")?;
+            let mut s = state.borrow_mut();
+            s.calls.clear();
+            s.results.clear();
+            Ok(Value::Null)
+        }),
+    );
+
+    mock.insert(
+        "restore".to_string(),
+        native(|args| {
+            restore(&state_of(&args, "restore")?);
+            Ok(Value::Null)
+        }),
+    );
+
+    mock.insert(
+        "restoreAll".to_string(),
+        native(|_| {
+            let all: Vec<_> = MOCKS.with(|m| m.borrow().values().cloned().collect());
+            for state in &all {
+                restore(state);
+            }
+            Ok(Value::Null)
+        }),
+    );
+
+    mock
+}
+
+/// Creates a temporary directory in `fs` that is removed on cleanup.
+fn temp_dir(fs: &FsBackend, prefix: &str, method: &str) -> Result<String, PawxError> {
+    let dir = fs
+        .temp_dir(prefix)
+        .map_err(|e| builtin_error(format!("Test.{}(): {}", method, e)))?;
+
+    let (fs, cleanup) = (fs.clone(), dir.clone());
+    testing::on_cleanup(move || {
+        let _ = fs.remove(&cleanup, true);
+    });
+
+    Ok(dir)
+}
+
+/// Builds the global `Test` object.
+///
+/// Temporary files are created in `fs`, the interpreter's `Fs` backend.
+pub fn create_global_test_object(fs: &FsBackend) -> HashMap<String, Value> {
+    let mut test = HashMap::new();
+
+    let backend = fs.clone();
+    test.insert(
+        "tempDir".to_string(),
+        native(move |args| {
+            let prefix = match args.first() {
+                Some(Value::String(p)) => p.to_string(),
+                None | Some(Value::Null) => "pawx-test".to_string(),
+                _ => return raise_error("Test.tempDir(prefix?) expects a string"),
+            };
+            Ok(Value::String(temp_dir(&backend, &prefix, "tempDir")?.into()))
+        }),
+    );
+
+    let backend = fs.clone();
+    test.insert(
+        "tempFile".to_string(),
+        native(move |args| {
+            let contents = match args.first() {
+                Some(Value::String(s)) => s.to_string(),
+                None | Some(Value::Null) => String::new(),
+                Some(other) => match crate::interpreter::display::value_to_json(other) {
+                    Ok(json) => json,
+                    Err(message) => return raise_error(message),
+                },
+            };
+            let name = match args.get(1) {
+                Some(Value::String(n)) => n.to_string(),
+                None | Some(Value::Null) => "file.txt".to_string(),
+                _ => return raise_error("Test.tempFile(contents?, name?) expects a file name"),
+            };
+
+            let path = format!("{}/{}", temp_dir(&backend, "pawx-test", "tempFile")?, name);
+            backend
+                .write(&path, contents.as_bytes())
+                .map_err(|e| builtin_error(format!("Test.tempFile(): {}", e)))?;
+            Ok(Value::String(path.into()))
+        }),
+    );
+
+    test.insert(
+        "mockModule".to_string(),
+        native(|args| {
+            let path = match args.first() {
+                Some(Value::String(path)) => path.to_string(),
+                _ => return raise_error("Test.mockModule(path, fake) expects a module path"),
+            };
+
+            modules::mock(&path, args.get(1).cloned().unwrap_or(Value::Null));
+            Ok(Value::Null)
+        }),
+    );
+
+    test.insert(
+        "unmockModule".to_string(),
+        native(|args| match args.first() {
+            Some(Value::String(path)) => Ok(Value::Bool(modules::unmock(path))),
+            _ => raise_error("Test.unmockModule(path) expects a module path"),
+        }),
+    );
+
+    test.insert(
+        "resetModules".to_string(),
+        native(|_| {
+            modules::clear_mocks();
+            Ok(Value::Null)
+        }),
+    );
+
+    crate::prototypes::http_test::install_http_fixtures(&mut test);
+
+    test
+}
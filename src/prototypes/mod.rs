@@ -27,10 +27,62 @@
  */
 
 pub mod array;
+pub mod typed_array;
+pub mod immutable;
+pub mod io;
+pub mod slice;
+pub mod lazy;
 pub mod math;
+pub mod decimal;
+pub mod duration;
 pub mod time;
+pub mod tz;
+pub mod intl;
 pub mod object;
 pub mod http;
+pub mod http_client;
+pub mod http_cache;
+pub mod http_conn;
+pub mod http_lifecycle;
+pub mod http_proxy;
+pub mod http_router;
+pub mod http_static;
+pub mod multipart;
+pub mod graphql;
+pub mod crypto;
+pub mod auth;
 pub mod string;
+pub mod string_builder;
 pub mod regex;
-pub mod fs;
\ No newline at end of file
+pub mod fs;
+pub mod memfs;
+pub mod cancel;
+pub mod furure;
+pub mod context;
+pub mod stream;
+pub mod limit;
+pub mod schema;
+pub mod memo;
+pub mod weak;
+pub mod eval;
+pub mod json;
+pub mod json_writer;
+pub mod codec;
+pub mod msgpack;
+pub mod protobuf;
+pub mod markdown;
+pub mod template;
+pub mod site;
+pub mod rpc;
+pub mod mqtt;
+pub mod perf;
+pub mod process;
+pub mod assert;
+pub mod mock;
+pub mod http_test;
+#[cfg(feature = "serial")]
+pub mod serial;
+#[cfg(feature = "gui")]
+pub mod gui;
+#[cfg(feature = "isolates")]
+pub mod isolate;
@@ -0,0 +1,810 @@
+/*
+ * ============================================================================
+ * PAWX - Code with Claws! 🐾
+ * ============================================================================
+ *
+ * MQTT Client for PAWX (`Mqtt`)
+ *
+ * Supports:
+ *   - Mqtt.connect(url, options?)             -> client
+ *   - client.publish(topic, payload, opts?)   -> Furure(null)
+ *   - client.subscribe(filter, cb, opts?)     -> Furure(granted qos)
+ *   - client.unsubscribe(filter)              -> Furure(null)
+ *   - client.poll()                           -> number of messages handled
+ *   - client.loop(ms?)                        -> runs callbacks for `ms`
+ *                                                (forever if omitted)
+ *   - client.isConnected()
+ *   - client.disconnect()
+ *
+ * Speaks MQTT 3.1.1 over plain TCP (`mqtt://host:port`, default port
+ * 1883). QoS 0 and 1 are supported for publishing and subscribing.
+ *
+ * Connect options:
+ *   { clientId, username, password, keepAlive (seconds), clean,
+ *     will: { topic, payload, qos, retain } }
+ *
+ * Publish / subscribe options: `{ qos, retain }`. A QoS 1 publish resolves
+ * once the broker acknowledges it.
+ *
+ * Incoming messages are queued by a background reader and handed to
+ * subscription callbacks on the interpreter thread: between top-level
+ * statements, whenever `poll()` is called, and while `loop()` runs.
+ * Callbacks receive `{ topic, payload, bytes, qos, retain }` where
+ * `payload` is the UTF-8 text and `bytes` the raw payload. Filters may use
+ * the `+` and `#` wildcards.
+ *
+ * --------------------------------------------------------------------------
+ * Author:   Sam Wilcox
+ * Email:    sam@pawx-lang.com
+ * Website:  https://www.pawx-lang.com
+ * GitHub:   https://github.com/samwilcox/pawx
+ *
+ * License:
+ * This file is part of the PAWX programming language project.
+ *
+ * PAWX is dual-licensed under the terms of:
+ *   - The MIT License
+ *   - The Apache License, Version 2.0
+ *
+ * You may choose either license to govern your use of this software.
+ * Full license text available at:
+ *     https://license.pawx-lang.com
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under these licenses is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *
+ * ==========================================================================
+ */
+
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, VecDeque};
+use std::io::{Read, Write};
+use std::net::{Shutdown, TcpStream};
+use std::rc::{Rc, Weak};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use rand::Rng;
+
+use crate::error::PawxError;
+use crate::interpreter::calls::{builtin_error, raise_error};
+use crate::prototypes::codec::{array_to_bytes, bytes_to_array};
+use crate::value::Value;
+
+const CONNECT: u8 = 0x10;
+const CONNACK: u8 = 0x20;
+const PUBLISH: u8 = 0x30;
+const PUBACK: u8 = 0x40;
+const SUBSCRIBE: u8 = 0x82;
+const SUBACK: u8 = 0x90;
+const UNSUBSCRIBE: u8 = 0xa2;
+const UNSUBACK: u8 = 0xb0;
+const PINGREQ: u8 = 0xc0;
+const PINGRESP: u8 = 0xd0;
+const DISCONNECT: u8 = 0xe0;
+
+/// How long to wait for CONNACK and other acknowledgements.
+const ACK_TIMEOUT: Duration = Duration::from_secs(10);
+
+fn native(f: impl Fn(Vec<Value>) -> Result<Value, PawxError> + 'static) -> Value {
+    Value::NativeFunction(Arc::new(f))
+}
+
+fn object(fields: HashMap<String, Value>) -> Value {
+    Value::Object {
+        fields: Rc::new(RefCell::new(fields)),
+    }
+}
+
+fn to_furure(result: Result<Value, String>) -> Value {
+    match result {
+        Ok(v) => Value::Furure(Box::new(v)),
+        Err(message) => Value::Furure(Box::new(Value::Error { message })),
+    }
+}
+
+/* ============================================================================
+ * PACKETS
+ * ============================================================================
+ */
+
+fn write_str(out: &mut Vec<u8>, s: &[u8]) {
+    out.extend_from_slice(&(s.len() as u16).to_be_bytes());
+    out.extend_from_slice(s);
+}
+
+/// Prefixes `body` with a fixed header.
+fn packet(header: u8, body: &[u8]) -> Vec<u8> {
+    let mut out = vec![header];
+    let mut len = body.len();
+
+    loop {
+        let mut byte = (len % 128) as u8;
+        len /= 128;
+        if len > 0 {
+            byte |= 0x80;
+        }
+        out.push(byte);
+        if len == 0 {
+            break;
+        }
+    }
+
+    out.extend_from_slice(body);
+    out
+}
+
+/// Reads one packet as `(header, body)`.
+fn read_packet(stream: &mut TcpStream) -> std::io::Result<(u8, Vec<u8>)> {
+    let mut byte = [0u8; 1];
+    stream.read_exact(&mut byte)?;
+    let header = byte[0];
+
+    let mut len = 0usize;
+    for shift in (0..28).step_by(7) {
+        stream.read_exact(&mut byte)?;
+        len |= ((byte[0] & 0x7f) as usize) << shift;
+        if byte[0] & 0x80 == 0 {
+            break;
+        }
+    }
+
+    let mut body = vec![0u8; len];
+    stream.read_exact(&mut body)?;
+    Ok((header, body))
+}
+
+fn packet_id(body: &[u8]) -> u16 {
+    match body {
+        [hi, lo, ..] => u16::from_be_bytes([*hi, *lo]),
+        _ => 0,
+    }
+}
+
+/// A received application message.
+struct Message {
+    topic: String,
+    payload: Vec<u8>,
+    qos: u8,
+    retain: bool,
+}
+
+impl Message {
+    fn parse(header: u8, body: &[u8]) -> Option<(Message, Option<u16>)> {
+        let qos = (header >> 1) & 0x03;
+        let topic_len = u16::from_be_bytes([*body.first()?, *body.get(1)?]) as usize;
+        let topic = String::from_utf8_lossy(body.get(2..2 + topic_len)?).to_string();
+        let mut rest = &body[2 + topic_len..];
+
+        let id = if qos > 0 {
+            let id = packet_id(rest);
+            rest = rest.get(2..)?;
+            Some(id)
+        } else {
+            None
+        };
+
+        let message = Message {
+            topic,
+            payload: rest.to_vec(),
+            qos,
+            retain: header & 0x01 != 0,
+        };
+
+        Some((message, id))
+    }
+
+    fn to_value(&self) -> Value {
+        let mut fields = HashMap::new();
+        fields.insert("topic".to_string(), Value::String(self.topic.as_str().into()));
+        fields.insert(
+            "payload".to_string(),
+            Value::String(String::from_utf8_lossy(&self.payload).into()),
+        );
+        fields.insert("bytes".to_string(), bytes_to_array(&self.payload));
+        fields.insert("qos".to_string(), Value::Number(self.qos as f64));
+        fields.insert("retain".to_string(), Value::Bool(self.retain));
+        object(fields)
+    }
+}
+
+/// What the reader thread reports to the interpreter thread.
+enum Incoming {
+    Message(Message),
+    PubAck(u16),
+    SubAck(u16, Vec<u8>),
+    UnsubAck(u16),
+    Closed(String),
+}
+
+/// Returns `true` if `topic` matches the subscription `filter`.
+fn topic_matches(filter: &str, topic: &str) -> bool {
+    // Wildcards never match topics starting with `$` (broker internals)
+    if topic.starts_with('$') && (filter.starts_with('+') || filter.starts_with('#')) {
+        return false;
+    }
+
+    let mut topic_levels = topic.split('/');
+
+    for level in filter.split('/') {
+        match (level, topic_levels.next()) {
+            ("#", _) => return true,
+            ("+", Some(_)) => {}
+            (level, Some(t)) if level == t => {}
+            _ => return false,
+        }
+    }
+
+    topic_levels.next().is_none()
+}
+
+/* ============================================================================
+ * CONNECTION
+ * ============================================================================
+ */
+
+struct ConnectOptions {
+    host: String,
+    port: u16,
+    client_id: String,
+    username: Option<String>,
+    password: Option<String>,
+    keep_alive: u16,
+    clean: bool,
+    will: Option<(String, Vec<u8>, u8, bool)>,
+}
+
+fn payload_bytes(value: Option<&Value>, usage: &str) -> Result<Vec<u8>, PawxError> {
+    match value {
+        Some(Value::String(s)) => Ok(s.as_bytes().to_vec()),
+        Some(v @ Value::Array { .. }) => Ok(array_to_bytes(Some(v), usage)?),
+        Some(Value::Null) | None => Ok(Vec::new()),
+        Some(other) => Ok(other.to_pawx_string().into_bytes()),
+    }
+}
+
+/// Reads `{ qos, retain }`, rejecting QoS 2.
+fn qos_retain(value: Option<&Value>, usage: &str) -> Result<(u8, bool), PawxError> {
+    let fields = match value {
+        Some(Value::Object { fields }) => fields.borrow().clone(),
+        _ => return Ok((0, false)),
+    };
+
+    let qos = match fields.get("qos") {
+        Some(Value::Number(n)) if *n == 0.0 || *n == 1.0 => *n as u8,
+        None | Some(Value::Null) => 0,
+        _ => return raise_error(format!("{}: qos must be 0 or 1", usage)),
+    };
+
+    Ok((qos, fields.get("retain").is_some_and(Value::is_truthy)))
+}
+
+fn parse_connect(args: &[Value]) -> Result<ConnectOptions, PawxError> {
+    let url = match args.first() {
+        Some(Value::String(u)) => u.to_string(),
+        _ => return raise_error("Mqtt.connect(url, options?) expects a url string"),
+    };
+
+    let address = url
+        .strip_prefix("mqtt://")
+        .or_else(|| url.strip_prefix("tcp://"))
+        .unwrap_or(&url)
+        .trim_end_matches('/');
+
+    let (host, port) = match address.rsplit_once(':') {
+        Some((host, port)) => (
+            host.to_string(),
+            port.parse()
+                .map_err(|_| builtin_error(format!("Mqtt.connect: invalid port in '{}'", url)))?,
+        ),
+        None => (address.to_string(), 1883),
+    };
+
+    let fields = match args.get(1) {
+        Some(Value::Object { fields }) => fields.borrow().clone(),
+        _ => HashMap::new(),
+    };
+
+    let string = |key: &str| match fields.get(key) {
+        Some(Value::String(s)) => Some(s.to_string()),
+        _ => None,
+    };
+
+    let will = match fields.get("will") {
+        Some(will_value @ Value::Object { fields: will }) => {
+            let (qos, retain) = qos_retain(Some(will_value), "Mqtt.connect will")?;
+            let will = will.borrow();
+            let topic = match will.get("topic") {
+                Some(Value::String(t)) => t.to_string(),
+                _ => return raise_error("Mqtt.connect: will needs a topic"),
+            };
+
+            Some((topic, payload_bytes(will.get("payload"), "Mqtt.connect will")?, qos, retain))
+        }
+        _ => None,
+    };
+
+    Ok(ConnectOptions {
+        host,
+        port,
+        client_id: string("clientId").unwrap_or_else(|| {
+            format!("pawx-{:08x}", rand::thread_rng().gen::<u32>())
+        }),
+        username: string("username"),
+        password: string("password"),
+        keep_alive: match fields.get("keepAlive") {
+            Some(Value::Number(n)) if *n >= 0.0 => *n as u16,
+            _ => 60,
+        },
+        clean: !matches!(fields.get("clean"), Some(Value::Bool(false))),
+        will,
+    })
+}
+
+fn connect_packet(opts: &ConnectOptions) -> Vec<u8> {
+    let mut flags = 0u8;
+    if opts.clean {
+        flags |= 0x02;
+    }
+    if let Some((_, _, qos, retain)) = &opts.will {
+        flags |= 0x04 | (qos << 3);
+        if *retain {
+            flags |= 0x20;
+        }
+    }
+    if opts.username.is_some() {
+        flags |= 0x80;
+    }
+    if opts.password.is_some() {
+        flags |= 0x40;
+    }
+
+    let mut body = Vec::new();
+    write_str(&mut body, b"MQTT");
+    body.push(4);
+    body.push(flags);
+    body.extend_from_slice(&opts.keep_alive.to_be_bytes());
+
+    write_str(&mut body, opts.client_id.as_bytes());
+    if let Some((topic, payload, _, _)) = &opts.will {
+        write_str(&mut body, topic.as_bytes());
+        write_str(&mut body, payload);
+    }
+    if let Some(username) = &opts.username {
+        write_str(&mut body, username.as_bytes());
+    }
+    if let Some(password) = &opts.password {
+        write_str(&mut body, password.as_bytes());
+    }
+
+    packet(CONNECT, &body)
+}
+
+/// Reads packets until the socket closes, forwarding them to `tx`.
+///
+/// Sends PINGREQ whenever the connection has been idle for half the
+/// keep-alive interval, and acknowledges QoS 1 messages on receipt.
+fn reader_thread(
+    mut stream: TcpStream,
+    writer: Arc<Mutex<TcpStream>>,
+    keep_alive: u16,
+    connected: Arc<AtomicBool>,
+    tx: Sender<Incoming>,
+) {
+    let idle = (keep_alive > 0).then(|| Duration::from_millis(keep_alive as u64 * 500));
+    let _ = stream.set_read_timeout(idle);
+
+    loop {
+        let result = read_packet(&mut stream);
+
+        let (header, body) = match result {
+            Ok(packet) => packet,
+            Err(e) if matches!(e.kind(), std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut) => {
+                let _ = writer.lock().map(|mut w| w.write_all(&[PINGREQ, 0]));
+                continue;
+            }
+            Err(e) => {
+                connected.store(false, Ordering::SeqCst);
+                let _ = tx.send(Incoming::Closed(e.to_string()));
+                return;
+            }
+        };
+
+        let incoming = match header & 0xf0 {
+            PUBLISH => match Message::parse(header, &body) {
+                Some((message, id)) => {
+                    if let Some(id) = id {
+                        let ack = packet(PUBACK, &id.to_be_bytes());
+                        let _ = writer.lock().map(|mut w| w.write_all(&ack));
+                    }
+                    Incoming::Message(message)
+                }
+                None => continue,
+            },
+            PUBACK => Incoming::PubAck(packet_id(&body)),
+            SUBACK => Incoming::SubAck(packet_id(&body), body.get(2..).unwrap_or_default().to_vec()),
+            UNSUBACK => Incoming::UnsubAck(packet_id(&body)),
+            PINGRESP => continue,
+            _ => continue,
+        };
+
+        if tx.send(incoming).is_err() {
+            return;
+        }
+    }
+}
+
+/* ============================================================================
+ * CLIENT
+ * ============================================================================
+ */
+
+struct MqttClient {
+    writer: Arc<Mutex<TcpStream>>,
+    rx: Receiver<Incoming>,
+    connected: Arc<AtomicBool>,
+    queue: RefCell<VecDeque<Message>>,
+    subscriptions: RefCell<Vec<(String, Value)>>,
+    next_id: Cell<u16>,
+}
+
+thread_local! {
+    /// Live clients, so the interpreter can deliver their messages.
+    static CLIENTS: RefCell<Vec<Weak<MqttClient>>> = const { RefCell::new(Vec::new()) };
+}
+
+impl MqttClient {
+    fn connect(opts: ConnectOptions) -> Result<Rc<MqttClient>, String> {
+        let mut stream = TcpStream::connect((opts.host.as_str(), opts.port))
+            .map_err(|e| format!("Mqtt.connect: {}:{}: {}", opts.host, opts.port, e))?;
+
+        stream
+            .write_all(&connect_packet(&opts))
+            .map_err(|e| format!("Mqtt.connect: {}", e))?;
+
+        let _ = stream.set_read_timeout(Some(ACK_TIMEOUT));
+        let (header, body) = read_packet(&mut stream).map_err(|e| format!("Mqtt.connect: no CONNACK: {}", e))?;
+
+        if header != CONNACK {
+            return Err("Mqtt.connect: broker did not answer with CONNACK".to_string());
+        }
+
+        match body.get(1) {
+            Some(0) => {}
+            Some(1) => return Err("Mqtt.connect: unacceptable protocol version".to_string()),
+            Some(2) => return Err("Mqtt.connect: client id rejected".to_string()),
+            Some(3) => return Err("Mqtt.connect: server unavailable".to_string()),
+            Some(4) => return Err("Mqtt.connect: bad username or password".to_string()),
+            Some(5) => return Err("Mqtt.connect: not authorized".to_string()),
+            _ => return Err("Mqtt.connect: connection refused".to_string()),
+        }
+
+        let writer = Arc::new(Mutex::new(
+            stream.try_clone().map_err(|e| format!("Mqtt.connect: {}", e))?,
+        ));
+        let connected = Arc::new(AtomicBool::new(true));
+        let (tx, rx) = mpsc::channel();
+
+        {
+            let writer = writer.clone();
+            let connected = connected.clone();
+            let keep_alive = opts.keep_alive;
+            thread::spawn(move || reader_thread(stream, writer, keep_alive, connected, tx));
+        }
+
+        let client = Rc::new(MqttClient {
+            writer,
+            rx,
+            connected,
+            queue: RefCell::new(VecDeque::new()),
+            subscriptions: RefCell::new(Vec::new()),
+            next_id: Cell::new(0),
+        });
+
+        CLIENTS.with(|c| c.borrow_mut().push(Rc::downgrade(&client)));
+        Ok(client)
+    }
+
+    fn next_id(&self) -> u16 {
+        // Packet id 0 is reserved
+        let id = self.next_id.get() % u16::MAX + 1;
+        self.next_id.set(id);
+        id
+    }
+
+    fn send(&self, bytes: &[u8]) -> Result<(), String> {
+        if !self.connected.load(Ordering::SeqCst) {
+            return Err("Mqtt: client is not connected".to_string());
+        }
+
+        self.writer
+            .lock()
+            .map_err(|_| "Mqtt: connection lock poisoned".to_string())?
+            .write_all(bytes)
+            .map_err(|e| format!("Mqtt: {}", e))
+    }
+
+    /// Handles one report from the reader; returns it if it is an ack.
+    fn accept(&self, incoming: Incoming) -> Option<Incoming> {
+        match incoming {
+            Incoming::Message(message) => {
+                self.queue.borrow_mut().push_back(message);
+                None
+            }
+            other => Some(other),
+        }
+    }
+
+    /// Waits for the ack `pick` accepts, queueing messages meanwhile.
+    fn wait_for<T>(&self, pick: impl Fn(&Incoming) -> Option<T>) -> Result<T, String> {
+        let deadline = Instant::now() + ACK_TIMEOUT;
+
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+
+            match self.rx.recv_timeout(remaining) {
+                Ok(incoming) => match self.accept(incoming) {
+                    Some(Incoming::Closed(reason)) => return Err(format!("Mqtt: connection closed: {}", reason)),
+                    Some(ack) => {
+                        if let Some(result) = pick(&ack) {
+                            return Ok(result);
+                        }
+                    }
+                    None => {}
+                },
+                Err(RecvTimeoutError::Timeout) => return Err("Mqtt: timed out waiting for the broker".to_string()),
+                Err(RecvTimeoutError::Disconnected) => return Err("Mqtt: connection closed".to_string()),
+            }
+        }
+    }
+
+    fn publish(&self, topic: &str, payload: &[u8], qos: u8, retain: bool) -> Result<(), String> {
+        let mut body = Vec::new();
+        write_str(&mut body, topic.as_bytes());
+
+        let id = (qos > 0).then(|| self.next_id());
+        if let Some(id) = id {
+            body.extend_from_slice(&id.to_be_bytes());
+        }
+        body.extend_from_slice(payload);
+
+        self.send(&packet(PUBLISH | (qos << 1) | retain as u8, &body))?;
+
+        match id {
+            Some(id) => self.wait_for(|ack| matches!(ack, Incoming::PubAck(got) if *got == id).then_some(())),
+            None => Ok(()),
+        }
+    }
+
+    fn subscribe(&self, filter: &str, callback: Value, qos: u8) -> Result<u8, String> {
+        let id = self.next_id();
+        let mut body = id.to_be_bytes().to_vec();
+        write_str(&mut body, filter.as_bytes());
+        body.push(qos);
+
+        self.send(&packet(SUBSCRIBE, &body))?;
+
+        let granted = self.wait_for(|ack| match ack {
+            Incoming::SubAck(got, codes) if *got == id => Some(codes.first().copied().unwrap_or(0x80)),
+            _ => None,
+        })?;
+
+        if granted == 0x80 {
+            return Err(format!("Mqtt: broker refused subscription to '{}'", filter));
+        }
+
+        self.subscriptions.borrow_mut().push((filter.to_string(), callback));
+        Ok(granted)
+    }
+
+    fn unsubscribe(&self, filter: &str) -> Result<(), String> {
+        let id = self.next_id();
+        let mut body = id.to_be_bytes().to_vec();
+        write_str(&mut body, filter.as_bytes());
+
+        self.send(&packet(UNSUBSCRIBE, &body))?;
+        self.wait_for(|ack| matches!(ack, Incoming::UnsubAck(got) if *got == id).then_some(()))?;
+
+        self.subscriptions.borrow_mut().retain(|(f, _)| f != filter);
+        Ok(())
+    }
+
+    /// Runs callbacks for every message received so far.
+    ///
+    /// # Errors
+    /// The first error raised by a callback; later messages stay queued.
+    fn dispatch(&self) -> Result<usize, PawxError> {
+        while let Ok(incoming) = self.rx.try_recv() {
+            self.accept(incoming);
+        }
+
+        let mut handled = 0;
+
+        loop {
+            // Release the queue borrow before running user callbacks
+            let Some(message) = self.queue.borrow_mut().pop_front() else {
+                return Ok(handled);
+            };
+
+            let callbacks: Vec<Value> = self
+                .subscriptions
+                .borrow()
+                .iter()
+                .filter(|(filter, _)| topic_matches(filter, &message.topic))
+                .map(|(_, cb)| cb.clone())
+                .collect();
+
+            let value = message.to_value();
+            for callback in callbacks {
+                if let Value::NativeFunction(f) = callback {
+                    f(vec![value.clone()])?;
+                }
+            }
+
+            handled += 1;
+        }
+    }
+
+    /// Dispatches messages as they arrive until `duration` passes (or the
+    /// connection closes).
+    fn run_for(&self, duration: Option<Duration>) -> Result<(), PawxError> {
+        let deadline = duration.map(|d| Instant::now() + d);
+
+        loop {
+            self.dispatch()?;
+
+            if !self.connected.load(Ordering::SeqCst) {
+                return Ok(());
+            }
+
+            let wait = match deadline {
+                Some(deadline) if Instant::now() >= deadline => return Ok(()),
+                Some(deadline) => deadline - Instant::now(),
+                None => Duration::from_secs(3600),
+            };
+
+            if let Ok(incoming) = self.rx.recv_timeout(wait) {
+                self.accept(incoming);
+            }
+        }
+    }
+
+    fn disconnect(&self) {
+        if self.connected.swap(false, Ordering::SeqCst) {
+            if let Ok(mut w) = self.writer.lock() {
+                let _ = w.write_all(&[DISCONNECT, 0]);
+                let _ = w.shutdown(Shutdown::Both);
+            }
+        }
+    }
+}
+
+/// Delivers queued messages for every live client.
+///
+/// Called by the interpreter between top-level statements and before exit.
+///
+/// # Errors
+/// The first error raised by a subscription callback.
+pub fn dispatch_messages() -> Result<(), PawxError> {
+    let clients: Vec<Rc<MqttClient>> = CLIENTS.with(|c| {
+        let mut clients = c.borrow_mut();
+        clients.retain(|w| w.strong_count() > 0);
+        clients.iter().filter_map(Weak::upgrade).collect()
+    });
+
+    for client in clients {
+        client.dispatch()?;
+    }
+
+    Ok(())
+}
+
+/* ============================================================================
+ * PAWX BINDING
+ * ============================================================================
+ */
+
+/// Creates the PAWX object for a connected client.
+fn client_value(client: Rc<MqttClient>) -> Value {
+    let mut fields = HashMap::new();
+
+    let c = client.clone();
+    fields.insert(
+        "publish".to_string(),
+        native(move |args| {
+            let topic = match args.first() {
+                Some(Value::String(t)) => t.to_string(),
+                _ => return Ok(to_furure(Err("client.publish(topic, payload, options?): topic must be a string".to_string()))),
+            };
+            let payload = payload_bytes(args.get(1), "client.publish")?;
+            let (qos, retain) = qos_retain(args.get(2), "client.publish")?;
+
+            Ok(to_furure(c.publish(&topic, &payload, qos, retain).map(|_| Value::Null)))
+        }),
+    );
+
+    let c = client.clone();
+    fields.insert(
+        "subscribe".to_string(),
+        native(move |args| {
+            let filter = match args.first() {
+                Some(Value::String(f)) => f.to_string(),
+                _ => return Ok(to_furure(Err("client.subscribe(filter, callback, options?): filter must be a string".to_string()))),
+            };
+            let callback = match args.get(1) {
+                Some(cb @ Value::NativeFunction(_)) => cb.clone(),
+                _ => return Ok(to_furure(Err("client.subscribe(filter, callback, options?): callback must be a function".to_string()))),
+            };
+            let (qos, _) = qos_retain(args.get(2), "client.subscribe")?;
+
+            Ok(to_furure(c.subscribe(&filter, callback, qos).map(|granted| Value::Number(granted as f64))))
+        }),
+    );
+
+    let c = client.clone();
+    fields.insert(
+        "unsubscribe".to_string(),
+        native(move |args| match args.first() {
+            Some(Value::String(filter)) => Ok(to_furure(c.unsubscribe(filter).map(|_| Value::Null))),
+            _ => Ok(to_furure(Err("client.unsubscribe(filter): filter must be a string".to_string()))),
+        }),
+    );
+
+    let c = client.clone();
+    fields.insert(
+        "poll".to_string(),
+        native(move |_| Ok(Value::Number(c.dispatch()? as f64))),
+    );
+
+    let c = client.clone();
+    fields.insert(
+        "loop".to_string(),
+        native(move |args| {
+            let duration = match args.first() {
+                Some(Value::Number(ms)) if *ms >= 0.0 => Some(Duration::from_millis(*ms as u64)),
+                _ => None,
+            };
+            c.run_for(duration)?;
+            Ok(Value::Null)
+        }),
+    );
+
+    let c = client.clone();
+    fields.insert(
+        "isConnected".to_string(),
+        native(move |_| Ok(Value::Bool(c.connected.load(Ordering::SeqCst)))),
+    );
+
+    let c = client;
+    fields.insert(
+        "disconnect".to_string(),
+        native(move |_| {
+            c.disconnect();
+            Ok(Value::Null)
+        }),
+    );
+
+    object(fields)
+}
+
+/// Builds the global `Mqtt` object.
+pub fn create_global_mqtt_object() -> HashMap<String, Value> {
+    let mut mqtt = HashMap::new();
+
+    mqtt.insert(
+        "connect".to_string(),
+        native(|args| {
+            let opts = parse_connect(&args)?;
+            match MqttClient::connect(opts) {
+                Ok(client) => Ok(client_value(client)),
+                Err(e) => raise_error(e.to_string()),
+            }
+        }),
+    );
+
+    mqtt
+}
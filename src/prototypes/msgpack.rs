@@ -0,0 +1,83 @@
+/*
+ * ============================================================================
+ * PAWX - Code with Claws! 🐾
+ * ============================================================================
+ *
+ * MessagePack Interop for PAWX (`Msgpack`)
+ *
+ * Supports:
+ *   - Msgpack.encode(value)   -> array<number> (bytes)
+ *   - Msgpack.decode(bytes)   -> value
+ *
+ * Unlike `Codec`, no PAWX extension types are written, so the bytes can be
+ * read by any MessagePack library:
+ *
+ *   - Decimals and regexes are written as strings
+ *   - Tuples, Float64Arrays and immutable lists are written as arrays
+ *   - Immutable maps are written as maps
+ *   - Durations are written as their length in milliseconds
+ *
+ * On decode, extension values from other producers come back as raw byte
+ * arrays, and binary values as arrays of numbers.
+ *
+ * --------------------------------------------------------------------------
+ * Author:   Sam Wilcox
+ * Email:    sam@pawx-lang.com
+ * Website:  https://www.pawx-lang.com
+ * GitHub:   https://github.com/samwilcox/pawx
+ *
+ * License:
+ * This file is part of the PAWX programming language project.
+ *
+ * PAWX is dual-licensed under the terms of:
+ *   - The MIT License
+ *   - The Apache License, Version 2.0
+ *
+ * You may choose either license to govern your use of this software.
+ * Full license text available at:
+ *     https://license.pawx-lang.com
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under these licenses is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *
+ * ==========================================================================
+ */
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::error::PawxError;
+use crate::interpreter::calls::{builtin_error, raise_error};
+use crate::prototypes::codec::{array_to_bytes, bytes_to_array, decode, encode, Mode};
+use crate::value::Value;
+
+fn native(f: impl Fn(Vec<Value>) -> Result<Value, PawxError> + 'static) -> Value {
+    Value::NativeFunction(Arc::new(f))
+}
+
+/// Builds the global `Msgpack` object.
+pub fn create_global_msgpack_object() -> HashMap<String, Value> {
+    let mut msgpack = HashMap::new();
+
+    msgpack.insert(
+        "encode".to_string(),
+        native(|args| {
+            let value = args.first().cloned().unwrap_or(Value::Null);
+            match encode(&value, Mode::Plain) {
+                Ok(bytes) => Ok(bytes_to_array(&bytes)),
+                Err(e) => raise_error(format!("Msgpack.encode(value): {}", e)),
+            }
+        }),
+    );
+
+    msgpack.insert(
+        "decode".to_string(),
+        native(|args| {
+            let bytes = array_to_bytes(args.first(), "Msgpack.decode(bytes)")?;
+            decode(&bytes, Mode::Plain).map_err(|e| builtin_error(format!("Msgpack.decode(bytes): {}", e)))
+        }),
+    );
+
+    msgpack
+}
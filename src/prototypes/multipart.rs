@@ -0,0 +1,399 @@
+/*
+ * ============================================================================
+ * PAWX - Code with Claws! 🐾
+ * ============================================================================
+ *
+ * Multipart form-data for PAWX
+ *
+ * Supports:
+ *   - Http.post(url, { multipart: { field: value, file: Fs.stream(path) } })
+ *   - Fs.stream(path, type?)          a file part, read while it is sent
+ *   - multipart/form-data request bodies parsed into `req.body`
+ *
+ * Client side, each entry of the `multipart` object becomes one part:
+ * strings as they are, other values by their string form, arrays as one
+ * part per item, `null` not at all. Files from `Fs.stream` are copied to
+ * the connection in chunks rather than loaded first, and their sizes are
+ * read when the request is built so it can carry a `Content-Length`.
+ *
+ * Server side, text fields parse to strings and file parts to
+ * `{ filename, type, size, content }`; a name used more than once gives
+ * an array.
+ *
+ * --------------------------------------------------------------------------
+ * Author:   Sam Wilcox
+ * Email:    sam@pawx-lang.com
+ * Website:  https://www.pawx-lang.com
+ * GitHub:   https://github.com/samwilcox/pawx
+ *
+ * License:
+ * This file is part of the PAWX programming language project.
+ *
+ * PAWX is dual-licensed under the terms of:
+ *   - The MIT License
+ *   - The Apache License, Version 2.0
+ *
+ * You may choose either license to govern your use of this software.
+ * Full license text available at:
+ *     https://license.pawx-lang.com
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under these licenses is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *
+ * ==========================================================================
+ */
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::io::{self, Read, Write};
+use std::rc::Rc;
+use std::sync::Arc;
+
+use rand::Rng;
+
+use crate::prototypes::array::create_array_proto;
+use crate::prototypes::http_static::content_type_for;
+use crate::prototypes::memfs::FsBackend;
+use crate::value::Value;
+
+/// Field of an `Fs.stream` object holding the file itself (see
+/// [`claim_file`]).
+const FILE_MARKER: &str = "__pawx_file_";
+
+thread_local! {
+    /// The file an `Fs.stream` object last handed over.
+    static CLAIMED: RefCell<Option<FileSource>> = const { RefCell::new(None) };
+}
+
+/* ============================================================================
+ * FILE PARTS
+ * ============================================================================
+ */
+
+/// A file to upload, read through the `Fs` backend it was opened with.
+#[derive(Debug, Clone)]
+pub struct FileSource {
+    path: String,
+    backend: FsBackend,
+}
+
+impl FileSource {
+    fn size(&self) -> io::Result<u64> {
+        self.backend.file_size(&self.path)
+    }
+
+    fn open(&self) -> io::Result<Box<dyn Read>> {
+        self.backend.open_read(&self.path)
+    }
+}
+
+/// Builds the object `Fs.stream(path, type?)` returns:
+/// `{ path, name, type }`, plus the file itself under a hidden field.
+pub fn file_part_value(path: String, content_type: Option<String>, backend: FsBackend) -> Value {
+    let name = path.rsplit(['/', '\\']).next().unwrap_or(&path).to_string();
+    let content_type = content_type.unwrap_or_else(|| content_type_for(&path).to_string());
+
+    let source = FileSource { path: path.clone(), backend };
+
+    let mut fields = HashMap::new();
+    fields.insert("path".to_string(), Value::String(path.into()));
+    fields.insert("name".to_string(), Value::String(name.into()));
+    fields.insert("type".to_string(), Value::String(content_type.into()));
+    fields.insert(
+        FILE_MARKER.to_string(),
+        Value::NativeFunction(Arc::new(move |_args| {
+            CLAIMED.with(|c| *c.borrow_mut() = Some(source.clone()));
+            Ok(Value::Null)
+        })),
+    );
+
+    Value::Object {
+        fields: Rc::new(RefCell::new(fields)),
+    }
+}
+
+/// The file behind an `Fs.stream` object, with its name and type.
+///
+/// Natives cannot carry Rust data, so the object's hidden function is
+/// called and leaves its file in [`CLAIMED`] for us to take.
+fn claim_file(value: &Value) -> Option<(FileSource, String, String)> {
+    let Value::Object { fields } = value else {
+        return None;
+    };
+
+    let fields = fields.borrow();
+    let Some(Value::NativeFunction(hand_over)) = fields.get(FILE_MARKER) else {
+        return None;
+    };
+
+    hand_over(Vec::new()).ok()?;
+    let source = CLAIMED.with(|c| c.borrow_mut().take())?;
+
+    let text = |key: &str| fields.get(key).map(Value::to_pawx_string).unwrap_or_default();
+    Some((source, text("name"), text("type")))
+}
+
+/* ============================================================================
+ * ENCODING
+ * ============================================================================
+ */
+
+#[derive(Debug, Clone)]
+enum PartBody {
+    Text(String),
+    File { source: FileSource, size: u64 },
+}
+
+#[derive(Debug, Clone)]
+struct Part {
+    name: String,
+    filename: Option<String>,
+    content_type: Option<String>,
+    body: PartBody,
+}
+
+/// A multipart/form-data request body.
+#[derive(Debug, Clone)]
+pub struct Multipart {
+    boundary: String,
+    parts: Vec<Part>,
+}
+
+/// Makes `value` safe inside a quoted header parameter.
+fn quote_param(value: &str) -> String {
+    value.replace('"', "%22").replace(['\r', '\n'], " ")
+}
+
+impl Multipart {
+    /// Builds a body from a PAWX `multipart` object, reading the size of
+    /// every file part.
+    pub fn from_value(value: &Value) -> Result<Self, String> {
+        let Value::Object { fields } = value else {
+            return Err(format!("Http: `multipart` must be an object, got {}", value.type_name()));
+        };
+
+        // Sorted so the same object always encodes the same way
+        let mut entries: Vec<(String, Value)> = fields.borrow().iter().map(|(k, v)| (k.clone(), v.clone())).collect();
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let mut parts = Vec::new();
+        for (name, value) in entries {
+            match value {
+                Value::Array { values, .. } => {
+                    for item in values.borrow().iter() {
+                        Self::push_part(&mut parts, &name, item)?;
+                    }
+                }
+                other => Self::push_part(&mut parts, &name, &other)?,
+            }
+        }
+
+        let boundary = format!("pawx-{:032x}", rand::thread_rng().gen::<u128>());
+        Ok(Multipart { boundary, parts })
+    }
+
+    fn push_part(parts: &mut Vec<Part>, name: &str, value: &Value) -> Result<(), String> {
+        if let Some((source, filename, content_type)) = claim_file(value) {
+            let size = source
+                .size()
+                .map_err(|e| format!("Http: cannot read '{}' for upload: {}", source.path, e))?;
+
+            parts.push(Part {
+                name: name.to_string(),
+                filename: Some(filename),
+                content_type: Some(content_type),
+                body: PartBody::File { source, size },
+            });
+            return Ok(());
+        }
+
+        let text = match value {
+            Value::Null => return Ok(()),
+            Value::String(s) => s.to_string(),
+            other => other.to_pawx_string(),
+        };
+
+        parts.push(Part {
+            name: name.to_string(),
+            filename: None,
+            content_type: None,
+            body: PartBody::Text(text),
+        });
+        Ok(())
+    }
+
+    /// The `Content-Type` header value, boundary included.
+    pub fn content_type(&self) -> String {
+        format!("multipart/form-data; boundary={}", self.boundary)
+    }
+
+    /// The delimiter line and headers that open `part`.
+    fn part_head(&self, part: &Part) -> String {
+        let mut head = format!(
+            "--{}\r\nContent-Disposition: form-data; name=\"{}\"",
+            self.boundary,
+            quote_param(&part.name)
+        );
+        if let Some(filename) = &part.filename {
+            head.push_str(&format!("; filename=\"{}\"", quote_param(filename)));
+        }
+        head.push_str("\r\n");
+        if let Some(content_type) = &part.content_type {
+            head.push_str(&format!("Content-Type: {}\r\n", content_type));
+        }
+        head.push_str("\r\n");
+        head
+    }
+
+    fn closing(&self) -> String {
+        format!("--{}--\r\n", self.boundary)
+    }
+
+    /// Total encoded size in bytes.
+    pub fn content_length(&self) -> u64 {
+        let parts: u64 = self
+            .parts
+            .iter()
+            .map(|part| {
+                let body = match &part.body {
+                    PartBody::Text(text) => text.len() as u64,
+                    PartBody::File { size, .. } => *size,
+                };
+                self.part_head(part).len() as u64 + body + 2
+            })
+            .sum();
+
+        parts + self.closing().len() as u64
+    }
+
+    /// Writes the body, copying files to `out` in chunks.
+    ///
+    /// Fails if a file can no longer be read or no longer has the size it
+    /// had when the body was built.
+    pub fn write_to(&self, out: &mut dyn Write) -> io::Result<()> {
+        for part in &self.parts {
+            out.write_all(self.part_head(part).as_bytes())?;
+
+            match &part.body {
+                PartBody::Text(text) => out.write_all(text.as_bytes())?,
+                PartBody::File { source, size } => {
+                    let copied = io::copy(&mut source.open()?.take(*size), out)?;
+                    if copied != *size {
+                        return Err(io::Error::new(
+                            io::ErrorKind::UnexpectedEof,
+                            format!("'{}' changed size while uploading", source.path),
+                        ));
+                    }
+                }
+            }
+
+            out.write_all(b"\r\n")?;
+        }
+
+        out.write_all(self.closing().as_bytes())
+    }
+
+    /// The whole body in memory, for requests answered by `Test.httpMock`.
+    pub fn to_bytes(&self) -> io::Result<Vec<u8>> {
+        let mut out = Vec::with_capacity(self.content_length() as usize);
+        self.write_to(&mut out)?;
+        Ok(out)
+    }
+}
+
+/* ============================================================================
+ * PARSING
+ * ============================================================================
+ */
+
+/// The `boundary` parameter of a multipart `Content-Type`.
+fn boundary_of(content_type: &str) -> Option<&str> {
+    content_type.split(';').find_map(|param| {
+        let (key, value) = param.trim().split_once('=')?;
+        key.eq_ignore_ascii_case("boundary").then(|| value.trim_matches('"'))
+    })
+}
+
+/// A parameter of a `Content-Disposition` header, e.g. `name`.
+fn disposition_param<'a>(disposition: &'a str, name: &str) -> Option<&'a str> {
+    disposition.split(';').find_map(|param| {
+        let (key, value) = param.trim().split_once('=')?;
+        key.eq_ignore_ascii_case(name).then(|| value.trim_matches('"'))
+    })
+}
+
+/// Parses a multipart/form-data body (see module docs).
+///
+/// Returns `None` if `content_type` has no boundary.
+pub fn parse(body: &str, content_type: &str) -> Option<Value> {
+    let delimiter = format!("--{}", boundary_of(content_type)?);
+    let mut fields: HashMap<String, Value> = HashMap::new();
+
+    // Everything before the first delimiter is preamble
+    for segment in body.split(delimiter.as_str()).skip(1) {
+        if segment.starts_with("--") {
+            break;
+        }
+
+        let segment = segment.strip_prefix("\r\n").unwrap_or(segment);
+        let segment = segment.strip_suffix("\r\n").unwrap_or(segment);
+        let Some((head, content)) = segment.split_once("\r\n\r\n") else {
+            continue;
+        };
+
+        let mut disposition = "";
+        let mut part_type = None;
+        for line in head.lines() {
+            if let Some((key, value)) = line.split_once(':') {
+                if key.trim().eq_ignore_ascii_case("Content-Disposition") {
+                    disposition = value.trim();
+                } else if key.trim().eq_ignore_ascii_case("Content-Type") {
+                    part_type = Some(value.trim());
+                }
+            }
+        }
+
+        let Some(name) = disposition_param(disposition, "name") else {
+            continue;
+        };
+
+        let value = match disposition_param(disposition, "filename") {
+            Some(filename) => {
+                let mut file = HashMap::new();
+                file.insert("filename".to_string(), Value::String(filename.into()));
+                file.insert(
+                    "type".to_string(),
+                    Value::String(part_type.unwrap_or("application/octet-stream").into()),
+                );
+                file.insert("size".to_string(), Value::Number(content.len() as f64));
+                file.insert("content".to_string(), Value::String(content.into()));
+                Value::Object {
+                    fields: Rc::new(RefCell::new(file)),
+                }
+            }
+            None => Value::String(content.into()),
+        };
+
+        match fields.get_mut(name) {
+            Some(Value::Array { values, .. }) => values.borrow_mut().push(value),
+            Some(first) => {
+                let first = std::mem::replace(first, Value::Null);
+                fields.insert(
+                    name.to_string(),
+                    Value::Array {
+                        values: Rc::new(RefCell::new(vec![first, value])),
+                        proto: create_array_proto(),
+                    },
+                );
+            }
+            None => {
+                fields.insert(name.to_string(), value);
+            }
+        }
+    }
+
+    Some(Value::Object {
+        fields: Rc::new(RefCell::new(fields)),
+    })
+}
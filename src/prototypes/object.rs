@@ -47,8 +47,11 @@ use std::sync::Arc;
 use std::cell::RefCell;
 use std::rc::Rc;
 
+use crate::error::PawxError;
 use crate::value::Value;
 use crate::prototypes::array::create_array_proto;
+use crate::interpreter::calls::raise_error;
+use crate::interpreter::determinism;
 
 /// Creates and returns the global `Object` namespace for the PAWX runtime.
 ///
@@ -116,25 +119,25 @@ pub fn create_global_object_value() -> Value {
 /// snuggle obj = { a: 1, b: 2 };
 /// meow(Object.keys(obj)); // ["a", "b"]
 /// ```
-pub fn object_keys(args: Vec<Value>) -> Value {
+pub fn object_keys(args: Vec<Value>) -> Result<Value, PawxError> {
     if args.len() != 1 {
-        panic!("Object.keys(obj) requires 1 argument");
+        return raise_error("Object.keys(obj) requires 1 argument");
     }
 
     match &args[0] {
         Value::Object { fields } => {
-            let keys = fields
-                .borrow()
-                .keys()
-                .map(|k| Value::String(k.clone()))
+            let fields = fields.borrow();
+            let keys = determinism::entries(&fields)
+                .into_iter()
+                .map(|(k, _)| Value::String(k.as_str().into()))
                 .collect::<Vec<_>>();
 
-            Value::Array {
+            Ok(Value::Array {
                 values: std::rc::Rc::new(std::cell::RefCell::new(keys)),
                 proto: create_array_proto(),
-            }
+            })
         }
-        _ => panic!("Object.keys() requires an object"),
+        _ => raise_error("Object.keys() requires an object"),
     }
 }
 
@@ -157,21 +160,25 @@ pub fn object_keys(args: Vec<Value>) -> Value {
 /// snuggle obj = { a: 1, b: 2 };
 /// meow(Object.values(obj)); // [1, 2]
 /// ```
-pub fn object_values(args: Vec<Value>) -> Value {
+pub fn object_values(args: Vec<Value>) -> Result<Value, PawxError> {
     if args.len() != 1 {
-        panic!("Object.values(obj) requires 1 argument");
+        return raise_error("Object.values(obj) requires 1 argument");
     }
 
     match &args[0] {
         Value::Object { fields } => {
-            let values = fields.borrow().values().cloned().collect::<Vec<_>>();
+            let fields = fields.borrow();
+            let values = determinism::entries(&fields)
+                .into_iter()
+                .map(|(_, v)| v.clone())
+                .collect::<Vec<_>>();
 
-            Value::Array {
+            Ok(Value::Array {
                 values: std::rc::Rc::new(std::cell::RefCell::new(values)),
                 proto: create_array_proto(),
-            }
+            })
         }
-        _ => panic!("Object.values() requires an object"),
+        _ => raise_error("Object.values() requires an object"),
     }
 }
 
@@ -197,29 +204,29 @@ pub fn object_values(args: Vec<Value>) -> Value {
 /// meow(Object.entries(obj));
 /// // [["a", 1], ["b", 2]]
 /// ```
-pub fn object_entries(args: Vec<Value>) -> Value {
+pub fn object_entries(args: Vec<Value>) -> Result<Value, PawxError> {
     if args.len() != 1 {
-        panic!("Object.entries(obj) requires 1 argument");
+        return raise_error("Object.entries(obj) requires 1 argument");
     }
 
     match &args[0] {
         Value::Object { fields } => {
-            let entries = fields
-                .borrow()
-                .iter()
+            let fields = fields.borrow();
+            let entries = determinism::entries(&fields)
+                .into_iter()
                 .map(|(k, v)| {
                     Value::Tuple(vec![
-                        Value::String(k.clone()),
+                        Value::String(k.as_str().into()),
                         v.clone(),
                     ])
                 })
                 .collect::<Vec<_>>();
 
-            Value::Array {
+            Ok(Value::Array {
                 values: std::rc::Rc::new(std::cell::RefCell::new(entries)),
                 proto: create_array_proto(),
-            }
+            })
         }
-        _ => panic!("Object.entries() requires an object"),
+        _ => raise_error("Object.entries() requires an object"),
     }
 }
\ No newline at end of file
@@ -0,0 +1,330 @@
+/*
+ * ============================================================================
+ * PAWX - Code with Claws! 🐾
+ * ============================================================================
+ *
+ * Performance Timing for PAWX (`Perf`)
+ *
+ * Supports:
+ *   - Perf.now()              microseconds since the program started
+ *   - Perf.mark(name)         remembers this moment as `name`; returns it
+ *                             like Perf.now()
+ *   - Perf.measure(a, b?)     the Duration from mark `a` to mark `b` (or to
+ *                             now), recorded for the summary as "a..b"
+ *   - Perf.stopwatch()        { elapsed(), lap(), reset() }; lap() is the
+ *                             Duration since the last lap
+ *   - Perf.entries()          [{ name, count, total, min, max, mean }] per
+ *                             measure, totals as Durations
+ *   - Perf.summary()          the same as a table, ready for meow
+ *   - Perf.clear()            forgets every mark and measure
+ *
+ *   Perf.mark("start");
+ *   build(data);
+ *   Perf.mark("built");
+ *   render(data);
+ *   Perf.measure("start", "built");
+ *   Perf.measure("built");
+ *   meow(Perf.summary());
+ *
+ * Times come from a monotonic clock (`std::time::Instant`), so they never
+ * jump when the system clock is changed, and they keep running under
+ * `--virtual-time`: they measure the program, not its clock. Measuring the
+ * same pair of marks again adds to the same summary row.
+ *
+ * --------------------------------------------------------------------------
+ * Author:   Sam Wilcox
+ * Email:    sam@pawx-lang.com
+ * Website:  https://www.pawx-lang.com
+ * GitHub:   https://github.com/samwilcox/pawx
+ *
+ * License:
+ * This file is part of the PAWX programming language project.
+ *
+ * PAWX is dual-licensed under the terms of:
+ *   - The MIT License
+ *   - The Apache License, Version 2.0
+ *
+ * You may choose either license to govern your use of this software.
+ * Full license text available at:
+ *     https://license.pawx-lang.com
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under these licenses is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *
+ * ==========================================================================
+ */
+
+use std::cell::{Cell, RefCell};
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::error::PawxError;
+use crate::interpreter::calls::raise_error;
+use crate::prototypes::array::create_array_proto;
+use crate::value::Value;
+
+fn native(f: impl Fn(Vec<Value>) -> Result<Value, PawxError> + 'static) -> Value {
+    Value::NativeFunction(Arc::new(f))
+}
+
+fn micros(d: Duration) -> f64 {
+    d.as_nanos() as f64 / 1_000.0
+}
+
+fn millis(d: Duration) -> f64 {
+    d.as_nanos() as f64 / 1_000_000.0
+}
+
+/// Every time recorded for one measure.
+struct Stats {
+    name: String,
+    count: u64,
+    total: Duration,
+    min: Duration,
+    max: Duration,
+}
+
+impl Stats {
+    fn mean(&self) -> Duration {
+        self.total.div_f64(self.count as f64)
+    }
+}
+
+/// The marks and measures of one program.
+struct Perf {
+    origin: Instant,
+    marks: RefCell<HashMap<String, Instant>>,
+    /// In the order each measure was first taken.
+    measures: RefCell<Vec<Stats>>,
+}
+
+impl Perf {
+    fn mark(&self, args: &[Value]) -> Result<Value, PawxError> {
+        let name = match name_arg(args.first(), "Perf.mark(name)") {
+            Ok(name) => name,
+            Err(message) => return raise_error(message),
+        };
+        let now = Instant::now();
+        self.marks.borrow_mut().insert(name, now);
+        Ok(Value::Number(micros(now - self.origin)))
+    }
+
+    fn measure(&self, args: &[Value]) -> Result<Value, PawxError> {
+        match self.between(args) {
+            Ok((name, elapsed)) => {
+                self.record(name, elapsed);
+                Ok(Value::Duration(millis(elapsed)))
+            }
+            Err(message) => raise_error(message),
+        }
+    }
+
+    /// The name and length of the measure `Perf.measure(a, b?)` asks for.
+    fn between(&self, args: &[Value]) -> Result<(String, Duration), String> {
+        let usage = "Perf.measure(a, b?)";
+        let from = name_arg(args.first(), usage)?;
+        let to = match args.get(1) {
+            None | Some(Value::Null) => None,
+            other => Some(name_arg(other, usage)?),
+        };
+
+        let now = Instant::now();
+        let marks = self.marks.borrow();
+        let at = |name: &str| {
+            marks
+                .get(name)
+                .copied()
+                .ok_or_else(|| format!("{}: no mark named '{}'", usage, name))
+        };
+
+        let start = at(&from)?;
+        let end = match &to {
+            Some(to) => at(to)?,
+            None => now,
+        };
+
+        let name = format!("{}..{}", from, to.as_deref().unwrap_or("now"));
+        Ok((name, end.saturating_duration_since(start)))
+    }
+
+    fn record(&self, name: String, elapsed: Duration) {
+        let mut measures = self.measures.borrow_mut();
+        match measures.iter_mut().find(|s| s.name == name) {
+            Some(stats) => {
+                stats.count += 1;
+                stats.total += elapsed;
+                stats.min = stats.min.min(elapsed);
+                stats.max = stats.max.max(elapsed);
+            }
+            None => measures.push(Stats {
+                name,
+                count: 1,
+                total: elapsed,
+                min: elapsed,
+                max: elapsed,
+            }),
+        }
+    }
+
+    fn entries(&self) -> Value {
+        let entries = self
+            .measures
+            .borrow()
+            .iter()
+            .map(|s| {
+                let fields = HashMap::from([
+                    ("name".to_string(), Value::String(s.name.as_str().into())),
+                    ("count".to_string(), Value::Number(s.count as f64)),
+                    ("total".to_string(), Value::Duration(millis(s.total))),
+                    ("min".to_string(), Value::Duration(millis(s.min))),
+                    ("max".to_string(), Value::Duration(millis(s.max))),
+                    ("mean".to_string(), Value::Duration(millis(s.mean()))),
+                ]);
+                Value::Object {
+                    fields: Rc::new(RefCell::new(fields)),
+                }
+            })
+            .collect();
+
+        Value::Array {
+            values: Rc::new(RefCell::new(entries)),
+            proto: create_array_proto(),
+        }
+    }
+
+    /// The measures as a table, slowest total first:
+    ///
+    /// ```text
+    ///   measure       count       total        mean         min         max
+    ///   start..built      1    12.227ms    12.227ms    12.227ms    12.227ms
+    ///   built..now        2     8.399ms     4.200ms     4.194ms     4.205ms
+    /// ```
+    fn summary(&self) -> String {
+        let measures = self.measures.borrow();
+        if measures.is_empty() {
+            return "perf: no measures".to_string();
+        }
+
+        let mut rows: Vec<&Stats> = measures.iter().collect();
+        rows.sort_by_key(|s| Reverse(s.total));
+
+        let width = rows.iter().map(|s| s.name.chars().count()).max().unwrap_or(0).max("measure".len());
+        let ms = |d: Duration| format!("{:.3}ms", millis(d));
+
+        let mut out = format!(
+            "  {:<width$}  {:>5}  {:>10}  {:>10}  {:>10}  {:>10}",
+            "measure", "count", "total", "mean", "min", "max",
+        );
+        for s in rows {
+            out.push_str(&format!(
+                "\n  {:<width$}  {:>5}  {:>10}  {:>10}  {:>10}  {:>10}",
+                s.name,
+                s.count,
+                ms(s.total),
+                ms(s.mean()),
+                ms(s.min),
+                ms(s.max),
+            ));
+        }
+        out
+    }
+}
+
+fn name_arg(value: Option<&Value>, usage: &str) -> Result<String, String> {
+    match value {
+        Some(Value::String(s)) => Ok(s.to_string()),
+        _ => Err(format!("{} expects mark names as strings", usage)),
+    }
+}
+
+/// Builds the object for a new stopwatch, started now.
+fn stopwatch() -> Value {
+    let started = Rc::new(Cell::new(Instant::now()));
+    let lap = Rc::new(Cell::new(started.get()));
+    let mut fields = HashMap::new();
+
+    let s = started.clone();
+    fields.insert(
+        "elapsed".to_string(),
+        native(move |_| Ok(Value::Duration(millis(s.get().elapsed())))),
+    );
+
+    let l = lap.clone();
+    fields.insert(
+        "lap".to_string(),
+        native(move |_| {
+            let now = Instant::now();
+            let elapsed = now - l.replace(now);
+            Ok(Value::Duration(millis(elapsed)))
+        }),
+    );
+
+    fields.insert(
+        "reset".to_string(),
+        native(move |_| {
+            let now = Instant::now();
+            started.set(now);
+            lap.set(now);
+            Ok(Value::Null)
+        }),
+    );
+
+    Value::Object {
+        fields: Rc::new(RefCell::new(fields)),
+    }
+}
+
+/// Creates the global `Perf` object, with its clock starting now.
+///
+/// # PAWX Example
+/// ```pawx
+/// snuggle sw = Perf.stopwatch();
+/// work();
+/// meow("work took", sw.lap());
+/// ```
+pub fn create_global_perf_object() -> HashMap<String, Value> {
+    let perf = Rc::new(Perf {
+        origin: Instant::now(),
+        marks: RefCell::new(HashMap::new()),
+        measures: RefCell::new(Vec::new()),
+    });
+    let mut map = HashMap::new();
+
+    let p = perf.clone();
+    map.insert(
+        "now".to_string(),
+        native(move |_| Ok(Value::Number(micros(p.origin.elapsed())))),
+    );
+
+    let p = perf.clone();
+    map.insert("mark".to_string(), native(move |args| p.mark(&args)));
+
+    let p = perf.clone();
+    map.insert("measure".to_string(), native(move |args| p.measure(&args)));
+
+    map.insert("stopwatch".to_string(), native(|_| Ok(stopwatch())));
+
+    let p = perf.clone();
+    map.insert("entries".to_string(), native(move |_| Ok(p.entries())));
+
+    let p = perf.clone();
+    map.insert(
+        "summary".to_string(),
+        native(move |_| Ok(Value::String(p.summary().into()))),
+    );
+
+    map.insert(
+        "clear".to_string(),
+        native(move |_| {
+            perf.marks.borrow_mut().clear();
+            perf.measures.borrow_mut().clear();
+            Ok(Value::Null)
+        }),
+    );
+
+    map
+}
@@ -0,0 +1,175 @@
+/*
+ * ============================================================================
+ * PAWX - Code with Claws! 🐾
+ * ============================================================================
+ *
+ * Process Information for PAWX (`Process`)
+ *
+ * Supports:
+ *   - Process.argv        [script, ...args] as passed to `pawx`
+ *   - Process.env         environment variables (live, see below)
+ *   - Process.cwd()       current working directory
+ *   - Process.exit(code?) exit immediately (default 0)
+ *   - Process.platform    "linux", "macos", "windows", ...
+ *
+ * `argv` comes from the prelude, so `pawx app.px a b` and `pawx run start
+ * a b` both see `["app.px", "a", "b"]`.
+ *
+ * `Process.env` is read from the environment when the script first uses
+ * `Process`, and assigning to it changes the real environment, which
+ * programs the script starts (and libraries that read variables such as
+ * `HTTPS_PROXY`) then see. Assigning `null` removes a variable; any other
+ * value is stored as its string form:
+ *
+ *   Process.env.LOG_LEVEL = "debug";
+ *   Process.env.TOKEN = null;
+ *
+ * --------------------------------------------------------------------------
+ * Author:   Sam Wilcox
+ * Email:    sam@pawx-lang.com
+ * Website:  https://www.pawx-lang.com
+ * GitHub:   https://github.com/samwilcox/pawx
+ *
+ * License:
+ * This file is part of the PAWX programming language project.
+ *
+ * PAWX is dual-licensed under the terms of:
+ *   - The MIT License
+ *   - The Apache License, Version 2.0
+ *
+ * You may choose either license to govern your use of this software.
+ * Full license text available at:
+ *     https://license.pawx-lang.com
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under these licenses is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *
+ * ==========================================================================
+ */
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::env;
+use std::rc::{Rc, Weak};
+use std::sync::Arc;
+
+use crate::error::PawxError;
+use crate::interpreter::calls::raise_error;
+use crate::prototypes::array::create_array_proto;
+use crate::value::Value;
+
+type Fields = RefCell<HashMap<String, Value>>;
+
+thread_local! {
+    /// The `Process.env` objects on this thread (one per prelude that built
+    /// `Process`), so an assignment to one reaches the environment and the
+    /// others.
+    static ENV_OBJECTS: RefCell<Vec<Weak<Fields>>> = const { RefCell::new(Vec::new()) };
+}
+
+fn native(f: impl Fn(Vec<Value>) -> Result<Value, PawxError> + 'static) -> Value {
+    Value::NativeFunction(Arc::new(f))
+}
+
+/// Called after `obj.name = value` on a plain object; passes the change on
+/// to the environment if `obj` is a `Process.env`.
+///
+/// # Errors
+/// If `name` or `value` cannot be an environment variable.
+pub fn on_object_set(fields: &Rc<Fields>, name: &str, value: &Value) -> Result<(), String> {
+    let is_env = ENV_OBJECTS.with(|envs| {
+        envs.borrow()
+            .iter()
+            .any(|env| std::ptr::eq(env.as_ptr(), Rc::as_ptr(fields)))
+    });
+    if !is_env {
+        return Ok(());
+    }
+
+    let value = match value {
+        Value::Null => None,
+        other => Some(other.to_pawx_string()),
+    };
+
+    if name.is_empty() || name.contains(['=', '\0']) || value.as_ref().is_some_and(|v| v.contains('\0')) {
+        fields.borrow_mut().remove(name);
+        return Err(format!("'{}' cannot be set as an environment variable", name));
+    }
+
+    match &value {
+        Some(value) => env::set_var(name, value),
+        None => env::remove_var(name),
+    }
+
+    ENV_OBJECTS.with(|envs| {
+        for env in envs.borrow().iter().filter_map(Weak::upgrade) {
+            let mut env = env.borrow_mut();
+            match &value {
+                Some(value) => env.insert(name.to_string(), Value::String(value.as_str().into())),
+                None => env.remove(name),
+            };
+        }
+    });
+
+    Ok(())
+}
+
+/// A new `Process.env` object holding the current environment.
+fn env_object() -> Value {
+    let fields: Rc<Fields> = Rc::new(RefCell::new(
+        env::vars().map(|(k, v)| (k, Value::String(v.into()))).collect(),
+    ));
+
+    ENV_OBJECTS.with(|envs| {
+        let mut envs = envs.borrow_mut();
+        envs.retain(|env| env.strong_count() > 0);
+        envs.push(Rc::downgrade(&fields));
+    });
+
+    Value::Object { fields }
+}
+
+/// Builds the global `Process` object.
+///
+/// # Parameters
+/// - `argv`: Script path followed by its arguments
+pub fn create_global_process_object(argv: &[String]) -> HashMap<String, Value> {
+    let mut process = HashMap::new();
+
+    process.insert(
+        "argv".to_string(),
+        Value::Array {
+            values: Rc::new(RefCell::new(
+                argv.iter().map(|a| Value::String(a.as_str().into())).collect(),
+            )),
+            proto: create_array_proto(),
+        },
+    );
+
+    process.insert("env".to_string(), env_object());
+
+    process.insert("platform".to_string(), Value::String(env::consts::OS.into()));
+
+    process.insert(
+        "cwd".to_string(),
+        native(|_| match env::current_dir() {
+            Ok(dir) => Ok(Value::String(dir.display().to_string().into())),
+            Err(e) => raise_error(format!("Process.cwd(): {}", e)),
+        }),
+    );
+
+    process.insert(
+        "exit".to_string(),
+        native(|args| {
+            let code = match args.first() {
+                Some(Value::Number(n)) => *n as i32,
+                None | Some(Value::Null) => 0,
+                _ => return raise_error("Process.exit(code?) expects a number"),
+            };
+            std::process::exit(code)
+        }),
+    );
+
+    process
+}
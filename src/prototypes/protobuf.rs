@@ -0,0 +1,519 @@
+/*
+ * ============================================================================
+ * PAWX - Code with Claws! 🐾
+ * ============================================================================
+ *
+ * Protocol Buffers for PAWX (`Protobuf`)
+ *
+ * Supports:
+ *   - Protobuf.message(descriptor)   -> message type
+ *   - Type.encode(object)            -> array<number> (bytes)
+ *   - Type.decode(bytes)             -> object
+ *
+ * A descriptor maps field names to `{ id, type, repeated? }`:
+ *
+ *   snuggle Point = Protobuf.message({
+ *       x: { id: 1, type: "sint32" },
+ *       y: { id: 2, type: "sint32" },
+ *   });
+ *
+ *   snuggle Shape = Protobuf.message({
+ *       name:   { id: 1, type: "string" },
+ *       points: { id: 2, type: Point, repeated: true },
+ *   });
+ *
+ * Scalar types: double, float, int32, int64, uint32, uint64, sint32,
+ * sint64, fixed32, fixed64, sfixed32, sfixed64, bool, string, bytes.
+ * A message type (the result of `Protobuf.message`) may be used as a field
+ * type; enums are plain `int32` fields.
+ *
+ * Encoding follows proto3: null or missing fields are skipped and repeated
+ * numeric fields are packed. Decoding fills missing fields with their
+ * proto3 defaults, accepts packed and unpacked repeated fields, and skips
+ * unknown field numbers. 64-bit integers are PAWX numbers, so values past
+ * 2^53 lose precision.
+ *
+ * --------------------------------------------------------------------------
+ * Author:   Sam Wilcox
+ * Email:    sam@pawx-lang.com
+ * Website:  https://www.pawx-lang.com
+ * GitHub:   https://github.com/samwilcox/pawx
+ *
+ * License:
+ * This file is part of the PAWX programming language project.
+ *
+ * PAWX is dual-licensed under the terms of:
+ *   - The MIT License
+ *   - The Apache License, Version 2.0
+ *
+ * You may choose either license to govern your use of this software.
+ * Full license text available at:
+ *     https://license.pawx-lang.com
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under these licenses is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *
+ * ==========================================================================
+ */
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use crate::error::PawxError;
+use crate::interpreter::calls::{builtin_error, raise_error};
+use crate::prototypes::array::create_array_proto;
+use crate::prototypes::codec::{array_to_bytes, bytes_to_array};
+use crate::value::Value;
+
+fn native(f: impl Fn(Vec<Value>) -> Result<Value, PawxError> + 'static) -> Value {
+    Value::NativeFunction(Arc::new(f))
+}
+
+const WIRE_VARINT: u64 = 0;
+const WIRE_I64: u64 = 1;
+const WIRE_LEN: u64 = 2;
+const WIRE_I32: u64 = 5;
+
+/* ============================================================================
+ * DESCRIPTORS
+ * ============================================================================
+ */
+
+#[derive(Clone)]
+enum Kind {
+    Double,
+    Float,
+    Int32,
+    Int64,
+    Uint32,
+    Uint64,
+    Sint32,
+    Sint64,
+    Fixed32,
+    Fixed64,
+    Sfixed32,
+    Sfixed64,
+    Bool,
+    String,
+    Bytes,
+    Message(Rc<Vec<Field>>),
+}
+
+impl Kind {
+    fn wire_type(&self) -> u64 {
+        match self {
+            Kind::Double | Kind::Fixed64 | Kind::Sfixed64 => WIRE_I64,
+            Kind::Float | Kind::Fixed32 | Kind::Sfixed32 => WIRE_I32,
+            Kind::String | Kind::Bytes | Kind::Message(_) => WIRE_LEN,
+            _ => WIRE_VARINT,
+        }
+    }
+
+    /// Whether repeated values of this kind are packed.
+    fn packable(&self) -> bool {
+        self.wire_type() != WIRE_LEN
+    }
+
+    fn default_value(&self) -> Value {
+        match self {
+            Kind::Bool => Value::Bool(false),
+            Kind::String => Value::String(String::new().into()),
+            Kind::Bytes => bytes_to_array(&[]),
+            Kind::Message(_) => Value::Null,
+            _ => Value::Number(0.0),
+        }
+    }
+}
+
+#[derive(Clone)]
+struct Field {
+    name: String,
+    number: u64,
+    kind: Kind,
+    repeated: bool,
+}
+
+fn parse_kind(field: &str, spec: Option<&Value>) -> Result<Kind, PawxError> {
+    match spec {
+        Some(Value::String(name)) => match &**name {
+            "double" => Ok(Kind::Double),
+            "float" => Ok(Kind::Float),
+            "int32" => Ok(Kind::Int32),
+            "int64" => Ok(Kind::Int64),
+            "uint32" => Ok(Kind::Uint32),
+            "uint64" => Ok(Kind::Uint64),
+            "sint32" => Ok(Kind::Sint32),
+            "sint64" => Ok(Kind::Sint64),
+            "fixed32" => Ok(Kind::Fixed32),
+            "fixed64" => Ok(Kind::Fixed64),
+            "sfixed32" => Ok(Kind::Sfixed32),
+            "sfixed64" => Ok(Kind::Sfixed64),
+            "bool" => Ok(Kind::Bool),
+            "string" => Ok(Kind::String),
+            "bytes" => Ok(Kind::Bytes),
+            other => raise_error(format!("Protobuf.message: field '{}' has unknown type '{}'", field, other)),
+        },
+
+        Some(Value::Object { fields }) => match fields.borrow().get("descriptor") {
+            Some(descriptor) => Ok(Kind::Message(Rc::new(parse_descriptor(descriptor)?))),
+            None => raise_error(format!("Protobuf.message: field '{}' type is not a message type", field)),
+        },
+
+        _ => raise_error(format!("Protobuf.message: field '{}' needs a type", field)),
+    }
+}
+
+/// Parses a `{ name: { id, type, repeated? } }` descriptor.
+///
+/// # Errors
+/// On malformed specs or duplicate field numbers.
+fn parse_descriptor(descriptor: &Value) -> Result<Vec<Field>, PawxError> {
+    let specs = match descriptor {
+        Value::Object { fields } => fields.borrow().clone(),
+        _ => return raise_error("Protobuf.message(descriptor) expects an object"),
+    };
+
+    let mut fields: Vec<Field> = specs
+        .iter()
+        .map(|(name, spec)| {
+            let spec = match spec {
+                Value::Object { fields } => fields.borrow().clone(),
+                _ => return raise_error(format!("Protobuf.message: field '{}' must be {{ id, type }}", name)),
+            };
+
+            let number = match spec.get("id") {
+                Some(Value::Number(n)) if n.fract() == 0.0 && *n >= 1.0 && *n < 536_870_912.0 => *n as u64,
+                _ => return raise_error(format!("Protobuf.message: field '{}' needs an id between 1 and 2^29 - 1", name)),
+            };
+
+            Ok(Field {
+                name: name.clone(),
+                number,
+                kind: parse_kind(name, spec.get("type"))?,
+                repeated: matches!(spec.get("repeated"), Some(Value::Bool(true))),
+            })
+        })
+        .collect::<Result<Vec<_>, PawxError>>()?;
+
+    fields.sort_by_key(|f| f.number);
+
+    for pair in fields.windows(2) {
+        if pair[0].number == pair[1].number {
+            return raise_error(format!(
+                "Protobuf.message: fields '{}' and '{}' share id {}",
+                pair[0].name, pair[1].name, pair[0].number
+            ));
+        }
+    }
+
+    Ok(fields)
+}
+
+/* ============================================================================
+ * ENCODER
+ * ============================================================================
+ */
+
+fn write_varint(out: &mut Vec<u8>, mut n: u64) {
+    while n >= 0x80 {
+        out.push((n as u8) | 0x80);
+        n >>= 7;
+    }
+    out.push(n as u8);
+}
+
+fn write_len(out: &mut Vec<u8>, data: &[u8]) {
+    write_varint(out, data.len() as u64);
+    out.extend_from_slice(data);
+}
+
+fn number(field: &Field, value: &Value) -> Result<f64, PawxError> {
+    match value {
+        Value::Number(n) => Ok(*n),
+        Value::Decimal(d) => Ok(d.to_string().parse().unwrap_or(0.0)),
+        other => raise_error(format!(
+            "Protobuf encode: field '{}' expects a number, got {}",
+            field.name,
+            other.type_name()
+        )),
+    }
+}
+
+/// Writes one value without its tag.
+fn write_value(out: &mut Vec<u8>, field: &Field, value: &Value) -> Result<(), PawxError> {
+    match &field.kind {
+        Kind::Double => out.extend_from_slice(&number(field, value)?.to_le_bytes()),
+        Kind::Float => out.extend_from_slice(&(number(field, value)? as f32).to_le_bytes()),
+        Kind::Int32 | Kind::Int64 => write_varint(out, number(field, value)? as i64 as u64),
+        Kind::Uint32 | Kind::Uint64 => write_varint(out, number(field, value)? as u64),
+        Kind::Sint32 | Kind::Sint64 => {
+            let n = number(field, value)? as i64;
+            write_varint(out, ((n << 1) ^ (n >> 63)) as u64);
+        }
+        Kind::Fixed32 => out.extend_from_slice(&(number(field, value)? as u32).to_le_bytes()),
+        Kind::Sfixed32 => out.extend_from_slice(&(number(field, value)? as i32).to_le_bytes()),
+        Kind::Fixed64 => out.extend_from_slice(&(number(field, value)? as u64).to_le_bytes()),
+        Kind::Sfixed64 => out.extend_from_slice(&(number(field, value)? as i64).to_le_bytes()),
+        Kind::Bool => write_varint(out, value.is_truthy() as u64),
+        Kind::String => match value {
+            Value::String(s) => write_len(out, s.as_bytes()),
+            other => write_len(out, other.to_pawx_string().as_bytes()),
+        },
+        Kind::Bytes => write_len(out, &array_to_bytes(Some(value), "Protobuf encode (bytes field)")?),
+        Kind::Message(fields) => write_len(out, &encode_message(fields, value)?),
+    }
+    Ok(())
+}
+
+fn encode_message(fields: &[Field], value: &Value) -> Result<Vec<u8>, PawxError> {
+    let object = match value {
+        Value::Object { fields } => fields.borrow().clone(),
+        other => return raise_error(format!("Protobuf encode expects an object, got {}", other.type_name())),
+    };
+
+    let mut out = Vec::new();
+
+    for field in fields {
+        let value = match object.get(&field.name) {
+            None | Some(Value::Null) => continue,
+            Some(value) => value,
+        };
+
+        if !field.repeated {
+            write_varint(&mut out, field.number << 3 | field.kind.wire_type());
+            write_value(&mut out, field, value)?;
+            continue;
+        }
+
+        let items = match value {
+            Value::Array { values, .. } => values.borrow().clone(),
+            other => return raise_error(format!(
+                "Protobuf encode: repeated field '{}' expects an array, got {}",
+                field.name,
+                other.type_name()
+            )),
+        };
+
+        if items.is_empty() {
+            continue;
+        }
+
+        if field.kind.packable() {
+            let mut packed = Vec::new();
+            for item in &items {
+                write_value(&mut packed, field, item)?;
+            }
+            write_varint(&mut out, field.number << 3 | WIRE_LEN);
+            write_len(&mut out, &packed);
+        } else {
+            for item in &items {
+                write_varint(&mut out, field.number << 3 | WIRE_LEN);
+                write_value(&mut out, field, item)?;
+            }
+        }
+    }
+
+    Ok(out)
+}
+
+/* ============================================================================
+ * DECODER
+ * ============================================================================
+ */
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn done(&self) -> bool {
+        self.pos >= self.bytes.len()
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], String> {
+        let end = self.pos.checked_add(len).filter(|end| *end <= self.bytes.len());
+        match end {
+            Some(end) => {
+                let data = &self.bytes[self.pos..end];
+                self.pos = end;
+                Ok(data)
+            }
+            None => Err("unexpected end of input".to_string()),
+        }
+    }
+
+    fn varint(&mut self) -> Result<u64, String> {
+        let mut n = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.take(1)?[0];
+            n |= ((byte & 0x7f) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(n);
+            }
+        }
+        Err("varint is too long".to_string())
+    }
+
+    fn fixed<const N: usize>(&mut self) -> Result<[u8; N], String> {
+        Ok(self.take(N)?.try_into().unwrap())
+    }
+
+    fn len_delimited(&mut self) -> Result<&'a [u8], String> {
+        let len = self.varint()? as usize;
+        self.take(len)
+    }
+
+    fn skip(&mut self, wire: u64) -> Result<(), String> {
+        match wire {
+            WIRE_VARINT => self.varint().map(|_| ()),
+            WIRE_I64 => self.take(8).map(|_| ()),
+            WIRE_LEN => self.len_delimited().map(|_| ()),
+            WIRE_I32 => self.take(4).map(|_| ()),
+            other => Err(format!("unsupported wire type {}", other)),
+        }
+    }
+
+    /// Reads one value of `kind` (without its tag).
+    fn value(&mut self, kind: &Kind) -> Result<Value, String> {
+        Ok(match kind {
+            Kind::Double => Value::Number(f64::from_le_bytes(self.fixed()?)),
+            Kind::Float => Value::Number(f32::from_le_bytes(self.fixed()?) as f64),
+            Kind::Int32 => Value::Number(self.varint()? as i32 as f64),
+            Kind::Int64 => Value::Number(self.varint()? as i64 as f64),
+            Kind::Uint32 => Value::Number(self.varint()? as u32 as f64),
+            Kind::Uint64 => Value::Number(self.varint()? as f64),
+            Kind::Sint32 | Kind::Sint64 => {
+                let n = self.varint()?;
+                Value::Number(((n >> 1) as i64 ^ -((n & 1) as i64)) as f64)
+            }
+            Kind::Fixed32 => Value::Number(u32::from_le_bytes(self.fixed()?) as f64),
+            Kind::Sfixed32 => Value::Number(i32::from_le_bytes(self.fixed()?) as f64),
+            Kind::Fixed64 => Value::Number(u64::from_le_bytes(self.fixed()?) as f64),
+            Kind::Sfixed64 => Value::Number(i64::from_le_bytes(self.fixed()?) as f64),
+            Kind::Bool => Value::Bool(self.varint()? != 0),
+            Kind::String => {
+                let data = self.len_delimited()?;
+                Value::String((String::from_utf8(data.to_vec()).map_err(|_| "invalid UTF-8 in string field".to_string())?).into())
+            }
+            Kind::Bytes => bytes_to_array(self.len_delimited()?),
+            Kind::Message(fields) => decode_message(fields, self.len_delimited()?)?,
+        })
+    }
+}
+
+fn decode_message(fields: &[Field], bytes: &[u8]) -> Result<Value, String> {
+    let mut reader = Reader { bytes, pos: 0 };
+    let mut object: HashMap<String, Value> = HashMap::new();
+    let mut repeated: HashMap<String, Vec<Value>> = HashMap::new();
+
+    while !reader.done() {
+        let tag = reader.varint()?;
+        let (number, wire) = (tag >> 3, tag & 7);
+
+        let Some(field) = fields.iter().find(|f| f.number == number) else {
+            reader.skip(wire)?;
+            continue;
+        };
+
+        if !field.repeated {
+            if wire != field.kind.wire_type() {
+                return Err(format!("field '{}' has wire type {}", field.name, wire));
+            }
+            object.insert(field.name.clone(), reader.value(&field.kind)?);
+            continue;
+        }
+
+        let items = repeated.entry(field.name.clone()).or_default();
+
+        if wire == WIRE_LEN && field.kind.packable() {
+            let mut packed = Reader { bytes: reader.len_delimited()?, pos: 0 };
+            while !packed.done() {
+                items.push(packed.value(&field.kind)?);
+            }
+        } else if wire == field.kind.wire_type() {
+            items.push(reader.value(&field.kind)?);
+        } else {
+            return Err(format!("field '{}' has wire type {}", field.name, wire));
+        }
+    }
+
+    for field in fields {
+        let value = match repeated.remove(&field.name) {
+            Some(items) => items,
+            None if field.repeated => Vec::new(),
+            None => {
+                object
+                    .entry(field.name.clone())
+                    .or_insert_with(|| field.kind.default_value());
+                continue;
+            }
+        };
+
+        object.insert(
+            field.name.clone(),
+            Value::Array {
+                values: Rc::new(RefCell::new(value)),
+                proto: create_array_proto(),
+            },
+        );
+    }
+
+    Ok(Value::Object {
+        fields: Rc::new(RefCell::new(object)),
+    })
+}
+
+/* ============================================================================
+ * GLOBAL OBJECT
+ * ============================================================================
+ */
+
+/// Builds a message type from a descriptor.
+fn message_type(descriptor: Value) -> Result<Value, PawxError> {
+    let fields = Rc::new(parse_descriptor(&descriptor)?);
+    let mut message = HashMap::new();
+
+    {
+        let fields = fields.clone();
+        message.insert(
+            "encode".to_string(),
+            native(move |args| match args.first() {
+                Some(value) => Ok(bytes_to_array(&encode_message(&fields, value)?)),
+                None => raise_error("Type.encode(object) expects an object"),
+            }),
+        );
+    }
+
+    message.insert(
+        "decode".to_string(),
+        native(move |args| {
+            let bytes = array_to_bytes(args.first(), "Type.decode(bytes)")?;
+            decode_message(&fields, &bytes).map_err(|e| builtin_error(format!("Type.decode(bytes): {}", e)))
+        }),
+    );
+
+    message.insert("descriptor".to_string(), descriptor);
+
+    Ok(Value::Object {
+        fields: Rc::new(RefCell::new(message)),
+    })
+}
+
+/// Builds the global `Protobuf` object.
+pub fn create_global_protobuf_object() -> HashMap<String, Value> {
+    let mut protobuf = HashMap::new();
+
+    protobuf.insert(
+        "message".to_string(),
+        native(|args| match args.first() {
+            Some(descriptor) => Ok(message_type(descriptor.clone())?),
+            None => raise_error("Protobuf.message(descriptor) expects a descriptor object"),
+        }),
+    );
+
+    protobuf
+}
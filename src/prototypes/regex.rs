@@ -8,15 +8,32 @@
  * JavaScript-style `Regex` standard library used by the PAWX runtime.
  *
  * It provides first-class regular expression support for:
- *   - Creating regex patterns at runtime
- *   - Performing boolean match tests against strings
+ *   - Creating regex patterns at runtime, with JavaScript-style flags
+ *   - Boolean match tests against strings
+ *   - exec / matchAll with numbered and named capture groups
+ *   - replace / replaceAll with `$1` / `$<name>` templates or callbacks
+ *   - split
  *
  * Installed API:
+ *   - Regex.new(pattern, flags?)
  *   - Regex.create(pattern)
- *   - Regex.test(regex, string)
+ *   - Regex.escape(string)
+ *   - Regex.test / exec / matchAll / replace / replaceAll / split
+ *     (regex, string, ...)
  *
- * These functions are installed once onto the global `Regex` namespace
- * and are shared across all PAWX programs.
+ * The same operations are available as methods on regex values:
+ *   r.test(str), r.exec(str), r.replaceAll(str, replacement), ...
+ *
+ * Supported flags:
+ *   - g → global (exec advances `lastIndex`, replace replaces every match)
+ *   - i → case-insensitive
+ *   - m → multiline (`^` / `$` match at line breaks)
+ *   - s → dot matches newlines
+ *   - x → ignore whitespace and `#` comments in the pattern
+ *   - u → accepted for compatibility (patterns are always Unicode-aware)
+ *
+ * Compiled patterns are kept in a small LRU cache keyed by source + flags,
+ * so building the same regex repeatedly (e.g. inside a loop) is cheap.
  *
  * --------------------------------------------------------------------------
  * Author:   Sam Wilcox
@@ -48,11 +65,437 @@
  * ==========================================================================
  */
 
+use std::cell::{Cell, RefCell};
 use std::collections::HashMap;
+use std::ops::Deref;
+use std::rc::Rc;
 use std::sync::Arc;
 
+use crate::error::PawxError;
+use crate::interpreter::calls::{builtin_error, raise_error};
+use crate::prototypes::array::create_array_proto;
 use crate::value::Value;
 
+/// Maximum number of compiled patterns kept in the cache.
+const CACHE_CAPACITY: usize = 64;
+
+/* ==========================================================================
+ * REGEX VALUE
+ * ==========================================================================
+ */
+
+/// A compiled PAWX regular expression.
+///
+/// Dereferences to the underlying [`regex::Regex`], so it can be used
+/// wherever a plain Rust regex is expected. Cloning shares `lastIndex`,
+/// matching how the same regex object behaves when passed around in PAWX.
+/// It is a single pointer, so `Value::Regex` stays small.
+#[derive(Clone)]
+pub struct PawxRegex {
+    inner: Rc<RegexInner>,
+}
+
+struct RegexInner {
+    re: regex::Regex,
+    flags: String,
+    last_index: Cell<usize>,
+}
+
+impl Deref for PawxRegex {
+    type Target = regex::Regex;
+
+    fn deref(&self) -> &regex::Regex {
+        &self.inner.re
+    }
+}
+
+impl PawxRegex {
+    /// Compiles `pattern` with the given flags, using the pattern cache.
+    pub fn new(pattern: &str, flags: &str) -> Result<Self, String> {
+        let flags = normalize_flags(flags)?;
+        let re = compile_cached(pattern, &flags)?;
+
+        Ok(Self {
+            inner: Rc::new(RegexInner {
+                re,
+                flags,
+                last_index: Cell::new(0),
+            }),
+        })
+    }
+
+    /// Flags in canonical order, e.g. `"gim"`.
+    pub fn flags(&self) -> &str {
+        &self.inner.flags
+    }
+
+    /// Returns `true` if the `g` flag is set.
+    pub fn is_global(&self) -> bool {
+        self.inner.flags.contains('g')
+    }
+
+    /// Returns `true` if both regexes have the same source and flags.
+    pub fn same_pattern(&self, other: &PawxRegex) -> bool {
+        self.as_str() == other.as_str() && self.inner.flags == other.inner.flags
+    }
+}
+
+/// Validates flags and returns them deduplicated in canonical order.
+fn normalize_flags(flags: &str) -> Result<String, String> {
+    for c in flags.chars() {
+        if !"gimsxu".contains(c) {
+            return Err(format!("Invalid regex flag '{}' (supported: g, i, m, s, x, u)", c));
+        }
+    }
+
+    Ok("gimsxu".chars().filter(|c| flags.contains(*c)).collect())
+}
+
+/* ==========================================================================
+ * PATTERN CACHE
+ * ==========================================================================
+ */
+
+thread_local! {
+    /// Least-recently-used compiled patterns, most recent last.
+    static PATTERN_CACHE: RefCell<Vec<(String, String, regex::Regex)>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Compiles a pattern, reusing a cached compilation for the same
+/// source + flags.
+fn compile_cached(pattern: &str, flags: &str) -> Result<regex::Regex, String> {
+    let hit = PATTERN_CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        let index = cache.iter().position(|(p, f, _)| p == pattern && f == flags)?;
+        let entry = cache.remove(index);
+        let re = entry.2.clone();
+        cache.push(entry);
+        Some(re)
+    });
+
+    if let Some(re) = hit {
+        return Ok(re);
+    }
+
+    let re = regex::RegexBuilder::new(pattern)
+        .case_insensitive(flags.contains('i'))
+        .multi_line(flags.contains('m'))
+        .dot_matches_new_line(flags.contains('s'))
+        .ignore_whitespace(flags.contains('x'))
+        .build()
+        .map_err(|e| format!("Invalid regex pattern '{}': {}", pattern, e))?;
+
+    PATTERN_CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        if cache.len() >= CACHE_CAPACITY {
+            cache.remove(0);
+        }
+        cache.push((pattern.to_string(), flags.to_string(), re.clone()));
+    });
+
+    Ok(re)
+}
+
+/* ==========================================================================
+ * MATCH OBJECTS
+ * ==========================================================================
+ */
+
+/// Converts a match into a PAWX object:
+/// `{ match, index, end, captures, groups }`.
+///
+/// - `index` / `end` are character offsets
+/// - `captures` holds groups 1..n (`null` for groups that did not take part)
+/// - `groups` maps named groups to their text
+fn match_to_value(re: &regex::Regex, caps: &regex::Captures, text: &str) -> Value {
+    let whole = caps.get(0).expect("group 0 always participates");
+
+    let captures: Vec<Value> = caps
+        .iter()
+        .skip(1)
+        .map(|m| match m {
+            Some(m) => Value::String(m.as_str().into()),
+            None => Value::Null,
+        })
+        .collect();
+
+    let mut groups = HashMap::new();
+    for name in re.capture_names().flatten() {
+        let value = match caps.name(name) {
+            Some(m) => Value::String(m.as_str().into()),
+            None => Value::Null,
+        };
+        groups.insert(name.to_string(), value);
+    }
+
+    let mut fields = HashMap::new();
+    fields.insert("match".to_string(), Value::String(whole.as_str().into()));
+    fields.insert(
+        "index".to_string(),
+        Value::Number(text[..whole.start()].chars().count() as f64),
+    );
+    fields.insert(
+        "end".to_string(),
+        Value::Number(text[..whole.end()].chars().count() as f64),
+    );
+    fields.insert("captures".to_string(), array(captures));
+    fields.insert(
+        "groups".to_string(),
+        Value::Object {
+            fields: Rc::new(RefCell::new(groups)),
+        },
+    );
+
+    Value::Object {
+        fields: Rc::new(RefCell::new(fields)),
+    }
+}
+
+fn array(values: Vec<Value>) -> Value {
+    Value::Array {
+        values: Rc::new(RefCell::new(values)),
+        proto: create_array_proto(),
+    }
+}
+
+/// Converts a character offset to a byte offset (clamped to the end).
+fn byte_offset(text: &str, chars: usize) -> usize {
+    text.char_indices().nth(chars).map(|(i, _)| i).unwrap_or(text.len())
+}
+
+/* ==========================================================================
+ * REPLACEMENT
+ * ==========================================================================
+ */
+
+/// Expands a JavaScript-style replacement template.
+///
+/// # Supported Tokens
+/// - `$&`       → whole match
+/// - `$1`..`$99` → numbered group
+/// - `$<name>`  → named group
+/// - `` $` `` / `$'` → text before / after the match
+/// - `$$`       → literal `$`
+fn expand_template(template: &str, caps: &regex::Captures, text: &str) -> String {
+    let whole = caps.get(0).expect("group 0 always participates");
+    let mut out = String::new();
+    let mut chars = template.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '$' {
+            out.push(c);
+            continue;
+        }
+
+        match chars.peek().copied() {
+            Some('$') => {
+                chars.next();
+                out.push('$');
+            }
+            Some('&') => {
+                chars.next();
+                out.push_str(whole.as_str());
+            }
+            Some('`') => {
+                chars.next();
+                out.push_str(&text[..whole.start()]);
+            }
+            Some('\'') => {
+                chars.next();
+                out.push_str(&text[whole.end()..]);
+            }
+            Some('<') => {
+                // `$<name>` — left untouched when the `>` is missing.
+                let lookahead: String = chars.clone().skip(1).collect();
+
+                match lookahead.find('>') {
+                    Some(end) => {
+                        let name = &lookahead[..end];
+                        for _ in 0..name.chars().count() + 2 {
+                            chars.next();
+                        }
+                        if let Some(m) = caps.name(name) {
+                            out.push_str(m.as_str());
+                        }
+                    }
+                    None => out.push('$'),
+                }
+            }
+            Some(d) if d.is_ascii_digit() => {
+                // Prefer a two-digit group when it exists, like JavaScript.
+                chars.next();
+                let mut index = d.to_digit(10).unwrap() as usize;
+
+                if let Some(d2) = chars.peek().copied().filter(char::is_ascii_digit) {
+                    let two = index * 10 + d2.to_digit(10).unwrap() as usize;
+                    if two < caps.len() {
+                        chars.next();
+                        index = two;
+                    }
+                }
+
+                if index > 0 && index < caps.len() {
+                    if let Some(m) = caps.get(index) {
+                        out.push_str(m.as_str());
+                    }
+                } else {
+                    out.push('$');
+                    out.push_str(&index.to_string());
+                }
+            }
+            _ => out.push('$'),
+        }
+    }
+
+    out
+}
+
+/// Replaces the first match, or every match when `all` is set.
+///
+/// `replacement` is either a template string or a function receiving the
+/// match object and returning the replacement text.
+fn replace_matches(re: &regex::Regex, text: &str, replacement: &Value, all: bool) -> Result<String, PawxError> {
+    let mut out = String::new();
+    let mut last = 0;
+
+    for caps in re.captures_iter(text) {
+        let whole = caps.get(0).expect("group 0 always participates");
+        out.push_str(&text[last..whole.start()]);
+
+        let piece = match replacement {
+            Value::String(template) => expand_template(template, &caps, text),
+            Value::NativeFunction(f) => f(vec![match_to_value(re, &caps, text)])?.to_pawx_string(),
+            _ => return raise_error("Regex replace expects a replacement string or function"),
+        };
+
+        out.push_str(&piece);
+        last = whole.end();
+
+        if !all {
+            break;
+        }
+    }
+
+    out.push_str(&text[last..]);
+    Ok(out)
+}
+
+/* ==========================================================================
+ * REGEX OPERATIONS
+ * ==========================================================================
+ */
+
+fn text_arg(args: &[Value], index: usize, method: &str) -> Result<String, PawxError> {
+    match args.get(index) {
+        Some(Value::String(s)) => Ok(s.to_string()),
+        _ => raise_error(format!("regex.{}(...) expects a string argument", method)),
+    }
+}
+
+/// Resolves a property or method on a regex value.
+///
+/// # Supported Members
+/// - `source`, `flags`, `global`, `lastIndex`
+/// - `test(str)`
+/// - `exec(str)` → match object or `null`; with `g`, continues from
+///   `lastIndex` and resets it to 0 once no further match is found
+/// - `matchAll(str)` → array of match objects
+/// - `replace(str, replacement)` → first match (every match with `g`)
+/// - `replaceAll(str, replacement)` → every match
+/// - `split(str, limit?)`
+/// - `resetLastIndex()`
+///
+/// # Errors
+/// - If the property does not exist
+pub fn regex_property(regex: &PawxRegex, name: &str) -> Result<Value, PawxError> {
+    let r = regex.clone();
+
+    match name {
+        "source" => Ok(Value::String(r.as_str().into())),
+        "flags" => Ok(Value::String(r.flags().into())),
+        "global" => Ok(Value::Bool(r.is_global())),
+        "lastIndex" => Ok(Value::Number(r.inner.last_index.get() as f64)),
+
+        "test" => Ok(Value::NativeFunction(Arc::new(move |args: Vec<Value>| -> Result<Value, PawxError> {
+            Ok(Value::Bool(r.is_match(&text_arg(&args, 0, "test")?)))
+        }))),
+
+        "exec" => Ok(Value::NativeFunction(Arc::new(move |args: Vec<Value>| -> Result<Value, PawxError> {
+            let text = text_arg(&args, 0, "exec")?;
+
+            if !r.is_global() {
+                return match r.captures(&text) {
+                    Some(caps) => Ok(match_to_value(&r, &caps, &text)),
+                    None => Ok(Value::Null),
+                };
+            }
+
+            let start = byte_offset(&text, r.inner.last_index.get());
+            match r.captures_at(&text, start) {
+                Some(caps) if start <= text.len() => {
+                    let whole = caps.get(0).expect("group 0 always participates");
+
+                    // Step past empty matches so repeated exec terminates.
+                    let mut next = text[..whole.end()].chars().count();
+                    if whole.start() == whole.end() {
+                        next += 1;
+                    }
+                    r.inner.last_index.set(next);
+
+                    Ok(match_to_value(&r, &caps, &text))
+                }
+                _ => {
+                    r.inner.last_index.set(0);
+                    Ok(Value::Null)
+                }
+            }
+        }))),
+
+        "matchAll" => Ok(Value::NativeFunction(Arc::new(move |args: Vec<Value>| -> Result<Value, PawxError> {
+            let text = text_arg(&args, 0, "matchAll")?;
+            Ok(array(
+                r.captures_iter(&text)
+                    .map(|caps| match_to_value(&r, &caps, &text))
+                    .collect(),
+            ))
+        }))),
+
+        "replace" | "replaceAll" => {
+            let all = name == "replaceAll" || r.is_global();
+            let method = if all { "replaceAll" } else { "replace" };
+
+            Ok(Value::NativeFunction(Arc::new(move |args: Vec<Value>| -> Result<Value, PawxError> {
+                let text = text_arg(&args, 0, method)?;
+                let replacement = args
+                    .get(1)
+                    .cloned()
+                    .ok_or_else(|| builtin_error(format!("regex.{}(str, replacement) requires a replacement", method)))?;
+
+                Ok(Value::String(replace_matches(&r, &text, &replacement, all)?.into()))
+            })))
+        }
+
+        "split" => Ok(Value::NativeFunction(Arc::new(move |args: Vec<Value>| -> Result<Value, PawxError> {
+            let text = text_arg(&args, 0, "split")?;
+            let parts = r.split(&text).map(|p| Value::String(p.into()));
+
+            let parts: Vec<Value> = match args.get(1) {
+                Some(Value::Number(n)) if *n >= 0.0 => parts.take(*n as usize).collect(),
+                _ => parts.collect(),
+            };
+
+            Ok(array(parts))
+        }))),
+
+        "resetLastIndex" => Ok(Value::NativeFunction(Arc::new(move |_args: Vec<Value>| -> Result<Value, PawxError> {
+            r.inner.last_index.set(0);
+            Ok(Value::Null)
+        }))),
+
+        other => raise_error(format!("Property '{}' not supported on Regex", other)),
+    }
+}
+
 /* ==========================================================================
  * GLOBAL REGEX NAMESPACE
  * ==========================================================================
@@ -61,8 +504,12 @@ use crate::value::Value;
 /// Creates and returns the global `Regex` namespace for the PAWX runtime.
 ///
 /// # Installed Functions
+/// - `Regex.new(pattern, flags?)` → Compiles a pattern with flags
 /// - `Regex.create(pattern)` → Compiles a string into a regex object
+/// - `Regex.escape(str)` → Escapes regex metacharacters
 /// - `Regex.test(regex, str)` → Tests a regex against a string
+/// - `Regex.exec` / `matchAll` / `replace` / `replaceAll` / `split`
+///   → Same as the regex methods, with the regex as first argument
 ///
 /// This function is called once during runtime initialization and the
 /// resulting object is injected into the global environment.
@@ -72,19 +519,86 @@ use crate::value::Value;
 pub fn create_global_regex_object() -> HashMap<String, Value> {
     let mut regex_obj = HashMap::new();
 
+    regex_obj.insert(
+        "new".into(),
+        Value::NativeFunction(Arc::new(regex_new)),
+    );
+
     regex_obj.insert(
         "create".into(),
         Value::NativeFunction(Arc::new(regex_create)),
     );
 
+    regex_obj.insert(
+        "escape".into(),
+        Value::NativeFunction(Arc::new(|args: Vec<Value>| -> Result<Value, PawxError> {
+            match args.first() {
+                Some(Value::String(s)) => Ok(Value::String(regex::escape(s).into())),
+                _ => raise_error("Regex.escape(str) expects a string"),
+            }
+        })),
+    );
+
     regex_obj.insert(
         "test".into(),
         Value::NativeFunction(Arc::new(regex_test)),
     );
 
+    for method in ["exec", "matchAll", "replace", "replaceAll", "split"] {
+        regex_obj.insert(
+            method.into(),
+            Value::NativeFunction(Arc::new(move |args: Vec<Value>| -> Result<Value, PawxError> {
+                let regex = match args.first() {
+                    Some(Value::Regex(r)) => r.clone(),
+                    _ => return raise_error(format!("Regex.{}(regex, ...) expects a regex as the first argument", method)),
+                };
+
+                match regex_property(&regex, method)? {
+                    Value::NativeFunction(f) => f(args[1..].to_vec()),
+                    _ => unreachable!(),
+                }
+            })),
+        );
+    }
+
     regex_obj
 }
 
+/* ==========================================================================
+ * REGEX.new(pattern, flags?)
+ * ==========================================================================
+ */
+
+/// Compiles a pattern with optional JavaScript-style flags.
+///
+/// # PAWX Usage
+/// ```pawx
+/// let r = Regex.new("(?<year>\d{4})-(?<month>\d{2})", "g");
+/// r.exec("2024-05 and 2025-01").groups.year;   // "2024"
+/// ```
+///
+/// # Errors
+/// - The pattern or flags are not strings
+/// - A flag is not supported
+/// - The pattern fails to compile
+fn regex_new(args: Vec<Value>) -> Result<Value, PawxError> {
+    let pattern = match args.first() {
+        Some(Value::String(p)) => p,
+        _ => return raise_error("Regex.new(pattern, flags?) expects a string pattern"),
+    };
+
+    let flags = match args.get(1) {
+        Some(Value::String(f)) => &**f,
+        Some(Value::Null) | None => "",
+        _ => return raise_error("Regex.new(pattern, flags?) expects flags as a string"),
+    };
+
+    match PawxRegex::new(pattern, flags) {
+        Ok(re) => Ok(Value::Regex(re)),
+        Err(e) => raise_error(format!("Regex.new: {}", e)),
+    }
+}
+
 /* ==========================================================================
  * REGEX.create(pattern)
  * ==========================================================================
@@ -101,22 +615,21 @@ pub fn create_global_regex_object() -> HashMap<String, Value> {
 /// - `pattern` (String) → The regular expression pattern
 ///
 /// # Returns
-/// - `Value::Regex` containing a compiled Rust `regex::Regex`
+/// - `Value::Regex` containing a compiled pattern with no flags
 ///
-/// # Panics
-/// Panics if:
+/// # Errors
 /// - The argument is not a string
 /// - The regex pattern is invalid and fails to compile
-fn regex_create(args: Vec<Value>) -> Value {
+fn regex_create(args: Vec<Value>) -> Result<Value, PawxError> {
     match args.get(0) {
         Some(Value::String(pattern)) => {
-            let re = regex::Regex::new(pattern)
-                .expect("Invalid regex pattern");
+            let re = PawxRegex::new(pattern, "")
+                .map_err(|e| builtin_error(format!("Regex.create: {}", e)))?;
 
-            Value::Regex(re)
+            Ok(Value::Regex(re))
         }
 
-        _ => panic!("Regex.create(pattern) expects a string"),
+        _ => raise_error("Regex.create(pattern) expects a string"),
     }
 }
 
@@ -142,20 +655,19 @@ fn regex_create(args: Vec<Value>) -> Value {
 /// - `Value::Bool(true)` if the regex matches
 /// - `Value::Bool(false)` if it does not
 ///
-/// # Panics
-/// Panics if:
+/// # Errors
 /// - The first argument is not a `Regex`
 /// - The second argument is not a `String`
-fn regex_test(args: Vec<Value>) -> Value {
+fn regex_test(args: Vec<Value>) -> Result<Value, PawxError> {
     let regex = match args.get(0) {
         Some(Value::Regex(r)) => r,
-        _ => panic!("Regex.test(regex, str) expects a regex as the first argument"),
+        _ => return raise_error("Regex.test(regex, str) expects a regex as the first argument"),
     };
 
     let text = match args.get(1) {
         Some(Value::String(s)) => s,
-        _ => panic!("Regex.test(regex, str) expects a string as the second argument"),
+        _ => return raise_error("Regex.test(regex, str) expects a string as the second argument"),
     };
 
-    Value::Bool(regex.is_match(text))
-}
\ No newline at end of file
+    Ok(Value::Bool(regex.is_match(text)))
+}
@@ -0,0 +1,482 @@
+/*
+ * ============================================================================
+ * PAWX - Code with Claws! 🐾
+ * ============================================================================
+ *
+ * JSON-RPC 2.0 for PAWX (`Rpc`)
+ *
+ * Supports:
+ *   - Rpc.createServer(service)          -> { listen(port) }
+ *   - Rpc.handler(service)               -> (req, res) handler for Http
+ *   - Rpc.dispatch(service, text)        -> reply text ("" if none)
+ *   - Rpc.connect(url, options?)         -> client
+ *   - client.call(method, params?)       -> Furure(result)
+ *   - client.notify(method, params?)     -> Furure(null)
+ *   - client.batch([[method, params?]])  -> Furure(array<result | Error>)
+ *
+ * A service is an object of handler functions:
+ *
+ *   Rpc.createServer({
+ *       add: (a, b) -> a + b,
+ *       greet: p -> "Hello " + p.name,
+ *   }).listen(4000);
+ *
+ * Positional params (an array) are passed as arguments; named params (an
+ * object) are passed as a single object argument. A handler that returns
+ * an `Error` (or a Furure holding one) answers with a JSON-RPC error.
+ *
+ * Batches are answered with one array; notifications (no `id`) get no
+ * reply. Requests must be sent as `application/json` (or plain text);
+ * form-encoded bodies are rejected as invalid. `Rpc.dispatch` speaks the protocol over plain text so other
+ * transports can reuse it.
+ *
+ * Client options: `{ headers, timeout }`. Error replies resolve the Furure
+ * to an `Error` whose message carries the server's message and code.
+ *
+ * --------------------------------------------------------------------------
+ * Author:   Sam Wilcox
+ * Email:    sam@pawx-lang.com
+ * Website:  https://www.pawx-lang.com
+ * GitHub:   https://github.com/samwilcox/pawx
+ *
+ * License:
+ * This file is part of the PAWX programming language project.
+ *
+ * PAWX is dual-licensed under the terms of:
+ *   - The MIT License
+ *   - The Apache License, Version 2.0
+ *
+ * You may choose either license to govern your use of this software.
+ * Full license text available at:
+ *     https://license.pawx-lang.com
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under these licenses is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *
+ * ==========================================================================
+ */
+
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde_json::{json, Value as JsonValue};
+
+use crate::error::PawxError;
+use crate::interpreter::calls::{builtin_error, raise_error};
+use crate::prototypes::array::create_array_proto;
+use crate::prototypes::cancel::cancellable;
+use crate::interpreter::display::value_to_json;
+use crate::prototypes::http::{json_to_value, server_bind, ServerOptions};
+use crate::prototypes::http_client::{default_client, parse_url, HttpRequest, ParsedUrl};
+use crate::value::Value;
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+/// Code for errors returned by service handlers.
+const SERVER_ERROR: i64 = -32000;
+
+fn native(f: impl Fn(Vec<Value>) -> Result<Value, PawxError> + 'static) -> Value {
+    Value::NativeFunction(Arc::new(f))
+}
+
+fn object(fields: HashMap<String, Value>) -> Value {
+    Value::Object {
+        fields: Rc::new(RefCell::new(fields)),
+    }
+}
+
+/// Converts a value to JSON, keeping integral numbers integral so request
+/// ids are echoed exactly.
+///
+/// # Errors
+/// If the value is nested too deeply to encode.
+fn to_json(value: &Value) -> Result<JsonValue, String> {
+    serde_json::from_str(&value_to_json(value)?).map_err(|e| e.to_string())
+}
+
+/* ============================================================================
+ * SERVER
+ * ============================================================================
+ */
+
+fn error_reply(id: JsonValue, code: i64, message: &str) -> JsonValue {
+    json!({
+        "jsonrpc": "2.0",
+        "error": { "code": code, "message": message },
+        "id": id,
+    })
+}
+
+/// Answers one request object, or `None` for a notification.
+fn handle_one(service: &HashMap<String, Value>, request: &JsonValue) -> Option<JsonValue> {
+    let Some(fields) = request.as_object() else {
+        return Some(error_reply(JsonValue::Null, INVALID_REQUEST, "Invalid Request"));
+    };
+
+    let id = fields.get("id").cloned();
+    let reply_id = id.clone().unwrap_or(JsonValue::Null);
+
+    let method = match (fields.get("jsonrpc"), fields.get("method")) {
+        (Some(JsonValue::String(v)), Some(JsonValue::String(m))) if v == "2.0" => m,
+        _ => return Some(error_reply(reply_id, INVALID_REQUEST, "Invalid Request")),
+    };
+
+    let reply = |result: Result<JsonValue, (i64, String)>| {
+        id.as_ref()?;
+        Some(match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "result": result, "id": reply_id.clone() }),
+            Err((code, message)) => error_reply(reply_id.clone(), code, &message),
+        })
+    };
+
+    let handler = match service.get(method) {
+        Some(Value::NativeFunction(f)) => f.clone(),
+        _ => return reply(Err((METHOD_NOT_FOUND, "Method not found".to_string()))),
+    };
+
+    let args = match fields.get("params") {
+        None => Vec::new(),
+        Some(JsonValue::Array(params)) => params.iter().cloned().map(json_to_value).collect(),
+        Some(params @ JsonValue::Object(_)) => vec![json_to_value(params.clone())],
+        Some(_) => return reply(Err((INVALID_PARAMS, "Invalid params".to_string()))),
+    };
+
+    let result = match handler(args) {
+        Ok(Value::Furure(inner)) => *inner,
+        Ok(other) => other,
+        Err(error) => return reply(Err((SERVER_ERROR, error.message))),
+    };
+
+    reply(match result {
+        Value::Error { message } => Err((SERVER_ERROR, message)),
+        other => to_json(&other).map_err(|message| (SERVER_ERROR, message)),
+    })
+}
+
+/// Answers a parsed request or batch, or `None` when nothing is owed.
+fn handle(service: &HashMap<String, Value>, request: &JsonValue) -> Option<JsonValue> {
+    match request {
+        JsonValue::Array(batch) if batch.is_empty() => {
+            Some(error_reply(JsonValue::Null, INVALID_REQUEST, "Invalid Request"))
+        }
+
+        JsonValue::Array(batch) => {
+            let replies: Vec<JsonValue> = batch.iter().filter_map(|r| handle_one(service, r)).collect();
+            (!replies.is_empty()).then_some(JsonValue::Array(replies))
+        }
+
+        single => handle_one(service, single),
+    }
+}
+
+/// Answers request text; an empty string means no reply is owed.
+pub fn dispatch(service: &HashMap<String, Value>, text: &str) -> String {
+    let reply = match serde_json::from_str::<JsonValue>(text) {
+        Ok(request) => handle(service, &request),
+        Err(_) => Some(error_reply(JsonValue::Null, PARSE_ERROR, "Parse error")),
+    };
+
+    reply.map(|r| r.to_string()).unwrap_or_default()
+}
+
+fn service_fields(value: Option<&Value>, usage: &str) -> Result<HashMap<String, Value>, PawxError> {
+    match value {
+        Some(Value::Object { fields }) => Ok(fields.borrow().clone()),
+        _ => raise_error(format!("{} expects a service object of functions", usage)),
+    }
+}
+
+/// Builds an Http `(req, res)` handler serving `service`.
+fn rpc_handler(service: HashMap<String, Value>) -> Value {
+    native(move |args| {
+        let (req, res) = match (args.first(), args.get(1)) {
+            (Some(Value::Object { fields: req }), Some(Value::Object { fields: res })) => (req, res),
+            _ => return raise_error("Rpc handler expects (req, res)"),
+        };
+
+        // Http parses JSON bodies when the client sends a JSON content type
+        let body = req.borrow().get("body").cloned().unwrap_or(Value::Null);
+        let reply = match body {
+            Value::String(text) => dispatch(&service, &text),
+            Value::Null => dispatch(&service, ""),
+            parsed => to_json(&parsed)
+                .ok()
+                .and_then(|request| handle(&service, &request))
+                .map(|r| r.to_string())
+                .unwrap_or_default(),
+        };
+
+        if let Some(Value::NativeFunction(send)) = res.borrow().get("json").cloned() {
+            send(vec![Value::String(reply.into())])?;
+        }
+
+        Ok(Value::Null)
+    })
+}
+
+/* ============================================================================
+ * CLIENT
+ * ============================================================================
+ */
+
+struct RpcClient {
+    url: ParsedUrl,
+    headers: Vec<(String, String)>,
+    timeout: Option<Duration>,
+    next_id: Cell<u64>,
+}
+
+impl RpcClient {
+    fn next_id(&self) -> u64 {
+        let id = self.next_id.get() + 1;
+        self.next_id.set(id);
+        id
+    }
+
+    /// Builds one request payload; notifications carry no `id`.
+    fn payload(&self, method: &Value, params: Option<&Value>, notify: bool) -> Result<JsonValue, String> {
+        let method = match method {
+            Value::String(m) => m.to_string(),
+            _ => return Err("Rpc: method must be a string".to_string()),
+        };
+
+        let mut payload = json!({ "jsonrpc": "2.0", "method": method });
+
+        match params {
+            None | Some(Value::Null) => {}
+            Some(p @ (Value::Array { .. } | Value::Object { .. })) => payload["params"] = to_json(p)?,
+            Some(_) => return Err("Rpc: params must be an array or object".to_string()),
+        }
+
+        if !notify {
+            payload["id"] = json!(self.next_id());
+        }
+
+        Ok(payload)
+    }
+
+    /// Posts a payload; returns the parsed reply, or `Null` for no reply.
+    fn post(&self, payload: &JsonValue) -> Result<JsonValue, String> {
+        let mut headers = vec![
+            ("Content-Type".to_string(), "application/json".to_string()),
+            ("Accept".to_string(), "application/json".to_string()),
+        ];
+
+        for (k, v) in &self.headers {
+            headers.retain(|(existing, _)| !existing.eq_ignore_ascii_case(k));
+            headers.push((k.clone(), v.clone()));
+        }
+
+        let response = default_client().send(&HttpRequest {
+            method: "POST".to_string(),
+            url: self.url.clone(),
+            headers,
+            body: Some(payload.to_string()),
+            multipart: None,
+            timeout: self.timeout,
+        })?;
+
+        if response.body.trim().is_empty() {
+            return Ok(JsonValue::Null);
+        }
+
+        serde_json::from_str(&response.body)
+            .map_err(|_| format!("Rpc: HTTP {} {}", response.status, response.status_text))
+    }
+}
+
+/// Converts one reply object into its result, or an `Error` value.
+fn reply_value(reply: &JsonValue) -> Value {
+    if let Some(error) = reply.get("error") {
+        let message = error.get("message").and_then(JsonValue::as_str).unwrap_or("unknown error");
+        let code = error.get("code").and_then(JsonValue::as_i64).unwrap_or(0);
+
+        return Value::Error {
+            message: format!("Rpc: {} ({})", message, code),
+        };
+    }
+
+    json_to_value(reply.get("result").cloned().unwrap_or(JsonValue::Null))
+}
+
+fn to_furure(result: Result<Value, String>) -> Value {
+    match result {
+        Ok(v) => Value::Furure(Box::new(v)),
+        Err(message) => Value::Furure(Box::new(Value::Error { message })),
+    }
+}
+
+fn parse_client(args: &[Value]) -> Result<RpcClient, String> {
+    let url = match args.first() {
+        Some(Value::String(u)) => parse_url(u)?,
+        _ => return Err("Rpc.connect(url, options?): url must be a string".to_string()),
+    };
+
+    let options = match args.get(1) {
+        Some(Value::Object { fields }) => fields.borrow().clone(),
+        Some(Value::Null) | None => HashMap::new(),
+        _ => return Err("Rpc.connect: options must be an object".to_string()),
+    };
+
+    let headers = match options.get("headers") {
+        Some(Value::Object { fields }) => fields
+            .borrow()
+            .iter()
+            .map(|(k, v)| (k.clone(), v.to_pawx_string()))
+            .collect(),
+        _ => Vec::new(),
+    };
+
+    let timeout = match options.get("timeout") {
+        Some(Value::Number(ms)) if *ms > 0.0 => Some(Duration::from_millis(*ms as u64)),
+        _ => None,
+    };
+
+    Ok(RpcClient {
+        url,
+        headers,
+        timeout,
+        next_id: Cell::new(0),
+    })
+}
+
+/// Creates the PAWX object for `Rpc.connect(url, options?)`.
+fn connect(args: Vec<Value>) -> Result<Value, PawxError> {
+    let client = Rc::new(parse_client(&args).map_err(|e| builtin_error(e.to_string()))?);
+    let mut fields = HashMap::new();
+
+    let c = client.clone();
+    fields.insert(
+        "call".to_string(),
+        cancellable(Arc::new(move |args: Vec<Value>| -> Result<Value, PawxError> {
+            let method = args.first().cloned().unwrap_or(Value::Null);
+
+            Ok(to_furure(
+                c.payload(&method, args.get(1), false)
+                    .and_then(|p| c.post(&p))
+                    .map(|reply| reply_value(&reply)),
+            ))
+        })),
+    );
+
+    let c = client.clone();
+    fields.insert(
+        "notify".to_string(),
+        cancellable(Arc::new(move |args: Vec<Value>| -> Result<Value, PawxError> {
+            let method = args.first().cloned().unwrap_or(Value::Null);
+
+            Ok(to_furure(
+                c.payload(&method, args.get(1), true)
+                    .and_then(|p| c.post(&p))
+                    .map(|_| Value::Null),
+            ))
+        })),
+    );
+
+    let c = client;
+    fields.insert(
+        "batch".to_string(),
+        cancellable(Arc::new(move |args: Vec<Value>| -> Result<Value, PawxError> {
+            let calls = match args.first() {
+                Some(Value::Array { values, .. }) => values.borrow().clone(),
+                _ => return Ok(to_furure(Err("client.batch(calls): expected an array".to_string()))),
+            };
+
+            let payloads = calls
+                .iter()
+                .map(|call| match call {
+                    Value::Array { values, .. } => {
+                        let values = values.borrow();
+                        let method = values.first().cloned().unwrap_or(Value::Null);
+                        c.payload(&method, values.get(1), false)
+                    }
+                    _ => Err("client.batch(calls): each call must be [method, params?]".to_string()),
+                })
+                .collect::<Result<Vec<_>, _>>();
+
+            Ok(to_furure(payloads.and_then(|payloads| {
+                let replies = match c.post(&JsonValue::Array(payloads.clone()))? {
+                    JsonValue::Array(replies) => replies,
+                    single => vec![single],
+                };
+
+                // Replies may arrive in any order; match them up by id
+                let results = payloads
+                    .iter()
+                    .map(|p| match replies.iter().find(|r| r.get("id") == p.get("id")) {
+                        Some(reply) => reply_value(reply),
+                        None => Value::Error {
+                            message: "Rpc: no reply for batched call".to_string(),
+                        },
+                    })
+                    .collect();
+
+                Ok(Value::Array {
+                    values: Rc::new(RefCell::new(results)),
+                    proto: create_array_proto(),
+                })
+            })))
+        })),
+    );
+
+    Ok(object(fields))
+}
+
+/* ============================================================================
+ * GLOBAL OBJECT
+ * ============================================================================
+ */
+
+/// Builds the global `Rpc` object.
+pub fn create_global_rpc_object() -> HashMap<String, Value> {
+    let mut rpc = HashMap::new();
+
+    rpc.insert(
+        "createServer".to_string(),
+        native(|args| {
+            let handler = rpc_handler(service_fields(args.first(), "Rpc.createServer(service)")?);
+            let mut server = HashMap::new();
+
+            server.insert(
+                "listen".to_string(),
+                native(move |listen_args| {
+                    let port = match listen_args.first() {
+                        Some(Value::Number(n)) => *n as u16,
+                        _ => return raise_error("listen(port) requires a number"),
+                    };
+
+                    Ok(server_bind(port, handler.clone(), &ServerOptions::default()))
+                }),
+            );
+
+            Ok(object(server))
+        }),
+    );
+
+    rpc.insert(
+        "handler".to_string(),
+        native(|args| Ok(rpc_handler(service_fields(args.first(), "Rpc.handler(service)")?))),
+    );
+
+    rpc.insert(
+        "dispatch".to_string(),
+        native(|args| {
+            let service = service_fields(args.first(), "Rpc.dispatch(service, text)")?;
+            match args.get(1) {
+                Some(Value::String(text)) => Ok(Value::String(dispatch(&service, text).into())),
+                _ => raise_error("Rpc.dispatch(service, text) expects request text"),
+            }
+        }),
+    );
+
+    rpc.insert("connect".to_string(), native(connect));
+
+    rpc
+}
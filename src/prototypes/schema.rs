@@ -0,0 +1,603 @@
+/*
+ * ============================================================================
+ * PAWX - Code with Claws! 🐾
+ * ============================================================================
+ *
+ * Schema Prototype for PAWX (validation of request bodies and config)
+ *
+ * Supports:
+ *   - Schema.string() / number() / boolean() / any()
+ *   - Schema.array(item) / object({ ... }) / oneOf([values])
+ *   - Modifiers: min, max, int, pattern, email, optional, default, strict,
+ *     refine
+ *   - schema.validate(value) → { ok, value, errors: [{ path, code, message }] }
+ *   - req.validate(schema) on the HTTP server
+ *
+ * Schemas are immutable: every modifier returns a new schema. Any object
+ * with a compatible `validate(value)` function can be nested as a schema.
+ *
+ * --------------------------------------------------------------------------
+ * Author:   Sam Wilcox
+ * Email:    sam@pawx-lang.com
+ * Website:  https://www.pawx-lang.com
+ * GitHub:   https://github.com/samwilcox/pawx
+ *
+ * License:
+ * This file is part of the PAWX programming language project.
+ *
+ * PAWX is dual-licensed under the terms of:
+ *   - The MIT License
+ *   - The Apache License, Version 2.0
+ *
+ * You may choose either license to govern your use of this software.
+ * Full license text available at:
+ *     https://license.pawx-lang.com
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under these licenses is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *
+ * ==========================================================================
+ */
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use regex::Regex;
+
+use crate::error::PawxError;
+use crate::interpreter::calls::{builtin_error, raise_error};
+use crate::prototypes::array::create_array_proto;
+use crate::value::Value;
+
+/* ============================================================================
+ * SCHEMA MODEL
+ * ============================================================================
+ */
+
+/// The base type a schema accepts.
+#[derive(Clone)]
+enum Kind {
+    Any,
+    String,
+    Number,
+    Bool,
+    Array(Value),
+    Object(Vec<(String, Value)>),
+    OneOf(Vec<Value>),
+}
+
+/// An extra rule checked after the type matches.
+#[derive(Clone)]
+enum Check {
+    /// Minimum value (numbers) or length (strings, arrays).
+    Min(f64),
+
+    /// Maximum value (numbers) or length (strings, arrays).
+    Max(f64),
+    Int,
+    Pattern(Regex),
+    Email,
+
+    /// User predicate plus the message reported when it returns falsy.
+    Refine(Box<Value>, String),
+}
+
+#[derive(Clone)]
+struct SchemaDef {
+    kind: Kind,
+    checks: Vec<Check>,
+    optional: bool,
+    default: Option<Value>,
+
+    /// Objects only: reject keys not declared in the shape.
+    strict: bool,
+}
+
+impl SchemaDef {
+    fn new(kind: Kind) -> Self {
+        Self {
+            kind,
+            checks: Vec::new(),
+            optional: false,
+            default: None,
+            strict: false,
+        }
+    }
+
+    fn type_label(&self) -> &'static str {
+        match self.kind {
+            Kind::Any => "any",
+            Kind::String => "string",
+            Kind::Number => "number",
+            Kind::Bool => "boolean",
+            Kind::Array(_) => "array",
+            Kind::Object(_) => "object",
+            Kind::OneOf(_) => "oneOf",
+        }
+    }
+}
+
+/// A single validation failure.
+struct Issue {
+    path: String,
+    code: String,
+    message: String,
+}
+
+/// Joins a parent path and a child path (`user` + `name` → `user.name`,
+/// `tags` + `[0]` → `tags[0]`).
+fn join_path(parent: &str, child: &str) -> String {
+    match (parent.is_empty(), child.is_empty()) {
+        (true, _) => child.to_string(),
+        (_, true) => parent.to_string(),
+        _ if child.starts_with('[') => format!("{}{}", parent, child),
+        _ => format!("{}.{}", parent, child),
+    }
+}
+
+fn issue(path: &str, code: &str, message: String) -> Issue {
+    Issue {
+        path: path.to_string(),
+        code: code.to_string(),
+        message,
+    }
+}
+
+/* ============================================================================
+ * VALIDATION
+ * ============================================================================
+ */
+
+/// Validates a value against a nested schema through its `validate`
+/// function, prefixing reported paths with `path`.
+fn validate_nested(schema: &Value, value: Value, path: &str, issues: &mut Vec<Issue>) -> Result<Value, PawxError> {
+    let validate = match schema {
+        Value::Object { fields } => fields.borrow().get("validate").cloned(),
+        _ => None,
+    };
+
+    let result = match validate {
+        Some(Value::NativeFunction(f)) => f(vec![value.clone()])?,
+        _ => return raise_error(format!("Schema: nested schema at '{}' has no validate(value) function", path)),
+    };
+
+    let fields = match result {
+        Value::Object { fields } => fields.borrow().clone(),
+        _ => return Ok(value),
+    };
+
+    if let Some(Value::Array { values, .. }) = fields.get("errors") {
+        for err in values.borrow().iter() {
+            if let Value::Object { fields: e } = err {
+                let e = e.borrow();
+                let child = match e.get("path") {
+                    Some(Value::String(p)) => p.to_string(),
+                    _ => String::new(),
+                };
+                let message = e.get("message").map(|m| m.stringify()).unwrap_or_default();
+                let code = match e.get("code") {
+                    Some(Value::String(c)) => &**c,
+                    _ => "custom",
+                };
+
+                issues.push(issue(&join_path(path, &child), code, message));
+            }
+        }
+    }
+
+    Ok(fields.get("value").cloned().unwrap_or(value))
+}
+
+/// Returns the size `min`/`max` apply to, if the value has one.
+fn measure(value: &Value) -> Option<(f64, &'static str)> {
+    match value {
+        Value::Number(n) => Some((*n, "")),
+        Value::String(s) => Some((s.chars().count() as f64, " characters")),
+        Value::Array { values, .. } => Some((values.borrow().len() as f64, " items")),
+        _ => None,
+    }
+}
+
+fn run_checks(def: &SchemaDef, value: &Value, path: &str, issues: &mut Vec<Issue>) -> Result<(), PawxError> {
+    for check in &def.checks {
+        match check {
+            Check::Min(min) => {
+                if let Some((size, unit)) = measure(value) {
+                    if size < *min {
+                        issues.push(issue(path, "min", format!("must be at least {}{}", min, unit)));
+                    }
+                }
+            }
+            Check::Max(max) => {
+                if let Some((size, unit)) = measure(value) {
+                    if size > *max {
+                        issues.push(issue(path, "max", format!("must be at most {}{}", max, unit)));
+                    }
+                }
+            }
+            Check::Int => {
+                if let Value::Number(n) = value {
+                    if n.fract() != 0.0 {
+                        issues.push(issue(path, "int", "must be an integer".to_string()));
+                    }
+                }
+            }
+            Check::Pattern(re) => {
+                if let Value::String(s) = value {
+                    if !re.is_match(s) {
+                        issues.push(issue(path, "pattern", format!("must match /{}/", re.as_str())));
+                    }
+                }
+            }
+            Check::Email => {
+                if let Value::String(s) = value {
+                    let valid = s.split_once('@').is_some_and(|(local, domain)| {
+                        !local.is_empty()
+                            && domain.contains('.')
+                            && !domain.starts_with('.')
+                            && !domain.ends_with('.')
+                            && !s.contains(char::is_whitespace)
+                    });
+                    if !valid {
+                        issues.push(issue(path, "email", "must be an email address".to_string()));
+                    }
+                }
+            }
+            Check::Refine(predicate, message) => {
+                if let Value::NativeFunction(f) = predicate.as_ref() {
+                    if !f(vec![value.clone()])?.is_truthy() {
+                        issues.push(issue(path, "custom", message.clone()));
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Validates `value`, collecting issues and returning the value with
+/// defaults applied.
+fn validate(def: &SchemaDef, value: Value, path: &str, issues: &mut Vec<Issue>) -> Result<Value, PawxError> {
+    if matches!(value, Value::Null) {
+        if let Some(default) = &def.default {
+            return Ok(default.clone());
+        }
+        if def.optional || matches!(def.kind, Kind::Any) {
+            return Ok(Value::Null);
+        }
+        issues.push(issue(path, "required", "is required".to_string()));
+        return Ok(Value::Null);
+    }
+
+    let value = match (&def.kind, value) {
+        (Kind::Any, v) => v,
+        (Kind::String, v @ Value::String(_)) => v,
+        (Kind::Number, v @ Value::Number(_)) => v,
+        (Kind::Bool, v @ Value::Bool(_)) => v,
+
+        (Kind::OneOf(allowed), v) => {
+            let mut found = false;
+            for a in allowed {
+                if Value::equals_strict(a, &v)? {
+                    found = true;
+                    break;
+                }
+            }
+            if !found {
+                let list: Vec<String> = allowed.iter().map(Value::stringify).collect();
+                issues.push(issue(path, "enum", format!("must be one of: {}", list.join(", "))));
+            }
+            v
+        }
+
+        (Kind::Array(item), Value::Array { values, proto }) => {
+            let mut checked = Vec::new();
+            for (i, v) in values.borrow().iter().enumerate() {
+                checked.push(validate_nested(item, v.clone(), &join_path(path, &format!("[{}]", i)), issues)?);
+            }
+
+            Value::Array {
+                values: Rc::new(RefCell::new(checked)),
+                proto,
+            }
+        }
+
+        (Kind::Object(shape), Value::Object { fields }) => {
+            let input = fields.borrow().clone();
+            let mut output = HashMap::new();
+
+            for (key, schema) in shape {
+                let field = input.get(key).cloned().unwrap_or(Value::Null);
+                let checked = validate_nested(schema, field, &join_path(path, key), issues)?;
+                if !matches!(checked, Value::Null) || input.contains_key(key) {
+                    output.insert(key.clone(), checked);
+                }
+            }
+
+            for (key, v) in input {
+                if shape.iter().any(|(k, _)| *k == key) {
+                    continue;
+                }
+                if def.strict {
+                    issues.push(issue(&join_path(path, &key), "unknown", "is not allowed".to_string()));
+                } else {
+                    output.insert(key, v);
+                }
+            }
+
+            Value::Object {
+                fields: Rc::new(RefCell::new(output)),
+            }
+        }
+
+        (_, v) => {
+            issues.push(issue(
+                path,
+                "type",
+                format!("expected {}, got {}", def.type_label(), v.type_name()),
+            ));
+            return Ok(v);
+        }
+    };
+
+    run_checks(def, &value, path, issues)?;
+    Ok(value)
+}
+
+/// Runs a schema and builds the PAWX result object:
+/// `{ ok, value, errors: [{ path, code, message }] }`.
+fn validate_to_value(def: &SchemaDef, value: Value) -> Result<Value, PawxError> {
+    let mut issues = Vec::new();
+    let value = validate(def, value, "", &mut issues)?;
+
+    let errors: Vec<Value> = issues
+        .into_iter()
+        .map(|i| {
+            let mut fields = HashMap::new();
+            fields.insert("path".to_string(), Value::String(i.path.into()));
+            fields.insert("code".to_string(), Value::String(i.code.into()));
+            fields.insert("message".to_string(), Value::String(i.message.into()));
+            object(fields)
+        })
+        .collect();
+
+    let mut fields = HashMap::new();
+    fields.insert("ok".to_string(), Value::Bool(errors.is_empty()));
+    fields.insert("value".to_string(), value);
+    fields.insert(
+        "errors".to_string(),
+        Value::Array {
+            values: Rc::new(RefCell::new(errors)),
+            proto: create_array_proto(),
+        },
+    );
+
+    Ok(object(fields))
+}
+
+/* ============================================================================
+ * PAWX BINDINGS
+ * ============================================================================
+ */
+
+fn object(fields: HashMap<String, Value>) -> Value {
+    Value::Object {
+        fields: Rc::new(RefCell::new(fields)),
+    }
+}
+
+fn number_arg(args: &[Value], method: &str) -> Result<f64, PawxError> {
+    match args.first() {
+        Some(Value::Number(n)) => Ok(*n),
+        _ => raise_error(format!("Schema.{}(n) requires a number", method)),
+    }
+}
+
+/// Registers a modifier that derives a new schema from the current one.
+fn modifier(
+    fields: &mut HashMap<String, Value>,
+    def: &Rc<SchemaDef>,
+    name: &'static str,
+    apply: impl Fn(&mut SchemaDef, &[Value]) -> Result<(), PawxError> + 'static,
+) {
+    let def = def.clone();
+    fields.insert(
+        name.to_string(),
+        Value::NativeFunction(Arc::new(move |args: Vec<Value>| -> Result<Value, PawxError> {
+            let mut next = (*def).clone();
+            apply(&mut next, &args)?;
+            Ok(schema_value(next))
+        })),
+    );
+}
+
+/// Wraps a schema definition as a PAWX object.
+///
+/// # Installed Functions
+/// - `validate(value)`
+/// - `optional()`, `default(value)`, `refine(fn, message?)`
+/// - `min(n)`, `max(n)` (numbers, strings, arrays)
+/// - `int()` (numbers)
+/// - `pattern(regex)`, `email()` (strings)
+/// - `strict()` (objects)
+fn schema_value(def: SchemaDef) -> Value {
+    let def = Rc::new(def);
+    let mut fields = HashMap::new();
+
+    let d = def.clone();
+    fields.insert(
+        "validate".to_string(),
+        Value::NativeFunction(Arc::new(move |args: Vec<Value>| -> Result<Value, PawxError> {
+            validate_to_value(&d, args.into_iter().next().unwrap_or(Value::Null))
+        })),
+    );
+
+    fields.insert("type".to_string(), Value::String(def.type_label().into()));
+
+    modifier(&mut fields, &def, "optional", |d, _| {
+        d.optional = true;
+        Ok(())
+    });
+    modifier(&mut fields, &def, "default", |d, args| {
+        d.default = Some(args.first().cloned().unwrap_or(Value::Null));
+        Ok(())
+    });
+    modifier(&mut fields, &def, "refine", |d, args| {
+        let predicate = match args.first() {
+            Some(f @ Value::NativeFunction(_)) => f.clone(),
+            _ => return raise_error("Schema.refine(fn, message?) requires a function"),
+        };
+        let message = match args.get(1) {
+            Some(Value::String(m)) => m.to_string(),
+            _ => "is invalid".to_string(),
+        };
+        d.checks.push(Check::Refine(Box::new(predicate), message));
+        Ok(())
+    });
+
+    if matches!(def.kind, Kind::Number | Kind::String | Kind::Array(_)) {
+        modifier(&mut fields, &def, "min", |d, args| {
+            d.checks.push(Check::Min(number_arg(args, "min")?));
+            Ok(())
+        });
+        modifier(&mut fields, &def, "max", |d, args| {
+            d.checks.push(Check::Max(number_arg(args, "max")?));
+            Ok(())
+        });
+    }
+
+    if matches!(def.kind, Kind::Number) {
+        modifier(&mut fields, &def, "int", |d, _| {
+            d.checks.push(Check::Int);
+            Ok(())
+        });
+    }
+
+    if matches!(def.kind, Kind::String) {
+        modifier(&mut fields, &def, "pattern", |d, args| {
+            let re = match args.first() {
+                Some(Value::String(source)) => Regex::new(source)
+                    .map_err(|e| builtin_error(format!("Schema.pattern: invalid pattern '{}': {}", source, e)))?,
+                Some(Value::Regex(re)) => Regex::clone(re),
+                _ => return raise_error("Schema.pattern(regex) requires a pattern string"),
+            };
+            d.checks.push(Check::Pattern(re));
+            Ok(())
+        });
+        modifier(&mut fields, &def, "email", |d, _| {
+            d.checks.push(Check::Email);
+            Ok(())
+        });
+    }
+
+    if matches!(def.kind, Kind::Object(_)) {
+        modifier(&mut fields, &def, "strict", |d, _| {
+            d.strict = true;
+            Ok(())
+        });
+    }
+
+    object(fields)
+}
+
+fn expect_schema(value: Option<&Value>, method: &str) -> Result<Value, PawxError> {
+    match value {
+        Some(v @ Value::Object { fields }) if fields.borrow().contains_key("validate") => Ok(v.clone()),
+        _ => raise_error(format!("{} expects a schema", method)),
+    }
+}
+
+/// Creates the global `Schema` object.
+///
+/// # PAWX Example
+/// ```pawx
+/// snuggle User = Schema.object({
+///     name: Schema.string().min(1),
+///     age: Schema.number().int().min(0),
+///     tags: Schema.array(Schema.string()).optional()
+/// });
+///
+/// snuggle result = User.validate({ name: "", age: 3.5 });
+/// result.errors.forEach(e -> { meow(e.path, e.message) });
+/// ```
+pub fn create_global_schema_object() -> HashMap<String, Value> {
+    let mut map = HashMap::new();
+
+    for (name, kind) in [
+        ("string", Kind::String),
+        ("number", Kind::Number),
+        ("boolean", Kind::Bool),
+        ("any", Kind::Any),
+    ] {
+        map.insert(
+            name.to_string(),
+            Value::NativeFunction(Arc::new(move |_args: Vec<Value>| -> Result<Value, PawxError> {
+                Ok(schema_value(SchemaDef::new(kind.clone())))
+            })),
+        );
+    }
+
+    map.insert(
+        "array".to_string(),
+        Value::NativeFunction(Arc::new(|args: Vec<Value>| -> Result<Value, PawxError> {
+            let item = match args.first() {
+                None | Some(Value::Null) => schema_value(SchemaDef::new(Kind::Any)),
+                other => expect_schema(other, "Schema.array(item?)")?,
+            };
+            Ok(schema_value(SchemaDef::new(Kind::Array(item))))
+        })),
+    );
+
+    map.insert(
+        "object".to_string(),
+        Value::NativeFunction(Arc::new(|args: Vec<Value>| -> Result<Value, PawxError> {
+            let shape = match args.first() {
+                Some(Value::Object { fields }) => fields.borrow().clone(),
+                _ => return raise_error("Schema.object(shape) expects an object of schemas"),
+            };
+
+            let mut entries: Vec<(String, Value)> = shape
+                .into_iter()
+                .map(|(key, schema)| {
+                    let schema = expect_schema(Some(&schema), &format!("Schema.object: field '{}'", key))?;
+                    Ok((key, schema))
+                })
+                .collect::<Result<_, PawxError>>()?;
+
+            // Stable error order regardless of hash iteration.
+            entries.sort_by(|a, b| a.0.cmp(&b.0));
+
+            Ok(schema_value(SchemaDef::new(Kind::Object(entries))))
+        })),
+    );
+
+    map.insert(
+        "oneOf".to_string(),
+        Value::NativeFunction(Arc::new(|args: Vec<Value>| -> Result<Value, PawxError> {
+            let allowed = match args.first() {
+                Some(Value::Array { values, .. }) => values.borrow().clone(),
+                _ => return raise_error("Schema.oneOf(values) expects an array"),
+            };
+            Ok(schema_value(SchemaDef::new(Kind::OneOf(allowed))))
+        })),
+    );
+
+    map
+}
+
+/// Validates `value` with any schema object; used by `req.validate`.
+pub fn validate_with(schema: Option<&Value>, value: Value) -> Result<Value, PawxError> {
+    let validate = match expect_schema(schema, "req.validate(schema)")? {
+        Value::Object { fields } => fields.borrow().get("validate").cloned(),
+        _ => None,
+    };
+
+    match validate {
+        Some(Value::NativeFunction(f)) => f(vec![value]),
+        _ => raise_error("req.validate(schema): schema.validate must be a function"),
+    }
+}
@@ -0,0 +1,488 @@
+/*
+ * ============================================================================
+ * PAWX - Code with Claws! 🐾
+ * ============================================================================
+ *
+ * Serial Port Access for PAWX (`Serial`)
+ *
+ * Only compiled with the `serial` cargo feature.
+ *
+ * Supports:
+ *   - Serial.list()                 -> array<{ path, type, ... }>
+ *   - Serial.open(path, options?)   -> port
+ *   - port.onData(fn)               incoming data (the port is a Stream)
+ *   - port.write(chunk)             string or array of bytes
+ *   - port.poll()                   deliver data received so far
+ *   - port.loop(ms?)                deliver data for `ms` (until closed
+ *                                   if omitted)
+ *   - port.close()
+ *
+ * Options:
+ *   { baud (9600), dataBits (8), parity ("none" | "odd" | "even"),
+ *     stopBits (1 | 2), flowControl ("none" | "software" | "hardware"),
+ *     encoding ("utf8" | "bytes") }
+ *
+ * A port is a Stream whose readable side carries the data received from
+ * the device: UTF-8 text chunks by default, arrays of bytes with
+ * `encoding: "bytes"`. `write()` sends to the device instead of the
+ * stream, so a port can also be used as a `pipe()` destination.
+ *
+ * A background thread reads the device; chunks are delivered on the
+ * interpreter thread between top-level statements, on `poll()` and while
+ * `loop()` runs.
+ *
+ * --------------------------------------------------------------------------
+ * Author:   Sam Wilcox
+ * Email:    sam@pawx-lang.com
+ * Website:  https://www.pawx-lang.com
+ * GitHub:   https://github.com/samwilcox/pawx
+ *
+ * License:
+ * This file is part of the PAWX programming language project.
+ *
+ * PAWX is dual-licensed under the terms of:
+ *   - The MIT License
+ *   - The Apache License, Version 2.0
+ *
+ * You may choose either license to govern your use of this software.
+ * Full license text available at:
+ *     https://license.pawx-lang.com
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under these licenses is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *
+ * ==========================================================================
+ */
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::io::{ErrorKind, Read, Write};
+use std::rc::{Rc, Weak};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serialport::{DataBits, FlowControl, Parity, SerialPort, SerialPortType, StopBits};
+
+use crate::error::PawxError;
+use crate::interpreter::calls::raise_error;
+use crate::prototypes::array::create_array_proto;
+use crate::prototypes::codec::{array_to_bytes, bytes_to_array};
+use crate::prototypes::stream::{
+    emit_error, end_stream, new_stream, stream_value, write_chunk, SharedStream,
+    DEFAULT_HIGH_WATER_MARK,
+};
+use crate::value::Value;
+
+/// How often the reader thread checks whether the port was closed.
+const READ_TIMEOUT: Duration = Duration::from_millis(100);
+
+fn native(f: impl Fn(Vec<Value>) -> Result<Value, PawxError> + 'static) -> Value {
+    Value::NativeFunction(Arc::new(f))
+}
+
+/* ============================================================================
+ * LISTING
+ * ============================================================================
+ */
+
+fn port_info(info: serialport::SerialPortInfo) -> Value {
+    let mut fields = HashMap::new();
+    fields.insert("path".to_string(), Value::String(info.port_name.into()));
+
+    let kind = match info.port_type {
+        SerialPortType::UsbPort(usb) => {
+            let text = |s: Option<String>| s.map(|s| Value::String(s.into())).unwrap_or(Value::Null);
+            fields.insert("vendorId".to_string(), Value::Number(usb.vid as f64));
+            fields.insert("productId".to_string(), Value::Number(usb.pid as f64));
+            fields.insert("manufacturer".to_string(), text(usb.manufacturer));
+            fields.insert("product".to_string(), text(usb.product));
+            fields.insert("serialNumber".to_string(), text(usb.serial_number));
+            "usb"
+        }
+        SerialPortType::PciPort => "pci",
+        SerialPortType::BluetoothPort => "bluetooth",
+        SerialPortType::Unknown => "unknown",
+    };
+    fields.insert("type".to_string(), Value::String(kind.into()));
+
+    Value::Object {
+        fields: Rc::new(RefCell::new(fields)),
+    }
+}
+
+/* ============================================================================
+ * OPENING
+ * ============================================================================
+ */
+
+/// Settings for `Serial.open`.
+struct OpenOptions {
+    baud: u32,
+    data_bits: DataBits,
+    parity: Parity,
+    stop_bits: StopBits,
+    flow_control: FlowControl,
+    text: bool,
+}
+
+/// Reads the `options` of `Serial.open(path, options?)`.
+///
+/// # Errors
+/// A message naming the first bad option.
+fn parse_options(value: Option<&Value>) -> Result<OpenOptions, String> {
+    let fields = match value {
+        Some(Value::Object { fields }) => fields.borrow().clone(),
+        Some(Value::Null) | None => HashMap::new(),
+        _ => return Err("Serial.open(path, options?): options must be an object".to_string()),
+    };
+
+    let number = |key: &str, default: f64| match fields.get(key) {
+        Some(Value::Number(n)) => Ok(*n),
+        None | Some(Value::Null) => Ok(default),
+        _ => Err(format!("Serial.open: {} must be a number", key)),
+    };
+
+    let string = |key: &str, default: &str| match fields.get(key) {
+        Some(Value::String(s)) => Ok(s.to_string()),
+        None | Some(Value::Null) => Ok(default.to_string()),
+        _ => Err(format!("Serial.open: {} must be a string", key)),
+    };
+
+    Ok(OpenOptions {
+        baud: number("baud", 9600.0)? as u32,
+        data_bits: match number("dataBits", 8.0)? as u8 {
+            5 => DataBits::Five,
+            6 => DataBits::Six,
+            7 => DataBits::Seven,
+            8 => DataBits::Eight,
+            other => return Err(format!("Serial.open: dataBits must be 5-8, got {}", other)),
+        },
+        parity: match string("parity", "none")?.as_str() {
+            "none" => Parity::None,
+            "odd" => Parity::Odd,
+            "even" => Parity::Even,
+            other => return Err(format!("Serial.open: unknown parity '{}'", other)),
+        },
+        stop_bits: match number("stopBits", 1.0)? as u8 {
+            1 => StopBits::One,
+            2 => StopBits::Two,
+            other => return Err(format!("Serial.open: stopBits must be 1 or 2, got {}", other)),
+        },
+        flow_control: match string("flowControl", "none")?.as_str() {
+            "none" => FlowControl::None,
+            "software" => FlowControl::Software,
+            "hardware" => FlowControl::Hardware,
+            other => return Err(format!("Serial.open: unknown flowControl '{}'", other)),
+        },
+        text: match string("encoding", "utf8")?.as_str() {
+            "utf8" => true,
+            "bytes" => false,
+            other => return Err(format!("Serial.open: unknown encoding '{}'", other)),
+        },
+    })
+}
+
+/* ============================================================================
+ * OPEN PORTS
+ * ============================================================================
+ */
+
+struct OpenPort {
+    path: String,
+    writer: Mutex<Box<dyn SerialPort>>,
+    rx: Receiver<Result<Vec<u8>, String>>,
+    closed: Arc<AtomicBool>,
+    stream: SharedStream,
+    text: bool,
+
+    /// Bytes of a UTF-8 character split across reads.
+    carry: RefCell<Vec<u8>>,
+}
+
+thread_local! {
+    /// Open ports, so the interpreter can deliver their data.
+    static PORTS: RefCell<Vec<Weak<OpenPort>>> = const { RefCell::new(Vec::new()) };
+}
+
+impl OpenPort {
+    /// Turns raw bytes into the next stream chunk, if any is complete.
+    fn chunk(&self, bytes: Vec<u8>) -> Option<Value> {
+        if !self.text {
+            return Some(bytes_to_array(&bytes));
+        }
+
+        let mut carry = self.carry.borrow_mut();
+        carry.extend_from_slice(&bytes);
+
+        let valid = match std::str::from_utf8(&carry) {
+            Ok(_) => carry.len(),
+            Err(e) if e.error_len().is_none() => e.valid_up_to(),
+            Err(_) => carry.len(),
+        };
+
+        let text = String::from_utf8_lossy(&carry[..valid]).to_string();
+        carry.drain(..valid);
+
+        (!text.is_empty()).then_some(Value::String(text.into()))
+    }
+
+    /// Handles one report from the reader thread.
+    ///
+    /// # Errors
+    /// Whatever the stream's listeners raise.
+    fn accept(&self, report: Result<Vec<u8>, String>) -> Result<(), PawxError> {
+        match report {
+            Ok(bytes) => {
+                if let Some(chunk) = self.chunk(bytes) {
+                    write_chunk(&self.stream, chunk)?;
+                }
+                Ok(())
+            }
+            Err(message) => {
+                self.closed.store(true, Ordering::SeqCst);
+                emit_error(&self.stream, Value::Error { message })
+            }
+        }
+    }
+
+    /// Delivers everything received so far; returns the number of reads.
+    fn dispatch(&self) -> Result<usize, PawxError> {
+        let reports: Vec<_> = self.rx.try_iter().collect();
+        let count = reports.len();
+
+        for report in reports {
+            self.accept(report)?;
+        }
+
+        Ok(count)
+    }
+
+    fn run_for(&self, duration: Option<Duration>) -> Result<(), PawxError> {
+        let deadline = duration.map(|d| Instant::now() + d);
+
+        loop {
+            self.dispatch()?;
+
+            if self.closed.load(Ordering::SeqCst) {
+                return Ok(());
+            }
+
+            let wait = match deadline {
+                Some(deadline) if Instant::now() >= deadline => return Ok(()),
+                Some(deadline) => deadline - Instant::now(),
+                None => READ_TIMEOUT,
+            };
+
+            if let Ok(report) = self.rx.recv_timeout(wait) {
+                self.accept(report)?;
+            }
+        }
+    }
+
+    fn write(&self, chunk: &Value) -> Result<(), String> {
+        if self.closed.load(Ordering::SeqCst) {
+            return Err(format!("Serial '{}': port is closed", self.path));
+        }
+
+        let bytes = match chunk {
+            Value::String(s) => s.as_bytes().to_vec(),
+            v @ Value::Array { .. } => array_to_bytes(Some(v), "port.write(chunk)").map_err(|e| e.message)?,
+            other => other.to_pawx_string().into_bytes(),
+        };
+
+        let mut writer = self
+            .writer
+            .lock()
+            .map_err(|_| format!("Serial '{}': port lock poisoned", self.path))?;
+
+        writer
+            .write_all(&bytes)
+            .and_then(|_| writer.flush())
+            .map_err(|e| format!("Serial '{}': {}", self.path, e))
+    }
+
+    fn close(&self) -> Result<(), PawxError> {
+        if !self.closed.swap(true, Ordering::SeqCst) {
+            self.dispatch()?;
+            end_stream(&self.stream)?;
+        }
+        Ok(())
+    }
+}
+
+/// Delivers received data for every open port.
+///
+/// Called by the interpreter between top-level statements and before exit.
+///
+/// # Errors
+/// The first error raised by a stream listener.
+pub fn dispatch_data() -> Result<(), PawxError> {
+    let ports: Vec<Rc<OpenPort>> = PORTS.with(|p| {
+        let mut ports = p.borrow_mut();
+        ports.retain(|w| w.strong_count() > 0);
+        ports.iter().filter_map(Weak::upgrade).collect()
+    });
+
+    for port in ports {
+        port.dispatch()?;
+    }
+
+    Ok(())
+}
+
+/// Opens `path` and starts its reader thread.
+///
+/// # Errors
+/// If the device cannot be opened.
+fn open(path: String, opts: OpenOptions) -> Result<Rc<OpenPort>, String> {
+    let port = serialport::new(&path, opts.baud)
+        .data_bits(opts.data_bits)
+        .parity(opts.parity)
+        .stop_bits(opts.stop_bits)
+        .flow_control(opts.flow_control)
+        .timeout(READ_TIMEOUT)
+        .open()
+        .map_err(|e| format!("Serial.open('{}'): {}", path, e))?;
+
+    let mut reader = port
+        .try_clone()
+        .map_err(|e| format!("Serial.open('{}'): {}", path, e))?;
+
+    let closed = Arc::new(AtomicBool::new(false));
+    let (tx, rx) = mpsc::channel();
+
+    {
+        let closed = closed.clone();
+        thread::spawn(move || {
+            let mut buf = [0u8; 1024];
+
+            while !closed.load(Ordering::SeqCst) {
+                let report = match reader.read(&mut buf) {
+                    Ok(0) => continue,
+                    Ok(n) => Ok(buf[..n].to_vec()),
+                    Err(e) if matches!(e.kind(), ErrorKind::TimedOut | ErrorKind::WouldBlock) => continue,
+                    Err(e) => Err(e.to_string()),
+                };
+
+                let failed = report.is_err();
+                if tx.send(report).is_err() || failed {
+                    return;
+                }
+            }
+        });
+    }
+
+    let port = Rc::new(OpenPort {
+        path,
+        writer: Mutex::new(port),
+        rx,
+        closed,
+        stream: new_stream(DEFAULT_HIGH_WATER_MARK),
+        text: opts.text,
+        carry: RefCell::new(Vec::new()),
+    });
+
+    PORTS.with(|p| p.borrow_mut().push(Rc::downgrade(&port)));
+    Ok(port)
+}
+
+/* ============================================================================
+ * PAWX BINDING
+ * ============================================================================
+ */
+
+/// Creates the PAWX object for an open port: its stream plus device I/O.
+fn port_value(port: Rc<OpenPort>) -> Value {
+    let Value::Object { fields } = stream_value(port.stream.clone()) else {
+        unreachable!("stream_value returns an object");
+    };
+
+    let mut fields = fields.borrow().clone();
+    fields.insert("path".to_string(), Value::String(port.path.as_str().into()));
+
+    let p = port.clone();
+    fields.insert(
+        "write".to_string(),
+        native(move |args| {
+            let chunk = args.first().cloned().unwrap_or(Value::Null);
+            match p.write(&chunk) {
+                Ok(()) => Ok(Value::Bool(true)),
+                Err(message) => raise_error(message),
+            }
+        }),
+    );
+
+    let p = port.clone();
+    fields.insert(
+        "poll".to_string(),
+        native(move |_| Ok(Value::Number(p.dispatch()? as f64))),
+    );
+
+    let p = port.clone();
+    fields.insert(
+        "loop".to_string(),
+        native(move |args| {
+            let duration = match args.first() {
+                Some(Value::Number(ms)) if *ms >= 0.0 => Some(Duration::from_millis(*ms as u64)),
+                _ => None,
+            };
+            p.run_for(duration)?;
+            Ok(Value::Null)
+        }),
+    );
+
+    // `end()` lets a port finish a pipe(); both close the device.
+    for name in ["close", "end"] {
+        let p = port.clone();
+        fields.insert(
+            name.to_string(),
+            native(move |_| {
+                p.close()?;
+                Ok(Value::Null)
+            }),
+        );
+    }
+
+    Value::Object {
+        fields: Rc::new(RefCell::new(fields)),
+    }
+}
+
+/// Builds the global `Serial` object.
+pub fn create_global_serial_object() -> HashMap<String, Value> {
+    let mut serial = HashMap::new();
+
+    serial.insert(
+        "list".to_string(),
+        native(|_| {
+            match serialport::available_ports() {
+                Ok(ports) => Ok(Value::Array {
+                    values: Rc::new(RefCell::new(ports.into_iter().map(port_info).collect())),
+                    proto: create_array_proto(),
+                }),
+                Err(e) => raise_error(format!("Serial.list(): {}", e)),
+            }
+        }),
+    );
+
+    serial.insert(
+        "open".to_string(),
+        native(|args| {
+            let path = match args.first() {
+                Some(Value::String(p)) => p.to_string(),
+                _ => return raise_error("Serial.open(path, options?) expects a path string"),
+            };
+
+            match parse_options(args.get(1)).and_then(|opts| open(path, opts)) {
+                Ok(port) => Ok(port_value(port)),
+                Err(message) => raise_error(message),
+            }
+        }),
+    );
+
+    serial
+}
@@ -0,0 +1,431 @@
+/*
+ * ============================================================================
+ * PAWX - Code with Claws! 🐾
+ * ============================================================================
+ *
+ * Static Site Builder for PAWX (`Site`)
+ *
+ * Supports:
+ *   - Site.build(config?)   -> { pages, written, unchanged, copied }
+ *
+ * Used by `pawx ssg`, which runs a `site.px` config script such as:
+ *
+ *   Site.build({
+ *       content: "content",      // pages (.md and .html)
+ *       layouts: "layouts",      // <name>.html templates
+ *       assets:  "assets",       // copied as-is
+ *       out:     "dist",
+ *       layout:  "default",      // layout for pages that do not pick one
+ *       data:    { title: "My Site" },
+ *   });
+ *
+ * Every option is optional; the values above are the defaults.
+ *
+ * Pages:
+ *   - `.md` files are rendered with `Markdown`; `.html` files are
+ *     rendered with `Template` (so listing pages can loop over
+ *     `site.pages`)
+ *   - Front matter (see `Markdown.parse`) becomes `page`; `layout: name`
+ *     picks a layout, `layout: none` skips it
+ *   - `content/a/b.md` is written to `dist/a/b.html` with `page.url`
+ *     `/a/b.html`; `index` files get their directory's URL
+ *   - Other files in the content directory are copied
+ *
+ * Layouts are `Template`s rendered with `{ site, page, content }`, where
+ * `site` is `data` plus `site.pages` (every page's front matter and url).
+ * A layout may name its own parent layout in front matter.
+ *
+ * Builds are incremental: output files are only written when their
+ * content changed, and assets are only copied when they are newer or a
+ * different size than the copy in `out`.
+ *
+ * --------------------------------------------------------------------------
+ * Author:   Sam Wilcox
+ * Email:    sam@pawx-lang.com
+ * Website:  https://www.pawx-lang.com
+ * GitHub:   https://github.com/samwilcox/pawx
+ *
+ * License:
+ * This file is part of the PAWX programming language project.
+ *
+ * PAWX is dual-licensed under the terms of:
+ *   - The MIT License
+ *   - The Apache License, Version 2.0
+ *
+ * You may choose either license to govern your use of this software.
+ * Full license text available at:
+ *     https://license.pawx-lang.com
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under these licenses is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *
+ * ==========================================================================
+ */
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::Arc;
+
+use crate::error::PawxError;
+use crate::interpreter::calls::{builtin_error, raise_error};
+use crate::prototypes::array::create_array_proto;
+use crate::prototypes::markdown::{split_front_matter, to_html};
+use crate::prototypes::template;
+use crate::value::Value;
+
+/// Layout chains deeper than this are assumed to be cycles.
+const MAX_LAYOUT_DEPTH: usize = 16;
+
+fn native(f: impl Fn(Vec<Value>) -> Result<Value, PawxError> + 'static) -> Value {
+    Value::NativeFunction(Arc::new(f))
+}
+
+fn object(fields: HashMap<String, Value>) -> Value {
+    Value::Object {
+        fields: Rc::new(RefCell::new(fields)),
+    }
+}
+
+thread_local! {
+    /// Directories read by the last build, for `pawx ssg --watch`.
+    static WATCH_ROOTS: RefCell<Vec<PathBuf>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Returns the directories the last `Site.build` read from.
+pub fn watch_roots() -> Vec<PathBuf> {
+    WATCH_ROOTS.with(|r| r.borrow().clone())
+}
+
+/// Lists every file under `dir`, sorted; a missing directory is empty.
+pub fn walk_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+
+    while let Some(dir) = pending.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                pending.push(path);
+            } else {
+                files.push(path);
+            }
+        }
+    }
+
+    files.sort();
+    files
+}
+
+/* ============================================================================
+ * CONFIG
+ * ============================================================================
+ */
+
+struct SiteConfig {
+    content: PathBuf,
+    layouts: PathBuf,
+    assets: PathBuf,
+    out: PathBuf,
+    layout: String,
+    data: HashMap<String, Value>,
+}
+
+fn parse_config(value: Option<&Value>) -> Result<SiteConfig, PawxError> {
+    let fields = match value {
+        Some(Value::Object { fields }) => fields.borrow().clone(),
+        Some(Value::Null) | None => HashMap::new(),
+        _ => return raise_error("Site.build(config?): config must be an object"),
+    };
+
+    let string = |key: &str, default: &str| match fields.get(key) {
+        Some(Value::String(s)) => Ok(s.to_string()),
+        None | Some(Value::Null) => Ok(default.to_string()),
+        _ => raise_error(format!("Site.build: '{}' must be a string", key)),
+    };
+
+    Ok(SiteConfig {
+        content: PathBuf::from(string("content", "content")?),
+        layouts: PathBuf::from(string("layouts", "layouts")?),
+        assets: PathBuf::from(string("assets", "assets")?),
+        out: PathBuf::from(string("out", "dist")?),
+        layout: string("layout", "default")?,
+        data: match fields.get("data") {
+            Some(Value::Object { fields }) => fields.borrow().clone(),
+            None | Some(Value::Null) => HashMap::new(),
+            _ => return raise_error("Site.build: 'data' must be an object"),
+        },
+    })
+}
+
+/* ============================================================================
+ * PAGES
+ * ============================================================================
+ */
+
+struct Page {
+    source: PathBuf,
+
+    /// Output path relative to `out`.
+    target: PathBuf,
+
+    /// Front matter plus `url`.
+    data: HashMap<String, Value>,
+
+    body: String,
+    markdown: bool,
+}
+
+fn url_for(target: &Path) -> String {
+    let path = target.to_string_lossy().replace('\\', "/");
+
+    match path.strip_suffix("index.html") {
+        Some(dir) => format!("/{}", dir),
+        None => format!("/{}", path),
+    }
+}
+
+fn load_page(config: &SiteConfig, source: PathBuf) -> Result<Option<Page>, PawxError> {
+    let markdown = match source.extension().and_then(|e| e.to_str()) {
+        Some("md") | Some("markdown") => true,
+        Some("html") | Some("htm") => false,
+        _ => return Ok(None),
+    };
+
+    let text = fs::read_to_string(&source)
+        .map_err(|e| builtin_error(format!("Site.build: {}: {}", source.display(), e)))?;
+    let (mut data, body) = split_front_matter(&text);
+
+    let relative = source.strip_prefix(&config.content).unwrap_or(&source);
+    let target = relative.with_extension("html");
+
+    data.insert("url".to_string(), Value::String(url_for(&target).into()));
+
+    Ok(Some(Page {
+        body: body.to_string(),
+        source,
+        target,
+        data,
+        markdown,
+    }))
+}
+
+struct Layout {
+    nodes: Vec<template::Node>,
+    parent: Option<String>,
+}
+
+fn load_layout(config: &SiteConfig, name: &str, cache: &mut HashMap<String, Rc<Layout>>) -> Result<Rc<Layout>, PawxError> {
+    if let Some(layout) = cache.get(name) {
+        return Ok(layout.clone());
+    }
+
+    let path = config.layouts.join(format!("{}.html", name));
+    let text = fs::read_to_string(&path)
+        .map_err(|e| builtin_error(format!("Site.build: layout '{}' ({}): {}", name, path.display(), e)))?;
+    let (data, body) = split_front_matter(&text);
+
+    let layout = Rc::new(Layout {
+        nodes: template::parse(body).map_err(|e| builtin_error(format!("Site.build: layout '{}': {}", name, e)))?,
+        parent: match data.get("layout") {
+            Some(Value::String(parent)) => Some(parent.to_string()),
+            _ => None,
+        },
+    });
+
+    cache.insert(name.to_string(), layout.clone());
+    Ok(layout)
+}
+
+/// Renders one page through its layout chain.
+fn render_page(
+    config: &SiteConfig,
+    page: &Page,
+    site: &Value,
+    layouts: &mut HashMap<String, Rc<Layout>>,
+) -> Result<String, PawxError> {
+    let page_value = object(page.data.clone());
+    let context = |content: String| {
+        let mut fields = HashMap::new();
+        fields.insert("site".to_string(), site.clone());
+        fields.insert("page".to_string(), page_value.clone());
+        fields.insert("content".to_string(), Value::String(content.into()));
+        object(fields)
+    };
+
+    let mut html = if page.markdown {
+        to_html(&page.body)
+    } else {
+        template::render(&page.body, &context(String::new()))
+            .map_err(|e| builtin_error(format!("Site.build: {}: {}", page.source.display(), e)))?
+    };
+
+    // A page without its own layout only uses the default one if it exists
+    let mut next = match page.data.get("layout") {
+        Some(Value::String(name)) if &**name == "none" => None,
+        Some(Value::String(name)) => Some(name.to_string()),
+        _ if config.layouts.join(format!("{}.html", config.layout)).exists() => Some(config.layout.clone()),
+        _ => None,
+    };
+
+    let mut depth = 0;
+    while let Some(name) = next {
+        depth += 1;
+        if depth > MAX_LAYOUT_DEPTH {
+            return raise_error(format!("Site.build: layout chain for {} is too deep (cycle?)", page.source.display()));
+        }
+
+        let layout = load_layout(config, &name, layouts)?;
+        html = template::render_parsed(&layout.nodes, &context(html));
+        next = layout.parent.clone();
+    }
+
+    Ok(html)
+}
+
+/* ============================================================================
+ * OUTPUT
+ * ============================================================================
+ */
+
+/// Writes `contents` unless the file already holds exactly that.
+///
+/// # Returns
+/// `true` if the file was written.
+fn write_if_changed(path: &Path, contents: &[u8]) -> Result<bool, PawxError> {
+    if fs::read(path).is_ok_and(|existing| existing == contents) {
+        return Ok(false);
+    }
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| builtin_error(format!("Site.build: {}: {}", parent.display(), e)))?;
+    }
+
+    fs::write(path, contents).map_err(|e| builtin_error(format!("Site.build: {}: {}", path.display(), e)))?;
+    Ok(true)
+}
+
+/// Copies `source` unless `target` is at least as new and the same size.
+///
+/// # Returns
+/// `true` if the file was copied.
+fn copy_if_newer(source: &Path, target: &Path) -> Result<bool, PawxError> {
+    let (Ok(src), dest) = (fs::metadata(source), fs::metadata(target)) else {
+        return Ok(false);
+    };
+
+    if let Ok(dest) = dest {
+        let newer = match (src.modified(), dest.modified()) {
+            (Ok(s), Ok(d)) => s > d,
+            _ => true,
+        };
+        if !newer && src.len() == dest.len() {
+            return Ok(false);
+        }
+    }
+
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| builtin_error(format!("Site.build: {}: {}", parent.display(), e)))?;
+    }
+
+    fs::copy(source, target)
+        .map_err(|e| builtin_error(format!("Site.build: copying {}: {}", source.display(), e)))?;
+    Ok(true)
+}
+
+/* ============================================================================
+ * BUILD
+ * ============================================================================
+ */
+
+fn build(config: SiteConfig) -> Result<Value, PawxError> {
+    WATCH_ROOTS.with(|r| {
+        *r.borrow_mut() = vec![config.content.clone(), config.layouts.clone(), config.assets.clone()];
+    });
+
+    let mut pages = Vec::new();
+    let mut copies = Vec::new();
+
+    for source in walk_files(&config.content) {
+        match load_page(&config, source.clone())? {
+            Some(page) => pages.push(page),
+            None => {
+                let relative = source.strip_prefix(&config.content).unwrap_or(&source);
+                copies.push((source.clone(), config.out.join(relative)));
+            }
+        }
+    }
+
+    for source in walk_files(&config.assets) {
+        let relative = source.strip_prefix(&config.assets).unwrap_or(&source);
+        copies.push((source.clone(), config.out.join(relative)));
+    }
+
+    pages.sort_by(|a, b| a.target.cmp(&b.target));
+
+    let mut site_fields = config.data.clone();
+    site_fields.insert(
+        "pages".to_string(),
+        Value::Array {
+            values: Rc::new(RefCell::new(pages.iter().map(|p| object(p.data.clone())).collect())),
+            proto: create_array_proto(),
+        },
+    );
+    let site = object(site_fields);
+
+    let mut layouts = HashMap::new();
+    let (mut written, mut unchanged) = (0, 0);
+
+    for page in &pages {
+        let html = render_page(&config, page, &site, &mut layouts)?;
+        if write_if_changed(&config.out.join(&page.target), html.as_bytes())? {
+            written += 1;
+        } else {
+            unchanged += 1;
+        }
+    }
+
+    let mut copied = 0;
+    for (source, target) in &copies {
+        if copy_if_newer(source, target)? {
+            copied += 1;
+        }
+    }
+
+    println!(
+        "🐾 Site built: {} pages ({} written, {} unchanged), {} files copied -> {}",
+        pages.len(),
+        written,
+        unchanged,
+        copied,
+        config.out.display()
+    );
+
+    let mut result = HashMap::new();
+    result.insert("pages".to_string(), Value::Number(pages.len() as f64));
+    result.insert("written".to_string(), Value::Number(written as f64));
+    result.insert("unchanged".to_string(), Value::Number(unchanged as f64));
+    result.insert("copied".to_string(), Value::Number(copied as f64));
+    Ok(object(result))
+}
+
+/// Builds the global `Site` object.
+pub fn create_global_site_object() -> HashMap<String, Value> {
+    let mut site = HashMap::new();
+
+    site.insert(
+        "build".to_string(),
+        native(|args| build(parse_config(args.first())?)),
+    );
+
+    site
+}
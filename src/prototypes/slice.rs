@@ -0,0 +1,103 @@
+/*
+ * ============================================================================
+ * PAWX - Code with Claws! 🐾
+ * ============================================================================
+ *
+ * Shared Slice Arithmetic for PAWX
+ *
+ * Every `slice(start?, end?, step?)` in PAWX resolves its arguments here,
+ * so arrays, strings, Float64Array and immutable lists agree:
+ *   - negative start / end count from the end:   arr.slice(-3)
+ *   - out-of-range positions are clamped, never an error
+ *   - step picks every n-th element:             arr.slice(0, 10, 2)
+ *   - a negative step walks backwards:           arr.slice(null, null, -1)
+ *   - `null` (or a missing argument) means "from the edge"
+ *   - a step of 0 is an error
+ *
+ * The rules are Python's: with a negative step, start defaults to the last
+ * element and end to "before the first", so `slice(null, null, -1)`
+ * reverses.
+ *
+ * --------------------------------------------------------------------------
+ * Author:   Sam Wilcox
+ * Email:    sam@pawx-lang.com
+ * Website:  https://www.pawx-lang.com
+ * GitHub:   https://github.com/samwilcox/pawx
+ *
+ * License:
+ * This file is part of the PAWX programming language project.
+ *
+ * PAWX is dual-licensed under the terms of:
+ *   - The MIT License
+ *   - The Apache License, Version 2.0
+ *
+ * You may choose either license to govern your use of this software.
+ * Full license text available at:
+ *     https://license.pawx-lang.com
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under these licenses is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *
+ * ==========================================================================
+ */
+
+use crate::error::PawxError;
+use crate::interpreter::calls::raise_error;
+use crate::value::Value;
+
+/// The positions selected by `slice(start?, end?, step?)` over `len`
+/// elements, in the order they are taken.
+///
+/// `args` holds the slice arguments only (not the receiver).
+///
+/// # Errors
+/// If an argument is not a number or `null`, or the step is 0.
+pub fn positions(args: &[Value], len: usize, usage: &str) -> Result<Vec<usize>, PawxError> {
+    let step = match number(args.get(2), usage)? {
+        None => 1,
+        Some(0) => return raise_error(format!("{} step cannot be 0", usage)),
+        Some(step) => step,
+    };
+
+    let len = len as i64;
+    let start = number(args.first(), usage)?;
+    let end = number(args.get(1), usage)?;
+
+    if step > 0 {
+        let start = start.map_or(0, |n| resolve(n, len, 0, len));
+        let end = end.map_or(len, |n| resolve(n, len, 0, len));
+
+        Ok((start..end.max(start)).step_by(step as usize).map(|i| i as usize).collect())
+    } else {
+        // -1 stands for "before the first element"
+        let start = start.map_or(len - 1, |n| resolve(n, len, -1, len - 1));
+        let end = end.map_or(-1, |n| resolve(n, len, -1, len - 1));
+
+        let mut taken = Vec::new();
+        let mut i = start;
+        while i > end {
+            taken.push(i as usize);
+            i += step;
+        }
+        Ok(taken)
+    }
+}
+
+/// Reads an optional integer argument; `null` counts as missing.
+fn number(arg: Option<&Value>, usage: &str) -> Result<Option<i64>, PawxError> {
+    match arg {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::Number(n)) if n.is_finite() => Ok(Some(n.trunc() as i64)),
+        Some(Value::Number(n)) if n.is_nan() => raise_error(format!("{} expects numbers", usage)),
+        // ±Infinity: as far as the edge in that direction
+        Some(Value::Number(n)) => Ok(Some(if *n > 0.0 { i64::MAX / 2 } else { i64::MIN / 2 })),
+        _ => raise_error(format!("{} expects numbers", usage)),
+    }
+}
+
+/// Counts a negative position from the end, then clamps it to `lo..=hi`.
+fn resolve(n: i64, len: i64, lo: i64, hi: i64) -> i64 {
+    let n = if n < 0 { n + len } else { n };
+    n.clamp(lo, hi)
+}
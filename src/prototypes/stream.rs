@@ -0,0 +1,714 @@
+/*
+ * ==========================================================================
+ * PAWX - Code with Claws!
+ * Stream Implementation
+ * ==========================================================================
+ *
+ * This module defines the common `Stream` abstraction used by the PAWX
+ * runtime. Every stream exposes the same interface:
+ *
+ *   - stream.onData(fn) / stream.onEnd(fn) / stream.onError(fn)
+ *   - stream.write(chunk) / stream.end() / stream.error(err)
+ *   - stream.pause() / stream.resume() / stream.onDrain(fn)
+ *   - stream.pipe(dest)
+ *   - stream.map(fn) / stream.filter(fn)
+ *   - stream.toArray()
+ *
+ * Streams are push-based and run entirely on the interpreter thread.
+ *
+ * Backpressure:
+ *   - `write()` returns `false` once the internal buffer reaches the
+ *     high-water mark
+ *   - `pipe()` pauses the source when the destination is full and resumes
+ *     it when the destination drains
+ *   - Pull sources (arrays, files) are only read while the stream is
+ *     flowing, so a paused pipeline stops reading input
+ *
+ * Any object with a `write(chunk)` function (and optionally `end()`,
+ * `error(err)` and `onDrain(fn)`) can be used as a pipe destination.
+ *
+ * --------------------------------------------------------------------------
+ * Author:   Sam Wilcox
+ * Email:    sam@pawx-lang.com
+ * Website:  https://www.pawx-lang.com
+ * GitHub:   https://github.com/samwilcox/pawx
+ *
+ * License:
+ * This file is part of the PAWX programming language project.
+ *
+ * PAWX is dual-licensed under the terms of:
+ *   - The MIT License
+ *   - The Apache License, Version 2.0
+ *
+ * You may choose either license to govern your use of this software.
+ * Full license text available at:
+ *     https://license.pawx-lang.com
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under these licenses is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *
+ * ==========================================================================
+ */
+
+use std::cell::RefCell;
+use std::collections::{HashMap, VecDeque};
+use std::rc::Rc;
+use std::sync::Arc;
+
+use crate::error::PawxError;
+use crate::interpreter::calls::{builtin_error, raise_error};
+use crate::prototypes::array::create_array_proto;
+use crate::value::Value;
+
+/// Default number of buffered chunks before `write()` reports backpressure.
+pub const DEFAULT_HIGH_WATER_MARK: usize = 16;
+
+/// Pull-based chunk producer.
+///
+/// Returns `Ok(Some(chunk))` for the next chunk, `Ok(None)` when exhausted,
+/// or `Err(message)` on failure.
+pub type StreamSource = Box<dyn FnMut() -> Result<Option<Value>, String>>;
+
+/// Per-chunk transform used by `map()` / `filter()`.
+///
+/// Returning `Ok(None)` drops the chunk.
+type StreamTransform = Rc<dyn Fn(Value) -> Result<Option<Value>, PawxError>>;
+
+/* ============================================================================
+ * Stream State
+ * ============================================================================
+ */
+
+/// Internal state shared by every handle to the same stream.
+pub struct StreamState {
+    buffer: VecDeque<Value>,
+    source: Option<StreamSource>,
+    transform: Option<StreamTransform>,
+
+    data_listeners: Vec<Value>,
+    end_listeners: Vec<Value>,
+    error_listeners: Vec<Value>,
+    drain_listeners: Vec<Value>,
+
+    /// `end()` was called or the source was exhausted.
+    ending: bool,
+
+    /// The end event has been emitted.
+    ended: bool,
+
+    error: Option<Value>,
+    paused: bool,
+    flushing: bool,
+    needs_drain: bool,
+    high_water_mark: usize,
+}
+
+/// Shared handle to a stream's state.
+pub type SharedStream = Rc<RefCell<StreamState>>;
+
+impl StreamState {
+    fn new(high_water_mark: usize) -> Self {
+        Self {
+            buffer: VecDeque::new(),
+            source: None,
+            transform: None,
+            data_listeners: Vec::new(),
+            end_listeners: Vec::new(),
+            error_listeners: Vec::new(),
+            drain_listeners: Vec::new(),
+            ending: false,
+            ended: false,
+            error: None,
+            paused: false,
+            flushing: false,
+            needs_drain: false,
+            high_water_mark: high_water_mark.max(1),
+        }
+    }
+}
+
+/* ============================================================================
+ * Stream Constructors (Rust API)
+ * ============================================================================
+ */
+
+/// Creates an empty writable/readable stream.
+pub fn new_stream(high_water_mark: usize) -> SharedStream {
+    Rc::new(RefCell::new(StreamState::new(high_water_mark)))
+}
+
+/// Creates a stream that pulls chunks from `source` while flowing.
+///
+/// Used by `Stream.from(array)` and `Fs.createReadStream(path)`.
+pub fn stream_from_source(source: StreamSource) -> Value {
+    let state = new_stream(DEFAULT_HIGH_WATER_MARK);
+    state.borrow_mut().source = Some(source);
+    stream_value(state)
+}
+
+/// Creates a sink stream that hands every chunk to `sink`, then calls
+/// `finish` once the stream ends.
+///
+/// Used by `Fs.createWriteStream(path)`.
+pub fn stream_to_sink<S, F>(sink: S, finish: F) -> Value
+where
+    S: Fn(Value) -> Result<(), String> + 'static,
+    F: Fn() + 'static,
+{
+    let state = new_stream(DEFAULT_HIGH_WATER_MARK);
+
+    // Nothing is buffered yet, so the listeners can be stored directly
+    let error_target = Rc::downgrade(&state);
+    state.borrow_mut().data_listeners.push(native(move |args| {
+        let chunk = args.into_iter().next().unwrap_or(Value::Null);
+
+        if let Err(message) = sink(chunk) {
+            if let Some(state) = error_target.upgrade() {
+                emit_error(&state, Value::Error { message })?;
+            }
+        }
+
+        Ok(Value::Null)
+    }));
+
+    state.borrow_mut().end_listeners.push(native(move |_args| {
+        finish();
+        Ok(Value::Null)
+    }));
+
+    stream_value(state)
+}
+
+/* ============================================================================
+ * Core Stream Operations
+ * ============================================================================
+ */
+
+#[derive(Clone, Copy)]
+enum Listener {
+    Data,
+    End,
+    Error,
+    Drain,
+}
+
+fn native<F>(f: F) -> Value
+where
+    F: Fn(Vec<Value>) -> Result<Value, PawxError> + 'static,
+{
+    Value::NativeFunction(Arc::new(f))
+}
+
+fn call(callback: &Value, args: Vec<Value>) -> Result<Value, PawxError> {
+    match callback {
+        Value::NativeFunction(f) => f(args),
+        _ => Ok(Value::Null),
+    }
+}
+
+/// Delivers buffered and pulled chunks to listeners while the stream is
+/// flowing, then emits `drain` and `end` when appropriate.
+///
+/// # Errors
+/// The first error raised by a listener. Chunks not yet delivered stay
+/// buffered, so the stream keeps working after the caller handles it.
+fn flush(state: &SharedStream) -> Result<(), PawxError> {
+    if state.borrow().flushing {
+        return Ok(());
+    }
+
+    state.borrow_mut().flushing = true;
+
+    loop {
+        loop {
+            let next = {
+                let mut s = state.borrow_mut();
+
+                if s.paused || s.data_listeners.is_empty() || s.error.is_some() {
+                    None
+                } else if let Some(chunk) = s.buffer.pop_front() {
+                    Some(Ok(chunk))
+                } else if s.ending {
+                    None
+                } else if let Some(source) = s.source.as_mut() {
+                    match source() {
+                        Ok(Some(chunk)) => Some(Ok(chunk)),
+                        Ok(None) => {
+                            s.source = None;
+                            s.ending = true;
+                            None
+                        }
+                        Err(message) => {
+                            s.source = None;
+                            Some(Err(message))
+                        }
+                    }
+                } else {
+                    None
+                }
+            };
+
+            match next {
+                Some(Ok(chunk)) => {
+                    let listeners = state.borrow().data_listeners.clone();
+                    for listener in &listeners {
+                        if let Err(error) = call(listener, vec![chunk.clone()]) {
+                            state.borrow_mut().flushing = false;
+                            return Err(error);
+                        }
+                    }
+                }
+
+                Some(Err(message)) => {
+                    state.borrow_mut().flushing = false;
+                    return emit_error(state, Value::Error { message });
+                }
+
+                None => break,
+            }
+        }
+
+        // drain: the buffer dropped back below the high-water mark
+        let drain = {
+            let mut s = state.borrow_mut();
+            if s.needs_drain && s.buffer.len() < s.high_water_mark {
+                s.needs_drain = false;
+                std::mem::take(&mut s.drain_listeners)
+            } else {
+                Vec::new()
+            }
+        };
+
+        if drain.is_empty() {
+            break;
+        }
+
+        // Drain listeners usually resume a producer; deliver whatever it
+        // writes before finishing this flush.
+        for listener in &drain {
+            if let Err(error) = call(listener, vec![]) {
+                state.borrow_mut().flushing = false;
+                return Err(error);
+            }
+        }
+    }
+
+    // end: everything written has been consumed
+    let end = {
+        let mut s = state.borrow_mut();
+        if s.ending && !s.ended && s.buffer.is_empty() && s.error.is_none() {
+            s.ended = true;
+            std::mem::take(&mut s.end_listeners)
+        } else {
+            Vec::new()
+        }
+    };
+
+    state.borrow_mut().flushing = false;
+
+    for listener in &end {
+        call(listener, vec![])?;
+    }
+
+    Ok(())
+}
+
+/// Registers `callback` and delivers whatever is already waiting for it.
+///
+/// # Errors
+/// Whatever the listeners raise while the stream flushes.
+fn add_listener(state: &SharedStream, kind: Listener, callback: Value) -> Result<(), PawxError> {
+    match kind {
+        Listener::Data => state.borrow_mut().data_listeners.push(callback),
+
+        Listener::End => {
+            if state.borrow().ended {
+                return call(&callback, vec![]).map(|_| ());
+            }
+            state.borrow_mut().end_listeners.push(callback);
+        }
+
+        Listener::Error => {
+            let existing = state.borrow().error.clone();
+            match existing {
+                Some(err) => return call(&callback, vec![err]).map(|_| ()),
+                None => state.borrow_mut().error_listeners.push(callback),
+            }
+        }
+
+        Listener::Drain => {
+            if !state.borrow().needs_drain {
+                return call(&callback, vec![]).map(|_| ());
+            }
+            state.borrow_mut().drain_listeners.push(callback);
+        }
+    }
+
+    flush(state)
+}
+
+/// Writes a chunk into the stream.
+///
+/// # Returns
+/// - `true` if more data may be written immediately
+/// - `false` if the buffer is full (wait for `onDrain`)
+///
+/// # Errors
+/// - If the stream has already ended
+/// - Whatever the transform or the listeners raise
+pub fn write_chunk(state: &SharedStream, chunk: Value) -> Result<bool, PawxError> {
+    if state.borrow().ending {
+        return raise_error("Stream.write(): write after end");
+    }
+
+    let transform = state.borrow().transform.clone();
+    let chunk = match transform {
+        Some(transform) => transform(chunk)?,
+        None => Some(chunk),
+    };
+
+    if let Some(chunk) = chunk {
+        state.borrow_mut().buffer.push_back(chunk);
+    }
+
+    flush(state)?;
+
+    let mut s = state.borrow_mut();
+    let ok = s.buffer.len() < s.high_water_mark;
+    if !ok {
+        s.needs_drain = true;
+    }
+    Ok(ok)
+}
+
+/// Signals that no more chunks will be written.
+///
+/// # Errors
+/// Whatever the listeners raise while the stream flushes.
+pub fn end_stream(state: &SharedStream) -> Result<(), PawxError> {
+    state.borrow_mut().ending = true;
+    flush(state)
+}
+
+/// Puts the stream into the errored state and notifies error listeners.
+///
+/// Errors without any listener are reported on stderr so they are never
+/// silently lost.
+///
+/// # Errors
+/// Whatever an error listener raises.
+pub fn emit_error(state: &SharedStream, err: Value) -> Result<(), PawxError> {
+    let listeners = {
+        let mut s = state.borrow_mut();
+        if s.error.is_some() {
+            return Ok(());
+        }
+        s.error = Some(err.clone());
+        std::mem::take(&mut s.error_listeners)
+    };
+
+    if listeners.is_empty() {
+        eprintln!("Unhandled stream error: {}", err.stringify());
+    }
+
+    for listener in &listeners {
+        call(listener, vec![err.clone()])?;
+    }
+
+    Ok(())
+}
+
+/// Pipes `source` into `dest`, honouring backpressure.
+///
+/// `dest` may be any object with a `write(chunk)` function. Its optional
+/// `end()`, `error(err)` and `onDrain(fn)` members are used when present.
+fn pipe(source: &SharedStream, dest: &Value) -> Result<(), PawxError> {
+    let member = |name: &str| -> Option<Value> {
+        match dest {
+            Value::Object { fields } => match fields.borrow().get(name) {
+                Some(f @ Value::NativeFunction(_)) => Some(f.clone()),
+                _ => None,
+            },
+            _ => None,
+        }
+    };
+
+    let write = member("write")
+        .ok_or_else(|| builtin_error("Stream.pipe(dest): destination must have a write(chunk) function"))?;
+    let end = member("end");
+    let error = member("error");
+    let on_drain = member("onDrain");
+
+    let weak = Rc::downgrade(source);
+    add_listener(
+        source,
+        Listener::Data,
+        native(move |args| {
+            let chunk = args.into_iter().next().unwrap_or(Value::Null);
+
+            if let Value::Bool(false) = call(&write, vec![chunk])? {
+                if let (Some(on_drain), Some(state)) = (&on_drain, weak.upgrade()) {
+                    state.borrow_mut().paused = true;
+
+                    // The drain callback keeps the paused source alive until
+                    // the destination asks for more data.
+                    call(
+                        on_drain,
+                        vec![native(move |_args| {
+                            state.borrow_mut().paused = false;
+                            flush(&state)?;
+                            Ok(Value::Null)
+                        })],
+                    )?;
+                }
+            }
+
+            Ok(Value::Null)
+        }),
+    )?;
+
+    if let Some(end) = end {
+        add_listener(
+            source,
+            Listener::End,
+            native(move |_args| {
+                call(&end, vec![])?;
+                Ok(Value::Null)
+            }),
+        )?;
+    }
+
+    if let Some(error) = error {
+        add_listener(
+            source,
+            Listener::Error,
+            native(move |args| {
+                call(&error, args)?;
+                Ok(Value::Null)
+            }),
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Creates a transform stream fed by `source`.
+fn transformed(source: &SharedStream, transform: StreamTransform) -> Result<Value, PawxError> {
+    let out = new_stream(source.borrow().high_water_mark);
+    out.borrow_mut().transform = Some(transform);
+
+    let dest = stream_value(out);
+    pipe(source, &dest)?;
+    Ok(dest)
+}
+
+/* ============================================================================
+ * PAWX Stream Object
+ * ============================================================================
+ */
+
+/// Wraps shared stream state in a PAWX object exposing the stream API.
+pub fn stream_value(state: SharedStream) -> Value {
+    let mut fields: HashMap<String, Value> = HashMap::new();
+
+    let listener_method = |kind: Listener, name: &'static str| {
+        let state = state.clone();
+        native(move |args| {
+            match args.first() {
+                Some(cb @ Value::NativeFunction(_)) => add_listener(&state, kind, cb.clone())?,
+                _ => return raise_error(format!("Stream.{}(fn) expects a function", name)),
+            }
+            Ok(stream_value(state.clone()))
+        })
+    };
+
+    fields.insert("onData".to_string(), listener_method(Listener::Data, "onData"));
+    fields.insert("onEnd".to_string(), listener_method(Listener::End, "onEnd"));
+    fields.insert("onError".to_string(), listener_method(Listener::Error, "onError"));
+    fields.insert("onDrain".to_string(), listener_method(Listener::Drain, "onDrain"));
+
+    let s = state.clone();
+    fields.insert(
+        "write".to_string(),
+        native(move |args| {
+            let chunk = args.into_iter().next().unwrap_or(Value::Null);
+            Ok(Value::Bool(write_chunk(&s, chunk)?))
+        }),
+    );
+
+    let s = state.clone();
+    fields.insert(
+        "end".to_string(),
+        native(move |args| {
+            if let Some(chunk) = args.into_iter().next() {
+                write_chunk(&s, chunk)?;
+            }
+            end_stream(&s)?;
+            Ok(Value::Null)
+        }),
+    );
+
+    let s = state.clone();
+    fields.insert(
+        "error".to_string(),
+        native(move |args| {
+            let err = match args.into_iter().next() {
+                Some(err @ Value::Error { .. }) => err,
+                Some(other) => Value::Error { message: other.stringify() },
+                None => Value::Error { message: "Stream error".to_string() },
+            };
+            emit_error(&s, err)?;
+            Ok(Value::Null)
+        }),
+    );
+
+    let s = state.clone();
+    fields.insert(
+        "pause".to_string(),
+        native(move |_args| {
+            s.borrow_mut().paused = true;
+            Ok(stream_value(s.clone()))
+        }),
+    );
+
+    let s = state.clone();
+    fields.insert(
+        "resume".to_string(),
+        native(move |_args| {
+            s.borrow_mut().paused = false;
+            flush(&s)?;
+            Ok(stream_value(s.clone()))
+        }),
+    );
+
+    let s = state.clone();
+    fields.insert(
+        "isEnded".to_string(),
+        native(move |_args| Ok(Value::Bool(s.borrow().ended))),
+    );
+
+    let s = state.clone();
+    fields.insert(
+        "pipe".to_string(),
+        native(move |args| {
+            let dest = args
+                .into_iter()
+                .next()
+                .ok_or_else(|| builtin_error("Stream.pipe(dest) requires a destination"))?;
+            pipe(&s, &dest)?;
+            Ok(dest)
+        }),
+    );
+
+    let s = state.clone();
+    fields.insert(
+        "map".to_string(),
+        native(move |args| {
+            let f = match args.into_iter().next() {
+                Some(f @ Value::NativeFunction(_)) => f,
+                _ => return raise_error("Stream.map(fn) expects a function"),
+            };
+            transformed(&s, Rc::new(move |chunk| Ok(Some(call(&f, vec![chunk])?))))
+        }),
+    );
+
+    let s = state.clone();
+    fields.insert(
+        "filter".to_string(),
+        native(move |args| {
+            let f = match args.into_iter().next() {
+                Some(f @ Value::NativeFunction(_)) => f,
+                _ => return raise_error("Stream.filter(fn) expects a function"),
+            };
+            transformed(
+                &s,
+                Rc::new(move |chunk| {
+                    if call(&f, vec![chunk.clone()])?.is_truthy() {
+                        Ok(Some(chunk))
+                    } else {
+                        Ok(None)
+                    }
+                }),
+            )
+        }),
+    );
+
+    let s = state.clone();
+    fields.insert(
+        "toArray".to_string(),
+        native(move |_args| {
+            let collected = Rc::new(RefCell::new(Vec::new()));
+            let sink = collected.clone();
+
+            add_listener(
+                &s,
+                Listener::Data,
+                native(move |args| {
+                    sink.borrow_mut().extend(args.into_iter().take(1));
+                    Ok(Value::Null)
+                }),
+            )?;
+
+            Ok(Value::Furure(Box::new(Value::Array {
+                values: collected,
+                proto: create_array_proto(),
+            })))
+        }),
+    );
+
+    Value::Object {
+        fields: Rc::new(RefCell::new(fields)),
+    }
+}
+
+/* ============================================================================
+ * Global `Stream` Object
+ * ============================================================================
+ */
+
+/// Creates and returns the global `Stream` namespace.
+///
+/// # Installed Functions
+/// - `Stream.create(highWaterMark?)` → empty writable/readable stream
+/// - `Stream.from(array)`            → stream emitting each element
+///
+/// # PAWX Example
+/// ```pawx
+/// Stream.from([1, 2, 3, 4])
+///     .filter(n -> n % 2 == 0)
+///     .map(n -> n * 10)
+///     .onData(n -> { meow(n) });
+/// ```
+pub fn create_global_stream_object() -> HashMap<String, Value> {
+    let mut stream = HashMap::new();
+
+    stream.insert("create".to_string(), native(stream_create));
+    stream.insert("from".to_string(), native(stream_from));
+
+    stream
+}
+
+/// Native implementation of `Stream.create(highWaterMark?)`.
+pub fn stream_create(args: Vec<Value>) -> Result<Value, PawxError> {
+    let hwm = match args.first() {
+        Some(Value::Number(n)) if *n >= 1.0 => *n as usize,
+        Some(Value::Null) | None => DEFAULT_HIGH_WATER_MARK,
+        _ => return raise_error("Stream.create(highWaterMark?) expects a positive number"),
+    };
+
+    Ok(stream_value(new_stream(hwm)))
+}
+
+/// Native implementation of `Stream.from(array)`.
+///
+/// Elements are pulled lazily while the stream is flowing.
+pub fn stream_from(args: Vec<Value>) -> Result<Value, PawxError> {
+    let items = match args.first() {
+        Some(Value::Array { values, .. }) => values.borrow().clone(),
+        _ => return raise_error("Stream.from(array) expects an array"),
+    };
+
+    let mut items = items.into_iter();
+    Ok(stream_from_source(Box::new(move || Ok(items.next()))))
+}
@@ -17,6 +17,34 @@
  * These functions are installed once onto the global `String` namespace
  * and are shared across all PAWX programs.
  *
+ * String values also have instance methods, looked up by `string_property`:
+ *   - "a,b".split(","), .trim(), .trimStart(), .trimEnd()
+ *   - .toUpperCase(), .toLowerCase()
+ *   - .replace(find, with), .replaceAll(find, with)   (find: string or regex)
+ *   - .startsWith(s), .endsWith(s), .includes(s)
+ *   - .indexOf(s, from?), .lastIndexOf(s)
+ *   - .slice(start?, end?, step?), .padStart(n, fill?), .padEnd(n, fill?)
+ *   - .repeat(n), .length
+ *   - .bytes(), .graphemes()
+ *   - .equalsIgnoreCase(s), .compareNatural(s)
+ *   - str[i]                                           (see expressions.rs)
+ *
+ * A string is a sequence of characters (Unicode scalar values): `str[i]`,
+ * `length`, `String.len`, positions and `slice` all count characters,
+ * never bytes, so none of them can split a character in two. `slice`
+ * follows the same rules as arrays (negative positions, optional step).
+ *
+ * Two other views are explicit:
+ *   - `bytes()` is the UTF-8 encoding, as an array of numbers 0-255
+ *   - `graphemes()` is what a reader sees as one character, as an array of
+ *     strings: "e" plus a combining accent, a flag, or an emoji built from
+ *     several code points with joiners and skin tones each stay whole
+ *
+ *   "né".length;           // 2
+ *   "né".bytes().length;   // 3
+ *   "👍🏽".length;           // 2 (thumbs up + skin tone)
+ *   "👍🏽".graphemes();      // ["👍🏽"]
+ *
  * --------------------------------------------------------------------------
  * Author:   Sam Wilcox
  * Email:    sam@pawx-lang.com
@@ -41,11 +69,20 @@
  * ==========================================================================
  */
 
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::cmp::Ordering;
 use std::collections::HashMap;
+use std::rc::Rc;
 use std::sync::Arc;
 
+use regex::Regex;
+
+use crate::error::PawxError;
+use crate::interpreter::calls::raise_error;
 use crate::value::Value;
 use crate::prototypes::array::create_array_proto;
+use crate::prototypes::slice;
 
 
 /// Creates and returns the global `String` namespace for the PAWX runtime.
@@ -87,10 +124,10 @@ pub fn create_global_string_object() -> HashMap<String, Value> {
 /// ```pawx
 /// let n = String.len("hello"); // 5
 /// ```
-pub fn string_len(args: Vec<Value>) -> Value {
+pub fn string_len(args: Vec<Value>) -> Result<Value, PawxError> {
     match args.get(0) {
-        Some(Value::String(s)) => Value::Number(s.len() as f64),
-        _ => panic!("String.len(str) expects a string"),
+        Some(Value::String(s)) => Ok(Value::Number(s.chars().count() as f64)),
+        _ => raise_error("String.len(str) expects a string"),
     }
 }
 
@@ -106,10 +143,10 @@ pub fn string_len(args: Vec<Value>) -> Value {
 /// ```pawx
 /// let s = String.upper("pawx"); // "PAWX"
 /// ```
-pub fn string_upper(args: Vec<Value>) -> Value {
+pub fn string_upper(args: Vec<Value>) -> Result<Value, PawxError> {
     match args.get(0) {
-        Some(Value::String(s)) => Value::String(s.to_uppercase()),
-        _ => panic!("String.upper(str) expects a string"),
+        Some(Value::String(s)) => Ok(Value::String(s.to_uppercase().into())),
+        _ => raise_error("String.upper(str) expects a string"),
     }
 }
 
@@ -125,10 +162,10 @@ pub fn string_upper(args: Vec<Value>) -> Value {
 /// ```pawx
 /// let s = String.lower("PAWX"); // "pawx"
 /// ```
-pub fn string_lower(args: Vec<Value>) -> Value {
+pub fn string_lower(args: Vec<Value>) -> Result<Value, PawxError> {
     match args.get(0) {
-        Some(Value::String(s)) => Value::String(s.to_lowercase()),
-        _ => panic!("String.lower(str) expects a string"),
+        Some(Value::String(s)) => Ok(Value::String(s.to_lowercase().into())),
+        _ => raise_error("String.lower(str) expects a string"),
     }
 }
 
@@ -144,10 +181,10 @@ pub fn string_lower(args: Vec<Value>) -> Value {
 /// ```pawx
 /// let s = String.trim("  hello  "); // "hello"
 /// ```
-pub fn string_trim(args: Vec<Value>) -> Value {
+pub fn string_trim(args: Vec<Value>) -> Result<Value, PawxError> {
     match args.get(0) {
-        Some(Value::String(s)) => Value::String(s.trim().to_string()),
-        _ => panic!("String.trim(str) expects a string"),
+        Some(Value::String(s)) => Ok(Value::String(s.trim().into())),
+        _ => raise_error("String.trim(str) expects a string"),
     }
 }
 
@@ -164,26 +201,26 @@ pub fn string_trim(args: Vec<Value>) -> Value {
 /// ```pawx
 /// let parts = String.split("a,b,c", ","); // ["a", "b", "c"]
 /// ```
-pub fn string_split(args: Vec<Value>) -> Value {
+pub fn string_split(args: Vec<Value>) -> Result<Value, PawxError> {
     let s = match args.get(0) {
         Some(Value::String(s)) => s.clone(),
-        _ => panic!("String.split(str, sep) expects a string"),
+        _ => return raise_error("String.split(str, sep) expects a string"),
     };
 
     let sep = match args.get(1) {
         Some(Value::String(s)) => s.clone(),
-        _ => panic!("String.split(str, sep) expects a string separator"),
+        _ => return raise_error("String.split(str, sep) expects a string separator"),
     };
 
     let parts = s
-        .split(&sep)
-        .map(|p| Value::String(p.to_string()))
+        .split(&*sep)
+        .map(|p| Value::String(p.into()))
         .collect::<Vec<_>>();
 
-    Value::Array {
+    Ok(Value::Array {
         values: std::rc::Rc::new(std::cell::RefCell::new(parts)),
         proto: create_array_proto(),
-    }
+    })
 }
 
 /// Checks whether a string contains a given substring.
@@ -201,18 +238,18 @@ pub fn string_split(args: Vec<Value>) -> Value {
 ///     meow("Found!");
 /// }
 /// ```
-pub fn string_contains(args: Vec<Value>) -> Value {
+pub fn string_contains(args: Vec<Value>) -> Result<Value, PawxError> {
     let s = match args.get(0) {
         Some(Value::String(s)) => s,
-        _ => panic!("String.contains(str, search) expects a string"),
+        _ => return raise_error("String.contains(str, search) expects a string"),
     };
 
     let search = match args.get(1) {
         Some(Value::String(s)) => s,
-        _ => panic!("String.contains(str, search) expects a string search value"),
+        _ => return raise_error("String.contains(str, search) expects a string search value"),
     };
 
-    Value::Bool(s.contains(search))
+    Ok(Value::Bool(s.contains(&**search)))
 }
 
 /// Checks whether a string starts with a given prefix.
@@ -228,18 +265,18 @@ pub fn string_contains(args: Vec<Value>) -> Value {
 /// ```pawx
 /// String.startsWith("pawx-lang", "pawx"); // true
 /// ```
-pub fn string_starts_with(args: Vec<Value>) -> Value {
+pub fn string_starts_with(args: Vec<Value>) -> Result<Value, PawxError> {
     let s = match args.get(0) {
         Some(Value::String(s)) => s,
-        _ => panic!("String.startsWith(str, prefix) expects a string"),
+        _ => return raise_error("String.startsWith(str, prefix) expects a string"),
     };
 
     let prefix = match args.get(1) {
         Some(Value::String(s)) => s,
-        _ => panic!("String.startsWith(str, prefix) expects a string"),
+        _ => return raise_error("String.startsWith(str, prefix) expects a string"),
     };
 
-    Value::Bool(s.starts_with(prefix))
+    Ok(Value::Bool(s.starts_with(&**prefix)))
 }
 
 /// Checks whether a string ends with a given suffix.
@@ -255,18 +292,18 @@ pub fn string_starts_with(args: Vec<Value>) -> Value {
 /// ```pawx
 /// String.endsWith("pawx-lang", "lang"); // true
 /// ```
-pub fn string_ends_with(args: Vec<Value>) -> Value {
+pub fn string_ends_with(args: Vec<Value>) -> Result<Value, PawxError> {
     let s = match args.get(0) {
         Some(Value::String(s)) => s,
-        _ => panic!("String.endsWith(str, suffix) expects a string"),
+        _ => return raise_error("String.endsWith(str, suffix) expects a string"),
     };
 
     let suffix = match args.get(1) {
         Some(Value::String(s)) => s,
-        _ => panic!("String.endsWith(str, suffix) expects a string"),
+        _ => return raise_error("String.endsWith(str, suffix) expects a string"),
     };
 
-    Value::Bool(s.ends_with(suffix))
+    Ok(Value::Bool(s.ends_with(&**suffix)))
 }
 
 /// Replaces all occurrences of a substring within a string.
@@ -283,23 +320,23 @@ pub fn string_ends_with(args: Vec<Value>) -> Value {
 /// ```pawx
 /// String.replace("cat-cat-cat", "cat", "paw"); // "paw-paw-paw"
 /// ```
-pub fn string_replace(args: Vec<Value>) -> Value {
+pub fn string_replace(args: Vec<Value>) -> Result<Value, PawxError> {
     let s = match args.get(0) {
         Some(Value::String(s)) => s.clone(),
-        _ => panic!("String.replace(str, find, replace) expects a string"),
+        _ => return raise_error("String.replace(str, find, replace) expects a string"),
     };
 
     let find = match args.get(1) {
         Some(Value::String(s)) => s,
-        _ => panic!("String.replace(str, find, replace) expects a string"),
+        _ => return raise_error("String.replace(str, find, replace) expects a string"),
     };
 
     let replace = match args.get(2) {
         Some(Value::String(s)) => s,
-        _ => panic!("String.replace(str, find, replace) expects a string"),
+        _ => return raise_error("String.replace(str, find, replace) expects a string"),
     };
 
-    Value::String(s.replace(find, replace))
+    Ok(Value::String(s.replace(&**find, replace).into()))
 }
 
 /// Repeats a string a specified number of times.
@@ -315,57 +352,332 @@ pub fn string_replace(args: Vec<Value>) -> Value {
 /// ```pawx
 /// String.repeat("ha", 3); // "hahaha"
 /// ```
-pub fn string_repeat(args: Vec<Value>) -> Value {
+pub fn string_repeat(args: Vec<Value>) -> Result<Value, PawxError> {
     let s = match args.get(0) {
         Some(Value::String(s)) => s.clone(),
-        _ => panic!("String.repeat(str, n) expects a string"),
+        _ => return raise_error("String.repeat(str, n) expects a string"),
     };
 
     let n = match args.get(1) {
         Some(Value::Number(n)) => *n as usize,
-        _ => panic!("String.repeat(str, n) expects a number"),
+        _ => return raise_error("String.repeat(str, n) expects a number"),
     };
 
-    Value::String(s.repeat(n))
+    Ok(Value::String(s.repeat(n).into()))
 }
 
-pub fn string_match(args: Vec<Value>) -> Value {
+pub fn string_match(args: Vec<Value>) -> Result<Value, PawxError> {
     let s = match args.get(0) {
         Some(Value::String(s)) => s,
-        _ => panic!("String.match(str, regex) expects a string"),
+        _ => return raise_error("String.match(str, regex) expects a string"),
     };
 
     let regex = match args.get(1) {
         Some(Value::Regex(r)) => r,
-        _ => panic!("String.match(str, regex) expects a regex"),
+        _ => return raise_error("String.match(str, regex) expects a regex"),
     };
 
     let matches = regex
         .find_iter(s)
-        .map(|m| Value::String(m.as_str().to_string()))
+        .map(|m| Value::String(m.as_str().into()))
         .collect::<Vec<_>>();
 
-    Value::Array {
+    Ok(Value::Array {
         values: std::rc::Rc::new(std::cell::RefCell::new(matches)),
         proto: create_array_proto(),
-    }
+    })
 }
 
-pub fn string_replace_regex(args: Vec<Value>) -> Value {
+pub fn string_replace_regex(args: Vec<Value>) -> Result<Value, PawxError> {
     let s = match args.get(0) {
         Some(Value::String(s)) => s.clone(),
-        _ => panic!("String.replaceRegex(str, regex, replace) expects a string"),
+        _ => return raise_error("String.replaceRegex(str, regex, replace) expects a string"),
     };
 
     let regex = match args.get(1) {
         Some(Value::Regex(r)) => r,
-        _ => panic!("String.replaceRegex(str, regex, replace) expects a regex"),
+        _ => return raise_error("String.replaceRegex(str, regex, replace) expects a regex"),
     };
 
     let replace = match args.get(2) {
         Some(Value::String(s)) => s,
-        _ => panic!("String.replaceRegex(str, regex, replace) expects a string"),
+        _ => return raise_error("String.replaceRegex(str, regex, replace) expects a string"),
+    };
+
+    Ok(Value::String(regex.replace_all(&s, &**replace).into()))
+}
+
+/* ==========================================================================
+ * STRING INSTANCE METHODS
+ * ==========================================================================
+ */
+
+fn method(f: impl Fn(Vec<Value>) -> Result<Value, PawxError> + 'static) -> Value {
+    Value::NativeFunction(Arc::new(f))
+}
+
+fn str_arg(args: &[Value], index: usize, usage: &str) -> Result<Rc<str>, PawxError> {
+    match args.get(index) {
+        Some(Value::String(s)) => Ok(s.clone()),
+        _ => raise_error(format!("str.{} expects a string", usage)),
+    }
+}
+
+fn count_arg(args: &[Value], index: usize, usage: &str) -> Result<usize, PawxError> {
+    match args.get(index) {
+        Some(Value::Number(n)) if *n >= 0.0 => Ok(*n as usize),
+        _ => raise_error(format!("str.{} expects a non-negative number", usage)),
+    }
+}
+
+/// Turns a possibly negative character position into one within `0..=len`.
+fn clamp_index(n: f64, len: usize) -> usize {
+    if n < 0.0 {
+        len.saturating_sub((-n) as usize)
+    } else {
+        (n as usize).min(len)
+    }
+}
+
+/// The character position of the byte offset `at` in `s`.
+fn char_index(s: &str, at: usize) -> f64 {
+    s[..at].chars().count() as f64
+}
+
+/// The character at position `index` of `s` (`str[i]`), or `null` past
+/// the end.
+pub fn char_at(s: &str, index: usize) -> Value {
+    match s.chars().nth(index) {
+        Some(c) => Value::String(c.to_string().into()),
+        None => Value::Null,
+    }
+}
+
+/// Splits `s` into extended grapheme clusters (Unicode TR #29), using the
+/// regex crate's grapheme break properties.
+fn graphemes(s: &str) -> Vec<Value> {
+    thread_local! {
+        static CLUSTER: Regex = Regex::new(
+            r"(?sx)
+              \r\n
+            | \p{gcb=Prepend}*
+              (?:
+                  \p{gcb=RI}\p{gcb=RI}
+                | \p{gcb=L}*(?:\p{gcb=V}+|\p{gcb=LV}\p{gcb=V}*|\p{gcb=LVT})\p{gcb=T}*
+                | \p{gcb=L}+
+                | \p{gcb=T}+
+                | \p{Extended_Pictographic}(?:\p{gcb=Extend}*\p{gcb=ZWJ}\p{Extended_Pictographic})*
+                | [^\p{gcb=Control}\r\n]
+              )
+              [\p{gcb=Extend}\p{gcb=ZWJ}\p{gcb=SpacingMark}]*
+            | .",
+        )
+        .expect("grapheme pattern compiles");
+    }
+
+    CLUSTER.with(|re| re.find_iter(s).map(|m| Value::String(m.as_str().into())).collect())
+}
+
+fn array(values: Vec<Value>) -> Value {
+    Value::Array {
+        values: Rc::new(RefCell::new(values)),
+        proto: create_array_proto(),
+    }
+}
+
+/// `s` with case differences removed: upper then lower case, so "ß" and
+/// "ss", or "Σ" and "ς", come out the same. Unicode's own mappings are
+/// used, never the machine's locale.
+pub fn fold_case(s: &str) -> String {
+    s.to_uppercase().to_lowercase()
+}
+
+/// Orders two strings, optionally treating digit runs as numbers
+/// ("file2" before "file10") and ignoring case (see [`fold_case`]).
+pub fn compare_strings(a: &str, b: &str, natural: bool, ignore_case: bool) -> Ordering {
+    let (a, b) = if ignore_case {
+        (Cow::Owned(fold_case(a)), Cow::Owned(fold_case(b)))
+    } else {
+        (Cow::Borrowed(a), Cow::Borrowed(b))
     };
 
-    Value::String(regex.replace_all(&s, replace).to_string())
-}
\ No newline at end of file
+    if natural {
+        compare_natural(&a, &b)
+    } else {
+        a.cmp(&b)
+    }
+}
+
+/// Compares `a` and `b` character by character, except that runs of ASCII
+/// digits compare by their value. Equal values with more leading zeros
+/// sort later ("7" < "07"), so only identical strings are equal.
+fn compare_natural(a: &str, b: &str) -> Ordering {
+    let (mut x, mut y) = (a.chars().peekable(), b.chars().peekable());
+
+    loop {
+        let (c, d) = match (x.peek(), y.peek()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(c), Some(d)) => (*c, *d),
+        };
+
+        if c.is_ascii_digit() && d.is_ascii_digit() {
+            let digits = |it: &mut std::iter::Peekable<std::str::Chars>| {
+                let mut run = String::new();
+                while let Some(c) = it.next_if(char::is_ascii_digit) {
+                    run.push(c);
+                }
+                run
+            };
+            let (m, n) = (digits(&mut x), digits(&mut y));
+            let (m_value, n_value) = (m.trim_start_matches('0'), n.trim_start_matches('0'));
+
+            let order = m_value
+                .len()
+                .cmp(&n_value.len())
+                .then_with(|| m_value.cmp(n_value))
+                .then_with(|| m.len().cmp(&n.len()));
+            if order != Ordering::Equal {
+                return order;
+            }
+        } else {
+            if c != d {
+                return c.cmp(&d);
+            }
+            x.next();
+            y.next();
+        }
+    }
+}
+
+/// Pads `s` to `width` characters with repetitions of `fill`.
+fn pad(s: &str, args: &[Value], usage: &str, at_start: bool) -> Result<Value, PawxError> {
+    let width = count_arg(args, 0, usage)?;
+    let fill = match args.get(1) {
+        Some(Value::String(f)) => f.clone(),
+        None => " ".into(),
+        _ => return raise_error(format!("str.{} expects a string fill", usage)),
+    };
+
+    let missing = width.saturating_sub(s.chars().count());
+    if missing == 0 || fill.is_empty() {
+        return Ok(Value::String(s.into()));
+    }
+
+    let padding: String = fill.chars().cycle().take(missing).collect();
+    let padded = if at_start { padding + s } else { format!("{}{}", s, padding) };
+    Ok(Value::String(padded.into()))
+}
+
+/// Replaces the first (or every) `find` in `s`; a regex `find` is handed to
+/// the regex's own `replace`, which follows its `g` flag.
+fn replace(s: &Rc<str>, args: &[Value], all: bool) -> Result<Value, PawxError> {
+    let usage = if all { "replaceAll(find, with)" } else { "replace(find, with)" };
+
+    match args.first() {
+        Some(Value::Regex(re)) => {
+            let name = if all { "replaceAll" } else { "replace" };
+            match crate::prototypes::regex::regex_property(re, name)? {
+                Value::NativeFunction(f) => f(vec![Value::String(s.clone()), args.get(1).cloned().unwrap_or(Value::Null)]),
+                _ => unreachable!("regex replace is a method"),
+            }
+        }
+        Some(Value::String(find)) => {
+            let with = str_arg(args, 1, usage)?;
+            let replaced = if all { s.replace(&**find, &with) } else { s.replacen(&**find, &with, 1) };
+            Ok(Value::String(replaced.into()))
+        }
+        _ => raise_error(format!("str.{} expects a string or regex to find", usage)),
+    }
+}
+
+/// Looks up `name` on a string value: `length`, or a method bound to `s`.
+///
+/// Returns `None` for names strings do not have.
+pub fn string_property(s: &Rc<str>, name: &str) -> Option<Value> {
+    let s = s.clone();
+
+    let value = match name {
+        "length" => Value::Number(s.chars().count() as f64),
+
+        "split" => method(move |args| {
+            let parts: Vec<Value> = match args.first() {
+                Some(Value::Regex(re)) => re.split(&s).map(|p| Value::String(p.into())).collect(),
+                Some(Value::String(sep)) if sep.is_empty() => {
+                    s.chars().map(|c| Value::String(c.to_string().into())).collect()
+                }
+                Some(Value::String(sep)) => s.split(&**sep).map(|p| Value::String(p.into())).collect(),
+                None => vec![Value::String(s.clone())],
+                _ => return raise_error("str.split(sep) expects a string or regex separator"),
+            };
+
+            Ok(array(parts))
+        }),
+
+        "bytes" => method(move |_| Ok(array(s.bytes().map(|b| Value::Number(b as f64)).collect()))),
+        "graphemes" => method(move |_| Ok(array(graphemes(&s)))),
+
+        "trim" => method(move |_| Ok(Value::String(s.trim().into()))),
+        "trimStart" => method(move |_| Ok(Value::String(s.trim_start().into()))),
+        "trimEnd" => method(move |_| Ok(Value::String(s.trim_end().into()))),
+        "toUpperCase" => method(move |_| Ok(Value::String(s.to_uppercase().into()))),
+        "toLowerCase" => method(move |_| Ok(Value::String(s.to_lowercase().into()))),
+
+        "replace" => method(move |args| replace(&s, &args, false)),
+        "replaceAll" => method(move |args| replace(&s, &args, true)),
+
+        "startsWith" => method(move |args| Ok(Value::Bool(s.starts_with(&*str_arg(&args, 0, "startsWith(prefix)")?)))),
+        "endsWith" => method(move |args| Ok(Value::Bool(s.ends_with(&*str_arg(&args, 0, "endsWith(suffix)")?)))),
+        "includes" => method(move |args| Ok(Value::Bool(s.contains(&*str_arg(&args, 0, "includes(search)")?)))),
+
+        "indexOf" => method(move |args| {
+            let search = str_arg(&args, 0, "indexOf(search, from?)")?;
+            let from = match args.get(1) {
+                Some(Value::Number(n)) => clamp_index(*n, s.chars().count()),
+                _ => 0,
+            };
+
+            let start = s.char_indices().nth(from).map_or(s.len(), |(i, _)| i);
+            match s[start..].find(&*search) {
+                Some(at) => Ok(Value::Number(char_index(&s, start + at))),
+                None => Ok(Value::Number(-1.0)),
+            }
+        }),
+
+        "lastIndexOf" => method(move |args| {
+            let search = str_arg(&args, 0, "lastIndexOf(search)")?;
+            match s.rfind(&*search) {
+                Some(at) => Ok(Value::Number(char_index(&s, at))),
+                None => Ok(Value::Number(-1.0)),
+            }
+        }),
+
+        "slice" => method(move |args| {
+            let chars: Vec<char> = s.chars().collect();
+            let sliced: String = slice::positions(&args, chars.len(), "str.slice(start?, end?, step?)")?
+                .into_iter()
+                .map(|i| chars[i])
+                .collect();
+            Ok(Value::String(sliced.into()))
+        }),
+
+        "padStart" => method(move |args| pad(&s, &args, "padStart(length, fill?)", true)),
+        "padEnd" => method(move |args| pad(&s, &args, "padEnd(length, fill?)", false)),
+
+        "repeat" => method(move |args| Ok(Value::String(s.repeat(count_arg(&args, 0, "repeat(n)")?).into()))),
+
+        "equalsIgnoreCase" => method(move |args| {
+            let other = str_arg(&args, 0, "equalsIgnoreCase(other)")?;
+            Ok(Value::Bool(compare_strings(&s, &other, false, true) == Ordering::Equal))
+        }),
+        "compareNatural" => method(move |args| {
+            let other = str_arg(&args, 0, "compareNatural(other)")?;
+            Ok(Value::Number(compare_strings(&s, &other, true, false) as i8 as f64))
+        }),
+
+        _ => return None,
+    };
+
+    Some(value)
+}
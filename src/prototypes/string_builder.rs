@@ -0,0 +1,168 @@
+/*
+ * ============================================================================
+ * PAWX - Code with Claws! 🐾
+ * ============================================================================
+ *
+ * String Builder for PAWX (`StringBuilder`)
+ *
+ * Supports:
+ *   - StringBuilder(initial?)      a new, growable buffer
+ *   - sb.append(x, ...)            appends each argument; returns sb
+ *   - sb.appendLine(x?)            appends x (if given) and a newline
+ *   - sb.length                    characters written so far
+ *   - sb.toString()                the text built
+ *   - sb.clear()                   empties the buffer; returns sb
+ *
+ * Strings are immutable, so `s = s + piece` copies everything built so
+ * far on every pass of a loop; building n pieces that way costs O(n²).
+ * A builder appends in place to one Rust `String` that grows as needed,
+ * so the same loop is linear:
+ *
+ *   snuggle sb = StringBuilder();
+ *   snuggle i = 0;
+ *   while (i < 1000) {
+ *       sb.append("row ", i).appendLine();
+ *       i = i + 1;
+ *   }
+ *   meow(sb.toString());
+ *
+ * Anything that is not a string is appended as it would print.
+ *
+ * --------------------------------------------------------------------------
+ * Author:   Sam Wilcox
+ * Email:    sam@pawx-lang.com
+ * Website:  https://www.pawx-lang.com
+ * GitHub:   https://github.com/samwilcox/pawx
+ *
+ * License:
+ * This file is part of the PAWX programming language project.
+ *
+ * PAWX is dual-licensed under the terms of:
+ *   - The MIT License
+ *   - The Apache License, Version 2.0
+ *
+ * You may choose either license to govern your use of this software.
+ * Full license text available at:
+ *     https://license.pawx-lang.com
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under these licenses is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *
+ * ==========================================================================
+ */
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::{Rc, Weak};
+use std::sync::Arc;
+
+use crate::error::PawxError;
+use crate::value::Value;
+
+type Fields = RefCell<HashMap<String, Value>>;
+
+fn native(f: impl Fn(Vec<Value>) -> Result<Value, PawxError> + 'static) -> Value {
+    Value::NativeFunction(Arc::new(f))
+}
+
+/// The text of a builder and its length in characters.
+#[derive(Default)]
+struct Buffer {
+    text: String,
+    chars: usize,
+}
+
+impl Buffer {
+    fn push(&mut self, value: &Value) {
+        match value {
+            Value::String(s) => self.push_str(s),
+            other => self.push_str(&other.to_pawx_string()),
+        }
+    }
+
+    fn push_str(&mut self, s: &str) {
+        self.text.push_str(s);
+        self.chars += s.chars().count();
+    }
+}
+
+/// The builder object again, with `length` brought up to date.
+///
+/// Methods hold the object's fields weakly: they live inside those fields,
+/// so a strong reference would keep every builder alive forever.
+fn refresh(fields: &Weak<Fields>, buffer: &RefCell<Buffer>) -> Value {
+    match fields.upgrade() {
+        Some(fields) => {
+            let length = Value::Number(buffer.borrow().chars as f64);
+            fields.borrow_mut().insert("length".to_string(), length);
+            Value::Object { fields }
+        }
+        None => Value::Null,
+    }
+}
+
+/// Builds the object for a new builder holding `initial`.
+fn string_builder(initial: Option<&Value>) -> Value {
+    let buffer = Rc::new(RefCell::new(Buffer::default()));
+    if let Some(value) = initial.filter(|v| !matches!(v, Value::Null)) {
+        buffer.borrow_mut().push(value);
+    }
+
+    let fields: Rc<Fields> = Rc::new(RefCell::new(HashMap::new()));
+    let mut methods = HashMap::new();
+
+    let (b, f) = (buffer.clone(), Rc::downgrade(&fields));
+    methods.insert(
+        "append".to_string(),
+        native(move |args| {
+            {
+                let mut buffer = b.borrow_mut();
+                for arg in &args {
+                    buffer.push(arg);
+                }
+            }
+            Ok(refresh(&f, &b))
+        }),
+    );
+
+    let (b, f) = (buffer.clone(), Rc::downgrade(&fields));
+    methods.insert(
+        "appendLine".to_string(),
+        native(move |args| {
+            {
+                let mut buffer = b.borrow_mut();
+                if let Some(value) = args.first() {
+                    buffer.push(value);
+                }
+                buffer.push_str("\n");
+            }
+            Ok(refresh(&f, &b))
+        }),
+    );
+
+    let (b, f) = (buffer.clone(), Rc::downgrade(&fields));
+    methods.insert(
+        "clear".to_string(),
+        native(move |_| {
+            *b.borrow_mut() = Buffer::default();
+            Ok(refresh(&f, &b))
+        }),
+    );
+
+    let b = buffer.clone();
+    methods.insert(
+        "toString".to_string(),
+        native(move |_| Ok(Value::String(b.borrow().text.as_str().into()))),
+    );
+
+    methods.insert("length".to_string(), Value::Number(buffer.borrow().chars as f64));
+    *fields.borrow_mut() = methods;
+
+    Value::Object { fields }
+}
+
+/// Creates the global `StringBuilder(initial?)` constructor.
+pub fn create_global_string_builder_value() -> Value {
+    native(|args| Ok(string_builder(args.first())))
+}
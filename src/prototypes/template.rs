@@ -0,0 +1,332 @@
+/*
+ * ============================================================================
+ * PAWX - Code with Claws! 🐾
+ * ============================================================================
+ *
+ * Text Templates for PAWX (`Template`)
+ *
+ * Supports:
+ *   - Template.render(template, data)   -> string
+ *   - Template.compile(template)        -> data -> string
+ *
+ * Syntax (mustache style):
+ *
+ *   {{ page.title }}                 value, HTML-escaped
+ *   {{{ content }}}                  value, inserted as-is
+ *   {{#if draft}} ... {{else}} ... {{/if}}
+ *   {{#each posts}} {{ title }} {{@index}} {{/each}}
+ *
+ * Names are dotted paths into `data`; array elements are reached with a
+ * number (`items.0`). Inside `each`, names are looked up on the current
+ * item first, then outward; `this` is the item itself and `@index` its
+ * position. Missing values render as nothing.
+ *
+ * --------------------------------------------------------------------------
+ * Author:   Sam Wilcox
+ * Email:    sam@pawx-lang.com
+ * Website:  https://www.pawx-lang.com
+ * GitHub:   https://github.com/samwilcox/pawx
+ *
+ * License:
+ * This file is part of the PAWX programming language project.
+ *
+ * PAWX is dual-licensed under the terms of:
+ *   - The MIT License
+ *   - The Apache License, Version 2.0
+ *
+ * You may choose either license to govern your use of this software.
+ * Full license text available at:
+ *     https://license.pawx-lang.com
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under these licenses is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *
+ * ==========================================================================
+ */
+
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use crate::error::PawxError;
+use crate::interpreter::calls::{builtin_error, raise_error};
+use crate::interpreter::display::value_to_string;
+use crate::value::Value;
+
+fn native(f: impl Fn(Vec<Value>) -> Result<Value, PawxError> + 'static) -> Value {
+    Value::NativeFunction(Arc::new(f))
+}
+
+/* ============================================================================
+ * PARSING
+ * ============================================================================
+ */
+
+/// A parsed template.
+#[derive(Debug)]
+pub enum Node {
+    Text(String),
+    Value { path: String, raw: bool },
+    If { path: String, then: Vec<Node>, otherwise: Vec<Node> },
+    Each { path: String, body: Vec<Node> },
+}
+
+enum Tag {
+    Value { path: String, raw: bool },
+    Open { kind: String, path: String },
+    Else,
+    Close(String),
+}
+
+/// Splits a template into text and tags.
+fn lex(template: &str) -> Result<Vec<Result<String, Tag>>, String> {
+    let mut parts = Vec::new();
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        if start > 0 {
+            parts.push(Ok(rest[..start].to_string()));
+        }
+
+        let raw = rest[start..].starts_with("{{{");
+        let (open, close) = if raw { ("{{{", "}}}") } else { ("{{", "}}") };
+        let inner_start = start + open.len();
+
+        let end = rest[inner_start..]
+            .find(close)
+            .ok_or_else(|| format!("unclosed '{}'", open))?;
+        let inner = rest[inner_start..inner_start + end].trim();
+        rest = &rest[inner_start + end + close.len()..];
+
+        let tag = if let Some(open) = inner.strip_prefix('#') {
+            let (kind, path) = open.split_once(char::is_whitespace).unwrap_or((open, ""));
+            Tag::Open {
+                kind: kind.to_string(),
+                path: path.trim().to_string(),
+            }
+        } else if let Some(kind) = inner.strip_prefix('/') {
+            Tag::Close(kind.trim().to_string())
+        } else if inner == "else" {
+            Tag::Else
+        } else {
+            Tag::Value {
+                path: inner.to_string(),
+                raw,
+            }
+        };
+
+        parts.push(Err(tag));
+    }
+
+    if !rest.is_empty() {
+        parts.push(Ok(rest.to_string()));
+    }
+
+    Ok(parts)
+}
+
+/// Builds nodes until the closing tag of `block` (or the end for `None`).
+///
+/// Returns the nodes and, for `if` blocks, the nodes after `{{else}}`.
+fn build(
+    parts: &mut std::vec::IntoIter<Result<String, Tag>>,
+    block: Option<&str>,
+) -> Result<(Vec<Node>, Vec<Node>), String> {
+    let mut nodes = Vec::new();
+    let mut otherwise = Vec::new();
+    let mut in_else = false;
+
+    while let Some(part) = parts.next() {
+        let target = if in_else { &mut otherwise } else { &mut nodes };
+
+        match part {
+            Ok(text) => target.push(Node::Text(text)),
+            Err(Tag::Value { path, raw }) => target.push(Node::Value { path, raw }),
+
+            Err(Tag::Open { kind, path }) => {
+                let (body, alt) = build(parts, Some(&kind))?;
+                target.push(match kind.as_str() {
+                    "if" => Node::If { path, then: body, otherwise: alt },
+                    "each" => Node::Each { path, body },
+                    other => return Err(format!("unknown block '#{}'", other)),
+                });
+            }
+
+            Err(Tag::Else) => match block {
+                Some("if") if !in_else => in_else = true,
+                _ => return Err("'{{else}}' outside of '{{#if}}'".to_string()),
+            },
+
+            Err(Tag::Close(kind)) => {
+                return match block {
+                    Some(open) if open == kind => Ok((nodes, otherwise)),
+                    Some(open) => Err(format!("'{{{{/{}}}}}' closes '{{{{#{}}}}}'", kind, open)),
+                    None => Err(format!("unexpected '{{{{/{}}}}}'", kind)),
+                };
+            }
+        }
+    }
+
+    match block {
+        Some(open) => Err(format!("missing '{{{{/{}}}}}'", open)),
+        None => Ok((nodes, otherwise)),
+    }
+}
+
+/// Parses a template.
+///
+/// # Errors
+/// On unclosed tags or mismatched blocks.
+pub fn parse(template: &str) -> Result<Vec<Node>, String> {
+    let mut parts = lex(template)?.into_iter();
+    build(&mut parts, None).map(|(nodes, _)| nodes)
+}
+
+/* ============================================================================
+ * RENDERING
+ * ============================================================================
+ */
+
+fn escape_html(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+fn field(value: &Value, key: &str) -> Option<Value> {
+    match value {
+        Value::Object { fields } | Value::Instance { fields, .. } => fields.borrow().get(key).cloned(),
+        Value::Array { values, .. } => key.parse::<usize>().ok().and_then(|i| values.borrow().get(i).cloned()),
+        _ => None,
+    }
+}
+
+/// One level of lookup scope: the value plus its `@index`, if any.
+struct Scope {
+    value: Value,
+    index: Option<usize>,
+}
+
+fn lookup(scopes: &[Scope], path: &str) -> Option<Value> {
+    let innermost = scopes.last()?;
+
+    match path {
+        "this" | "." => return Some(innermost.value.clone()),
+        "@index" => return innermost.index.map(|i| Value::Number(i as f64)),
+        _ => {}
+    }
+
+    let mut keys = path.split('.');
+    let first = keys.next()?;
+    let first = match first {
+        "this" => innermost.value.clone(),
+        _ => scopes.iter().rev().find_map(|s| field(&s.value, first))?,
+    };
+
+    keys.try_fold(first, |value, key| field(&value, key))
+}
+
+fn render_nodes(nodes: &[Node], scopes: &mut Vec<Scope>, out: &mut String) {
+    for node in nodes {
+        match node {
+            Node::Text(text) => out.push_str(text),
+
+            Node::Value { path, raw } => match lookup(scopes, path) {
+                None | Some(Value::Null) => {}
+                Some(Value::String(s)) if *raw => out.push_str(&s),
+                Some(Value::String(s)) => out.push_str(&escape_html(&s)),
+                Some(v) if *raw => out.push_str(&value_to_string(&v)),
+                Some(v) => out.push_str(&escape_html(&value_to_string(&v))),
+            },
+
+            Node::If { path, then, otherwise } => {
+                let truthy = match lookup(scopes, path) {
+                    Some(Value::Array { values, .. }) => !values.borrow().is_empty(),
+                    Some(Value::String(s)) => !s.is_empty(),
+                    Some(v) => v.is_truthy(),
+                    None => false,
+                };
+                render_nodes(if truthy { then } else { otherwise }, scopes, out);
+            }
+
+            Node::Each { path, body } => {
+                let items: Vec<Value> = match lookup(scopes, path) {
+                    Some(Value::Array { values, .. }) => values.borrow().clone(),
+                    Some(Value::Object { fields }) => fields.borrow().values().cloned().collect(),
+                    _ => Vec::new(),
+                };
+
+                for (index, item) in items.into_iter().enumerate() {
+                    scopes.push(Scope { value: item, index: Some(index) });
+                    render_nodes(body, scopes, out);
+                    scopes.pop();
+                }
+            }
+        }
+    }
+}
+
+/// Renders parsed nodes against `data`.
+pub fn render_parsed(nodes: &[Node], data: &Value) -> String {
+    let mut out = String::new();
+    let mut scopes = vec![Scope { value: data.clone(), index: None }];
+    render_nodes(nodes, &mut scopes, &mut out);
+    out
+}
+
+/// Parses and renders a template in one step.
+///
+/// # Errors
+/// If the template does not parse.
+pub fn render(template: &str, data: &Value) -> Result<String, String> {
+    Ok(render_parsed(&parse(template)?, data))
+}
+
+/* ============================================================================
+ * GLOBAL OBJECT
+ * ============================================================================
+ */
+
+fn template_arg(args: &[Value], usage: &str) -> Result<Vec<Node>, PawxError> {
+    match args.first() {
+        Some(Value::String(t)) => Ok(parse(t).map_err(|e| builtin_error(format!("{}: {}", usage, e)))?),
+        _ => raise_error(format!("{} expects a template string", usage)),
+    }
+}
+
+/// Builds the global `Template` object.
+pub fn create_global_template_object() -> HashMap<String, Value> {
+    let mut template = HashMap::new();
+
+    template.insert(
+        "render".to_string(),
+        native(|args| {
+            let nodes = template_arg(&args, "Template.render(template, data)")?;
+            let data = args.get(1).cloned().unwrap_or(Value::Null);
+            Ok(Value::String(render_parsed(&nodes, &data).into()))
+        }),
+    );
+
+    template.insert(
+        "compile".to_string(),
+        native(|args| {
+            let nodes = Rc::new(template_arg(&args, "Template.compile(template)")?);
+            Ok(native(move |args| {
+                let data = args.first().cloned().unwrap_or(Value::Null);
+                Ok(Value::String(render_parsed(&nodes, &data).into()))
+            }))
+        }),
+    );
+
+    template
+}
@@ -11,6 +11,8 @@
  *   - Current timestamps (local & UTC)
  *   - Time formatting
  *   - Timezone offsets
+ *   - Dates in IANA time zones, with DST-aware arithmetic (`Date.in`,
+ *     backed by the system tz database, see tz.rs)
  *   - Sleep / blocking delays
  * 
  * These functions are installed once onto the global `Time` namespace
@@ -45,10 +47,19 @@ use std::sync::Arc;
 use std::cell::RefCell;
 use std::rc::Rc;
 
-use chrono::{Local, Utc};
+use chrono::{Datelike, DateTime, FixedOffset, Local, Months, NaiveDate, NaiveDateTime, SecondsFormat, Timelike, Utc};
 
+use crate::error::PawxError;
+use crate::interpreter::calls::{builtin_error, raise_error};
+use crate::interpreter::clock::VirtualClock;
+use crate::prototypes::duration;
+use crate::prototypes::tz::{self, Zone};
 use crate::value::Value;
 
+fn native(f: impl Fn(Vec<Value>) -> Result<Value, PawxError> + 'static) -> Value {
+    Value::NativeFunction(Arc::new(f))
+}
+
 /// Creates and returns the global `Time` namespace for the PAWX runtime.
 ///
 /// This function installs JavaScript-style static time utilities:
@@ -57,6 +68,7 @@ use crate::value::Value;
 /// - `Time.local()`
 /// - `Time.format(fmt)`
 /// - `Time.tzOffset()`
+/// - `Time.in(zone, when?)`
 /// - `Time.sleep(ms)`
 ///
 /// These functions are globally available and do **not** rely on
@@ -100,6 +112,13 @@ pub fn create_global_time_object() -> HashMap<String, Value> {
         Value::NativeFunction(Arc::new(time_tzOffset)),
     );
 
+    time.insert(
+        "in".to_string(),
+        Value::NativeFunction(Arc::new(|args: Vec<Value>| -> Result<Value, PawxError> {
+            time_in(&args, Utc::now().timestamp_millis())
+        })),
+    );
+
     // ---------------------------------------------------------------------
     // Blocking Utilities
     // ---------------------------------------------------------------------
@@ -127,6 +146,52 @@ pub fn create_global_time_value() -> Value {
     }
 }
 
+/// Creates the global `Time` object bound to a **virtual clock**.
+///
+/// Used by `--virtual-time` mode. It behaves like
+/// [`create_global_time_value`], except:
+/// - `Time.now()` returns the virtual time in milliseconds
+/// - `Time.sleep(ms)` advances the virtual clock instead of blocking
+/// - `Time.in(zone)` is the virtual time in `zone`
+///
+/// # Returns
+/// A runtime `Value::Object` representing `Time`.
+pub fn create_virtual_time_value(clock: &VirtualClock) -> Value {
+    let mut time_map = create_global_time_object();
+
+    let c = clock.clone();
+    time_map.insert(
+        "now".to_string(),
+        Value::NativeFunction(Arc::new(move |_args: Vec<Value>| -> Result<Value, PawxError> {
+            Ok(Value::Number(c.now() as f64))
+        })),
+    );
+
+    let c = clock.clone();
+    time_map.insert(
+        "in".to_string(),
+        native(move |args| time_in(&args, c.now() as i64)),
+    );
+
+    let c = clock.clone();
+    time_map.insert(
+        "sleep".to_string(),
+        Value::NativeFunction(Arc::new(move |args: Vec<Value>| -> Result<Value, PawxError> {
+            let ms = match args.first().and_then(duration::millis) {
+                Some(n) => n as u64,
+                _ => return raise_error("Time.sleep(ms) requires a number or Duration"),
+            };
+
+            c.advance(ms)?;
+            Ok(Value::Null)
+        })),
+    );
+
+    Value::Object {
+        fields: Rc::new(RefCell::new(time_map)),
+    }
+}
+
 /// Native implementation of `Time.now()` for PAWX.
 ///
 /// Returns the **current Unix timestamp in milliseconds**.
@@ -138,9 +203,9 @@ pub fn create_global_time_value() -> Value {
 /// ```pawx
 /// meow(Time.now());
 /// ```
-pub fn time_now(args: Vec<Value>) -> Value {
+pub fn time_now(args: Vec<Value>) -> Result<Value, PawxError> {
     let millis = Utc::now().timestamp_millis();
-    Value::Number(millis as f64)
+    Ok(Value::Number(millis as f64))
 }
 
 /// Native implementation of `Time.utc()` for PAWX.
@@ -154,8 +219,8 @@ pub fn time_now(args: Vec<Value>) -> Value {
 /// ```pawx
 /// meow(Time.utc());
 /// ```
-pub fn time_utc(args: Vec<Value>) -> Value {
-    Value::String(Utc::now().to_rfc3339())
+pub fn time_utc(args: Vec<Value>) -> Result<Value, PawxError> {
+    Ok(Value::String(Utc::now().to_rfc3339().into()))
 }
 
 /// Native implementation of `Time.local()` for PAWX.
@@ -169,8 +234,8 @@ pub fn time_utc(args: Vec<Value>) -> Value {
 /// ```pawx
 /// meow(Time.local());
 /// ```
-pub fn time_local(args: Vec<Value>) -> Value {
-    Value::String(Local::now().to_rfc3339())
+pub fn time_local(args: Vec<Value>) -> Result<Value, PawxError> {
+    Ok(Value::String(Local::now().to_rfc3339().into()))
 }
 
 /// Native implementation of `Time.format()` for PAWX.
@@ -191,14 +256,14 @@ pub fn time_local(args: Vec<Value>) -> Value {
 /// ```pawx
 /// meow(Time.format("%Y-%m-%d %H:%M:%S"));
 /// ```
-pub fn time_format(args: Vec<Value>) -> Value {
+pub fn time_format(args: Vec<Value>) -> Result<Value, PawxError> {
     let fmt = match args.get(0) {
-        Some(Value::String(s)) => s.clone(),
-        _ => panic!("Time.format() requires a format string"),
+        Some(Value::String(s)) => s.to_string(),
+        _ => return raise_error("Time.format() requires a format string"),
     };
 
     let formatted = Local::now().format(&fmt).to_string();
-    Value::String(formatted)
+    Ok(Value::String(formatted.into()))
 }
 
 /// Native implementation of `Time.tzOffset()` for PAWX.
@@ -212,9 +277,9 @@ pub fn time_format(args: Vec<Value>) -> Value {
 /// ```pawx
 /// meow(Time.tzOffset());
 /// ```
-pub fn time_tzOffset(args: Vec<Value>) -> Value {
+pub fn time_tzOffset(args: Vec<Value>) -> Result<Value, PawxError> {
     let offset = Local::now().offset().local_minus_utc() / 60;
-    Value::Number(offset as f64)
+    Ok(Value::Number(offset as f64))
 }
 
 /// Native implementation of `Time.sleep()` for PAWX.
@@ -237,13 +302,180 @@ pub fn time_tzOffset(args: Vec<Value>) -> Value {
 /// Time.sleep(1000);
 /// meow("Done!");
 /// ```
-pub fn time_sleep(args: Vec<Value>) -> Value {
-    let ms = match args.get(0) {
-        Some(Value::Number(n)) => *n as u64,
-        _ => panic!("Time.sleep(ms) requires a number"),
+pub fn time_sleep(args: Vec<Value>) -> Result<Value, PawxError> {
+    let ms = match args.first().and_then(duration::millis) {
+        Some(n) => n as u64,
+        _ => return raise_error("Time.sleep(ms) requires a number or Duration"),
     };
 
     std::thread::sleep(std::time::Duration::from_millis(ms));
 
-    Value::Null
-}
\ No newline at end of file
+    Ok(Value::Null)
+}
+/// Native implementation of `Time.in()` / `Date.in()` for PAWX.
+///
+/// Returns a date in an IANA time zone: the instant `when`, or the date and
+/// time `when` describes on that zone's wall clocks.
+///
+/// # Parameters (via `args`)
+/// - `args[0]`: Zone name (e.g. `"America/New_York"`)
+/// - `args[1]` (optional): Milliseconds since the epoch, or
+///   `{ year, month?, day?, hour?, minute?, second?, millisecond? }`;
+///   defaults to now
+///
+/// # Returns
+/// An object with `year`, `month` (1-12), `day`, `hour`, `minute`,
+/// `second`, `millisecond`, `weekday` (0 = Sunday), `offset` (minutes east
+/// of UTC), `dst`, `abbreviation`, `zone` and `epoch` (milliseconds), and
+/// the methods `add(units)`, `in(zone)`, `format(fmt)` and `toString()`.
+///
+/// # Behavior
+/// - `add` moves `years`, `months`, `weeks` and `days` on the wall clock
+///   (9:00 stays 9:00 across a DST change) and `hours`, `minutes`,
+///   `seconds` and `milliseconds` in elapsed time.
+/// - A wall-clock time skipped by DST moves forward by the gap; one that
+///   happens twice means the first.
+///
+/// # PAWX Example
+/// ```pawx
+/// snuggle ny = Date.in("America/New_York", { year: 2024, month: 3, day: 9, hour: 9 });
+/// meow(ny.add({ days: 1 }).toString()); // 2024-03-10T09:00:00-04:00
+/// ```
+pub fn time_in(args: &[Value], now_ms: i64) -> Result<Value, PawxError> {
+    let zone = match args.first() {
+        Some(Value::String(name)) => tz::zone(name).map_err(|e| builtin_error(format!("Date.in(zone, when?): {}", e)))?,
+        _ => return raise_error("Date.in(zone, when?) expects a time zone name"),
+    };
+
+    let epoch = match args.get(1) {
+        None | Some(Value::Null) => now_ms,
+        Some(Value::Number(ms)) if ms.is_finite() => *ms as i64,
+        Some(Value::Object { fields }) => from_wall_clock(&zone, &fields.borrow())?,
+        _ => return raise_error("Date.in(zone, when?) expects milliseconds or an object of date parts"),
+    };
+
+    zoned_date(zone, epoch)
+}
+
+/// The instant at which `zone` shows the date and time in `parts`.
+fn from_wall_clock(zone: &Zone, parts: &HashMap<String, Value>) -> Result<i64, PawxError> {
+    let part = |name: &str, default: Option<i64>| -> Result<i64, PawxError> {
+        match parts.get(name) {
+            Some(Value::Number(n)) if n.is_finite() => Ok(n.trunc() as i64),
+            None | Some(Value::Null) => default.ok_or_else(|| builtin_error(format!("Date.in(zone, parts) needs parts.{}", name))),
+            _ => raise_error(format!("Date.in(zone, parts) expects parts.{} to be a number", name)),
+        }
+    };
+
+    let (year, month, day) = (part("year", None)?, part("month", Some(1))?, part("day", Some(1))?);
+    let date = NaiveDate::from_ymd_opt(year as i32, month as u32, day as u32)
+        .ok_or_else(|| builtin_error(format!("Date.in(zone, parts): {}-{}-{} is not a date", year, month, day)))?;
+
+    let (hour, minute, second) = (part("hour", Some(0))?, part("minute", Some(0))?, part("second", Some(0))?);
+    let time = date
+        .and_hms_opt(hour as u32, minute as u32, second as u32)
+        .ok_or_else(|| builtin_error(format!("Date.in(zone, parts): {}:{}:{} is not a time of day", hour, minute, second)))?;
+
+    Ok(zone.resolve(time.and_utc().timestamp()) * 1000 + part("millisecond", Some(0))?)
+}
+
+/// What wall clocks in `zone` show at `epoch` (milliseconds), with its offset.
+fn wall_clock(zone: &Zone, epoch: i64) -> Result<(NaiveDateTime, tz::LocalType), PawxError> {
+    let local_type = zone.at(epoch.div_euclid(1000));
+    let local = DateTime::from_timestamp_millis(epoch + local_type.offset as i64 * 1000)
+        .ok_or_else(|| builtin_error(format!("Date.in: {} is out of range", epoch)))?
+        .naive_utc();
+    Ok((local, local_type))
+}
+
+/// Builds the PAWX object for `epoch` (milliseconds) in `zone`.
+fn zoned_date(zone: Rc<Zone>, epoch: i64) -> Result<Value, PawxError> {
+    let (local, local_type) = wall_clock(&zone, epoch)?;
+    let mut fields = HashMap::new();
+
+    let number = |n: i64| Value::Number(n as f64);
+    fields.insert("zone".to_string(), Value::String(zone.name.as_str().into()));
+    fields.insert("epoch".to_string(), number(epoch));
+    fields.insert("year".to_string(), number(local.year() as i64));
+    fields.insert("month".to_string(), number(local.month() as i64));
+    fields.insert("day".to_string(), number(local.day() as i64));
+    fields.insert("hour".to_string(), number(local.hour() as i64));
+    fields.insert("minute".to_string(), number(local.minute() as i64));
+    fields.insert("second".to_string(), number(local.second() as i64));
+    fields.insert("millisecond".to_string(), number(epoch.rem_euclid(1000)));
+    fields.insert("weekday".to_string(), number(local.weekday().num_days_from_sunday() as i64));
+    fields.insert("offset".to_string(), number(local_type.offset as i64 / 60));
+    fields.insert("dst".to_string(), Value::Bool(local_type.dst));
+    fields.insert("abbreviation".to_string(), Value::String(local_type.abbreviation.as_str().into()));
+
+    let offset = FixedOffset::east_opt(local_type.offset).unwrap_or_else(|| FixedOffset::east_opt(0).unwrap());
+    let instant = DateTime::from_timestamp_millis(epoch).unwrap_or_default().with_timezone(&offset);
+
+    fields.insert(
+        "toString".to_string(),
+        native(move |_| Ok(Value::String(instant.to_rfc3339_opts(SecondsFormat::AutoSi, false).into()))),
+    );
+
+    let abbreviation = local_type.abbreviation.clone();
+    fields.insert(
+        "format".to_string(),
+        native(move |args| {
+            let fmt = match args.first() {
+                Some(Value::String(s)) => s.replace("%Z", &abbreviation),
+                _ => return raise_error("date.format(fmt) requires a format string"),
+            };
+            Ok(Value::String(instant.format(&fmt).to_string().into()))
+        }),
+    );
+
+    let z = zone.clone();
+    fields.insert(
+        "add".to_string(),
+        native(move |args| match args.first() {
+            Some(Value::Object { fields }) => zoned_date(z.clone(), add_units(&z, epoch, &fields.borrow())?),
+            Some(Value::Duration(ms)) => zoned_date(z.clone(), epoch + ms.round() as i64),
+            _ => raise_error("date.add(units) expects a Duration or an object such as { days: 1 }"),
+        }),
+    );
+
+    fields.insert(
+        "in".to_string(),
+        native(move |args| time_in(&[args.first().cloned().unwrap_or(Value::Null)], epoch)),
+    );
+
+    Ok(Value::Object {
+        fields: Rc::new(RefCell::new(fields)),
+    })
+}
+
+/// `epoch` moved by `units`: calendar units on `zone`'s wall clock, then
+/// fixed-length units in elapsed time.
+fn add_units(zone: &Zone, epoch: i64, units: &HashMap<String, Value>) -> Result<i64, PawxError> {
+    let unit = |name: &str| -> Result<f64, PawxError> {
+        match units.get(name) {
+            Some(Value::Number(n)) if n.is_finite() => Ok(*n),
+            None | Some(Value::Null) => Ok(0.0),
+            _ => raise_error(format!("date.add(units) expects units.{} to be a number", name)),
+        }
+    };
+
+    let months = (unit("years")? * 12.0 + unit("months")?).trunc() as i64;
+    let days = (unit("weeks")? * 7.0 + unit("days")?).trunc() as i64;
+
+    let mut epoch = epoch;
+    if months != 0 || days != 0 {
+        let (local, _) = wall_clock(zone, epoch)?;
+        let shifted = if months >= 0 {
+            local.checked_add_months(Months::new(months as u32))
+        } else {
+            local.checked_sub_months(Months::new(months.unsigned_abs() as u32))
+        }
+        .and_then(|d| d.checked_add_signed(chrono::Duration::days(days)))
+        .ok_or_else(|| builtin_error("date.add(units): the result is out of range"))?;
+
+        epoch = zone.resolve(shifted.and_utc().timestamp()) * 1000 + epoch.rem_euclid(1000);
+    }
+
+    let elapsed = unit("hours")? * 3_600_000.0 + unit("minutes")? * 60_000.0 + unit("seconds")? * 1000.0 + unit("milliseconds")?;
+    Ok(epoch + elapsed.round() as i64)
+}
@@ -0,0 +1,393 @@
+/*
+ * ============================================================================
+ * PAWX - Code with Claws! 🐾
+ * ============================================================================
+ *
+ * Typed Numeric Arrays for PAWX (`Float64Array`)
+ *
+ * A `Float64Array` stores its elements in one contiguous `Vec<f64>`
+ * instead of a vector of boxed `Value`s, and runs its bulk operations
+ * natively. Use it for numeric work where plain arrays are too slow.
+ *
+ * Supports:
+ *   - Construction from lengths, arrays, ranges, and fills
+ *   - `arr[i]` reads and `arr[i] = x` writes
+ *   - Element-wise add / sub / mul / div (with arrays or scalars)
+ *   - Slicing with negative indices
+ *   - sum / mean / min / max / dot product
+ *
+ * Installed API:
+ *   - Float64Array.new(lengthOrArray)
+ *   - Float64Array.from(array)
+ *   - Float64Array.filled(length, value)
+ *   - Float64Array.range(start, end, step?)
+ *   - Float64Array.linspace(start, end, count)
+ *
+ * Typed arrays are shared by reference, like plain arrays; use `copy()`
+ * for an independent buffer.
+ *
+ * --------------------------------------------------------------------------
+ * Author:   Sam Wilcox
+ * Email:    sam@pawx-lang.com
+ * Website:  https://www.pawx-lang.com
+ * GitHub:   https://github.com/samwilcox/pawx
+ *
+ * License:
+ * This file is part of the PAWX programming language project.
+ *
+ * PAWX is dual-licensed under the terms of:
+ *   - The MIT License
+ *   - The Apache License, Version 2.0
+ *
+ * You may choose either license to govern your use of this software.
+ * Full license text available at:
+ *     https://license.pawx-lang.com
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under these licenses is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *
+ * ==========================================================================
+ */
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use crate::error::PawxError;
+use crate::interpreter::calls::{builtin_error, raise_error};
+use crate::prototypes::array::create_array_proto;
+use crate::prototypes::slice;
+use crate::value::Value;
+
+/// Shared storage behind a `Float64Array` value.
+pub type Float64Buffer = Rc<RefCell<Vec<f64>>>;
+
+/// Wraps a vector as a new `Float64Array` value.
+pub fn float64_array(data: Vec<f64>) -> Value {
+    Value::Float64Array(Rc::new(RefCell::new(data)))
+}
+
+fn method(f: impl Fn(Vec<Value>) -> Result<Value, PawxError> + 'static) -> Value {
+    Value::NativeFunction(Arc::new(f))
+}
+
+/* ============================================================================
+ * CONVERSION
+ * ============================================================================
+ */
+
+/// Copies a plain array (or another typed array) into a `Vec<f64>`.
+///
+/// # Errors
+/// - If the value is not an array, or an element is not a number
+fn to_f64_vec(value: Option<&Value>, context: &str) -> Result<Vec<f64>, PawxError> {
+    match value {
+        Some(Value::Float64Array(data)) => Ok(data.borrow().clone()),
+        Some(Value::Array { values, .. }) => values
+            .borrow()
+            .iter()
+            .enumerate()
+            .map(|(i, v)| Ok(match v {
+                Value::Number(n) => *n,
+                other => return raise_error(format!("{}: element {} is {}, expected Number", context, i, other.type_name())),
+            }))
+            .collect(),
+        _ => raise_error(format!("{} expects an array of numbers", context)),
+    }
+}
+
+fn number(value: Option<&Value>, context: &str) -> Result<f64, PawxError> {
+    match value {
+        Some(Value::Number(n)) => Ok(*n),
+        _ => raise_error(format!("{} expects a number", context)),
+    }
+}
+
+fn length(value: Option<&Value>, context: &str) -> Result<usize, PawxError> {
+    match value {
+        Some(Value::Number(n)) if *n >= 0.0 && n.fract() == 0.0 => Ok(*n as usize),
+        _ => raise_error(format!("{} expects a non-negative integer length", context)),
+    }
+}
+
+/* ============================================================================
+ * INDEXING
+ * ============================================================================
+ */
+
+/// Reads `arr[i]`, returning `null` when out of bounds.
+pub fn get_index(data: &Float64Buffer, index: usize) -> Value {
+    match data.borrow().get(index) {
+        Some(n) => Value::Number(*n),
+        None => Value::Null,
+    }
+}
+
+/// Writes `arr[i] = value`.
+///
+/// # Errors
+/// - If `index` is out of bounds or `value` is not a number
+pub fn set_index(data: &Float64Buffer, index: usize, value: &Value) -> Result<(), String> {
+    let n = match value {
+        Value::Number(n) => *n,
+        other => return Err(format!("Float64Array elements must be numbers, got {}", other.type_name())),
+    };
+
+    let mut data = data.borrow_mut();
+    match data.get_mut(index) {
+        Some(slot) => {
+            *slot = n;
+            Ok(())
+        }
+        None => Err(format!("index {} out of bounds for length {}", index, data.len())),
+    }
+}
+
+/* ============================================================================
+ * ELEMENT-WISE OPERATIONS
+ * ============================================================================
+ */
+
+/// Applies `op` element-wise against another typed array, a plain array of
+/// numbers, or a scalar, producing a new array.
+fn elementwise(data: &Float64Buffer, other: Option<&Value>, name: &str, op: fn(f64, f64) -> f64) -> Result<Value, PawxError> {
+    let context = format!("Float64Array.{}(other)", name);
+    let left = data.borrow();
+
+    let result: Vec<f64> = match other {
+        Some(Value::Number(k)) => left.iter().map(|a| op(*a, *k)).collect(),
+
+        // Same buffer on both sides: avoid a second borrow of the RefCell
+        Some(Value::Float64Array(right)) if Rc::ptr_eq(data, right) => {
+            left.iter().map(|a| op(*a, *a)).collect()
+        }
+
+        other => {
+            let right = to_f64_vec(other, &context)?;
+            if right.len() != left.len() {
+                return raise_error(format!("{}: length mismatch ({} vs {})", context, left.len(), right.len()));
+            }
+            left.iter().zip(right.iter()).map(|(a, b)| op(*a, *b)).collect()
+        }
+    };
+
+    Ok(float64_array(result))
+}
+
+/// Resolves a property or method on a `Float64Array`.
+///
+/// # Supported Members
+/// - `length`
+/// - `get(i)`, `set(i, x)`
+/// - `add(x)`, `sub(x)`, `mul(x)`, `div(x)` → new array; `x` is an array
+///   of the same length or a number
+/// - `map(fn)` → new array of `fn(value, index)`
+/// - `fill(x)` → fills in place and returns the array
+/// - `slice(start?, end?, step?)` → copy of a range; negative indices count
+///   from the end, a negative step walks backwards
+/// - `sum()`, `mean()`, `min()`, `max()` (`mean`/`min`/`max` are `null`
+///   when empty)
+/// - `dot(other)`
+/// - `copy()`, `toArray()`
+///
+/// # Errors
+/// - On length mismatches, non-numeric elements, or unknown properties
+pub fn float64_array_property(data: &Float64Buffer, name: &str) -> Result<Value, PawxError> {
+    let data = data.clone();
+
+    match name {
+        "length" => Ok(Value::Number(data.borrow().len() as f64)),
+
+        "get" => Ok(method(move |args| Ok(get_index(&data, length(args.first(), "Float64Array.get(i)")?)))),
+
+        "set" => Ok(method(move |args| {
+            let index = length(args.first(), "Float64Array.set(i, x)")?;
+            let value = args.get(1).cloned().unwrap_or(Value::Null);
+            set_index(&data, index, &value).map_err(|e| builtin_error(format!("Float64Array.set(i, x): {}", e)))?;
+            Ok(value)
+        })),
+
+        "add" => Ok(method(move |args| elementwise(&data, args.first(), "add", |a, b| a + b))),
+        "sub" => Ok(method(move |args| elementwise(&data, args.first(), "sub", |a, b| a - b))),
+        "mul" => Ok(method(move |args| elementwise(&data, args.first(), "mul", |a, b| a * b))),
+        "div" => Ok(method(move |args| elementwise(&data, args.first(), "div", |a, b| a / b))),
+
+        "map" => Ok(method(move |args| {
+            let f = match args.first() {
+                Some(Value::NativeFunction(f)) => f.clone(),
+                _ => return raise_error("Float64Array.map(fn) expects a function"),
+            };
+
+            let source = data.borrow().clone();
+            let mapped = source
+                .iter()
+                .enumerate()
+                .map(|(i, n)| match f(vec![Value::Number(*n), Value::Number(i as f64)])? {
+                    Value::Number(m) => Ok(m),
+                    other => raise_error(format!("Float64Array.map(fn): callback returned {}, expected Number", other.type_name())),
+                })
+                .collect::<Result<_, _>>()?;
+
+            Ok(float64_array(mapped))
+        })),
+
+        "fill" => Ok(method(move |args| {
+            let n = number(args.first(), "Float64Array.fill(x)")?;
+            data.borrow_mut().iter_mut().for_each(|slot| *slot = n);
+            Ok(Value::Float64Array(data.clone()))
+        })),
+
+        "slice" => Ok(method(move |args| {
+            let source = data.borrow();
+            let picked = slice::positions(&args, source.len(), "Float64Array.slice(start?, end?, step?)")?;
+
+            Ok(float64_array(picked.into_iter().map(|i| source[i]).collect()))
+        })),
+
+        "sum" => Ok(method(move |_| Ok(Value::Number(data.borrow().iter().sum())))),
+
+        "mean" => Ok(method(move |_| {
+            let data = data.borrow();
+            if data.is_empty() {
+                Ok(Value::Null)
+            } else {
+                Ok(Value::Number(data.iter().sum::<f64>() / data.len() as f64))
+            }
+        })),
+
+        "min" => Ok(method(move |_| match data.borrow().iter().copied().reduce(f64::min) {
+            Some(n) => Ok(Value::Number(n)),
+            None => Ok(Value::Null),
+        })),
+
+        "max" => Ok(method(move |_| match data.borrow().iter().copied().reduce(f64::max) {
+            Some(n) => Ok(Value::Number(n)),
+            None => Ok(Value::Null),
+        })),
+
+        "dot" => Ok(method(move |args| {
+            let left = data.borrow();
+            let right = match args.first() {
+                Some(Value::Float64Array(other)) if Rc::ptr_eq(&data, other) => left.clone(),
+                other => to_f64_vec(other, "Float64Array.dot(other)")?,
+            };
+
+            if right.len() != left.len() {
+                return raise_error(format!("Float64Array.dot(other): length mismatch ({} vs {})", left.len(), right.len()));
+            }
+
+            Ok(Value::Number(left.iter().zip(right.iter()).map(|(a, b)| a * b).sum()))
+        })),
+
+        "copy" => Ok(method(move |_| Ok(float64_array(data.borrow().clone())))),
+
+        "toArray" => Ok(method(move |_| Ok(Value::Array {
+            values: Rc::new(RefCell::new(
+                data.borrow().iter().map(|n| Value::Number(*n)).collect(),
+            )),
+            proto: create_array_proto(),
+        }))),
+
+        other => raise_error(format!("Property '{}' not supported on Float64Array", other)),
+    }
+}
+
+/* ============================================================================
+ * GLOBAL FLOAT64ARRAY NAMESPACE
+ * ============================================================================
+ */
+
+/// Creates and returns the global `Float64Array` namespace for the PAWX
+/// runtime.
+///
+/// # Installed Functions
+/// - `Float64Array.new(length)` → zero-filled array
+/// - `Float64Array.new(array)` / `Float64Array.from(array)` → copy of a
+///   plain array of numbers
+/// - `Float64Array.filled(length, value)`
+/// - `Float64Array.range(start, end, step?)` → `start` up to (not
+///   including) `end`
+/// - `Float64Array.linspace(start, end, count)` → `count` evenly spaced
+///   values including both ends
+///
+/// # PAWX Example
+/// ```pawx
+/// snuggle xs = Float64Array.range(0, 5);        // [0, 1, 2, 3, 4]
+/// snuggle ys = xs.mul(2).add(1);                // [1, 3, 5, 7, 9]
+/// meow(xs.dot(ys), ys.mean());                  // 70 5
+/// ```
+///
+/// # Returns
+/// A `HashMap<String, Value>` representing the `Float64Array` namespace.
+pub fn create_global_float64_array_object() -> HashMap<String, Value> {
+    let mut ns = HashMap::new();
+
+    ns.insert(
+        "new".to_string(),
+        method(|args| match args.first() {
+            Some(Value::Number(_)) => {
+                Ok(float64_array(vec![0.0; length(args.first(), "Float64Array.new(length)")?]))
+            }
+            other => Ok(float64_array(to_f64_vec(other, "Float64Array.new(lengthOrArray)")?)),
+        }),
+    );
+
+    ns.insert(
+        "from".to_string(),
+        method(|args| Ok(float64_array(to_f64_vec(args.first(), "Float64Array.from(array)")?))),
+    );
+
+    ns.insert(
+        "filled".to_string(),
+        method(|args| {
+            let len = length(args.first(), "Float64Array.filled(length, value)")?;
+            let value = number(args.get(1), "Float64Array.filled(length, value)")?;
+            Ok(float64_array(vec![value; len]))
+        }),
+    );
+
+    ns.insert(
+        "range".to_string(),
+        method(|args| {
+            let context = "Float64Array.range(start, end, step?)";
+            let start = number(args.first(), context)?;
+            let end = number(args.get(1), context)?;
+            let step = match args.get(2) {
+                Some(Value::Null) | None => 1.0,
+                other => number(other, context)?,
+            };
+
+            if step == 0.0 || !step.is_finite() {
+                return raise_error(format!("{}: step must be a non-zero number", context));
+            }
+
+            let count = ((end - start) / step).ceil().max(0.0) as usize;
+            Ok(float64_array((0..count).map(|i| start + step * i as f64).collect()))
+        }),
+    );
+
+    ns.insert(
+        "linspace".to_string(),
+        method(|args| {
+            let context = "Float64Array.linspace(start, end, count)";
+            let start = number(args.first(), context)?;
+            let end = number(args.get(1), context)?;
+            let count = length(args.get(2), context)?;
+
+            let data = match count {
+                0 => Vec::new(),
+                1 => vec![start],
+                _ => {
+                    let step = (end - start) / (count - 1) as f64;
+                    (0..count).map(|i| start + step * i as f64).collect()
+                }
+            };
+
+            Ok(float64_array(data))
+        }),
+    );
+
+    ns
+}
@@ -0,0 +1,513 @@
+/*
+ * ============================================================================
+ * PAWX - Code with Claws! 🐾
+ * ============================================================================
+ *
+ * Time Zone Database for PAWX
+ *
+ * Reads IANA time zones ("America/New_York", "Europe/London", ...) from
+ * the system's compiled tz database, the TZif files under
+ * `/usr/share/zoneinfo` (or `$TZDIR`) that every Unix keeps up to date.
+ * Each file lists a zone's past transitions and ends with a POSIX rule,
+ * such as `EST5EDT,M3.2.0,M11.1.0`, for every year after them.
+ *
+ * Supports:
+ *   - the offset, abbreviation and DST flag in effect at any instant
+ *   - wall-clock time → instant, with DST gaps and overlaps resolved
+ *     the usual way:
+ *       - a time skipped by a spring-forward gap is moved forward by
+ *         the length of the gap (02:30 → 03:30)
+ *       - a time repeated by a fall-back overlap means its first
+ *         occurrence
+ *
+ * `UTC` (and `Etc/UTC`) work without a database. Zones are read once per
+ * thread and cached.
+ *
+ * --------------------------------------------------------------------------
+ * Author:   Sam Wilcox
+ * Email:    sam@pawx-lang.com
+ * Website:  https://www.pawx-lang.com
+ * GitHub:   https://github.com/samwilcox/pawx
+ *
+ * License:
+ * This file is part of the PAWX programming language project.
+ *
+ * PAWX is dual-licensed under the terms of:
+ *   - The MIT License
+ *   - The Apache License, Version 2.0
+ *
+ * You may choose either license to govern your use of this software.
+ * Full license text available at:
+ *     https://license.pawx-lang.com
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under these licenses is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *
+ * ==========================================================================
+ */
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use chrono::{Datelike, NaiveDate};
+
+/// Where tz databases are usually installed.
+const SEARCH_PATH: &[&str] = &["/usr/share/zoneinfo", "/usr/lib/zoneinfo", "/usr/share/lib/zoneinfo"];
+
+/// The offset in effect in a zone at some instant.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LocalType {
+    /// Seconds east of UTC.
+    pub offset: i32,
+    pub dst: bool,
+    pub abbreviation: String,
+}
+
+/// A loaded time zone.
+#[derive(Debug)]
+pub struct Zone {
+    pub name: String,
+    /// UTC seconds at which each transition happens, ascending.
+    transitions: Vec<i64>,
+    /// The type in effect from each transition on.
+    transition_types: Vec<usize>,
+    types: Vec<LocalType>,
+    /// Covers every instant after the last transition.
+    rule: Option<Rule>,
+}
+
+thread_local! {
+    static ZONES: RefCell<HashMap<String, Rc<Zone>>> = RefCell::new(HashMap::new());
+}
+
+/// Loads the zone called `name`.
+///
+/// # Errors
+/// If the name is not a zone in the database (or there is no database).
+pub fn zone(name: &str) -> Result<Rc<Zone>, String> {
+    if let Some(zone) = ZONES.with(|zones| zones.borrow().get(name).cloned()) {
+        return Ok(zone);
+    }
+
+    let zone = Rc::new(load(name)?);
+    ZONES.with(|zones| zones.borrow_mut().insert(name.to_string(), zone.clone()));
+    Ok(zone)
+}
+
+fn load(name: &str) -> Result<Zone, String> {
+    let valid = !name.is_empty()
+        && !name.starts_with('/')
+        && name.split('/').all(|part| !part.is_empty() && part != "." && part != "..");
+    if !valid {
+        return Err(format!("'{}' is not a time zone name", name));
+    }
+
+    let dirs = std::env::var("TZDIR")
+        .ok()
+        .into_iter()
+        .chain(SEARCH_PATH.iter().map(|d| d.to_string()));
+
+    for dir in dirs {
+        let path: PathBuf = [dir.as_str(), name].iter().collect();
+        if let Ok(bytes) = std::fs::read(&path) {
+            return parse_tzif(name, &bytes).ok_or_else(|| format!("'{}' is not a valid time zone file", path.display()));
+        }
+    }
+
+    if matches!(name, "UTC" | "Etc/UTC" | "Etc/GMT" | "GMT" | "Zulu") {
+        return Ok(Zone {
+            name: name.to_string(),
+            transitions: Vec::new(),
+            transition_types: Vec::new(),
+            types: vec![LocalType { offset: 0, dst: false, abbreviation: "UTC".to_string() }],
+            rule: None,
+        });
+    }
+
+    Err(format!("unknown time zone '{}'", name))
+}
+
+impl Zone {
+    /// The offset in effect at `utc` (seconds since the epoch).
+    pub fn at(&self, utc: i64) -> LocalType {
+        let after_last = self.transitions.last().is_none_or(|last| utc >= *last);
+        if let (true, Some(rule)) = (after_last, &self.rule) {
+            return rule.at(utc);
+        }
+
+        match self.transitions.partition_point(|t| *t <= utc) {
+            // Before the first transition: the zone's first type
+            0 => self.types[0].clone(),
+            n => self.types[self.transition_types[n - 1]].clone(),
+        }
+    }
+
+    /// The instant (UTC seconds) at which wall clocks in this zone show
+    /// `local` (seconds since the epoch, read as a local date and time).
+    pub fn resolve(&self, local: i64) -> i64 {
+        // Any offset that could apply is in effect within a day either side
+        let before = self.at(local - 86_400).offset as i64;
+        let after = self.at(local + 86_400).offset as i64;
+
+        let fits = |offset: i64| self.at(local - offset).offset as i64 == offset;
+        match (fits(before), fits(after)) {
+            // Repeated by a fall-back: the first occurrence is the earlier instant
+            (true, true) => (local - before).min(local - after),
+            (true, false) => local - before,
+            (false, true) => local - after,
+            // Skipped by a spring-forward: read with the offset from before the gap
+            (false, false) => local - before,
+        }
+    }
+}
+
+/* ============================================================================
+ * TZif FILES (RFC 8536)
+ * ============================================================================
+ */
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    at: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Option<&'a [u8]> {
+        let slice = self.bytes.get(self.at..self.at + n)?;
+        self.at += n;
+        Some(slice)
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        Some(u32::from_be_bytes(self.take(4)?.try_into().ok()?))
+    }
+
+    fn i32(&mut self) -> Option<i32> {
+        Some(i32::from_be_bytes(self.take(4)?.try_into().ok()?))
+    }
+
+    fn i64(&mut self) -> Option<i64> {
+        Some(i64::from_be_bytes(self.take(8)?.try_into().ok()?))
+    }
+}
+
+/// The counts in a TZif header.
+struct Header {
+    version: u8,
+    isutcnt: usize,
+    isstdcnt: usize,
+    leapcnt: usize,
+    timecnt: usize,
+    typecnt: usize,
+    charcnt: usize,
+}
+
+fn header(r: &mut Reader) -> Option<Header> {
+    if r.take(4)? != b"TZif" {
+        return None;
+    }
+    let version = r.take(1)?[0];
+    r.take(15)?;
+
+    let mut count = || r.u32().map(|n| n as usize);
+    Some(Header {
+        version,
+        isutcnt: count()?,
+        isstdcnt: count()?,
+        leapcnt: count()?,
+        timecnt: count()?,
+        typecnt: count()?,
+        charcnt: count()?,
+    })
+}
+
+fn parse_tzif(name: &str, bytes: &[u8]) -> Option<Zone> {
+    let mut r = Reader { bytes, at: 0 };
+    let mut h = header(&mut r)?;
+    let mut time_size = 4;
+
+    // Version 2+ files repeat the data with 64-bit times; use that copy
+    if h.version >= b'2' {
+        r.take(h.timecnt * 5 + h.typecnt * 6 + h.charcnt + h.leapcnt * 8 + h.isstdcnt + h.isutcnt)?;
+        h = header(&mut r)?;
+        time_size = 8;
+    }
+
+    let mut transitions = Vec::with_capacity(h.timecnt);
+    for _ in 0..h.timecnt {
+        transitions.push(if time_size == 8 { r.i64()? } else { r.i32()? as i64 });
+    }
+    let transition_types: Vec<usize> = r.take(h.timecnt)?.iter().map(|t| *t as usize).collect();
+
+    let mut raw_types = Vec::with_capacity(h.typecnt);
+    for _ in 0..h.typecnt {
+        let offset = r.i32()?;
+        let flags = r.take(2)?;
+        raw_types.push((offset, flags[0] != 0, flags[1] as usize));
+    }
+
+    let chars = r.take(h.charcnt)?;
+    let types: Vec<LocalType> = raw_types
+        .into_iter()
+        .map(|(offset, dst, at)| {
+            let rest = chars.get(at..).unwrap_or_default();
+            let end = rest.iter().position(|b| *b == 0).unwrap_or(rest.len());
+            LocalType {
+                offset,
+                dst,
+                abbreviation: String::from_utf8_lossy(&rest[..end]).into_owned(),
+            }
+        })
+        .collect();
+
+    if types.is_empty() || transition_types.iter().any(|t| *t >= types.len()) {
+        return None;
+    }
+
+    r.take(h.leapcnt * (time_size + 4) + h.isstdcnt + h.isutcnt)?;
+
+    // The footer: "\n<POSIX TZ string>\n"
+    let rule = if time_size == 8 {
+        let footer = String::from_utf8_lossy(r.bytes.get(r.at..).unwrap_or_default()).into_owned();
+        Rule::parse(footer.trim())
+    } else {
+        None
+    };
+
+    Some(Zone {
+        name: name.to_string(),
+        transitions,
+        transition_types,
+        types,
+        rule,
+    })
+}
+
+/* ============================================================================
+ * POSIX TZ RULES ("CET-1CEST,M3.5.0,M10.5.0/3")
+ * ============================================================================
+ */
+
+/// A day of the year a POSIX rule switches on.
+#[derive(Debug, Clone, Copy)]
+enum RuleDay {
+    /// `Jn`: day 1-365, never counting 29 February.
+    Julian(u16),
+    /// `n`: day 0-365, counting 29 February.
+    Ordinal(u16),
+    /// `Mm.w.d`: weekday `d` (0 = Sunday) of week `w` (5 = last) of month `m`.
+    Weekday { month: u32, week: u32, weekday: u32 },
+}
+
+/// When daylight saving time starts and ends.
+#[derive(Debug)]
+struct Dst {
+    local: LocalType,
+    start: (RuleDay, i64),
+    end: (RuleDay, i64),
+}
+
+#[derive(Debug)]
+struct Rule {
+    standard: LocalType,
+    dst: Option<Dst>,
+}
+
+impl Rule {
+    fn parse(s: &str) -> Option<Rule> {
+        let mut p = Parser { s, at: 0 };
+
+        let std_name = p.name()?;
+        let std_offset = -p.time()?;
+        let standard = LocalType {
+            offset: std_offset as i32,
+            dst: false,
+            abbreviation: std_name,
+        };
+
+        if p.done() {
+            return Some(Rule { standard, dst: None });
+        }
+
+        let dst_name = p.name()?;
+        // Without an explicit offset, DST is an hour ahead of standard time
+        let dst_offset = if p.peek() == Some(',') { std_offset + 3600 } else { -p.time()? };
+
+        p.expect(',')?;
+        let start = p.transition()?;
+        p.expect(',')?;
+        let end = p.transition()?;
+
+        Some(Rule {
+            standard,
+            dst: Some(Dst {
+                local: LocalType {
+                    offset: dst_offset as i32,
+                    dst: true,
+                    abbreviation: dst_name,
+                },
+                start,
+                end,
+            }),
+        })
+    }
+
+    fn at(&self, utc: i64) -> LocalType {
+        let Some(dst) = &self.dst else {
+            return self.standard.clone();
+        };
+
+        // The year as it is locally (standard time is close enough)
+        let year = civil_year(utc + self.standard.offset as i64);
+
+        // Starts are given in standard time, ends in daylight time
+        let start = day_start(year, dst.start.0) + dst.start.1 - self.standard.offset as i64;
+        let end = day_start(year, dst.end.0) + dst.end.1 - dst.local.offset as i64;
+
+        let in_dst = if start < end {
+            utc >= start && utc < end
+        } else {
+            // Southern hemisphere: DST spans the new year
+            utc < end || utc >= start
+        };
+
+        if in_dst { dst.local.clone() } else { self.standard.clone() }
+    }
+}
+
+struct Parser<'a> {
+    s: &'a str,
+    at: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<char> {
+        self.s[self.at..].chars().next()
+    }
+
+    fn done(&self) -> bool {
+        self.at >= self.s.len()
+    }
+
+    fn expect(&mut self, c: char) -> Option<()> {
+        (self.peek()? == c).then(|| self.at += c.len_utf8())
+    }
+
+    fn take_while(&mut self, f: impl Fn(char) -> bool) -> &str {
+        let start = self.at;
+        while self.peek().is_some_and(&f) {
+            self.at += self.peek().map_or(0, char::len_utf8);
+        }
+        &self.s[start..self.at]
+    }
+
+    /// `EST` or `<+0330>`.
+    fn name(&mut self) -> Option<String> {
+        let name = if self.peek()? == '<' {
+            self.at += 1;
+            let name = self.take_while(|c| c != '>').to_string();
+            self.expect('>')?;
+            name
+        } else {
+            self.take_while(|c| c.is_ascii_alphabetic()).to_string()
+        };
+        (!name.is_empty()).then_some(name)
+    }
+
+    fn number(&mut self) -> Option<i64> {
+        self.take_while(|c| c.is_ascii_digit()).parse().ok()
+    }
+
+    /// `[+-]hh[:mm[:ss]]`, in seconds.
+    fn time(&mut self) -> Option<i64> {
+        let sign = match self.peek()? {
+            '-' => {
+                self.at += 1;
+                -1
+            }
+            '+' => {
+                self.at += 1;
+                1
+            }
+            _ => 1,
+        };
+
+        let mut seconds = self.number()? * 3600;
+        for unit in [60, 1] {
+            if self.peek() != Some(':') {
+                break;
+            }
+            self.at += 1;
+            seconds += self.number()? * unit;
+        }
+        Some(sign * seconds)
+    }
+
+    /// `Mm.w.d[/time]`, `Jn[/time]` or `n[/time]`.
+    fn transition(&mut self) -> Option<(RuleDay, i64)> {
+        let day = match self.peek()? {
+            'M' => {
+                self.at += 1;
+                let month = self.number()? as u32;
+                self.expect('.')?;
+                let week = self.number()? as u32;
+                self.expect('.')?;
+                let weekday = self.number()? as u32;
+                if !(1..=12).contains(&month) || !(1..=5).contains(&week) || weekday > 6 {
+                    return None;
+                }
+                RuleDay::Weekday { month, week, weekday }
+            }
+            'J' => {
+                self.at += 1;
+                RuleDay::Julian(self.number()? as u16)
+            }
+            _ => RuleDay::Ordinal(self.number()? as u16),
+        };
+
+        let time = if self.peek() == Some('/') {
+            self.at += 1;
+            self.time()?
+        } else {
+            7200
+        };
+        Some((day, time))
+    }
+}
+
+fn civil_year(seconds: i64) -> i32 {
+    chrono::DateTime::from_timestamp(seconds, 0).map_or(1970, |dt| dt.year())
+}
+
+/// Seconds since the epoch at the start of `day` in `year` (as if UTC).
+fn day_start(year: i32, day: RuleDay) -> i64 {
+    let jan1 = NaiveDate::from_ymd_opt(year, 1, 1).unwrap_or_default();
+
+    let date = match day {
+        RuleDay::Ordinal(n) => jan1 + chrono::Days::new(n as u64),
+        RuleDay::Julian(n) => {
+            let leap = NaiveDate::from_ymd_opt(year, 2, 29).is_some();
+            let skip_leap_day = leap && n >= 60;
+            jan1 + chrono::Days::new(n.saturating_sub(1) as u64 + skip_leap_day as u64)
+        }
+        RuleDay::Weekday { month, week, weekday } => {
+            let first = NaiveDate::from_ymd_opt(year, month, 1).unwrap_or(jan1);
+            let first_weekday = first.weekday().num_days_from_sunday();
+            let mut day = 1 + (weekday + 7 - first_weekday) % 7 + (week - 1) * 7;
+
+            // Week 5 means the last one, which may be the 4th
+            let days_in_month = first
+                .checked_add_months(chrono::Months::new(1))
+                .map_or(31, |next| next.signed_duration_since(first).num_days() as u32);
+            while day > days_in_month {
+                day -= 7;
+            }
+            first.with_day(day).unwrap_or(first)
+        }
+    };
+
+    date.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc().timestamp()
+}
@@ -0,0 +1,227 @@
+/*
+ * ============================================================================
+ * PAWX - Code with Claws! 🐾
+ * ============================================================================
+ *
+ * Weak References + Finalization for PAWX (`WeakRef`)
+ *
+ * Supports:
+ *   - WeakRef(value)              weak handle to an object, array,
+ *                                 Float64Array or instance
+ *   - ref.deref()                 the target, or null once it is gone
+ *   - ref.isAlive()
+ *   - ref.onFinalize(cb, held?)   run cb(held) after the target is freed
+ *
+ * A weak reference does not keep its target alive, so caches and observer
+ * registries can hold values without pinning whole object graphs.
+ *
+ * PAWX values are reference counted. A target is freed as soon as its last
+ * strong reference goes away; finalizers for freed targets run on the
+ * interpreter thread between top-level statements and once more before the
+ * program exits. Values kept alive by a reference cycle are never freed, so
+ * their finalizers never run.
+ *
+ * --------------------------------------------------------------------------
+ * Author:   Sam Wilcox
+ * Email:    sam@pawx-lang.com
+ * Website:  https://www.pawx-lang.com
+ * GitHub:   https://github.com/samwilcox/pawx
+ *
+ * License:
+ * This file is part of the PAWX programming language project.
+ *
+ * PAWX is dual-licensed under the terms of:
+ *   - The MIT License
+ *   - The Apache License, Version 2.0
+ *
+ * You may choose either license to govern your use of this software.
+ * Full license text available at:
+ *     https://license.pawx-lang.com
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under these licenses is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *
+ * ==========================================================================
+ */
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::{Rc, Weak};
+use std::sync::Arc;
+
+use crate::error::PawxError;
+use crate::interpreter::calls::raise_error;
+use crate::prototypes::array::create_array_proto;
+use crate::value::{ClassDef, Value};
+
+fn native(f: impl Fn(Vec<Value>) -> Result<Value, PawxError> + 'static) -> Value {
+    Value::NativeFunction(Arc::new(f))
+}
+
+/* ============================================================================
+ * WEAK TARGETS
+ * ============================================================================
+ */
+
+/// The weakly held side of a `WeakRef`.
+#[derive(Clone)]
+enum WeakTarget {
+    Array(Weak<RefCell<Vec<Value>>>),
+    Object(Weak<RefCell<HashMap<String, Value>>>),
+    Float64Array(Weak<RefCell<Vec<f64>>>),
+    Instance {
+        class: Rc<ClassDef>,
+        fields: Weak<RefCell<HashMap<String, Value>>>,
+    },
+}
+
+impl WeakTarget {
+    /// # Errors
+    /// If `value` has no heap identity (numbers, strings, ...).
+    fn new(value: &Value) -> Result<Self, PawxError> {
+        match value {
+            Value::Array { values, .. } => Ok(WeakTarget::Array(Rc::downgrade(values))),
+            Value::Object { fields } => Ok(WeakTarget::Object(Rc::downgrade(fields))),
+            Value::Float64Array(data) => Ok(WeakTarget::Float64Array(Rc::downgrade(data))),
+            Value::Instance { class, fields } => Ok(WeakTarget::Instance {
+                class: class.clone(),
+                fields: Rc::downgrade(fields),
+            }),
+            other => raise_error(format!(
+                "WeakRef(value) expects an object, array, Float64Array or instance, got {}",
+                other.type_name()
+            )),
+        }
+    }
+
+    fn is_alive(&self) -> bool {
+        match self {
+            WeakTarget::Array(w) => w.strong_count() > 0,
+            WeakTarget::Object(w) => w.strong_count() > 0,
+            WeakTarget::Float64Array(w) => w.strong_count() > 0,
+            WeakTarget::Instance { fields, .. } => fields.strong_count() > 0,
+        }
+    }
+
+    /// Rebuilds a strong value, or `None` once the target is freed.
+    fn upgrade(&self) -> Option<Value> {
+        match self {
+            WeakTarget::Array(w) => w.upgrade().map(|values| Value::Array {
+                values,
+                proto: create_array_proto(),
+            }),
+            WeakTarget::Object(w) => w.upgrade().map(|fields| Value::Object { fields }),
+            WeakTarget::Float64Array(w) => w.upgrade().map(Value::Float64Array),
+            WeakTarget::Instance { class, fields } => fields.upgrade().map(|fields| Value::Instance {
+                class: class.clone(),
+                fields,
+            }),
+        }
+    }
+}
+
+/* ============================================================================
+ * FINALIZATION REGISTRY
+ * ============================================================================
+ */
+
+struct Finalizer {
+    target: WeakTarget,
+    callback: Value,
+    held: Value,
+}
+
+thread_local! {
+    static FINALIZERS: RefCell<Vec<Finalizer>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Runs (and forgets) every finalizer whose target has been freed.
+///
+/// Called by the interpreter between top-level statements and before exit.
+/// Callbacks run after the registry borrow is released, so they may
+/// register new finalizers.
+///
+/// # Errors
+/// The first error raised by a finalizer callback.
+pub fn run_finalizers() -> Result<(), PawxError> {
+    let ready: Vec<Finalizer> = FINALIZERS.with(|f| {
+        let mut finalizers = f.borrow_mut();
+        let (dead, alive) = finalizers.drain(..).partition(|fin| !fin.target.is_alive());
+        *finalizers = alive;
+        dead
+    });
+
+    if ready.is_empty() {
+        return Ok(());
+    }
+    let finalized = ready.len();
+
+    for finalizer in ready {
+        if let Value::NativeFunction(cb) = &finalizer.callback {
+            cb(vec![finalizer.held])?;
+        }
+    }
+
+    crate::interpreter::hooks::gc(finalized);
+    Ok(())
+}
+
+/* ============================================================================
+ * GLOBAL
+ * ============================================================================
+ */
+
+/// Builds a `WeakRef` handle for `value`.
+fn weak_ref(value: &Value) -> Result<Value, PawxError> {
+    let target = WeakTarget::new(value)?;
+    let mut fields = HashMap::new();
+
+    {
+        let target = target.clone();
+        fields.insert(
+            "deref".to_string(),
+            native(move |_| Ok(target.upgrade().unwrap_or(Value::Null))),
+        );
+    }
+
+    {
+        let target = target.clone();
+        fields.insert(
+            "isAlive".to_string(),
+            native(move |_| Ok(Value::Bool(target.is_alive()))),
+        );
+    }
+
+    fields.insert(
+        "onFinalize".to_string(),
+        native(move |args| {
+            let callback = match args.first() {
+                Some(cb @ Value::NativeFunction(_)) => cb.clone(),
+                _ => return raise_error("WeakRef.onFinalize(fn, held?) expects a function"),
+            };
+
+            FINALIZERS.with(|f| {
+                f.borrow_mut().push(Finalizer {
+                    target: target.clone(),
+                    callback,
+                    held: args.get(1).cloned().unwrap_or(Value::Null),
+                })
+            });
+
+            Ok(Value::Null)
+        }),
+    );
+
+    Ok(Value::Object {
+        fields: Rc::new(RefCell::new(fields)),
+    })
+}
+
+/// Builds the global `WeakRef(value)` constructor.
+pub fn create_global_weak_ref_value() -> Value {
+    native(|args| match args.first() {
+        Some(value) => Ok(weak_ref(value)?),
+        None => raise_error("WeakRef(value) expects a value"),
+    })
+}
@@ -0,0 +1,366 @@
+/*
+ * ==========================================================================
+ * PAWX - Code with Claws!
+ * ==========================================================================
+ *
+ * Package Registry Client (`pawx install`)
+ * ----------------------------------------
+ * Downloads packages from a registry into `paw_modules/`.
+ *
+ * A registry serves one JSON document per package at `<registry>/<name>`:
+ *
+ * ```text
+ * {
+ *   "name": "left-pad",
+ *   "latest": "1.3.0",
+ *   "versions": {
+ *     "1.3.0": {
+ *       "tarball": "left-pad/-/left-pad-1.3.0.tgz",
+ *       "checksum": "sha256-…"
+ *     }
+ *   }
+ * }
+ * ```
+ *
+ * `tarball` may be absolute or relative to the registry. `checksum` is the
+ * hash of the `.tgz` archive itself; an archive that does not match is
+ * rejected. Verified archives are kept in a global content-addressed cache
+ * (`~/.pawx/cache`, or `PAWX_CACHE`) so each version is downloaded once per
+ * machine.
+ *
+ * The registry defaults to `https://registry.pawx-lang.com` and can be
+ * changed with `--registry <url>` or `PAWX_REGISTRY`.
+ *
+ * --------------------------------------------------------------------------
+ * Author:   Sam Wilcox
+ * Email:    sam@pawx-lang.com
+ * Website:  https://www.pawx-lang.com
+ * Github:   https://github.com/samwilcox/pawx
+ *
+ * License:
+ * This file is part of the PAWX programming language project.
+ *
+ * PAWX is dual-licensed under the terms of:
+ *   - The MIT License
+ *   - The Apache License, Version 2.0
+ *
+ * You may choose either license to govern your use of this software.
+ * Full license text available at:
+ *    https://license.pawx-lang.com
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under these licenses is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *
+ * ==========================================================================
+ */
+
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use base64::Engine;
+use flate2::read::GzDecoder;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+use crate::lockfile::{hash_dir, LockedPackage, MODULES_DIR};
+
+/// Registry used when neither `--registry` nor `PAWX_REGISTRY` is set.
+pub const DEFAULT_REGISTRY: &str = "https://registry.pawx-lang.com";
+
+/// Resolves the registry URL: the flag, then `PAWX_REGISTRY`, then the
+/// default.
+pub fn registry_url(flag: Option<&str>) -> String {
+    let url = match flag {
+        Some(url) => url.to_string(),
+        None => env::var("PAWX_REGISTRY").unwrap_or_else(|_| DEFAULT_REGISTRY.to_string()),
+    };
+    url.trim_end_matches('/').to_string()
+}
+
+/// Directory of the global download cache.
+pub fn cache_dir() -> PathBuf {
+    if let Ok(dir) = env::var("PAWX_CACHE") {
+        return PathBuf::from(dir);
+    }
+
+    let home = env::var("HOME")
+        .or_else(|_| env::var("USERPROFILE"))
+        .unwrap_or_else(|_| ".".to_string());
+    Path::new(&home).join(".pawx").join("cache")
+}
+
+/* ============================================================================
+ * Package Specs
+ * ============================================================================
+ */
+
+/// A requested package: `name`, `name@version` or `@scope/name@version`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PackageSpec {
+    pub name: String,
+
+    /// Exact version; `None`, `latest` or `*` mean the latest version.
+    pub version: Option<String>,
+}
+
+impl PackageSpec {
+    /// Parses a spec from the command line.
+    ///
+    /// # Errors
+    /// If the name is empty or not a valid package name.
+    pub fn parse(spec: &str) -> Result<PackageSpec, String> {
+        // A leading `@` belongs to the scope, not the version.
+        let split_at = spec.get(1..).and_then(|s| s.find('@')).map(|i| i + 1);
+        let (name, version) = match split_at {
+            Some(i) => (&spec[..i], Some(&spec[i + 1..])),
+            None => (spec, None),
+        };
+
+        PackageSpec::new(name, version)
+    }
+
+    /// Builds a spec from a `paw.json` dependency entry.
+    ///
+    /// # Errors
+    /// If the name is not a valid package name.
+    pub fn new(name: &str, version: Option<&str>) -> Result<PackageSpec, String> {
+        let valid_part = |p: &str| {
+            !p.is_empty()
+                && !p.starts_with('.')
+                && p.chars().all(|c| c.is_ascii_alphanumeric() || "-_.".contains(c))
+        };
+
+        let valid = match name.strip_prefix('@').and_then(|n| n.split_once('/')) {
+            Some((scope, inner)) => valid_part(scope) && valid_part(inner),
+            None => valid_part(name),
+        };
+
+        if !valid {
+            return Err(format!("invalid package name '{}'", name));
+        }
+
+        let version = version
+            .map(str::trim)
+            .filter(|v| !v.is_empty() && *v != "latest" && *v != "*")
+            .map(str::to_string);
+
+        Ok(PackageSpec {
+            name: name.to_string(),
+            version,
+        })
+    }
+}
+
+/* ============================================================================
+ * Registry Metadata
+ * ============================================================================
+ */
+
+#[derive(Debug, Deserialize)]
+struct PackageDocument {
+    latest: Option<String>,
+    versions: HashMap<String, VersionDocument>,
+}
+
+#[derive(Debug, Deserialize)]
+struct VersionDocument {
+    tarball: String,
+    checksum: String,
+}
+
+/// A package version resolved against the registry.
+#[derive(Debug, Clone)]
+pub struct Resolved {
+    pub name: String,
+    pub version: String,
+
+    /// Absolute URL of the `.tgz` archive.
+    pub tarball: String,
+
+    /// `sha256-<base64>` hash of the archive.
+    pub checksum: String,
+}
+
+fn fetch(url: &str) -> Result<Vec<u8>, String> {
+    let response = ureq::get(url).call().map_err(|e| match e {
+        ureq::Error::Status(code, _) => format!("GET {}: HTTP {}", url, code),
+        other => format!("GET {}: {}", url, other),
+    })?;
+
+    let mut bytes = Vec::new();
+    response
+        .into_reader()
+        .read_to_end(&mut bytes)
+        .map_err(|e| format!("GET {}: {}", url, e))?;
+    Ok(bytes)
+}
+
+/// Looks up `spec` in the registry.
+///
+/// # Errors
+/// If the registry cannot be reached, the package does not exist, or the
+/// requested version is not published.
+pub fn resolve(registry: &str, spec: &PackageSpec) -> Result<Resolved, String> {
+    let url = format!("{}/{}", registry, spec.name);
+    let document: PackageDocument = serde_json::from_slice(&fetch(&url)?)
+        .map_err(|e| format!("{}: invalid registry response: {}", url, e))?;
+
+    let version = match &spec.version {
+        Some(version) => version.clone(),
+        None => document
+            .latest
+            .clone()
+            .ok_or_else(|| format!("{} has no latest version", spec.name))?,
+    };
+
+    let entry = document.versions.get(&version).ok_or_else(|| {
+        let mut known: Vec<&String> = document.versions.keys().collect();
+        known.sort();
+        let known: Vec<&str> = known.into_iter().map(String::as_str).collect();
+        format!("{}@{} not found (published: {})", spec.name, version, known.join(", "))
+    })?;
+
+    let tarball = if entry.tarball.contains("://") {
+        entry.tarball.clone()
+    } else {
+        format!("{}/{}", registry, entry.tarball.trim_start_matches('/'))
+    };
+
+    Ok(Resolved {
+        name: spec.name.clone(),
+        version,
+        tarball,
+        checksum: entry.checksum.clone(),
+    })
+}
+
+/* ============================================================================
+ * Download + Cache
+ * ============================================================================
+ */
+
+fn checksum(bytes: &[u8]) -> String {
+    format!(
+        "sha256-{}",
+        base64::engine::general_purpose::STANDARD.encode(Sha256::digest(bytes))
+    )
+}
+
+/// Cache file for an archive with the given checksum.
+fn cache_path(checksum: &str) -> Result<PathBuf, String> {
+    let digest = checksum
+        .strip_prefix("sha256-")
+        .and_then(|b64| base64::engine::general_purpose::STANDARD.decode(b64).ok())
+        .ok_or_else(|| format!("unsupported checksum '{}'", checksum))?;
+
+    let hex: String = digest.iter().map(|b| format!("{:02x}", b)).collect();
+    Ok(cache_dir().join("sha256").join(&hex[..2]).join(hex))
+}
+
+/// Returns the verified archive for `resolved`, from the cache if present.
+fn archive(resolved: &Resolved) -> Result<Vec<u8>, String> {
+    let cached = cache_path(&resolved.checksum)?;
+
+    if let Ok(bytes) = fs::read(&cached) {
+        if checksum(&bytes) == resolved.checksum {
+            return Ok(bytes);
+        }
+    }
+
+    let bytes = fetch(&resolved.tarball)?;
+    let actual = checksum(&bytes);
+    if actual != resolved.checksum {
+        return Err(format!(
+            "{}@{}: checksum mismatch (expected {}, got {})",
+            resolved.name, resolved.version, resolved.checksum, actual
+        ));
+    }
+
+    // The cache is best-effort: a failed write only costs a re-download.
+    if let Some(parent) = cached.parent() {
+        let tmp = cached.with_extension("tmp");
+        if fs::create_dir_all(parent).is_ok() && fs::write(&tmp, &bytes).is_ok() {
+            let _ = fs::rename(&tmp, &cached);
+        }
+    }
+
+    Ok(bytes)
+}
+
+/* ============================================================================
+ * Install
+ * ============================================================================
+ */
+
+/// Unpacks a `.tgz` into `dest`, replacing it.
+///
+/// Archives whose files all sit under one top-level directory (such as
+/// `package/`) are unpacked from inside that directory.
+fn unpack(bytes: &[u8], dest: &Path) -> Result<(), String> {
+    let parent = dest.parent().ok_or("invalid install directory")?;
+    fs::create_dir_all(parent).map_err(|e| format!("{}: {}", parent.display(), e))?;
+
+    let staging = parent.join(format!(
+        ".staging-{}",
+        dest.file_name().unwrap_or_default().to_string_lossy()
+    ));
+    let _ = fs::remove_dir_all(&staging);
+
+    tar::Archive::new(GzDecoder::new(bytes))
+        .unpack(&staging)
+        .map_err(|e| format!("{}: invalid archive: {}", dest.display(), e))?;
+
+    let entries: Vec<PathBuf> = fs::read_dir(&staging)
+        .map_err(|e| e.to_string())?
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .collect();
+
+    let root = match entries.as_slice() {
+        [only] if only.is_dir() => only.clone(),
+        _ => staging.clone(),
+    };
+
+    let _ = fs::remove_dir_all(dest);
+    fs::rename(&root, dest).map_err(|e| format!("{}: {}", dest.display(), e))?;
+    let _ = fs::remove_dir_all(&staging);
+
+    Ok(())
+}
+
+/// Installs a resolved package under `root/paw_modules`.
+///
+/// When `locked` describes the same version, the unpacked package must
+/// match its integrity hash; a mismatch removes it again.
+///
+/// # Returns
+/// - `Ok(LockedPackage)` describing what was installed
+///
+/// # Errors
+/// On download, checksum, unpack or integrity failures.
+pub fn install(root: &Path, resolved: &Resolved, locked: Option<&LockedPackage>) -> Result<LockedPackage, String> {
+    let bytes = archive(resolved)?;
+    let dest = root.join(MODULES_DIR).join(&resolved.name);
+    unpack(&bytes, &dest)?;
+
+    let integrity = hash_dir(&dest)?;
+
+    if let Some(locked) = locked.filter(|l| l.version == resolved.version) {
+        if locked.integrity != integrity {
+            let _ = fs::remove_dir_all(&dest);
+            return Err(format!(
+                "{}@{} does not match paw.lock (expected {}, got {})",
+                resolved.name, resolved.version, locked.integrity, integrity
+            ));
+        }
+    }
+
+    Ok(LockedPackage {
+        version: resolved.version.clone(),
+        integrity,
+        resolved: Some(resolved.tarball.clone()),
+    })
+}
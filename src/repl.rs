@@ -0,0 +1,368 @@
+/*
+ * ==========================================================================
+ * PAWX - Code with Claws!
+ * ==========================================================================
+ *
+ * Interactive Mode (`pawx repl`)
+ * ------------------------------
+ * Reads PAWX a line at a time and runs it in one persistent global scope,
+ * printing the value of every expression entered:
+ *
+ * ```text
+ * pawx> snuggle cats = ["Tom", "Felix"];
+ * pawx> cats.length
+ * 2
+ * pawx> purr greet -> (name) -> {
+ *  ...>     return "Hello, " + name;
+ *  ...> }
+ * pawx> greet(cats[0])
+ * Hello, Tom
+ * ```
+ *
+ * Input that stops part-way through (an open block, an unterminated
+ * string, a trailing operator) is continued on the next line. The
+ * semicolon after a final expression may be left out.
+ *
+ * Timers keep running while the prompt waits for input. Entries are kept
+ * in `~/.pawx_history` (or `$PAWX_HISTORY`) between sessions, and lines
+ * starting with a `.` command control the REPL itself (see `.help`).
+ *
+ * --------------------------------------------------------------------------
+ * Author:   Sam Wilcox
+ * Email:    sam@pawx-lang.com
+ * Website:  https://www.pawx-lang.com
+ * Github:   https://github.com/samwilcox/pawx
+ *
+ * License:
+ * This file is part of the PAWX programming language project.
+ *
+ * PAWX is dual-licensed under the terms of:
+ *   - The MIT License
+ *   - The Apache License, Version 2.0
+ *
+ * You may choose either license to govern your use of this software.
+ * Full license text available at:
+ *    https://license.pawx-lang.com
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under these licenses is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *
+ * ==========================================================================
+ */
+
+use std::env;
+use std::fs::{self, OpenOptions};
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::ast::Stmt;
+use crate::diagnostics::DiagnosticPrinter;
+use crate::error::PawxError;
+use crate::interpreter::display::value_to_string;
+use crate::interpreter::prelude::Prelude;
+use crate::interpreter::Session;
+use crate::value::Value;
+use crate::{lexer, parser};
+
+/// Entries kept in the history file.
+const MAX_HISTORY: usize = 1000;
+
+/// How often timers are checked while waiting for input with none pending.
+const IDLE_POLL: Duration = Duration::from_millis(50);
+
+const HELP: &str = "\
+.break          Discard the block being entered
+.exit           Leave the REPL (or press Ctrl+D)
+.help           Show this list
+.history [n]    Show the last n entries (default 20)
+.redo <n>       Run history entry n again";
+
+/* ============================================================================
+ * INPUT
+ * ============================================================================
+ */
+
+/// What the lines entered so far amount to.
+enum Input {
+    /// A whole program, ready to run.
+    Complete(Vec<Stmt>),
+
+    /// The start of one; keep reading.
+    Incomplete,
+
+    /// A syntax error no further input could fix.
+    Invalid(PawxError),
+}
+
+/// Whether `error` only says the source stopped too early.
+fn ran_out(error: &PawxError) -> bool {
+    error.message.starts_with("unterminated") || error.message.ends_with("found end of input")
+}
+
+fn parse(source: &str) -> Result<Vec<Stmt>, PawxError> {
+    lexer::tokenize(source).and_then(parser::parse)
+}
+
+fn classify(source: &str) -> Input {
+    match parse(source) {
+        Ok(statements) => Input::Complete(statements),
+
+        // `1 + 2` is only missing its semicolon
+        Err(error) if ran_out(&error) => match parse(&format!("{};", source)) {
+            Ok(statements) => Input::Complete(statements),
+            Err(_) => Input::Incomplete,
+        },
+
+        Err(error) => Input::Invalid(error),
+    }
+}
+
+/// Reads stdin on its own thread, so timers can fire while the prompt
+/// waits. The channel closes at end of input.
+fn spawn_reader() -> Receiver<String> {
+    let (tx, rx) = mpsc::channel();
+
+    thread::spawn(move || {
+        for line in io::stdin().lock().lines() {
+            let Ok(line) = line else { break };
+            if tx.send(line).is_err() {
+                break;
+            }
+        }
+    });
+
+    rx
+}
+
+/// Waits for the next line, running timers as they come due.
+///
+/// Returns `None` at end of input.
+fn next_line(session: &Session, lines: &Receiver<String>) -> Option<String> {
+    loop {
+        let wait = session
+            .next_due()
+            .map(|due| due.saturating_duration_since(Instant::now()).min(IDLE_POLL))
+            .unwrap_or(IDLE_POLL);
+
+        match lines.recv_timeout(wait) {
+            Ok(line) => return Some(line),
+            Err(RecvTimeoutError::Disconnected) => return None,
+            Err(RecvTimeoutError::Timeout) => {
+                if let Err(error) = session.pump() {
+                    eprintln!("error[{}]: {}", error.code, error.message);
+                }
+            }
+        }
+    }
+}
+
+/* ============================================================================
+ * HISTORY
+ * ============================================================================
+ */
+
+/// Entries run in this and earlier sessions.
+///
+/// The file holds one JSON string per line, so multi-line entries survive
+/// the round trip.
+struct History {
+    entries: Vec<String>,
+    file: Option<PathBuf>,
+}
+
+impl History {
+    fn path() -> Option<PathBuf> {
+        env::var_os("PAWX_HISTORY")
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".pawx_history")))
+    }
+
+    /// Loads the history file; with `persist` off, nothing is saved.
+    fn load(persist: bool) -> Self {
+        let file = if persist { Self::path() } else { None };
+
+        let mut entries: Vec<String> = file
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .map(|text| text.lines().filter_map(|line| serde_json::from_str(line).ok()).collect())
+            .unwrap_or_default();
+
+        if entries.len() > MAX_HISTORY {
+            entries.drain(..entries.len() - MAX_HISTORY);
+            if let Some(path) = &file {
+                let lines: Vec<String> = entries.iter().filter_map(|e| serde_json::to_string(e).ok()).collect();
+                let _ = fs::write(path, lines.join("\n") + "\n");
+            }
+        }
+
+        History { entries, file }
+    }
+
+    fn push(&mut self, entry: &str) {
+        if self.entries.last().map(String::as_str) == Some(entry) {
+            return;
+        }
+        self.entries.push(entry.to_string());
+
+        // Best effort: a history that cannot be saved is still kept in memory
+        if let (Some(path), Ok(line)) = (&self.file, serde_json::to_string(entry)) {
+            if let Ok(mut file) = OpenOptions::new().create(true).append(true).open(path) {
+                let _ = writeln!(file, "{}", line);
+            }
+        }
+    }
+
+    /// Entry `n`, numbered from 1 as `.history` shows them.
+    fn get(&self, n: usize) -> Option<&str> {
+        n.checked_sub(1).and_then(|i| self.entries.get(i)).map(String::as_str)
+    }
+
+    fn print_last(&self, count: usize) {
+        let start = self.entries.len().saturating_sub(count);
+        for (i, entry) in self.entries.iter().enumerate().skip(start) {
+            let mut lines = entry.lines();
+            println!("{:>5}  {}", i + 1, lines.next().unwrap_or(""));
+            for line in lines {
+                println!("       {}", line);
+            }
+        }
+    }
+}
+
+/* ============================================================================
+ * THE LOOP
+ * ============================================================================
+ */
+
+/// What a `.command` line asks for.
+enum Command {
+    Exit,
+    Run(String),
+    Handled,
+}
+
+/// Runs a `.command`, or returns `None` if `line` is not one, so code
+/// like `.then(...)` continuing a block is left alone.
+fn command(line: &str, history: &History, buffer: &mut String) -> Option<Command> {
+    let mut words = line.trim().strip_prefix('.')?.split_whitespace();
+    let name = words.next()?;
+    let arg = words.next();
+
+    let outcome = match name {
+        "exit" => Command::Exit,
+        "help" => {
+            println!("{}", HELP);
+            Command::Handled
+        }
+        "break" => {
+            buffer.clear();
+            Command::Handled
+        }
+        "history" => {
+            match arg.map(str::parse::<usize>) {
+                None => history.print_last(20),
+                Some(Ok(count)) => history.print_last(count),
+                Some(Err(_)) => eprintln!(".history expects a number of entries"),
+            }
+            Command::Handled
+        }
+        "redo" => match arg.and_then(|n| n.parse().ok()).and_then(|n| history.get(n)) {
+            Some(entry) => {
+                println!("{}", entry);
+                Command::Run(entry.to_string())
+            }
+            None => {
+                eprintln!(".redo expects the number of a history entry (see .history)");
+                Command::Handled
+            }
+        },
+        _ => return None,
+    };
+
+    Some(outcome)
+}
+
+fn prompt(text: &str) {
+    print!("{}", text);
+    let _ = io::stdout().flush();
+}
+
+/// Runs `source` and prints its value, or the error it stopped with.
+fn evaluate(session: &Session, source: &str, statements: Vec<Stmt>) {
+    match session.run(statements) {
+        Ok(Some(Value::Null)) | Ok(None) => {}
+        Ok(Some(value)) => println!("{}", value_to_string(&value)),
+        Err(error) => DiagnosticPrinter::new("<repl>", source).print(&error),
+    }
+}
+
+/// Runs the REPL until `.exit` or end of input.
+///
+/// Prompts and history are only used when stdin is a terminal; piped
+/// input is run like a script, waiting for pending timers at the end.
+pub fn run(prelude: &Prelude) {
+    let interactive = io::stdin().is_terminal();
+    let session = Session::new(prelude);
+    let mut history = History::load(interactive);
+    let lines = spawn_reader();
+    let mut buffer = String::new();
+
+    if interactive {
+        println!("PAWX {} (type .help for commands, .exit to leave)", env!("CARGO_PKG_VERSION"));
+    }
+
+    loop {
+        if interactive {
+            prompt(if buffer.is_empty() { "pawx> " } else { " ...> " });
+        }
+
+        let Some(line) = next_line(&session, &lines) else {
+            if interactive {
+                println!();
+            }
+            break;
+        };
+
+        let line = match command(&line, &history, &mut buffer) {
+            Some(Command::Exit) => return,
+            Some(Command::Handled) => continue,
+            Some(Command::Run(entry)) => entry,
+            None => line,
+        };
+
+        if !buffer.is_empty() {
+            buffer.push('\n');
+        }
+        buffer.push_str(&line);
+
+        match classify(&buffer) {
+            Input::Incomplete => continue,
+            Input::Invalid(error) => DiagnosticPrinter::new("<repl>", buffer.as_str()).print(&error),
+            Input::Complete(statements) => {
+                if !buffer.trim().is_empty() {
+                    history.push(buffer.trim());
+                }
+                evaluate(&session, &buffer, statements);
+            }
+        }
+
+        buffer.clear();
+    }
+
+    if interactive {
+        return;
+    }
+
+    // Like a script, piped input runs its timers to completion
+    while let Some(due) = session.next_due() {
+        thread::sleep(due.saturating_duration_since(Instant::now()));
+        if let Err(error) = session.pump() {
+            eprintln!("error[{}]: {}", error.code, error.message);
+            break;
+        }
+    }
+}
@@ -0,0 +1,181 @@
+/*
+ * ==========================================================================
+ * PAWX - Code with Claws!
+ * ==========================================================================
+ *
+ * Project Scaffolding (`pawx new`)
+ * --------------------------------
+ * Generates a new project from one of the templates embedded in the binary:
+ *
+ *  • api  — an HTTP server built on `Http.createServer`
+ *  • cli  — a command-line tool reading `Process.argv`
+ *  • lib  — a library of functions
+ *
+ * Every project gets the same layout:
+ *
+ * ```text
+ * myproject/
+ *   paw.json
+ *   src/main.px
+ *   tests/main.px
+ *   .gitignore
+ * ```
+ *
+ * Templates substitute `{{name}}`, `{{version}}` and `{{port}}`.
+ *
+ * --------------------------------------------------------------------------
+ * Author:   Sam Wilcox
+ * Email:    sam@pawx-lang.com
+ * Website:  https://www.pawx-lang.com
+ * Github:   https://github.com/samwilcox/pawx
+ *
+ * License:
+ * This file is part of the PAWX programming language project.
+ *
+ * PAWX is dual-licensed under the terms of:
+ *   - The MIT License
+ *   - The Apache License, Version 2.0
+ *
+ * You may choose either license to govern your use of this software.
+ * Full license text available at:
+ *    https://license.pawx-lang.com
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under these licenses is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *
+ * ==========================================================================
+ */
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::manifest::{Manifest, MANIFEST_FILE};
+
+/// Names of the available project templates.
+pub const TEMPLATES: &[&str] = &["api", "cli", "lib"];
+
+const TESTS: &str = include_str!("templates/tests.px");
+const GITIGNORE: &str = include_str!("templates/gitignore");
+
+/// Returns the `src/main.px` template for `kind`.
+fn main_template(kind: &str) -> Option<&'static str> {
+    match kind {
+        "api" => Some(include_str!("templates/api/main.px")),
+        "cli" => Some(include_str!("templates/cli/main.px")),
+        "lib" => Some(include_str!("templates/lib/main.px")),
+        _ => None,
+    }
+}
+
+/// Settings for a new project.
+#[derive(Debug, Clone)]
+pub struct ScaffoldOptions {
+    /// Template name, one of [`TEMPLATES`].
+    pub template: String,
+
+    /// Directory to create the project in.
+    pub dir: PathBuf,
+
+    /// Initial `version` in `paw.json`.
+    pub version: String,
+
+    /// Port the `api` template listens on.
+    pub port: u16,
+
+    /// Write into a directory that already has files in it.
+    pub force: bool,
+}
+
+impl ScaffoldOptions {
+    /// Creates options for `template` in `dir` with default settings.
+    pub fn new(template: impl Into<String>, dir: impl Into<PathBuf>) -> Self {
+        Self {
+            template: template.into(),
+            dir: dir.into(),
+            version: "0.1.0".to_string(),
+            port: 3000,
+            force: false,
+        }
+    }
+
+    /// The project name: the last component of the directory.
+    pub fn name(&self) -> String {
+        self.dir
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "pawx-project".to_string())
+    }
+
+    fn fill(&self, template: &str) -> String {
+        template
+            .replace("{{name}}", &self.name())
+            .replace("{{version}}", &self.version)
+            .replace("{{port}}", &self.port.to_string())
+    }
+}
+
+/// Generates a project.
+///
+/// # Returns
+/// - `Ok(Vec<PathBuf>)` with every file written
+///
+/// # Errors
+/// If the template is unknown, the directory is not empty (without
+/// `force`), or a file cannot be written.
+pub fn generate(options: &ScaffoldOptions) -> Result<Vec<PathBuf>, String> {
+    let main = main_template(&options.template).ok_or_else(|| {
+        format!(
+            "unknown template '{}' (available: {})",
+            options.template,
+            TEMPLATES.join(", ")
+        )
+    })?;
+
+    let dir = &options.dir;
+    let occupied = fs::read_dir(dir).map(|mut d| d.next().is_some()).unwrap_or(false);
+    if occupied && !options.force {
+        return Err(format!("{} is not empty (use --force to write anyway)", dir.display()));
+    }
+
+    let mut tasks = BTreeMap::new();
+    tasks.insert("test".to_string(), "tests/main.px".to_string());
+
+    let manifest = Manifest {
+        name: options.name(),
+        version: options.version.clone(),
+        entry: "src/main.px".to_string(),
+        tasks,
+        dependencies: BTreeMap::new(),
+        workspaces: Vec::new(),
+        fixes: None,
+    };
+
+    let mut written = Vec::new();
+
+    let mut write = |rel: &str, contents: &str| -> Result<(), String> {
+        let path = dir.join(rel);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("{}: {}", parent.display(), e))?;
+        }
+        fs::write(&path, contents).map_err(|e| format!("{}: {}", path.display(), e))?;
+        written.push(path);
+        Ok(())
+    };
+
+    write("src/main.px", &options.fill(main))?;
+    write("tests/main.px", &options.fill(TESTS))?;
+    write(".gitignore", GITIGNORE)?;
+
+    let manifest_path = dir.join(MANIFEST_FILE);
+    manifest.save(&manifest_path)?;
+    written.push(manifest_path);
+
+    Ok(written)
+}
+
+/// Returns `path` relative to `base` for display, if it is inside it.
+pub fn display_path(path: &Path, base: &Path) -> String {
+    path.strip_prefix(base).unwrap_or(path).display().to_string()
+}
@@ -26,7 +26,14 @@
  * ==========================================================================
  */
 
-#[derive(Debug, Clone, Copy)]
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// A position in the source: a 1-based line and a 0-based column.
+///
+/// `Span::default()` (line 0) means "unknown".
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Span {
     pub line: usize,
     pub column: usize,
@@ -36,4 +43,11 @@ impl Span {
     pub fn new(line: usize, column: usize) -> Self {
         Self {line, column }
     }
+}
+
+/// Formats as `line:column`, with the column counted from 1.
+impl fmt::Display for Span {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.column + 1)
+    }
 }
\ No newline at end of file
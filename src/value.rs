@@ -34,31 +34,178 @@ use std::fmt;
 use std::rc::Rc;
 use std::sync::Arc;
 
-use regex::Regex;
+use crate::prototypes::regex::PawxRegex;
 
+use crate::ast::{AccessLevel, InstinctMemberKind};
+use crate::error::PawxError;
+use crate::interpreter::calls::builtin_error;
 use crate::interpreter::environment::FunctionDef;
+use crate::interpreter::limits;
+use crate::prototypes::cancel::CancelToken;
+use crate::prototypes::furure::PendingFurure;
+
+/// The signature of a native host function. An `Err` fails the call and
+/// can be caught by the script's `try / catch`.
+pub type NativeFn = dyn Fn(Vec<Value>) -> Result<Value, PawxError>;
+
+/// A class definition, shared by the class value and all of its instances.
+#[derive(Debug)]
+pub struct ClassDef {
+    pub name: String,
+
+    /// The class named by `inherits`, if any.
+    pub base: Option<Rc<ClassDef>>,
+
+    /// Methods, getters and setters declared by this class itself; inherited
+    /// ones are found through [`ClassDef::find_method`] and friends.
+    pub methods: HashMap<String, FunctionDef>,
+    pub getters: HashMap<String, FunctionDef>,
+    pub setters: HashMap<String, FunctionDef>,
+
+    /// Initial field values copied into each new instance, inherited ones
+    /// included.
+    pub fields: HashMap<String, Value>,
+
+    /// `static` methods and fields, reached through the class itself
+    /// (`Counter.next()`); inherited ones through
+    /// [`ClassDef::find_static_method`] and [`ClassDef::find_static_field`].
+    pub static_methods: HashMap<String, FunctionDef>,
+    pub static_fields: RefCell<HashMap<String, Value>>,
+
+    /// How visible each field and method this class declares itself is,
+    /// static ones included; getters and setters are always public.
+    pub access: HashMap<String, AccessLevel>,
+
+    /// The instincts named by `practices`, checked when the class was
+    /// defined.
+    pub instincts: Vec<Rc<InstinctDef>>,
+}
+
+impl ClassDef {
+    /// Finds a method on this class or its nearest ancestor that declares
+    /// it, together with the class that does.
+    pub fn find_method(self: &Rc<Self>, name: &str) -> Option<(FunctionDef, Rc<ClassDef>)> {
+        self.find(|class| &class.methods, name)
+    }
+
+    /// Like [`ClassDef::find_method`], for getters.
+    pub fn find_getter(self: &Rc<Self>, name: &str) -> Option<(FunctionDef, Rc<ClassDef>)> {
+        self.find(|class| &class.getters, name)
+    }
+
+    /// Like [`ClassDef::find_method`], for setters.
+    pub fn find_setter(self: &Rc<Self>, name: &str) -> Option<(FunctionDef, Rc<ClassDef>)> {
+        self.find(|class| &class.setters, name)
+    }
+
+    fn find(
+        self: &Rc<Self>,
+        table: fn(&ClassDef) -> &HashMap<String, FunctionDef>,
+        name: &str,
+    ) -> Option<(FunctionDef, Rc<ClassDef>)> {
+        let mut class = self.clone();
+        loop {
+            if let Some(func) = table(&class).get(name) {
+                return Some((func.clone(), class));
+            }
+            class = class.base.clone()?;
+        }
+    }
+
+    /// Like [`ClassDef::find_method`], for static methods.
+    pub fn find_static_method(self: &Rc<Self>, name: &str) -> Option<(FunctionDef, Rc<ClassDef>)> {
+        self.find(|class| &class.static_methods, name)
+    }
+
+    /// The value of the static field `name` on this class or its nearest
+    /// ancestor that declares it.
+    pub fn find_static_field(&self, name: &str) -> Option<Value> {
+        match self.static_fields.borrow().get(name) {
+            Some(value) => Some(value.clone()),
+            None => self.base.as_ref()?.find_static_field(name),
+        }
+    }
+
+    /// The access level of member `name` as declared by this class or its
+    /// nearest ancestor that declares it, together with that class.
+    pub fn find_access(self: &Rc<Self>, name: &str) -> Option<(AccessLevel, Rc<ClassDef>)> {
+        let mut class = self.clone();
+        loop {
+            if let Some(access) = class.access.get(name) {
+                return Some((access.clone(), class));
+            }
+            class = class.base.clone()?;
+        }
+    }
+
+    /// Whether this class is `other` or inherits from it.
+    pub fn inherits_from(&self, other: &Rc<ClassDef>) -> bool {
+        std::ptr::eq(self, Rc::as_ptr(other)) || self.base.as_ref().is_some_and(|base| base.inherits_from(other))
+    }
+
+    /// Whether this class or one of its ancestors practices `instinct`.
+    pub fn practices(&self, instinct: &Rc<InstinctDef>) -> bool {
+        self.instincts.iter().any(|own| Rc::ptr_eq(own, instinct))
+            || self.base.as_ref().is_some_and(|base| base.practices(instinct))
+    }
+}
+
+/// An instinct (interface): the members a clowder that `practices` it must
+/// provide.
+#[derive(Debug)]
+pub struct InstinctDef {
+    pub name: String,
+    pub members: Vec<InstinctSignature>,
+}
+
+/// One member an instinct requires.
+#[derive(Debug, Clone)]
+pub struct InstinctSignature {
+    pub name: String,
+    pub kind: InstinctMemberKind,
+
+    /// Parameter names; empty for getters, one for setters.
+    pub params: Vec<String>,
+}
+
+impl fmt::Display for InstinctSignature {
+    /// Formats the signature as it is declared, e.g. `purr speak(times)`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            InstinctMemberKind::Method => write!(f, "purr {}({})", self.name, self.params.join(", ")),
+            InstinctMemberKind::Getter => write!(f, "get {}", self.name),
+            InstinctMemberKind::Setter => write!(f, "set {}({})", self.name, self.params.join(", ")),
+        }
+    }
+}
 
 /// PAWX runtime value representation.
 ///
 /// This is the core type that flows through the interpreter.
 /// Every expression ultimately evaluates to one of these.
+///
+/// Variants are kept at most three words wide (large payloads live behind
+/// a single pointer), so cloning and moving values stays cheap.
 pub enum Value {
     // Primitive scalars
     Number(f64),
-    String(String),
+
+    // Immutable, so copies share one allocation; build a new string
+    // and convert it with `.into()`
+    String(Rc<str>),
     Bool(bool),
     Null,
 
     // Native host function:
-    // takes a vector of PAWX Values → returns a PAWX Value
-    NativeFunction(Arc<dyn Fn(Vec<Value>) -> Value>),
+    // takes a vector of PAWX Values → returns a PAWX Value or an error
+    NativeFunction(Arc<NativeFn>),
 
     // Dynamic array (JS-style)
     // - Shared across copies using Rc<RefCell<_>>
-    // - Prototype table holds methods (push, map, etc.)
+    // - Prototype table holds methods (push, map, etc.), shared by all arrays
     Array {
         values: Rc<RefCell<Vec<Value>>>,
-        proto: HashMap<String, Value>,
+        proto: Rc<HashMap<String, Value>>,
     },
 
     // Pride / object literal / plain object:
@@ -68,26 +215,25 @@ pub enum Value {
     },
 
     // Class definition:
-    Class {
-        name: String,
-        methods: HashMap<String, FunctionDef>,
-        getters: HashMap<String, FunctionDef>,
-        setters: HashMap<String, FunctionDef>,
-        fields: HashMap<String, Value>,
-    },
+    Class(Rc<ClassDef>),
+
+    // Instinct (interface) definition, for `practices` and `instanceof`
+    Instinct(Rc<InstinctDef>),
 
     // Instance of a class:
+    // - Methods come from the shared class definition
+    // - Fields are per-instance and shared across copies
     Instance {
-        class_name: String,
+        class: Rc<ClassDef>,
         fields: Rc<RefCell<HashMap<String, Value>>>,
-        methods: HashMap<String, FunctionDef>,
-        getters: HashMap<String, FunctionDef>,
-        setters: HashMap<String, FunctionDef>,
     },
 
     // Simple "future" / promise-like wrapper
     Furure(Box<Value>),
 
+    // Furure settled later on the interpreter thread (Semaphore.acquire())
+    PendingFurure(PendingFurure),
+
     // Error wrapper used by the runtime and Error() constructor
     Error {
         message: String,
@@ -95,7 +241,7 @@ pub enum Value {
 
     // Module value produced by tap()
     Module {
-        exports: HashMap<String, Value>,
+        exports: Rc<HashMap<String, Value>>,
         default: Option<Box<Value>>,
     },
 
@@ -103,7 +249,26 @@ pub enum Value {
     Tuple(Vec<Value>),
 
     // Regex literal / constructed regex
-    Regex(Regex),
+    Regex(PawxRegex),
+
+    // Exact base-10 number (Decimal.new("19.99"))
+    Decimal(rust_decimal::Decimal),
+
+    // Span of time in milliseconds, possibly negative (Duration.parse("1h30m"))
+    Duration(f64),
+
+    // Contiguous numeric buffer (Float64Array.new(n))
+    Float64Array(Rc<RefCell<Vec<f64>>>),
+
+    // Persistent list with structural sharing (Immutable.list([...]))
+    // Boxed: the list handle is several words wide.
+    ImmutableList(Box<crate::prototypes::immutable::ImList>),
+
+    // Persistent string-keyed map with structural sharing (Immutable.map({...}))
+    ImmutableMap(crate::prototypes::immutable::ImMap),
+
+    // Cooperative cancellation token (CancelToken())
+    CancelToken(CancelToken),
 }
 
 impl Clone for Value {
@@ -125,35 +290,17 @@ impl Clone for Value {
                 fields: fields.clone(),
             },
 
-            Value::Class {
-                name,
-                methods,
-                getters,
-                setters,
-                fields,
-            } => Value::Class {
-                name: name.clone(),
-                methods: methods.clone(),
-                getters: getters.clone(),
-                setters: setters.clone(),
-                fields: fields.clone(),
-            },
+            Value::Class(class) => Value::Class(class.clone()),
 
-            Value::Instance {
-                class_name,
-                fields,
-                methods,
-                getters,
-                setters,
-            } => Value::Instance {
-                class_name: class_name.clone(),
+            Value::Instinct(instinct) => Value::Instinct(instinct.clone()),
+
+            Value::Instance { class, fields } => Value::Instance {
+                class: class.clone(),
                 fields: fields.clone(),
-                methods: methods.clone(),
-                getters: getters.clone(),
-                setters: setters.clone(),
             },
 
             Value::Furure(inner) => Value::Furure(inner.clone()),
+            Value::PendingFurure(f) => Value::PendingFurure(f.clone()),
 
             Value::Error { message } => Value::Error {
                 message: message.clone(),
@@ -167,6 +314,18 @@ impl Clone for Value {
             Value::Tuple(values) => Value::Tuple(values.clone()),
 
             Value::Regex(r) => Value::Regex(r.clone()),
+
+            Value::Decimal(d) => Value::Decimal(*d),
+
+            Value::Duration(ms) => Value::Duration(*ms),
+
+            Value::Float64Array(data) => Value::Float64Array(data.clone()),
+
+            Value::ImmutableList(list) => Value::ImmutableList(list.clone()),
+
+            Value::ImmutableMap(map) => Value::ImmutableMap(map.clone()),
+
+            Value::CancelToken(t) => Value::CancelToken(t.clone()),
         }
     }
 }
@@ -181,15 +340,27 @@ impl fmt::Debug for Value {
 
             Value::NativeFunction(_) => write!(f, "[NativeFunction]"),
 
-            Value::Regex(r) => write!(f, "[Regex /{}/]", r.as_str()),
+            Value::Regex(r) => write!(f, "[Regex /{}/{}]", r.as_str(), r.flags()),
+
+            Value::Decimal(d) => write!(f, "Decimal({})", d),
+
+            Value::Duration(ms) => write!(f, "Duration({}ms)", ms),
+
+            Value::Float64Array(data) => write!(f, "[Float64Array len={}]", data.borrow().len()),
+
+            Value::ImmutableList(list) => write!(f, "[ImmutableList len={}]", list.len()),
+
+            Value::ImmutableMap(map) => write!(f, "[ImmutableMap size={}]", map.len()),
 
             Value::Object { .. } => write!(f, "[Object]"),
 
             Value::Array { values, .. } => write!(f, "[Array len={}]", values.borrow().len()),
 
-            Value::Class { name, .. } => write!(f, "[Class {}]", name),
+            Value::Class(class) => write!(f, "[Class {}]", class.name),
 
-            Value::Instance { class_name, .. } => write!(f, "[Instance {}]", class_name),
+            Value::Instinct(instinct) => write!(f, "[Instinct {}]", instinct.name),
+
+            Value::Instance { class, .. } => write!(f, "[Instance {}]", class.name),
 
             Value::Module { exports, default } => {
                 let default_str = if default.is_some() { " + default" } else { "" };
@@ -197,10 +368,16 @@ impl fmt::Debug for Value {
             }
 
             Value::Furure(inner) => write!(f, "[Furure {:?}]", inner),
+            Value::PendingFurure(furure) => match furure.value() {
+                Some(inner) => write!(f, "[Furure {:?}]", inner),
+                None => write!(f, "[Furure pending]"),
+            },
 
             Value::Error { message } => write!(f, "Error({})", message),
 
             Value::Tuple(values) => write!(f, "[Tuple {:?}]", values),
+
+            Value::CancelToken(t) => write!(f, "[CancelToken cancelled={}]", t.is_cancelled()),
         }
     }
 }
@@ -216,13 +393,21 @@ impl Value {
             Value::Array { .. }      => "Array",
             Value::Object { .. }     => "Object",
             Value::Tuple(_)          => "Tuple",
-            Value::Class { .. }      => "Class",
+            Value::Class(_)          => "Class",
+            Value::Instinct(_)       => "Instinct",
             Value::Instance { .. }   => "Instance",
             Value::NativeFunction(_) => "Function",
             Value::Furure(_)         => "Furure",
+            Value::PendingFurure(_)  => "Furure",
             Value::Error { .. }      => "Error",
             Value::Module { .. }     => "Module",
             Value::Regex(_)          => "Regex",
+            Value::Decimal(_)        => "Decimal",
+            Value::Duration(_)       => "Duration",
+            Value::Float64Array(_)   => "Float64Array",
+            Value::ImmutableList(_)  => "ImmutableList",
+            Value::ImmutableMap(_)   => "ImmutableMap",
+            Value::CancelToken(_)    => "CancelToken",
         }
     }
 
@@ -232,6 +417,8 @@ impl Value {
             Value::Bool(b) => *b,
             Value::Null => false,
             Value::Number(n) => *n != 0.0 && !n.is_nan(),
+            Value::Decimal(d) => !d.is_zero(),
+            Value::Duration(ms) => *ms != 0.0,
             Value::String(s) => !s.is_empty(),
             // everything else is truthy
             _ => true,
@@ -245,11 +432,34 @@ impl Value {
                 // keep it simple; you can add nicer formatting later
                 n.to_string()
             }
-            Value::String(s) => s.clone(),
+            Value::String(s) => s.to_string(),
             Value::Bool(b) => b.to_string(),
             Value::Null => "null".to_string(),
 
-            Value::Regex(r) => format!("/{}/", r.as_str()),
+            Value::Regex(r) => format!("/{}/{}", r.as_str(), r.flags()),
+
+            Value::Decimal(d) => d.to_string(),
+
+            Value::Duration(ms) => crate::prototypes::duration::format_duration(*ms),
+
+            Value::Float64Array(data) => {
+                let inner = data.borrow().iter().map(|n| n.to_string()).collect::<Vec<_>>().join(", ");
+                format!("Float64Array[{}]", inner)
+            }
+
+            Value::ImmutableList(list) => {
+                let inner = list.iter().map(|x| x.stringify()).collect::<Vec<_>>().join(", ");
+                format!("List[{}]", inner)
+            }
+
+            Value::ImmutableMap(map) => {
+                let inner = map
+                    .iter()
+                    .map(|(k, v)| format!("{}: {}", k, v.stringify()))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                format!("Map{{{}}}", inner)
+            }
 
             Value::Tuple(v) => {
                 let inner = v.iter().map(|x| x.stringify()).collect::<Vec<_>>().join(", ");
@@ -268,11 +478,13 @@ impl Value {
 
             Value::Object { .. } => "[object Object]".to_string(),
             Value::NativeFunction(_) => "[function]".to_string(),
-            Value::Class { name, .. } => format!("[class {}]", name),
-            Value::Instance { class_name, .. } => format!("[instance {}]", class_name),
+            Value::Class(class) => format!("[class {}]", class.name),
+            Value::Instinct(instinct) => format!("[instinct {}]", instinct.name),
+            Value::Instance { class, .. } => format!("[instance {}]", class.name),
             Value::Module { .. } => "[module]".to_string(),
-            Value::Furure(_) => "[furure]".to_string(),
+            Value::Furure(_) | Value::PendingFurure(_) => "[furure]".to_string(),
             Value::Error { message } => format!("Error({})", message),
+            Value::CancelToken(_) => "[CancelToken]".to_string(),
         }
     }
 
@@ -280,61 +492,120 @@ impl Value {
     /// - primitives compare by value
     /// - tuples deep-compare
     /// - everything else: false unless same discriminant and both Null (handled above)
-    pub fn equals_loose(a: &Value, b: &Value) -> bool {
-        match (a, b) {
-            (Value::Number(x), Value::Number(y)) => x == y,
-            (Value::String(x), Value::String(y)) => x == y,
-            (Value::Bool(x), Value::Bool(y)) => x == y,
-            (Value::Null, Value::Null) => true,
+    ///
+    /// # Errors
+    /// If the values are nested deeper than `--max-depth`.
+    pub fn equals_loose(a: &Value, b: &Value) -> Result<bool, PawxError> {
+        Value::try_equals(a, b, false).map_err(builtin_error)
+    }
 
-            (Value::Tuple(x), Value::Tuple(y)) => {
-                if x.len() != y.len() {
-                    return false;
+    /// Strict equality (`===`) — JS-style identity for reference types:
+    /// - primitives compare by value
+    /// - tuples deep-compare
+    /// - arrays/objects/functions compare by pointer identity
+    ///
+    /// # Errors
+    /// If the values are nested deeper than `--max-depth`.
+    pub fn equals_strict(a: &Value, b: &Value) -> Result<bool, PawxError> {
+        Value::try_equals(a, b, true).map_err(builtin_error)
+    }
+
+    /// [`equals_strict`](Value::equals_strict) when `strict`, otherwise
+    /// [`equals_loose`](Value::equals_loose).
+    ///
+    /// Tuples and immutable collections are compared from an explicit stack
+    /// rather than by recursion, so deep values cannot overflow the Rust
+    /// stack. Elements of immutable collections always compare strictly.
+    ///
+    /// # Errors
+    /// If the values are nested deeper than `--max-depth`.
+    pub fn try_equals(a: &Value, b: &Value, strict: bool) -> Result<bool, String> {
+        let mut pending = vec![(a.clone(), b.clone(), strict, 0)];
+
+        while let Some((a, b, strict, depth)) = pending.pop() {
+            let mut nested = |pairs: Vec<(Value, Value)>, strict: bool| {
+                limits::check_nesting(depth + 1, "equality")?;
+                pending.extend(pairs.into_iter().map(|(a, b)| (a, b, strict, depth + 1)));
+                Ok::<(), String>(())
+            };
+
+            let equal = match (&a, &b) {
+                (Value::Tuple(x), Value::Tuple(y)) => {
+                    let same_len = x.len() == y.len();
+                    if same_len {
+                        nested(x.iter().cloned().zip(y.iter().cloned()).collect(), strict)?;
+                    }
+                    same_len
+                }
+
+                // Immutable values have no identity; compare structurally
+                (Value::ImmutableList(x), Value::ImmutableList(y)) => {
+                    let same_len = x.len() == y.len();
+                    if same_len {
+                        nested(x.iter().cloned().zip(y.iter().cloned()).collect(), true)?;
+                    }
+                    same_len
                 }
-                for (a, b) in x.iter().zip(y.iter()) {
-                    if !Value::equals_loose(a, b) {
-                        return false;
+
+                (Value::ImmutableMap(x), Value::ImmutableMap(y)) => {
+                    let same_keys = x.len() == y.len() && x.keys().all(|k| y.contains_key(k));
+                    if same_keys {
+                        nested(x.iter().map(|(k, v)| (v.clone(), y[k].clone())).collect(), true)?;
                     }
+                    same_keys
                 }
-                true
+
+                _ if strict => Value::scalar_equals_strict(&a, &b),
+                _ => Value::scalar_equals_loose(&a, &b),
+            };
+
+            if !equal {
+                return Ok(false);
             }
+        }
+
+        Ok(true)
+    }
+
+    /// Loose equality of values that hold no others.
+    fn scalar_equals_loose(a: &Value, b: &Value) -> bool {
+        match (a, b) {
+            (Value::Number(x), Value::Number(y)) => x == y,
+            (Value::Decimal(x), Value::Decimal(y)) => x == y,
+            (Value::Duration(x), Value::Duration(y)) => x == y,
+            (Value::String(x), Value::String(y)) => x == y,
+            (Value::Bool(x), Value::Bool(y)) => x == y,
+            (Value::Null, Value::Null) => true,
 
             _ => false,
         }
     }
 
-    /// Strict equality (`===`) — JS-style identity for reference types:
-    /// - primitives compare by value
-    /// - tuples deep-compare
-    /// - arrays/objects/functions compare by pointer identity
-    pub fn equals_strict(a: &Value, b: &Value) -> bool {
+    /// Strict equality of values that hold no others, or of references.
+    fn scalar_equals_strict(a: &Value, b: &Value) -> bool {
         match (a, b) {
             (Value::Number(x), Value::Number(y)) => x == y,
+            (Value::Decimal(x), Value::Decimal(y)) => x == y,
+            (Value::Duration(x), Value::Duration(y)) => x == y,
             (Value::String(x), Value::String(y)) => x == y,
             (Value::Bool(x), Value::Bool(y)) => x == y,
             (Value::Null, Value::Null) => true,
 
-            (Value::Tuple(x), Value::Tuple(y)) => {
-                if x.len() != y.len() {
-                    return false;
-                }
-                for (a, b) in x.iter().zip(y.iter()) {
-                    if !Value::equals_strict(a, b) {
-                        return false;
-                    }
-                }
-                true
-            }
-
             (Value::Array { values: a, .. }, Value::Array { values: b, .. }) => Rc::ptr_eq(a, b),
 
+            (Value::Float64Array(a), Value::Float64Array(b)) => Rc::ptr_eq(a, b),
+
             (Value::Object { fields: a }, Value::Object { fields: b }) => Rc::ptr_eq(a, b),
 
             (Value::NativeFunction(a), Value::NativeFunction(b)) => Arc::ptr_eq(a, b),
 
             // You can decide how strict should behave for Regex:
-            // Here: equal if pattern string matches.
-            (Value::Regex(a), Value::Regex(b)) => a.as_str() == b.as_str(),
+            // Here: equal if pattern string and flags match.
+            (Value::Regex(a), Value::Regex(b)) => a.same_pattern(b),
+
+            (Value::CancelToken(a), Value::CancelToken(b)) => a.ptr_eq(b),
+
+            (Value::PendingFurure(a), Value::PendingFurure(b)) => a.ptr_eq(b),
 
             // Classes/Instances/Modules/Furure:
             // treat as identity types unless you want deeper behavior later.
@@ -345,7 +616,7 @@ impl Value {
     /// Attempts to extract a String reference from a Value.
     pub fn as_string(&self) -> Option<&str> {
         match self {
-            Value::String(s) => Some(s.as_str()),
+            Value::String(s) => Some(&**s),
             _ => None,
         }
     }
@@ -374,9 +645,32 @@ impl Value {
             Value::Null => "null".to_string(),
             Value::Bool(b) => b.to_string(),
             Value::Number(n) => n.to_string(),
-            Value::String(s) => s.clone(),
+            Value::String(s) => s.to_string(),
+
+            Value::Regex(r) => format!("/{}/{}", r.as_str(), r.flags()),
+
+            Value::Decimal(d) => d.to_string(),
 
-            Value::Regex(r) => format!("/{}/", r.as_str()),
+            Value::Duration(ms) => crate::prototypes::duration::format_duration(*ms),
+
+            Value::Float64Array(data) => {
+                let inner = data.borrow().iter().map(|n| n.to_string()).collect::<Vec<_>>().join(", ");
+                format!("Float64Array[{}]", inner)
+            }
+
+            Value::ImmutableList(list) => {
+                let inner = list.iter().map(|v| v.to_pawx_string()).collect::<Vec<_>>().join(", ");
+                format!("List[{}]", inner)
+            }
+
+            Value::ImmutableMap(map) => {
+                let inner = map
+                    .iter()
+                    .map(|(k, v)| format!("{}: {}", k, v.to_pawx_string()))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                format!("Map{{{}}}", inner)
+            }
 
             Value::Tuple(values) => {
                 let inner = values
@@ -401,19 +695,23 @@ impl Value {
 
             Value::NativeFunction(_) => "[function]".to_string(),
 
-            Value::Class { name, .. } => format!("[class {}]", name),
+            Value::Class(class) => format!("[class {}]", class.name),
+
+            Value::Instinct(instinct) => format!("[instinct {}]", instinct.name),
 
-            Value::Instance { class_name, .. } => format!("[instance {}]", class_name),
+            Value::Instance { class, .. } => format!("[instance {}]", class.name),
 
             Value::Module { .. } => "[module]".to_string(),
 
-            Value::Furure(_) => "[furure]".to_string(),
+            Value::Furure(_) | Value::PendingFurure(_) => "[furure]".to_string(),
 
             Value::Error { message } => message.clone(),
+
+            Value::CancelToken(_) => "[CancelToken]".to_string(),
         }
     }
 
     fn native_to_string(this: Value) -> Value {
-        Value::String(this.stringify())
+        Value::String(this.stringify().into())
     }
 }
\ No newline at end of file